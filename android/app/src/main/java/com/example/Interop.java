@@ -0,0 +1,20 @@
+// cdylib_gen 的 Java 接口，native 方法由 packages/cdylib_gen/src/java.rs 实现（需要启用 jni 特性）
+// Java interface of cdylib_gen, the native methods are implemented by packages/cdylib_gen/src/java.rs
+// (requires the jni feature)
+package com.example;
+
+public final class Interop {
+    static {
+        // Android 从 APK 的 lib/<abi>/ 中加载 libcdylib_gen.so，桌面 JVM 从 java.library.path 中加载
+        // Android loads libcdylib_gen.so from lib/<abi>/ in the APK, a desktop JVM from java.library.path
+        System.loadLibrary("cdylib_gen");
+    }
+
+    private Interop() {}
+
+    /** Adds a and b, throws ArithmeticException on overflow. */
+    public static native int add(int a, int b);
+
+    /** Builds the same greeting as rxc_cdylib_make_greeting. */
+    public static native String greeting(String name);
+}
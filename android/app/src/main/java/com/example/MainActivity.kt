@@ -0,0 +1,21 @@
+// 在 Android 应用中调用 cdylib_gen 的示例
+// Example of calling cdylib_gen from an Android app
+
+package com.example
+
+import android.app.Activity
+import android.os.Bundle
+import android.util.Log
+
+class MainActivity : Activity() {
+    override fun onCreate(savedInstanceState: Bundle?) {
+        super.onCreate(savedInstanceState)
+        Log.i("Interop", Interop.greeting("Android"))
+        Log.i("Interop", "The result (1 + 2) is ${Interop.add(1, 2)}!")
+        try {
+            Interop.add(Int.MAX_VALUE, 1)
+        } catch (err: ArithmeticException) {
+            Log.i("Interop", "Rust reported: ${err.message}")
+        }
+    }
+}
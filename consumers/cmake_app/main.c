@@ -0,0 +1,41 @@
+// 这个 C 程序通过 CMake 的 find_package 链接 cdylib_gen，先做 ABI 握手再调用
+// This C program links cdylib_gen through CMake's find_package, handshaking on the ABI before calling it
+#include <stdio.h>
+#include "cdylib_gen.h"
+
+int main(void)
+{
+    if (rxc_cdylib_abi_version() != CDYLIB_ABI_VERSION)
+    {
+        printf("[C] cdylib_gen has ABI version %u, expected %u\n", rxc_cdylib_abi_version(), CDYLIB_ABI_VERSION);
+        return 1;
+    }
+
+    Version version;
+    if (rxc_cdylib_version(&version) != FFI_STATUS_OK)
+    {
+        printf("[C] rxc_cdylib_version failed\n");
+        return 1;
+    }
+    printf("[C] Linked against cdylib_gen %u.%u.%u (%s)\n", version.major, version.minor, version.patch,
+           version.git_hash[0] != '\0' ? version.git_hash : "unknown commit");
+
+    // 其他函数要在 rxc_rustlib_init 之后才能调用，NULL 配置即默认配置
+    // The other functions can only be called after rxc_rustlib_init, a NULL config is the default
+    if (rxc_rustlib_init(NULL) != FFI_STATUS_OK)
+    {
+        printf("[C] rxc_rustlib_init failed\n");
+        return 1;
+    }
+    char result[64] = "CMake";
+    int sum = 0;
+    enum FfiStatus status = rxc_cdylib_add(20, 22, result, sizeof(result), &sum, NULL);
+    rxc_rustlib_shutdown();
+    if (status != FFI_STATUS_OK)
+    {
+        printf("[C] rxc_cdylib_add failed\n");
+        return 1;
+    }
+    printf("[C] %s\n", result);
+    return sum == 42 ? 0 : 1;
+}
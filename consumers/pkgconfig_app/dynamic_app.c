@@ -0,0 +1,31 @@
+// 这个 C 程序通过 pkg-config 动态链接 cdylib_gen
+// This C program links cdylib_gen dynamically through pkg-config
+#include <stdio.h>
+#include "cdylib_gen.h"
+
+int main(void)
+{
+    if (rxc_cdylib_abi_version() != CDYLIB_ABI_VERSION)
+    {
+        printf("[C] cdylib_gen has ABI version %u, expected %u\n", rxc_cdylib_abi_version(), CDYLIB_ABI_VERSION);
+        return 1;
+    }
+
+    if (rxc_rustlib_init(NULL) != FFI_STATUS_OK)
+    {
+        printf("[C] rxc_rustlib_init failed\n");
+        return 1;
+    }
+    char result[64] = "pkg-config";
+    int sum = 0;
+    enum FfiStatus status = rxc_cdylib_add(1, 2, result, sizeof(result), &sum, NULL);
+    rxc_rustlib_shutdown();
+    if (status != FFI_STATUS_OK)
+    {
+        printf("[C] rxc_cdylib_add failed\n");
+        return 1;
+    }
+    printf("[C] %s\n", result);
+    printf("[C] Dynamic link through pkg-config passed\n");
+    return sum == 3 ? 0 : 1;
+}
@@ -0,0 +1,34 @@
+// 这个 C 程序通过 pkg-config 静态链接 staticlib_gen
+// This C program links staticlib_gen statically through pkg-config
+#include <stdio.h>
+#include "staticlib_gen.h"
+
+int main(void)
+{
+    if (rxc_staticlib_init(NULL) != FFI_STATUS_OK)
+    {
+        printf("[C] rxc_staticlib_init failed\n");
+        return 1;
+    }
+    char result[64] = "pkg-config";
+    int sum = 0;
+    if (rxc_staticlib_add(1, 2, result, sizeof(result), &sum, NULL) != FFI_STATUS_OK)
+    {
+        printf("[C] rxc_staticlib_add failed\n");
+        rxc_staticlib_shutdown();
+        return 1;
+    }
+    printf("[C] %s\n", result);
+
+    int values[] = {1, 2, 3, 4};
+    long total = 0;
+    enum FfiStatus status = rxc_staticlib_sum(values, sizeof(values) / sizeof(values[0]), &total);
+    rxc_staticlib_shutdown();
+    if (status != FFI_STATUS_OK || total != 10)
+    {
+        printf("[C] rxc_staticlib_sum returned %ld\n", total);
+        return 1;
+    }
+    printf("[C] Static link through pkg-config passed\n");
+    return sum == 3 ? 0 : 1;
+}
@@ -0,0 +1,31 @@
+// 这个 Swift 程序通过 cargo xtask xcframework 生成的 XCFramework 调用 staticlib_gen；
+// 在 Xcode 中把 staticlib_gen.xcframework 加入目标即可，命令行下可以用 macOS 切片编译：
+// This Swift program calls staticlib_gen through the XCFramework cargo xtask xcframework builds;
+// in Xcode add staticlib_gen.xcframework to the target, on the command line build against the
+// macOS slice:
+//   swiftc main.swift -I target/xcframework/staticlib_gen.xcframework/macos-arm64_x86_64/Headers \
+//       -L target/xcframework/staticlib_gen.xcframework/macos-arm64_x86_64 -lstaticlib_gen
+import staticlib_gen
+
+// 其他函数要在 rxc_staticlib_init 之后才能调用，nil 配置即默认配置
+// The other functions can only be called after rxc_staticlib_init, a nil config is the default
+guard rxc_staticlib_init(nil) == FFI_STATUS_OK else {
+    fatalError("rxc_staticlib_init failed")
+}
+defer { rxc_staticlib_shutdown() }
+
+// rxc_staticlib_add 的 result 既传入名字也传出消息
+// rxc_staticlib_add's result buffer carries the name in and the message out
+var buffer = [CChar](repeating: 0, count: 64)
+let name = "Swift".utf8CString
+buffer.replaceSubrange(0..<name.count, with: name)
+
+var sum: Int32 = 0
+let status = buffer.withUnsafeMutableBufferPointer { result in
+    rxc_staticlib_add(1, 2, result.baseAddress, result.count, &sum, nil)
+}
+guard status == FFI_STATUS_OK else {
+    fatalError("rxc_staticlib_add failed with status \(status.rawValue)")
+}
+print(buffer.withUnsafeBufferPointer { String(cString: $0.baseAddress!) })
+print("[Swift] Result from static library: \(sum)")
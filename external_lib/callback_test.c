@@ -0,0 +1,72 @@
+// 这个 C 程序测试 cdylib_gen 的回调接口 rxc_cdylib_add_async，回调在 Rust 的线程上执行
+// This C program tests cdylib_gen's callback API rxc_cdylib_add_async, the callback runs on a Rust
+// thread
+#define _POSIX_C_SOURCE 200809L
+#include <stdio.h>
+#include <stdatomic.h>
+#include "cdylib_gen.h"
+
+#ifdef _WIN32
+#include <windows.h>
+#define sleep_ms(ms) Sleep(ms)
+#else
+#include <time.h>
+#define sleep_ms(ms) nanosleep(&(struct timespec){0, (ms) * 1000000L}, NULL)
+#endif
+
+typedef struct
+{
+    atomic_int done;
+    int sum;
+} Context;
+
+static void on_done(int sum, void *user_data)
+{
+    Context *ctx = (Context *)user_data;
+    printf("[C callback] Received the result %d\n", sum);
+    ctx->sum = sum;
+    atomic_store(&ctx->done, 1);
+}
+
+static int run(void)
+{
+    Context ctx = {0};
+
+    if (rxc_cdylib_add_async(5, 6, on_done, &ctx) != FFI_STATUS_OK)
+    {
+        printf("[C] rxc_cdylib_add_async failed\n");
+        return 1;
+    }
+    for (int i = 0; i < 500 && !atomic_load(&ctx.done); i++)
+    {
+        sleep_ms(10);
+    }
+    if (!atomic_load(&ctx.done) || ctx.sum != 11)
+    {
+        printf("[C] The callback did not deliver 11\n");
+        return 1;
+    }
+
+    if (rxc_cdylib_add_async(1, 2, NULL, &ctx) != FFI_STATUS_NULL_POINTER)
+    {
+        printf("[C] A NULL callback was not rejected\n");
+        return 1;
+    }
+
+    printf("[C] Callback test passed\n");
+    return 0;
+}
+
+// 回调所在的工作线程由 rxc_rustlib_init 启动
+// The worker thread running the callback is started by rxc_rustlib_init
+int main(void)
+{
+    if (rxc_rustlib_init(NULL) != FFI_STATUS_OK)
+    {
+        printf("[C] rxc_rustlib_init failed\n");
+        return 1;
+    }
+    int code = run();
+    rxc_rustlib_shutdown();
+    return code;
+}
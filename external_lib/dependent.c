@@ -0,0 +1,15 @@
+// 这个动态库用到 dylib.c 中的 dyloading_call_count，却不链接它，加载时由动态链接器在已经加载的库中查找；
+// 只有 external_dy 是以 RTLD_GLOBAL 加载的时候才找得到
+// This dynamic library uses dyloading_call_count from dylib.c without being linked against it,
+// leaving the dynamic linker to look it up among the libraries already loaded; it is only found
+// when external_dy was loaded with RTLD_GLOBAL
+#include <stdint.h>
+
+int32_t dyloading_call_count(void);
+
+// 通过上面未定义的符号读取 external_dy 中的计数
+// Reads the count in external_dy through the undefined symbol above
+int32_t dependent_call_count(void)
+{
+    return dyloading_call_count();
+}
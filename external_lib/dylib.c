@@ -1,18 +1,188 @@
 // 这个文件为动态库源文件，我们将在 Windows 使用 MSBuild 来编译它，在 Linux 使用 gcc 来编译它
 // This file is a dynamic library source file and we will compile it using MSBuild on Windows and gcc on Linux
+#include <stddef.h>
 #include <stdio.h>
 #include <stdint.h>
+#include <stdlib.h>
+
+// 调用方在调用其他函数之前比较这个版本，签名变化时递增
+// Callers compare this version before calling anything else, bump it when a signature changes
+#define DYLOADING_ABI_VERSION 1
+
+// 消息的前缀，call_libs 的 build.rs 用 -D 改掉它编译出另一个构建，供 dlmopen 演示区分两者
+// The prefix of the messages, call_libs' build.rs overrides it with -D to compile a second build
+// the dlmopen demo can tell apart
+#ifndef DYLOADING_BUILD
+#define DYLOADING_BUILD "External dyloading"
+#endif
+
+#ifdef _WIN32
+__declspec(dllexport)
+#endif
+uint32_t dyloading_abi_version(void)
+{
+    return DYLOADING_ABI_VERSION;
+}
+
+// 库内部的状态，库被卸载再重新加载后会归零
+// State inside the library, it is reset when the library is unloaded and loaded again
+static int32_t call_count = 0;
+
+// 加载和卸载时运行的函数：构造函数在 dlopen 返回之前运行，析构函数在 dlclose 真正卸载库时运行，静态链接
+// 进可执行文件时则分别在 main 之前和 exit 中运行。构造函数运行时调用方还没有拿到任何符号，不能指望它做过
+// 什么配置；析构函数运行时库的代码还在，但调用方登记的 hook 如果指向一个已经卸载的库就会崩溃
+// Functions run at load and unload time: the constructor runs before dlopen returns and the
+// destructor when dlclose really unloads the library, or before main and during exit when it is
+// linked into the executable. When the constructor runs the caller has no symbol yet and can't
+// have configured anything; when the destructor runs the library's code is still there, but a
+// hook the caller registered crashes if it points into a library that was unloaded already
+typedef void (*unload_hook_fn)(void *ctx);
+
+static int32_t constructor_runs = 0;
+static unload_hook_fn unload_hook = NULL;
+static void *unload_ctx = NULL;
+
+static void dyloading_unload(void)
+{
+    if (unload_hook != NULL)
+    {
+        unload_hook(unload_ctx);
+    }
+}
+
+#if defined(_MSC_VER)
+// MSVC 没有 constructor 属性：把构造函数的指针放进 CRT 启动时遍历的 .CRT$XCU 段，/include 让链接器保留它；
+// DLL 中用 atexit 登记的函数在 DLL 卸载时运行
+// MSVC has no constructor attribute: a pointer to the constructor goes into the .CRT$XCU section
+// the CRT walks at startup, and /include keeps the linker from dropping it; functions a DLL
+// registers with atexit run when the DLL unloads
+static void dyloading_load(void)
+{
+    constructor_runs++;
+    atexit(dyloading_unload);
+}
+
+#pragma section(".CRT$XCU", read)
+__declspec(allocate(".CRT$XCU")) void (*dyloading_load_entry)(void) = dyloading_load;
+#if defined(_M_IX86)
+#pragma comment(linker, "/include:_dyloading_load_entry")
+#else
+#pragma comment(linker, "/include:dyloading_load_entry")
+#endif
+#else
+__attribute__((constructor)) static void dyloading_load(void)
+{
+    constructor_runs++;
+}
+
+__attribute__((destructor)) static void dyloading_destructor(void)
+{
+    dyloading_unload();
+}
+#endif
+
+// 构造函数运行过的次数，每个加载的副本都是 1
+// How many times the constructor ran, 1 for every loaded copy
+#ifdef _WIN32
+__declspec(dllexport)
+#endif
+int32_t dyloading_constructor_runs(void)
+{
+    return constructor_runs;
+}
+
+// 登记卸载时调用的 hook，NULL 取消登记；hook 不能在这个库里
+// Registers the hook called at unload time, NULL unregisters it; the hook must not live in this
+// library
+#ifdef _WIN32
+__declspec(dllexport)
+#endif
+void dyloading_set_unload_hook(unload_hook_fn hook, void *ctx)
+{
+    unload_hook = hook;
+    unload_ctx = ctx;
+}
+
 #ifdef _WIN32
 __declspec(dllexport)
 #endif
 int32_t dyloading_add(int32_t a, int32_t b, char *result)
 {
-    printf("[External dyloading] Hello %s\n", result);
+    printf("[" DYLOADING_BUILD "] Hello %s\n", result);
+    call_count++;
 
     int32_t sum = a + b;
 
-    sprintf(result,"[External dyloading] The result (%d + %d) is %d!", a, b, sum);
+    sprintf(result, "[" DYLOADING_BUILD "] The result (%d + %d) is %d!", a, b, sum);
     return sum;
 }
 
+// dyloading_add 的后继：名字和结果分开传入，结果按 result_len 截断，不会写出缓冲区。较早的构建没有它，
+// call_libs 的 build.rs 用 -DDYLOADING_WITHOUT_ADD_V2 编译出这样一个构建，调用方要先探测这个符号
+// The successor of dyloading_add: the name and the result are passed separately and the result is
+// truncated to result_len, never written past the buffer. Older builds lack it, call_libs' build.rs
+// compiles such a build with -DDYLOADING_WITHOUT_ADD_V2, so callers have to probe for the symbol
+#ifndef DYLOADING_WITHOUT_ADD_V2
+#ifdef _WIN32
+__declspec(dllexport)
+#endif
+int32_t dyloading_add_v2(int32_t a, int32_t b, const char *name, char *result, size_t result_len)
+{
+    printf("[" DYLOADING_BUILD "] Hello %s\n", name);
+    call_count++;
+
+    int32_t sum = a + b;
+
+    snprintf(result, result_len, "[" DYLOADING_BUILD "] The result (%d + %d) is %d!", a, b, sum);
+    return sum;
+}
+#endif
+
+#ifdef _WIN32
+__declspec(dllexport)
+#endif
+int32_t dyloading_call_count(void)
+{
+    return call_count;
+}
+
+// 不做任何输出的求和，call_libs 的 benches/call_overhead.rs 用它测量 dlopen 调用的开销
+// A sum without any output, call_libs' benches/call_overhead.rs uses it to measure the cost of a dlopen call
+#ifdef _WIN32
+__declspec(dllexport)
+#endif
+int32_t dyloading_sum(const int32_t *values, size_t len, int64_t *total)
+{
+    int64_t sum = 0;
+    for (size_t i = 0; i < len; i++)
+    {
+        sum += values[i];
+    }
+    *total = sum;
+    return 0;
+}
 
+
+
+// 对 [from, to] 中的每个值调用 visit，visit 返回非零时提前停止，返回访问过的值的个数
+// Calls visit for every value in [from, to], stopping early when visit returns non-zero; returns the number of values visited
+typedef int32_t (*visit_fn)(void *ctx, int32_t value);
+
+#ifdef _WIN32
+__declspec(dllexport)
+#endif
+int32_t dyloading_for_each(int32_t from, int32_t to, visit_fn visit, void *ctx)
+{
+    int32_t count = 0;
+    // 用 int64_t 计数，to 为 INT32_MAX 时自增也不会溢出
+    // Counting in int64_t keeps the increment from overflowing when to is INT32_MAX
+    for (int64_t value = from; value <= to; value++)
+    {
+        count++;
+        if (visit(ctx, (int32_t)value) != 0)
+        {
+            break;
+        }
+    }
+    return count;
+}
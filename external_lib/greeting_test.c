@@ -0,0 +1,61 @@
+// 这个 C 程序测试 cdylib_gen 返回的堆分配字符串和导出的分配器，所有内存都用 rxc_rust_free 释放
+// This C program tests the heap-allocated string returned by cdylib_gen and the exported
+// allocator, releasing all of the memory with rxc_rust_free
+#include <stdint.h>
+#include <stdio.h>
+#include <string.h>
+#include "cdylib_gen.h"
+
+static int run(void)
+{
+    char *greeting = rxc_cdylib_make_greeting("Wang");
+    if (greeting == NULL || strstr(greeting, "Hello Wang") == NULL)
+    {
+        printf("[C] rxc_cdylib_make_greeting returned an unexpected greeting\n");
+        return 1;
+    }
+    printf("[C] %s\n", greeting);
+    // 字符串属于 Rust 的分配器，不能用 free() 释放
+    // The string belongs to Rust's allocator, it must not be released with free()
+    rxc_rust_free(greeting);
+
+    if (rxc_cdylib_make_greeting(NULL) != NULL)
+    {
+        printf("[C] A NULL name was not rejected\n");
+        return 1;
+    }
+    rxc_rust_free(NULL);
+
+    // 交给 Rust 的缓冲区也可以由 Rust 分配，扩大之后内容保持不变
+    // Buffers handed to Rust can be allocated by Rust as well, growing one keeps its contents
+    char *buf = rxc_rust_alloc(6, 64);
+    if (buf == NULL || (uintptr_t)buf % 64 != 0)
+    {
+        printf("[C] rxc_rust_alloc did not return a 64-byte aligned block\n");
+        return 1;
+    }
+    memcpy(buf, "hello", 6);
+    char *grown = rxc_rust_realloc(buf, 4096);
+    if (grown == NULL || strcmp(grown, "hello") != 0 || (uintptr_t)grown % 64 != 0)
+    {
+        printf("[C] rxc_rust_realloc lost the contents or the alignment\n");
+        rxc_rust_free(grown == NULL ? buf : grown);
+        return 1;
+    }
+    rxc_rust_free(grown);
+
+    printf("[C] Greeting test passed\n");
+    return 0;
+}
+
+int main(void)
+{
+    if (rxc_rustlib_init(NULL) != FFI_STATUS_OK)
+    {
+        printf("[C] rxc_rustlib_init failed\n");
+        return 1;
+    }
+    int code = run();
+    rxc_rustlib_shutdown();
+    return code;
+}
@@ -0,0 +1,43 @@
+// 这个 C 程序向 cdylib_gen 中以 Option 表示的参数传入 NULL，检查两种分支的行为
+// This C program passes NULL to cdylib_gen arguments modelled as Option and checks both branches
+#include <stdio.h>
+#include "cdylib_gen.h"
+
+static int failures = 0;
+
+#define CHECK(cond)                                     \
+    do                                                  \
+    {                                                   \
+        if (!(cond))                                    \
+        {                                               \
+            printf("[C] Check failed: %s\n", #cond);    \
+            failures++;                                 \
+        }                                               \
+    } while (0)
+
+static int triple(int value)
+{
+    return value * 3;
+}
+
+int main(void)
+{
+    int a = 2, b = 5, counter = 41;
+
+    CHECK(rxc_cdylib_apply(7, triple) == 21);
+    CHECK(rxc_cdylib_apply(7, NULL) == 7);
+
+    CHECK(rxc_cdylib_add_or_default(&a, &b, 100) == 7);
+    CHECK(rxc_cdylib_add_or_default(&a, NULL, 100) == 102);
+    CHECK(rxc_cdylib_add_or_default(NULL, NULL, 100) == 200);
+
+    CHECK(rxc_cdylib_increment(&counter) == 42 && counter == 42);
+    CHECK(rxc_cdylib_increment(NULL) == -1);
+
+    if (failures != 0)
+    {
+        return 1;
+    }
+    printf("[C] Option test passed\n");
+    return 0;
+}
@@ -0,0 +1,32 @@
+// 用 C 编写的示例插件，实现减法，不需要 user_data
+// A sample plugin written in C, it subtracts and needs no user_data
+#include <limits.h>
+#include "plugin_api.h"
+
+static int subtract(void *user_data, int a, int b, int *out)
+{
+    (void)user_data;
+    if (out == NULL)
+    {
+        return -1;
+    }
+    long long difference = (long long)a - b;
+    if (difference < INT_MIN || difference > INT_MAX)
+    {
+        return -1;
+    }
+    *out = (int)difference;
+    return 0;
+}
+
+PLUGIN_EXPORT int plugin_register(const PluginHost *host)
+{
+    if (host == NULL || host->api_version != PLUGIN_API_VERSION)
+    {
+        return -1;
+    }
+    host->log(host->ctx, "[C plugin] Registering subtract");
+
+    PluginVTable vtable = {"subtract", NULL, subtract, NULL};
+    return host->register_vtable(host->ctx, &vtable);
+}
@@ -0,0 +1,77 @@
+// 把任意名字字节、操作数和容量传给 staticlib_add，覆盖读取名字、格式化消息和写回缓冲区的整条路径
+// Passes arbitrary name bytes, operands and capacities to staticlib_add, covering the whole path of
+// reading the name, formatting the message and writing it back into the buffer
+//
+// 运行 / Run: cargo +nightly fuzz run add_message
+
+#![no_main]
+
+use std::ffi::{c_char, c_int};
+
+use arbitrary::Arbitrary;
+use libfuzzer_sys::fuzz_target;
+use staticlib_gen::{staticlib_add, staticlib_init, FfiStatus};
+
+#[derive(Debug, Arbitrary)]
+struct Input {
+    a: c_int,
+    b: c_int,
+    capacity: u8,
+    name: Vec<u8>,
+}
+
+// 在第一个输入之前初始化一次库
+// The library is initialized once before the first input
+fuzz_target!(
+    init: {
+        assert_eq!(unsafe { staticlib_init(std::ptr::null()) }, FfiStatus::Ok);
+    },
+    |input: Input| {
+        let cap = usize::from(input.capacity);
+        // 名字可以占满整个缓冲区而没有 NUL，也可以不是 UTF-8
+        // The name may fill the whole buffer without a NUL, and need not be UTF-8
+        let mut buf: Vec<c_char> = input.name.iter().map(|&b| b as c_char).collect();
+        buf.resize(cap, 0);
+        let buf = &mut buf[..cap];
+        let ptr = if cap == 0 {
+            std::ptr::null_mut()
+        } else {
+            buf.as_mut_ptr()
+        };
+        let (mut sum, mut required) = (0, 0);
+        let status = unsafe { staticlib_add(input.a, input.b, ptr, cap, &mut sum, &mut required) };
+
+        // 整数溢出在读取名字之前就被检查
+        // Integer overflow is checked before the name is read
+        let Some(total) = input.a.checked_add(input.b) else {
+            assert_eq!(status, FfiStatus::Overflow);
+            return;
+        };
+        if matches!(status, FfiStatus::Unterminated | FfiStatus::InvalidUtf8) {
+            return;
+        }
+        let expected = format!(
+            "[Rust staticlib] The result ({} + {}) is {total}!",
+            input.a, input.b
+        );
+        assert_eq!(sum, total);
+        assert_eq!(required, expected.len());
+        if cap == 0 {
+            assert_eq!(status, FfiStatus::BufferTooSmall);
+            return;
+        }
+        let written = expected.len().min(cap - 1);
+        let bytes: Vec<u8> = buf[..written].iter().map(|&b| b as u8).collect();
+        assert_eq!(bytes, &expected.as_bytes()[..written]);
+        assert_eq!(buf[written], 0);
+        let ok = written == expected.len();
+        assert_eq!(
+            status,
+            if ok {
+                FfiStatus::Ok
+            } else {
+                FfiStatus::BufferTooSmall
+            }
+        );
+    }
+);
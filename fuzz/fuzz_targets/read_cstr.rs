@@ -0,0 +1,31 @@
+// 用任意字节和任意 max_len 调用 read_cstr，缓冲区刚好是输入的大小，越界读取会被 AddressSanitizer 发现
+// Calls read_cstr with arbitrary bytes and an arbitrary max_len; the buffer is exactly the size of
+// the input, so AddressSanitizer catches any out-of-bounds read
+//
+// 运行 / Run: cargo +nightly fuzz run read_cstr
+
+#![no_main]
+
+use std::ffi::c_char;
+
+use interop_common::{read_cstr, FfiError};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let Some((&max_len, bytes)) = data.split_first() else {
+        return;
+    };
+    // max_len 不能超过缓冲区大小，这是调用方的约定
+    // max_len may not exceed the buffer size, that is the caller's contract
+    let max_len = usize::from(max_len).min(bytes.len());
+    let buf = bytes.to_vec().into_boxed_slice();
+    let result = unsafe { read_cstr(buf.as_ptr() as *const c_char, max_len) };
+
+    match bytes[..max_len].iter().position(|&b| b == 0) {
+        None => assert_eq!(result, Err(FfiError::Unterminated)),
+        Some(len) => match std::str::from_utf8(&bytes[..len]) {
+            Ok(s) => assert_eq!(result, Ok(s)),
+            Err(_) => assert_eq!(result, Err(FfiError::InvalidUtf8)),
+        },
+    }
+});
@@ -0,0 +1,55 @@
+// 把任意字符串写入任意容量的缓冲区，检查 write_cstr 从不越界、总是写入 NUL 且只按字节截断
+// Writes arbitrary strings into buffers of arbitrary capacity and checks that write_cstr never
+// writes out of bounds, always terminates and only truncates at the byte level
+//
+// 运行 / Run: cargo +nightly fuzz run write_cstr
+
+#![no_main]
+
+use std::ffi::c_char;
+
+use arbitrary::Arbitrary;
+use interop_common::{write_cstr, FfiError};
+use libfuzzer_sys::fuzz_target;
+
+// 缓冲区末尾的哨兵字节，被改写说明发生了越界写入
+// Sentinel bytes after the buffer, overwriting them means a write went out of bounds
+const SENTINEL: c_char = 0x5a;
+const GUARD: usize = 8;
+
+#[derive(Debug, Arbitrary)]
+struct Input {
+    capacity: u8,
+    s: String,
+}
+
+fuzz_target!(|input: Input| {
+    let cap = usize::from(input.capacity);
+    let s = input.s.as_str();
+    let mut buf = vec![SENTINEL; cap + GUARD];
+    let result = unsafe { write_cstr(buf.as_mut_ptr(), cap, s) };
+
+    assert!(
+        buf[cap..].iter().all(|&b| b == SENTINEL),
+        "wrote past {cap} bytes"
+    );
+    if s.as_bytes().contains(&0) {
+        assert_eq!(result, Err(FfiError::InteriorNul));
+        assert!(buf.iter().all(|&b| b == SENTINEL));
+        return;
+    }
+    if cap == 0 {
+        assert_eq!(result, Err(FfiError::BufferTooSmall { required: s.len() }));
+        return;
+    }
+
+    let written = s.len().min(cap - 1);
+    let bytes: Vec<u8> = buf[..written].iter().map(|&b| b as u8).collect();
+    assert_eq!(bytes, &s.as_bytes()[..written]);
+    assert_eq!(buf[written], 0, "missing NUL terminator");
+    if written == s.len() {
+        assert_eq!(result, Ok(written));
+    } else {
+        assert_eq!(result, Err(FfiError::BufferTooSmall { required: s.len() }));
+    }
+});
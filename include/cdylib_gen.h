@@ -0,0 +1,1322 @@
+#ifndef CDYLIB_GEN_H
+#define CDYLIB_GEN_H
+
+/* Generated by cbindgen from packages/cdylib_gen, do not edit by hand. */
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+#define RUSTLIB_API(name) rxc_##name
+
+/**
+ * The `size` of each version of `InitConfig`: version 1 ends before `install_panic_hook`, version
+ * 2 is the current struct. The Rust constants of the same names can't be evaluated by cbindgen.
+ */
+#define INIT_CONFIG_SIZE_V1 offsetof(InitConfig, install_panic_hook)
+#define INIT_CONFIG_SIZE_V2 sizeof(InitConfig)
+
+/**
+ * A packed message header of the binary protocol, matching the 20 bytes on the wire one to one
+ * in host byte order. The multi-byte fields are misaligned, so copy them out instead of taking
+ * their addresses; GCC warns about the latter with -Waddress-of-packed-member.
+ */
+#pragma pack(push, 1)
+typedef struct MessageHeader {
+  uint32_t magic;
+  uint8_t version;
+  uint8_t kind;
+  uint32_t payload_len;
+  uint64_t sequence;
+  /**
+   * The wrapping sum of the bytes before it.
+   */
+  uint16_t checksum;
+} MessageHeader;
+#pragma pack(pop)
+
+/**
+ * The buffer size a [`Config`] gets unless `rxc_config_set_buffer_size` is called.
+ */
+#define CONFIG_DEFAULT_BUFFER_SIZE 4096
+
+/**
+ * The smallest buffer size `rxc_config_set_buffer_size` accepts.
+ */
+#define CONFIG_MIN_BUFFER_SIZE 64
+
+/**
+ * The largest buffer size `rxc_config_set_buffer_size` accepts.
+ */
+#define CONFIG_MAX_BUFFER_SIZE (1 << 20)
+
+/**
+ * The magic every header starts with, "RXC1" in little-endian byte order.
+ */
+#define MESSAGE_HEADER_MAGIC 826497106
+
+/**
+ * The version of the header format.
+ */
+#define MESSAGE_HEADER_VERSION 1
+
+/**
+ * The size of a header in bytes, without any padding.
+ */
+#define MESSAGE_HEADER_SIZE 20
+
+/**
+ * Bumped whenever an exported signature or `#[repr(C)]` type changes incompatibly.
+ */
+#define CDYLIB_ABI_VERSION 1
+
+/**
+ * Room for a full 40-character git commit hash and its NUL terminator.
+ */
+#define CDYLIB_GIT_HASH_LEN 41
+
+/**
+ * 导出函数的状态码，C 端看到的是一个 `int` 枚举
+ * Status codes returned by the exported functions, seen as an `int` enum on the C side.
+ */
+typedef enum FfiStatus {
+  /**
+   * The call succeeded and every output was written in full.
+   */
+  FFI_STATUS_OK = 0,
+  /**
+   * A required pointer argument was NULL.
+   */
+  FFI_STATUS_NULL_POINTER = 1,
+  /**
+   * An input string is not valid UTF-8.
+   */
+  FFI_STATUS_INVALID_UTF8 = 2,
+  /**
+   * An input string has no NUL terminator within its buffer length.
+   */
+  FFI_STATUS_UNTERMINATED = 3,
+  /**
+   * An arithmetic result does not fit in its C type.
+   */
+  FFI_STATUS_OVERFLOW = 4,
+  /**
+   * An output string was truncated to fit its buffer.
+   */
+  FFI_STATUS_BUFFER_TOO_SMALL = 5,
+  /**
+   * The Rust code panicked; the message is available as the last error and from
+   * `rxc_rustlib_take_last_panic`.
+   */
+  FFI_STATUS_PANIC = 6,
+  /**
+   * A pointer argument is not properly aligned for its type.
+   */
+  FFI_STATUS_MISALIGNED = 7,
+  /**
+   * A string to be written contains an interior NUL byte.
+   */
+  FFI_STATUS_INTERIOR_NUL = 8,
+  /**
+   * The library has not been initialized, or it was shut down.
+   */
+  FFI_STATUS_NOT_INITIALIZED = 9,
+  /**
+   * The library was initialized a second time without being shut down in between.
+   */
+  FFI_STATUS_ALREADY_INITIALIZED = 10,
+  /**
+   * The call would block waiting for itself, for example when made from one of the library's
+   * own threads.
+   */
+  FFI_STATUS_WOULD_DEADLOCK = 11,
+  /**
+   * The call stopped early because the caller cancelled it, through a cancellation token or by
+   * returning non-zero from a progress callback.
+   */
+  FFI_STATUS_CANCELLED = 12,
+  /**
+   * An allocation's alignment is not a power of two or its size is too large.
+   */
+  FFI_STATUS_INVALID_LAYOUT = 13,
+  /**
+   * The allocator could not provide the requested memory.
+   */
+  FFI_STATUS_OUT_OF_MEMORY = 14,
+  /**
+   * The handle does not refer to a live object: it is null, was already freed, or is a stale
+   * copy of one whose slot now holds another object.
+   */
+  FFI_STATUS_INVALID_HANDLE = 15,
+  /**
+   * An input wide string is not valid UTF-16: it has an unpaired surrogate.
+   */
+  FFI_STATUS_INVALID_UTF16 = 16,
+  /**
+   * A file or other I/O operation failed; the last error has the OS error code.
+   */
+  FFI_STATUS_IO = 17,
+  /**
+   * The input bytes don't form a valid message, such as a header with the wrong magic or
+   * checksum.
+   */
+  FFI_STATUS_MALFORMED = 18,
+  /**
+   * The `size` a struct such as `InitConfig` starts with is not the size of any version of it
+   * the library knows, such as one from a newer header.
+   */
+  FFI_STATUS_UNSUPPORTED_VERSION = 19,
+  /**
+   * An argument is outside the range the function accepts, such as a buffer size of 0, or a
+   * value the call requires was never set.
+   */
+  FFI_STATUS_INVALID_ARGUMENT = 20,
+} FfiStatus;
+
+/**
+ * 日志级别，数值与 `log::Level` 相同
+ * The level of a log message, with the same values as `log::Level`.
+ */
+typedef enum LogLevel {
+  LOG_LEVEL_ERROR = 1,
+  LOG_LEVEL_WARN = 2,
+  LOG_LEVEL_INFO = 3,
+  LOG_LEVEL_DEBUG = 4,
+  LOG_LEVEL_TRACE = 5,
+} LogLevel;
+
+/**
+ * What a `TraceRecord` describes.
+ */
+typedef enum TraceKind {
+  /**
+   * The thread entered the span `span_id`.
+   */
+  TRACE_KIND_SPAN_ENTER = 0,
+  /**
+   * The thread left the span `span_id`.
+   */
+  TRACE_KIND_SPAN_EXIT = 1,
+  /**
+   * An event inside the span `span_id`, or outside any span when it is 0.
+   */
+  TRACE_KIND_EVENT = 2,
+} TraceKind;
+
+/**
+ * 分配器的统计数字
+ * Statistics of a library's global allocator.
+ */
+typedef struct AllocStats {
+  /**
+   * Blocks allocated so far.
+   */
+  uint64_t allocations;
+  /**
+   * Blocks released so far.
+   */
+  uint64_t deallocations;
+  /**
+   * Blocks resized in place or moved so far, not counted as allocations or frees.
+   */
+  uint64_t reallocations;
+  /**
+   * Blocks allocated and not released yet.
+   */
+  uint64_t live_blocks;
+  /**
+   * Bytes in the blocks allocated and not released yet.
+   */
+  uint64_t live_bytes;
+  /**
+   * The largest `live_bytes` has been.
+   */
+  uint64_t peak_bytes;
+  /**
+   * Blocks the last leak check at shutdown found still allocated, 0 if none ran.
+   */
+  uint64_t leaked_blocks;
+  /**
+   * Bytes in those blocks.
+   */
+  uint64_t leaked_bytes;
+} AllocStats;
+
+/**
+ * 借用的数组：指针加元素个数
+ * A borrowed array: a pointer and a count of elements.
+ *
+ * `ptr` may be NULL when `len` is 0. In C, `FfiSlice<int>` is `FfiSlice_c_int`.
+ */
+typedef struct FfiSlice_c_int {
+  const int *ptr;
+  size_t len;
+} FfiSlice_c_int;
+
+/**
+ * 交给 C 的句柄，全零是空句柄
+ * A handle given to C; the all-zero value is the null handle.
+ *
+ * C code treats it as an opaque value passed and returned by value, never made up.
+ */
+typedef struct FfiHandle {
+  /**
+   * The slot in the registry.
+   */
+  uint32_t index;
+  /**
+   * Which occupant of the slot the handle refers to, never 0 for a valid handle.
+   */
+  uint32_t generation;
+} FfiHandle;
+/**
+ * The handle constructors return on failure, it never refers to anything.
+ */
+#define FfiHandle_NULL (FfiHandle){ .index = 0, .generation = 0 }
+
+/**
+ * A calculator handed to C, the null handle when `rxc_calc_new` failed.
+ */
+typedef struct FfiHandle CalculatorHandle;
+
+/**
+ * Completion callback for `rxc_cdylib_add_async`, receiving the sum and the caller's `user_data`.
+ *
+ * `Option` makes a NULL function pointer representable, so it can be rejected instead of called.
+ */
+typedef void (*AddCallback)(int sum, void *user_data);
+
+/**
+ * A token handed to C, the null handle when `rxc_cancel_token_new` failed.
+ */
+typedef struct FfiHandle CancelTokenHandle;
+
+/**
+ * A builder handed to C, the null handle when `rxc_config_new` failed.
+ */
+typedef struct FfiHandle ConfigBuilderHandle;
+
+/**
+ * A finished configuration handed to C.
+ */
+typedef struct FfiHandle ConfigHandle;
+
+/**
+ * What the load-time constructor saw, filled in by [`cdylib_load_report`].
+ */
+typedef struct LoadReport {
+  /**
+   * How many times the constructor ran, 1 for every loaded copy of the library.
+   */
+  uint32_t constructor_runs;
+  /**
+   * Whether the library was initialized when the constructor ran, which it never is.
+   */
+  bool initialized_at_load;
+} LoadReport;
+
+/**
+ * 借用的 UTF-8 字符串，不以 NUL 结尾，可以包含 NUL
+ * A borrowed UTF-8 string: a pointer and a length in bytes, not NUL terminated and possibly
+ * containing NULs.
+ *
+ * `ptr` may be NULL when `len` is 0.
+ */
+typedef struct FfiStr {
+  const char *ptr;
+  size_t len;
+} FfiStr;
+
+/**
+ * Receives one log message: `msg` points to `msg_len` bytes of UTF-8 that are not NUL
+ * terminated and only valid during the call; `user_data` is passed through untouched.
+ *
+ * The callback may be invoked from any thread the library logs on, including its own worker
+ * threads. `Option` makes NULL representable, which restores the stderr fallback.
+ */
+typedef void (*LogCallback)(enum LogLevel level, const char *msg, size_t msg_len, void *user_data);
+
+/**
+ * 传给库的 init 函数的配置，size 之外的字段全部为零时得到默认配置
+ * The configuration passed to a library's init function; a zeroed struct with `size` set is the
+ * default configuration.
+ *
+ * Version 1 ends before `install_panic_hook`, version 2 is the whole struct.
+ */
+typedef struct InitConfig {
+  /**
+   * The size of the struct the caller passes, `sizeof(InitConfig)` when compiled against the
+   * current header; anything but [`INIT_CONFIG_SIZE_V1`] or [`INIT_CONFIG_SIZE_V2`] is
+   * rejected with `FFI_STATUS_UNSUPPORTED_VERSION`.
+   */
+  size_t size;
+  /**
+   * Receives the library's log messages as with `rxc_rustlib_set_log_callback`; NULL writes them
+   * to stderr.
+   */
+  LogCallback log_callback;
+  /**
+   * Passed through untouched to `log_callback`, it must stay valid until shutdown.
+   */
+  void *log_user_data;
+  /**
+   * Installs a panic hook that records the location of a panic for
+   * `rxc_rustlib_take_last_panic`; without it only the panic message is available.
+   */
+  bool install_panic_hook;
+  /**
+   * The number of worker threads for asynchronous calls such as `rxc_cdylib_add_async` and
+   * `rxc_async_submit`, 0 picks one. Libraries without asynchronous calls ignore it.
+   */
+  uint32_t worker_threads;
+  /**
+   * Reports the allocations made since init and still outstanding when the library shuts
+   * down, as with `rxc_rustlib_alloc_stats`. Libraries without allocation statistics ignore it.
+   */
+  bool leak_check;
+} InitConfig;
+
+/**
+ * A transform applied by `rxc_cdylib_apply`; NULL means "leave the value unchanged".
+ */
+typedef int (*Transform)(int value);
+
+/**
+ * A transform applied by `rxc_cdylib_apply_unwind`, which may unwind (for example a C++ function
+ * that throws); NULL means "leave the value unchanged".
+ */
+typedef int (*TransformUnwind)(int value);
+
+#if !defined(_WIN32)
+/**
+ * A code unit of a native path: a `char` of bytes on Unix.
+ */
+typedef char PathChar;
+#endif
+
+#if defined(_WIN32)
+/**
+ * A code unit of a native path: a UTF-16 `wchar_t` on Windows.
+ */
+typedef uint16_t PathChar;
+#endif
+
+/**
+ * A pool handed to C, the null handle when `rxc_pool_create` failed.
+ */
+typedef struct FfiHandle ThreadPoolHandle;
+
+/**
+ * A job for `rxc_pool_submit`, receiving the caller's `user_data` and returning a result for the
+ * completion callback.
+ */
+typedef int (*PoolJob)(void *user_data);
+
+/**
+ * Completion callback for `rxc_pool_submit`, receiving the job's result and the same `user_data`.
+ */
+typedef void (*PoolDone)(int result, void *user_data);
+
+/**
+ * Progress callback for `rxc_cdylib_sum_with_progress`, receiving the percentage done (0 to 100)
+ * and the caller's `user_data`; returning non-zero aborts the computation.
+ */
+typedef int (*ProgressCallback)(uint8_t percent, void *user_data);
+
+/**
+ * Progress callback for `rxc_cdylib_sum_with_progress_unwind`, which may unwind (for example a
+ * Rust callback that panics or a C++ function that throws).
+ */
+typedef int (*ProgressCallbackUnwind)(uint8_t percent, void *user_data);
+
+/**
+ * 借用的数组：指针加元素个数
+ * A borrowed array: a pointer and a count of elements.
+ *
+ * `ptr` may be NULL when `len` is 0. In C, `FfiSlice<int>` is `FfiSlice_c_int`.
+ */
+typedef struct FfiSlice_u8 {
+  const uint8_t *ptr;
+  size_t len;
+} FfiSlice_u8;
+
+/**
+ * A queue handed to C, the null handle when `rxc_queue_new` failed.
+ */
+typedef struct FfiHandle QueueHandle;
+
+/**
+ * Result callback for a queue, receiving each item, the result of processing it (its square)
+ * and the `user_data` given to `rxc_queue_new`.
+ */
+typedef void (*QueueCallback)(int item, int64_t result, void *user_data);
+
+/**
+ * 交给运行时的请求：等待 `delay_ms` 毫秒后计算 `a + b`，模拟一次 I/O
+ * A request for the runtime: computes `a + b` after waiting `delay_ms` milliseconds, standing in
+ * for a round of I/O.
+ */
+typedef struct AsyncRequest {
+  int a;
+  int b;
+  uint32_t delay_ms;
+} AsyncRequest;
+
+/**
+ * Completion callback for `rxc_async_submit`, receiving the request's status, the sum (0 unless
+ * the status is `FFI_STATUS_OK`) and the caller's `user_data`.
+ */
+typedef void (*AsyncCallback)(enum FfiStatus status, int sum, void *user_data);
+
+/**
+ * A UTF-8 string that is not NUL terminated, the same as `FfiStr`.
+ */
+typedef struct FfiStr TraceStr;
+
+/**
+ * One `key = value` field of a span or event, the value formatted as with `{:?}`.
+ */
+typedef struct TraceField {
+  TraceStr key;
+  TraceStr value;
+} TraceField;
+
+/**
+ * A span enter/exit or an event, with every pointer only valid during the callback.
+ *
+ * Span records carry the fields the span was created and recorded with; an event's message is
+ * its `message` field.
+ */
+typedef struct TraceRecord {
+  enum TraceKind kind;
+  enum LogLevel level;
+  uint64_t span_id;
+  TraceStr name;
+  TraceStr target;
+  const struct TraceField *fields;
+  size_t field_count;
+} TraceRecord;
+
+/**
+ * Receives every `TraceRecord` with the caller's `user_data`, on whichever thread the record
+ * happened.
+ */
+typedef void (*TraceCallback)(const struct TraceRecord *record, void *user_data);
+
+/**
+ * The library version, filled in by [`cdylib_version`].
+ */
+typedef struct Version {
+  uint32_t major;
+  uint32_t minor;
+  uint32_t patch;
+  /**
+   * NUL-terminated commit hash the library was built from, empty when it was built outside git.
+   */
+  char git_hash[CDYLIB_GIT_HASH_LEN];
+} Version;
+
+/**
+ * Adds `a` and `b`, stores the sum in `sum` and writes a greeting message into `result`.
+ *
+ * `result` is an in/out buffer of `result_len` bytes: on input it holds the caller's
+ * NUL-terminated name, on output the message. Like `snprintf`, the message is truncated to
+ * fit and always NUL terminated; if `required_len` is not NULL it receives the length the full
+ * message needs (excluding the NUL). Passing a NULL `result` with `result_len == 0` only
+ * queries that length.
+ *
+ * Safe to call from several threads at once as long as each call has its own buffers: besides
+ * them it only touches the calling thread's last error.
+ *
+ * # Safety
+ *
+ * `result` must be valid for reads and writes of `result_len` bytes (it may be NULL when
+ * `result_len` is 0), `sum` must be valid for writes and `required_len` must be NULL or
+ * valid for writes.
+ */
+enum FfiStatus rxc_cdylib_add(int a,
+                              int b,
+                              char *result,
+                              size_t result_len,
+                              int *sum,
+                              size_t *required_len);
+
+/**
+ * Writes the statistics of the library's allocator into `out`: the blocks and bytes allocated
+ * and still in use, and the result of the last leak check `rxc_rustlib_shutdown` ran for an
+ * `InitConfig` with `leak_check` set. Works at any time.
+ *
+ * The counters cover every allocation in the library, including its own worker threads and
+ * caches, so compare two snapshots rather than reading one on its own. State kept for a calling
+ * thread until it exits, such as the runtime's after `rxc_async_block_on`, is counted by the leak
+ * check too.
+ *
+ * # Safety
+ *
+ * `out` must be valid for writes.
+ */
+enum FfiStatus rxc_rustlib_alloc_stats(struct AllocStats *out);
+
+/**
+ * Writes the values of `[start, end)` into `out`, stopping after `cap` values.
+ *
+ * Returns the number of values in the range, so a return value greater than `cap` means `out`
+ * was too small; passing a NULL `out` with `cap == 0` only queries that count.
+ *
+ * # Safety
+ *
+ * `out` must be valid for writes of `cap` `int`s (it may be NULL when `cap` is 0).
+ */
+size_t rxc_cdylib_range(int start, int end, int *out, size_t cap);
+
+/**
+ * Sums the `len` values at `values` into `total`, like `rxc_staticlib_sum`.
+ *
+ * `values` may be NULL when `len` is 0. Fails with `FFI_STATUS_OVERFLOW` if the sum does not fit
+ * in a `long`, which on Windows is only 32 bits wide.
+ *
+ * # Safety
+ *
+ * `values` must point to `len` readable `int`s and `total` must be valid for writes.
+ */
+enum FfiStatus rxc_cdylib_sum(const int *values, size_t len, long *total);
+
+/**
+ * The same as `rxc_cdylib_sum`, taking the values as one `FfiSlice_c_int`.
+ *
+ * # Safety
+ *
+ * `values` must view `len` readable `int`s and `total` must be valid for writes.
+ */
+enum FfiStatus rxc_cdylib_sum_slice(struct FfiSlice_c_int values, long *total);
+
+/**
+ * Creates a new calculator. The handle must be released with `rxc_calc_free`.
+ *
+ * Returns the null handle if the library is not initialized.
+ */
+CalculatorHandle rxc_calc_new(void);
+
+/**
+ * Adds `a` and `b` with the calculator, storing the sum in `sum` and recording it in the history.
+ *
+ * Fails with `FFI_STATUS_INVALID_HANDLE` when `handle` was freed or never came from
+ * `rxc_calc_new`.
+ *
+ * # Safety
+ *
+ * `sum` must be valid for writes.
+ */
+enum FfiStatus rxc_calc_add(CalculatorHandle handle, int a, int b, int *sum);
+
+/**
+ * Writes the calculator's history, one addition per line, into `buf` like `snprintf` does.
+ *
+ * If `required_len` is not NULL it receives the length of the full history (excluding the NUL).
+ * Fails with `FFI_STATUS_INVALID_HANDLE` when `handle` was freed or never came from
+ * `rxc_calc_new`.
+ *
+ * # Safety
+ *
+ * `buf` must be valid for writes of `len` bytes (it may be NULL when `len` is 0) and
+ * `required_len` must be NULL or valid for writes.
+ */
+enum FfiStatus rxc_calc_history(CalculatorHandle handle,
+                                char *buf,
+                                size_t len,
+                                size_t *required_len);
+
+/**
+ * Destroys a calculator created by `rxc_calc_new`. Passing the null handle is a no-op.
+ *
+ * Freeing a handle twice, or one that never came from `rxc_calc_new`, fails with
+ * `FFI_STATUS_INVALID_HANDLE` and leaves every other calculator alone. A call still running on
+ * another thread finishes with the calculator before it is destroyed.
+ */
+enum FfiStatus rxc_calc_free(CalculatorHandle handle);
+
+/**
+ * Adds `a` and `b` on one of the library's worker threads and reports the sum through `cb`.
+ *
+ * On success `cb` is invoked exactly once, from another thread, with `user_data` passed through
+ * untouched; on failure (NULL `cb`, overflow or an uninitialized library) it is never invoked.
+ * `user_data` must stay valid until the callback has run, at the latest by `rxc_rustlib_shutdown`.
+ */
+enum FfiStatus rxc_cdylib_add_async(int a, int b, AddCallback cb, void *user_data);
+
+/**
+ * Creates a token that is not cancelled yet. The handle must be released with
+ * `rxc_cancel_token_free`.
+ *
+ * Returns the null handle if the library is not initialized.
+ */
+CancelTokenHandle rxc_cancel_token_new(void);
+
+/**
+ * Cancels `token`, making the calls using it return `FFI_STATUS_CANCELLED` at their next check.
+ *
+ * Safe to call from any thread, including while the library shuts down; cancelling twice is
+ * harmless. Fails with `FFI_STATUS_INVALID_HANDLE` when `token` was freed or never came from
+ * `rxc_cancel_token_new`.
+ */
+enum FfiStatus rxc_cancel_token_cancel(CancelTokenHandle token);
+
+/**
+ * Destroys a token created by `rxc_cancel_token_new`. Passing the null handle is a no-op.
+ *
+ * Calls still using the token keep their own reference to it until they return, so it may be
+ * freed at any time. Freeing a handle twice, or one that never came from `rxc_cancel_token_new`,
+ * fails with `FFI_STATUS_INVALID_HANDLE`.
+ */
+enum FfiStatus rxc_cancel_token_free(CancelTokenHandle token);
+
+/**
+ * Sums the `len` values at `values` into `total` like `rxc_cdylib_sum`, waiting `delay_ms`
+ * milliseconds before each value to stand in for slow work.
+ *
+ * Before each value it checks `token` (which may be the null handle to run to completion) and
+ * fails with `FFI_STATUS_CANCELLED` once the token was cancelled, leaving `total` untouched. A
+ * token that was freed or never came from `rxc_cancel_token_new` fails with
+ * `FFI_STATUS_INVALID_HANDLE` before any value is summed.
+ *
+ * # Safety
+ *
+ * `values` must point to `len` readable `int`s and `total` must be valid for writes.
+ */
+enum FfiStatus rxc_cdylib_slow_sum(const int *values,
+                                   size_t len,
+                                   uint32_t delay_ms,
+                                   CancelTokenHandle token,
+                                   long *total);
+
+/**
+ * Creates a builder with the default options and no name. The handle is used up by a successful
+ * `rxc_config_build`, or else must be released with `rxc_config_builder_free`.
+ *
+ * Returns the null handle if the library is not initialized.
+ */
+ConfigBuilderHandle rxc_config_new(void);
+
+/**
+ * Sets the configuration's name to a copy of the NUL-terminated UTF-8 `name`, which is required.
+ *
+ * Fails with `FFI_STATUS_INVALID_ARGUMENT` when `name` is empty and with
+ * `FFI_STATUS_INVALID_HANDLE` when `builder` was built, freed or never came from
+ * `rxc_config_new`; the builder is unchanged when it fails.
+ *
+ * # Safety
+ *
+ * `name` must be NULL or point to a NUL-terminated string.
+ */
+enum FfiStatus rxc_config_set_name(ConfigBuilderHandle builder, const char *name);
+
+/**
+ * Sets the size of the configuration's buffer in bytes, `CONFIG_DEFAULT_BUFFER_SIZE` unless
+ * called.
+ *
+ * Fails with `FFI_STATUS_INVALID_ARGUMENT` unless `size` is a power of two from
+ * `CONFIG_MIN_BUFFER_SIZE` to `CONFIG_MAX_BUFFER_SIZE`, and like `rxc_config_set_name` for a bad
+ * handle; the builder is unchanged when it fails.
+ */
+enum FfiStatus rxc_config_set_buffer_size(ConfigBuilderHandle builder, size_t size);
+
+/**
+ * Finishes the configuration, storing its handle in `config`; it must be released with
+ * `rxc_config_free`.
+ *
+ * On success the builder is used up and its handle becomes stale. Fails with
+ * `FFI_STATUS_INVALID_ARGUMENT` when no name was set, and then leaves the builder valid so it
+ * can be completed and built again, or freed.
+ *
+ * # Safety
+ *
+ * `config` must be valid for writes.
+ */
+enum FfiStatus rxc_config_build(ConfigBuilderHandle builder, ConfigHandle *config);
+
+/**
+ * Destroys a builder that was never built. Passing the null handle is a no-op.
+ *
+ * Fails with `FFI_STATUS_INVALID_HANDLE` for a builder already built or freed.
+ */
+enum FfiStatus rxc_config_builder_free(ConfigBuilderHandle builder);
+
+/**
+ * Writes the configuration's name into `buf` like `snprintf` does.
+ *
+ * If `required_len` is not NULL it receives the length of the full name (excluding the NUL).
+ * Fails with `FFI_STATUS_INVALID_HANDLE` when `config` was freed or never came from
+ * `rxc_config_build`.
+ *
+ * # Safety
+ *
+ * `buf` must be valid for writes of `len` bytes (it may be NULL when `len` is 0) and
+ * `required_len` must be NULL or valid for writes.
+ */
+enum FfiStatus rxc_config_name(ConfigHandle config, char *buf, size_t len, size_t *required_len);
+
+/**
+ * Stores the configuration's buffer size in `size`, failing like `rxc_config_name` for a bad
+ * handle.
+ *
+ * # Safety
+ *
+ * `size` must be valid for writes.
+ */
+enum FfiStatus rxc_config_buffer_size(ConfigHandle config, size_t *size);
+
+/**
+ * Destroys a configuration built by `rxc_config_build`. Passing the null handle is a no-op.
+ *
+ * Freeing a handle twice, or one that never came from `rxc_config_build`, fails with
+ * `FFI_STATUS_INVALID_HANDLE`.
+ */
+enum FfiStatus rxc_config_free(ConfigHandle config);
+
+/**
+ * Writes what the load-time constructor saw into `out`; works at any time.
+ *
+ * # Safety
+ *
+ * `out` must be NULL or valid for writes.
+ */
+enum FfiStatus rxc_cdylib_load_report(struct LoadReport *out);
+
+/**
+ * Builds a greeting for the NUL-terminated UTF-8 `name`.
+ *
+ * Ownership of the returned string passes to the caller, who must release it with `rxc_rust_free`
+ * (never with `free`, the memory belongs to Rust's allocator). Returns NULL
+ * on failure, with the reason available from `rxc_rustlib_last_error_message`.
+ *
+ * # Safety
+ *
+ * `name` must be NULL or point to a NUL-terminated string.
+ */
+char *rxc_cdylib_make_greeting(const char *name);
+
+/**
+ * The same as `rxc_cdylib_make_greeting`, taking `name` as an `FfiStr` that doesn't need a NUL
+ * terminator, such as a slice of a longer string. Fails if `name` contains a NUL.
+ *
+ * # Safety
+ *
+ * `name` must view `len` readable bytes.
+ */
+char *rxc_cdylib_make_greeting_str(struct FfiStr name);
+
+/**
+ * Releases a string returned by `rxc_cdylib_make_greeting`. Passing NULL is a no-op.
+ *
+ * The same as `rxc_rust_free`, kept for callers written before it existed.
+ *
+ * # Safety
+ *
+ * `s` must be NULL or a string returned by this library that has not been freed yet.
+ */
+void rxc_cdylib_string_free(char *s);
+
+/**
+ * Writes the same greeting as `rxc_cdylib_make_greeting` into a caller-provided buffer.
+ *
+ * This is the two-call pattern: call once with a NULL `buf` and `len == 0` to learn the length
+ * through `required_len` (the status is then `FFI_STATUS_BUFFER_TOO_SMALL`), allocate
+ * `required_len + 1` bytes and call again.
+ *
+ * # Safety
+ *
+ * `name` must be NULL or point to a NUL-terminated string, `buf` must be valid for writes of
+ * `len` bytes (it may be NULL when `len` is 0) and `required_len` must be NULL or valid for writes.
+ */
+enum FfiStatus rxc_cdylib_greeting_message(const char *name,
+                                           char *buf,
+                                           size_t len,
+                                           size_t *required_len);
+
+/**
+ * Returns the buffer size (including the NUL) needed for the calling thread's last error
+ * message, or 0 if no call on this thread has failed yet.
+ */
+size_t rxc_rustlib_last_error_length(void);
+
+/**
+ * Copies the calling thread's last error message into `buf` like `snprintf` does.
+ *
+ * An empty string is written when there is no error. Reading does not clear the error.
+ *
+ * # Safety
+ *
+ * `buf` must be valid for writes of `len` bytes (it may be NULL when `len` is 0).
+ */
+enum FfiStatus rxc_rustlib_last_error_message(char *buf, size_t len);
+
+/**
+ * Copies the message and location of the calling thread's last caught panic into `buf` like
+ * `snprintf` does, and returns the full message length (excluding the NUL).
+ *
+ * Returns 0 when no panic is recorded. The message is only cleared once it was copied in full,
+ * so after a truncated read the call can be repeated with a buffer of the returned length plus
+ * one; a NULL `buf` with `len == 0` only queries the length. Returns -1 if `buf` is NULL while
+ * `len` is not 0.
+ *
+ * # Safety
+ *
+ * `buf` must be valid for writes of `len` bytes (it may be NULL when `len` is 0).
+ */
+int rxc_rustlib_take_last_panic(char *buf, size_t len);
+
+/**
+ * Initializes the library with `config`, or with the default configuration when `config` is
+ * NULL, and must be called before any other export returning a status.
+ *
+ * Sets up logging, the worker threads for `rxc_cdylib_add_async`, the async runtime for
+ * `rxc_async_submit` and, if requested, the panic hook once; Rust's global allocator is fixed when
+ * the library is linked, so there is nothing to set up for it. With `leak_check` set, the
+ * allocations from here on are checked at shutdown. Until this succeeds the other exports fail
+ * with `FFI_STATUS_NOT_INITIALIZED` (or return NULL), while the ABI and version queries, the last
+ * error accessors and the log and trace callback setters work at any time. Calling it again before
+ * `rxc_rustlib_shutdown` fails with `FFI_STATUS_ALREADY_INITIALIZED` and keeps the first
+ * configuration.
+ *
+ * Only the `size` bytes `config` declares are read, so callers compiled against the version 1
+ * `InitConfig` keep working and get the defaults for the newer fields; a size of no known
+ * version fails with `FFI_STATUS_UNSUPPORTED_VERSION`.
+ *
+ * # Safety
+ *
+ * `config` must be NULL or point to an `InitConfig` of the version its `size` declares.
+ */
+enum FfiStatus rxc_rustlib_init(const struct InitConfig *config);
+
+/**
+ * Shuts the library down again, after which the other exports fail with
+ * `FFI_STATUS_NOT_INITIALIZED` until `rxc_rustlib_init` is called again.
+ *
+ * Waits for the pending `rxc_cdylib_add_async` and `rxc_async_submit` callbacks to run, so called
+ * from one of them it fails with `FFI_STATUS_WOULD_DEADLOCK` and leaves the library initialized,
+ * and restores writing log messages to stderr. Calls running on
+ * other threads at the same time finish normally, the log callback's `user_data` must stay valid
+ * until they return. Calculators and strings the library returned can still be freed afterwards.
+ *
+ * With the `leak_check` of the `InitConfig` given to `rxc_rustlib_init` set, it finally logs how
+ * many allocations made since then are still outstanding, as a warning to stderr, and records
+ * them for `rxc_rustlib_alloc_stats`.
+ */
+enum FfiStatus rxc_rustlib_shutdown(void);
+
+/**
+ * Routes the library's log messages to `callback`, which receives each message with its level
+ * and `user_data`; passing NULL restores the default of writing them to stderr.
+ *
+ * The callback may be invoked from any thread the library logs on. `user_data` must stay valid
+ * until another callback is registered.
+ */
+void rxc_rustlib_set_log_callback(LogCallback callback, void *user_data);
+
+/**
+ * Allocates `size` bytes aligned to `align` with Rust's allocator, to be released with
+ * `rxc_rust_free`. The contents are uninitialized.
+ *
+ * A `size` of 0 still returns a unique pointer that must be freed. Returns NULL if `align` is
+ * not a power of two, the size is too large or the allocator is out of memory, with the reason
+ * available from `rxc_rustlib_last_error_message`. Works before `rxc_rustlib_init`.
+ *
+ * With the `malloc` feature the block comes from `malloc` (or `posix_memalign`) and may be
+ * released with `free` as well; on Windows alignments above `malloc`'s are then rejected.
+ */
+void *rxc_rust_alloc(size_t size, size_t align);
+
+/**
+ * Resizes a block from `rxc_rust_alloc` to `new_size` bytes, keeping its alignment and the
+ * contents up to the smaller of the two sizes, like `realloc`.
+ *
+ * A NULL `ptr` allocates a new block aligned like `malloc` would. On failure NULL is returned
+ * and the original block is left untouched, so it must still be freed. With the `malloc`
+ * feature this is `realloc` itself, which only keeps alignments up to `malloc`'s.
+ *
+ * # Safety
+ *
+ * `ptr` must be NULL or come from `rxc_rust_alloc` or `rxc_rust_realloc` and not have been freed;
+ * on success it must not be used again.
+ */
+void *rxc_rust_realloc(void *ptr, size_t new_size);
+
+/**
+ * Releases memory from `rxc_rust_alloc` or `rxc_rust_realloc`, and every string this library
+ * returns, such as the one from `rxc_cdylib_make_greeting`. Passing NULL is a no-op. Works at any
+ * time.
+ *
+ * This is the only correct way to release them: `free` belongs to a different allocator and,
+ * on Windows, possibly to a different C runtime. The `malloc` feature lifts that restriction
+ * for C code sharing the library's C runtime, and lets `rxc_rust_free` release `malloc`'s memory.
+ *
+ * # Safety
+ *
+ * `ptr` must be NULL or come from one of those functions, and must not be used again afterwards.
+ */
+void rxc_rust_free(void *ptr);
+
+/**
+ * Releases an array of strings this library returns, such as the one from
+ * `rxc_cdylib_split_words`, together with the `len` strings in it. Passing NULL is a no-op.
+ *
+ * # Safety
+ *
+ * `array` must be NULL or an array of `len` strings returned by this library that has not been
+ * freed yet, and neither it nor its strings may be used again afterwards.
+ */
+void rxc_rust_string_array_free(char **array, size_t len);
+
+/**
+ * Applies `transform` to `value`, or returns `value` unchanged when `transform` is NULL.
+ */
+int rxc_cdylib_apply(int value, Transform transform);
+
+/**
+ * Like `rxc_cdylib_apply`, but an exception thrown by `transform` unwinds through Rust back to the
+ * caller, running Rust's destructors on the way.
+ *
+ * An exception must never escape a `Transform` passed to `rxc_cdylib_apply`: Rust assumes that
+ * `extern "C"` calls do not unwind, so that is undefined behavior.
+ *
+ * # Safety
+ *
+ * `transform` must be NULL or a function that either returns or unwinds with an exception the
+ * caller of `rxc_cdylib_apply_unwind` catches.
+ */
+int rxc_cdylib_apply_unwind(int value, TransformUnwind transform);
+
+/**
+ * Adds the values behind `a` and `b`, using `fallback` for an operand that is NULL.
+ *
+ * The sum wraps around on overflow.
+ */
+int rxc_cdylib_add_or_default(const int *a, const int *b, int fallback);
+
+/**
+ * Increments the counter behind `counter` and returns its new value, or returns -1 without
+ * doing anything when `counter` is NULL.
+ */
+int rxc_cdylib_increment(int *counter);
+
+/**
+ * Reads the file at `path` into `out`, writing its full size to `file_len`.
+ *
+ * `path` is NUL terminated and in the platform's native encoding: bytes on Unix, UTF-16 on
+ * Windows, so the `wchar_t` paths `_wfopen` and the `W` functions take are passed as is. Neither
+ * has to be UTF-8. The contents are truncated to fit `out` with `FFI_STATUS_BUFFER_TOO_SMALL`, and a
+ * failure to read the file returns `FFI_STATUS_IO` with the OS error code in the last error.
+ *
+ * # Safety
+ *
+ * `path` must point to a NUL-terminated native path, `out` must be valid for writes of `len` bytes
+ * (it may be NULL when `len` is 0) and `file_len` must be NULL or valid for writes.
+ */
+enum FfiStatus rxc_cdylib_read_file(const PathChar *path,
+                                    uint8_t *out,
+                                    size_t len,
+                                    size_t *file_len);
+
+/**
+ * Creates a pool with `threads` worker threads, or one per CPU when `threads` is 0. The handle
+ * must be released with `rxc_pool_free`.
+ *
+ * Returns the null handle if the library is not initialized.
+ */
+ThreadPoolHandle rxc_pool_create(uint32_t threads);
+
+/**
+ * Queues `job` to run with `user_data` on one of the pool's threads.
+ *
+ * Once it returns, `done` (which may be NULL) is invoked on the same thread with the job's result
+ * and `user_data`. Jobs start in the order they were submitted but may finish in any order;
+ * `user_data` must stay valid until `done` has run, at the latest by `rxc_pool_join` or
+ * `rxc_pool_free`. Fails with `FFI_STATUS_INVALID_HANDLE` when `pool` was freed or never came from
+ * `rxc_pool_create`.
+ */
+enum FfiStatus rxc_pool_submit(ThreadPoolHandle pool, PoolJob job, PoolDone done, void *user_data);
+
+/**
+ * Blocks until every job submitted so far and its completion callback have run; the pool can be
+ * used again afterwards.
+ *
+ * Called from one of the pool's own jobs or completion callbacks, which would wait for itself, it
+ * fails with `FFI_STATUS_WOULD_DEADLOCK`.
+ */
+enum FfiStatus rxc_pool_join(ThreadPoolHandle pool);
+
+/**
+ * Runs the jobs still queued, stops the pool's threads and destroys it. Passing the null handle
+ * is a no-op.
+ *
+ * Like `rxc_pool_join` it fails with `FFI_STATUS_WOULD_DEADLOCK` from one of the pool's own jobs,
+ * leaving the pool as it was. Freeing a handle twice, or one that never came from
+ * `rxc_pool_create`, fails with `FFI_STATUS_INVALID_HANDLE`.
+ */
+enum FfiStatus rxc_pool_free(ThreadPoolHandle pool);
+
+/**
+ * Sums the `len` values at `values` into `total` like `rxc_cdylib_sum`, one value per step, and
+ * reports the progress through `cb` (which may be NULL).
+ *
+ * `cb` is called on the calling thread with 0 before the first step and then whenever the
+ * percentage grows, ending with 100, and gets `user_data` passed through untouched. It may call
+ * back into the library, since no lock is held while it runs. When it returns non-zero the sum
+ * stops there and fails with `FFI_STATUS_CANCELLED`, leaving `total` untouched.
+ *
+ * `cb` must not unwind: a Rust callback that panics aborts the process at its own `extern "C"`
+ * boundary, and a C++ exception escaping it is undefined behavior. Use
+ * `rxc_cdylib_sum_with_progress_unwind` for callbacks that may unwind.
+ *
+ * # Safety
+ *
+ * `values` must point to `len` readable `int`s and `total` must be valid for writes.
+ */
+enum FfiStatus rxc_cdylib_sum_with_progress(const int *values,
+                                            size_t len,
+                                            ProgressCallback cb,
+                                            void *user_data,
+                                            long *total);
+
+/**
+ * Like `rxc_cdylib_sum_with_progress`, but a panic or exception raised by `cb` unwinds through the
+ * library back to the caller, which has to be compiled with unwinding enabled to catch it.
+ * `total` is left untouched then.
+ *
+ * Nothing is caught on the way, so a panic in the library itself unwinds to the caller as well
+ * instead of returning `FFI_STATUS_PANIC`.
+ *
+ * # Safety
+ *
+ * Like `rxc_cdylib_sum_with_progress`; in addition `cb` must be NULL or a function that either
+ * returns or unwinds with a panic or exception the caller catches.
+ */
+enum FfiStatus rxc_cdylib_sum_with_progress_unwind(const int *values,
+                                                   size_t len,
+                                                   ProgressCallbackUnwind cb,
+                                                   void *user_data,
+                                                   long *total);
+
+/**
+ * Fills in `header` for a message of `kind` with a payload of `payload_len` bytes, including its
+ * magic, version and checksum.
+ *
+ * # Safety
+ *
+ * `header` must be NULL or valid for writes of a `MessageHeader`; it may point anywhere into a
+ * byte buffer, as the header's alignment is 1.
+ */
+enum FfiStatus rxc_cdylib_header_init(uint8_t kind,
+                                      uint64_t sequence,
+                                      uint32_t payload_len,
+                                      MessageHeader *header);
+
+/**
+ * Parses the header at the start of `bytes` into `header`.
+ *
+ * Fails with `FFI_STATUS_BUFFER_TOO_SMALL` when `bytes` is shorter than a header and with
+ * `FFI_STATUS_MALFORMED` when its magic, version or checksum is wrong, leaving `header` untouched
+ * either way. `bytes` may start at any address.
+ *
+ * # Safety
+ *
+ * `bytes` must view `len` readable bytes and `header` must be NULL or valid for writes.
+ */
+enum FfiStatus rxc_cdylib_header_parse(struct FfiSlice_u8 bytes, MessageHeader *header);
+
+/**
+ * Creates a queue whose consumer thread calls `cb` with `user_data` for every item pushed, in the
+ * order the pushes happened. The handle must be released with `rxc_queue_free`.
+ *
+ * `user_data` must stay valid until `rxc_queue_free` returns. Returns the null handle if `cb` is
+ * NULL or the library is not initialized.
+ */
+QueueHandle rxc_queue_new(QueueCallback cb, void *user_data);
+
+/**
+ * Pushes `item` onto the queue without waiting for the consumer.
+ *
+ * Fails with `FFI_STATUS_INVALID_HANDLE` when `queue` was freed or never came from
+ * `rxc_queue_new`.
+ */
+enum FfiStatus rxc_queue_push(QueueHandle queue, int item);
+
+/**
+ * Lets the consumer finish the items already pushed, stops it and destroys the queue. Passing
+ * the null handle is a no-op.
+ *
+ * Must not be called from the queue's callback, which would wait for itself. A push still running
+ * on another thread is finished first, by that thread, and later pushes fail with
+ * `FFI_STATUS_INVALID_HANDLE`, as does freeing the handle twice.
+ */
+enum FfiStatus rxc_queue_free(QueueHandle queue);
+
+/**
+ * Runs `request` on the library's async runtime and reports the outcome through `cb`.
+ *
+ * Returns as soon as the request is queued. `cb` is then invoked exactly once, on one of the
+ * runtime's worker threads, with `user_data` passed through untouched; if the status it gets is
+ * not `FFI_STATUS_OK`, that thread's last error describes why. When this call fails (NULL `cb`
+ * or an uninitialized library) `cb` is never invoked. `user_data` must stay valid until the
+ * callback has run, at the latest by `rxc_rustlib_shutdown`, which waits for it.
+ *
+ * The callback must not block for long, since it holds up the other requests sharing its thread,
+ * and must not call `rxc_async_block_on` or `rxc_rustlib_shutdown`.
+ */
+enum FfiStatus rxc_async_submit(struct AsyncRequest request, AsyncCallback cb, void *user_data);
+
+/**
+ * Runs `request` on the library's async runtime and blocks the calling thread until it is done,
+ * storing the sum in `sum`.
+ *
+ * Fails with `FFI_STATUS_WOULD_DEADLOCK` when called on one of the runtime's threads, such as
+ * from an `rxc_async_submit` callback.
+ *
+ * # Safety
+ *
+ * `sum` must be valid for writes.
+ */
+enum FfiStatus rxc_async_block_on(struct AsyncRequest request, int *sum);
+
+/**
+ * Whether `value` is even.
+ */
+bool rxc_cdylib_is_even(int64_t value);
+
+/**
+ * Returns `!value`.
+ */
+bool rxc_cdylib_not(bool value);
+
+/**
+ * Rounds `size` up to a multiple of `align`, returning 0 if `align` is not a power of two or
+ * the result does not fit in a `size_t`.
+ */
+size_t rxc_cdylib_align_up(size_t size, size_t align);
+
+/**
+ * Linearly interpolates between `a` and `b`: `a` at `t == 0.0` and `b` at `t == 1.0`.
+ */
+double rxc_cdylib_lerp(double a, double b, double t);
+
+/**
+ * Joins the `len` strings at `strings` with `separator` in between, such as `argc` and `argv`.
+ *
+ * Ownership of the returned string passes to the caller, who must release it with
+ * `rxc_rust_free`. Returns NULL on failure, with the reason available from
+ * `rxc_rustlib_last_error_message`.
+ *
+ * # Safety
+ *
+ * `strings` must point to `len` NUL-terminated strings (it may be NULL when `len` is 0) and
+ * `separator` must be NULL or point to a NUL-terminated string.
+ */
+char *rxc_cdylib_join_strings(const char *const *strings, size_t len, const char *separator);
+
+/**
+ * Splits `text` at whitespace, returning the words as an array of `*count` strings followed by
+ * a NULL.
+ *
+ * Ownership of the array and its strings passes to the caller, who must release them with
+ * `rxc_rust_string_array_free(array, *count)`. Returns NULL on failure, with the reason
+ * available from `rxc_rustlib_last_error_message`; text without words still gets an array
+ * holding only the NULL.
+ *
+ * # Safety
+ *
+ * `text` must be NULL or point to a NUL-terminated string, and `count` must be valid for writes.
+ */
+char **rxc_cdylib_split_words(const char *text, size_t *count);
+
+/**
+ * Forwards this library's tracing spans and events to `callback` with `user_data`; passing NULL
+ * stops forwarding.
+ *
+ * The subscriber is installed as the process's global tracing default on the first call, unless
+ * a Rust host linking the library as an rlib set its own default first. `user_data` must stay
+ * valid until another callback is registered.
+ */
+void rxc_cdylib_set_trace_callback(TraceCallback callback, void *user_data);
+
+/**
+ * Returns the ABI version this library was built with, compare it with `CDYLIB_ABI_VERSION`
+ * from the header the caller was compiled against.
+ */
+uint32_t rxc_cdylib_abi_version(void);
+
+/**
+ * Writes the package version and the git commit the library was built from into `out`.
+ *
+ * # Safety
+ *
+ * `out` must be NULL or valid for writes.
+ */
+enum FfiStatus rxc_cdylib_version(struct Version *out);
+
+/**
+ * The first version of `rxc_cdylib_add`, kept for callers compiled against it.
+ *
+ * `result` holds the caller's NUL-terminated name on input and receives the message on output,
+ * it must be large enough for the whole message. Returns the sum, or 0 on failure with the reason
+ * available from `rxc_rustlib_last_error_message`; a real sum of 0 can't be told apart from a
+ * failure, one of the reasons `rxc_cdylib_add_v2` replaced it.
+ *
+ * # Safety
+ *
+ * `result` must be NULL or point to a NUL-terminated string in a buffer large enough for the
+ * message.
+ */
+int rxc_cdylib_add_v1(int a, int b, char *result);
+
+/**
+ * The length-checked `rxc_cdylib_add` under its versioned name, see it for the contract.
+ *
+ * # Safety
+ *
+ * The same as `rxc_cdylib_add`.
+ */
+enum FfiStatus rxc_cdylib_add_v2(int a,
+                                 int b,
+                                 char *result,
+                                 size_t result_len,
+                                 int *sum,
+                                 size_t *required_len);
+
+/**
+ * Like `rxc_cdylib_add`, with `result` a buffer of `len` UTF-16 code units, a `wchar_t` buffer
+ * on Windows.
+ *
+ * `result` holds the caller's NUL-terminated name on input and receives the message on output,
+ * truncated to fit with `FFI_STATUS_BUFFER_TOO_SMALL` but never in the middle of a surrogate
+ * pair. A name with an unpaired surrogate fails with `FFI_STATUS_INVALID_UTF16`. `result` may be
+ * NULL when `len` is 0.
+ *
+ * # Safety
+ *
+ * `result` must be valid for reads and writes of `len` code units and hold a NUL-terminated
+ * string within them.
+ */
+enum FfiStatus rxc_cdylib_add_w(int a, int b, uint16_t *result, size_t len);
+
+#endif  /* CDYLIB_GEN_H */
+
+#if defined(_WIN32) && (defined(_M_IX86) || defined(__i386__)) && !defined(CDYLIB_GEN_STDCALL_H)
+#define CDYLIB_GEN_STDCALL_H
+
+/**
+ * A transform applied by `rxc_cdylib_apply_stdcall`, called with the stdcall convention; NULL means
+ * "leave the value unchanged".
+ */
+typedef int (__stdcall *TransformStdcall)(int value);
+
+/**
+ * Returns `a + b`, wrapping around on overflow, with the stdcall convention.
+ */
+int __stdcall rxc_cdylib_add_stdcall(int a, int b);
+
+/**
+ * Like `rxc_cdylib_apply`, with the stdcall convention for both the function and `transform`.
+ */
+int __stdcall rxc_cdylib_apply_stdcall(int value, TransformStdcall transform);
+
+#endif  /* CDYLIB_GEN_STDCALL_H */
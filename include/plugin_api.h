@@ -0,0 +1,51 @@
+// call_libs 插件 ABI 的 C 端定义，必须和 packages/plugin_api/src/lib.rs 保持一致
+// The C definitions of the call_libs plugin ABI, they must match packages/plugin_api/src/lib.rs
+#ifndef PLUGIN_API_H
+#define PLUGIN_API_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#define PLUGIN_API_VERSION 1
+
+#ifdef _WIN32
+#define PLUGIN_EXPORT __declspec(dllexport)
+#else
+#define PLUGIN_EXPORT
+#endif
+
+typedef struct PluginVTable
+{
+    // 插件加载期间必须保持有效
+    // Must stay valid while the plugin is loaded
+    const char *name;
+    void *user_data;
+    int (*compute)(void *user_data, int a, int b, int *out);
+    // 可以为 NULL
+    // May be NULL
+    void (*destroy)(void *user_data);
+} PluginVTable;
+
+// 只在 plugin_register 调用期间有效
+// Only valid during the plugin_register call
+typedef struct PluginHost
+{
+    uint32_t api_version;
+    void *ctx;
+    void (*log)(void *ctx, const char *message);
+    int (*register_vtable)(void *ctx, const PluginVTable *vtable);
+} PluginHost;
+
+// 布局断言，Rust 端有对应的编译期断言
+// Layout assertions, mirrored by compile-time assertions on the Rust side
+_Static_assert(sizeof(PluginHost) == 4 * sizeof(void *), "PluginHost must be 4 pointers");
+_Static_assert(offsetof(PluginHost, ctx) == sizeof(void *), "PluginHost.ctx must follow the version");
+_Static_assert(offsetof(PluginHost, register_vtable) == 3 * sizeof(void *), "PluginHost.register_vtable must be last");
+_Static_assert(sizeof(PluginVTable) == 4 * sizeof(void *), "PluginVTable must be 4 pointers");
+_Static_assert(offsetof(PluginVTable, destroy) == 3 * sizeof(void *), "PluginVTable.destroy must be last");
+
+// 每个插件导出的入口，注册成功返回 0
+// The entry point every plugin exports, returns 0 once registered
+PLUGIN_EXPORT int plugin_register(const PluginHost *host);
+
+#endif
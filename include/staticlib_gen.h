@@ -0,0 +1,364 @@
+#ifndef STATICLIB_GEN_H
+#define STATICLIB_GEN_H
+
+/* Generated by cbindgen from packages/staticlib_gen, do not edit by hand. */
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+#define RUSTLIB_API(name) rxc_##name
+
+/**
+ * The `size` of each version of `InitConfig`: version 1 ends before `install_panic_hook`, version
+ * 2 is the current struct. The Rust constants of the same names can't be evaluated by cbindgen.
+ */
+#define INIT_CONFIG_SIZE_V1 offsetof(InitConfig, install_panic_hook)
+#define INIT_CONFIG_SIZE_V2 sizeof(InitConfig)
+
+/**
+ * A counter C and Rust update together, an `AtomicU32` in Rust with the size and alignment of
+ * `uint32_t`. Only touch it with atomic operations such as `__atomic_fetch_add` or
+ * `InterlockedExchangeAdd`: plain reads and writes race with the other side.
+ */
+extern uint32_t rxc_staticlib_counter;
+
+/**
+ * 导出函数的状态码，C 端看到的是一个 `int` 枚举
+ * Status codes returned by the exported functions, seen as an `int` enum on the C side.
+ */
+typedef enum FfiStatus {
+  /**
+   * The call succeeded and every output was written in full.
+   */
+  FFI_STATUS_OK = 0,
+  /**
+   * A required pointer argument was NULL.
+   */
+  FFI_STATUS_NULL_POINTER = 1,
+  /**
+   * An input string is not valid UTF-8.
+   */
+  FFI_STATUS_INVALID_UTF8 = 2,
+  /**
+   * An input string has no NUL terminator within its buffer length.
+   */
+  FFI_STATUS_UNTERMINATED = 3,
+  /**
+   * An arithmetic result does not fit in its C type.
+   */
+  FFI_STATUS_OVERFLOW = 4,
+  /**
+   * An output string was truncated to fit its buffer.
+   */
+  FFI_STATUS_BUFFER_TOO_SMALL = 5,
+  /**
+   * The Rust code panicked; the message is available as the last error and from
+   * `rxc_rustlib_take_last_panic`.
+   */
+  FFI_STATUS_PANIC = 6,
+  /**
+   * A pointer argument is not properly aligned for its type.
+   */
+  FFI_STATUS_MISALIGNED = 7,
+  /**
+   * A string to be written contains an interior NUL byte.
+   */
+  FFI_STATUS_INTERIOR_NUL = 8,
+  /**
+   * The library has not been initialized, or it was shut down.
+   */
+  FFI_STATUS_NOT_INITIALIZED = 9,
+  /**
+   * The library was initialized a second time without being shut down in between.
+   */
+  FFI_STATUS_ALREADY_INITIALIZED = 10,
+  /**
+   * The call would block waiting for itself, for example when made from one of the library's
+   * own threads.
+   */
+  FFI_STATUS_WOULD_DEADLOCK = 11,
+  /**
+   * The call stopped early because the caller cancelled it, through a cancellation token or by
+   * returning non-zero from a progress callback.
+   */
+  FFI_STATUS_CANCELLED = 12,
+  /**
+   * An allocation's alignment is not a power of two or its size is too large.
+   */
+  FFI_STATUS_INVALID_LAYOUT = 13,
+  /**
+   * The allocator could not provide the requested memory.
+   */
+  FFI_STATUS_OUT_OF_MEMORY = 14,
+  /**
+   * The handle does not refer to a live object: it is null, was already freed, or is a stale
+   * copy of one whose slot now holds another object.
+   */
+  FFI_STATUS_INVALID_HANDLE = 15,
+  /**
+   * An input wide string is not valid UTF-16: it has an unpaired surrogate.
+   */
+  FFI_STATUS_INVALID_UTF16 = 16,
+  /**
+   * A file or other I/O operation failed; the last error has the OS error code.
+   */
+  FFI_STATUS_IO = 17,
+  /**
+   * The input bytes don't form a valid message, such as a header with the wrong magic or
+   * checksum.
+   */
+  FFI_STATUS_MALFORMED = 18,
+  /**
+   * The `size` a struct such as `InitConfig` starts with is not the size of any version of it
+   * the library knows, such as one from a newer header.
+   */
+  FFI_STATUS_UNSUPPORTED_VERSION = 19,
+  /**
+   * An argument is outside the range the function accepts, such as a buffer size of 0, or a
+   * value the call requires was never set.
+   */
+  FFI_STATUS_INVALID_ARGUMENT = 20,
+} FfiStatus;
+
+/**
+ * 日志级别，数值与 `log::Level` 相同
+ * The level of a log message, with the same values as `log::Level`.
+ */
+typedef enum LogLevel {
+  LOG_LEVEL_ERROR = 1,
+  LOG_LEVEL_WARN = 2,
+  LOG_LEVEL_INFO = 3,
+  LOG_LEVEL_DEBUG = 4,
+  LOG_LEVEL_TRACE = 5,
+} LogLevel;
+
+/**
+ * 借用的数组：指针加元素个数
+ * A borrowed array: a pointer and a count of elements.
+ *
+ * `ptr` may be NULL when `len` is 0. In C, `FfiSlice<int>` is `FfiSlice_c_int`.
+ */
+typedef struct FfiSlice_c_int {
+  const int *ptr;
+  size_t len;
+} FfiSlice_c_int;
+
+/**
+ * Receives one log message: `msg` points to `msg_len` bytes of UTF-8 that are not NUL
+ * terminated and only valid during the call; `user_data` is passed through untouched.
+ *
+ * The callback may be invoked from any thread the library logs on, including its own worker
+ * threads. `Option` makes NULL representable, which restores the stderr fallback.
+ */
+typedef void (*LogCallback)(enum LogLevel level, const char *msg, size_t msg_len, void *user_data);
+
+/**
+ * 传给库的 init 函数的配置，size 之外的字段全部为零时得到默认配置
+ * The configuration passed to a library's init function; a zeroed struct with `size` set is the
+ * default configuration.
+ *
+ * Version 1 ends before `install_panic_hook`, version 2 is the whole struct.
+ */
+typedef struct InitConfig {
+  /**
+   * The size of the struct the caller passes, `sizeof(InitConfig)` when compiled against the
+   * current header; anything but [`INIT_CONFIG_SIZE_V1`] or [`INIT_CONFIG_SIZE_V2`] is
+   * rejected with `FFI_STATUS_UNSUPPORTED_VERSION`.
+   */
+  size_t size;
+  /**
+   * Receives the library's log messages as with `rxc_rustlib_set_log_callback`; NULL writes them
+   * to stderr.
+   */
+  LogCallback log_callback;
+  /**
+   * Passed through untouched to `log_callback`, it must stay valid until shutdown.
+   */
+  void *log_user_data;
+  /**
+   * Installs a panic hook that records the location of a panic for
+   * `rxc_rustlib_take_last_panic`; without it only the panic message is available.
+   */
+  bool install_panic_hook;
+  /**
+   * The number of worker threads for asynchronous calls such as `rxc_cdylib_add_async` and
+   * `rxc_async_submit`, 0 picks one. Libraries without asynchronous calls ignore it.
+   */
+  uint32_t worker_threads;
+  /**
+   * Reports the allocations made since init and still outstanding when the library shuts
+   * down, as with `rxc_rustlib_alloc_stats`. Libraries without allocation statistics ignore it.
+   */
+  bool leak_check;
+} InitConfig;
+
+#if (defined(__linux__) || defined(__ANDROID__))
+/**
+ * An operation in the table `rxc_staticlib_ops` returns.
+ */
+typedef struct StaticlibOp {
+  /**
+   * The name of the operation, NUL terminated.
+   */
+  const char *name;
+  /**
+   * Applies the operation to `value`.
+   */
+  int (*apply)(int value);
+} StaticlibOp;
+#endif
+
+/**
+ * Adds `a` and `b`, stores the sum in `sum` and writes a greeting message into `result`.
+ *
+ * `result` is an in/out buffer of `result_len` bytes: on input it holds the caller's
+ * NUL-terminated name, on output the message. Like `snprintf`, the message is truncated to
+ * fit and always NUL terminated; if `required_len` is not NULL it receives the length the full
+ * message needs (excluding the NUL). Passing a NULL `result` with `result_len == 0` only
+ * queries that length.
+ *
+ * Safe to call from several threads at once as long as each call has its own buffers: besides
+ * them it only touches the calling thread's last error.
+ *
+ * # Safety
+ *
+ * `result` must be valid for reads and writes of `result_len` bytes (it may be NULL when
+ * `result_len` is 0), `sum` must be valid for writes and `required_len` must be NULL or
+ * valid for writes.
+ */
+enum FfiStatus rxc_staticlib_add(int a,
+                                 int b,
+                                 char *result,
+                                 size_t result_len,
+                                 int *sum,
+                                 size_t *required_len);
+
+/**
+ * Sums the `len` values at `values` into `total`.
+ *
+ * `values` may be NULL when `len` is 0. Fails with `FFI_STATUS_OVERFLOW` if the sum does not fit
+ * in a `long`, which on Windows is only 32 bits wide.
+ *
+ * # Safety
+ *
+ * `values` must point to `len` readable `int`s and `total` must be valid for writes.
+ */
+enum FfiStatus rxc_staticlib_sum(const int *values, size_t len, long *total);
+
+/**
+ * The same as `rxc_staticlib_sum`, taking the values as one `FfiSlice_c_int`.
+ *
+ * # Safety
+ *
+ * `values` must view `len` readable `int`s and `total` must be valid for writes.
+ */
+enum FfiStatus rxc_staticlib_sum_slice(struct FfiSlice_c_int values, long *total);
+
+/**
+ * Adds `n` to `rxc_staticlib_counter`, wrapping around on overflow, and returns the new value.
+ */
+uint32_t rxc_staticlib_counter_add(uint32_t n);
+
+/**
+ * Returns the buffer size (including the NUL) needed for the calling thread's last error
+ * message, or 0 if no call on this thread has failed yet.
+ */
+size_t rxc_staticlib_last_error_length(void);
+
+/**
+ * Copies the calling thread's last error message into `buf` like `snprintf` does.
+ *
+ * An empty string is written when there is no error. Reading does not clear the error.
+ *
+ * # Safety
+ *
+ * `buf` must be valid for writes of `len` bytes (it may be NULL when `len` is 0).
+ */
+enum FfiStatus rxc_staticlib_last_error_message(char *buf, size_t len);
+
+/**
+ * Copies the message and location of the calling thread's last caught panic into `buf` like
+ * `snprintf` does, and returns the full message length (excluding the NUL).
+ *
+ * Returns 0 when no panic is recorded. The message is only cleared once it was copied in full,
+ * so after a truncated read the call can be repeated with a buffer of the returned length plus
+ * one; a NULL `buf` with `len == 0` only queries the length. Returns -1 if `buf` is NULL while
+ * `len` is not 0.
+ *
+ * # Safety
+ *
+ * `buf` must be valid for writes of `len` bytes (it may be NULL when `len` is 0).
+ */
+int rxc_staticlib_take_last_panic(char *buf, size_t len);
+
+/**
+ * Initializes the library with `config`, or with the default configuration when `config` is
+ * NULL, and must be called before any other export returning a status.
+ *
+ * Sets up logging and, if requested, the panic hook once; Rust's global allocator is fixed when
+ * the library is linked, so there is nothing to set up for it. Until this succeeds the other
+ * exports fail with `FFI_STATUS_NOT_INITIALIZED`. Calling it again before `rxc_staticlib_shutdown`
+ * fails with `FFI_STATUS_ALREADY_INITIALIZED` and keeps the first configuration.
+ *
+ * Only the `size` bytes `config` declares are read, like `rxc_rustlib_init` does.
+ *
+ * # Safety
+ *
+ * `config` must be NULL or point to an `InitConfig` of the version its `size` declares.
+ */
+enum FfiStatus rxc_staticlib_init(const struct InitConfig *config);
+
+/**
+ * Shuts the library down again, after which the other exports fail with
+ * `FFI_STATUS_NOT_INITIALIZED` until `rxc_staticlib_init` is called again.
+ *
+ * Restores writing log messages to stderr. Calls running on other threads at the same time
+ * finish normally, the log callback's `user_data` must stay valid until they return.
+ */
+enum FfiStatus rxc_staticlib_shutdown(void);
+
+/**
+ * Routes the library's log messages to `callback`, which receives each message with its level
+ * and `user_data`; passing NULL restores the default of writing them to stderr.
+ *
+ * The callback may be invoked from any thread the library logs on. `user_data` must stay valid
+ * until another callback is registered.
+ */
+void rxc_staticlib_set_log_callback(LogCallback callback, void *user_data);
+
+#if (defined(__linux__) || defined(__ANDROID__))
+/**
+ * Stores the table of operations linked into the program in `ops` and its length in `len`.
+ *
+ * Which entries the table has depends on how the library was linked: ones referenced by nothing
+ * else, such as `square`, only survive when the whole archive is linked.
+ *
+ * # Safety
+ *
+ * `ops` and `len` must be valid for writes.
+ */
+enum FfiStatus rxc_staticlib_ops(const struct StaticlibOp **ops, size_t *len);
+#endif
+
+/**
+ * Returns `a * b`, following IEEE 754 for infinities, NaN and signed zeros.
+ */
+double rxc_staticlib_mul_f64(double a, double b);
+
+/**
+ * Returns `a * b` in single precision.
+ */
+float rxc_staticlib_mul_f32(float a, float b);
+
+/**
+ * Returns `a + b`, wrapping around on overflow.
+ */
+int64_t rxc_staticlib_add_i64(int64_t a, int64_t b);
+
+/**
+ * Returns the high 64 bits of the 128-bit product `a * b`.
+ */
+uint64_t rxc_staticlib_mul_high_u64(uint64_t a, uint64_t b);
+
+#endif  /* STATICLIB_GEN_H */
@@ -0,0 +1,10 @@
+// 这是我们的构建脚本，编译使用 long double 和 __int128 的 C 代码
+// This is our build script, it compiles the C code using long double and __int128
+
+fn main() {
+    cc::Build::new()
+        .file("c/caveats.c")
+        .std("c11")
+        .compile("caveats");
+    println!("cargo::rerun-if-changed=c");
+}
@@ -0,0 +1,106 @@
+// long double 和 __int128 的 C 端实现，见 caveats.h
+// The C side of long double and __int128, see caveats.h
+#include <float.h>
+#include "caveats.h"
+
+// 类型作为结构体成员时的偏移就是 ABI 规定的对齐
+// The offset of a type as a struct member is the alignment the ABI requires
+#define ABI_ALIGN(type) offsetof(struct { char c; type value; }, value)
+
+size_t c_long_double_size(void)
+{
+    return sizeof(long double);
+}
+
+size_t c_long_double_align(void)
+{
+    return ABI_ALIGN(long double);
+}
+
+int c_long_double_mant_dig(void)
+{
+    return LDBL_MANT_DIG;
+}
+
+long double c_long_double_half(long double x)
+{
+    return x / 2;
+}
+
+void c_long_double_from_f64(double x, long double *out)
+{
+    *out = x;
+}
+
+double c_long_double_to_f64(const long double *x)
+{
+    return (double)*x;
+}
+
+void c_long_double_add(const long double *a, const long double *b, long double *out)
+{
+    *out = *a + *b;
+}
+
+#ifdef __SIZEOF_INT128__
+int c_has_int128(void)
+{
+    return 1;
+}
+
+size_t c_int128_size(void)
+{
+    return sizeof(unsigned __int128);
+}
+
+size_t c_int128_align(void)
+{
+    return ABI_ALIGN(unsigned __int128);
+}
+
+unsigned __int128 c_u128_mul(uint64_t a, uint64_t b)
+{
+    return (unsigned __int128)a * b;
+}
+
+unsigned __int128 c_u128_add(unsigned __int128 a, unsigned __int128 b)
+{
+    return a + b;
+}
+
+void c_u128_add_ptr(const unsigned __int128 *a, const unsigned __int128 *b, unsigned __int128 *out)
+{
+    *out = *a + *b;
+}
+#else
+int c_has_int128(void)
+{
+    return 0;
+}
+
+size_t c_int128_size(void)
+{
+    return 0;
+}
+
+size_t c_int128_align(void)
+{
+    return 0;
+}
+#endif
+
+// 把两个数拆成 32 位的半边，按小学乘法的方式相乘，每一步都放得进 uint64_t
+// Splits both numbers into 32-bit halves and multiplies them the schoolbook way, every step
+// fitting in a uint64_t
+void c_u64_mul_parts(uint64_t a, uint64_t b, uint64_t out[2])
+{
+    uint64_t a_lo = a & 0xFFFFFFFFu, a_hi = a >> 32;
+    uint64_t b_lo = b & 0xFFFFFFFFu, b_hi = b >> 32;
+    uint64_t lo_lo = a_lo * b_lo;
+    uint64_t hi_lo = a_hi * b_lo;
+    uint64_t lo_hi = a_lo * b_hi;
+    uint64_t hi_hi = a_hi * b_hi;
+    uint64_t middle = (lo_lo >> 32) + (hi_lo & 0xFFFFFFFFu) + (lo_hi & 0xFFFFFFFFu);
+    out[0] = (middle << 32) | (lo_lo & 0xFFFFFFFFu);
+    out[1] = hi_hi + (hi_lo >> 32) + (lo_hi >> 32) + (middle >> 32);
+}
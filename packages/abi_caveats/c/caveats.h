@@ -0,0 +1,54 @@
+// Rust 没有对应类型、或者 ABI 因平台而异的两种 C 标量：long double 在 MSVC、Apple 的 arm64 和 32 位 ARM 上
+// 就是 double，在 x86 上是 80 位的扩展精度，在 aarch64 Linux 上是 128 位的四精度；__int128 是 GCC 和
+// Clang 的扩展，MSVC 没有。按值传递只在两边的布局和调用约定确实相同时才可以，其余情况都通过指针传递
+// Two C scalars Rust has no counterpart for, or whose ABI differs between platforms: long double is
+// double on MSVC, Apple's arm64 and 32-bit ARM, 80-bit extended precision on x86 and 128-bit quad
+// precision on aarch64 Linux; __int128 is a GCC and Clang extension MSVC lacks. Passing by value
+// only works where both sides really agree on the layout and calling convention, everywhere else
+// the values go through pointers
+#ifndef CAVEATS_H
+#define CAVEATS_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+// 由 C 实现，Rust 调用
+// Implemented in C, called from Rust
+
+// long double 的大小、ABI 规定的对齐和尾数位数（LDBL_MANT_DIG）
+// The size, ABI alignment and mantissa digits (LDBL_MANT_DIG) of long double
+size_t c_long_double_size(void);
+size_t c_long_double_align(void);
+int c_long_double_mant_dig(void);
+
+// 按值传递，只有 long double 就是 double 的平台上 Rust 才能用 f64 声明它
+// Passed by value, Rust can only declare it with f64 where long double is double
+long double c_long_double_half(long double x);
+
+// 通过指针传递，在所有平台上都可以用；out 指向的内存必须满足 long double 的大小和对齐
+// Passed through pointers, usable on every platform; out must point to memory with the size and
+// alignment of a long double
+void c_long_double_from_f64(double x, long double *out);
+double c_long_double_to_f64(const long double *x);
+void c_long_double_add(const long double *a, const long double *b, long double *out);
+
+// __int128 是否存在，以及它的大小和对齐；不存在时大小和对齐都是 0
+// Whether __int128 exists, and its size and alignment; both are 0 without it
+int c_has_int128(void);
+size_t c_int128_size(void);
+size_t c_int128_align(void);
+
+// 只用 uint64_t 的乘法，out[0] 是低 64 位，out[1] 是高 64 位；MSVC 上也可以用
+// A multiplication using nothing but uint64_t, out[0] is the low 64 bits and out[1] the high 64
+// bits; usable on MSVC too
+void c_u64_mul_parts(uint64_t a, uint64_t b, uint64_t out[2]);
+
+#ifdef __SIZEOF_INT128__
+_Static_assert(sizeof(unsigned __int128) == 16, "__int128 must be 16 bytes");
+
+unsigned __int128 c_u128_mul(uint64_t a, uint64_t b);
+unsigned __int128 c_u128_add(unsigned __int128 a, unsigned __int128 b);
+void c_u128_add_ptr(const unsigned __int128 *a, const unsigned __int128 *b, unsigned __int128 *out);
+#endif
+
+#endif
@@ -0,0 +1,170 @@
+// 这个库记录 Rust 和 C 的 ABI 在两种标量上的分歧：C 的 long double 在 Rust 中没有对应的类型，只有它就是
+// double 的平台上才能用 f64 按值传递，其他平台上 Rust 只能把它当作一块足够大、足够对齐的内存，通过指针
+// 交给 C 计算；__int128 和 Rust 的 u128 在 x86_64 和 aarch64 的 Unix 上布局和传递方式相同（Rust 1.77
+// 起 u128 在 x86_64 上也是 16 字节对齐），Windows 上的约定则彼此不一致，MSVC 甚至没有这个类型，可移植的
+// 做法是拆成两个 u64 传递。每个按值传递的函数都只在确认过两边一致的平台上声明
+// This library records where the Rust and C ABIs part ways on two scalars: C's long double has no
+// Rust counterpart and can only be passed by value, as f64, where it is double, elsewhere Rust can
+// only treat it as a large and aligned enough piece of memory handed to C through pointers to work
+// on; __int128 and Rust's u128 share their layout and way of being passed on x86_64 and aarch64
+// Unix (u128 is 16-byte aligned on x86_64 too since Rust 1.77), while on Windows the conventions
+// disagree and MSVC doesn't even have the type, the portable way being to pass two u64 halves.
+// Every by-value function is only declared on platforms where both sides were confirmed to agree
+
+use std::ops::Add;
+
+extern "C" {
+    fn c_long_double_size() -> usize;
+    fn c_long_double_align() -> usize;
+    fn c_long_double_mant_dig() -> i32;
+    fn c_long_double_from_f64(x: f64, out: *mut LongDouble);
+    fn c_long_double_to_f64(x: *const LongDouble) -> f64;
+    fn c_long_double_add(a: *const LongDouble, b: *const LongDouble, out: *mut LongDouble);
+    fn c_has_int128() -> i32;
+    fn c_int128_size() -> usize;
+    fn c_int128_align() -> usize;
+    fn c_u64_mul_parts(a: u64, b: u64, out: *mut [u64; 2]);
+}
+
+/// 内存布局：大小和 ABI 规定的对齐
+/// A memory layout: the size and the alignment the ABI requires.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Layout {
+    pub size: usize,
+    pub align: usize,
+}
+
+/// The layout of C's `long double` on this target.
+pub fn long_double_layout() -> Layout {
+    unsafe {
+        Layout {
+            size: c_long_double_size(),
+            align: c_long_double_align(),
+        }
+    }
+}
+
+/// The bits of precision of C's `long double`, `LDBL_MANT_DIG`: 53 where it is `double`, 64 for
+/// x87 extended precision and 113 for quad precision.
+pub fn long_double_mantissa_digits() -> u32 {
+    unsafe { c_long_double_mant_dig() as u32 }
+}
+
+/// 由 C 计算的 long double，Rust 只负责保存它
+/// A C `long double` that Rust only stores, leaving all arithmetic to C.
+///
+/// 16 bytes aligned to 16 hold a `long double` on every supported target; Rust never looks at
+/// the bytes, whose format differs between them.
+#[repr(C, align(16))]
+#[derive(Clone, Copy)]
+pub struct LongDouble {
+    bytes: [u8; 16],
+}
+
+// 保存的内存必须容得下任何平台上的 long double
+// The storage must fit the long double of any platform
+const _: () = assert!(std::mem::size_of::<LongDouble>() == 16);
+
+impl LongDouble {
+    pub fn from_f64(x: f64) -> LongDouble {
+        let mut value = LongDouble { bytes: [0; 16] };
+        unsafe { c_long_double_from_f64(x, &mut value) };
+        value
+    }
+
+    /// Rounds the value to the nearest `f64`.
+    pub fn to_f64(self) -> f64 {
+        unsafe { c_long_double_to_f64(&self) }
+    }
+}
+
+impl Add for LongDouble {
+    type Output = LongDouble;
+
+    fn add(self, rhs: LongDouble) -> LongDouble {
+        let mut sum = LongDouble { bytes: [0; 16] };
+        unsafe { c_long_double_add(&self, &rhs, &mut sum) };
+        sum
+    }
+}
+
+// long double 就是 double 的平台：MSVC、Apple 的 arm64 和 32 位 ARM
+// Platforms where long double is double: MSVC, Apple's arm64 and 32-bit ARM
+#[cfg(any(
+    all(windows, target_env = "msvc"),
+    all(target_vendor = "apple", target_arch = "aarch64"),
+    target_arch = "arm"
+))]
+mod long_double_by_value {
+    extern "C" {
+        pub fn c_long_double_half(x: f64) -> f64;
+    }
+}
+
+/// Halves `x` in C, passing a `long double` by value as `f64`, which is only correct where
+/// `long double` is `double`.
+#[cfg(any(
+    all(windows, target_env = "msvc"),
+    all(target_vendor = "apple", target_arch = "aarch64"),
+    target_arch = "arm"
+))]
+pub fn long_double_half(x: f64) -> f64 {
+    unsafe { long_double_by_value::c_long_double_half(x) }
+}
+
+/// The layout of C's `unsigned __int128`, or `None` when the C compiler doesn't have it.
+pub fn int128_layout() -> Option<Layout> {
+    unsafe {
+        (c_has_int128() != 0).then(|| Layout {
+            size: c_int128_size(),
+            align: c_int128_align(),
+        })
+    }
+}
+
+/// Multiplies `a` and `b` into 128 bits in C using only `uint64_t`, the portable workaround that
+/// works with every C compiler.
+pub fn mul_wide(a: u64, b: u64) -> u128 {
+    let mut parts = [0; 2];
+    unsafe { c_u64_mul_parts(a, b, &mut parts) };
+    (parts[1] as u128) << 64 | parts[0] as u128
+}
+
+// __int128 按值传递与 u128 一致的平台：x86_64 和 aarch64 上的 Unix
+// Platforms where __int128 is passed by value the same way as u128: Unix on x86_64 and aarch64
+#[cfg(all(unix, any(target_arch = "x86_64", target_arch = "aarch64")))]
+mod int128_by_value {
+    extern "C" {
+        pub fn c_u128_mul(a: u64, b: u64) -> u128;
+        pub fn c_u128_add(a: u128, b: u128) -> u128;
+    }
+}
+
+/// Multiplies `a` and `b` in C, which returns an `unsigned __int128` by value.
+#[cfg(all(unix, any(target_arch = "x86_64", target_arch = "aarch64")))]
+pub fn mul_by_value(a: u64, b: u64) -> u128 {
+    unsafe { int128_by_value::c_u128_mul(a, b) }
+}
+
+/// Adds `a` and `b` in C, passing and returning `unsigned __int128` by value, wrapping around on
+/// overflow.
+#[cfg(all(unix, any(target_arch = "x86_64", target_arch = "aarch64")))]
+pub fn add_by_value(a: u128, b: u128) -> u128 {
+    unsafe { int128_by_value::c_u128_add(a, b) }
+}
+
+// 只要 C 有 __int128，通过指针传递就可以用：两边只需要在布局上一致
+// Passing through pointers works wherever C has __int128: both sides only need to agree on the
+// layout
+#[cfg(not(target_env = "msvc"))]
+extern "C" {
+    fn c_u128_add_ptr(a: *const u128, b: *const u128, out: *mut u128);
+}
+
+/// Adds `a` and `b` in C through pointers to `unsigned __int128`, wrapping around on overflow.
+#[cfg(not(target_env = "msvc"))]
+pub fn add_by_pointer(a: u128, b: u128) -> u128 {
+    let mut sum = 0;
+    unsafe { c_u128_add_ptr(&a, &b, &mut sum) };
+    sum
+}
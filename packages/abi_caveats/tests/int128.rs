@@ -0,0 +1,64 @@
+// __int128 的测试：C 有这个类型时它和 u128 的布局一致；拆成两个 u64 的乘法在所有平台上都可以用；按值传递
+// 只在 x86_64 和 aarch64 的 Unix 上测试，通过指针传递在 C 有 __int128 的每个平台上测试
+// Tests for __int128: its layout matches u128 where C has the type; the multiplication split into
+// two u64 halves works on every platform; passing by value is only tested on Unix on x86_64 and
+// aarch64, passing through pointers on every platform where C has __int128
+
+use abi_caveats::{int128_layout, mul_wide, Layout};
+
+#[test]
+fn matches_the_layout_of_u128() {
+    let rust = Layout {
+        size: std::mem::size_of::<u128>(),
+        align: std::mem::align_of::<u128>(),
+    };
+    // MSVC 没有 __int128，其他编译器都有
+    // MSVC has no __int128, every other compiler does
+    if cfg!(target_env = "msvc") {
+        assert_eq!(int128_layout(), None);
+    } else {
+        assert_eq!(int128_layout(), Some(rust));
+    }
+}
+
+#[test]
+fn multiplies_through_u64_parts() {
+    assert_eq!(mul_wide(0, u64::MAX), 0);
+    assert_eq!(mul_wide(3, 7), 21);
+    assert_eq!(
+        mul_wide(u64::MAX, u64::MAX),
+        u64::MAX as u128 * u64::MAX as u128
+    );
+    assert_eq!(mul_wide(1 << 63, 4), 1 << 65);
+    assert_eq!(
+        mul_wide(0x1234_5678_9abc_def0, 0x0fed_cba9_8765_4321),
+        0x1234_5678_9abc_def0u128 * 0x0fed_cba9_8765_4321
+    );
+}
+
+#[cfg(all(unix, any(target_arch = "x86_64", target_arch = "aarch64")))]
+#[test]
+fn passes_by_value_on_64_bit_unix() {
+    use abi_caveats::{add_by_value, mul_by_value};
+
+    assert_eq!(
+        mul_by_value(u64::MAX, u64::MAX),
+        mul_wide(u64::MAX, u64::MAX)
+    );
+    assert_eq!(mul_by_value(1 << 40, 1 << 40), 1 << 80);
+    let a = 0x0123_4567_89ab_cdef_fedc_ba98_7654_3210;
+    let b = (u64::MAX as u128) + 1;
+    assert_eq!(add_by_value(a, b), a + b);
+    assert_eq!(add_by_value(u128::MAX, 2), 1);
+}
+
+#[cfg(not(target_env = "msvc"))]
+#[test]
+fn passes_through_pointers() {
+    use abi_caveats::add_by_pointer;
+
+    let a = u128::MAX / 3;
+    assert_eq!(add_by_pointer(a, a), 2 * a);
+    assert_eq!(add_by_pointer(u128::MAX, 1), 0);
+    assert_eq!(add_by_pointer(1 << 64, 1 << 64), 1 << 65);
+}
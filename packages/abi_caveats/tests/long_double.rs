@@ -0,0 +1,90 @@
+// long double 的测试：逐个平台断言它的布局和精度，记录哪里可以用 f64 按值传递；通过指针传递的运算在所有
+// 平台上都可以用，精度比 double 高的平台上还能保留 f64 会丢掉的低位
+// Tests for long double: its layout and precision are asserted platform by platform, recording
+// where it can be passed by value as f64; the arithmetic passed through pointers works on every
+// platform, and keeps the low bits f64 would lose where the precision is higher than double's
+
+use abi_caveats::{long_double_layout, long_double_mantissa_digits, Layout, LongDouble};
+
+// x87 的 80 位扩展精度，补齐到 16 字节
+// x87's 80-bit extended precision, padded to 16 bytes
+#[cfg(all(target_arch = "x86_64", not(target_env = "msvc")))]
+#[test]
+fn is_x87_extended_precision_on_x86_64() {
+    assert_eq!(
+        long_double_layout(),
+        Layout {
+            size: 16,
+            align: 16
+        }
+    );
+    assert_eq!(long_double_mantissa_digits(), 64);
+}
+
+// aarch64 的 Linux 上是 IEEE 四精度，Rust 还没有稳定的 f128
+// IEEE quad precision on aarch64 Linux, which Rust has no stable f128 for yet
+#[cfg(all(target_arch = "aarch64", target_os = "linux"))]
+#[test]
+fn is_quad_precision_on_aarch64_linux() {
+    assert_eq!(
+        long_double_layout(),
+        Layout {
+            size: 16,
+            align: 16
+        }
+    );
+    assert_eq!(long_double_mantissa_digits(), 113);
+}
+
+#[cfg(any(
+    all(windows, target_env = "msvc"),
+    all(target_vendor = "apple", target_arch = "aarch64")
+))]
+#[test]
+fn is_double_on_msvc_and_apple_arm64() {
+    assert_eq!(long_double_layout(), Layout { size: 8, align: 8 });
+    assert_eq!(long_double_mantissa_digits(), 53);
+}
+
+#[test]
+fn fits_the_rust_storage() {
+    let layout = long_double_layout();
+    assert!(layout.size <= std::mem::size_of::<LongDouble>());
+    assert!(layout.align <= std::mem::align_of::<LongDouble>());
+}
+
+#[test]
+fn round_trips_through_pointers() {
+    for x in [0.0, -1.5, 1e300, f64::MIN_POSITIVE, 0.1] {
+        assert_eq!(LongDouble::from_f64(x).to_f64(), x);
+    }
+    let sum = LongDouble::from_f64(1.5) + LongDouble::from_f64(2.25);
+    assert_eq!(sum.to_f64(), 3.75);
+}
+
+// 1 + 2^-60 在 double 中舍入为 1，只有尾数至少 61 位时减去 1 之后才留下 2^-60
+// 1 + 2^-60 rounds to 1 in a double, and only leaves 2^-60 after subtracting 1 when the mantissa
+// has at least 61 digits
+#[test]
+fn keeps_the_precision_of_the_platform() {
+    let tiny = 2f64.powi(-60);
+    let difference =
+        (LongDouble::from_f64(1.0) + LongDouble::from_f64(tiny)) + LongDouble::from_f64(-1.0);
+    let expected = if long_double_mantissa_digits() >= 61 {
+        tiny
+    } else {
+        0.0
+    };
+    assert_eq!(difference.to_f64(), expected);
+}
+
+#[cfg(any(
+    all(windows, target_env = "msvc"),
+    all(target_vendor = "apple", target_arch = "aarch64"),
+    target_arch = "arm"
+))]
+#[test]
+fn passes_by_value_where_it_is_double() {
+    assert_eq!(abi_caveats::long_double_half(3.0), 1.5);
+    assert_eq!(abi_caveats::long_double_half(-0.5), -0.25);
+}
@@ -0,0 +1,15 @@
+// 这是我们的构建脚本，编译用 C11 原子操作和 Rust 在同一块内存上交替的 C 代码
+// This is our build script, it compiles the C code taking turns with Rust on the same memory with
+// C11 atomics
+
+fn main() {
+    let mut build = cc::Build::new();
+    build.file("c/ping_pong.c").std("c11");
+    // MSVC 的 <stdatomic.h> 还需要打开这个开关
+    // MSVC's <stdatomic.h> also needs this switch
+    if build.get_compiler().is_like_msvc() {
+        build.flag("/experimental:c11atomics");
+    }
+    build.compile("ping_pong");
+    println!("cargo::rerun-if-changed=c");
+}
@@ -0,0 +1,66 @@
+// PingPong 的 C 一侧：C 分配的表，以及在轮到 C 时用 acquire 读取 turn、用 release 交还它的 pong
+// The C side of PingPong: tables C allocates, and the pong that reads turn with acquire when it is
+// C's turn and hands it back with release
+#include <stdlib.h>
+#include "ping_pong.h"
+
+#ifdef _WIN32
+#include <windows.h>
+#else
+#include <sched.h>
+#endif
+
+PingPong *c_ping_pong_new(void)
+{
+    PingPong *table = malloc(sizeof(PingPong));
+    if (table != NULL)
+    {
+        atomic_init(&table->turn, 0);
+        table->payload = 0;
+    }
+    return table;
+}
+
+void c_ping_pong_free(PingPong *table)
+{
+    free(table);
+}
+
+// 有锁的 _Atomic 由 C 运行时里的锁保护，Rust 的原子操作不会去拿那把锁，两边就不再互斥
+// A lock-based _Atomic is guarded by a lock in the C runtime, which Rust's atomics never take, so
+// the two sides would no longer exclude each other
+bool c_ping_pong_lock_free(const PingPong *table)
+{
+    return atomic_is_lock_free(&table->turn);
+}
+
+// 玩 rounds 轮奇数的回合：等 turn 变成 t，检查 Rust 在上一回合写入的 payload 是 t，写入 t + 1，再把 turn
+// 交给 Rust。acquire 保证能看到 Rust 在 release 之前写入的 payload，release 让 Rust 看到这里写入的值。
+// 返回 payload 不符的次数
+// Plays rounds odd turns: waits for turn to become t, checks the payload Rust wrote on the previous
+// turn is t, writes t + 1 and hands turn to Rust. The acquire guarantees seeing the payload Rust
+// wrote before its release, and the release lets Rust see the value written here. Returns how
+// many times the payload was off
+uint32_t c_pong(PingPong *table, uint32_t rounds)
+{
+    uint32_t mismatches = 0;
+    for (uint32_t round = 0; round < rounds; round++)
+    {
+        uint32_t turn = 2 * round + 1;
+        while (atomic_load_explicit(&table->turn, memory_order_acquire) != turn)
+        {
+#ifdef _WIN32
+            SwitchToThread();
+#else
+            sched_yield();
+#endif
+        }
+        if (table->payload != turn)
+        {
+            mismatches++;
+        }
+        table->payload = turn + 1;
+        atomic_store_explicit(&table->turn, turn + 1, memory_order_release);
+    }
+    return mismatches;
+}
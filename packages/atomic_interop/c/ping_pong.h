@@ -0,0 +1,36 @@
+// 交替使用的 PingPong：turn 是两边都用原子操作访问的 32 位计数，payload 是普通的整数，只在轮到自己时读写。
+// 必须和 src/lib.rs 中的 #[repr(C)] struct PingPong 保持一致
+// The PingPong two sides take turns on: turn is a 32-bit count both access with atomics, payload a
+// plain integer only read and written on one's own turn. It must match the #[repr(C)] struct
+// PingPong in src/lib.rs
+#ifndef PING_PONG_H
+#define PING_PONG_H
+
+#include <stdatomic.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+typedef struct PingPong
+{
+    _Atomic uint32_t turn;
+    uint32_t payload;
+} PingPong;
+
+// 布局断言，Rust 端有对应的编译期断言；_Atomic 类型只有在无锁时才和 Rust 的 AtomicU32 兼容，见
+// c_ping_pong_lock_free
+// Layout assertions, mirrored by compile-time assertions on the Rust side; an _Atomic type is only
+// compatible with Rust's AtomicU32 when it is lock-free, see c_ping_pong_lock_free
+_Static_assert(sizeof(_Atomic uint32_t) == 4, "_Atomic uint32_t must be 4 bytes");
+_Static_assert(_Alignof(_Atomic uint32_t) == 4, "_Atomic uint32_t must be 4-byte aligned");
+_Static_assert(sizeof(PingPong) == 8, "PingPong must be 8 bytes");
+_Static_assert(offsetof(PingPong, payload) == 4, "PingPong.payload must be at offset 4");
+
+// 由 C 实现，Rust 调用
+// Implemented in C, called from Rust
+PingPong *c_ping_pong_new(void);
+void c_ping_pong_free(PingPong *table);
+bool c_ping_pong_lock_free(const PingPong *table);
+uint32_t c_pong(PingPong *table, uint32_t rounds);
+
+#endif
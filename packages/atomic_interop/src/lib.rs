@@ -0,0 +1,133 @@
+// 这个库演示 Rust 的 AtomicU32 和 C11 的 _Atomic uint32_t 作用于同一块内存：两者大小和对齐相同，
+// 只要 C 的原子类型是无锁的，两边的原子操作、以及 acquire 和 release 建立的先行关系就互相成立。
+// PingPong 可以由 Rust 分配、把指针交给 C，也可以由 C 分配、把指针交给 Rust；两个线程轮流执行，
+// 一个运行 Rust 的代码，一个运行 C 的代码，靠 turn 上的 acquire/release 保护普通的 payload
+// This library demonstrates Rust's AtomicU32 and C11's _Atomic uint32_t working on the same memory:
+// the two have the same size and alignment, and as long as C's atomic type is lock-free the
+// atomic operations of both sides, and the happens-before relations acquire and release
+// establish, hold across them. A PingPong can be allocated by Rust with the pointer handed to C,
+// or by C with the pointer handed to Rust; two threads take turns, one running Rust code and one
+// running C code, and the acquire/release on turn protects the plain payload
+
+use std::cell::UnsafeCell;
+use std::mem;
+use std::ops::Deref;
+use std::ptr::NonNull;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::thread;
+
+/// Shared turn-taking state laid out exactly like `struct PingPong` in c/ping_pong.h.
+///
+/// `turn` is even when it's Rust's turn and odd when it's C's; `payload` may only be touched by
+/// the side whose turn it is.
+#[repr(C)]
+#[derive(Debug, Default)]
+pub struct PingPong {
+    turn: AtomicU32,
+    payload: UnsafeCell<u32>,
+}
+
+// payload 只在持有回合时访问，回合的交接由 turn 上的 acquire/release 同步
+// payload is only accessed while holding the turn, and handing the turn over is synchronized by
+// the acquire/release on turn
+unsafe impl Sync for PingPong {}
+
+// 布局断言，C 端的 ping_pong.h 中有对应的 _Static_assert
+// Layout assertions, mirrored by the _Static_asserts in ping_pong.h
+const _: () = assert!(mem::size_of::<AtomicU32>() == 4);
+const _: () = assert!(mem::align_of::<AtomicU32>() == 4);
+const _: () = assert!(mem::size_of::<PingPong>() == 8);
+const _: () = assert!(mem::offset_of!(PingPong, payload) == 4);
+
+extern "C" {
+    fn c_ping_pong_new() -> *mut PingPong;
+    fn c_ping_pong_free(table: *mut PingPong);
+    fn c_ping_pong_lock_free(table: *const PingPong) -> bool;
+    fn c_pong(table: *const PingPong, rounds: u32) -> u32;
+}
+
+impl PingPong {
+    /// A table at turn 0, Rust's, with a payload of 0.
+    pub fn new() -> PingPong {
+        PingPong::default()
+    }
+
+    /// How many turns were taken so far.
+    pub fn turn(&self) -> u32 {
+        self.turn.load(Ordering::Acquire)
+    }
+
+    /// Whether C's `_Atomic uint32_t` is lock-free on this table, which sharing it with Rust
+    /// requires.
+    pub fn lock_free_in_c(&self) -> bool {
+        unsafe { c_ping_pong_lock_free(self) }
+    }
+
+    /// Plays `rounds` even turns in Rust, the counterpart to `c_pong`, and returns how many times
+    /// the payload C left was off.
+    pub fn ping(&self, rounds: u32) -> u32 {
+        let mut mismatches = 0;
+        for round in 0..rounds {
+            let turn = 2 * round;
+            while self.turn.load(Ordering::Acquire) != turn {
+                thread::yield_now();
+            }
+            // SAFETY: it's Rust's turn, C doesn't touch payload until the release below
+            unsafe {
+                if *self.payload.get() != turn {
+                    mismatches += 1;
+                }
+                *self.payload.get() = turn + 1;
+            }
+            self.turn.store(turn + 1, Ordering::Release);
+        }
+        mismatches
+    }
+
+    /// Plays `rounds` odd turns in C, see [`PingPong::ping`].
+    pub fn pong_in_c(&self, rounds: u32) -> u32 {
+        unsafe { c_pong(self, rounds) }
+    }
+
+    /// Plays `rounds` rounds with [`PingPong::ping`] on this thread and `c_pong` on another,
+    /// returning the mismatches each side saw.
+    pub fn play(&self, rounds: u32) -> (u32, u32) {
+        thread::scope(|scope| {
+            let c = scope.spawn(|| self.pong_in_c(rounds));
+            let rust = self.ping(rounds);
+            (rust, c.join().unwrap())
+        })
+    }
+}
+
+/// A [`PingPong`] allocated by C with `malloc` and freed by C when dropped.
+pub struct CPingPong {
+    table: NonNull<PingPong>,
+}
+
+// C 分配的表和 Rust 分配的一样可以在线程间共享
+// A table C allocated can be shared between threads just like one Rust allocated
+unsafe impl Send for CPingPong {}
+unsafe impl Sync for CPingPong {}
+
+impl CPingPong {
+    /// Has C allocate a table at turn 0, or returns `None` when `malloc` fails.
+    pub fn new() -> Option<CPingPong> {
+        NonNull::new(unsafe { c_ping_pong_new() }).map(|table| CPingPong { table })
+    }
+}
+
+impl Deref for CPingPong {
+    type Target = PingPong;
+
+    fn deref(&self) -> &PingPong {
+        // SAFETY: C initialized the table, and it lives until drop
+        unsafe { self.table.as_ref() }
+    }
+}
+
+impl Drop for CPingPong {
+    fn drop(&mut self) {
+        unsafe { c_ping_pong_free(self.table.as_ptr()) }
+    }
+}
@@ -0,0 +1,43 @@
+// Rust 的 AtomicU32 和 C 的 _Atomic uint32_t 在同一块内存上轮流的测试：无论表由哪一边分配，两个线程都
+// 按顺序交接回合，每次都看到对方在交接之前写入的 payload
+// Tests for Rust's AtomicU32 and C's _Atomic uint32_t taking turns on the same memory: whichever
+// side allocated the table, the two threads hand the turn over in order and always see the
+// payload the other wrote before handing it over
+
+use atomic_interop::{CPingPong, PingPong};
+
+const ROUNDS: u32 = 10_000;
+
+#[test]
+fn c_atomics_are_lock_free() {
+    assert!(PingPong::new().lock_free_in_c());
+    assert!(CPingPong::new().unwrap().lock_free_in_c());
+}
+
+#[test]
+fn rust_allocated_table() {
+    let table = Box::new(PingPong::new());
+    assert_eq!(table.play(ROUNDS), (0, 0));
+    assert_eq!(table.turn(), 2 * ROUNDS);
+}
+
+#[test]
+fn c_allocated_table() {
+    let table = CPingPong::new().unwrap();
+    assert_eq!(table.play(ROUNDS), (0, 0));
+    assert_eq!(table.turn(), 2 * ROUNDS);
+}
+
+// 打乱顺序的表：C 等的回合永远不会到来之前，Rust 一回合也拿不到
+// A table played out of order: Rust can't get a single turn before the one C waits for arrives
+#[test]
+fn turns_are_taken_in_order() {
+    let table = PingPong::new();
+    std::thread::scope(|scope| {
+        let c = scope.spawn(|| table.pong_in_c(1));
+        assert_eq!(table.turn(), 0);
+        assert_eq!(table.ping(1), 0);
+        assert_eq!(c.join().unwrap(), 0);
+    });
+    assert_eq!(table.turn(), 2);
+}
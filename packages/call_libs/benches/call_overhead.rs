@@ -0,0 +1,187 @@
+// 测量同一个求和操作经由不同链接方式调用时每次调用的开销，以及把多次调用合并成一次批量调用的效果
+// Measures the per-call overhead of the same sum reached through each way of linking, and the
+// effect of folding many calls into one batched call
+//
+// 运行 / Run: cargo bench -p call_libs --bench call_overhead
+
+use std::ffi::{c_int, c_long, c_void};
+use std::hint::black_box;
+use std::ptr;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use libloading::{Library, Symbol};
+
+// build.rs 用 cc 编译的 c/clib.c，通过 bindgen 生成的绑定调用
+// c/clib.c, compiled by build.rs with cc and called through the bindings bindgen generated
+#[path = "../src/clib.rs"]
+mod clib;
+
+use clib::clib_sum;
+
+extern "C" {
+    // 静态链接的 staticlib_gen
+    // The statically linked staticlib_gen
+    fn rxc_staticlib_sum(values: *const c_int, len: usize, total: *mut c_long) -> c_int;
+    // 动态链接的 cdylib_gen
+    // The dynamically linked cdylib_gen
+    fn rxc_cdylib_sum(values: *const c_int, len: usize, total: *mut c_long) -> c_int;
+    // NULL 配置即默认配置
+    // A NULL config is the default configuration
+    fn rxc_rustlib_init(config: *const c_void) -> c_int;
+    fn rxc_staticlib_init(config: *const c_void) -> c_int;
+}
+
+type DyloadingSum = unsafe extern "C" fn(values: *const i32, len: usize, total: *mut i64) -> i32;
+
+#[cfg(target_os = "macos")]
+const EXTERNAL_DY: &str = "libexternal_dy.dylib";
+#[cfg(windows)]
+const EXTERNAL_DY: &str = "external_dy.dll";
+#[cfg(not(any(target_os = "macos", windows)))]
+const EXTERNAL_DY: &str = "libexternal_dy.so";
+
+const BATCH: usize = 1024;
+const STATUS_OK: c_int = 0;
+const STATUS_ALREADY_INITIALIZED: c_int = 10;
+
+// 每个基准组都先初始化两个 Rust 库，第二次初始化返回 ALREADY_INITIALIZED
+// Each benchmark group initializes both Rust libraries first, the second time returns
+// ALREADY_INITIALIZED
+fn init_libraries() {
+    for status in unsafe {
+        [
+            rxc_rustlib_init(ptr::null()),
+            rxc_staticlib_init(ptr::null()),
+        ]
+    } {
+        assert!(matches!(status, STATUS_OK | STATUS_ALREADY_INITIALIZED));
+    }
+}
+
+fn open_external_dy() -> Library {
+    let path = std::path::Path::new(env!("OUT_DIR")).join(EXTERNAL_DY);
+    unsafe { Library::new(&path) }.unwrap_or_else(|e| panic!("{}: {}", path.display(), e))
+}
+
+// Rust 内联求和作为基线，其余结果减去它就是跨越 FFI 边界的开销
+// An inlined Rust sum is the baseline, subtracting it from the others leaves the cost of crossing
+// the FFI boundary
+fn rust_sum(values: &[i32]) -> i64 {
+    values.iter().map(|&v| i64::from(v)).sum()
+}
+
+fn per_call(c: &mut Criterion) {
+    init_libraries();
+    let lib = open_external_dy();
+    // 符号只查找一次，循环里通过 Symbol 的函数指针调用
+    // The symbol is looked up once, the loop calls through the Symbol's function pointer
+    let dyloading_sum: Symbol<DyloadingSum> = unsafe { lib.get(b"dyloading_sum\0") }.unwrap();
+    let values = [20, 22];
+
+    let mut group = c.benchmark_group("per_call");
+    group.bench_function("rust_inline", |b| b.iter(|| rust_sum(black_box(&values))));
+    group.bench_function("c_source", |b| {
+        b.iter(|| {
+            let mut total = 0;
+            unsafe { clib_sum(black_box(values.as_ptr()), values.len(), &mut total) };
+            total
+        })
+    });
+    group.bench_function("staticlib", |b| {
+        b.iter(|| {
+            let mut total = 0;
+            unsafe { rxc_staticlib_sum(black_box(values.as_ptr()), values.len(), &mut total) };
+            total
+        })
+    });
+    group.bench_function("cdylib", |b| {
+        b.iter(|| {
+            let mut total = 0;
+            unsafe { rxc_cdylib_sum(black_box(values.as_ptr()), values.len(), &mut total) };
+            total
+        })
+    });
+    group.bench_function("dlopen_symbol", |b| {
+        b.iter(|| {
+            let mut total = 0;
+            unsafe { dyloading_sum(black_box(values.as_ptr()), values.len(), &mut total) };
+            total
+        })
+    });
+    // 每次调用都重新查找符号，展示为什么要缓存 Symbol
+    // Looks the symbol up on every call, showing why the Symbol should be cached
+    group.bench_function("dlopen_lookup_per_call", |b| {
+        b.iter(|| {
+            let mut total = 0;
+            unsafe {
+                let sum: Symbol<DyloadingSum> = lib.get(b"dyloading_sum\0").unwrap();
+                sum(black_box(values.as_ptr()), values.len(), &mut total)
+            };
+            total
+        })
+    });
+    group.finish();
+}
+
+// 同样的 BATCH 个值，逐个调用 BATCH 次与一次传入整个切片的对比
+// The same BATCH values, summed with BATCH single-value calls versus one call over the whole slice
+fn batched(c: &mut Criterion) {
+    init_libraries();
+    let lib = open_external_dy();
+    let dyloading_sum: Symbol<DyloadingSum> = unsafe { lib.get(b"dyloading_sum\0") }.unwrap();
+    let values: Vec<i32> = (0..BATCH as i32).collect();
+    let c_values: Vec<c_int> = values.iter().map(|&v| v as c_int).collect();
+
+    let mut group = c.benchmark_group("batched");
+    group.throughput(Throughput::Elements(BATCH as u64));
+    for (name, f) in [
+        ("c_source", clib_sum as DyloadingSum),
+        ("dlopen_symbol", *dyloading_sum),
+    ] {
+        bench_batched(&mut group, name, &values, call_i32(f));
+    }
+    for (name, f) in [
+        ("staticlib", rxc_staticlib_sum as RustSum),
+        ("cdylib", rxc_cdylib_sum),
+    ] {
+        bench_batched(&mut group, name, &c_values, call_c_long(f));
+    }
+    group.finish();
+}
+
+type RustSum = unsafe extern "C" fn(*const c_int, usize, *mut c_long) -> c_int;
+
+fn call_i32(f: DyloadingSum) -> impl Fn(&[i32]) -> i64 {
+    move |values| {
+        let mut total = 0;
+        unsafe { f(black_box(values.as_ptr()), values.len(), &mut total) };
+        total
+    }
+}
+
+fn call_c_long(f: RustSum) -> impl Fn(&[c_int]) -> c_long {
+    move |values| {
+        let mut total = 0;
+        unsafe { f(black_box(values.as_ptr()), values.len(), &mut total) };
+        total
+    }
+}
+
+fn bench_batched<T, S: std::iter::Sum<S>>(
+    group: &mut criterion::BenchmarkGroup<'_, criterion::measurement::WallTime>,
+    name: &str,
+    values: &[T],
+    sum: impl Fn(&[T]) -> S,
+) {
+    group.bench_with_input(
+        BenchmarkId::new("single_calls", name),
+        values,
+        |b, values| b.iter(|| values.chunks(1).map(&sum).sum::<S>()),
+    );
+    group.bench_with_input(BenchmarkId::new("one_call", name), values, |b, values| {
+        b.iter(|| sum(values))
+    });
+}
+
+criterion_group!(benches, per_call, batched);
+criterion_main!(benches);
@@ -0,0 +1,107 @@
+/* automatically generated by rust-bindgen 0.72.1 */
+
+unsafe extern "C" {
+    pub fn add(a: i32, b: i32, result: *mut ::std::os::raw::c_char) -> i32;
+}
+unsafe extern "C" {
+    pub fn clib_sum(values: *const i32, len: usize, total: *mut i64) -> i32;
+}
+unsafe extern "C" {
+    pub fn clib_staticlib_counter_add(n: u32) -> u32;
+}
+unsafe extern "C" {
+    pub fn clib_staticlib_counter_get() -> u32;
+}
+unsafe extern "C" {
+    pub static mut clib_counter: u32;
+}
+unsafe extern "C" {
+    pub fn clib_counter_add(n: u32) -> u32;
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct ClibLockedCounter {
+    _unused: [u8; 0],
+}
+unsafe extern "C" {
+    pub fn clib_locked_counter_new() -> *mut ClibLockedCounter;
+}
+unsafe extern "C" {
+    pub fn clib_locked_counter_free(counter: *mut ClibLockedCounter);
+}
+unsafe extern "C" {
+    pub fn clib_locked_counter_lock(counter: *mut ClibLockedCounter)
+        -> *mut ::std::os::raw::c_void;
+}
+unsafe extern "C" {
+    pub fn clib_locked_counter_value(counter: *mut ClibLockedCounter) -> *mut u64;
+}
+unsafe extern "C" {
+    pub fn clib_locked_counter_start(counter: *mut ClibLockedCounter, rounds: u32) -> i32;
+}
+unsafe extern "C" {
+    pub fn clib_locked_counter_join(counter: *mut ClibLockedCounter);
+}
+pub type ClibPushFn =
+    ::std::option::Option<unsafe extern "C" fn(ctx: *mut ::std::os::raw::c_void, work: u64)>;
+pub type ClibCloseFn =
+    ::std::option::Option<unsafe extern "C" fn(ctx: *mut ::std::os::raw::c_void)>;
+unsafe extern "C" {
+    pub fn clib_spawn_producer(
+        count: u32,
+        interval_ms: u32,
+        push: ClibPushFn,
+        close: ClibCloseFn,
+        ctx: *mut ::std::os::raw::c_void,
+    ) -> i32;
+}
+#[repr(C)]
+#[repr(align(4))]
+#[derive(Debug, Copy, Clone)]
+pub struct ClibStatus {
+    pub _bindgen_opaque_blob: u32,
+}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of ClibStatus"][::std::mem::size_of::<ClibStatus>() - 4usize];
+    ["Alignment of ClibStatus"][::std::mem::align_of::<ClibStatus>() - 4usize];
+};
+unsafe extern "C" {
+    pub fn clib_status_get_ready(status: *const ClibStatus) -> u32;
+}
+unsafe extern "C" {
+    pub fn clib_status_set_ready(status: *mut ClibStatus, value: u32) -> i32;
+}
+unsafe extern "C" {
+    pub fn clib_status_get_error(status: *const ClibStatus) -> u32;
+}
+unsafe extern "C" {
+    pub fn clib_status_set_error(status: *mut ClibStatus, value: u32) -> i32;
+}
+unsafe extern "C" {
+    pub fn clib_status_get_mode(status: *const ClibStatus) -> u32;
+}
+unsafe extern "C" {
+    pub fn clib_status_set_mode(status: *mut ClibStatus, value: u32) -> i32;
+}
+unsafe extern "C" {
+    pub fn clib_status_get_channel(status: *const ClibStatus) -> u32;
+}
+unsafe extern "C" {
+    pub fn clib_status_set_channel(status: *mut ClibStatus, value: u32) -> i32;
+}
+unsafe extern "C" {
+    pub fn clib_status_get_count(status: *const ClibStatus) -> u32;
+}
+unsafe extern "C" {
+    pub fn clib_status_set_count(status: *mut ClibStatus, value: u32) -> i32;
+}
+unsafe extern "C" {
+    pub fn clib_status_size() -> usize;
+}
+unsafe extern "C" {
+    pub fn clib_status_align() -> usize;
+}
+unsafe extern "C" {
+    pub fn clib_status_example(out: *mut ClibStatus);
+}
@@ -3,18 +3,575 @@
 
 extern crate cc;
 
+// sanitize feature 打开时 C 代码的插桩参数。运行时来自可执行文件：-Zsanitizer=address 把包含 UBSan
+// 处理函数的 ASan 运行时链接进 call_libs，gcc 则把自己的运行时链接进各个 consumer 程序
+// Instrumentation flags for the C code when the sanitize feature is on. The runtime comes from the
+// executable: -Zsanitizer=address links the ASan runtime, UBSan handlers included, into call_libs,
+// and gcc links its own into the consumer programs
+const SANITIZER_FLAGS: &[&str] = if cfg!(feature = "sanitize") {
+    &["-fsanitize=address,undefined", "-fno-omit-frame-pointer"]
+} else {
+    &[]
+};
 
 fn main() {
-    cc::Build::new().file("c/clib.c").compile("clib");
+    let mut clib = cc::Build::new();
+    clib.file("c/clib.c").include("../../include");
+    for flag in SANITIZER_FLAGS {
+        clib.flag(flag);
+    }
+    clib.compile("clib");
+    println!("cargo::rerun-if-changed=c");
+
+    // tests/unwind.rs 中的 C 栈帧：-fexceptions 让 GCC 和 Clang（包括 MinGW）为 C 代码生成展开所需的表和
+    // 清理代码，MSVC 的 SEH 本来就能穿过 C 栈帧，cl 也不认识这个参数
+    // The C frames of tests/unwind.rs: -fexceptions makes GCC and Clang (MinGW included) emit the
+    // unwind tables and cleanups unwinding needs for C code, MSVC's SEH passes through C frames
+    // anyway and cl doesn't know the flag
+    let mut c_frames = cc::Build::new();
+    c_frames.file("tests/unwind/c_frames.c").include("../../include");
+    if !c_frames.get_compiler().is_like_msvc() {
+        c_frames.flag("-fexceptions");
+    }
+    for flag in SANITIZER_FLAGS {
+        c_frames.flag(flag);
+    }
+    c_frames.compile("c_frames");
+    println!("cargo::rerun-if-changed=tests/unwind");
+
+    // tests/stdcall.rs 中的 C 调用方，__stdcall 只在 32 位 Windows 上有意义
+    // The C caller of tests/stdcall.rs, __stdcall only means something on 32-bit Windows
+    if std::env::var("CARGO_CFG_TARGET_OS").unwrap() == "windows"
+        && std::env::var("CARGO_CFG_TARGET_ARCH").unwrap() == "x86"
+    {
+        cc::Build::new()
+            .file("tests/stdcall/stdcall_caller.c")
+            .include("../../include")
+            .compile("stdcall_caller");
+    }
+    println!("cargo::rerun-if-changed=tests/stdcall");
+
+    // tests/wide.rs 中的 C 调用方，L"..." 字面量按 UTF-16 编码只在 MSVC 上有保证
+    // The C caller of tests/wide.rs, L"..." literals are only guaranteed to be UTF-16 with MSVC
+    if std::env::var("CARGO_CFG_TARGET_ENV").unwrap() == "msvc" {
+        cc::Build::new()
+            .file("tests/wide/wide_caller.c")
+            .include("../../include")
+            .compile("wide_caller");
+    }
+    println!("cargo::rerun-if-changed=tests/wide");
+
+    // tests/scalars.rs 中的 C 调用方
+    // The C callers of tests/scalars.rs
+    cc::Build::new()
+        .files(["tests/scalars/static_scalars.c", "tests/scalars/dynamic_scalars.c"])
+        .include("../../include")
+        .compile("scalar_callers");
+    println!("cargo::rerun-if-changed=tests/scalars");
+
+    // tests/whole_archive.rs 中从 C 注册的操作：没有符号引用它，+whole-archive 让链接器取出整个静态库，
+    // 而不是只取出解析了未定义符号的成员。操作表依赖 ELF 链接器生成的 __start_/__stop_ 符号
+    // The operation tests/whole_archive.rs registers from C: no symbol refers to it, and
+    // +whole-archive makes the linker take the whole archive instead of only the members that
+    // resolve an undefined symbol. The operation table relies on the __start_/__stop_ symbols ELF
+    // linkers generate
+    let target_os = std::env::var("CARGO_CFG_TARGET_OS").unwrap();
+    if target_os == "linux" || target_os == "android" {
+        cc::Build::new()
+            .file("tests/whole_archive/cube_op.c")
+            .include("../../include")
+            .link_lib_modifier("+whole-archive")
+            .compile("cube_op");
+    }
+    println!("cargo::rerun-if-changed=tests/whole_archive");
+
+    #[cfg(feature = "bindgen")]
+    generate_bindings();
+
+    let target = Target::from_env();
+    build_external_lib(&target);
+    #[cfg(feature = "wasm")]
+    build_wasm_gen();
+    let staged_dir = stage_rust_libs(&target);
+    build_consumers(&target, &staged_dir);
+    println!("cargo::rustc-link-search=native={}", staged_dir.display());
+    // Windows 上通过导入库链接 cdylib_gen.dll，它的文件名取决于工具链：MSVC 是 cdylib_gen.dll.lib，
+    // MinGW 是 libcdylib_gen.dll.a；+verbatim 让 rustc 把文件名原样交给链接器，而不是由 link.exe 和
+    // ld 各自套用命名规则去猜。static 特性打开时 cdylib_gen 作为 rlib 依赖静态链接进来，不再链接动态库
+    // On Windows cdylib_gen.dll is linked through its import library, whose file name depends on
+    // the toolchain: cdylib_gen.dll.lib for MSVC, libcdylib_gen.dll.a for MinGW; +verbatim makes
+    // rustc hand the file name to the linker as is, instead of link.exe and ld each guessing it
+    // with their own naming rules. With the static feature cdylib_gen is linked statically as an
+    // rlib dependency instead of the dynamic library
+    if cfg!(not(feature = "static")) {
+        if let Some(import_lib) = target.import_lib_file("cdylib_gen") {
+            println!("cargo::rustc-link-lib=dylib:+verbatim={}", import_lib);
+        } else {
+            println!("cargo::rustc-link-lib=dylib=cdylib_gen");
+            // 直接运行可执行文件时也能找到 OUT_DIR 中的 cdylib
+            // Lets the executable find the cdylib in OUT_DIR when it is run directly
+            println!("cargo::rustc-link-arg-bins=-Wl,-rpath,{}", staged_dir.display());
+        }
+    }
+    // static 特性打开时 staticlib_gen 作为 rlib 链接：.a 里自带一份分配器垫片，和 cdylib_gen 的 rlib 重复
+    // With the static feature staticlib_gen is linked as an rlib: the .a carries an allocator shim
+    // of its own, which clashes with the one cdylib_gen's rlib brings
+    if cfg!(not(feature = "static")) {
+        println!(
+            "cargo::rustc-link-lib=static:+verbatim={}",
+            target.staticlib_file("staticlib_gen")
+        );
+    }
+    // 交叉编译出的可执行文件通常和库一起复制到目标机器上，所以再让它在自身目录中查找。
+    // 静态链接的可执行文件启动时不加载任何库，glibc 的 static-pie 启动代码遇到 RUNPATH 还会崩溃
+    // A cross-compiled executable is usually copied to the target machine along with the
+    // libraries, so also let it search its own directory. A statically linked executable loads
+    // no libraries at startup, and glibc's static-pie startup code even crashes on a RUNPATH
+    if (target.os == "linux" || target.os == "android") && !target.crt_static {
+        println!("cargo::rustc-link-arg-bins=-Wl,-rpath,$ORIGIN");
+    }
+    // dlopen 加载的 external_dy 没有链接 ASan 运行时，要从可执行文件中找到它
+    // external_dy, loaded with dlopen, is not linked against the ASan runtime and has to find it
+    // in the executable
+    if cfg!(feature = "sanitize") && target.os == "linux" {
+        println!("cargo::rustc-link-arg-bins=-Wl,--export-dynamic-symbol=__asan_*");
+        println!("cargo::rustc-link-arg-bins=-Wl,--export-dynamic-symbol=__ubsan_*");
+    }
+    // macOS 上 dylib 的安装名是 @rpath/...，让可执行文件在自身目录和 external_lib/lib_build 中查找
+    // On macOS the dylibs' install names are @rpath/..., so let the executable search its own
+    // directory and external_lib/lib_build (the binary lives in target/{profile})
+    if target.os == "macos" {
+        println!("cargo::rustc-link-arg-bins=-Wl,-rpath,@executable_path");
+        println!("cargo::rustc-link-arg-bins=-Wl,-rpath,@executable_path/../../external_lib/lib_build");
+    }
+}
+
+// 构建脚本在主机上运行，所以库的命名和链接方式要按 CARGO_CFG_TARGET_* 描述的目标决定，
+// 而不是用 cfg!(...) 或 std::env::consts
+// The build script runs on the host, so library naming and linking follow the target described
+// by CARGO_CFG_TARGET_*, not cfg!(...) or std::env::consts
+struct Target {
+    triple: String,
+    os: String,
+    env: String,
+    vendor: String,
+    crt_static: bool,
+}
+
+impl Target {
+    fn from_env() -> Self {
+        let var = |name: &str| std::env::var(name).unwrap_or_default();
+        let features = var("CARGO_CFG_TARGET_FEATURE");
+        let target = Target {
+            triple: var("TARGET"),
+            os: var("CARGO_CFG_TARGET_OS"),
+            env: var("CARGO_CFG_TARGET_ENV"),
+            vendor: var("CARGO_CFG_TARGET_VENDOR"),
+            crt_static: features.split(',').any(|feature| feature == "crt-static"),
+        };
+        // musl 目标默认静态链接 C 运行时，这时 rustc 会丢弃 cdylib 而可执行文件也无法链接动态库
+        // musl targets link the C runtime statically by default, in which case rustc drops the
+        // cdylib and the executable cannot link dynamic libraries
+        if target.env == "musl" && target.crt_static && cfg!(not(feature = "static")) {
+            panic!(
+                "call_libs links cdylib_gen dynamically, build {} with --features static or RUSTFLAGS=\"-C target-feature=-crt-static\".",
+                target.triple
+            );
+        }
+        if cfg!(feature = "sanitize") && target.is_msvc() {
+            panic!("The sanitize feature passes GCC/Clang flags and does not support {}.", target.triple);
+        }
+        target
+    }
+
+    fn is_windows(&self) -> bool {
+        self.os == "windows"
+    }
+
+    fn is_msvc(&self) -> bool {
+        self.env == "msvc"
+    }
+
+    fn dylib_file(&self, name: &str) -> String {
+        if self.is_windows() {
+            format!("{}.dll", name)
+        } else if self.vendor == "apple" {
+            format!("lib{}.dylib", name)
+        } else {
+            format!("lib{}.so", name)
+        }
+    }
+
+    // 链接 Windows DLL 用的导入库
+    // The import library used to link against a Windows DLL
+    fn import_lib_file(&self, name: &str) -> Option<String> {
+        match self.is_windows() {
+            true if self.is_msvc() => Some(format!("{}.dll.lib", name)),
+            true => Some(format!("lib{}.dll.a", name)),
+            false => None,
+        }
+    }
+
+    fn staticlib_file(&self, name: &str) -> String {
+        if self.is_msvc() {
+            format!("{}.lib", name)
+        } else {
+            format!("lib{}.a", name)
+        }
+    }
+
+    fn exe_file(&self, name: &str) -> String {
+        if self.is_windows() {
+            format!("{}.exe", name)
+        } else {
+            name.to_owned()
+        }
+    }
 
+    // 静态链接 Rust 库时还需要的系统库，即 rustc --print native-static-libs 的输出
+    // System libraries a static link of a Rust library also needs, as printed by
+    // rustc --print native-static-libs
+    fn native_static_libs(&self) -> &'static [&'static str] {
+        match self.os.as_str() {
+            "windows" if self.is_msvc() => &[
+                "kernel32.lib",
+                "advapi32.lib",
+                "ntdll.lib",
+                "userenv.lib",
+                "ws2_32.lib",
+                "dbghelp.lib",
+            ],
+            // MinGW 的 gcc 自己加上 mingw32、mingwex 和 msvcrt，但不会加上 Rust 展开用的 gcc_eh
+            // 和标准库用的静态 winpthreads
+            // MinGW's gcc adds mingw32, mingwex and msvcrt itself, but not the gcc_eh Rust unwinds
+            // with nor the static winpthreads the standard library uses
+            "windows" => &[
+                "-lkernel32",
+                "-ladvapi32",
+                "-lntdll",
+                "-luserenv",
+                "-lws2_32",
+                "-ldbghelp",
+                "-lgcc_eh",
+                "-l:libpthread.a",
+            ],
+            "macos" | "ios" => &["-liconv", "-lSystem", "-lc", "-lm"],
+            // 静态链接时没有 libgcc_s.so：glibc 上换成 libgcc_eh.a，musl 的标准库自带 libunwind
+            // There is no libgcc_s.so in a static link: glibc uses libgcc_eh.a instead, musl's
+            // standard library bundles libunwind
+            _ if self.crt_static && self.env == "musl" => &["-lc"],
+            _ if self.crt_static => {
+                &["-lgcc_eh", "-lutil", "-lrt", "-lpthread", "-lm", "-ldl", "-lc"]
+            }
+            _ => &["-lgcc_s", "-lutil", "-lrt", "-lpthread", "-lm", "-ldl", "-lc"],
+        }
+    }
+}
+
+// 在 OUT_DIR 下用同样的 target 和 profile 构建 cdylib_gen 和 staticlib_gen，并把产物暂存到 OUT_DIR。
+// 这样自定义 --target-dir、交叉编译和单独构建 call_libs 时都无需猜测 target/{profile}
+// Builds cdylib_gen and staticlib_gen under OUT_DIR with the same target and profile and stages
+// the artifacts in OUT_DIR, so a custom --target-dir, cross compilation and building call_libs on
+// its own all link the right files without guessing target/{profile}
+fn stage_rust_libs(target: &Target) -> std::path::PathBuf {
+    let out_dir = std::path::PathBuf::from(std::env::var("OUT_DIR").unwrap());
     let profile = std::env::var("PROFILE").unwrap();
-    let search_dir = format!("target/{}", profile);
-    println!("cargo::rustc-link-search=native={}", search_dir);
-    if cfg!(target_os = "windows") {
-        println!("cargo::rustc-link-lib=dylib=cdylib_gen.dll");
-        println!("cargo::rustc-link-lib=static=staticlib_gen");
+    let target_dir = out_dir.join("rust_libs");
+
+    let mut cargo = std::process::Command::new(std::env::var("CARGO").unwrap());
+    cargo
+        .args(["build", "-p", "cdylib_gen", "-p", "staticlib_gen", "--target", &target.triple])
+        .arg("--target-dir")
+        .arg(&target_dir)
+        .current_dir("../..");
+    if profile == "release" {
+        cargo.arg("--release");
+    }
+    let status = cargo.status().expect("Failed to run cargo for cdylib_gen and staticlib_gen.");
+    assert!(status.success(), "Building cdylib_gen and staticlib_gen failed.");
+
+    let mut artifacts = vec![target.staticlib_file("staticlib_gen")];
+    if cfg!(not(feature = "static")) {
+        artifacts.push(target.dylib_file("cdylib_gen"));
+        artifacts.extend(target.import_lib_file("cdylib_gen"));
+    }
+    let built_dir = target_dir.join(&target.triple).join(&profile);
+    for artifact in &artifacts {
+        std::fs::copy(built_dir.join(artifact), out_dir.join(artifact))
+            .unwrap_or_else(|err| panic!("Failed to stage {}: {}", artifact, err));
+    }
+
+    for dir in ["../cdylib_gen", "../staticlib_gen", "../interop_common"] {
+        println!("cargo::rerun-if-changed={}", dir);
+    }
+    out_dir
+}
+
+// 编译 tests/c_consumer 和 tests/cpp_consumer 中调用 Rust 库的 C 和 C++ 程序，放在暂存的库旁边供
+// tests/c_consumer.rs 和 tests/cpp_consumer.rs 运行；Windows 上可执行文件目录中的 DLL 会被优先加载，
+// 其他平台通过 rpath 找到 cdylib
+// Compiles the C and C++ programs in tests/c_consumer and tests/cpp_consumer that call the Rust
+// libraries and puts them next to the staged libraries for tests/c_consumer.rs and
+// tests/cpp_consumer.rs to run; on Windows the DLL in the executable's directory is loaded
+// first, elsewhere the rpath finds the cdylib
+fn build_consumers(target: &Target, staged_dir: &std::path::Path) {
+    let staticlib = target.staticlib_file("staticlib_gen");
+    let cdylib = target
+        .import_lib_file("cdylib_gen")
+        .unwrap_or_else(|| target.dylib_file("cdylib_gen"));
+    // 目标打开 crt-static 时，cc 给出的参数已经和 Rust 一致：cl 用 /MT 而不是 /MD，GCC 和 Clang 带上 -static
+    // With crt-static on for the target, the flags cc hands out already match Rust's: /MT instead
+    // of /MD for cl, -static for GCC and Clang
+    let c = cc::Build::new().get_compiler();
+    let cpp = cc::Build::new().cpp(true).get_compiler();
+    let programs: [(&str, &cc::Tool, &[&str], &[&str]); 4] = [
+        (
+            "static_consumer",
+            &c,
+            &["tests/c_consumer/static_consumer.c"],
+            &[&staticlib],
+        ),
+        (
+            "dynamic_consumer",
+            &c,
+            &["tests/c_consumer/dynamic_consumer.c"],
+            &[&cdylib],
+        ),
+        (
+            "legacy_consumer",
+            &c,
+            &["tests/c_consumer/legacy_consumer.c"],
+            &[&cdylib],
+        ),
+        (
+            "cpp_consumer",
+            &cpp,
+            &[
+                "tests/cpp_consumer/main.cpp",
+                "tests/cpp_consumer/static_part.cpp",
+            ],
+            &[&cdylib, &staticlib],
+        ),
+    ];
+    for (program, compiler, sources, libs) in programs {
+        // static 特性打开时没有暂存 cdylib_gen，只编译 static_consumer
+        // With the static feature no cdylib_gen is staged, so only static_consumer is compiled
+        if cfg!(feature = "static") && libs.contains(&cdylib.as_str()) {
+            continue;
+        }
+        let out_file = staged_dir.join(target.exe_file(program));
+        let mut command = compiler.to_command();
+        if compiler.is_like_msvc() {
+            command
+                .args(["/nologo", "/EHsc", "/I../../include"])
+                .args(sources)
+                .arg(format!("/Fe{}", out_file.display()))
+                .arg(format!("/Fo{}\\", staged_dir.display()));
+        } else {
+            command
+                .args(sources)
+                .args(["-I../../include", "-o"])
+                .arg(&out_file);
+            if !target.is_windows() {
+                command.arg(format!("-Wl,-rpath,{}", staged_dir.display()));
+            }
+        }
+        command.args(SANITIZER_FLAGS);
+        command.args(libs.iter().map(|lib| staged_dir.join(lib)));
+        if libs.contains(&staticlib.as_str()) {
+            command.args(target.native_static_libs());
+        }
+        let status = command.status().expect("Failed to run the C compiler for the consumers.");
+        assert!(status.success(), "Compiling {} failed.", program);
+    }
+
+    // 同一个程序链接两次，对比普通链接和 --whole-archive 链接出的操作表
+    // The same program linked twice, comparing the operation tables of a plain and a
+    // --whole-archive link
+    if target.os == "linux" || target.os == "android" {
+        let archive = staged_dir.join(&staticlib);
+        for (program, whole_archive) in [
+            ("ops_consumer", false),
+            ("ops_consumer_whole_archive", true),
+        ] {
+            let out_file = staged_dir.join(target.exe_file(program));
+            let mut command = c.to_command();
+            command
+                .args(["tests/whole_archive/ops_consumer.c", "-I../../include", "-o"])
+                .arg(&out_file)
+                .args(SANITIZER_FLAGS);
+            if whole_archive {
+                command.arg("-Wl,--whole-archive").arg(&archive).arg("-Wl,--no-whole-archive");
+            } else {
+                command.arg(&archive);
+            }
+            command.args(target.native_static_libs());
+            let status = command.status().expect("Failed to run the C compiler for the consumers.");
+            assert!(status.success(), "Compiling {} failed.", program);
+        }
+    }
+    println!("cargo::rerun-if-changed=tests/c_consumer");
+    println!("cargo::rerun-if-changed=tests/cpp_consumer");
+}
+
+// 从 external_lib/dylib.c 编译出 dlopen 演示使用的动态库，放在 OUT_DIR 中供运行时查找
+// Compiles the dynamic library the dlopen demo uses from external_lib/dylib.c into OUT_DIR, where
+// the runtime lookup finds it
+fn build_external_lib(target: &Target) {
+    let out_dir = std::path::PathBuf::from(std::env::var("OUT_DIR").unwrap());
+    let source = "../../external_lib/dylib.c";
+    compile_external_dylib(target, source, &out_dir, "external_dy", &[]);
+    // 没有 dyloading_add_v2 的较早构建，文件名相同，放在 OUT_DIR/old 中，--lib-path 指向那里时
+    // 调用方退回 dyloading_add
+    // An older build without dyloading_add_v2, with the same file name in OUT_DIR/old; pointing
+    // --lib-path there makes callers fall back to dyloading_add
+    let old_dir = out_dir.join("old");
+    std::fs::create_dir_all(&old_dir).expect("Failed to create OUT_DIR/old.");
+    let without_v2 = ["-DDYLOADING_WITHOUT_ADD_V2"];
+    compile_external_dylib(target, source, &old_dir, "external_dy", &without_v2);
+    // --dlmopen 把两个不同的构建加载进各自的链接映射命名空间，第二个构建只有消息前缀不同；
+    // dlmopen 是 glibc 的扩展
+    // --dlmopen loads two different builds into link-map namespaces of their own, the second
+    // build only differs in its message prefix; dlmopen is a glibc extension
+    if target.os == "linux" && target.env == "gnu" {
+        compile_external_dylib(
+            target,
+            source,
+            &out_dir,
+            "external_dy_alt",
+            &["-DDYLOADING_BUILD=\"External dyloading alt\""],
+        );
+    }
+    // 演示 RTLD_GLOBAL 的库，留下一个未定义符号在加载时解析；DLL 不能这样链接，macOS 还需要
+    // -undefined dynamic_lookup
+    // The library the RTLD_GLOBAL demo uses, leaving an undefined symbol to be resolved at load
+    // time; a DLL can't be linked like that, and macOS would also need -undefined dynamic_lookup
+    let dependent = "../../external_lib/dependent.c";
+    if !target.is_windows() && target.os != "macos" {
+        compile_external_dylib(target, dependent, &out_dir, "external_dependent", &[]);
+    }
+    println!("cargo::rerun-if-changed={}", dependent);
+
+    // static-external 特性打开时再把同一份源文件编译成静态库，由 cc 告诉 rustc 链接它
+    // With the static-external feature the same source is also compiled into a static archive,
+    // which cc tells rustc to link
+    if cfg!(feature = "static-external") {
+        let mut archive = cc::Build::new();
+        archive.file(source);
+        for flag in SANITIZER_FLAGS {
+            archive.flag(flag);
+        }
+        archive.compile("external_dy_static");
+    }
+
+    println!("cargo::rerun-if-changed={}", source);
+}
+
+// 把 source 编译成 out_dir 中名为 name 的动态库，defines 是 -D 参数，cl 也认识这种形式
+// Compiles source into the dynamic library called name in out_dir, defines are -D flags, a form
+// cl understands as well
+fn compile_external_dylib(
+    target: &Target,
+    source: &str,
+    out_dir: &std::path::Path,
+    name: &str,
+    defines: &[&str],
+) {
+    let lib_file = target.dylib_file(name);
+    let out_file = out_dir.join(&lib_file);
+    // cc 只会生成静态库，所以借用它探测到的编译器和参数自己链接成动态库；
+    // 交叉编译时 cc 会按 TARGET 选择对应的交叉编译器，如 aarch64-linux-gnu-gcc
+    // cc only produces static archives, so borrow the compiler and flags it detects and link the
+    // dynamic library ourselves; when cross compiling cc picks the cross compiler for TARGET,
+    // such as aarch64-linux-gnu-gcc
+    //
+    // 目标打开 crt-static 时 cc 会为 GCC 和 Clang 加上 -static，但动态库不能这样链接
+    // With crt-static on for the target cc adds -static for GCC and Clang, which a dynamic library
+    // cannot be linked with
+    let compiler = cc::Build::new().static_flag(false).get_compiler();
+    let mut command = compiler.to_command();
+    command.args(defines);
+    if compiler.is_like_msvc() {
+        command
+            .args(["/nologo", "/LD", source])
+            .arg(format!("/Fe{}", out_file.display()))
+            .arg(format!("/Fo{}\\", out_dir.display()));
+    } else if target.is_windows() {
+        // MinGW 生成的 DLL 不需要 -fPIC
+        // DLLs produced by MinGW do not need -fPIC
+        command.args(["-shared", source, "-o"]).arg(&out_file);
+    } else if !SANITIZER_FLAGS.is_empty() {
+        // 只在编译时插桩：带着 -fsanitize 链接时 gcc 会把自己的 libasan.so 加进来，和加载它的
+        // 可执行文件中的 ASan 运行时冲突
+        // Instrument at compile time only: linking with -fsanitize makes gcc add its own
+        // libasan.so, which clashes with the ASan runtime in the executable that loads the library
+        let mut build = cc::Build::new();
+        build.file(source).pic(true);
+        for flag in defines.iter().chain(SANITIZER_FLAGS) {
+            build.flag(flag);
+        }
+        command.arg("-shared").args(build.compile_intermediates()).arg("-o").arg(&out_file);
     } else {
-        println!("cargo::rustc-link-lib=dylib=cdylib_gen");
-        println!("cargo::rustc-link-lib=static=staticlib_gen");
+        command.args(["-shared", "-fPIC", source, "-o"]).arg(&out_file);
+    }
+    let status = command.status().expect("Failed to run the C compiler for external_lib.");
+    assert!(status.success(), "Compiling {} from {} failed.", lib_file, source);
+}
+
+// 为 wasm32-wasip1 构建 wasm_gen，把 wasm_gen.wasm 放在 OUT_DIR 中供 wasm 后端加载
+// Builds wasm_gen for wasm32-wasip1 and puts wasm_gen.wasm into OUT_DIR for the wasm backend
+#[cfg(feature = "wasm")]
+fn build_wasm_gen() {
+    let out_dir = std::path::PathBuf::from(std::env::var("OUT_DIR").unwrap());
+    let profile = std::env::var("PROFILE").unwrap();
+    let target_dir = out_dir.join("wasm");
+
+    let mut cargo = std::process::Command::new(std::env::var("CARGO").unwrap());
+    cargo
+        .args(["build", "-p", "wasm_gen", "--target", "wasm32-wasip1"])
+        .arg("--target-dir")
+        .arg(&target_dir)
+        .current_dir("../..")
+        // 外层构建的 RUSTFLAGS 是给 call_libs 的目标用的，不适用于 wasm
+        // The outer build's RUSTFLAGS are meant for call_libs' target, not for wasm
+        .env_remove("CARGO_ENCODED_RUSTFLAGS");
+    if profile == "release" {
+        cargo.arg("--release");
+    }
+    let status = cargo.status().expect("Failed to run cargo for wasm_gen.");
+    assert!(
+        status.success(),
+        "Building wasm_gen failed, is the target installed (rustup target add wasm32-wasip1)?"
+    );
+
+    let wasm = target_dir.join("wasm32-wasip1").join(&profile).join("wasm_gen.wasm");
+    std::fs::copy(&wasm, out_dir.join("wasm_gen.wasm"))
+        .unwrap_or_else(|err| panic!("Failed to stage {}: {}", wasm.display(), err));
+    println!("cargo::rerun-if-changed=../wasm_gen");
+}
+
+// 用 bindgen 从 c/clib.h 生成 Rust 绑定，UPDATE_BINDINGS=1 时同步到 bindings/clib.rs
+// Generates the Rust bindings from c/clib.h with bindgen, with UPDATE_BINDINGS=1 they are copied to bindings/clib.rs
+#[cfg(feature = "bindgen")]
+fn generate_bindings() {
+    let out_file = std::path::PathBuf::from(std::env::var("OUT_DIR").unwrap()).join("clib.rs");
+
+    bindgen::Builder::default()
+        .header("c/clib.h")
+        // clib.h 声明的所有函数和变量；位域结构体 ClibStatus 的布局只有 C 知道，只生成同样大小和对齐的存储
+        // Every function and variable clib.h declares; only C knows the layout of the bitfield
+        // struct ClibStatus, so it's generated as storage of the same size and alignment
+        .allowlist_function("add|clib_.*")
+        .allowlist_var("clib_.*")
+        .opaque_type("ClibStatus")
+        .parse_callbacks(Box::new(bindgen::CargoCallbacks::new()))
+        .generate()
+        .expect("Unable to generate bindings for c/clib.h.")
+        .write_to_file(&out_file)
+        .unwrap();
+
+    if std::env::var_os("UPDATE_BINDINGS").is_some() {
+        std::fs::copy(&out_file, "bindings/clib.rs").unwrap();
     }
+    println!("cargo::rerun-if-env-changed=UPDATE_BINDINGS");
 }
@@ -1,5 +1,8 @@
 #include <stdio.h>
+#include <stddef.h>
 #include <stdint.h>
+#include "clib.h"
+#include "staticlib_gen.h"
 
 int32_t add(int32_t a, int32_t b, char *result)
 {
@@ -10,3 +13,313 @@ int32_t add(int32_t a, int32_t b, char *result)
     sprintf(result,"[C source] The result (%d + %d) is %d!", a, b, sum);
     return sum;
 }
+
+// 不做任何输出的求和，benches/call_overhead.rs 用它测量调用本身的开销
+// A sum without any output, benches/call_overhead.rs uses it to measure the cost of the call itself
+int32_t clib_sum(const int32_t *values, size_t len, int64_t *total)
+{
+    int64_t sum = 0;
+    for (size_t i = 0; i < len; i++)
+    {
+        sum += values[i];
+    }
+    *total = sum;
+    return 0;
+}
+
+// 原子地给 counter 加上 n 并返回新的值，以及原子地读取它；GCC 和 Clang 用 __atomic 内建函数，MSVC 用
+// Interlocked 函数。两者和 Rust 的原子操作作用于同一个 32 位的值
+// Atomically adds n to counter and returns the new value, and atomically reads it; GCC and Clang
+// use the __atomic builtins, MSVC the Interlocked functions. Either works on the same 32-bit value
+// as Rust's atomics
+#if defined(_MSC_VER)
+#include <intrin.h>
+
+static uint32_t counter_add(uint32_t *counter, uint32_t n)
+{
+    return (uint32_t)_InterlockedExchangeAdd((volatile long *)counter, (long)n) + n;
+}
+
+static uint32_t counter_load(uint32_t *counter)
+{
+    return (uint32_t)_InterlockedCompareExchange((volatile long *)counter, 0, 0);
+}
+#else
+static uint32_t counter_add(uint32_t *counter, uint32_t n)
+{
+    return __atomic_add_fetch(counter, n, __ATOMIC_SEQ_CST);
+}
+
+static uint32_t counter_load(uint32_t *counter)
+{
+    return __atomic_load_n(counter, __ATOMIC_SEQ_CST);
+}
+#endif
+
+// staticlib_gen 在 Rust 中定义的 rxc_staticlib_counter，C 按名字读写它
+// rxc_staticlib_counter, defined in Rust by staticlib_gen, read and written by name from C
+uint32_t clib_staticlib_counter_add(uint32_t n)
+{
+    return counter_add(&rxc_staticlib_counter, n);
+}
+
+uint32_t clib_staticlib_counter_get(void)
+{
+    return counter_load(&rxc_staticlib_counter);
+}
+
+// 反方向：在 C 中定义、由 Rust 按名字读写的计数器
+// The other direction: a counter defined in C and read and written by name from Rust
+uint32_t clib_counter = 0;
+
+uint32_t clib_counter_add(uint32_t n)
+{
+    return counter_add(&clib_counter, n);
+}
+
+// C 创建、和 Rust 共用一把锁的计数器。锁就地初始化在 C 分配的结构体中，初始化后不能移动，所以 Rust
+// 只拿到指向它的指针
+// A counter C creates and shares with Rust under one lock. The lock is initialized in place in the
+// struct C allocates and can't move once initialized, so Rust only gets a pointer to it
+#ifdef _WIN32
+#include <windows.h>
+#else
+#include <pthread.h>
+#include <time.h>
+#endif
+#include <stdlib.h>
+
+struct ClibLockedCounter
+{
+#ifdef _WIN32
+    SRWLOCK lock;
+    HANDLE thread;
+#else
+    pthread_mutex_t lock;
+    pthread_t thread;
+#endif
+    int running;
+    uint32_t rounds;
+    uint64_t value;
+};
+
+ClibLockedCounter *clib_locked_counter_new(void)
+{
+    ClibLockedCounter *counter = calloc(1, sizeof(ClibLockedCounter));
+    if (counter == NULL)
+    {
+        return NULL;
+    }
+#ifdef _WIN32
+    InitializeSRWLock(&counter->lock);
+#else
+    if (pthread_mutex_init(&counter->lock, NULL) != 0)
+    {
+        free(counter);
+        return NULL;
+    }
+#endif
+    return counter;
+}
+
+// 还在运行的 C 线程会先被等待结束，锁随后销毁
+// A C thread still running is waited for first, then the lock is destroyed
+void clib_locked_counter_free(ClibLockedCounter *counter)
+{
+    if (counter == NULL)
+    {
+        return;
+    }
+    clib_locked_counter_join(counter);
+#ifndef _WIN32
+    pthread_mutex_destroy(&counter->lock);
+#endif
+    free(counter);
+}
+
+void *clib_locked_counter_lock(ClibLockedCounter *counter)
+{
+    return &counter->lock;
+}
+
+// 只能在持有锁的时候读写
+// Only to be read and written while holding the lock
+uint64_t *clib_locked_counter_value(ClibLockedCounter *counter)
+{
+    return &counter->value;
+}
+
+// C 线程每一轮都加锁、加 1、解锁，和 Rust 那边争用同一把锁
+// Every round the C thread locks, adds 1 and unlocks, contending for the same lock as Rust
+#ifdef _WIN32
+static DWORD WINAPI increment(LPVOID arg)
+#else
+static void *increment(void *arg)
+#endif
+{
+    ClibLockedCounter *counter = arg;
+    for (uint32_t i = 0; i < counter->rounds; i++)
+    {
+#ifdef _WIN32
+        AcquireSRWLockExclusive(&counter->lock);
+        counter->value++;
+        ReleaseSRWLockExclusive(&counter->lock);
+#else
+        pthread_mutex_lock(&counter->lock);
+        counter->value++;
+        pthread_mutex_unlock(&counter->lock);
+#endif
+    }
+    return 0;
+}
+
+// 启动一个给计数器加 rounds 次的 C 线程，成功时返回 0；已经有一个在运行或者线程无法启动时返回 -1
+// Starts a C thread adding to the counter rounds times, returning 0 on success; returns -1 when
+// one is already running or the thread could not be started
+int32_t clib_locked_counter_start(ClibLockedCounter *counter, uint32_t rounds)
+{
+    if (counter->running)
+    {
+        return -1;
+    }
+    counter->rounds = rounds;
+#ifdef _WIN32
+    counter->thread = CreateThread(NULL, 0, increment, counter, 0, NULL);
+    int failed = counter->thread == NULL;
+#else
+    int failed = pthread_create(&counter->thread, NULL, increment, counter) != 0;
+#endif
+    if (failed)
+    {
+        return -1;
+    }
+    counter->running = 1;
+    return 0;
+}
+
+// 等待 clib_locked_counter_start 启动的线程结束，没有线程在运行时直接返回
+// Waits for the thread clib_locked_counter_start started to finish, returns at once when none is
+// running
+void clib_locked_counter_join(ClibLockedCounter *counter)
+{
+    if (!counter->running)
+    {
+        return;
+    }
+#ifdef _WIN32
+    WaitForSingleObject(counter->thread, INFINITE);
+    CloseHandle(counter->thread);
+#else
+    pthread_join(counter->thread, NULL);
+#endif
+    counter->running = 0;
+}
+
+// 生产者线程，它唯一的同步手段就是 Rust 提供的 push 和 close：条件变量在 Rust 那边，C 只负责通知
+// The producer thread, whose only means of synchronization are the push and close Rust provides:
+// the condition variable lives on the Rust side, C only signals it
+typedef struct Producer
+{
+    uint32_t count;
+    uint32_t interval_ms;
+    ClibPushFn push;
+    ClibCloseFn close;
+    void *ctx;
+} Producer;
+
+static void sleep_ms(uint32_t ms)
+{
+#ifdef _WIN32
+    Sleep(ms);
+#else
+    struct timespec delay = {(time_t)(ms / 1000), (long)(ms % 1000) * 1000000L};
+    nanosleep(&delay, NULL);
+#endif
+}
+
+#ifdef _WIN32
+static DWORD WINAPI produce(LPVOID arg)
+#else
+static void *produce(void *arg)
+#endif
+{
+    Producer *producer = arg;
+    for (uint32_t i = 1; i <= producer->count; i++)
+    {
+        sleep_ms(producer->interval_ms);
+        producer->push(producer->ctx, i);
+    }
+    producer->close(producer->ctx);
+    free(producer);
+    return 0;
+}
+
+int32_t clib_spawn_producer(uint32_t count, uint32_t interval_ms, ClibPushFn push,
+                            ClibCloseFn close, void *ctx)
+{
+    Producer *producer = malloc(sizeof(Producer));
+    if (producer == NULL)
+    {
+        return -1;
+    }
+    *producer = (Producer){count, interval_ms, push, close, ctx};
+#ifdef _WIN32
+    HANDLE thread = CreateThread(NULL, 0, produce, producer, 0, NULL);
+    int failed = thread == NULL;
+    if (!failed)
+    {
+        CloseHandle(thread);
+    }
+#else
+    pthread_t thread;
+    int failed = pthread_create(&thread, NULL, produce, producer) != 0;
+    if (!failed)
+    {
+        pthread_detach(thread);
+    }
+#endif
+    if (failed)
+    {
+        free(producer);
+        return -1;
+    }
+    return 0;
+}
+
+// CLIB_STATUS_FIELDS 中的每个字段各生成一个 getter 和 setter，宽度从同一个列表得到，不会和结构体不一致
+// One getter and one setter for every field of CLIB_STATUS_FIELDS, taking the width from the same
+// list so they can't disagree with the struct
+_Static_assert(sizeof(ClibStatus) == sizeof(unsigned int), "ClibStatus must fit one unsigned int");
+
+#define CLIB_STATUS_ACCESSORS(name, width)                             \
+    uint32_t clib_status_get_##name(const ClibStatus *status)          \
+    {                                                                  \
+        return status->name;                                           \
+    }                                                                  \
+                                                                       \
+    int32_t clib_status_set_##name(ClibStatus *status, uint32_t value) \
+    {                                                                  \
+        if (value >> (width) != 0)                                     \
+        {                                                              \
+            return -1;                                                 \
+        }                                                              \
+        status->name = value;                                          \
+        return 0;                                                      \
+    }
+CLIB_STATUS_FIELDS(CLIB_STATUS_ACCESSORS)
+#undef CLIB_STATUS_ACCESSORS
+
+size_t clib_status_size(void)
+{
+    return sizeof(ClibStatus);
+}
+
+size_t clib_status_align(void)
+{
+    return offsetof(struct { char c; ClibStatus status; }, status);
+}
+
+void clib_status_example(ClibStatus *out)
+{
+    *out = (ClibStatus){.ready = 1, .mode = 5, .channel = 9, .count = 4000};
+}
@@ -0,0 +1,78 @@
+// 这是 clib.c 的头文件，Rust 端的绑定由 bindgen 从这里生成
+// This is the header of clib.c, the Rust bindings are generated from it by bindgen
+#ifndef CLIB_H
+#define CLIB_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+int32_t add(int32_t a, int32_t b, char *result);
+int32_t clib_sum(const int32_t *values, size_t len, int64_t *total);
+
+// 和 Rust 共享的计数器，只能用原子操作访问
+// Counters shared with Rust, only to be accessed with atomic operations
+uint32_t clib_staticlib_counter_add(uint32_t n);
+uint32_t clib_staticlib_counter_get(void);
+extern uint32_t clib_counter;
+uint32_t clib_counter_add(uint32_t n);
+
+// C 创建的锁保护的计数器：锁在 Unix 上是 pthread_mutex_t，在 Windows 上是 SRWLOCK，Rust 通过
+// clib_locked_counter_lock 返回的指针加锁，再读写 clib_locked_counter_value 返回的值
+// A lock-protected counter created by C: the lock is a pthread_mutex_t on Unix and an SRWLOCK on
+// Windows, Rust locks it through the pointer clib_locked_counter_lock returns and then reads and
+// writes the value clib_locked_counter_value returns
+typedef struct ClibLockedCounter ClibLockedCounter;
+
+ClibLockedCounter *clib_locked_counter_new(void);
+void clib_locked_counter_free(ClibLockedCounter *counter);
+void *clib_locked_counter_lock(ClibLockedCounter *counter);
+uint64_t *clib_locked_counter_value(ClibLockedCounter *counter);
+int32_t clib_locked_counter_start(ClibLockedCounter *counter, uint32_t rounds);
+void clib_locked_counter_join(ClibLockedCounter *counter);
+
+// 在分离的 C 线程中每隔 interval_ms 毫秒交出一项工作，共 count 项，最后关闭 ctx；线程无法启动时返回
+// -1，push 和 close 都不会被调用
+// Hands over count pieces of work from a detached C thread, one every interval_ms milliseconds,
+// and finally closes ctx; returns -1 when the thread could not be started, in which case neither
+// push nor close gets called
+typedef void (*ClibPushFn)(void *ctx, uint64_t work);
+typedef void (*ClibCloseFn)(void *ctx);
+
+int32_t clib_spawn_producer(uint32_t count, uint32_t interval_ms, ClibPushFn push,
+                            ClibCloseFn close, void *ctx);
+
+// 用位域打包的状态寄存器。位域在存储单元中的顺序、跨单元时的处理方式都由实现决定，Rust 无法可移植地描述
+// 这样的布局，所以 Rust 只保存和它同样大小、同样对齐的存储，每个字段都通过下面由 CLIB_STATUS_FIELDS
+// 生成的 getter 和 setter 访问。setter 在值超出字段宽度时返回 -1 并保持字段不变，否则返回 0
+// A status register packed with bitfields. The order of bitfields within their storage unit and
+// what happens when one doesn't fit are implementation-defined, so Rust can't portably describe
+// the layout; Rust only holds storage of the same size and alignment and accesses every field
+// through the getters and setters CLIB_STATUS_FIELDS generates below. A setter returns -1 and
+// leaves the field unchanged when the value doesn't fit its width, and 0 otherwise
+#define CLIB_STATUS_FIELDS(X) \
+    X(ready, 1)               \
+    X(error, 1)               \
+    X(mode, 3)                \
+    X(channel, 4)             \
+    X(count, 12)
+
+typedef struct ClibStatus
+{
+#define CLIB_STATUS_FIELD(name, width) unsigned int name : width;
+    CLIB_STATUS_FIELDS(CLIB_STATUS_FIELD)
+#undef CLIB_STATUS_FIELD
+} ClibStatus;
+
+#define CLIB_STATUS_ACCESSORS(name, width)                              \
+    uint32_t clib_status_get_##name(const ClibStatus *status);          \
+    int32_t clib_status_set_##name(ClibStatus *status, uint32_t value);
+CLIB_STATUS_FIELDS(CLIB_STATUS_ACCESSORS)
+#undef CLIB_STATUS_ACCESSORS
+
+size_t clib_status_size(void);
+size_t clib_status_align(void);
+// C 端用指定初始化器填好的寄存器，Rust 通过 getter 读出来
+// A register C fills in with designated initializers, for Rust to read back through the getters
+void clib_status_example(ClibStatus *out);
+
+#endif
@@ -0,0 +1,32 @@
+// 以指针加长度的形式把切片传给静态库，并让动态库填充调用方的缓冲区
+// Passes a slice to the static library as pointer + length and lets the dynamic library fill a caller buffer
+
+use std::ffi::{c_int, c_long};
+use std::ptr;
+
+use interop_common::FfiSlice;
+
+extern "C" {
+    fn rxc_staticlib_sum(values: *const c_int, len: usize, total: *mut c_long) -> c_int;
+    fn rxc_staticlib_sum_slice(values: FfiSlice<c_int>, total: *mut c_long) -> c_int;
+    fn rxc_cdylib_range(start: c_int, end: c_int, out: *mut c_int, cap: usize) -> usize;
+}
+
+pub fn array_demo() {
+    println!("[Rust] Passing slices to and from the libraries");
+    // 先用 NULL 查询元素个数，再分配刚好足够的缓冲区
+    // Query the element count with NULL first, then allocate a buffer that fits exactly
+    let count = unsafe { rxc_cdylib_range(1, 6, ptr::null_mut(), 0) };
+    let mut values = vec![0; count];
+    unsafe { rxc_cdylib_range(1, 6, values.as_mut_ptr(), values.len()) };
+    println!("[Rust] Range from dynamic library: {:?}", values);
+
+    let mut total = 0;
+    let status = unsafe { rxc_staticlib_sum(values.as_ptr(), values.len(), &mut total) };
+    println!("[Rust] Sum from static library: {} (status {})", total, status);
+
+    // 同一个和，切片作为一个 FfiSlice 传递
+    // The same sum, with the slice passed as one FfiSlice
+    let status = unsafe { rxc_staticlib_sum_slice(FfiSlice::new(&values), &mut total) };
+    println!("[Rust] Sum of an FfiSlice: {} (status {})\n", total, status);
+}
@@ -0,0 +1,36 @@
+// 调用 atomic_interop：Rust 的 AtomicU32 和 C 的 _Atomic uint32_t 在同一块内存上轮流，表先由 Rust 分配，
+// 再由 C 分配
+// Calls atomic_interop: Rust's AtomicU32 and C's _Atomic uint32_t take turns on the same memory,
+// first in a table Rust allocated, then in one C allocated
+
+use atomic_interop::{CPingPong, PingPong};
+
+const ROUNDS: u32 = 1000;
+
+pub fn atomics_demo() {
+    println!("[Rust] Playing ping-pong with C through atomics on shared memory");
+    let rust_table = PingPong::new();
+    if !rust_table.lock_free_in_c() {
+        eprintln!("[Rust] C's _Atomic uint32_t is not lock-free, it can't be shared with Rust\n");
+        return;
+    }
+    let (rust, c) = rust_table.play(ROUNDS);
+    println!(
+        "[Rust] Table from Rust: {} turns, {} mismatches seen by Rust, {} by C",
+        rust_table.turn(),
+        rust,
+        c
+    );
+    match CPingPong::new() {
+        Some(c_table) => {
+            let (rust, c) = c_table.play(ROUNDS);
+            println!(
+                "[Rust] Table from C: {} turns, {} mismatches seen by Rust, {} by C\n",
+                c_table.turn(),
+                rust,
+                c
+            );
+        }
+        None => eprintln!("[Rust] C could not allocate a table\n"),
+    }
+}
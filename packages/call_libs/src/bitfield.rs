@@ -0,0 +1,178 @@
+// 通过 clib.c 生成的访问函数使用 C 的位域结构体 ClibStatus：Status 只保存和它同样大小、同样对齐的存储，
+// 从不自己解释其中的位，每个字段都交给 C 的 getter 和 setter，位在哪里由 C 编译器决定
+// Uses C's bitfield struct ClibStatus through the accessors clib.c generates: Status only holds
+// storage of the same size and alignment and never interprets the bits itself, every field goes
+// through C's getters and setters, leaving where the bits are to the C compiler
+
+use std::fmt;
+
+// clib.rs 中的 ClibStatus 由 bindgen 生成为不透明的存储，和 C 的结构体同样大小、同样对齐；clib.c 中的
+// _Static_assert 保证它只占一个 unsigned int
+// ClibStatus in clib.rs is generated by bindgen as opaque storage of the same size and alignment
+// as C's struct; the _Static_assert in clib.c keeps it to one unsigned int
+use crate::clib::{
+    clib_status_align, clib_status_example, clib_status_get_channel, clib_status_get_count,
+    clib_status_get_error, clib_status_get_mode, clib_status_get_ready, clib_status_set_channel,
+    clib_status_set_count, clib_status_set_error, clib_status_set_mode, clib_status_set_ready,
+    clib_status_size, ClibStatus,
+};
+
+/// A value that doesn't fit the width of the bitfield it was meant for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OutOfRange {
+    pub field: &'static str,
+    pub value: u32,
+}
+
+impl fmt::Display for OutOfRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} does not fit the {} bitfield", self.value, self.field)
+    }
+}
+
+impl std::error::Error for OutOfRange {}
+
+/// C 的 ClibStatus 的安全包装
+/// A safe wrapper around C's `ClibStatus`, every field of which is read and written by C.
+#[derive(Clone, Copy)]
+pub struct Status {
+    raw: ClibStatus,
+}
+
+// 全零的存储是每个字段都为 0 的合法寄存器
+// All-zero storage is a valid register with every field 0
+impl Default for Status {
+    fn default() -> Status {
+        Status {
+            raw: ClibStatus {
+                _bindgen_opaque_blob: 0,
+            },
+        }
+    }
+}
+
+// 按字段比较，不依赖 C 怎样摆放这些位
+// Compares field by field, without depending on how C places the bits
+impl PartialEq for Status {
+    fn eq(&self, other: &Status) -> bool {
+        (
+            self.ready(),
+            self.error(),
+            self.mode(),
+            self.channel(),
+            self.count(),
+        ) == (
+            other.ready(),
+            other.error(),
+            other.mode(),
+            other.channel(),
+            other.count(),
+        )
+    }
+}
+
+impl Eq for Status {}
+
+fn set(
+    raw: &mut ClibStatus,
+    setter: unsafe extern "C" fn(*mut ClibStatus, u32) -> i32,
+    field: &'static str,
+    value: u32,
+) -> Result<(), OutOfRange> {
+    match unsafe { setter(raw, value) } {
+        0 => Ok(()),
+        _ => Err(OutOfRange { field, value }),
+    }
+}
+
+impl Status {
+    /// Checks that C's `ClibStatus` has the size and alignment of the storage Rust holds, which
+    /// the accessors rely on.
+    pub fn layout_matches() -> bool {
+        unsafe {
+            clib_status_size() == std::mem::size_of::<ClibStatus>()
+                && clib_status_align() == std::mem::align_of::<ClibStatus>()
+        }
+    }
+
+    /// The register `clib_status_example` fills in on the C side.
+    pub fn example() -> Status {
+        let mut status = Status::default();
+        unsafe { clib_status_example(&mut status.raw) };
+        status
+    }
+
+    pub fn ready(&self) -> bool {
+        unsafe { clib_status_get_ready(&self.raw) != 0 }
+    }
+
+    pub fn set_ready(&mut self, ready: bool) {
+        unsafe { clib_status_set_ready(&mut self.raw, ready as u32) };
+    }
+
+    pub fn error(&self) -> bool {
+        unsafe { clib_status_get_error(&self.raw) != 0 }
+    }
+
+    pub fn set_error(&mut self, error: bool) {
+        unsafe { clib_status_set_error(&mut self.raw, error as u32) };
+    }
+
+    /// The 3-bit mode.
+    pub fn mode(&self) -> u32 {
+        unsafe { clib_status_get_mode(&self.raw) }
+    }
+
+    pub fn set_mode(&mut self, mode: u32) -> Result<(), OutOfRange> {
+        set(&mut self.raw, clib_status_set_mode, "mode", mode)
+    }
+
+    /// The 4-bit channel.
+    pub fn channel(&self) -> u32 {
+        unsafe { clib_status_get_channel(&self.raw) }
+    }
+
+    pub fn set_channel(&mut self, channel: u32) -> Result<(), OutOfRange> {
+        set(&mut self.raw, clib_status_set_channel, "channel", channel)
+    }
+
+    /// The 12-bit count.
+    pub fn count(&self) -> u32 {
+        unsafe { clib_status_get_count(&self.raw) }
+    }
+
+    pub fn set_count(&mut self, count: u32) -> Result<(), OutOfRange> {
+        set(&mut self.raw, clib_status_set_count, "count", count)
+    }
+}
+
+impl fmt::Debug for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Status")
+            .field("ready", &self.ready())
+            .field("error", &self.error())
+            .field("mode", &self.mode())
+            .field("channel", &self.channel())
+            .field("count", &self.count())
+            .finish()
+    }
+}
+
+pub fn bitfield_demo() {
+    println!("[Rust] Using a C bitfield struct through accessor shims");
+    if !Status::layout_matches() {
+        eprintln!("[Rust] ClibStatus is not laid out like one unsigned int\n");
+        return;
+    }
+    let mut status = Status::example();
+    println!("[Rust] C filled in {:?}", status);
+    status.set_ready(false);
+    status.set_error(true);
+    if let Err(err) = status.set_channel(16) {
+        println!("[Rust] Refused: {}", err);
+    }
+    let result = status
+        .set_mode(2)
+        .and_then(|()| status.set_count(status.count() + 95));
+    println!("[Rust] After the updates: {:?}, {:?}\n", status, result);
+}
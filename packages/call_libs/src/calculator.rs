@@ -0,0 +1,83 @@
+// 在 Rust 调用端包装 cdylib_gen 的 Calculator 句柄，Drop 时自动调用 rxc_calc_free
+// Wraps cdylib_gen's Calculator handle on the Rust caller side, rxc_calc_free runs on Drop
+
+use std::ffi::{c_char, c_int, CStr};
+use std::ptr;
+
+use interop_common::FfiHandle;
+
+extern "C" {
+    fn rxc_calc_new() -> FfiHandle;
+    fn rxc_calc_add(handle: FfiHandle, a: c_int, b: c_int, sum: *mut c_int) -> c_int;
+    fn rxc_calc_history(
+        handle: FfiHandle,
+        buf: *mut c_char,
+        len: usize,
+        required_len: *mut usize,
+    ) -> c_int;
+    fn rxc_calc_free(handle: FfiHandle) -> c_int;
+}
+
+/// Owns a `CalculatorHandle` from cdylib_gen; the handle is freed exactly once, on Drop.
+pub struct Calculator {
+    handle: FfiHandle,
+}
+
+impl Calculator {
+    pub fn new() -> Self {
+        let handle = unsafe { rxc_calc_new() };
+        assert!(!handle.is_null(), "rxc_calc_new returned the null handle.");
+        Calculator { handle }
+    }
+
+    /// Returns the sum, or the FfiStatus code reported by the library.
+    pub fn add(&mut self, a: c_int, b: c_int) -> Result<c_int, c_int> {
+        let mut sum = 0;
+        match unsafe { rxc_calc_add(self.handle, a, b, &mut sum) } {
+            0 => Ok(sum),
+            status => Err(status),
+        }
+    }
+
+    /// Reads the history with two calls: one to get its length, one with a buffer that fits.
+    pub fn history(&self) -> String {
+        let mut required = 0;
+        unsafe { rxc_calc_history(self.handle, ptr::null_mut(), 0, &mut required) };
+
+        let mut buf = vec![0 as c_char; required + 1];
+        unsafe { rxc_calc_history(self.handle, buf.as_mut_ptr(), buf.len(), ptr::null_mut()) };
+        unsafe { CStr::from_ptr(buf.as_ptr()) }
+            .to_string_lossy()
+            .into_owned()
+    }
+}
+
+impl Drop for Calculator {
+    fn drop(&mut self) {
+        unsafe { rxc_calc_free(self.handle) };
+    }
+}
+
+pub fn calculator_demo() {
+    println!("[Rust] Using the opaque Calculator handle from dynamic library");
+    let mut calc = Calculator::new();
+    for (a, b) in [(1, 2), (10, 20), (c_int::MAX, 1)] {
+        match calc.add(a, b) {
+            Ok(sum) => println!("[Rust] rxc_calc_add({a}, {b}) = {sum}"),
+            Err(status) => println!("[Rust] rxc_calc_add({a}, {b}) failed with status {status}"),
+        }
+    }
+    println!("[Rust] Calculator history:\n{}", calc.history());
+    // calc 在这里离开作用域，句柄通过 rxc_calc_free 释放
+    // calc goes out of scope here and the handle is released through rxc_calc_free
+    drop(calc);
+
+    // 句柄释放后就过期了，重复释放会被识别出来，不会破坏内存
+    // A handle goes stale once freed, so freeing it again is caught instead of corrupting memory
+    let stale = unsafe { rxc_calc_new() };
+    unsafe { rxc_calc_free(stale) };
+    let status = unsafe { rxc_calc_free(stale) };
+    println!(
+        "[Rust] Freeing a calculator twice: the second rxc_calc_free returned status {status}\n"
+    );
+}
@@ -0,0 +1,37 @@
+// 向 cdylib_gen 注册完成回调，上下文指针是交给回调的 Box<mpsc::Sender>
+// Registers a completion callback with cdylib_gen, the context pointer is a Box<mpsc::Sender>
+// handed over to the callback
+
+use std::ffi::{c_int, c_void};
+use std::sync::mpsc::{self, Sender};
+
+type AddCallback = Option<extern "C" fn(sum: c_int, user_data: *mut c_void)>;
+
+extern "C" {
+    fn rxc_cdylib_add_async(a: c_int, b: c_int, cb: AddCallback, user_data: *mut c_void) -> c_int;
+}
+
+extern "C" fn on_done(sum: c_int, user_data: *mut c_void) {
+    // user_data 是 callback_demo 交出的 Box<Sender>，回调只运行一次，在这里收回：
+    // 工作线程发送时 callback_demo 可能已经返回
+    // user_data is the Box<Sender> callback_demo handed over; the callback runs once, so it is
+    // reclaimed here, since callback_demo may have returned while the worker is still sending
+    let tx = unsafe { Box::from_raw(user_data as *mut Sender<c_int>) };
+    let _ = tx.send(sum);
+}
+
+pub fn callback_demo() {
+    println!("[Rust] Calling function with a callback in dynamic library");
+    let (tx, rx) = mpsc::channel::<c_int>();
+    let user_data = Box::into_raw(Box::new(tx)) as *mut c_void;
+    let status = unsafe { rxc_cdylib_add_async(20, 22, Some(on_done), user_data) };
+    if status != 0 {
+        // 失败时回调不会运行，Sender 还归这里
+        // The callback never runs on failure, so the Sender is still ours
+        drop(unsafe { Box::from_raw(user_data as *mut Sender<c_int>) });
+        println!("[Rust] rxc_cdylib_add_async failed with status {status}\n");
+        return;
+    }
+    let sum = rx.recv().expect("The callback was never invoked.");
+    println!("[Rust] Result from the callback: {sum}\n");
+}
@@ -0,0 +1,55 @@
+// 协作式取消：一个线程运行 rxc_cdylib_slow_sum，主线程过一会儿通过令牌取消它
+// Cooperative cancellation: one thread runs rxc_cdylib_slow_sum, the main thread cancels it through
+// the token a little later
+
+use std::ffi::{c_int, c_long};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use interop_common::FfiHandle;
+
+// 和 include/cdylib_gen.h 中 FFI_STATUS_CANCELLED 的值相同
+// The value of FFI_STATUS_CANCELLED in include/cdylib_gen.h
+const STATUS_CANCELLED: c_int = 12;
+
+extern "C" {
+    fn rxc_cancel_token_new() -> FfiHandle;
+    fn rxc_cancel_token_cancel(token: FfiHandle) -> c_int;
+    fn rxc_cancel_token_free(token: FfiHandle) -> c_int;
+    fn rxc_cdylib_slow_sum(
+        values: *const c_int,
+        len: usize,
+        delay_ms: u32,
+        token: FfiHandle,
+        total: *mut c_long,
+    ) -> c_int;
+}
+
+pub fn cancel_demo() {
+    println!("[Rust] Cancelling a long-running call in dynamic library");
+    let token = unsafe { rxc_cancel_token_new() };
+    if token.is_null() {
+        println!("[Rust] rxc_cancel_token_new failed\n");
+        return;
+    }
+    let start = Instant::now();
+    let worker = thread::spawn(move || {
+        let values = [1; 100];
+        let mut total = 0;
+        let status =
+            unsafe { rxc_cdylib_slow_sum(values.as_ptr(), values.len(), 50, token, &mut total) };
+        (status, total)
+    });
+    thread::sleep(Duration::from_millis(120));
+    unsafe { rxc_cancel_token_cancel(token) };
+    let (status, total) = worker.join().expect("The worker thread panicked.");
+    unsafe { rxc_cancel_token_free(token) };
+    if status == STATUS_CANCELLED {
+        println!(
+            "[Rust] rxc_cdylib_slow_sum was cancelled after {} ms instead of running for 5 s\n",
+            start.elapsed().as_millis()
+        );
+    } else {
+        println!("[Rust] rxc_cdylib_slow_sum returned status {status}, total {total}\n");
+    }
+}
@@ -0,0 +1,43 @@
+// 调用 abi_caveats：long double 和 __int128 在这个平台上的布局，以及通过指针和按值传递的结果
+// Calls abi_caveats: the layouts of long double and __int128 on this platform, and the results of
+// passing them through pointers and by value
+
+use abi_caveats::{
+    int128_layout, long_double_layout, long_double_mantissa_digits, mul_wide, LongDouble,
+};
+
+pub fn caveats_demo() {
+    println!("[Rust] Scalars whose ABI differs between platforms");
+    let layout = long_double_layout();
+    println!(
+        "[Rust] long double is {} bytes aligned to {}, with {} bits of mantissa",
+        layout.size,
+        layout.align,
+        long_double_mantissa_digits()
+    );
+    let third = LongDouble::from_f64(1.0 / 3.0);
+    println!(
+        "[Rust] 1/3 + 1/3 added by C through pointers: {}",
+        (third + third).to_f64()
+    );
+    match int128_layout() {
+        Some(layout) => println!(
+            "[Rust] __int128 is {} bytes aligned to {}, u128 is {} aligned to {}",
+            layout.size,
+            layout.align,
+            std::mem::size_of::<u128>(),
+            std::mem::align_of::<u128>()
+        ),
+        None => println!("[Rust] The C compiler has no __int128"),
+    }
+    println!(
+        "[Rust] u64::MAX squared through u64 halves: {}",
+        mul_wide(u64::MAX, u64::MAX)
+    );
+    #[cfg(all(unix, any(target_arch = "x86_64", target_arch = "aarch64")))]
+    println!(
+        "[Rust] u64::MAX squared by value: {}",
+        abi_caveats::mul_by_value(u64::MAX, u64::MAX)
+    );
+    println!();
+}
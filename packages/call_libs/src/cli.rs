@@ -0,0 +1,146 @@
+// call_libs 的命令行参数
+// Command-line arguments of call_libs
+
+use std::ffi::c_int;
+use std::path::PathBuf;
+
+use clap::{CommandFactory, Parser, ValueEnum};
+
+/// The ways call_libs can call into native code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Backend {
+    /// C source compiled into the binary by build.rs.
+    CSource,
+    /// The Rust staticlib linked at build time.
+    Static,
+    /// The Rust cdylib linked at build time.
+    Dynamic,
+    /// The external C library loaded at runtime with libloading.
+    Dlopen,
+    /// wasm_gen running in a wasmtime sandbox (requires the wasm feature).
+    Wasm,
+}
+
+/// Flags for loading the external library, dlopen's on Unix and LoadLibraryExW's on Windows.
+///
+/// Each one only applies on its own platform and is ignored elsewhere.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum LoadFlag {
+    /// RTLD_NOW: resolve every undefined symbol while loading (Unix).
+    Now,
+    /// RTLD_LAZY: resolve functions on their first call, the default (Unix).
+    Lazy,
+    /// RTLD_GLOBAL: let libraries loaded later resolve their symbols against this one (Unix).
+    Global,
+    /// RTLD_LOCAL: only reach the symbols through this handle, the default (Unix).
+    Local,
+    /// LOAD_WITH_ALTERED_SEARCH_PATH: search the DLL's own directory for its dependencies
+    /// (Windows).
+    AlteredSearchPath,
+    /// LOAD_LIBRARY_SEARCH_DLL_LOAD_DIR with the default directories: search only the DLL's
+    /// directory and the safe defaults for its dependencies; needs an absolute path (Windows).
+    SearchDllLoadDir,
+}
+
+/// Calls C code and Rust libraries through every interop path shown in this guide.
+#[derive(Debug, Parser)]
+#[command(version)]
+pub struct Args {
+    /// Backends to run, comma separated or repeated; all of them by default.
+    #[arg(long = "backend", value_enum, value_delimiter = ',')]
+    pub backends: Vec<Backend>,
+
+    /// First operand, overriding each backend's default.
+    #[arg(short, allow_negative_numbers = true)]
+    pub a: Option<c_int>,
+
+    /// Second operand, overriding each backend's default.
+    #[arg(short, allow_negative_numbers = true)]
+    pub b: Option<c_int>,
+
+    /// Name passed to the libraries, overriding each backend's default.
+    #[arg(short, long)]
+    pub name: Option<String>,
+
+    /// Initial result buffer size for the static and dynamic backends, which grow it on demand.
+    /// The C source and dlopen backends cannot be told the size and always use 1024 bytes.
+    #[arg(long)]
+    pub buffer_size: Option<usize>,
+
+    /// Directory searched first for the external dynamic library.
+    #[arg(long, env = "CALL_LIBS_LIB_PATH")]
+    pub lib_path: Option<PathBuf>,
+
+    /// Flags the external dynamic library is loaded with, comma separated or repeated.
+    #[arg(long = "load-flag", value_enum, value_delimiter = ',')]
+    pub load_flags: Vec<LoadFlag>,
+
+    /// With the dlopen backend, also load two builds of the external library into separate
+    /// link-map namespaces with dlmopen (Linux with glibc only).
+    #[arg(long)]
+    pub dlmopen: bool,
+
+    /// Run the plugin mode instead: load every plugin in these directories and call it.
+    #[arg(long, value_name = "DIR")]
+    pub plugins: Vec<PathBuf>,
+
+    /// Run the watch mode instead: reload the external library whenever it changes on disk.
+    #[arg(long)]
+    pub watch: bool,
+
+    /// Skip the struct, callback, handle and string examples that follow the backends.
+    #[arg(long)]
+    pub no_examples: bool,
+}
+
+impl Args {
+    pub fn parse_checked() -> Self {
+        let args = Args::parse();
+        if let (Some(size), Some(name)) = (args.buffer_size, &args.name) {
+            if size <= name.len() {
+                Args::command()
+                    .error(
+                        clap::error::ErrorKind::ValueValidation,
+                        format!("--buffer-size {size} cannot hold the name and its NUL terminator"),
+                    )
+                    .exit();
+            }
+        }
+        let name = |flag: LoadFlag| flag.to_possible_value().unwrap().get_name().to_owned();
+        for (a, b) in [
+            (LoadFlag::Now, LoadFlag::Lazy),
+            (LoadFlag::Global, LoadFlag::Local),
+        ] {
+            if args.load_flags.contains(&a) && args.load_flags.contains(&b) {
+                Args::command()
+                    .error(
+                        clap::error::ErrorKind::ArgumentConflict,
+                        format!("--load-flag {} and {} contradict each other", name(a), name(b)),
+                    )
+                    .exit();
+            }
+        }
+        args
+    }
+
+    pub fn runs(&self, backend: Backend) -> bool {
+        self.backends.is_empty() || self.backends.contains(&backend)
+    }
+
+    pub fn a_or(&self, default: c_int) -> c_int {
+        self.a.unwrap_or(default)
+    }
+
+    pub fn b_or(&self, default: c_int) -> c_int {
+        self.b.unwrap_or(default)
+    }
+
+    pub fn name_or<'a>(&'a self, default: &'a str) -> &'a str {
+        self.name.as_deref().unwrap_or(default)
+    }
+
+    /// The first buffer size tried for `name`: the requested one, or just enough for the name.
+    pub fn initial_capacity(&self, name: &str) -> usize {
+        self.buffer_size.unwrap_or(name.len() + 1).max(name.len() + 1)
+    }
+}
@@ -0,0 +1,14 @@
+// 由 bindgen 从 c/clib.h 生成的绑定
+// Bindings generated by bindgen from c/clib.h
+//
+// 开启 `bindgen` feature 时在构建期重新生成，否则使用 bindings/clib.rs 中提交的版本
+// With the `bindgen` feature they are regenerated at build time, otherwise the committed copy
+// in bindings/clib.rs is used
+
+#![allow(non_upper_case_globals, non_camel_case_types, non_snake_case, dead_code)]
+
+#[cfg(feature = "bindgen")]
+include!(concat!(env!("OUT_DIR"), "/clib.rs"));
+
+#[cfg(not(feature = "bindgen"))]
+include!("../bindings/clib.rs");
@@ -0,0 +1,25 @@
+// 调用 condvar_interop：clib.c 启动的 C 线程每准备好一项工作就通过条件变量唤醒这里等待的 Rust 线程
+// Calls condvar_interop: a C thread started by clib.c wakes the Rust thread waiting here through a
+// condition variable whenever it has a piece of work ready
+
+use condvar_interop::{work_queue_close, work_queue_push, WorkQueue};
+
+use crate::clib::clib_spawn_producer;
+
+pub fn condvar_demo() {
+    println!("[Rust] Waiting on a condition variable for work from a C thread");
+    let queue = WorkQueue::new();
+    let ctx = queue.producer();
+    if unsafe { clib_spawn_producer(3, 10, Some(work_queue_push), Some(work_queue_close), ctx) }
+        != 0
+    {
+        // SAFETY: C never got the context
+        unsafe { WorkQueue::reclaim(ctx) };
+        eprintln!("[Rust] C could not start its thread\n");
+        return;
+    }
+    while let Some(work) = queue.pop() {
+        println!("[Rust] Woken up for work {} from C", work);
+    }
+    println!("[Rust] C closed the queue\n");
+}
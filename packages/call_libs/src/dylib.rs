@@ -0,0 +1,233 @@
+// external_dy 的类型化函数表：所有符号名和签名集中在这里，打开库时一次性解析
+// Typed function table for external_dy: every symbol name and signature lives here and is
+// resolved once when the library is opened
+
+use std::{
+    ffi::{c_char, c_int, c_void},
+    fmt,
+    path::{Path, PathBuf},
+};
+
+use dyload::{LibraryHandle, WeakLibraryHandle};
+use interop_common::{with_callback, CBuffer, CtxCallback, FfiError};
+use libloading::Library;
+
+use crate::resolve::{open_lib, LoadFlags, ResolveError};
+
+// 按编译目标而不是主机选择，交叉编译出的程序查找的是目标平台上的文件名
+// Picked for the compilation target rather than the host, so a cross-compiled program looks for
+// the target platform's file name
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+pub const LIB_FILE: &str = "libexternal_dy.so";
+#[cfg(target_os = "macos")]
+pub const LIB_FILE: &str = "libexternal_dy.dylib";
+#[cfg(target_os = "windows")]
+pub const LIB_FILE: &str = "external_dy.dll";
+
+// 下面的签名对应这个版本的 external_lib/dylib.c
+// The signatures below match this version of external_lib/dylib.c
+const DYLOADING_ABI_VERSION: u32 = 1;
+
+type DyloadingAbiVersion = unsafe extern "C" fn() -> u32;
+type DyloadingAdd = unsafe extern "C" fn(c_int, c_int, *mut c_char) -> c_int;
+type DyloadingAddV2 =
+    unsafe extern "C" fn(c_int, c_int, *const c_char, *mut c_char, usize) -> c_int;
+type DyloadingCallCount = unsafe extern "C" fn() -> c_int;
+type DyloadingForEach =
+    unsafe extern "C" fn(c_int, c_int, CtxCallback<c_int, c_int>, *mut c_void) -> c_int;
+
+// dyloading_add 用 sprintf 写结果且不知道缓冲区大小，这个容量足以容纳任意 int32 的结果
+// dyloading_add writes with sprintf without knowing the buffer size; this capacity fits the
+// result for any pair of int32 operands
+const ADD_RESULT_CAPACITY: usize = 1024;
+
+// dyloading_add_v2 按缓冲区大小截断结果，不必为最坏情况预留空间
+// dyloading_add_v2 truncates the result to the buffer size, so there's no need to reserve room for
+// the worst case
+const ADD_V2_RESULT_CAPACITY: usize = 128;
+
+// static-external 特性打开时 external_lib/dylib.c 被静态链接进来，这些符号由链接器在构建时解析
+// With the static-external feature external_lib/dylib.c is linked in statically, and these
+// symbols are resolved by the linker at build time
+#[cfg(feature = "static-external")]
+extern "C" {
+    fn dyloading_abi_version() -> u32;
+    fn dyloading_add(a: c_int, b: c_int, result: *mut c_char) -> c_int;
+    fn dyloading_add_v2(
+        a: c_int,
+        b: c_int,
+        name: *const c_char,
+        result: *mut c_char,
+        result_len: usize,
+    ) -> c_int;
+    fn dyloading_call_count() -> c_int;
+    fn dyloading_for_each(
+        from: c_int,
+        to: c_int,
+        visit: CtxCallback<c_int, c_int>,
+        ctx: *mut c_void,
+    ) -> c_int;
+}
+
+/// Why [`DyLib::open`] failed.
+pub enum LoadError {
+    Resolve(ResolveError),
+    AbiMismatch {
+        expected: u32,
+        found: u32,
+    },
+    Symbol {
+        name: &'static str,
+        source: libloading::Error,
+    },
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LoadError::Resolve(err) => err.fmt(f),
+            LoadError::AbiMismatch { expected, found } => write!(
+                f,
+                "{} has ABI version {}, but call_libs was built for version {}",
+                LIB_FILE, found, expected
+            ),
+            LoadError::Symbol { name, source } => {
+                write!(f, "{} has no usable {} symbol: {}", LIB_FILE, name, source)
+            }
+        }
+    }
+}
+
+/// The external dynamic library, opened once with all of its symbols resolved.
+pub struct DyLib {
+    add: DyloadingAdd,
+    // 较早的构建没有 dyloading_add_v2，此时为 None
+    // None for older builds, which lack dyloading_add_v2
+    add_v2: Option<DyloadingAddV2>,
+    call_count: DyloadingCallCount,
+    for_each: DyloadingForEach,
+    path: Option<PathBuf>,
+    // 函数指针只在库保持加载期间有效；静态链接的副本没有 Library
+    // The function pointers are only valid while the library stays loaded; the statically linked
+    // copy has no Library
+    lib: Option<LibraryHandle>,
+}
+
+impl DyLib {
+    /// Finds and loads the library with `flags`, see [`open_lib`] for where it looks.
+    pub fn open(lib_dir: Option<&Path>, flags: LoadFlags) -> Result<DyLib, LoadError> {
+        // SAFETY: external_dy has no initialisers, and the types below match external_lib/dylib.c
+        unsafe {
+            let (lib, path) = open_lib(lib_dir, LIB_FILE, flags).map_err(LoadError::Resolve)?;
+            DyLib::from_library(lib, path)
+        }
+    }
+
+    /// Binds a build of the library that is already loaded, such as one `dlmopen` loaded into a
+    /// namespace of its own.
+    ///
+    /// # Safety
+    ///
+    /// `lib` must be a build of external_lib/dylib.c.
+    pub unsafe fn from_library(lib: Library, path: Option<PathBuf>) -> Result<DyLib, LoadError> {
+        // 先握手，版本不符时其他符号的签名都不可信
+        // Handshake first, the other symbols' signatures cannot be trusted on a mismatch
+        let abi_version: DyloadingAbiVersion = symbol(&lib, "dyloading_abi_version")?;
+        let found = abi_version();
+        if found != DYLOADING_ABI_VERSION {
+            return Err(LoadError::AbiMismatch {
+                expected: DYLOADING_ABI_VERSION,
+                found,
+            });
+        }
+        Ok(DyLib {
+            add: symbol(&lib, "dyloading_add")?,
+            add_v2: optional_symbol(&lib, "dyloading_add_v2"),
+            call_count: symbol(&lib, "dyloading_call_count")?,
+            for_each: symbol(&lib, "dyloading_for_each")?,
+            path,
+            lib: Some(LibraryHandle::new(lib)),
+        })
+    }
+
+    /// Binds the copy of the library the static-external feature links into the executable,
+    /// with the same handshake as [`DyLib::open`].
+    #[cfg(feature = "static-external")]
+    pub fn linked() -> Result<DyLib, LoadError> {
+        // SAFETY: the declarations above match external_lib/dylib.c, which has no initialisers
+        let found = unsafe { dyloading_abi_version() };
+        if found != DYLOADING_ABI_VERSION {
+            return Err(LoadError::AbiMismatch {
+                expected: DYLOADING_ABI_VERSION,
+                found,
+            });
+        }
+        Ok(DyLib {
+            add: dyloading_add,
+            add_v2: Some(dyloading_add_v2),
+            call_count: dyloading_call_count,
+            for_each: dyloading_for_each,
+            path: None,
+            lib: None,
+        })
+    }
+
+    /// Calls `dyloading_add_v2`, or `dyloading_add` when the library is too old to have it, which
+    /// greets `name` and returns the sum with its message.
+    pub fn add(&self, a: c_int, b: c_int, name: &str) -> Result<(c_int, String), FfiError> {
+        if let Some(add_v2) = self.add_v2 {
+            let name = CBuffer::new(name, name.len() + 1)?;
+            let mut buf = CBuffer::with_capacity(ADD_V2_RESULT_CAPACITY)?;
+            // SAFETY: both buffers are NUL-terminated and dyloading_add_v2 writes at most the
+            // capacity it is given
+            let sum = unsafe {
+                add_v2(a, b, name.as_c_ptr(), buf.as_mut_c_ptr(), buf.capacity())
+            };
+            return Ok((sum, buf.to_str()?.to_owned()));
+        }
+        let mut buf = CBuffer::new(name, ADD_RESULT_CAPACITY.max(name.len() + 1))?;
+        // SAFETY: the buffer holds a NUL-terminated name and has room for any result message
+        let sum = unsafe { (self.add)(a, b, buf.as_mut_c_ptr()) };
+        Ok((sum, buf.to_str()?.to_owned()))
+    }
+
+    /// Whether the library has `dyloading_add_v2`, which [`DyLib::add`] prefers.
+    pub fn has_add_v2(&self) -> bool {
+        self.add_v2.is_some()
+    }
+
+    /// The file the library was loaded from, unless the system loader found it.
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    /// A reference to the loaded library that doesn't keep it loaded, or `None` for the statically
+    /// linked copy.
+    pub fn downgrade(&self) -> Option<WeakLibraryHandle> {
+        self.lib.as_ref().map(LibraryHandle::downgrade)
+    }
+
+    /// How many times `dyloading_add` ran since this copy of the library was loaded.
+    pub fn call_count(&self) -> c_int {
+        // SAFETY: dyloading_call_count only reads a static
+        unsafe { (self.call_count)() }
+    }
+
+    /// Calls `dyloading_for_each`, visiting `[from, to]` with `visit` until it returns non-zero.
+    pub fn for_each(&self, from: c_int, to: c_int, visit: impl FnMut(c_int) -> c_int) -> c_int {
+        // SAFETY: with_callback keeps the context alive for the whole call
+        with_callback(visit, |cb, ctx| unsafe { (self.for_each)(from, to, cb, ctx) })
+    }
+}
+
+// 可选的符号缺失不是错误，调用方退回旧的函数
+// A missing optional symbol is not an error, callers fall back to the older function
+unsafe fn optional_symbol<T: Copy>(lib: &Library, name: &str) -> Option<T> {
+    lib.get::<T>(name.as_bytes()).ok().map(|symbol| *symbol)
+}
+
+unsafe fn symbol<T: Copy>(lib: &Library, name: &'static str) -> Result<T, LoadError> {
+    lib.get::<T>(name.as_bytes())
+        .map(|symbol| *symbol)
+        .map_err(|source| LoadError::Symbol { name, source })
+}
@@ -0,0 +1,28 @@
+// 调用 fd_interop：C 打开的文件先借给 Rust、再交给 Rust 关闭，Rust 打开的文件先借给 C、再交给 C 关闭
+// Calls fd_interop: a file C opened is lent to Rust and then handed to Rust to close, and a file
+// Rust opened is lent to C and then handed to C to close
+
+use std::fs;
+
+use fd_interop::{c_puts_borrowed, c_puts_transferred, c_share_file};
+
+pub fn files_demo() {
+    println!("[Rust] Passing open files between C and Rust");
+    let path = std::env::temp_dir().join(format!("call_libs_files_{}", std::process::id()));
+    match c_share_file(&path) {
+        Ok(contents) => print!("[Rust] C's file, closed by Rust, contains:\n{}", contents),
+        Err(err) => eprintln!("[Rust] Sharing C's file failed: {}", err),
+    }
+
+    let result = fs::File::create(&path).and_then(|file| {
+        c_puts_borrowed(&file, "[C source] written through a borrowed file\n")?;
+        c_puts_transferred(file, "[C source] written through a transferred file\n")?;
+        fs::read_to_string(&path)
+    });
+    match result {
+        Ok(contents) => print!("[Rust] Rust's file, closed by C, contains:\n{}", contents),
+        Err(err) => eprintln!("[Rust] Lending the file to C failed: {}", err),
+    }
+    let _ = fs::remove_file(&path);
+    println!();
+}
@@ -0,0 +1,61 @@
+// RTLD_GLOBAL 的演示：external_lib/dependent.c 编译出的库不链接 external_dy，却用到它的
+// dyloading_call_count。只有 external_dy 以 RTLD_GLOBAL 加载时，动态链接器才会拿它的符号解析后加载的库
+// The RTLD_GLOBAL demo: the library built from external_lib/dependent.c isn't linked against
+// external_dy but uses its dyloading_call_count. The dynamic linker only resolves a library loaded
+// later against external_dy's symbols when external_dy was loaded with RTLD_GLOBAL
+
+use std::{ffi::c_int, path::Path};
+
+use crate::cli::LoadFlag;
+use crate::dylib::DyLib;
+use crate::resolve::LoadFlags;
+
+// build.rs 把 dependent.c 编译到这里
+// build.rs compiles dependent.c into here
+const OUT_DIR: &str = env!("OUT_DIR");
+const DEPENDENT_FILE: &str = "libexternal_dependent.so";
+
+type DependentCallCount = unsafe extern "C" fn() -> c_int;
+
+/// Loads the dependent library after `lib`, which `flags` loaded, and reads `lib`'s call count
+/// through it.
+pub fn global_symbols_demo(lib: &DyLib, flags: &[LoadFlag]) {
+    println!(
+        "[Rust] Loading {}, which leaves dyloading_call_count to the dynamic linker",
+        DEPENDENT_FILE
+    );
+    // RTLD_NOW 让缺少的符号在加载时就失败，而不是在第一次调用时终止进程
+    // RTLD_NOW makes a missing symbol fail the load rather than kill the process on the first call
+    let path = Path::new(OUT_DIR).join(DEPENDENT_FILE);
+    let dependent = match unsafe { LoadFlags::new(&[LoadFlag::Now]).open(&path) } {
+        Ok(dependent) => dependent,
+        Err(err) => {
+            let hint = if flags.contains(&LoadFlag::Global) {
+                ""
+            } else {
+                ", run with --load-flag global to make external_dy's symbols available"
+            };
+            println!("[Rust] The load failed{}: {}\n", hint, err);
+            return;
+        }
+    };
+    // SAFETY: the signature matches external_lib/dependent.c
+    let count = unsafe {
+        match dependent.get::<DependentCallCount>(b"dependent_call_count\0") {
+            Ok(call_count) => call_count(),
+            Err(err) => {
+                eprintln!(
+                    "[Rust] {} has no dependent_call_count: {}\n",
+                    DEPENDENT_FILE, err
+                );
+                return;
+            }
+        }
+    };
+    println!(
+        "[Rust] {} sees {} call(s) through external_dy's symbols, external_dy counts {}\n",
+        DEPENDENT_FILE,
+        count,
+        lib.call_count()
+    );
+}
@@ -0,0 +1,74 @@
+// 接收 cdylib_gen 分配的字符串，Drop 时交还给 rxc_rust_free
+// Receives strings allocated by cdylib_gen and hands them back to rxc_rust_free on Drop
+
+use std::ffi::{c_char, c_int, c_void, CStr, CString};
+use std::ptr::NonNull;
+
+use crate::{STATUS_BUFFER_TOO_SMALL, STATUS_OK};
+
+extern "C" {
+    fn rxc_cdylib_make_greeting(name: *const c_char) -> *mut c_char;
+    fn rxc_rust_free(ptr: *mut c_void);
+    fn rxc_cdylib_greeting_message(
+        name: *const c_char,
+        buf: *mut c_char,
+        len: usize,
+        required_len: *mut usize,
+    ) -> c_int;
+}
+
+/// A string owned by cdylib_gen's allocator.
+pub struct RustString(NonNull<c_char>);
+
+impl RustString {
+    pub fn as_c_str(&self) -> &CStr {
+        unsafe { CStr::from_ptr(self.0.as_ptr()) }
+    }
+}
+
+impl Drop for RustString {
+    fn drop(&mut self) {
+        unsafe { rxc_rust_free(self.0.as_ptr().cast()) }
+    }
+}
+
+pub fn make_greeting(name: &str) -> Option<RustString> {
+    let name = CString::new(name).ok()?;
+    NonNull::new(unsafe { rxc_cdylib_make_greeting(name.as_ptr()) }).map(RustString)
+}
+
+/// 两次调用的模式：先用空缓冲区查询长度，再分配刚好足够的缓冲区；长度变化时重复
+/// The two-call pattern: query the length with an empty buffer, then allocate exactly enough;
+/// repeat if the length changed in between.
+pub fn greeting_message(name: &str) -> Option<String> {
+    let name = CString::new(name).ok()?;
+    let mut buf: Vec<c_char> = Vec::new();
+    let mut required = 0;
+    loop {
+        let status = unsafe {
+            rxc_cdylib_greeting_message(name.as_ptr(), buf.as_mut_ptr(), buf.len(), &mut required)
+        };
+        match status {
+            STATUS_OK => break,
+            STATUS_BUFFER_TOO_SMALL => buf = vec![0; required + 1],
+            _ => return None,
+        }
+    }
+    let msg = unsafe { CStr::from_ptr(buf.as_ptr()) };
+    Some(msg.to_string_lossy().into_owned())
+}
+
+pub fn greeting_demo() {
+    println!("[Rust] Receiving a string allocated by dynamic library");
+    match make_greeting("Zhang") {
+        Some(greeting) => println!("{}", greeting.as_c_str().to_string_lossy()),
+        None => println!("[Rust] rxc_cdylib_make_greeting returned NULL"),
+    }
+    println!("[Rust] The string was released with rxc_rust_free");
+
+    println!("[Rust] Querying the greeting length before allocating the buffer");
+    match greeting_message("Zhao") {
+        Some(greeting) => println!("{}\n", greeting),
+        None => println!("[Rust] rxc_cdylib_greeting_message failed\n"),
+    }
+}
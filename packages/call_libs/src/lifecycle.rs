@@ -0,0 +1,74 @@
+// 在调用库函数之前初始化两个库，退出前再关闭它们。staticlib_gen 的函数都带自己的前缀，
+// 不会和动态库的同名函数混在一起
+// Initializes both libraries before any call into them and shuts them down before exiting.
+// staticlib_gen's functions all carry its own prefix, so they never get mixed up with the dynamic
+// library's
+
+use std::ffi::c_int;
+
+use interop_common::InitConfig;
+
+use crate::logging::print_log;
+
+extern "C" {
+    fn rxc_rustlib_init(config: *const InitConfig) -> c_int;
+    fn rxc_rustlib_shutdown() -> c_int;
+    #[cfg(not(feature = "static"))]
+    fn rxc_staticlib_init(config: *const InitConfig) -> c_int;
+    #[cfg(not(feature = "static"))]
+    fn rxc_staticlib_shutdown() -> c_int;
+}
+
+type Init = unsafe extern "C" fn(*const InitConfig) -> c_int;
+type Shutdown = unsafe extern "C" fn() -> c_int;
+
+#[cfg(not(feature = "static"))]
+const LIBRARIES: [(&str, Init, Shutdown); 2] = [
+    ("cdylib_gen", rxc_rustlib_init, rxc_rustlib_shutdown),
+    ("staticlib_gen", rxc_staticlib_init, rxc_staticlib_shutdown),
+];
+
+// static 特性打开时两个库共用同一份 interop_common，rxc_rustlib_init 一次就初始化了两者，
+// rxc_staticlib_init 只会返回 ALREADY_INITIALIZED
+// With the static feature both libraries share one interop_common, so rxc_rustlib_init initializes
+// both at once and rxc_staticlib_init would only return ALREADY_INITIALIZED
+#[cfg(feature = "static")]
+const LIBRARIES: [(&str, Init, Shutdown); 1] = [(
+    "cdylib_gen and staticlib_gen",
+    rxc_rustlib_init,
+    rxc_rustlib_shutdown,
+)];
+
+pub fn init_libraries() -> Result<(), String> {
+    let config = InitConfig {
+        log_callback: Some(print_log),
+        install_panic_hook: true,
+        worker_threads: 2,
+        ..InitConfig::default()
+    };
+    for (name, init, _) in LIBRARIES {
+        let status = unsafe { init(&config) };
+        if status != 0 {
+            return Err(format!(
+                "Initializing {} failed with status {}",
+                name, status
+            ));
+        }
+    }
+    Ok(())
+}
+
+// 按初始化的相反顺序关闭，rxc_rustlib_shutdown 会等待 rxc_cdylib_add_async 排队的回调
+// Shuts down in the reverse order of initialization, rxc_rustlib_shutdown waits for the callbacks
+// rxc_cdylib_add_async queued
+pub fn shutdown_libraries() {
+    for (name, _, shutdown) in LIBRARIES.into_iter().rev() {
+        let status = unsafe { shutdown() };
+        if status != 0 {
+            eprintln!(
+                "[Rust] Shutting down {} failed with status {}",
+                name, status
+            );
+        }
+    }
+}
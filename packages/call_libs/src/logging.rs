@@ -0,0 +1,21 @@
+// 两个库在 InitConfig 中收到 print_log，把它们的日志打印到 stdout，错误和警告打印到 stderr
+// Both libraries receive print_log in their InitConfig, which prints their log messages to stdout
+// and errors and warnings to stderr
+
+use std::ffi::{c_char, c_void};
+use std::slice;
+
+use interop_common::LogLevel;
+
+pub unsafe extern "C" fn print_log(
+    level: LogLevel,
+    msg: *const c_char,
+    msg_len: usize,
+    _: *mut c_void,
+) {
+    let msg = String::from_utf8_lossy(slice::from_raw_parts(msg as *const u8, msg_len));
+    match level {
+        LogLevel::Error | LogLevel::Warn => eprintln!("{}", msg),
+        _ => println!("{}", msg),
+    }
+}
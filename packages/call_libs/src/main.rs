@@ -2,25 +2,122 @@
 // This is our entry file for calling both static and dynamic libraries
 
 use std::{
-    ffi::{self, c_char, c_int},
-    path::Path,
+    ffi::{c_char, c_int},
+    process,
 };
 
-use libloading::{Library, Symbol};
+use interop_common::CBuffer;
+// static 特性打开时 extern 块中 cdylib_* 和 staticlib_* 的定义来自两个库的 rlib，要让 rustc 链接它们
+// With the static feature the definitions of the cdylib_* and staticlib_* functions in the extern
+// blocks come from the rlibs of the two libraries, which rustc has to be told to link
+#[cfg(feature = "static")]
+use {cdylib_gen as _, staticlib_gen as _};
+
+mod array;
+mod atomics;
+mod bitfield;
+mod calculator;
+mod callback;
+mod cancel;
+mod caveats;
+mod clib;
+mod cli;
+mod condvar;
+mod dylib;
+mod files;
+#[cfg(all(unix, not(target_os = "macos"), not(feature = "static-external")))]
+mod global;
+mod greeting;
+mod lifecycle;
+mod logging;
+mod mapping;
+mod mutex;
+#[cfg(all(target_os = "linux", target_env = "gnu"))]
+mod namespace;
+mod ownership;
+mod plugin;
+mod point;
+mod pool;
+mod progress;
+mod queue;
+mod resolve;
+mod runtime;
+mod shape;
+mod shared_static;
+#[cfg(all(target_os = "linux", not(feature = "static")))]
+mod soname;
+mod strings;
+mod tally;
+mod tls;
+mod trace;
+mod version;
+#[cfg(feature = "wasm")]
+mod wasm;
+mod watch;
+
+use array::array_demo;
+use atomics::atomics_demo;
+use bitfield::bitfield_demo;
+use calculator::calculator_demo;
+use callback::callback_demo;
+use cancel::cancel_demo;
+use caveats::caveats_demo;
+use clib::add;
+use cli::{Args, Backend};
+use condvar::condvar_demo;
+use dylib::DyLib;
+use files::files_demo;
+#[cfg(all(unix, not(target_os = "macos"), not(feature = "static-external")))]
+use global::global_symbols_demo;
+use greeting::greeting_demo;
+use lifecycle::{init_libraries, shutdown_libraries};
+use mapping::mapping_demo;
+use mutex::mutex_demo;
+#[cfg(all(target_os = "linux", target_env = "gnu"))]
+use namespace::dlmopen_demo;
+use ownership::ownership_demo;
+use plugin::plugin_demo;
+use point::struct_demo;
+use pool::pool_demo;
+use progress::progress_demo;
+use queue::queue_demo;
+use resolve::LoadFlags;
+use runtime::async_demo;
+use shape::shape_demo;
+use shared_static::shared_static_demo;
+#[cfg(all(target_os = "linux", not(feature = "static")))]
+use soname::soname_demo;
+use strings::strings_demo;
+use tally::tally_demo;
+use tls::tls_demo;
+use trace::trace_demo;
+use version::{cdylib_load_report_string, cdylib_version_string, check_cdylib_abi};
+#[cfg(feature = "wasm")]
+use wasm::wasm_demo;
+use watch::watch_demo;
 
 extern "C" {
-    fn add(a: c_int, b: c_int, result: *mut c_char) -> c_int;
-    fn cdylib_add(a: c_int, b: c_int, result: *mut c_char) -> c_int;
-    fn staticlib_add(a: c_int, b: c_int, result: *mut c_char) -> c_int;
+    fn rxc_cdylib_add(
+        a: c_int,
+        b: c_int,
+        result: *mut c_char,
+        result_len: usize,
+        sum: *mut c_int,
+        required_len: *mut usize,
+    ) -> c_int;
+    fn rxc_staticlib_add(
+        a: c_int,
+        b: c_int,
+        result: *mut c_char,
+        result_len: usize,
+        sum: *mut c_int,
+        required_len: *mut usize,
+    ) -> c_int;
+    fn rxc_rustlib_last_error_length() -> usize;
+    fn rxc_rustlib_last_error_message(buf: *mut c_char, len: usize) -> c_int;
 }
-fn buf(label: &str, capacity: usize) -> Vec<i8> {
-    let mut b = label.as_bytes().to_vec();
-    let len = b.len();
-    if len < capacity {
-        b.extend_from_slice(&vec![u8::MIN; capacity - len]);
-    };
-
-    b.iter().map(|&i| i as i8).collect()
+fn buf(label: &str, capacity: usize) -> CBuffer {
+    CBuffer::new(label, capacity).expect("The label does not fit in the buffer.")
 }
 
 macro_rules! CallLibFn {
@@ -28,34 +125,203 @@ macro_rules! CallLibFn {
         let mut b = $buf;
 
         println!("[Rust] Calling function in {}", $t);
-        let result = $call_fn($arg1, $arg2, b.as_mut_ptr());
-        let msg = ffi::CStr::from_ptr(b.as_ptr()).to_str().unwrap();
+        let result = $call_fn($arg1, $arg2, b.as_mut_c_ptr());
+        let msg = b.to_str().unwrap();
         println!("{}", msg);
         println!("[Rust] Result from {}: {}\n", $t, result);
     };
 }
-unsafe fn dynamic_load_bind() {
-    #[cfg(target_os = "linux")]
-    let lib_file = "libexternal_dy.so";
-    #[cfg(target_os = "windows")]
-    let lib_file = "external_dy.dll";
-    let lib_path = format!("external_lib/lib_build/{}", lib_file);
-    
-    if Path::new(&lib_path).exists() {
-        let lib = Library::new(lib_path).expect("Failed to load the dynamic library.");
-        type CdylibAdd = unsafe extern "C" fn(c_int, c_int, *mut c_char) -> c_int;
-        let dyloading_add: Symbol<CdylibAdd> = lib
-            .get(b"dyloading_add")
-            .expect("Failed to find the symbol.");
-
-        CallLibFn! { dyloading_add, 8, 9, buf("Jack", 1024), "dynamic loading library" };
+
+// 调用带缓冲区长度参数并返回状态码的函数，状态码与库中的 FfiStatus 保持一致
+// Calls functions taking the buffer length and returning a status code, matching FfiStatus in the libraries
+const STATUS_OK: c_int = 0;
+const STATUS_BUFFER_TOO_SMALL: c_int = 5;
+
+// 缓冲区一开始只够放下名字，库返回 BUFFER_TOO_SMALL 时按它报告的长度重新分配并再调用一次
+// The buffer starts out just large enough for the name; when the library returns BUFFER_TOO_SMALL
+// it is reallocated with the reported length and the call is made again
+macro_rules! CallBoundedLibFn {
+    ($call_fn:expr, $arg1:expr, $arg2:expr, $name:expr, $capacity:expr, $t:expr) => {
+        let mut b = buf($name, $capacity);
+        let mut sum = 0;
+        let mut required = 0;
+
+        println!("[Rust] Calling function in {}", $t);
+        let mut status = $call_fn($arg1, $arg2, b.as_mut_c_ptr(), b.capacity(), &mut sum, &mut required);
+        while status == STATUS_BUFFER_TOO_SMALL {
+            println!("[Rust] A {}-byte buffer is too small, retrying with {} bytes", b.capacity(), required + 1);
+            b = buf($name, required + 1);
+            status = $call_fn($arg1, $arg2, b.as_mut_c_ptr(), b.capacity(), &mut sum, &mut required);
+        }
+        match status {
+            STATUS_OK => {
+                let msg = b.to_str().unwrap();
+                println!("{}", msg);
+                println!("[Rust] Result from {}: {}\n", $t, sum);
+            }
+            _ => println!("[Rust] {} failed with status {}\n", $t, status),
+        }
+    };
+}
+unsafe fn last_error_demo(name: &str, capacity: usize) {
+    println!("[Rust] Calling function in dynamic library with overflowing operands");
+    // 缓冲区和 CallBoundedLibFn! 一样按名字确定大小，太小时按报告的长度重试
+    // The buffer is sized from the name like in CallBoundedLibFn!, and retried with the reported
+    // length when it is too small
+    let mut b = buf(name, capacity);
+    let mut sum = 0;
+    let mut required = 0;
+    let mut status = rxc_cdylib_add(c_int::MAX, 1, b.as_mut_c_ptr(), b.capacity(), &mut sum, &mut required);
+    while status == STATUS_BUFFER_TOO_SMALL {
+        b = buf(name, required + 1);
+        status = rxc_cdylib_add(c_int::MAX, 1, b.as_mut_c_ptr(), b.capacity(), &mut sum, &mut required);
     }
+
+    let mut msg = CBuffer::with_capacity(rxc_rustlib_last_error_length().max(1)).unwrap();
+    rxc_rustlib_last_error_message(msg.as_mut_c_ptr(), msg.capacity());
+    let msg = msg.to_str().unwrap();
+    println!("[Rust] dynamic library failed with status {}: {}\n", status, msg);
 }
+
+fn dynamic_load_bind(args: &Args) {
+    // musl 静态链接的可执行文件里 dlopen 总是失败；glibc 的虽然能加载，但库带着另一份 C 运行时，进程退出时可能崩溃
+    // dlopen always fails in a statically linked musl executable; glibc's does load the library,
+    // but with a second copy of the C runtime that may crash the process as it exits. The copy
+    // static-external links in does not need dlopen
+    if cfg!(all(feature = "static", not(feature = "static-external"))) {
+        eprintln!(
+            "[Rust] Skipping the dynamic loading demo, a statically linked call_libs cannot load libraries\n"
+        );
+        return;
+    }
+    // 下面的代码不关心函数指针来自 dlopen 还是链接器
+    // The code below doesn't care whether the function pointers come from dlopen or the linker
+    #[cfg(feature = "static-external")]
+    let lib = DyLib::linked();
+    #[cfg(not(feature = "static-external"))]
+    let lib = DyLib::open(args.lib_path.as_deref(), LoadFlags::new(&args.load_flags));
+    let lib = match lib {
+        Ok(lib) => lib,
+        Err(err) => {
+            eprintln!("[Rust] Skipping the dynamic loading demo, {}\n", err);
+            return;
+        }
+    };
+
+    println!("[Rust] Calling function in dynamic loading library");
+    // 较早的构建没有 dyloading_add_v2，add 退回 dyloading_add
+    // Older builds lack dyloading_add_v2, and add falls back to dyloading_add
+    if !lib.has_add_v2() {
+        println!("[Rust] dyloading_add_v2 is missing, falling back to dyloading_add");
+    }
+    match lib.add(args.a_or(8), args.b_or(9), args.name_or("Jack")) {
+        Ok((sum, msg)) => {
+            println!("{}", msg);
+            println!("[Rust] Result from dynamic loading library: {}\n", sum);
+        }
+        Err(err) => eprintln!("[Rust] Calling the dynamic loading library failed: {}\n", err),
+    }
+
+    // 把捕获了局部变量的 Rust 闭包作为 C 的 visit 回调传入
+    // Pass a Rust closure capturing a local variable as the C visit callback
+    println!("[Rust] Calling function with a closure in dynamic loading library");
+    let mut seen = Vec::new();
+    let visited = lib.for_each(1, 10, |value| {
+        seen.push(value);
+        c_int::from(value * value > 20)
+    });
+    println!("[Rust] The closure saw {:?}, {} values visited\n", seen, visited);
+
+    // 静态链接的副本不在动态符号表中，后加载的库无论如何都找不到它
+    // The statically linked copy isn't in the dynamic symbol table, a library loaded later can't
+    // find it either way
+    #[cfg(all(unix, not(target_os = "macos"), not(feature = "static-external")))]
+    global_symbols_demo(&lib, &args.load_flags);
+
+    if args.dlmopen {
+        #[cfg(all(target_os = "linux", target_env = "gnu"))]
+        dlmopen_demo();
+        #[cfg(not(all(target_os = "linux", target_env = "gnu")))]
+        eprintln!("[Rust] --dlmopen needs Linux with glibc\n");
+    }
+}
+
 fn main() {
+    let args = Args::parse_checked();
+    if !args.plugins.is_empty() {
+        plugin_demo(&args.plugins, args.a_or(6), args.b_or(7));
+        return;
+    }
+    if args.watch {
+        watch_demo(&args);
+        return;
+    }
+    if let Err(err) = check_cdylib_abi() {
+        eprintln!("[Rust] {}", err);
+        process::exit(1);
+    }
+    if let Err(err) = init_libraries() {
+        eprintln!("[Rust] {}", err);
+        process::exit(1);
+    }
+    if let Some(version) = cdylib_version_string() {
+        println!("[Rust] Using cdylib_gen {}", version);
+    }
+    if let Some(report) = cdylib_load_report_string() {
+        println!("[Rust] cdylib_gen's load-time constructor {}\n", report);
+    }
     unsafe {
-        CallLibFn! { add, 1, 2, buf("Lucy", 1024), "C source code" };
-        CallLibFn! { cdylib_add, 1, 2, buf("Lee", 1024), "dynamic library" };
-        CallLibFn! { staticlib_add, 3, 4, buf("Chen", 1024), "static library" };
-        dynamic_load_bind()
+        if args.runs(Backend::CSource) {
+            CallLibFn! { add, args.a_or(1), args.b_or(2), buf(args.name_or("Lucy"), 1024), "C source code" };
+        }
+        if args.runs(Backend::Dynamic) {
+            let name = args.name_or("Lee");
+            CallBoundedLibFn! { rxc_cdylib_add, args.a_or(1), args.b_or(2), name, args.initial_capacity(name), "dynamic library" };
+            last_error_demo(name, args.initial_capacity(name));
+        }
+        if args.runs(Backend::Static) {
+            let name = args.name_or("Chen");
+            CallBoundedLibFn! { rxc_staticlib_add, args.a_or(3), args.b_or(4), name, args.initial_capacity(name), "static library" };
+        }
+        if args.runs(Backend::Dlopen) {
+            dynamic_load_bind(&args);
+            #[cfg(all(target_os = "linux", not(feature = "static")))]
+            soname_demo();
+        }
+    }
+    // 默认运行全部后端时，没有 wasm 特性就悄悄跳过 wasm
+    // When every backend runs by default, wasm is skipped quietly without the wasm feature
+    if args.runs(Backend::Wasm) && (cfg!(feature = "wasm") || args.backends.contains(&Backend::Wasm)) {
+        #[cfg(feature = "wasm")]
+        wasm_demo(&args);
+        #[cfg(not(feature = "wasm"))]
+        eprintln!("[Rust] The wasm backend needs call_libs built with --features wasm\n");
+    }
+    if !args.no_examples {
+        array_demo();
+        calculator_demo();
+        callback_demo();
+        pool_demo();
+        async_demo();
+        cancel_demo();
+        progress_demo();
+        queue_demo();
+        greeting_demo();
+        strings_demo();
+        struct_demo();
+        bitfield_demo();
+        shape_demo();
+        ownership_demo();
+        tally_demo();
+        shared_static_demo();
+        tls_demo();
+        atomics_demo();
+        mutex_demo();
+        condvar_demo();
+        files_demo();
+        mapping_demo();
+        trace_demo();
+        caveats_demo();
     }
+    shutdown_libraries();
 }
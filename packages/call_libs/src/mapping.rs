@@ -0,0 +1,36 @@
+// 调用 mmap_interop：同一个文件先由 Rust 映射、借给 C 读取，再由 C 映射、交给 Rust 读取，数据都不经过复制
+// Calls mmap_interop: the same file is first mapped by Rust and lent to C to read, then mapped by
+// C and read by Rust, without the data being copied either way
+
+use std::fs;
+
+use mmap_interop::{c_hash_rust_mapping, checksum, CMapping};
+
+pub fn mapping_demo() {
+    println!("[Rust] Sharing memory-mapped files with C");
+    let path = std::env::temp_dir().join(format!("call_libs_mapping_{}", std::process::id()));
+    let contents = "mapped once by Rust and once by C\n".repeat(1000);
+    if let Err(err) = fs::write(&path, &contents) {
+        eprintln!("[Rust] Cannot write {}: {}\n", path.display(), err);
+        return;
+    }
+    match c_hash_rust_mapping(&path) {
+        Ok(hash) => println!(
+            "[Rust] C hashed {} bytes Rust mapped: {:016x}",
+            contents.len(),
+            hash
+        ),
+        Err(err) => eprintln!("[Rust] C could not read the Rust mapping: {}", err),
+    }
+    match CMapping::open(&path) {
+        Ok(mapping) => println!(
+            "[Rust] Rust hashed {} bytes C mapped: {:016x}, the first line is {:?}",
+            mapping.len(),
+            checksum(mapping.as_slice()),
+            mapping.get(0..33).map(String::from_utf8_lossy)
+        ),
+        Err(err) => eprintln!("[Rust] C could not map {}: {}", path.display(), err),
+    }
+    let _ = fs::remove_file(&path);
+    println!();
+}
@@ -0,0 +1,47 @@
+// C 创建的锁保护的计数器：C 线程用 pthread_mutex_lock 或 AcquireSRWLockExclusive 加锁，Rust 线程通过
+// interop_common 的 CMutex 加锁，两边争用同一把锁，一次增加都不会丢失
+// A lock-protected counter created by C: a C thread locks with pthread_mutex_lock or
+// AcquireSRWLockExclusive, a Rust thread through interop_common's CMutex, and as both contend for
+// the same lock no increment gets lost
+
+use interop_common::{CMutex, RawCMutex};
+
+use crate::clib::{
+    clib_locked_counter_free, clib_locked_counter_join, clib_locked_counter_lock,
+    clib_locked_counter_new, clib_locked_counter_start, clib_locked_counter_value,
+};
+
+const ROUNDS: u32 = 100_000;
+
+pub fn mutex_demo() {
+    println!("[Rust] Sharing a lock C created between a C thread and this one");
+    let counter = unsafe { clib_locked_counter_new() };
+    if counter.is_null() {
+        eprintln!("[Rust] C could not create the counter\n");
+        return;
+    }
+    // SAFETY: C initialized the lock in place and only touches the value while holding it; both
+    // live until clib_locked_counter_free below
+    let mutex = unsafe {
+        CMutex::from_raw(
+            clib_locked_counter_lock(counter).cast::<RawCMutex>(),
+            clib_locked_counter_value(counter),
+        )
+    }
+    .expect("C returned a NULL lock or value");
+    if unsafe { clib_locked_counter_start(counter, ROUNDS) } != 0 {
+        eprintln!("[Rust] C could not start its thread\n");
+    } else {
+        for _ in 0..ROUNDS {
+            *mutex.lock().expect("Failed to lock C's mutex") += 1;
+        }
+        unsafe { clib_locked_counter_join(counter) };
+        println!(
+            "[Rust] {} increments from C and {} from Rust, the counter is {}\n",
+            ROUNDS,
+            ROUNDS,
+            *mutex.lock().expect("Failed to lock C's mutex")
+        );
+    }
+    unsafe { clib_locked_counter_free(counter) };
+}
@@ -0,0 +1,139 @@
+// 用 dlmopen 把 external_dy 的两个构建加载进各自的链接映射命名空间（仅 glibc 的 Linux）：两个构建导出同名的
+// 符号，每个命名空间有自己的一份库、依赖和静态状态，符号互不冲突
+// Loads two builds of external_dy into link-map namespaces of their own with dlmopen (Linux with
+// glibc only): both builds export the same symbol names, and every namespace has its own copy of
+// the library, its dependencies and their static state, so the symbols never collide
+//
+// 新命名空间里的 libc 也是单独的一份，库的 printf 写入它自己的 stdout 缓冲区，进程退出时没有人刷新它；
+// stdout 是管道时不手动调用那一份 libc 的 fflush，这些输出就会丢失
+// The namespace gets a separate libc as well: the library's printf writes into a stdout buffer of
+// its own that nobody flushes when the process exits, so when stdout is a pipe that output is lost
+// unless the fflush of that libc is called by hand
+
+use std::{
+    ffi::{c_char, c_int, c_long, c_void, CStr, CString},
+    os::unix::ffi::OsStrExt,
+    path::Path,
+    ptr,
+};
+
+use libloading::{os::unix::RTLD_NOW, Library};
+
+use crate::dylib::{DyLib, LoadError};
+use crate::resolve::{Attempt, ResolveError};
+
+// build.rs 把两个构建编译到这里
+// build.rs compiles both builds into here
+const OUT_DIR: &str = env!("OUT_DIR");
+const BUILDS: [&str; 2] = ["libexternal_dy.so", "libexternal_dy_alt.so"];
+
+// <dlfcn.h> 中的 GNU 扩展
+// GNU extensions from <dlfcn.h>
+const LM_ID_NEWLM: c_long = -1;
+const RTLD_DI_LMID: c_int = 1;
+
+extern "C" {
+    fn dlmopen(lmid: c_long, filename: *const c_char, flags: c_int) -> *mut c_void;
+    fn dlinfo(handle: *mut c_void, request: c_int, info: *mut c_void) -> c_int;
+    fn dlerror() -> *mut c_char;
+}
+
+type Fflush = unsafe extern "C" fn(stream: *mut c_void) -> c_int;
+
+/// A build of the library loaded into a namespace of its own.
+struct Isolated {
+    lib: DyLib,
+    namespace: c_long,
+    // 命名空间里那一份 libc 的 fflush
+    // The fflush of the libc in the namespace
+    fflush: Fflush,
+}
+
+/// Loads `path` into a new namespace.
+///
+/// # Safety
+///
+/// `path` must be a build of external_lib/dylib.c, loading it runs its initialisers.
+unsafe fn open_isolated(path: &Path) -> Result<Isolated, LoadError> {
+    let failed = |call: &str| {
+        let reason = CStr::from_ptr(dlerror()).to_string_lossy();
+        LoadError::Resolve(ResolveError {
+            lib_file: path.display().to_string(),
+            attempts: vec![Attempt {
+                candidate: path.display().to_string(),
+                outcome: format!("{} failed: {}", call, reason),
+            }],
+        })
+    };
+    let filename = CString::new(path.as_os_str().as_bytes()).expect("OUT_DIR has no NUL bytes");
+    let handle = dlmopen(LM_ID_NEWLM, filename.as_ptr(), RTLD_NOW);
+    if handle.is_null() {
+        return Err(failed("dlmopen"));
+    }
+    // 句柄交给 libloading 后由它负责 dlclose
+    // Once the handle is handed to libloading, it takes care of dlclose
+    let lib: Library = libloading::os::unix::Library::from_raw(handle).into();
+    let mut namespace: c_long = 0;
+    if dlinfo(handle, RTLD_DI_LMID, (&mut namespace as *mut c_long).cast()) != 0 {
+        return Err(failed("dlinfo"));
+    }
+    // 通过句柄查找符号时也会搜索库的依赖，找到的是同一个命名空间里的 libc
+    // Looking a symbol up through the handle searches the library's dependencies too, which finds
+    // the libc in the same namespace
+    let fflush = *lib
+        .get::<Fflush>(b"fflush\0")
+        .map_err(|source| LoadError::Symbol {
+            name: "fflush",
+            source,
+        })?;
+    Ok(Isolated {
+        lib: DyLib::from_library(lib, Some(path.to_path_buf()))?,
+        namespace,
+        fflush,
+    })
+}
+
+/// Loads the two builds of the external library build.rs compiles into namespaces of their own
+/// and calls both.
+pub fn dlmopen_demo() {
+    println!("[Rust] Loading two builds of external_dy into separate namespaces with dlmopen");
+    let mut libs = Vec::new();
+    for file in BUILDS {
+        let path = Path::new(OUT_DIR).join(file);
+        match unsafe { open_isolated(&path) } {
+            Ok(loaded) => libs.push(loaded),
+            Err(err) => {
+                eprintln!("[Rust] Skipping the dlmopen demo, {}\n", err);
+                return;
+            }
+        }
+    }
+    // 第一个构建调用两次、第二个调用一次，各自的计数说明静态状态没有共享
+    // The first build is called twice and the second once, their counts show the static state
+    // isn't shared
+    for (isolated, calls) in libs.iter().zip([2, 1]) {
+        for _ in 0..calls {
+            let (sum, msg) = isolated
+                .lib
+                .add(1, 2, "namespace")
+                .expect("The name has no NUL bytes.");
+            // 先把库的问候从它的 libc 中刷出去，再打印结果
+            // Flushes the library's greeting out of its libc before printing the result
+            // SAFETY: fflush(NULL) flushes every stream of that libc
+            unsafe { (isolated.fflush)(ptr::null_mut()) };
+            println!("{} (sum {})", msg, sum);
+        }
+        let file = isolated
+            .lib
+            .path()
+            .and_then(Path::file_name)
+            .unwrap_or_default();
+        println!(
+            "[Rust] {} in namespace {}: dyloading_add ran {} time(s)",
+            file.to_string_lossy(),
+            isolated.namespace,
+            isolated.lib.call_count()
+        );
+    }
+    println!();
+}
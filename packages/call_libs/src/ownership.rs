@@ -0,0 +1,54 @@
+// 调用 ownership_interop：Box 交给 C 保管再取回，然后让 C 重复释放一个句柄、释放后继续使用它，
+// 最后 C 线程和 Rust 线程共享同一个 Arc
+// Calls ownership_interop: a Box is left with C and taken back, then C frees a handle twice and
+// uses one after freeing it, and finally C threads and a Rust thread share one Arc
+
+use std::thread;
+
+use ownership_interop::{
+    free_handle_twice, share_across_c_threads, shared_add, shared_clone, shared_new,
+    shared_release, shared_value, touch_after_free, widget_handle_new, Stash, Widget,
+};
+
+pub fn ownership_demo() {
+    println!("[Rust] Handing a Box to C and taking it back");
+    let mut stash = Stash::new();
+    stash.keep(Box::new(Widget::new(42)));
+    if let Some(widget) = stash.give_back() {
+        println!(
+            "[Rust] C handed back widget {}, dropping it in Rust",
+            widget.id()
+        );
+    }
+
+    let freed = free_handle_twice(widget_handle_new(1));
+    println!(
+        "[Rust] C freed a handle twice, {} of the frees succeeded",
+        freed
+    );
+    match touch_after_free(widget_handle_new(2)) {
+        Some(touches) => println!(
+            "[Rust] A freed handle was still usable, touched {} times",
+            touches
+        ),
+        None => println!("[Rust] Touching a freed handle was rejected"),
+    }
+
+    let shared = shared_new();
+    // 裸指针不是 Send，以地址的形式交给 Rust 线程，线程拿到的是它自己的引用
+    // Raw pointers are not Send, so the Rust thread gets the address of a reference of its own
+    let address = unsafe { shared_clone(shared) } as usize;
+    let rust_thread = thread::spawn(move || unsafe {
+        shared_add(address as *const _, 100);
+        shared_release(address as *const _);
+    });
+    let shared_by_c = unsafe { share_across_c_threads(shared, 3, 10) };
+    rust_thread.join().expect("The Rust thread panicked.");
+    if shared_by_c {
+        println!(
+            "[Rust] Three C threads and a Rust thread counted to {} through one Arc\n",
+            unsafe { shared_value(shared) }
+        );
+    }
+    unsafe { shared_release(shared) };
+}
@@ -0,0 +1,149 @@
+// 插件模式：扫描目录，加载所有导出 plugin_register 的动态库，并通过 #[repr(C)] vtable 调用它们
+// Plugin mode: scans directories, loads every dynamic library exporting plugin_register and calls
+// them through their #[repr(C)] vtables
+
+use std::{
+    env::consts::DLL_EXTENSION,
+    ffi::{c_char, c_int, c_void, CStr},
+    fs,
+    path::{Path, PathBuf},
+};
+
+use libloading::Library;
+use plugin_api::{
+    PluginHost, PluginRegisterFn, PluginVTable, PLUGIN_API_VERSION, PLUGIN_REGISTER_SYMBOL,
+};
+
+// 插件注册期间 PluginHost.ctx 指向的注册表
+// The registry PluginHost.ctx points to while a plugin registers
+type Registry = Vec<PluginVTable>;
+
+extern "C" fn host_log(_ctx: *mut c_void, message: *const c_char) {
+    if !message.is_null() {
+        println!("{}", unsafe { CStr::from_ptr(message) }.to_string_lossy());
+    }
+}
+
+extern "C" fn host_register(ctx: *mut c_void, vtable: *const PluginVTable) -> c_int {
+    // SAFETY: ctx is the Registry passed to plugin_register in Plugin::load
+    let registry = unsafe { &mut *ctx.cast::<Registry>() };
+    match unsafe { vtable.as_ref() } {
+        Some(vtable) if !vtable.name.is_null() => {
+            registry.push(*vtable);
+            0
+        }
+        _ => -1,
+    }
+}
+
+/// A loaded plugin library and the vtables it registered.
+struct Plugin {
+    path: PathBuf,
+    vtables: Registry,
+    // 在 Drop 中调用完 destroy 之后才卸载
+    // Only unloaded after Drop has called destroy
+    _lib: Library,
+}
+
+impl Plugin {
+    /// Loads `path` and lets it register, or returns `Ok(None)` when it is not a plugin.
+    unsafe fn load(path: &Path) -> Result<Option<Plugin>, String> {
+        let lib = Library::new(path).map_err(|err| err.to_string())?;
+        let register: PluginRegisterFn = match lib.get(PLUGIN_REGISTER_SYMBOL.as_bytes()) {
+            Ok(register) => *register,
+            Err(_) => return Ok(None),
+        };
+
+        let mut plugin = Plugin {
+            path: path.to_owned(),
+            vtables: Vec::new(),
+            _lib: lib,
+        };
+        let host = PluginHost {
+            api_version: PLUGIN_API_VERSION,
+            ctx: (&mut plugin.vtables as *mut Registry).cast(),
+            log: host_log,
+            register_vtable: host_register,
+        };
+        match register(&host) {
+            0 => Ok(Some(plugin)),
+            status => Err(format!("plugin_register failed with status {}", status)),
+        }
+    }
+
+    fn name(vtable: &PluginVTable) -> String {
+        unsafe { CStr::from_ptr(vtable.name) }
+            .to_string_lossy()
+            .into_owned()
+    }
+}
+
+impl Drop for Plugin {
+    fn drop(&mut self) {
+        for vtable in &self.vtables {
+            if let Some(destroy) = vtable.destroy {
+                destroy(vtable.user_data);
+            }
+        }
+    }
+}
+
+// 按文件名排序的动态库，找不到目录时打印原因
+// The dynamic libraries in `dir` sorted by file name, printing why when the directory is unreadable
+fn libraries_in(dir: &Path) -> Vec<PathBuf> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(err) => {
+            eprintln!("[Rust] Cannot scan {}: {}", dir.display(), err);
+            return Vec::new();
+        }
+    };
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == DLL_EXTENSION))
+        .collect();
+    paths.sort();
+    paths
+}
+
+/// Loads every plugin found in `dirs` and runs each registered operation on `a` and `b`.
+pub fn plugin_demo(dirs: &[PathBuf], a: c_int, b: c_int) {
+    let mut plugins = Vec::new();
+    for path in dirs.iter().flat_map(|dir| libraries_in(dir)) {
+        // SAFETY: only libraries exporting plugin_register are kept, and it follows plugin_api.h
+        match unsafe { Plugin::load(&path) } {
+            Ok(Some(plugin)) => plugins.push(plugin),
+            Ok(None) => println!(
+                "[Rust] Skipping {}, it has no {}",
+                path.display(),
+                PLUGIN_REGISTER_SYMBOL
+            ),
+            Err(err) => eprintln!("[Rust] Cannot load plugin {}: {}", path.display(), err),
+        }
+    }
+    println!("[Rust] Loaded {} plugins\n", plugins.len());
+
+    for plugin in &plugins {
+        for vtable in &plugin.vtables {
+            let mut out = 0;
+            let name = Plugin::name(vtable);
+            match (vtable.compute)(vtable.user_data, a, b, &mut out) {
+                0 => println!(
+                    "[Rust] {}: {}({}, {}) = {}",
+                    plugin.path.display(),
+                    name,
+                    a,
+                    b,
+                    out
+                ),
+                status => println!(
+                    "[Rust] {}: {} failed with status {}",
+                    plugin.path.display(),
+                    name,
+                    status
+                ),
+            }
+        }
+    }
+    println!();
+}
@@ -0,0 +1,36 @@
+// 调用 struct_interop 中按值和按指针传递 Point 的函数
+// Calls the struct_interop functions passing Point by value and by pointer
+
+use struct_interop::{
+    energy_with_rust, rust_point_dot, rust_point_swap, scale, step, sum_with_rust, translate,
+    CParticle, Point, Vec4,
+};
+
+pub fn struct_demo() {
+    println!("[Rust] Passing #[repr(C)] structs to and from C");
+    let mut p = translate(Point { x: 1, y: 2 }, 10, 20);
+    scale(&mut p, 2);
+    println!("[Rust] Scaled by C through a pointer: {:?}", p);
+
+    let sum = sum_with_rust(&p, &Point { x: 3, y: 4 });
+    let dot = unsafe { rust_point_dot(&p, &sum) };
+    unsafe { rust_point_swap(&mut p) };
+    println!("[Rust] Sum {:?}, dot product {}, swapped {:?}", sum, dot, p);
+
+    // 16 字节对齐的结构体由 C 分配，只通过指针传递
+    // A 16-byte aligned struct allocated by C and only passed through pointers
+    let Some(mut particle) = CParticle::new(1) else {
+        eprintln!("[Rust] C could not allocate a Particle\n");
+        return;
+    };
+    particle.velocity = Vec4 {
+        lanes: [3.0, 4.0, 0.0, 0.0],
+    };
+    step(&mut particle, 0.5);
+    println!(
+        "[Rust] C moved the aligned particle at {:p} to {:?}, its energy is {}\n",
+        &*particle,
+        particle.position.lanes,
+        energy_with_rust(&particle)
+    );
+}
@@ -0,0 +1,76 @@
+// 通过 cdylib_gen 的 C ABI 线程池运行几个任务，完成回调把结果发回主线程
+// Runs a few jobs on cdylib_gen's C ABI thread pool, the completion callback sends each result
+// back to the main thread
+
+use std::ffi::{c_int, c_void};
+use std::sync::mpsc::{self, Sender};
+
+use interop_common::FfiHandle;
+
+type PoolJob = Option<extern "C" fn(user_data: *mut c_void) -> c_int>;
+type PoolDone = Option<extern "C" fn(result: c_int, user_data: *mut c_void)>;
+
+extern "C" {
+    fn rxc_pool_create(threads: u32) -> FfiHandle;
+    fn rxc_pool_submit(
+        pool: FfiHandle,
+        job: PoolJob,
+        done: PoolDone,
+        user_data: *mut c_void,
+    ) -> c_int;
+    fn rxc_pool_join(pool: FfiHandle) -> c_int;
+    fn rxc_pool_free(pool: FfiHandle) -> c_int;
+}
+
+// 每个任务的上下文：要平方的数和把结果发回去的通道。提交时交给线程池，完成回调收回
+// Each job's context: the number to square and the channel to send the result back on. It is
+// handed to the pool on submission and reclaimed by the completion callback
+struct Square {
+    n: c_int,
+    tx: Sender<(c_int, c_int)>,
+}
+
+extern "C" fn square_job(user_data: *mut c_void) -> c_int {
+    let square = unsafe { &*(user_data as *const Square) };
+    square.n * square.n
+}
+
+extern "C" fn on_done(result: c_int, user_data: *mut c_void) {
+    // user_data 是 pool_demo 为这个任务交出的 Box<Square>，完成回调只运行一次，在这里收回
+    // user_data is the Box<Square> pool_demo handed over for this job; the completion callback
+    // runs once, so it is reclaimed here
+    let square = unsafe { Box::from_raw(user_data as *mut Square) };
+    let _ = square.tx.send((square.n, result));
+}
+
+pub fn pool_demo() {
+    println!("[Rust] Running jobs on a thread pool in dynamic library");
+    let pool = unsafe { rxc_pool_create(2) };
+    if pool.is_null() {
+        println!("[Rust] rxc_pool_create failed\n");
+        return;
+    }
+    let (tx, rx) = mpsc::channel();
+    for n in 1..=4 {
+        let square = Square { n, tx: tx.clone() };
+        let user_data = Box::into_raw(Box::new(square)) as *mut c_void;
+        let status = unsafe { rxc_pool_submit(pool, Some(square_job), Some(on_done), user_data) };
+        if status != 0 {
+            // 提交失败时任务和回调都不会运行，Square 还归这里
+            // Neither the job nor the callback runs when submitting fails, so the Square is still ours
+            drop(unsafe { Box::from_raw(user_data as *mut Square) });
+            println!("[Rust] rxc_pool_submit failed with status {status}");
+        }
+    }
+    drop(tx);
+    unsafe {
+        rxc_pool_join(pool);
+        rxc_pool_free(pool);
+    }
+    let mut results: Vec<_> = rx.iter().collect();
+    results.sort();
+    for (n, result) in results {
+        println!("[Rust] {n} squared on a pool thread is {result}");
+    }
+    println!();
+}
@@ -0,0 +1,51 @@
+// 带进度回调的计算：第一次运行到结束，第二次在回调中返回非零值中途停止
+// A computation with a progress callback: the first run finishes, the second stops midway when the
+// callback returns non-zero
+
+use std::ffi::{c_int, c_long, c_void};
+
+type ProgressCallback = Option<extern "C" fn(percent: u8, user_data: *mut c_void) -> c_int>;
+
+extern "C" {
+    fn rxc_cdylib_sum_with_progress(
+        values: *const c_int,
+        len: usize,
+        cb: ProgressCallback,
+        user_data: *mut c_void,
+        total: *mut c_long,
+    ) -> c_int;
+}
+
+// user_data 指向停止的百分比，超过 100 就不会停止
+// user_data points to the percentage to stop at, anything above 100 never stops
+extern "C" fn on_progress(percent: u8, user_data: *mut c_void) -> c_int {
+    let stop_at = unsafe { *(user_data as *const u8) };
+    if percent.is_multiple_of(25) {
+        println!("[Rust] Progress: {percent}%");
+    }
+    c_int::from(percent >= stop_at)
+}
+
+fn run(values: &[c_int], stop_at: u8) {
+    let mut total = 0;
+    let status = unsafe {
+        rxc_cdylib_sum_with_progress(
+            values.as_ptr(),
+            values.len(),
+            Some(on_progress),
+            &stop_at as *const u8 as *mut c_void,
+            &mut total,
+        )
+    };
+    println!("[Rust] rxc_cdylib_sum_with_progress returned status {status}, total {total}");
+}
+
+pub fn progress_demo() {
+    println!("[Rust] Calling a function with a progress callback in dynamic library");
+    let values: Vec<c_int> = (1..=100).collect();
+    run(&values, u8::MAX);
+    // 返回非零值时计算在 50% 停止
+    // Returning non-zero stops the computation at 50%
+    run(&values, 50);
+    println!();
+}
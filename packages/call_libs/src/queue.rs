@@ -0,0 +1,52 @@
+// 几个生产者线程把元素推入 cdylib_gen 的队列，库里的消费者线程通过回调报告每个元素的平方
+// A few producer threads push items onto cdylib_gen's queue, the consumer thread in the library
+// reports each item's square through the callback
+
+use std::ffi::{c_int, c_void};
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::thread;
+
+use interop_common::FfiHandle;
+
+type QueueCallback = Option<extern "C" fn(item: c_int, result: i64, user_data: *mut c_void)>;
+
+extern "C" {
+    fn rxc_queue_new(cb: QueueCallback, user_data: *mut c_void) -> FfiHandle;
+    fn rxc_queue_push(queue: FfiHandle, item: c_int) -> c_int;
+    fn rxc_queue_free(queue: FfiHandle) -> c_int;
+}
+
+// user_data 指向 queue_demo 中的累加器，rxc_queue_free 返回之前一直有效
+// user_data points to the accumulator in queue_demo, which stays alive until rxc_queue_free returns
+extern "C" fn on_item(_item: c_int, result: i64, user_data: *mut c_void) {
+    let total = unsafe { &*(user_data as *const AtomicI64) };
+    total.fetch_add(result, Ordering::Relaxed);
+}
+
+pub fn queue_demo() {
+    println!("[Rust] Pushing items from 4 threads onto a queue in dynamic library");
+    let total = AtomicI64::new(0);
+    let queue = unsafe { rxc_queue_new(Some(on_item), &total as *const _ as *mut c_void) };
+    if queue.is_null() {
+        println!("[Rust] rxc_queue_new failed\n");
+        return;
+    }
+    // 句柄是普通的值，可以直接复制给生产者线程
+    // The handle is a plain value that is simply copied into the producer threads
+    thread::scope(|scope| {
+        for producer in 0..4 {
+            scope.spawn(move || {
+                for item in producer * 25 + 1..=(producer + 1) * 25 {
+                    unsafe { rxc_queue_push(queue, item) };
+                }
+            });
+        }
+    });
+    // 等消费者处理完剩下的元素
+    // Waits for the consumer to process the remaining items
+    unsafe { rxc_queue_free(queue) };
+    println!(
+        "[Rust] The consumer thread reported squares adding up to {}\n",
+        total.load(Ordering::Relaxed)
+    );
+}
@@ -0,0 +1,180 @@
+// 运行时动态库的查找：依次尝试显式指定的目录、可执行文件目录、build.rs 在 OUT_DIR 中编译的库和系统加载器
+// Runtime library lookup: tries the explicit directory, the executable's directory, the library
+// build.rs compiled in OUT_DIR and the system loader, in that order
+
+use std::{
+    env, fmt,
+    path::{Path, PathBuf},
+};
+
+use libloading::Library;
+
+use crate::cli::LoadFlag;
+
+// build.rs 把 external_lib/dylib.c 编译到这里
+// build.rs compiles external_lib/dylib.c into here
+const OUT_DIR: &str = env!("OUT_DIR");
+
+/// One place the resolver looked for the library, and what happened there.
+pub struct Attempt {
+    pub candidate: String,
+    pub outcome: String,
+}
+
+/// Returned when no candidate could be loaded; lists every candidate in the order tried.
+pub struct ResolveError {
+    pub lib_file: String,
+    pub attempts: Vec<Attempt>,
+}
+
+impl fmt::Display for ResolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "could not load {}, tried:", self.lib_file)?;
+        for attempt in &self.attempts {
+            write!(f, "\n  {}: {}", attempt.candidate, attempt.outcome)?;
+        }
+        Ok(())
+    }
+}
+
+/// The raw flags for dlopen or LoadLibraryExW, built from the `--load-flag` values.
+#[derive(Debug, Clone, Copy)]
+pub struct LoadFlags {
+    #[cfg(unix)]
+    raw: std::ffi::c_int,
+    #[cfg(windows)]
+    raw: u32,
+}
+
+impl LoadFlags {
+    /// Picks the flags of the current platform, RTLD_LAZY | RTLD_LOCAL and 0 unless told
+    /// otherwise, the same as [`Library::new`].
+    pub fn new(flags: &[LoadFlag]) -> LoadFlags {
+        #[cfg(unix)]
+        {
+            use libloading::os::unix::{RTLD_GLOBAL, RTLD_LAZY, RTLD_LOCAL, RTLD_NOW};
+
+            let binding = if flags.contains(&LoadFlag::Now) {
+                RTLD_NOW
+            } else {
+                RTLD_LAZY
+            };
+            let scope = if flags.contains(&LoadFlag::Global) {
+                RTLD_GLOBAL
+            } else {
+                RTLD_LOCAL
+            };
+            LoadFlags {
+                raw: binding | scope,
+            }
+        }
+        // DLL 的导入表写明了每个符号来自哪个 DLL，导入总在加载时绑定，所以没有和 RTLD_NOW、RTLD_GLOBAL
+        // 对应的标志；能选的是去哪里找依赖
+        // A DLL's import table names the DLL every symbol comes from and imports are always bound
+        // at load time, so nothing corresponds to RTLD_NOW or RTLD_GLOBAL; what can be chosen is
+        // where the dependencies are looked for
+        #[cfg(windows)]
+        {
+            use libloading::os::windows::{
+                LOAD_LIBRARY_SEARCH_DEFAULT_DIRS, LOAD_LIBRARY_SEARCH_DLL_LOAD_DIR,
+                LOAD_WITH_ALTERED_SEARCH_PATH,
+            };
+
+            let mut raw = 0;
+            if flags.contains(&LoadFlag::AlteredSearchPath) {
+                raw |= LOAD_WITH_ALTERED_SEARCH_PATH;
+            }
+            if flags.contains(&LoadFlag::SearchDllLoadDir) {
+                raw |= LOAD_LIBRARY_SEARCH_DLL_LOAD_DIR | LOAD_LIBRARY_SEARCH_DEFAULT_DIRS;
+            }
+            LoadFlags { raw }
+        }
+    }
+
+    /// Loads `filename` with these flags.
+    ///
+    /// # Safety
+    ///
+    /// Loading a library runs its initialisers, see [`Library::new`].
+    pub unsafe fn open(
+        self,
+        filename: impl AsRef<std::ffi::OsStr>,
+    ) -> Result<Library, libloading::Error> {
+        #[cfg(unix)]
+        let lib = libloading::os::unix::Library::open(Some(filename), self.raw);
+        #[cfg(windows)]
+        let lib = libloading::os::windows::Library::load_with_flags(filename, self.raw);
+        lib.map(Library::from)
+    }
+}
+
+enum Candidate {
+    // 只有文件存在时才交给加载器
+    // Only handed to the loader when the file exists
+    File(PathBuf),
+    // 原样交给 dlopen/LoadLibrary，由系统的搜索路径解析
+    // Passed to dlopen/LoadLibrary as is and resolved through the system search path
+    Loader(String),
+}
+
+fn candidates(lib_dir: Option<&Path>, lib_file: &str) -> Vec<Candidate> {
+    let mut candidates = Vec::new();
+    if let Some(lib_dir) = lib_dir {
+        candidates.push(Candidate::File(lib_dir.join(lib_file)));
+    }
+    if let Some(exe_dir) = env::current_exe().ok().as_deref().and_then(Path::parent) {
+        candidates.push(Candidate::File(exe_dir.join(lib_file)));
+    }
+    candidates.push(Candidate::File(Path::new(OUT_DIR).join(lib_file)));
+    // 在 macOS 上交给 dyld 通过可执行文件的 LC_RPATH 解析 @rpath
+    // On macOS, let dyld resolve @rpath through the executable's LC_RPATH entries
+    if cfg!(target_os = "macos") {
+        candidates.push(Candidate::Loader(format!("@rpath/{}", lib_file)));
+    }
+    candidates.push(Candidate::Loader(lib_file.to_owned()));
+    candidates
+}
+
+/// Loads `lib_file` with `flags` from the first candidate that works, along with its path when
+/// the system loader did not pick it.
+///
+/// # Safety
+///
+/// Loading a library runs its initialisers, see [`Library::new`].
+pub unsafe fn open_lib(
+    lib_dir: Option<&Path>,
+    lib_file: &str,
+    flags: LoadFlags,
+) -> Result<(Library, Option<PathBuf>), ResolveError> {
+    let mut attempts = Vec::new();
+    for candidate in candidates(lib_dir, lib_file) {
+        let (name, path, result) = match candidate {
+            Candidate::File(path) if !path.exists() => {
+                attempts.push(Attempt {
+                    candidate: path.display().to_string(),
+                    outcome: "not found".to_owned(),
+                });
+                continue;
+            }
+            Candidate::File(path) => {
+                let result = flags.open(&path);
+                (path.display().to_string(), Some(path), result)
+            }
+            Candidate::Loader(name) => {
+                let result = flags.open(&name);
+                (format!("system loader ({})", name), None, result)
+            }
+        };
+        match result {
+            Ok(lib) => return Ok((lib, path)),
+            Err(err) => attempts.push(Attempt {
+                candidate: name,
+                outcome: err.to_string(),
+            }),
+        }
+    }
+    Err(ResolveError {
+        lib_file: lib_file.to_owned(),
+        attempts,
+    })
+}
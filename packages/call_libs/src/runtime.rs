@@ -0,0 +1,64 @@
+// 把请求交给 cdylib_gen 内嵌的异步运行时：rxc_async_submit 的回调在运行时的线程上通过通道送回结果，
+// rxc_async_block_on 在当前线程上等待
+// Hands requests to the async runtime embedded in cdylib_gen: rxc_async_submit's callback sends the
+// result back through a channel from a runtime thread, rxc_async_block_on waits on this thread
+
+use std::ffi::{c_int, c_void};
+use std::sync::mpsc::{self, Sender};
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct AsyncRequest {
+    a: c_int,
+    b: c_int,
+    delay_ms: u32,
+}
+
+type AsyncCallback = Option<extern "C" fn(status: c_int, sum: c_int, user_data: *mut c_void)>;
+
+extern "C" {
+    fn rxc_async_submit(request: AsyncRequest, cb: AsyncCallback, user_data: *mut c_void) -> c_int;
+    fn rxc_async_block_on(request: AsyncRequest, sum: *mut c_int) -> c_int;
+}
+
+extern "C" fn on_done(status: c_int, sum: c_int, user_data: *mut c_void) {
+    // user_data 是 async_demo 为这次提交交出的 Box<Sender>，回调只运行一次，在这里收回
+    // user_data is the Box<Sender> async_demo handed over for this submission; the callback runs
+    // once, so it is reclaimed here
+    let tx = unsafe { Box::from_raw(user_data as *mut Sender<(c_int, c_int)>) };
+    let _ = tx.send((status, sum));
+}
+
+pub fn async_demo() {
+    println!("[Rust] Submitting requests to the async runtime in dynamic library");
+    let (tx, rx) = mpsc::channel::<(c_int, c_int)>();
+    // 等得最久的请求先提交，回调按完成的先后到达
+    // The request waiting longest is submitted first, the callbacks arrive in order of completion
+    let requests = [(1, 1, 30), (2, 2, 20), (3, 3, 10)];
+    let mut submitted = 0;
+    for (a, b, delay_ms) in requests {
+        let request = AsyncRequest { a, b, delay_ms };
+        let user_data = Box::into_raw(Box::new(tx.clone())) as *mut c_void;
+        let status = unsafe { rxc_async_submit(request, Some(on_done), user_data) };
+        if status == 0 {
+            submitted += 1;
+        } else {
+            // 提交失败时回调不会运行，Sender 还归这里
+            // The callback never runs when submitting fails, so the Sender is still ours
+            drop(unsafe { Box::from_raw(user_data as *mut Sender<(c_int, c_int)>) });
+            println!("[Rust] rxc_async_submit failed with status {status}");
+        }
+    }
+    for (status, sum) in rx.iter().take(submitted) {
+        println!("[Rust] Async request finished with status {status}, sum {sum}");
+    }
+
+    let mut sum = 0;
+    let request = AsyncRequest {
+        a: 20,
+        b: 22,
+        delay_ms: 10,
+    };
+    let status = unsafe { rxc_async_block_on(request, &mut sum) };
+    println!("[Rust] rxc_async_block_on returned status {status}, sum {sum}\n");
+}
@@ -0,0 +1,17 @@
+// 调用 enum_interop 中使用枚举和标签联合体的函数，并运行它的 C 测试程序
+// Calls the enum_interop functions using enums and tagged unions, and runs its C harness
+
+use enum_interop::{kind_name, make, run_c_harness, rust_shape_area, Shape, ShapeKind};
+
+pub fn shape_demo() {
+    println!("[Rust] Passing #[repr(C)] enums and tagged unions to and from C");
+    for kind in [ShapeKind::Circle, ShapeKind::Rect, ShapeKind::Triangle] {
+        let value = make(kind, 2.0);
+        let shape = Shape::from(value.scale(1.5));
+        let mut area = 0.0;
+        unsafe { rust_shape_area(&shape, &mut area) };
+        println!("[Rust] {} {:?} has area {:.2}", kind_name(shape.tag), value, area);
+    }
+    println!("[Rust] C names the invalid tag 7 \"{}\"", kind_name(7));
+    println!("[Rust] Result from the C harness: {} failure(s)\n", run_c_harness());
+}
@@ -0,0 +1,67 @@
+// C 和 Rust 共享的静态变量：staticlib_gen 中定义的 rxc_staticlib_counter 被 c/clib.c 按名字读写，
+// c/clib.c 中定义的 clib_counter 被这里按名字读写。两边都只用原子操作：Rust 把 rxc_staticlib_counter 声明为
+// AtomicU32；clib_counter 来自 bindgen 生成的绑定，是一个 static mut 的 u32，Rust 只通过
+// AtomicU32::from_ptr 访问它，从不把它当作普通的 u32 读写
+// Statics shared between C and Rust: rxc_staticlib_counter, defined in staticlib_gen, is read and
+// written by name from c/clib.c, and clib_counter, defined in c/clib.c, is read and written by
+// name from here. Both sides only use atomic operations: Rust declares rxc_staticlib_counter as an
+// AtomicU32, while clib_counter comes from the bindings bindgen generated as a static mut u32,
+// which Rust only accesses through AtomicU32::from_ptr and never reads or writes as a plain u32
+
+use std::ptr;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::thread;
+
+use crate::clib::{
+    clib_counter, clib_counter_add, clib_staticlib_counter_add, clib_staticlib_counter_get,
+};
+
+const ROUNDS: u32 = 1000;
+
+extern "C" {
+    static rxc_staticlib_counter: AtomicU32;
+}
+
+// Rust 和 C 各用两个线程同时给 counter 加 1，返回结束时的值
+// Two Rust threads and two calling into C add 1 to counter at the same time, returning the value at
+// the end
+fn race(counter: &AtomicU32, c_add: unsafe extern "C" fn(u32) -> u32) -> u32 {
+    thread::scope(|scope| {
+        for _ in 0..2 {
+            scope.spawn(|| {
+                for _ in 0..ROUNDS {
+                    counter.fetch_add(1, Ordering::SeqCst);
+                }
+            });
+            scope.spawn(|| {
+                for _ in 0..ROUNDS {
+                    // SAFETY: both C functions only update their counter atomically
+                    unsafe { c_add(1) };
+                }
+            });
+        }
+    });
+    counter.load(Ordering::SeqCst)
+}
+
+pub fn shared_static_demo() {
+    println!("[Rust] Updating statics shared with C from Rust and C threads at the same time");
+    // SAFETY: both statics are plain 32-bit values defined with static storage duration, and
+    // clib_counter is only ever accessed atomically
+    let (staticlib, clib) = unsafe {
+        (
+            &rxc_staticlib_counter,
+            AtomicU32::from_ptr(ptr::addr_of_mut!(clib_counter)),
+        )
+    };
+    let from_rust = race(staticlib, clib_staticlib_counter_add);
+    let from_c = unsafe { clib_staticlib_counter_get() };
+    println!(
+        "[Rust] rxc_staticlib_counter, defined in Rust: {} read from Rust, {} read from C",
+        from_rust, from_c
+    );
+    println!(
+        "[Rust] clib_counter, defined in C: {} read from Rust\n",
+        race(clib, clib_counter_add)
+    );
+}
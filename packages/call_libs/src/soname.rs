@@ -0,0 +1,41 @@
+// 按 SONAME 加载 cargo xtask install 安装的 cdylib_gen（仅 Linux）
+// Loads the cdylib_gen installed by cargo xtask install by its SONAME (Linux only)
+
+use libloading::{Library, Symbol};
+
+// 安装的库是 libcdylib_gen.so.0.1.0，SONAME 链接指向它；0.x 版本的 SONAME 带上次版本号
+// The installed library is libcdylib_gen.so.0.1.0 and the SONAME link points at it; 0.x SONAMEs keep the minor
+const CDYLIB_SONAME: &str = "libcdylib_gen.so.0.1";
+
+/// Asks the system loader for cdylib_gen by SONAME, the way an executable linked against it would.
+pub fn soname_demo() {
+    // 只给出文件名，由动态链接器按 LD_LIBRARY_PATH、ld.so.cache 和系统目录查找
+    // Only the file name is given, so the dynamic linker searches LD_LIBRARY_PATH, ld.so.cache and the system directories
+    let lib = match unsafe { Library::new(CDYLIB_SONAME) } {
+        Ok(lib) => lib,
+        Err(err) => {
+            eprintln!(
+                "[Rust] Skipping the SONAME demo, {}: {}\n       \
+                 run cargo xtask install and set LD_LIBRARY_PATH=target/install/lib\n",
+                CDYLIB_SONAME, err
+            );
+            return;
+        }
+    };
+    let abi_version: Symbol<unsafe extern "C" fn() -> u32> =
+        match unsafe { lib.get(b"rxc_cdylib_abi_version\0") } {
+            Ok(symbol) => symbol,
+            Err(err) => {
+                eprintln!(
+                    "[Rust] {} has no rxc_cdylib_abi_version: {}\n",
+                    CDYLIB_SONAME, err
+                );
+                return;
+            }
+        };
+    println!(
+        "[Rust] Loaded {} by SONAME, ABI version {}\n",
+        CDYLIB_SONAME,
+        unsafe { abi_version() }
+    );
+}
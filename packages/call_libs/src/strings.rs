@@ -0,0 +1,80 @@
+// 把 argv 形式的字符串数组传给 cdylib_gen，再接收它分配的字符串数组，Drop 时交还给
+// rxc_rust_string_array_free
+// Passes an argv-style string array to cdylib_gen and receives a string array it allocated,
+// handed back to rxc_rust_string_array_free on Drop
+
+use std::ffi::{c_char, c_void, CStr, CString};
+use std::ptr::NonNull;
+
+extern "C" {
+    fn rxc_cdylib_join_strings(
+        strings: *const *const c_char,
+        len: usize,
+        separator: *const c_char,
+    ) -> *mut c_char;
+    fn rxc_cdylib_split_words(text: *const c_char, count: *mut usize) -> *mut *mut c_char;
+    fn rxc_rust_free(ptr: *mut c_void);
+    fn rxc_rust_string_array_free(array: *mut *mut c_char, len: usize);
+}
+
+/// An array of strings owned by cdylib_gen's allocator.
+pub struct RustStrings {
+    array: NonNull<*mut c_char>,
+    len: usize,
+}
+
+impl RustStrings {
+    pub fn iter(&self) -> impl Iterator<Item = &CStr> {
+        (0..self.len).map(|i| unsafe { CStr::from_ptr(*self.array.as_ptr().add(i)) })
+    }
+}
+
+impl Drop for RustStrings {
+    fn drop(&mut self) {
+        unsafe { rxc_rust_string_array_free(self.array.as_ptr(), self.len) }
+    }
+}
+
+/// Joins `strings` with `separator` in cdylib_gen, passing them as an array of pointers.
+pub fn join(strings: &[&str], separator: &str) -> Option<String> {
+    // CString 必须活到调用结束，指针数组只是借用它们
+    // The CStrings must outlive the call, the pointer array only borrows them
+    let owned = strings
+        .iter()
+        .map(|s| CString::new(*s))
+        .collect::<Result<Vec<_>, _>>()
+        .ok()?;
+    let pointers: Vec<*const c_char> = owned.iter().map(|s| s.as_ptr()).collect();
+    let separator = CString::new(separator).ok()?;
+    let joined =
+        unsafe { rxc_cdylib_join_strings(pointers.as_ptr(), pointers.len(), separator.as_ptr()) };
+    let joined = NonNull::new(joined)?;
+    let result = unsafe { CStr::from_ptr(joined.as_ptr()) }
+        .to_string_lossy()
+        .into_owned();
+    unsafe { rxc_rust_free(joined.as_ptr().cast()) };
+    Some(result)
+}
+
+pub fn split_words(text: &str) -> Option<RustStrings> {
+    let text = CString::new(text).ok()?;
+    let mut len = 0;
+    let array = unsafe { rxc_cdylib_split_words(text.as_ptr(), &mut len) };
+    NonNull::new(array).map(|array| RustStrings { array, len })
+}
+
+pub fn strings_demo() {
+    println!("[Rust] Passing string arrays to and from dynamic library");
+    match join(&["Rust", "and", "C"], " + ") {
+        Some(joined) => println!("[Rust] Joined by dynamic library: {}", joined),
+        None => println!("[Rust] rxc_cdylib_join_strings failed"),
+    }
+    match split_words("one array, many strings") {
+        Some(words) => {
+            let read: Vec<_> = words.iter().map(CStr::to_string_lossy).collect();
+            println!("[Rust] Words from dynamic library: {:?}", read);
+        }
+        None => println!("[Rust] rxc_cdylib_split_words failed"),
+    }
+    println!("[Rust] The array was released with rxc_rust_string_array_free\n");
+}
@@ -0,0 +1,20 @@
+// 通过 cxx 使用 C++ 的 Tally 类，和其他示例中手写的 extern "C" 相对照
+// Uses the C++ Tally class through cxx, the counterpart to the hand-written extern "C" in the
+// other examples
+
+use cxx_interop::{new_tally, squares};
+
+pub fn tally_demo() {
+    println!("[Rust] Using a C++ class, std::string and std::vector through cxx");
+    let mut tally = new_tally("cxx");
+    for value in squares(4).iter() {
+        tally.pin_mut().add(*value);
+    }
+    tally.pin_mut().add(i32::MAX);
+    println!(
+        "[Rust] Tally \"{}\" holds {:?}",
+        tally.label(),
+        tally.values().iter().collect::<Vec<_>>()
+    );
+    println!("[Rust] Summary from C++: {}\n", tally.summary());
+}
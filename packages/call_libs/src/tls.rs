@@ -0,0 +1,27 @@
+// 调用 tls_interop：Rust 和 C 的线程局部变量在每个线程中各有一份，C 线程退出时 Rust 的那一份随之销毁
+// Calls tls_interop: the Rust and C thread-local variables have a copy per thread, and the Rust
+// copy of a C thread is destroyed as that thread exits
+
+use tls_interop::{
+    c_value, check_c_threads, rust_tls_get, rust_tls_set, set_c_value, slots_dropped,
+};
+
+pub fn tls_demo() {
+    println!("[Rust] Sharing thread-local variables with C threads");
+    rust_tls_set(7);
+    set_c_value(-7);
+    let dropped = slots_dropped();
+    match check_c_threads(4, 100) {
+        Some(isolated) => println!(
+            "[Rust] {} of 4 C threads only saw their own values, {} Rust thread-locals destroyed as they exited",
+            isolated,
+            slots_dropped() - dropped
+        ),
+        None => eprintln!("[Rust] C could not start its threads"),
+    }
+    println!(
+        "[Rust] This thread still has Rust {} and C {}\n",
+        rust_tls_get(),
+        c_value()
+    );
+}
@@ -0,0 +1,95 @@
+// 接收 cdylib_gen 转发的 tracing span 和事件，像原生宿主那样把它们打印成自己的格式
+// Receives the tracing spans and events cdylib_gen forwards and prints them in a format of its
+// own, the way a native host would
+
+use std::ffi::{c_char, c_int, c_void};
+use std::{ptr, slice};
+
+use interop_common::CBuffer;
+
+/// Mirrors `TraceStr` in cdylib_gen.h.
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct TraceStr {
+    ptr: *const c_char,
+    len: usize,
+}
+
+/// Mirrors `TraceField` in cdylib_gen.h.
+#[repr(C)]
+struct TraceField {
+    key: TraceStr,
+    value: TraceStr,
+}
+
+/// Mirrors `TraceRecord` in cdylib_gen.h; `kind` and `level` are C enums passed as ints.
+#[repr(C)]
+struct TraceRecord {
+    kind: c_int,
+    level: c_int,
+    span_id: u64,
+    name: TraceStr,
+    target: TraceStr,
+    fields: *const TraceField,
+    field_count: usize,
+}
+
+type TraceCallback =
+    Option<unsafe extern "C" fn(record: *const TraceRecord, user_data: *mut c_void)>;
+
+extern "C" {
+    fn rxc_cdylib_set_trace_callback(callback: TraceCallback, user_data: *mut c_void);
+    fn rxc_cdylib_add(
+        a: c_int,
+        b: c_int,
+        result: *mut c_char,
+        result_len: usize,
+        sum: *mut c_int,
+        required_len: *mut usize,
+    ) -> c_int;
+}
+
+unsafe fn string(s: TraceStr) -> String {
+    String::from_utf8_lossy(slice::from_raw_parts(s.ptr as *const u8, s.len)).into_owned()
+}
+
+unsafe extern "C" fn print_record(record: *const TraceRecord, _: *mut c_void) {
+    let record = &*record;
+    // 事件的名字只是源码位置，打印它所在的 span
+    // An event's name is only its source location, so print the span it happened in instead
+    let what = match record.kind {
+        0 => format!("enter {}", string(record.name)),
+        1 => format!("exit {}", string(record.name)),
+        _ => "event".to_string(),
+    };
+    let fields: Vec<String> = slice::from_raw_parts(record.fields, record.field_count)
+        .iter()
+        .map(|field| format!("{}={}", string(field.key), string(field.value)))
+        .collect();
+    println!(
+        "[Rust] trace {} span={} level={} {}",
+        what,
+        record.span_id,
+        record.level,
+        fields.join(" ")
+    );
+}
+
+pub fn trace_demo() {
+    println!("[Rust] Forwarding tracing spans and events from dynamic library");
+    unsafe {
+        rxc_cdylib_set_trace_callback(Some(print_record), ptr::null_mut());
+        let mut b = CBuffer::new("Wang", 64).unwrap();
+        let mut sum = 0;
+        rxc_cdylib_add(
+            20,
+            22,
+            b.as_mut_c_ptr(),
+            b.capacity(),
+            &mut sum,
+            ptr::null_mut(),
+        );
+        rxc_cdylib_set_trace_callback(None, ptr::null_mut());
+    }
+    println!();
+}
@@ -0,0 +1,93 @@
+// 和 cdylib_gen 的 ABI 版本握手（在调用它的任何其他函数之前进行）以及版本查询
+// ABI version handshake with cdylib_gen (done before calling any of its other functions) and its version query
+
+use std::ffi::{c_char, c_int, CStr};
+
+use crate::STATUS_OK;
+
+// 这个程序中的 extern 声明是按照这个版本的 cdylib_gen.h 编写的
+// The extern declarations in this program were written against this version of cdylib_gen.h
+const CDYLIB_ABI_VERSION: u32 = 1;
+
+const CDYLIB_GIT_HASH_LEN: usize = 41;
+
+/// Mirrors `Version` in cdylib_gen.h.
+#[repr(C)]
+struct Version {
+    major: u32,
+    minor: u32,
+    patch: u32,
+    git_hash: [c_char; CDYLIB_GIT_HASH_LEN],
+}
+
+/// Mirrors `LoadReport` in cdylib_gen.h.
+#[repr(C)]
+struct LoadReport {
+    constructor_runs: u32,
+    initialized_at_load: bool,
+}
+
+extern "C" {
+    fn rxc_cdylib_abi_version() -> u32;
+    fn rxc_cdylib_version(out: *mut Version) -> c_int;
+    fn rxc_cdylib_load_report(out: *mut LoadReport) -> c_int;
+}
+
+/// Checks that the cdylib_gen the loader picked speaks the ABI this program was written for.
+pub fn check_cdylib_abi() -> Result<(), String> {
+    // SAFETY: rxc_cdylib_abi_version takes no arguments and has been stable since the first ABI
+    let found = unsafe { rxc_cdylib_abi_version() };
+    if found == CDYLIB_ABI_VERSION {
+        Ok(())
+    } else {
+        Err(format!(
+            "cdylib_gen has ABI version {}, but call_libs was built for version {}; rebuild them together",
+            found, CDYLIB_ABI_VERSION
+        ))
+    }
+}
+
+/// Describes the loaded cdylib_gen as `major.minor.patch (git hash)`.
+pub fn cdylib_version_string() -> Option<String> {
+    let mut version = Version {
+        major: 0,
+        minor: 0,
+        patch: 0,
+        git_hash: [0; CDYLIB_GIT_HASH_LEN],
+    };
+    if unsafe { rxc_cdylib_version(&mut version) } != STATUS_OK {
+        return None;
+    }
+    let bytes: Vec<u8> = version.git_hash.iter().map(|&c| c as u8).collect();
+    let git_hash = CStr::from_bytes_until_nul(&bytes).ok()?.to_string_lossy();
+    let hash = if git_hash.is_empty() {
+        "unknown commit"
+    } else {
+        &git_hash
+    };
+    Some(format!(
+        "{}.{}.{} ({})",
+        version.major, version.minor, version.patch, hash
+    ))
+}
+
+/// Describes what cdylib_gen's load-time constructor saw; it ran before main, so before
+/// [`crate::lifecycle::init_libraries`] could initialize anything.
+pub fn cdylib_load_report_string() -> Option<String> {
+    let mut report = LoadReport {
+        constructor_runs: 0,
+        initialized_at_load: false,
+    };
+    if unsafe { rxc_cdylib_load_report(&mut report) } != STATUS_OK {
+        return None;
+    }
+    Some(format!(
+        "ran {} time(s) before main, with the library {}",
+        report.constructor_runs,
+        if report.initialized_at_load {
+            "already initialized"
+        } else {
+            "not initialized yet"
+        }
+    ))
+}
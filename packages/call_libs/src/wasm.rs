@@ -0,0 +1,165 @@
+// 用 wasmtime 在沙箱中运行编译成 wasm32-wasip1 的 wasm_gen（wasm 特性）。和 dlopen 不同，
+// 模块只能访问自己的线性内存和宿主显式开放的 WASI 功能，错误的指针只会让调用陷入 trap
+// Runs wasm_gen compiled to wasm32-wasip1 in a wasmtime sandbox (the wasm feature). Unlike
+// dlopen, the module can only touch its own linear memory and the WASI capabilities the host
+// grants, so a bad pointer only makes the call trap
+
+use std::path::{Path, PathBuf};
+
+use wasmtime::{Engine, Linker, Memory, Module, Store, TypedFunc};
+use wasmtime_wasi::{p1::WasiP1Ctx, WasiCtxBuilder};
+
+use crate::cli::Args;
+use crate::{STATUS_BUFFER_TOO_SMALL, STATUS_OK};
+
+// build.rs 把 wasm_gen.wasm 构建到这里
+// build.rs builds wasm_gen.wasm into here
+const OUT_DIR: &str = env!("OUT_DIR");
+
+const MODULE_FILE: &str = "wasm_gen.wasm";
+
+/// An instance of wasm_gen with its exports; pointers and `usize` are both `u32` on wasm32.
+struct WasmGen {
+    store: Store<WasiP1Ctx>,
+    memory: Memory,
+    alloc: TypedFunc<u32, u32>,
+    free: TypedFunc<(u32, u32), ()>,
+    add: TypedFunc<(i32, i32, u32, u32, u32, u32), i32>,
+}
+
+/// What one `wasm_add` call produced.
+struct AddOutcome {
+    status: i32,
+    sum: i32,
+    required: u32,
+    msg: String,
+}
+
+impl WasmGen {
+    fn load(path: &Path) -> wasmtime::Result<Self> {
+        let engine = Engine::default();
+        let module = Module::from_file(&engine, path)?;
+        let mut linker = Linker::new(&engine);
+        wasmtime_wasi::p1::add_to_linker_sync(&mut linker, |wasi| wasi)?;
+        // 只开放标准输出，不开放文件系统、环境变量和网络
+        // Only standard output is granted, no file system, environment variables or network
+        let mut store = Store::new(&engine, WasiCtxBuilder::new().inherit_stdout().build_p1());
+        let instance = linker.instantiate(&mut store, &module)?;
+        // wasm32-wasip1 的 cdylib 是 reactor 模块，调用其他导出函数前先运行 _initialize
+        // A wasm32-wasip1 cdylib is a reactor module, _initialize runs before any other export
+        if let Ok(initialize) = instance.get_typed_func::<(), ()>(&mut store, "_initialize") {
+            initialize.call(&mut store, ())?;
+        }
+        let memory = instance
+            .get_memory(&mut store, "memory")
+            .ok_or_else(|| wasmtime::format_err!("{} exports no memory", MODULE_FILE))?;
+        Ok(WasmGen {
+            memory,
+            alloc: instance.get_typed_func(&mut store, "wasm_alloc")?,
+            free: instance.get_typed_func(&mut store, "wasm_free")?,
+            add: instance.get_typed_func(&mut store, "wasm_add")?,
+            store,
+        })
+    }
+
+    // 宿主的数据要先复制进模块的内存，再把偏移作为指针传入
+    // The host's data is first copied into the module's memory, then its offset is passed as the pointer
+    fn alloc(&mut self, len: u32) -> wasmtime::Result<u32> {
+        match self.alloc.call(&mut self.store, len)? {
+            0 => Err(wasmtime::format_err!("wasm_alloc({}) failed", len)),
+            ptr => Ok(ptr),
+        }
+    }
+
+    fn add(&mut self, a: i32, b: i32, name: &str, capacity: u32) -> wasmtime::Result<AddOutcome> {
+        let buf = self.alloc(capacity)?;
+        // sum 在偏移 0，required_len 在偏移 4
+        // sum lives at offset 0, required_len at offset 4
+        let outs = self.alloc(8)?;
+        let mut input = name.as_bytes().to_vec();
+        input.push(0);
+        self.memory.write(&mut self.store, buf as usize, &input)?;
+
+        let status = self
+            .add
+            .call(&mut self.store, (a, b, buf, capacity, outs, outs + 4))?;
+
+        let data = self.memory.data(&self.store);
+        let word = |at: u32| -> [u8; 4] { data[at as usize..at as usize + 4].try_into().unwrap() };
+        let text = &data[buf as usize..(buf + capacity) as usize];
+        let text = &text[..text.iter().position(|&c| c == 0).unwrap_or(text.len())];
+        let outcome = AddOutcome {
+            status,
+            sum: i32::from_le_bytes(word(outs)),
+            required: u32::from_le_bytes(word(outs + 4)),
+            msg: String::from_utf8_lossy(text).into_owned(),
+        };
+        self.free.call(&mut self.store, (buf, capacity))?;
+        self.free.call(&mut self.store, (outs, 8))?;
+        Ok(outcome)
+    }
+
+    // 传给模块一个超出线性内存的指针：dlopen 的库可能因此破坏宿主进程，这里只会 trap
+    // Hands the module a pointer past its linear memory: a dlopen'd library could corrupt the host
+    // process with it, here it only traps
+    fn add_out_of_bounds(&mut self) -> wasmtime::Result<i32> {
+        let past_end = u32::try_from(self.memory.data_size(&self.store)).unwrap_or(u32::MAX);
+        self.add
+            .call(&mut self.store, (1, 2, past_end, 16, past_end, 0))
+    }
+}
+
+fn module_path(args: &Args) -> PathBuf {
+    args.lib_path
+        .as_deref()
+        .unwrap_or(Path::new(OUT_DIR))
+        .join(MODULE_FILE)
+}
+
+pub fn wasm_demo(args: &Args) {
+    let path = module_path(args);
+    let mut wasm = match WasmGen::load(&path) {
+        Ok(wasm) => wasm,
+        Err(err) => {
+            eprintln!(
+                "[Rust] Skipping the wasm demo, cannot load {}: {}\n",
+                path.display(),
+                err
+            );
+            return;
+        }
+    };
+
+    println!("[Rust] Calling function in wasm module");
+    let (a, b, name) = (args.a_or(5), args.b_or(6), args.name_or("Wang"));
+    let mut capacity = args.initial_capacity(name) as u32;
+    let result = loop {
+        match wasm.add(a, b, name, capacity) {
+            Ok(outcome) if outcome.status == STATUS_BUFFER_TOO_SMALL => {
+                println!(
+                    "[Rust] A {}-byte buffer is too small, retrying with {} bytes",
+                    capacity,
+                    outcome.required + 1
+                );
+                capacity = outcome.required + 1;
+            }
+            result => break result,
+        }
+    };
+    match result {
+        Ok(outcome) if outcome.status == STATUS_OK => {
+            println!("{}", outcome.msg);
+            println!("[Rust] Result from wasm module: {}\n", outcome.sum);
+        }
+        Ok(outcome) => println!("[Rust] wasm module failed with status {}\n", outcome.status),
+        Err(err) => println!("[Rust] wasm module trapped: {}\n", err),
+    }
+
+    match wasm.add_out_of_bounds() {
+        Ok(status) => println!("[Rust] The out-of-bounds call returned status {}\n", status),
+        Err(err) => println!(
+            "[Rust] An out-of-bounds pointer trapped inside the sandbox: {}\n",
+            err.root_cause()
+        ),
+    }
+}
@@ -0,0 +1,121 @@
+// 热重载：外部动态库在磁盘上变化时卸载旧的 Library，重新加载并重新解析符号
+// Hot reload: when the external dynamic library changes on disk, unload the old Library, load it
+// again and re-resolve its symbols
+
+use std::{path::Path, sync::mpsc, thread, time::Duration};
+
+use notify::{Event, EventKind, RecursiveMode, Watcher};
+
+use crate::cli::Args;
+use dyload::WeakLibraryHandle;
+
+use crate::dylib::{DyLib, LIB_FILE};
+use crate::resolve::LoadFlags;
+
+// 编译器和复制命令往往分几步写文件，等这么久再重新加载
+// Compilers and copies often write the file in several steps, wait this long before reloading
+const SETTLE: Duration = Duration::from_millis(300);
+
+fn call(lib: &DyLib, args: &Args) {
+    match lib.add(args.a_or(8), args.b_or(9), args.name_or("Jack")) {
+        Ok((sum, msg)) => println!(
+            "{}\n[Rust] Result from the reloadable library: {}",
+            msg, sum
+        ),
+        Err(err) => eprintln!("[Rust] Cannot call the reloadable library: {}", err),
+    }
+    // 库里的静态变量随旧的映像一起消失，这个计数在每次重新加载后都从头开始
+    // Statics in the library go away with the old image, so this count restarts after every reload
+    println!(
+        "[Rust] This copy of {} has handled {} calls\n",
+        LIB_FILE,
+        lib.call_count()
+    );
+}
+
+fn is_library_change(event: &notify::Result<Event>) -> bool {
+    match event {
+        Ok(event) => {
+            !matches!(event.kind, EventKind::Access(_))
+                && event
+                    .paths
+                    .iter()
+                    .any(|path| path.file_name().is_some_and(|name| name == LIB_FILE))
+        }
+        Err(_) => false,
+    }
+}
+
+/// Calls the external library, then reloads and calls it again whenever its file changes.
+///
+/// Everything obtained from the old copy dies with it: function pointers, statics and any
+/// pointer it handed out. [`DyLib`] keeps its symbols next to the shared library handle, so none of
+/// them can outlive the copy they came from; anything else holding that handle keeps the old copy
+/// loaded across a reload. Replace the file atomically (build elsewhere, then rename); overwriting a
+/// mapped library in place can crash the process before the reload even starts.
+pub fn watch_demo(args: &Args) {
+    let flags = LoadFlags::new(&args.load_flags);
+    let lib = match DyLib::open(args.lib_path.as_deref(), flags) {
+        Ok(lib) => lib,
+        Err(err) => {
+            eprintln!("[Rust] Cannot watch the dynamic loading library, {}", err);
+            return;
+        }
+    };
+    let Some(dir) = lib.path().and_then(Path::parent).map(Path::to_owned) else {
+        eprintln!(
+            "[Rust] The system loader found {}, there is no file to watch",
+            LIB_FILE
+        );
+        return;
+    };
+
+    let (tx, rx) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(tx).expect("Failed to create a file watcher.");
+    // 监视目录而不是文件，这样原子替换（新建再重命名）之后仍能收到事件
+    // Watch the directory rather than the file, so events keep arriving after an atomic replace
+    watcher
+        .watch(&dir, RecursiveMode::NonRecursive)
+        .expect("Failed to watch the library directory.");
+    println!(
+        "[Rust] Watching {} for changes to {}, press Ctrl-C to stop\n",
+        dir.display(),
+        LIB_FILE
+    );
+    call(&lib, args);
+
+    let mut lib = Some(lib);
+    while let Ok(event) = rx.recv() {
+        if !is_library_change(&event) {
+            continue;
+        }
+        thread::sleep(SETTLE);
+        while rx.try_recv().is_ok() {}
+
+        // 必须先卸载旧库：同一路径的库仍被加载时 dlopen 会直接返回旧的句柄
+        // The old copy must be unloaded first: dlopen returns the existing handle while a library
+        // with the same path is still loaded
+        let old = lib.as_ref().and_then(DyLib::downgrade);
+        drop(lib.take());
+        // 其他持有者让旧库保持加载时，重新加载拿到的还是旧的映像
+        // While other holders keep the old copy loaded, reloading gets the old image back
+        if let Some(old) = old.filter(WeakLibraryHandle::is_loaded) {
+            eprintln!(
+                "[Rust] {} holders keep the old {} loaded, the reload will see the old copy",
+                old.holders(),
+                LIB_FILE
+            );
+        }
+        println!("[Rust] {} changed, reloading", LIB_FILE);
+        match DyLib::open(Some(&dir), flags) {
+            Ok(reloaded) => {
+                call(&reloaded, args);
+                lib = Some(reloaded);
+            }
+            Err(err) => eprintln!(
+                "[Rust] Reload failed, waiting for the next change: {}\n",
+                err
+            ),
+        }
+    }
+}
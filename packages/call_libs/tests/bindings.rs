@@ -0,0 +1,21 @@
+// 开启 bindgen 特性时，构建期生成的绑定必须和仓库中提交的 bindings/clib.rs 一致；没有这个检查，修改
+// c/clib.h 之后，不开启特性的构建会悄悄地继续使用过时的绑定
+// With the bindgen feature on, the bindings generated at build time must match the committed
+// bindings/clib.rs; without this check, builds without the feature would silently keep using
+// stale bindings after c/clib.h changes
+//
+// 运行 / Run: cargo test -p call_libs --features bindgen --test bindings
+
+#![cfg(feature = "bindgen")]
+
+const GENERATED: &str = include_str!(concat!(env!("OUT_DIR"), "/clib.rs"));
+const COMMITTED: &str = include_str!("../bindings/clib.rs");
+
+#[test]
+fn bindings_match_committed_copy() {
+    assert!(
+        GENERATED == COMMITTED,
+        "bindings/clib.rs is out of date, rebuild with --features bindgen and UPDATE_BINDINGS=1 to \
+         refresh it"
+    );
+}
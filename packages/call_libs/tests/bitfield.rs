@@ -0,0 +1,133 @@
+// C 位域结构体的访问函数的测试：每个字段都能写入和读回，互不干扰；超出宽度的值被 setter 拒绝，字段保持
+// 不变；C 用指定初始化器填好的值可以通过 getter 读出来。位的具体位置只在小端目标上断言，GCC、Clang 和
+// MSVC 在那里都从最低位开始分配
+// Tests for the accessors of C's bitfield struct: every field can be written and read back without
+// disturbing the others; values too wide are refused by the setter, which leaves the field
+// unchanged; the values C filled in with designated initializers read back through the getters.
+// Where the bits actually are is only asserted on little-endian targets, where GCC, Clang and MSVC
+// all allocate from the lowest bit
+//
+// clib.c 引用 staticlib_gen 中的符号，静态构建（--features static）中不运行
+// clib.c refers to symbols from staticlib_gen, so it does not run in the static build
+// (--features static)
+
+#![cfg(not(feature = "static"))]
+
+#[path = "../src/clib.rs"]
+mod clib;
+
+use clib::{
+    clib_status_align, clib_status_example, clib_status_get_channel, clib_status_get_count,
+    clib_status_get_error, clib_status_get_mode, clib_status_get_ready, clib_status_set_channel,
+    clib_status_set_count, clib_status_set_error, clib_status_set_mode, clib_status_set_ready,
+    clib_status_size, ClibStatus,
+};
+
+type Getter = unsafe extern "C" fn(*const ClibStatus) -> u32;
+type Setter = unsafe extern "C" fn(*mut ClibStatus, u32) -> i32;
+
+// bindgen 把 ClibStatus 生成为一个不透明的 u32，这里直接看其中的位
+// bindgen generates ClibStatus as an opaque u32, whose bits are looked at directly here
+fn zeroed() -> ClibStatus {
+    ClibStatus {
+        _bindgen_opaque_blob: 0,
+    }
+}
+
+fn bits(status: &ClibStatus) -> u32 {
+    status._bindgen_opaque_blob
+}
+
+// 字段的名字、宽度和访问函数，顺序和 clib.h 中的 CLIB_STATUS_FIELDS 相同
+// The name, width and accessors of every field, in the order of CLIB_STATUS_FIELDS in clib.h
+const FIELDS: [(&str, u32, Getter, Setter); 5] = [
+    ("ready", 1, clib_status_get_ready, clib_status_set_ready),
+    ("error", 1, clib_status_get_error, clib_status_set_error),
+    ("mode", 3, clib_status_get_mode, clib_status_set_mode),
+    (
+        "channel",
+        4,
+        clib_status_get_channel,
+        clib_status_set_channel,
+    ),
+    ("count", 12, clib_status_get_count, clib_status_set_count),
+];
+
+fn values(status: &ClibStatus) -> Vec<u32> {
+    FIELDS
+        .iter()
+        .map(|(_, _, get, _)| unsafe { get(status) })
+        .collect()
+}
+
+#[test]
+fn fits_one_unsigned_int() {
+    unsafe {
+        assert_eq!(clib_status_size(), std::mem::size_of::<ClibStatus>());
+        assert_eq!(clib_status_align(), std::mem::align_of::<ClibStatus>());
+    }
+}
+
+#[test]
+fn fields_round_trip_independently() {
+    let mut status = zeroed();
+    for (i, (name, width, get, set)) in FIELDS.iter().enumerate() {
+        let max = (1 << width) - 1;
+        let before = values(&status);
+        unsafe {
+            assert_eq!(set(&mut status, max), 0, "{}", name);
+            assert_eq!(get(&status), max, "{}", name);
+        }
+        let mut expected = before;
+        expected[i] = max;
+        assert_eq!(
+            values(&status),
+            expected,
+            "setting {} changed another field",
+            name
+        );
+    }
+    for (name, _, get, set) in FIELDS {
+        unsafe {
+            assert_eq!(set(&mut status, 0), 0, "{}", name);
+            assert_eq!(get(&status), 0, "{}", name);
+        }
+    }
+    assert_eq!(bits(&status), 0);
+}
+
+#[test]
+fn setters_refuse_values_too_wide() {
+    let mut status = zeroed();
+    for (name, width, get, set) in FIELDS {
+        unsafe {
+            assert_eq!(set(&mut status, 1), 0);
+            assert_eq!(set(&mut status, 1 << width), -1, "{}", name);
+            assert_eq!(set(&mut status, u32::MAX), -1, "{}", name);
+            assert_eq!(get(&status), 1, "{}", name);
+        }
+    }
+}
+
+#[test]
+fn reads_what_c_initialized() {
+    let mut status = zeroed();
+    unsafe { clib_status_example(&mut status) };
+    assert_eq!(values(&status), [1, 0, 5, 9, 4000]);
+}
+
+// 从最低位开始：ready 是第 0 位，error 第 1 位，mode 第 2 到 4 位，channel 第 5 到 8 位，count 第 9 到 20 位
+// From the lowest bit: ready is bit 0, error bit 1, mode bits 2 to 4, channel bits 5 to 8 and
+// count bits 9 to 20
+#[cfg(target_endian = "little")]
+#[test]
+fn allocates_from_the_lowest_bit() {
+    let mut status = zeroed();
+    let mut offset = 0;
+    for (name, width, _, set) in FIELDS {
+        unsafe { assert_eq!(set(&mut status, 1), 0) };
+        assert_eq!(bits(&status), 1 << offset, "{}", name);
+        unsafe { assert_eq!(set(&mut status, 0), 0) };
+        offset += width;
+    }
+}
@@ -0,0 +1,137 @@
+// 运行 build.rs 从 tests/c_consumer 编译的 C 程序（反方向：C 调用 Rust），检查它们的输出和退出码
+// Runs the C programs build.rs compiles from tests/c_consumer (the reverse direction: C calling
+// Rust) and checks their output and exit codes
+
+use std::env::consts::EXE_SUFFIX;
+use std::path::Path;
+use std::process::{Command, Output};
+
+// 和 include/*.h 中 FFI_STATUS_OVERFLOW 的值相同
+// The value of FFI_STATUS_OVERFLOW in include/*.h
+const STATUS_OVERFLOW: i32 = 4;
+
+fn run(program: &str, args: &[&str]) -> Output {
+    let path = Path::new(env!("OUT_DIR")).join(format!("{}{}", program, EXE_SUFFIX));
+    Command::new(&path)
+        .args(args)
+        .output()
+        .unwrap_or_else(|err| panic!("cannot run {}: {}", path.display(), err))
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+fn stderr(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stderr).into_owned()
+}
+
+fn assert_contains(text: &str, expected: &[&str]) {
+    for line in expected {
+        assert!(text.contains(line), "missing {:?} in:\n{}", line, text);
+    }
+}
+
+#[test]
+fn static_consumer_calls_staticlib_gen() {
+    let output = run("static_consumer", &[]);
+    assert!(
+        output.status.success(),
+        "exited with {}: {}",
+        output.status,
+        stderr(&output)
+    );
+    assert_contains(
+        &stdout(&output),
+        &[
+            "[Rust staticlib] Hello C consumer",
+            "[C] [Rust staticlib] The result (1 + 2) is 3!",
+            "[C] rxc_staticlib_sum returned 10",
+        ],
+    );
+}
+
+#[test]
+fn static_consumer_exits_with_the_overflow_status() {
+    let output = run("static_consumer", &["overflow"]);
+    assert_eq!(output.status.code(), Some(STATUS_OVERFLOW));
+    assert_contains(
+        &stderr(&output),
+        &["rxc_staticlib_add failed: the arithmetic result overflowed"],
+    );
+}
+
+#[test]
+#[cfg(not(feature = "static"))]
+fn dynamic_consumer_calls_cdylib_gen() {
+    let output = run("dynamic_consumer", &[]);
+    assert!(
+        output.status.success(),
+        "exited with {}: {}",
+        output.status,
+        stderr(&output)
+    );
+    let stdout = stdout(&output);
+    assert_contains(
+        &stdout,
+        &[
+            "[C] rxc_cdylib_add before rxc_rustlib_init: FFI_STATUS_NOT_INITIALIZED",
+            "[Rust cdylib] Hello C consumer",
+            "[C] [Rust cdylib] The result (1 + 2) is 3!",
+            "[C] Joined: C calls Rust",
+            "[C] 3 words: [one] [two] [three]",
+            "[C] Header at an odd address: kind 7, sequence 42, 512 payload bytes",
+            "[C] Parsing a corrupted header: FFI_STATUS_MALFORMED",
+            "[C] Sum of squares from the pool: 30",
+            "[C] rxc_cdylib_slow_sum: FFI_STATUS_CANCELLED",
+            "[C] Freeing the token twice: FFI_STATUS_INVALID_HANDLE",
+            "[C] Building without a name: FFI_STATUS_INVALID_ARGUMENT",
+            "[C] A buffer of 1000 bytes: FFI_STATUS_INVALID_ARGUMENT",
+            "[C] Built config \"c consumer\" with a 1024 byte buffer",
+            "[C] rxc_async_block_on returned 42",
+        ],
+    );
+    // 一次来自 rxc_cdylib_greeting_message，一次来自 rxc_cdylib_make_greeting
+    // Once from rxc_cdylib_greeting_message, once from rxc_cdylib_make_greeting
+    assert_eq!(
+        stdout
+            .matches("[C] [Rust cdylib] Hello C, nice to meet you!")
+            .count(),
+        2,
+        "{}",
+        stdout
+    );
+}
+
+#[test]
+#[cfg(not(feature = "static"))]
+fn dynamic_consumer_exits_with_the_overflow_status() {
+    let output = run("dynamic_consumer", &["overflow"]);
+    assert_eq!(output.status.code(), Some(STATUS_OVERFLOW));
+    assert_contains(
+        &stderr(&output),
+        &["rxc_cdylib_add failed: the arithmetic result overflowed"],
+    );
+}
+
+// 按旧签名编译的程序通过 rxc_cdylib_add_v1 垫片继续工作
+// A program compiled against the old signature keeps working through the rxc_cdylib_add_v1 shim
+#[test]
+#[cfg(not(feature = "static"))]
+fn legacy_consumer_calls_the_v1_shim() {
+    let output = run("legacy_consumer", &[]);
+    assert!(
+        output.status.success(),
+        "exited with {}: {}",
+        output.status,
+        stderr(&output)
+    );
+    assert_contains(
+        &stdout(&output),
+        &[
+            "[C] sum = 3",
+            "[C] [Rust cdylib] The result (1 + 2) is 3!",
+            "[C] overflow: sum = 0, the arithmetic result overflowed",
+        ],
+    );
+}
@@ -0,0 +1,284 @@
+// 这个 C 程序动态链接 cdylib_gen，由 tests/c_consumer.rs 运行并检查输出和退出码；
+// 传入 overflow 参数时加法溢出，程序以对应的 FfiStatus 作为退出码
+// This C program links cdylib_gen dynamically, tests/c_consumer.rs runs it and checks its
+// output and exit code; with the overflow argument the addition overflows and the program exits
+// with the matching FfiStatus
+#include <limits.h>
+#include <stddef.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+#include "cdylib_gen.h"
+
+// 加载到的库和头文件的 ABI 版本不一致时的退出码，和任何 FfiStatus 都不同
+// Exit code when the loaded library and the header disagree on the ABI version, distinct from every FfiStatus
+#define EXIT_ABI_MISMATCH 100
+
+// 把库的日志写到 stdout，和程序自己的输出放在一起
+// Writes the library's log messages to stdout alongside the program's own output
+static void print_log(enum LogLevel level, const char *msg, size_t msg_len, void *user_data)
+{
+    (void)level;
+    (void)user_data;
+    printf("%.*s\n", (int)msg_len, msg);
+}
+
+static int fail(const char *call, enum FfiStatus status)
+{
+    char msg[256] = "";
+    rxc_rustlib_last_error_message(msg, sizeof(msg));
+    fprintf(stderr, "[C] %s failed: %s\n", call, msg);
+    // panic 时还能取到带位置的 panic 消息
+    // After a panic the panic message with its location is available as well
+    if (status == FFI_STATUS_PANIC && rxc_rustlib_take_last_panic(msg, sizeof(msg)) > 0)
+    {
+        fprintf(stderr, "[C] Rust panicked: %s\n", msg);
+    }
+    return (int)status;
+}
+
+// 线程池任务的上下文：每个任务有自己的一格，rxc_pool_join 返回后主线程才读取结果
+// A pool job's context: every job has a slot of its own, the main thread only reads the results
+// after rxc_pool_join returns
+struct square
+{
+    int n;
+    int result;
+};
+
+static int square_job(void *user_data)
+{
+    const struct square *square = user_data;
+    return square->n * square->n;
+}
+
+static void square_done(int result, void *user_data)
+{
+    struct square *square = user_data;
+    square->result = result;
+}
+
+// 在池的线程上取消令牌，这样 C 程序不需要自己创建线程
+// Cancels the token on one of the pool's threads, so the C program needs no threads of its own
+static int cancel_job(void *user_data)
+{
+    const CancelTokenHandle *token = user_data;
+    return (int)rxc_cancel_token_cancel(*token);
+}
+
+static int run(int argc, char **argv)
+{
+    int a = argc > 1 && strcmp(argv[1], "overflow") == 0 ? INT_MAX : 1;
+    char result[64] = "C consumer";
+    int sum = 0;
+    enum FfiStatus status = rxc_cdylib_add(a, 2, result, sizeof(result), &sum, NULL);
+    if (status != FFI_STATUS_OK)
+    {
+        return fail("rxc_cdylib_add", status);
+    }
+    printf("[C] %s\n", result);
+
+    // 两次调用：先查询长度，再分配刚好够用的缓冲区
+    // Two calls: query the length first, then allocate a buffer that is just large enough
+    size_t required = 0;
+    status = rxc_cdylib_greeting_message("C", NULL, 0, &required);
+    if (status != FFI_STATUS_BUFFER_TOO_SMALL)
+    {
+        return fail("rxc_cdylib_greeting_message", status);
+    }
+    char *message = malloc(required + 1);
+    status = rxc_cdylib_greeting_message("C", message, required + 1, &required);
+    if (status != FFI_STATUS_OK)
+    {
+        free(message);
+        return fail("rxc_cdylib_greeting_message", status);
+    }
+    printf("[C] %s\n", message);
+    free(message);
+
+    // Rust 分配的字符串必须还给 rxc_rust_free
+    // Strings allocated by Rust must go back to rxc_rust_free
+    char *greeting = rxc_cdylib_make_greeting("C");
+    if (greeting == NULL)
+    {
+        return fail("rxc_cdylib_make_greeting", FFI_STATUS_NULL_POINTER);
+    }
+    printf("[C] %s\n", greeting);
+    rxc_rust_free(greeting);
+
+    // 以 argv 的形式传入字符串数组；返回的数组以 NULL 结尾，连同其中的字符串一起交给 rxc_rust_string_array_free
+    // A string array passed the way argv is; the returned array ends with a NULL and goes back to
+    // rxc_rust_string_array_free together with its strings
+    const char *parts[] = {"C", "calls", "Rust"};
+    char *joined = rxc_cdylib_join_strings(parts, 3, " ");
+    if (joined == NULL)
+    {
+        return fail("rxc_cdylib_join_strings", FFI_STATUS_NULL_POINTER);
+    }
+    printf("[C] Joined: %s\n", joined);
+    rxc_rust_free(joined);
+    size_t count = 0;
+    char **words = rxc_cdylib_split_words("one two three", &count);
+    if (words == NULL)
+    {
+        return fail("rxc_cdylib_split_words", FFI_STATUS_NULL_POINTER);
+    }
+    printf("[C] %zu words:", count);
+    for (char **word = words; *word != NULL; word++)
+    {
+        printf(" [%s]", *word);
+    }
+    printf("\n");
+    rxc_rust_string_array_free(words, count);
+
+    // 打包的消息头可以放在字节缓冲区中的任何位置；它的字段可能没有对齐，C 只按值读取它们
+    // A packed message header can sit anywhere in a byte buffer; its fields may be misaligned, so
+    // C only reads them by value
+    _Static_assert(sizeof(MessageHeader) == MESSAGE_HEADER_SIZE, "MessageHeader must not be padded");
+    unsigned char wire[1 + MESSAGE_HEADER_SIZE];
+    status = rxc_cdylib_header_init(7, 42, 512, (MessageHeader *)(wire + 1));
+    if (status != FFI_STATUS_OK)
+    {
+        return fail("rxc_cdylib_header_init", status);
+    }
+    MessageHeader header;
+    status = rxc_cdylib_header_parse((FfiSlice_u8){wire + 1, MESSAGE_HEADER_SIZE}, &header);
+    if (status != FFI_STATUS_OK)
+    {
+        return fail("rxc_cdylib_header_parse", status);
+    }
+    printf("[C] Header at an odd address: kind %u, sequence %llu, %u payload bytes\n",
+           (unsigned)header.kind, (unsigned long long)header.sequence, (unsigned)header.payload_len);
+    wire[1 + offsetof(MessageHeader, kind)] ^= 1;
+    status = rxc_cdylib_header_parse((FfiSlice_u8){wire + 1, MESSAGE_HEADER_SIZE}, &header);
+    printf("[C] Parsing a corrupted header: %s\n",
+           status == FFI_STATUS_MALFORMED ? "FFI_STATUS_MALFORMED" : "unexpected status");
+
+    // 任务和完成回调都在 Rust 的线程上运行；句柄按值传递，空句柄的 generation 为 0
+    // Jobs and their completion callbacks both run on Rust's threads; handles are passed by value
+    // and the null handle has generation 0
+    ThreadPoolHandle pool = rxc_pool_create(2);
+    if (pool.generation == 0)
+    {
+        return fail("rxc_pool_create", FFI_STATUS_NULL_POINTER);
+    }
+    struct square squares[4];
+    for (int i = 0; i < 4; i++)
+    {
+        squares[i] = (struct square){.n = i + 1, .result = 0};
+        status = rxc_pool_submit(pool, square_job, square_done, &squares[i]);
+        if (status != FFI_STATUS_OK)
+        {
+            rxc_pool_free(pool);
+            return fail("rxc_pool_submit", status);
+        }
+    }
+    rxc_pool_join(pool);
+    int squares_sum = 0;
+    for (int i = 0; i < 4; i++)
+    {
+        squares_sum += squares[i].result;
+    }
+    printf("[C] Sum of squares from the pool: %d\n", squares_sum);
+
+    // 完整运行要 5 秒；池的线程取消令牌后，rxc_cdylib_slow_sum 在下一步检查时返回
+    // A full run takes 5 seconds; once the pool's thread cancels the token, rxc_cdylib_slow_sum
+    // returns at its next check
+    CancelTokenHandle token = rxc_cancel_token_new();
+    if (token.generation == 0)
+    {
+        rxc_pool_free(pool);
+        return fail("rxc_cancel_token_new", FFI_STATUS_NULL_POINTER);
+    }
+    rxc_pool_submit(pool, cancel_job, NULL, &token);
+    int values[100];
+    for (int i = 0; i < 100; i++)
+    {
+        values[i] = 1;
+    }
+    long total = 0;
+    status = rxc_cdylib_slow_sum(values, 100, 50, token, &total);
+    rxc_pool_join(pool);
+    rxc_pool_free(pool);
+    rxc_cancel_token_free(token);
+    printf("[C] rxc_cdylib_slow_sum: %s\n", status == FFI_STATUS_CANCELLED ? "FFI_STATUS_CANCELLED" : "unexpected status");
+
+    // 释放过的句柄已经过期，再释放一次会被识别出来
+    // A freed handle is stale, so freeing it again is caught
+    status = rxc_cancel_token_free(token);
+    printf("[C] Freeing the token twice: %s\n", status == FFI_STATUS_INVALID_HANDLE ? "FFI_STATUS_INVALID_HANDLE" : "unexpected status");
+
+    // 通过不透明的建造者配置库：每个 setter 立即检查自己的参数，失败时建造者保持不变；
+    // 成功的 rxc_config_build 用掉建造者，失败时它仍然有效
+    // Configures the library through an opaque builder: every setter checks its own argument right
+    // away and leaves the builder unchanged when it fails; a successful rxc_config_build uses up
+    // the builder, while after a failing one it stays valid
+    ConfigBuilderHandle builder = rxc_config_new();
+    if (builder.generation == 0)
+    {
+        return fail("rxc_config_new", FFI_STATUS_NULL_POINTER);
+    }
+    ConfigHandle config;
+    status = rxc_config_build(builder, &config);
+    printf("[C] Building without a name: %s\n",
+           status == FFI_STATUS_INVALID_ARGUMENT ? "FFI_STATUS_INVALID_ARGUMENT" : "unexpected status");
+    status = rxc_config_set_buffer_size(builder, 1000);
+    printf("[C] A buffer of 1000 bytes: %s\n",
+           status == FFI_STATUS_INVALID_ARGUMENT ? "FFI_STATUS_INVALID_ARGUMENT" : "unexpected status");
+    if ((status = rxc_config_set_name(builder, "c consumer")) != FFI_STATUS_OK ||
+        (status = rxc_config_set_buffer_size(builder, 1024)) != FFI_STATUS_OK ||
+        (status = rxc_config_build(builder, &config)) != FFI_STATUS_OK)
+    {
+        rxc_config_builder_free(builder);
+        return fail("configuring the library", status);
+    }
+    char config_name[32];
+    size_t buffer_size = 0;
+    rxc_config_name(config, config_name, sizeof(config_name), NULL);
+    rxc_config_buffer_size(config, &buffer_size);
+    printf("[C] Built config \"%s\" with a %zu byte buffer\n", config_name, buffer_size);
+    rxc_config_free(config);
+
+    // 在 Rust 的异步运行时上运行一个请求，当前线程等它完成
+    // Runs a request on Rust's async runtime, the current thread waits for it to complete
+    AsyncRequest request = {.a = 20, .b = 22, .delay_ms = 10};
+    status = rxc_async_block_on(request, &sum);
+    if (status != FFI_STATUS_OK)
+    {
+        return fail("rxc_async_block_on", status);
+    }
+    printf("[C] rxc_async_block_on returned %d\n", sum);
+    return 0;
+}
+
+// 调用顺序：ABI 握手可以在初始化之前进行，其他函数要等 rxc_rustlib_init 之后，最后无论成败都调用 rxc_rustlib_shutdown
+// The call order: the ABI handshake may happen before initialization, the other functions wait
+// for rxc_rustlib_init, and rxc_rustlib_shutdown comes last whether or not the calls succeeded
+int main(int argc, char **argv)
+{
+    if (rxc_cdylib_abi_version() != CDYLIB_ABI_VERSION)
+    {
+        fprintf(stderr, "[C] cdylib_gen has ABI version %u, expected %d\n", rxc_cdylib_abi_version(), CDYLIB_ABI_VERSION);
+        return EXIT_ABI_MISMATCH;
+    }
+
+    int sum = 0;
+    enum FfiStatus status = rxc_cdylib_add(1, 2, NULL, 0, &sum, NULL);
+    printf("[C] rxc_cdylib_add before rxc_rustlib_init: %s\n",
+           status == FFI_STATUS_NOT_INITIALIZED ? "FFI_STATUS_NOT_INITIALIZED" : "unexpected status");
+
+    InitConfig config = {
+        .size = sizeof(InitConfig),
+        .log_callback = print_log,
+        .install_panic_hook = true,
+        .worker_threads = 1,
+    };
+    status = rxc_rustlib_init(&config);
+    if (status != FFI_STATUS_OK)
+    {
+        return fail("rxc_rustlib_init", status);
+    }
+    int code = run(argc, argv);
+    rxc_rustlib_shutdown();
+    return code;
+}
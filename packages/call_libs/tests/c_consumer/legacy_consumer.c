@@ -0,0 +1,41 @@
+// 这个 C 程序模仿按旧版头文件编译的调用方：不包含 cdylib_gen.h，自己声明 rxc_cdylib_add_v1 的旧签名，
+// 检查新版 cdylib_gen 仍然通过兼容垫片为它服务。由 tests/c_consumer.rs 运行
+// This C program mimics a caller compiled against an old header: it doesn't include
+// cdylib_gen.h but declares the old signature of rxc_cdylib_add_v1 itself, checking the new
+// cdylib_gen still serves it through the compatibility shim. tests/c_consumer.rs runs it
+#include <limits.h>
+#include <stddef.h>
+#include <stdio.h>
+
+// 旧版头文件中的声明；配置只按指针传递，NULL 即默认配置
+// The declarations of the old header; the config is only passed by pointer, NULL is the default
+int rxc_rustlib_init(const void *config);
+void rxc_rustlib_shutdown(void);
+int rxc_rustlib_last_error_message(char *buf, size_t len);
+int rxc_cdylib_add_v1(int a, int b, char *result);
+
+int main(void)
+{
+    if (rxc_rustlib_init(NULL) != 0)
+    {
+        fprintf(stderr, "[C] rxc_rustlib_init failed\n");
+        return 1;
+    }
+
+    // 旧签名不传缓冲区长度，调用方负责让缓冲区放得下整条消息
+    // The old signature passes no buffer length, the caller makes sure the whole message fits
+    char result[100] = "legacy caller";
+    int sum = rxc_cdylib_add_v1(1, 2, result);
+    printf("[C] sum = %d\n", sum);
+    printf("[C] %s\n", result);
+
+    // 失败时返回 0，原因在最后错误中
+    // On failure it returns 0 with the reason in the last error
+    char msg[256] = "";
+    sum = rxc_cdylib_add_v1(INT_MAX, 1, result);
+    rxc_rustlib_last_error_message(msg, sizeof(msg));
+    printf("[C] overflow: sum = %d, %s\n", sum, msg);
+
+    rxc_rustlib_shutdown();
+    return 0;
+}
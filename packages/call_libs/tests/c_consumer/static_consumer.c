@@ -0,0 +1,76 @@
+// 这个 C 程序静态链接 staticlib_gen，由 tests/c_consumer.rs 运行并检查输出和退出码；
+// 传入 overflow 参数时加法溢出，程序以对应的 FfiStatus 作为退出码
+// This C program links staticlib_gen statically, tests/c_consumer.rs runs it and checks its
+// output and exit code; with the overflow argument the addition overflows and the program exits
+// with the matching FfiStatus
+#include <limits.h>
+#include <stdio.h>
+#include <string.h>
+#include "staticlib_gen.h"
+
+// 把库的日志写到 stdout，和程序自己的输出放在一起
+// Writes the library's log messages to stdout alongside the program's own output
+static void print_log(enum LogLevel level, const char *msg, size_t msg_len, void *user_data)
+{
+    (void)level;
+    (void)user_data;
+    printf("%.*s\n", (int)msg_len, msg);
+}
+
+static int fail(const char *call, enum FfiStatus status)
+{
+    char msg[256] = "";
+    rxc_staticlib_last_error_message(msg, sizeof(msg));
+    fprintf(stderr, "[C] %s failed: %s\n", call, msg);
+    // panic 时还能取到带位置的 panic 消息
+    // After a panic the panic message with its location is available as well
+    if (status == FFI_STATUS_PANIC && rxc_staticlib_take_last_panic(msg, sizeof(msg)) > 0)
+    {
+        fprintf(stderr, "[C] Rust panicked: %s\n", msg);
+    }
+    return (int)status;
+}
+
+static int run(int argc, char **argv)
+{
+    int a = argc > 1 && strcmp(argv[1], "overflow") == 0 ? INT_MAX : 1;
+    char result[64] = "C consumer";
+    int sum = 0;
+    enum FfiStatus status = rxc_staticlib_add(a, 2, result, sizeof(result), &sum, NULL);
+    if (status != FFI_STATUS_OK)
+    {
+        return fail("rxc_staticlib_add", status);
+    }
+    printf("[C] %s\n", result);
+
+    int values[] = {1, 2, 3, 4};
+    long total = 0;
+    status = rxc_staticlib_sum(values, sizeof(values) / sizeof(values[0]), &total);
+    if (status != FFI_STATUS_OK)
+    {
+        return fail("rxc_staticlib_sum", status);
+    }
+    printf("[C] rxc_staticlib_sum returned %ld\n", total);
+    return 0;
+}
+
+// 先用 rxc_staticlib_init 初始化库，其他函数在此之前返回 FFI_STATUS_NOT_INITIALIZED；最后无论成败都关闭它
+// The library is initialized with rxc_staticlib_init first, the other functions return
+// FFI_STATUS_NOT_INITIALIZED before that; finally it is shut down whether or not the calls
+// succeeded
+int main(int argc, char **argv)
+{
+    InitConfig config = {
+        .size = sizeof(InitConfig),
+        .log_callback = print_log,
+        .install_panic_hook = true,
+    };
+    enum FfiStatus status = rxc_staticlib_init(&config);
+    if (status != FFI_STATUS_OK)
+    {
+        return fail("rxc_staticlib_init", status);
+    }
+    int code = run(argc, argv);
+    rxc_staticlib_shutdown();
+    return code;
+}
@@ -0,0 +1,100 @@
+// C 创建的锁的测试：C 线程和 Rust 线程争用同一把锁给计数器加 1，一次增加都不会丢失；Rust 持有守卫时
+// C 线程一直等待，守卫释放后才继续
+// Tests for a lock created by C: a C thread and a Rust thread contend for the same lock to add 1
+// to a counter and no increment gets lost; while Rust holds the guard the C thread keeps waiting
+// and only goes on once it is dropped
+//
+// clib.c 引用 staticlib_gen 中的符号，静态构建（--features static）中不运行
+// clib.c refers to symbols from staticlib_gen, so it does not run in the static build
+// (--features static)
+
+#![cfg(not(feature = "static"))]
+
+use std::thread;
+use std::time::Duration;
+
+use interop_common::{CMutex, FfiError, RawCMutex};
+
+#[path = "../src/clib.rs"]
+mod clib;
+
+use clib::{
+    clib_locked_counter_free, clib_locked_counter_join, clib_locked_counter_lock,
+    clib_locked_counter_new, clib_locked_counter_start, clib_locked_counter_value,
+    ClibLockedCounter,
+};
+
+const ROUNDS: u32 = 100_000;
+
+// 一个 C 创建的计数器，测试结束时交还给 C 释放
+// A counter created by C, handed back to C to free when the test ends
+struct Counter(*mut ClibLockedCounter);
+
+impl Counter {
+    fn new() -> Counter {
+        let counter = unsafe { clib_locked_counter_new() };
+        assert!(!counter.is_null());
+        Counter(counter)
+    }
+
+    fn mutex(&self) -> CMutex<u64> {
+        unsafe {
+            CMutex::from_raw(
+                clib_locked_counter_lock(self.0).cast::<RawCMutex>(),
+                clib_locked_counter_value(self.0),
+            )
+        }
+        .unwrap()
+    }
+
+    fn start(&self, rounds: u32) {
+        assert_eq!(unsafe { clib_locked_counter_start(self.0, rounds) }, 0);
+    }
+
+    fn join(&self) {
+        unsafe { clib_locked_counter_join(self.0) }
+    }
+}
+
+impl Drop for Counter {
+    fn drop(&mut self) {
+        unsafe { clib_locked_counter_free(self.0) }
+    }
+}
+
+#[test]
+fn c_and_rust_threads_contend_without_losing_increments() {
+    let counter = Counter::new();
+    let mutex = counter.mutex();
+    counter.start(ROUNDS);
+    thread::scope(|scope| {
+        scope.spawn(|| {
+            for _ in 0..ROUNDS {
+                *mutex.lock().unwrap() += 1;
+            }
+        });
+    });
+    counter.join();
+    assert_eq!(*mutex.lock().unwrap(), 2 * u64::from(ROUNDS));
+}
+
+#[test]
+fn c_waits_while_rust_holds_the_guard() {
+    let counter = Counter::new();
+    let mutex = counter.mutex();
+    let mut guard = mutex.lock().unwrap();
+    counter.start(1);
+    thread::sleep(Duration::from_millis(50));
+    assert_eq!(*guard, 0);
+    *guard = 10;
+    drop(guard);
+    counter.join();
+    assert_eq!(*mutex.lock().unwrap(), 11);
+}
+
+#[test]
+fn null_pointers_are_rejected() {
+    let mut value = 0u64;
+    let result = unsafe { CMutex::from_raw(std::ptr::null_mut(), &mut value) };
+    assert_eq!(result.err(), Some(FfiError::NullPointer));
+}
@@ -0,0 +1,27 @@
+// 集成测试共用的代码，测试文件用 mod common; 引入
+// Code shared by the integration tests, which pull it in with mod common;
+
+use std::ffi::c_int;
+use std::sync::Once;
+
+use interop_common::InitConfig;
+
+// 和 include/cdylib_gen.h 中 FFI_STATUS_OK 的值相同
+// The value of FFI_STATUS_OK in include/cdylib_gen.h
+const STATUS_OK: c_int = 0;
+
+extern "C" {
+    fn rxc_rustlib_init(config: *const InitConfig) -> c_int;
+    fn rxc_staticlib_init(config: *const InitConfig) -> c_int;
+}
+
+/// Initializes the linked dynamic and static libraries once for the whole test binary, with the
+/// `config` of the first call. The tests run in any order and on several threads, so each one
+/// calls this first.
+pub fn init(config: &InitConfig) {
+    static INIT: Once = Once::new();
+    INIT.call_once(|| {
+        assert_eq!(unsafe { rxc_rustlib_init(config) }, STATUS_OK);
+        assert_eq!(unsafe { rxc_staticlib_init(config) }, STATUS_OK);
+    });
+}
@@ -0,0 +1,197 @@
+// 从 16 个线程同时调用链接进来的 rxc_cdylib_add 和 rxc_staticlib_add，每个线程用自己的缓冲区，
+// 检查结果、消息和每个线程自己的最近错误互不干扰；再让这些线程共用一个 Calculator 句柄
+// Calls the linked rxc_cdylib_add and rxc_staticlib_add from 16 threads at once, each with buffers
+// of its own, and checks that results, messages and each thread's last error do not interfere; then
+// has the threads share one Calculator handle
+//
+// 需要动态库，静态构建（--features static）中不运行
+// Needs the dynamic library, so it does not run in the static build (--features static)
+
+#![cfg(not(feature = "static"))]
+
+use std::ffi::{c_char, c_int, c_void, CStr};
+use std::sync::Barrier;
+use std::{ptr, thread};
+
+use interop_common::{FfiHandle, InitConfig, LogLevel};
+
+mod common;
+use common::init;
+
+const THREADS: usize = 16;
+const CALLS: c_int = 500;
+const STATUS_OK: c_int = 0;
+const STATUS_OVERFLOW: c_int = 4;
+
+type AddFn =
+    unsafe extern "C" fn(c_int, c_int, *mut c_char, usize, *mut c_int, *mut usize) -> c_int;
+
+extern "C" {
+    fn rxc_cdylib_add(
+        a: c_int,
+        b: c_int,
+        result: *mut c_char,
+        result_len: usize,
+        sum: *mut c_int,
+        required_len: *mut usize,
+    ) -> c_int;
+    fn rxc_staticlib_add(
+        a: c_int,
+        b: c_int,
+        result: *mut c_char,
+        result_len: usize,
+        sum: *mut c_int,
+        required_len: *mut usize,
+    ) -> c_int;
+    fn rxc_rustlib_last_error_message(buf: *mut c_char, len: usize) -> c_int;
+    fn rxc_calc_new() -> FfiHandle;
+    fn rxc_calc_add(handle: FfiHandle, a: c_int, b: c_int, sum: *mut c_int) -> c_int;
+    fn rxc_calc_history(
+        handle: FfiHandle,
+        buf: *mut c_char,
+        len: usize,
+        required_len: *mut usize,
+    ) -> c_int;
+    fn rxc_calc_free(handle: FfiHandle) -> c_int;
+}
+
+// 丢弃日志消息，否则每次调用都会在 stderr 上留下一行；回调本身也会被多个线程同时调用
+// Discards the log messages, which would otherwise leave a line on stderr for every call; the
+// callback itself is called from several threads at once
+unsafe extern "C" fn discard_log(_: LogLevel, _: *const c_char, _: usize, _: *mut c_void) {}
+
+fn quiet_config() -> InitConfig {
+    InitConfig {
+        log_callback: Some(discard_log),
+        ..InitConfig::default()
+    }
+}
+
+fn name_buf(name: &str, capacity: usize) -> Vec<c_char> {
+    let mut buf = vec![0; capacity];
+    for (dst, &src) in buf.iter_mut().zip(name.as_bytes()) {
+        *dst = src as c_char;
+    }
+    buf
+}
+
+fn to_string(buf: &[c_char]) -> String {
+    unsafe { CStr::from_ptr(buf.as_ptr()) }
+        .to_str()
+        .unwrap()
+        .to_owned()
+}
+
+// 所有线程在同一个屏障后同时开始，每个线程调用 CALLS 次，每次都重新填入自己的名字
+// Every thread starts at once behind the same barrier and makes CALLS calls, refilling its own
+// name each time
+fn hammer(add: AddFn, label: &str) {
+    init(&quiet_config());
+    let barrier = Barrier::new(THREADS);
+    thread::scope(|scope| {
+        for t in 0..THREADS {
+            let barrier = &barrier;
+            scope.spawn(move || {
+                let t = t as c_int;
+                let name = format!("thread {}", t);
+                barrier.wait();
+                for i in 0..CALLS {
+                    let mut buf = name_buf(&name, 64);
+                    let (mut sum, mut required) = (0, 0);
+                    let status =
+                        unsafe { add(t, i, buf.as_mut_ptr(), buf.len(), &mut sum, &mut required) };
+                    let expected =
+                        format!("[Rust {}] The result ({} + {}) is {}!", label, t, i, t + i);
+                    assert_eq!(status, STATUS_OK, "thread {} call {}", t, i);
+                    assert_eq!(sum, t + i);
+                    assert_eq!(required, expected.len());
+                    assert_eq!(to_string(&buf), expected);
+                }
+            });
+        }
+    });
+}
+
+#[test]
+fn cdylib_add_from_16_threads() {
+    hammer(rxc_cdylib_add, "cdylib");
+}
+
+#[test]
+fn staticlib_add_from_16_threads() {
+    hammer(rxc_staticlib_add, "staticlib");
+}
+
+// 一半线程的调用溢出，另一半成功，每个线程读到的最近错误只反映它自己的调用
+// Half of the threads overflow and the other half succeed, the last error each thread reads only
+// reflects its own calls
+#[test]
+fn last_error_stays_per_thread() {
+    init(&quiet_config());
+    let barrier = Barrier::new(THREADS);
+    thread::scope(|scope| {
+        for t in 0..THREADS {
+            let barrier = &barrier;
+            scope.spawn(move || {
+                let overflow = t % 2 == 0;
+                let a = if overflow { c_int::MAX } else { 1 };
+                barrier.wait();
+                for _ in 0..CALLS {
+                    let mut sum = 0;
+                    let status = unsafe {
+                        rxc_cdylib_add(a, 1, ptr::null_mut(), 0, &mut sum, ptr::null_mut())
+                    };
+                    let mut msg = [0 as c_char; 128];
+                    unsafe { rxc_rustlib_last_error_message(msg.as_mut_ptr(), msg.len()) };
+                    let msg = to_string(&msg);
+                    if overflow {
+                        assert_eq!(status, STATUS_OVERFLOW);
+                        assert_eq!(msg, "the arithmetic result overflowed");
+                    } else {
+                        // 查询长度的调用返回 BUFFER_TOO_SMALL，但不会看到其他线程的溢出
+                        // The length query returns BUFFER_TOO_SMALL, but never sees another
+                        // thread's overflow
+                        assert_ne!(status, STATUS_OVERFLOW);
+                        assert_ne!(msg, "the arithmetic result overflowed");
+                    }
+                }
+            });
+        }
+    });
+}
+
+// 多个线程共用一个 Calculator 句柄时，每次加法都完整地记进历史
+// With several threads sharing one Calculator handle, every addition lands in the history intact
+#[test]
+fn calculator_shared_between_threads() {
+    init(&quiet_config());
+    let calc = unsafe { rxc_calc_new() };
+    assert!(!calc.is_null());
+    thread::scope(|scope| {
+        for t in 0..THREADS as c_int {
+            scope.spawn(move || {
+                for i in 0..CALLS {
+                    let mut sum = 0;
+                    let status = unsafe { rxc_calc_add(calc, t, i, &mut sum) };
+                    assert_eq!((status, sum), (STATUS_OK, t + i));
+                }
+            });
+        }
+    });
+
+    let mut required = 0;
+    unsafe { rxc_calc_history(calc, ptr::null_mut(), 0, &mut required) };
+    let mut buf = vec![0 as c_char; required + 1];
+    let status = unsafe { rxc_calc_history(calc, buf.as_mut_ptr(), buf.len(), ptr::null_mut()) };
+    assert_eq!(status, STATUS_OK);
+    assert_eq!(unsafe { rxc_calc_free(calc) }, STATUS_OK);
+
+    let history = to_string(&buf);
+    let mut lines: Vec<&str> = history.lines().collect();
+    let mut expected: Vec<String> = (0..THREADS as c_int)
+        .flat_map(|t| (0..CALLS).map(move |i| format!("{} + {} = {}", t, i, t + i)))
+        .collect();
+    lines.sort_unstable();
+    expected.sort_unstable();
+    assert_eq!(lines, expected);
+}
@@ -0,0 +1,57 @@
+// C 线程通过条件变量唤醒 Rust 线程的测试：clib.c 启动的生产者交出的每一项工作都按顺序收到，线程结束时
+// 交还它持有的队列引用
+// Tests for a C thread waking a Rust thread through a condition variable: every piece of work the
+// producer started by clib.c hands over arrives in order, and the thread hands back the queue
+// reference it held as it finishes
+//
+// clib.c 引用 staticlib_gen 中的符号，静态构建（--features static）中不运行
+// clib.c refers to symbols from staticlib_gen, so it does not run in the static build
+// (--features static)
+
+#![cfg(not(feature = "static"))]
+
+use std::sync::Arc;
+use std::thread;
+
+use condvar_interop::{work_queue_close, work_queue_push, WorkQueue};
+
+#[path = "../src/clib.rs"]
+mod clib;
+
+use clib::clib_spawn_producer;
+
+fn spawn_c_producer(queue: &Arc<WorkQueue>, count: u32, interval_ms: u32) {
+    let ctx = queue.producer();
+    let status = unsafe {
+        clib_spawn_producer(
+            count,
+            interval_ms,
+            Some(work_queue_push),
+            Some(work_queue_close),
+            ctx,
+        )
+    };
+    assert_eq!(status, 0);
+}
+
+#[test]
+fn rust_thread_is_woken_by_a_c_thread() {
+    let queue = WorkQueue::new();
+    spawn_c_producer(&queue, 5, 5);
+    let consumer = thread::spawn({
+        let queue = Arc::clone(&queue);
+        move || std::iter::from_fn(|| queue.pop()).collect::<Vec<_>>()
+    });
+    assert_eq!(consumer.join().unwrap(), [1, 2, 3, 4, 5]);
+    assert_eq!(queue.producers(), 0);
+}
+
+#[test]
+fn several_c_threads_feed_one_queue() {
+    let queue = WorkQueue::new();
+    for _ in 0..4 {
+        spawn_c_producer(&queue, 50, 0);
+    }
+    let received = std::iter::from_fn(|| queue.pop()).count();
+    assert_eq!(received, 200);
+}
@@ -0,0 +1,90 @@
+// 加载时和卸载时运行的函数的测试：cdylib_gen 的构造函数在 main 之前、rxc_rustlib_init 之前运行；
+// external_dy 的构造函数在 dlopen 返回之前运行，析构函数在库被卸载时调用登记的 hook
+// Tests for the functions run at load and unload time: cdylib_gen's constructor runs before main
+// and before rxc_rustlib_init; external_dy's constructor runs before dlopen returns, and its
+// destructor calls the registered hook when the library is unloaded
+
+#![cfg(all(unix, not(feature = "static")))]
+
+use std::ffi::{c_int, c_void};
+use std::path::Path;
+use std::ptr;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use libloading::Library;
+
+const STATUS_OK: c_int = 0;
+
+#[repr(C)]
+struct LoadReport {
+    constructor_runs: u32,
+    initialized_at_load: bool,
+}
+
+extern "C" {
+    fn rxc_cdylib_load_report(out: *mut LoadReport) -> c_int;
+}
+
+type DyloadingConstructorRuns = unsafe extern "C" fn() -> i32;
+type UnloadHook = extern "C" fn(ctx: *mut c_void);
+type DyloadingSetUnloadHook = unsafe extern "C" fn(Option<UnloadHook>, *mut c_void);
+
+// hook 在测试程序里，不在被卸载的库里
+// The hook lives in the test program, not in the library being unloaded
+extern "C" fn count_unload(ctx: *mut c_void) {
+    let unloads = unsafe { &*(ctx as *const AtomicU32) };
+    unloads.fetch_add(1, Ordering::SeqCst);
+}
+
+fn open() -> Library {
+    unsafe { Library::new(Path::new(env!("OUT_DIR")).join("libexternal_dy.so")) }.unwrap()
+}
+
+fn constructor_runs(lib: &Library) -> i32 {
+    unsafe {
+        lib.get::<DyloadingConstructorRuns>(b"dyloading_constructor_runs\0")
+            .unwrap()()
+    }
+}
+
+#[test]
+fn cdylib_constructor_ran_before_initialization() {
+    let mut report = LoadReport {
+        constructor_runs: 0,
+        initialized_at_load: true,
+    };
+    assert_eq!(unsafe { rxc_cdylib_load_report(&mut report) }, STATUS_OK);
+    assert_eq!(report.constructor_runs, 1);
+    assert!(!report.initialized_at_load);
+}
+
+// 这个测试程序里只有这一个测试加载 external_dy，别的副本会让它一直保持加载
+// The only test in this program loading external_dy, another copy would keep it loaded
+#[test]
+fn external_destructor_runs_on_unload() {
+    static UNLOADS: AtomicU32 = AtomicU32::new(0);
+
+    let lib = open();
+    // 还没有调用任何函数，构造函数已经运行过了
+    // The constructor already ran before anything was called
+    assert_eq!(constructor_runs(&lib), 1);
+    unsafe {
+        let set_hook = lib
+            .get::<DyloadingSetUnloadHook>(b"dyloading_set_unload_hook\0")
+            .unwrap();
+        set_hook(
+            Some(count_unload),
+            ptr::from_ref(&UNLOADS).cast_mut().cast(),
+        );
+    }
+    assert_eq!(UNLOADS.load(Ordering::SeqCst), 0);
+    drop(lib);
+    assert_eq!(UNLOADS.load(Ordering::SeqCst), 1);
+
+    // 重新加载的副本又运行一次构造函数，hook 随旧的静态变量一起消失
+    // The reloaded copy runs its constructor again, and the hook went away with the old statics
+    let lib = open();
+    assert_eq!(constructor_runs(&lib), 1);
+    drop(lib);
+    assert_eq!(UNLOADS.load(Ordering::SeqCst), 1);
+}
@@ -0,0 +1,75 @@
+// 运行 build.rs 从 tests/cpp_consumer 编译的 C++ 程序，检查回调中的异常在 extern "C" 边界上被捕获，
+// 以及通过 extern "C-unwind" 穿过 Rust 时 Rust 的析构函数先于 C++ 的 catch 运行
+// Runs the C++ program build.rs compiles from tests/cpp_consumer and checks that an exception in a
+// callback is caught at the extern "C" boundary, and that when it passes through Rust via
+// extern "C-unwind" Rust's destructors run before the C++ catch
+//
+// 需要动态库，静态构建（--features static）中不运行
+// Needs the dynamic library, so it does not run in the static build (--features static)
+
+#![cfg(not(feature = "static"))]
+
+use std::env::consts::EXE_SUFFIX;
+use std::path::Path;
+use std::process::Command;
+
+fn run_cpp_consumer() -> Vec<String> {
+    let path = Path::new(env!("OUT_DIR")).join(format!("cpp_consumer{}", EXE_SUFFIX));
+    let output = Command::new(&path)
+        .output()
+        .unwrap_or_else(|err| panic!("cannot run {}: {}", path.display(), err));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "exited with {}:\n{}",
+        output.status,
+        stdout
+    );
+    stdout.lines().map(str::to_owned).collect()
+}
+
+fn position(lines: &[String], line: &str) -> usize {
+    lines
+        .iter()
+        .position(|l| l == line)
+        .unwrap_or_else(|| panic!("missing {:?} in {:#?}", line, lines))
+}
+
+#[test]
+fn calls_both_libraries() {
+    let lines = run_cpp_consumer();
+    position(&lines, "[C++] [Rust staticlib] The result (2 + 3) is 5!");
+    position(&lines, "[C++] [Rust cdylib] The result (2 + 3) is 5!");
+}
+
+#[test]
+fn extern_c_callback_catches_at_the_boundary() {
+    let lines = run_cpp_consumer();
+    position(&lines, "[C++] rxc_cdylib_apply(21) = 42");
+    let caught = position(
+        &lines,
+        "[C++] Caught \"doubling 2147483647 overflows\" before it reached Rust",
+    );
+    assert!(caught < position(&lines, "[C++] rxc_cdylib_apply(INT_MAX) = -1"));
+}
+
+#[test]
+fn c_unwind_callback_unwinds_through_rust() {
+    let lines = run_cpp_consumer();
+    let returned = position(&lines, "[C++] rxc_cdylib_apply_unwind(21) = 42");
+    let caught = position(
+        &lines,
+        "[C++] Caught \"doubling 2147483647 overflows\" after it unwound through Rust",
+    );
+    // 正常返回和展开时 Rust 的析构函数都各运行一次，并且都在 C++ 看到结果之前
+    // Rust's destructor runs once for the normal return and once while unwinding, each before
+    // C++ sees the outcome
+    let cleanups: Vec<_> = lines
+        .iter()
+        .enumerate()
+        .filter(|(_, line)| *line == "[Rust cdylib] rxc_cdylib_apply_unwind cleaned up")
+        .map(|(i, _)| i)
+        .collect();
+    assert_eq!(cleanups.len(), 2, "{:#?}", lines);
+    assert!(cleanups[0] < returned && returned < cleanups[1] && cleanups[1] < caught);
+}
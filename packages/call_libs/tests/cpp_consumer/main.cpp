@@ -0,0 +1,103 @@
+// 这个 C++ 程序调用两个 Rust 库，并展示回调中抛出的 C++ 异常如何与 Rust 交互，
+// 由 tests/cpp_consumer.rs 运行并检查输出
+// This C++ program calls both Rust libraries and shows how a C++ exception thrown in a callback
+// interacts with Rust; tests/cpp_consumer.rs runs it and checks its output
+#include <climits>
+#include <cstdio>
+#include <stdexcept>
+#include <string>
+
+extern "C" {
+#include "cdylib_gen.h"
+}
+
+bool call_staticlib();
+
+static int checked_double(int value)
+{
+    if (value > INT_MAX / 2 || value < INT_MIN / 2)
+    {
+        throw std::overflow_error("doubling " + std::to_string(value) + " overflows");
+    }
+    return value * 2;
+}
+
+// 传给 rxc_cdylib_apply 的回调绝不能让异常逃出：Rust 假定 extern "C" 调用不会展开，
+// 异常穿过 Rust 是未定义行为，所以在边界上捕获它并换成返回值
+// A callback passed to rxc_cdylib_apply must never let an exception escape: Rust assumes extern "C"
+// calls do not unwind and an exception passing through Rust is undefined behavior, so it is
+// caught at the boundary and turned into a return value
+extern "C" int double_or_minus_one(int value)
+{
+    try
+    {
+        return checked_double(value);
+    }
+    catch (const std::exception &err)
+    {
+        std::printf("[C++] Caught \"%s\" before it reached Rust\n", err.what());
+        return -1;
+    }
+}
+
+// 传给 rxc_cdylib_apply_unwind 的回调可以抛出：Rust 用 extern "C-unwind" 声明它，异常穿过 Rust
+// 回到调用方，途中运行 Rust 的析构函数
+// A callback passed to rxc_cdylib_apply_unwind may throw: Rust declares it extern "C-unwind", so
+// the exception passes through Rust back to the caller, running Rust's destructors on the way
+extern "C" int double_or_throw(int value)
+{
+    return checked_double(value);
+}
+
+// 把 cdylib_gen 的日志写到 stdout，staticlib_gen 在 static_part.cpp 中有自己的一份
+// Writes cdylib_gen's log messages to stdout, staticlib_gen gets its own copy in static_part.cpp
+static void print_log(LogLevel, const char *msg, size_t msg_len, void *)
+{
+    std::printf("%.*s\n", static_cast<int>(msg_len), msg);
+}
+
+int main()
+{
+    // Rust 的标准输出按行刷新，关闭 C 的缓冲让两者在管道中保持顺序
+    // Rust flushes standard output per line, unbuffered C output keeps both in order in a pipe
+    std::setvbuf(stdout, nullptr, _IONBF, 0);
+    InitConfig config{};
+    config.size = sizeof(InitConfig);
+    config.log_callback = print_log;
+    if (rxc_rustlib_init(&config) != FFI_STATUS_OK)
+    {
+        std::printf("[C++] rxc_rustlib_init failed\n");
+        return 1;
+    }
+
+    bool ok = call_staticlib();
+
+    char result[64] = "C++";
+    int sum = 0;
+    if (rxc_cdylib_add(2, 3, result, sizeof(result), &sum, nullptr) == FFI_STATUS_OK && sum == 5)
+    {
+        std::printf("[C++] %s\n", result);
+    }
+    else
+    {
+        std::printf("[C++] rxc_cdylib_add failed\n");
+        ok = false;
+    }
+
+    std::printf("[C++] rxc_cdylib_apply(21) = %d\n", rxc_cdylib_apply(21, double_or_minus_one));
+    std::printf("[C++] rxc_cdylib_apply(INT_MAX) = %d\n", rxc_cdylib_apply(INT_MAX, double_or_minus_one));
+
+    std::printf("[C++] rxc_cdylib_apply_unwind(21) = %d\n", rxc_cdylib_apply_unwind(21, double_or_throw));
+    try
+    {
+        int value = rxc_cdylib_apply_unwind(INT_MAX, double_or_throw);
+        std::printf("[C++] rxc_cdylib_apply_unwind(INT_MAX) unexpectedly returned %d\n", value);
+        ok = false;
+    }
+    catch (const std::overflow_error &err)
+    {
+        std::printf("[C++] Caught \"%s\" after it unwound through Rust\n", err.what());
+    }
+    rxc_rustlib_shutdown();
+    return ok ? 0 : 1;
+}
@@ -0,0 +1,43 @@
+// staticlib_gen.h 和 cdylib_gen.h 都定义了 FfiStatus，所以把它放在单独的翻译单元中
+// staticlib_gen.h and cdylib_gen.h both define FfiStatus, so it gets a translation unit of its own
+#include <cstdio>
+
+// cbindgen 生成的是 C 头文件，C++ 中要放在 extern "C" 里才能按 C 的符号名链接
+// cbindgen generates C headers, in C++ they go inside extern "C" to link against the C symbol names
+extern "C" {
+#include "staticlib_gen.h"
+}
+
+// staticlib_gen 的日志回调和 cdylib_gen 的一样通过 InitConfig 注册
+// staticlib_gen's log callback is registered through the InitConfig, like cdylib_gen's
+static void print_log(LogLevel, const char *msg, size_t msg_len, void *)
+{
+    std::printf("%.*s\n", static_cast<int>(msg_len), msg);
+}
+
+// staticlib_gen 有自己的 init 和 shutdown，所以在调用前后初始化和关闭它
+// staticlib_gen has an init and shutdown of its own, so it is initialized and shut down around
+// the call
+bool call_staticlib()
+{
+    InitConfig config{};
+    config.size = sizeof(InitConfig);
+    config.log_callback = print_log;
+    if (rxc_staticlib_init(&config) != FFI_STATUS_OK)
+    {
+        std::printf("[C++] rxc_staticlib_init failed\n");
+        return false;
+    }
+
+    char result[64] = "C++";
+    int sum = 0;
+    FfiStatus status = rxc_staticlib_add(2, 3, result, sizeof(result), &sum, nullptr);
+    rxc_staticlib_shutdown();
+    if (status != FFI_STATUS_OK)
+    {
+        std::printf("[C++] rxc_staticlib_add failed\n");
+        return false;
+    }
+    std::printf("[C++] %s\n", result);
+    return sum == 5;
+}
@@ -0,0 +1,61 @@
+// dlopen 标志的测试：external_lib/dependent.c 编译出的库只有在 external_dy 以 RTLD_GLOBAL 加载后才能加载，
+// 已经以 RTLD_LOCAL 加载的库可以通过再次以 RTLD_GLOBAL 打开来提升
+// Tests for the dlopen flags: the library built from external_lib/dependent.c only loads once
+// external_dy was loaded with RTLD_GLOBAL, and a library already loaded with RTLD_LOCAL can be
+// promoted by opening it again with RTLD_GLOBAL
+
+#![cfg(all(unix, not(target_os = "macos"), not(feature = "static")))]
+
+use std::ffi::{c_char, c_int};
+use std::path::{Path, PathBuf};
+
+use libloading::os::unix::{Library, RTLD_GLOBAL, RTLD_LAZY, RTLD_LOCAL, RTLD_NOW};
+
+type Add = unsafe extern "C" fn(c_int, c_int, *mut c_char) -> c_int;
+type CallCount = unsafe extern "C" fn() -> c_int;
+
+fn path(file: &str) -> PathBuf {
+    Path::new(env!("OUT_DIR")).join(file)
+}
+
+fn open_dependent() -> Result<Library, libloading::Error> {
+    unsafe {
+        Library::open(
+            Some(path("libexternal_dependent.so")),
+            RTLD_NOW | RTLD_LOCAL,
+        )
+    }
+}
+
+// 一个测试里按顺序进行：提升之后 external_dy 的符号在整个进程中都可见
+// Done in order in a single test: once promoted, external_dy's symbols are visible to the whole
+// process
+#[test]
+fn global_symbols_resolve_later_libraries() {
+    let local =
+        unsafe { Library::open(Some(path("libexternal_dy.so")), RTLD_LAZY | RTLD_LOCAL) }.unwrap();
+    let err = open_dependent().expect_err("dyloading_call_count should be missing");
+    assert!(
+        err.to_string()
+            .contains("undefined symbol: dyloading_call_count"),
+        "{err}"
+    );
+
+    let global =
+        unsafe { Library::open(Some(path("libexternal_dy.so")), RTLD_NOW | RTLD_GLOBAL) }.unwrap();
+    let dependent = open_dependent().unwrap();
+    unsafe {
+        let add = global.get::<Add>(b"dyloading_add\0").unwrap();
+        let mut buf = [0 as c_char; 128];
+        add(1, 2, buf.as_mut_ptr());
+        // 计数来自 external_dy 的那一份静态变量
+        // The count comes from external_dy's copy of the static
+        let own = global.get::<CallCount>(b"dyloading_call_count\0").unwrap();
+        let seen = dependent
+            .get::<CallCount>(b"dependent_call_count\0")
+            .unwrap();
+        assert!(own() >= 1);
+        assert_eq!(seen(), own());
+    }
+    drop(local);
+}
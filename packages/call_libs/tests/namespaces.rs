@@ -0,0 +1,93 @@
+// dlmopen 的测试：build.rs 编译的两个 external_dy 构建导出同名符号，加载进各自的命名空间后各有一份
+// 静态状态，和用 dlopen 加载进主命名空间的那一份也互不影响
+// Tests for dlmopen: the two builds of external_dy build.rs compiles export the same symbol names,
+// and once loaded into namespaces of their own each has its own static state, independent of the
+// copy dlopen loads into the main namespace as well
+
+#![cfg(all(target_os = "linux", target_env = "gnu"))]
+
+use std::ffi::{c_char, c_int, c_long, c_void, CStr, CString};
+use std::path::Path;
+
+use libloading::os::unix::{Library, RTLD_NOW};
+
+const LM_ID_BASE: c_long = 0;
+const LM_ID_NEWLM: c_long = -1;
+const RTLD_DI_LMID: c_int = 1;
+
+extern "C" {
+    fn dlmopen(lmid: c_long, filename: *const c_char, flags: c_int) -> *mut c_void;
+    fn dlinfo(handle: *mut c_void, request: c_int, info: *mut c_void) -> c_int;
+}
+
+type DyloadingAdd = unsafe extern "C" fn(c_int, c_int, *mut c_char) -> c_int;
+type DyloadingCallCount = unsafe extern "C" fn() -> c_int;
+
+fn path(file: &str) -> CString {
+    let path = Path::new(env!("OUT_DIR")).join(file);
+    CString::new(path.to_str().unwrap()).unwrap()
+}
+
+// 返回库和它所在的命名空间
+// Returns the library and the namespace it lives in
+fn open_in(lmid: c_long, file: &str) -> (Library, c_long) {
+    unsafe {
+        let handle = dlmopen(lmid, path(file).as_ptr(), RTLD_NOW);
+        assert!(!handle.is_null(), "dlmopen {} failed", file);
+        let mut namespace: c_long = -1;
+        assert_eq!(
+            dlinfo(handle, RTLD_DI_LMID, (&mut namespace as *mut c_long).cast()),
+            0
+        );
+        (Library::from_raw(handle), namespace)
+    }
+}
+
+fn add(lib: &Library) -> String {
+    let mut buf = [0 as c_char; 128];
+    unsafe {
+        let add = lib.get::<DyloadingAdd>(b"dyloading_add\0").unwrap();
+        assert_eq!(add(1, 2, buf.as_mut_ptr()), 3);
+        CStr::from_ptr(buf.as_ptr()).to_str().unwrap().to_owned()
+    }
+}
+
+fn call_count(lib: &Library) -> c_int {
+    unsafe {
+        lib.get::<DyloadingCallCount>(b"dyloading_call_count\0")
+            .unwrap()()
+    }
+}
+
+#[test]
+fn each_build_gets_a_namespace_of_its_own() {
+    let (first, first_ns) = open_in(LM_ID_NEWLM, "libexternal_dy.so");
+    let (alt, alt_ns) = open_in(LM_ID_NEWLM, "libexternal_dy_alt.so");
+    assert_ne!(first_ns, LM_ID_BASE);
+    assert_ne!(alt_ns, LM_ID_BASE);
+    assert_ne!(first_ns, alt_ns);
+
+    // 同名的 dyloading_add 解析到各自的构建
+    // The same dyloading_add name resolves to each build's own function
+    assert_eq!(add(&first), "[External dyloading] The result (1 + 2) is 3!");
+    assert_eq!(
+        add(&alt),
+        "[External dyloading alt] The result (1 + 2) is 3!"
+    );
+    add(&first);
+    assert_eq!(call_count(&first), 2);
+    assert_eq!(call_count(&alt), 1);
+}
+
+// LM_ID_BASE 即 dlopen 使用的主命名空间
+// LM_ID_BASE is the main namespace dlopen uses
+#[test]
+fn the_same_file_in_two_namespaces_is_two_copies() {
+    let (isolated, isolated_ns) = open_in(LM_ID_NEWLM, "libexternal_dy.so");
+    let (shared, shared_ns) = open_in(LM_ID_BASE, "libexternal_dy.so");
+    assert_ne!(isolated_ns, shared_ns);
+    assert_eq!(shared_ns, LM_ID_BASE);
+    add(&isolated);
+    assert_eq!(call_count(&isolated), 1);
+    assert_eq!(call_count(&shared), 0);
+}
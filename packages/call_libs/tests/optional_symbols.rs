@@ -0,0 +1,93 @@
+// 可选符号的测试：build.rs 编译的 external_dy 有 dyloading_add_v2，OUT_DIR/old 中较早的构建没有。
+// call_libs 用 Library::get 探测它，缺失时退回 dyloading_add 而不是 panic
+// Tests for optional symbols: the external_dy build.rs compiles has dyloading_add_v2, the older
+// build in OUT_DIR/old doesn't. call_libs probes for it with Library::get and falls back to
+// dyloading_add instead of panicking when it's missing
+
+#![cfg(all(unix, not(target_os = "macos"), not(feature = "static")))]
+
+use std::ffi::{c_char, c_int, CStr};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use libloading::Library;
+
+type DyloadingAdd = unsafe extern "C" fn(c_int, c_int, *mut c_char) -> c_int;
+type DyloadingAddV2 =
+    unsafe extern "C" fn(c_int, c_int, *const c_char, *mut c_char, usize) -> c_int;
+
+fn new_dir() -> PathBuf {
+    PathBuf::from(env!("OUT_DIR"))
+}
+
+fn old_dir() -> PathBuf {
+    new_dir().join("old")
+}
+
+fn open(dir: &Path) -> Library {
+    unsafe { Library::new(dir.join("libexternal_dy.so")) }.unwrap()
+}
+
+// 只运行 dlopen 后端，返回它的输出
+// Runs only the dlopen backend and returns what it printed
+fn run_dlopen_backend(dir: &Path) -> String {
+    let output = Command::new(env!("CARGO_BIN_EXE_call_libs"))
+        .args(["--backend", "dlopen", "--no-examples", "--lib-path"])
+        .arg(dir)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "exited with {}", output.status);
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+#[test]
+fn only_the_new_build_has_add_v2() {
+    let new = open(&new_dir());
+    let old = open(&old_dir());
+    unsafe {
+        assert!(new.get::<DyloadingAddV2>(b"dyloading_add_v2\0").is_ok());
+        assert!(old.get::<DyloadingAddV2>(b"dyloading_add_v2\0").is_err());
+        // 两个构建都有 v1
+        // Both builds have v1
+        assert!(new.get::<DyloadingAdd>(b"dyloading_add\0").is_ok());
+        assert!(old.get::<DyloadingAdd>(b"dyloading_add\0").is_ok());
+    }
+}
+
+// dyloading_add_v2 按给出的长度截断结果
+// dyloading_add_v2 truncates the result to the length it is given
+#[test]
+fn add_v2_stays_within_the_buffer() {
+    let new = open(&new_dir());
+    let mut buf = [0x7f as c_char; 16];
+    let message = unsafe {
+        let add_v2 = new.get::<DyloadingAddV2>(b"dyloading_add_v2\0").unwrap();
+        assert_eq!(add_v2(1, 2, c"Jack".as_ptr(), buf.as_mut_ptr(), 8), 3);
+        CStr::from_ptr(buf.as_ptr()).to_str().unwrap()
+    };
+    assert_eq!(message, "[Extern");
+    assert!(buf[8..].iter().all(|&byte| byte == 0x7f));
+}
+
+#[test]
+fn new_build_uses_add_v2() {
+    let stdout = run_dlopen_backend(&new_dir());
+    assert!(!stdout.contains("falling back"), "{stdout}");
+    assert!(
+        stdout.contains("[External dyloading] The result (8 + 9) is 17!"),
+        "{stdout}"
+    );
+}
+
+#[test]
+fn old_build_falls_back_to_add() {
+    let stdout = run_dlopen_backend(&old_dir());
+    assert!(
+        stdout.contains("dyloading_add_v2 is missing, falling back to dyloading_add"),
+        "{stdout}"
+    );
+    assert!(
+        stdout.contains("[External dyloading] The result (8 + 9) is 17!"),
+        "{stdout}"
+    );
+}
@@ -0,0 +1,124 @@
+// int 以外的标量的映射：C 编译器给出的大小和对齐与 Rust 相同，经过 C 声明传递的值和直接调用 Rust 得到的
+// 结果逐位相同，包括 NaN、负零、次正规数和整数的边界值
+// The mapping of scalars other than int: the C compiler gives them the same size and alignment as
+// Rust, and values passed through the C declarations give results bit for bit identical to
+// calling Rust directly, NaN, negative zero, subnormals and integer limits included
+//
+// 两个 C 文件都引用 Rust 库中的符号，静态构建（--features static）中不运行
+// Both C files refer to symbols from the Rust libraries, so this does not run in the static build
+// (--features static)
+
+#![cfg(not(feature = "static"))]
+
+use std::ffi::c_int;
+use std::mem::{align_of, size_of};
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct ScalarLayout {
+    size: usize,
+    align: usize,
+}
+
+const fn layout<T>() -> ScalarLayout {
+    ScalarLayout {
+        size: size_of::<T>(),
+        align: align_of::<T>(),
+    }
+}
+
+// bool、size_t、int64_t、uint64_t、float、double
+// bool, size_t, int64_t, uint64_t, float, double
+const RUST_LAYOUTS: [ScalarLayout; 6] = [
+    layout::<bool>(),
+    layout::<usize>(),
+    layout::<i64>(),
+    layout::<u64>(),
+    layout::<f32>(),
+    layout::<f64>(),
+];
+
+extern "C" {
+    fn rxc_staticlib_mul_f64(a: f64, b: f64) -> f64;
+    fn rxc_staticlib_mul_f32(a: f32, b: f32) -> f32;
+    fn rxc_staticlib_add_i64(a: i64, b: i64) -> i64;
+    fn rxc_staticlib_mul_high_u64(a: u64, b: u64) -> u64;
+    fn rxc_cdylib_is_even(value: i64) -> bool;
+    fn rxc_cdylib_not(value: bool) -> bool;
+    fn rxc_cdylib_align_up(size: usize, align: usize) -> usize;
+    fn rxc_cdylib_lerp(a: f64, b: f64, t: f64) -> f64;
+
+    fn c_scalar_layouts(out: *mut ScalarLayout, len: usize) -> usize;
+    fn c_mul_f64(a: f64, b: f64) -> f64;
+    fn c_mul_f32(a: f32, b: f32) -> f32;
+    fn c_add_i64(a: i64, b: i64) -> i64;
+    fn c_mul_high_u64(a: u64, b: u64) -> u64;
+    fn c_is_even(value: i64) -> bool;
+    fn c_not(value: bool) -> c_int;
+    fn c_align_up(size: usize, align: usize) -> usize;
+    fn c_lerp(a: f64, b: f64, t: f64) -> f64;
+}
+
+#[test]
+fn c_and_rust_agree_on_layouts() {
+    let mut layouts = [ScalarLayout { size: 0, align: 0 }; 6];
+    let count = unsafe { c_scalar_layouts(layouts.as_mut_ptr(), layouts.len()) };
+    assert_eq!(count, RUST_LAYOUTS.len());
+    assert_eq!(layouts, RUST_LAYOUTS);
+}
+
+#[test]
+fn floats_pass_bit_for_bit() {
+    let cases = [
+        (1.5, -2.0),
+        (-0.0, 3.0),
+        (f64::NAN, 1.0),
+        (f64::INFINITY, 0.0),
+        (f64::MIN_POSITIVE, 0.5),
+    ];
+    for (a, b) in cases {
+        let direct = unsafe { rxc_staticlib_mul_f64(a, b) };
+        let through_c = unsafe { c_mul_f64(a, b) };
+        assert_eq!(direct.to_bits(), through_c.to_bits(), "{} * {}", a, b);
+        assert_eq!(direct.to_bits(), (a * b).to_bits());
+    }
+    for (a, b) in [(1.5f32, 2.0), (f32::MIN_POSITIVE, 0.5), (-0.0, 1.0)] {
+        let direct = unsafe { rxc_staticlib_mul_f32(a, b) };
+        let through_c = unsafe { c_mul_f32(a, b) };
+        assert_eq!(direct.to_bits(), through_c.to_bits(), "{} * {}", a, b);
+        assert_eq!(direct.to_bits(), (a * b).to_bits());
+    }
+    assert_eq!(unsafe { c_lerp(2.0, 4.0, 0.25) }, 2.5);
+    assert_eq!(unsafe { rxc_cdylib_lerp(2.0, 4.0, 0.25) }, 2.5);
+}
+
+#[test]
+fn wide_integers_keep_every_bit() {
+    assert_eq!(unsafe { c_add_i64(i64::MAX, 1) }, i64::MIN);
+    assert_eq!(unsafe { c_add_i64(-1, 1 << 40) }, (1 << 40) - 1);
+    assert_eq!(unsafe { rxc_staticlib_add_i64(i64::MIN, -1) }, i64::MAX);
+    assert_eq!(unsafe { c_mul_high_u64(u64::MAX, u64::MAX) }, u64::MAX - 1);
+    assert_eq!(unsafe { rxc_staticlib_mul_high_u64(1 << 63, 4) }, 2);
+}
+
+#[test]
+fn bools_are_zero_or_one() {
+    assert!(unsafe { c_is_even(i64::MIN) });
+    assert!(!unsafe { c_is_even(-3) });
+    assert!(unsafe { rxc_cdylib_is_even(0) });
+    assert_eq!(unsafe { c_not(true) }, 0);
+    assert_eq!(unsafe { c_not(false) }, 1);
+    assert!(unsafe { rxc_cdylib_not(false) });
+}
+
+#[test]
+fn sizes_span_the_whole_size_t() {
+    assert_eq!(unsafe { c_align_up(13, 8) }, 16);
+    assert_eq!(unsafe { c_align_up(16, 16) }, 16);
+    assert_eq!(unsafe { c_align_up(1, 3) }, 0);
+    assert_eq!(unsafe { c_align_up(usize::MAX, 2) }, 0);
+    assert_eq!(
+        unsafe { rxc_cdylib_align_up(usize::MAX - 7, 8) },
+        usize::MAX - 7
+    );
+}
@@ -0,0 +1,28 @@
+// tests/scalars.rs 用到的 C 调用方，按 cdylib_gen.h 中的声明传递 bool、size_t、int64_t 和 double；
+// 两个头文件都定义了 FfiStatus 等类型，所以和 static_scalars.c 分成两个文件
+// The C caller tests/scalars.rs uses, passing bool, size_t, int64_t and double through the
+// declarations in cdylib_gen.h; both headers define FfiStatus and other types, hence a file apart
+// from static_scalars.c
+#include "cdylib_gen.h"
+
+bool c_is_even(int64_t value)
+{
+    return rxc_cdylib_is_even(value);
+}
+
+// 把返回的 bool 作为 int 交给 Rust，检查它在 C 这边确实是 0 或 1
+// Hands the returned bool to Rust as an int, checking it really is 0 or 1 on the C side
+int c_not(bool value)
+{
+    return (int)rxc_cdylib_not(value);
+}
+
+size_t c_align_up(size_t size, size_t align)
+{
+    return rxc_cdylib_align_up(size, align);
+}
+
+double c_lerp(double a, double b, double t)
+{
+    return rxc_cdylib_lerp(a, b, t);
+}
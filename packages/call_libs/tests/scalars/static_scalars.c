@@ -0,0 +1,61 @@
+// tests/scalars.rs 用到的 C 调用方，按 staticlib_gen.h 中的声明传递 int 以外的标量，并报告 C 编译器眼中
+// 这些类型的大小和对齐
+// The C caller tests/scalars.rs uses: it passes scalars other than int through the declarations in
+// staticlib_gen.h, and reports the size and alignment the C compiler gives those types
+#include <stddef.h>
+#include "staticlib_gen.h"
+
+struct scalar_layout
+{
+    size_t size;
+    size_t align;
+};
+
+// 类型作为结构体成员时的偏移就是 ABI 规定的对齐，_Alignof 在某些 32 位平台上给出的是更大的首选对齐
+// The offset of a type as a struct member is the alignment the ABI requires, _Alignof gives a
+// larger preferred alignment on some 32-bit platforms
+#define LAYOUT(type)                                                        \
+    (struct scalar_layout)                                                  \
+    {                                                                       \
+        sizeof(type), offsetof(struct { char c; type value; }, value)     \
+    }
+
+// 顺序和 tests/scalars.rs 中的 RUST_LAYOUTS 相同
+// In the same order as RUST_LAYOUTS in tests/scalars.rs
+size_t c_scalar_layouts(struct scalar_layout *out, size_t len)
+{
+    const struct scalar_layout layouts[] = {
+        LAYOUT(bool),
+        LAYOUT(size_t),
+        LAYOUT(int64_t),
+        LAYOUT(uint64_t),
+        LAYOUT(float),
+        LAYOUT(double),
+    };
+    size_t count = sizeof(layouts) / sizeof(layouts[0]);
+    for (size_t i = 0; i < count && i < len; i++)
+    {
+        out[i] = layouts[i];
+    }
+    return count;
+}
+
+double c_mul_f64(double a, double b)
+{
+    return rxc_staticlib_mul_f64(a, b);
+}
+
+float c_mul_f32(float a, float b)
+{
+    return rxc_staticlib_mul_f32(a, b);
+}
+
+int64_t c_add_i64(int64_t a, int64_t b)
+{
+    return rxc_staticlib_add_i64(a, b);
+}
+
+uint64_t c_mul_high_u64(uint64_t a, uint64_t b)
+{
+    return rxc_staticlib_mul_high_u64(a, b);
+}
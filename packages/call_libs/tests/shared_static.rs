@@ -0,0 +1,74 @@
+// C 和 Rust 共享的静态变量的测试：Rust 线程用原子操作、C 线程用 __atomic 内建函数同时更新同一个计数器，
+// 一次增加都不会丢失，两边读到的值相同
+// Tests for the statics shared between C and Rust: Rust threads with atomics and C threads with
+// the __atomic builtins update the same counter at the same time, no increment gets lost and both
+// sides read the same value
+
+#![cfg(not(feature = "static"))]
+
+use std::ptr;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::thread;
+
+#[path = "../src/clib.rs"]
+mod clib;
+
+use clib::{
+    clib_counter, clib_counter_add, clib_staticlib_counter_add, clib_staticlib_counter_get,
+};
+
+extern "C" {
+    static rxc_staticlib_counter: AtomicU32;
+    fn rxc_staticlib_counter_add(n: u32) -> u32;
+}
+
+const THREADS: u32 = 4;
+const ROUNDS: u32 = 10_000;
+
+fn hammer(rust_add: impl Fn() + Sync, c_add: impl Fn() + Sync) {
+    thread::scope(|scope| {
+        for _ in 0..THREADS {
+            scope.spawn(|| (0..ROUNDS).for_each(|_| rust_add()));
+            scope.spawn(|| (0..ROUNDS).for_each(|_| c_add()));
+        }
+    });
+}
+
+#[test]
+fn rust_static_is_updated_from_both_sides() {
+    let counter = unsafe { &rxc_staticlib_counter };
+    let before = counter.load(Ordering::SeqCst);
+    hammer(
+        || {
+            counter.fetch_add(1, Ordering::SeqCst);
+        },
+        || {
+            unsafe { clib_staticlib_counter_add(1) };
+        },
+    );
+    let expected = before + 2 * THREADS * ROUNDS;
+    assert_eq!(counter.load(Ordering::SeqCst), expected);
+    assert_eq!(unsafe { clib_staticlib_counter_get() }, expected);
+    // staticlib_gen 自己的导出函数作用于同一个变量
+    // staticlib_gen's own export works on the same variable
+    assert_eq!(unsafe { rxc_staticlib_counter_add(5) }, expected + 5);
+}
+
+#[test]
+fn c_static_is_updated_from_both_sides() {
+    // clib_counter 在绑定中是 static mut，只通过原子操作访问
+    // clib_counter is a static mut in the bindings and only accessed atomically
+    let counter = unsafe { AtomicU32::from_ptr(ptr::addr_of_mut!(clib_counter)) };
+    let before = counter.load(Ordering::SeqCst);
+    hammer(
+        || {
+            counter.fetch_add(1, Ordering::SeqCst);
+        },
+        || {
+            unsafe { clib_counter_add(1) };
+        },
+    );
+    let expected = before + 2 * THREADS * ROUNDS;
+    assert_eq!(counter.load(Ordering::SeqCst), expected);
+    assert_eq!(unsafe { clib_counter_add(0) }, expected);
+}
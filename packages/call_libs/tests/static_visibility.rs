@@ -0,0 +1,80 @@
+// 把暂存的 libstaticlib_gen.a 链接进共享库，检查 staticlib_gen.map 只让允许的 C API 出现在动态符号表中
+// Links the staged libstaticlib_gen.a into a shared library and checks that staticlib_gen.map only
+// lets the allowed C API into the dynamic symbol table
+#![cfg(target_os = "linux")]
+
+use std::collections::BTreeSet;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use object::Object;
+
+const ARCHIVE: &str = concat!(env!("OUT_DIR"), "/libstaticlib_gen.a");
+const VERSION_SCRIPT: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/../staticlib_gen/staticlib_gen.map"
+);
+
+// 版本脚本 global: 和 local: 之间的名字
+// The names between global: and local: in the version script
+fn allowed() -> BTreeSet<String> {
+    let script = fs::read_to_string(VERSION_SCRIPT).unwrap();
+    let global = script.split("global:").nth(1).unwrap();
+    let global = global.split("local:").next().unwrap();
+    global
+        .split(';')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(str::to_owned)
+        .collect()
+}
+
+fn link_shared(name: &str, version_script: Option<&str>) -> PathBuf {
+    let out = Path::new(env!("CARGO_TARGET_TMPDIR")).join(name);
+    let mut cc = Command::new(env::var("CC").unwrap_or_else(|_| "cc".to_owned()));
+    cc.arg("-shared").arg("-o").arg(&out);
+    if let Some(script) = version_script {
+        cc.arg(format!("-Wl,--version-script={}", script));
+    }
+    cc.args(["-Wl,--whole-archive", ARCHIVE, "-Wl,--no-whole-archive"])
+        .args([
+            "-lgcc_s",
+            "-lutil",
+            "-lrt",
+            "-lpthread",
+            "-lm",
+            "-ldl",
+            "-lc",
+        ]);
+    let status = cc.status().expect("failed to run the C compiler");
+    assert!(status.success(), "linking {} failed", name);
+    out
+}
+
+fn exported(path: &Path) -> BTreeSet<String> {
+    let data = fs::read(path).unwrap();
+    let file = object::File::parse(&*data).unwrap();
+    file.exports()
+        .unwrap()
+        .iter()
+        .map(|export| String::from_utf8(export.name().to_vec()).unwrap())
+        .collect()
+}
+
+#[test]
+fn version_script_exports_only_the_c_api() {
+    let exported = exported(&link_shared("libvisible.so", Some(VERSION_SCRIPT)));
+    assert_eq!(exported, allowed());
+}
+
+#[test]
+fn without_version_script_rust_internals_leak() {
+    let exported = exported(&link_shared("libleaky.so", None));
+    assert!(exported.is_superset(&allowed()));
+    assert!(
+        exported.iter().any(|name| name.starts_with("_ZN")),
+        "expected mangled Rust symbols to leak without the version script"
+    );
+}
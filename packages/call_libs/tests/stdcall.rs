@@ -0,0 +1,56 @@
+// 32 位 Windows 上 stdcall 调用约定的测试：Rust 和 C 都能调用 cdylib_gen 的 stdcall 导出函数并传入
+// stdcall 回调；把 stdcall 函数当作 cdecl 调用时，每次调用都让栈指针偏移参数的字节数
+// Tests for the stdcall calling convention on 32-bit Windows: both Rust and C can call
+// cdylib_gen's stdcall exports and pass stdcall callbacks to them; calling a stdcall function as
+// a cdecl one moves the stack pointer by the size of the arguments on every call
+
+#![cfg(all(windows, target_arch = "x86", not(feature = "static")))]
+
+use std::ffi::c_int;
+
+type TransformStdcall = Option<extern "stdcall" fn(value: c_int) -> c_int>;
+
+// 声明的约定决定 Rust 链接的符号：stdcall 函数是 _name@N，N 为参数的字节数
+// The declared convention decides the symbol Rust links to: a stdcall function is _name@N, with N
+// the size of the arguments in bytes
+extern "stdcall" {
+    fn rxc_cdylib_add_stdcall(a: c_int, b: c_int) -> c_int;
+    fn rxc_cdylib_apply_stdcall(value: c_int, transform: TransformStdcall) -> c_int;
+}
+
+extern "C" {
+    fn c_stdcall_add(a: c_int, b: c_int) -> c_int;
+    fn c_stdcall_apply(value: c_int) -> c_int;
+    fn c_esp_drift_matched() -> c_int;
+    fn c_esp_drift_mismatched() -> c_int;
+}
+
+extern "stdcall" fn negate(value: c_int) -> c_int {
+    -value
+}
+
+#[test]
+fn rust_calls_stdcall_exports() {
+    assert_eq!(unsafe { rxc_cdylib_add_stdcall(2, 3) }, 5);
+    assert_eq!(unsafe { rxc_cdylib_add_stdcall(c_int::MAX, 1) }, c_int::MIN);
+    assert_eq!(unsafe { rxc_cdylib_apply_stdcall(7, Some(negate)) }, -7);
+    assert_eq!(unsafe { rxc_cdylib_apply_stdcall(7, None) }, 7);
+}
+
+#[test]
+fn c_calls_stdcall_exports() {
+    assert_eq!(unsafe { c_stdcall_add(40, 2) }, 42);
+    assert_eq!(unsafe { c_stdcall_apply(14) }, 42);
+}
+
+// 被调用者用 ret 8 弹出了两个 int 参数，以为自己要清理栈的调用方又弹了一次
+// The callee popped the two int arguments with ret 8, and the caller, thinking the cleanup is its
+// job, popped them again
+#[test]
+fn mismatched_convention_unbalances_the_stack() {
+    assert_eq!(unsafe { c_esp_drift_matched() }, 0);
+    assert_eq!(
+        unsafe { c_esp_drift_mismatched() },
+        2 * std::mem::size_of::<c_int>() as c_int
+    );
+}
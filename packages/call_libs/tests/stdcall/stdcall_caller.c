@@ -0,0 +1,84 @@
+// tests/stdcall.rs 用到的 C 调用方，只在 32 位 Windows 上编译：c_stdcall_* 按头文件中的 __stdcall
+// 声明调用 cdylib_gen，c_esp_drift_* 则测量一次调用前后栈指针的偏移，展示约定不一致时的后果
+// The C caller tests/stdcall.rs uses, only compiled on 32-bit Windows: c_stdcall_* call cdylib_gen
+// through the __stdcall declarations of the header, while c_esp_drift_* measure how far the stack
+// pointer moves across a call, showing what happens when the conventions disagree
+#include "cdylib_gen.h"
+
+static int __stdcall triple(int value)
+{
+    return value * 3;
+}
+
+int c_stdcall_add(int a, int b)
+{
+    return rxc_cdylib_add_stdcall(a, b);
+}
+
+int c_stdcall_apply(int value)
+{
+    return rxc_cdylib_apply_stdcall(value, triple);
+}
+
+typedef int(__cdecl *cdecl_add)(int a, int b);
+
+static int __cdecl add_cdecl(int a, int b)
+{
+    return a + b;
+}
+
+// 以 cdecl 约定调用 add(1, 2)：压入两个参数，调用，再由调用方弹出它们，返回调用前后 ESP 之差。
+// cdecl 的被调用者不碰参数，差为 0；stdcall 的被调用者已经用 ret 8 弹出了参数，调用方再弹一次，差为 8。
+// 用汇编写成，编译器不会插入或推迟栈调整，最后从 EBX 恢复 ESP，偏移不会传给调用者
+// Calls add(1, 2) with the cdecl convention: pushes the two arguments, calls, lets the caller pop
+// them and returns how far ESP moved across the call. A cdecl callee leaves the arguments alone,
+// giving 0; a stdcall callee already popped them with ret 8 and the caller pops them again,
+// giving 8. Written in assembly so the compiler can't insert or defer stack adjustments; ESP is
+// restored from EBX at the end, so the drift doesn't reach the caller
+#if defined(_MSC_VER)
+static __declspec(naked) int __cdecl esp_drift(cdecl_add add)
+{
+    __asm {
+        push ebx
+        mov ebx, esp
+        push 2
+        push 1
+        call dword ptr [ebx + 8]
+        add esp, 8
+        mov eax, esp
+        sub eax, ebx
+        mov esp, ebx
+        pop ebx
+        ret
+    }
+}
+#else
+// add 只在汇编中通过 8(%ebx) 使用，GCC 看不到
+// add is only used in the assembly, through 8(%ebx), which GCC can't see
+__attribute__((naked)) static int __cdecl esp_drift(__attribute__((unused)) cdecl_add add)
+{
+    __asm__("push %ebx\n\t"
+            "mov %esp, %ebx\n\t"
+            "push $2\n\t"
+            "push $1\n\t"
+            "call *8(%ebx)\n\t"
+            "add $8, %esp\n\t"
+            "mov %esp, %eax\n\t"
+            "sub %ebx, %eax\n\t"
+            "mov %ebx, %esp\n\t"
+            "pop %ebx\n\t"
+            "ret");
+}
+#endif
+
+int c_esp_drift_matched(void)
+{
+    return esp_drift(add_cdecl);
+}
+
+// 常见的错误：转换函数指针的类型，把 stdcall 函数当作 cdecl 函数调用
+// The usual mistake: casting the function pointer and calling a stdcall function as a cdecl one
+int c_esp_drift_mismatched(void)
+{
+    return esp_drift((cdecl_add)rxc_cdylib_add_stdcall);
+}
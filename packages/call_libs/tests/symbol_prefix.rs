@@ -0,0 +1,84 @@
+// 导出符号前缀的演示：自带 staticlib_add 的 C 程序可以和 staticlib_gen 链接在一起，因为库导出的是
+// rxc_staticlib_add；名字真的相同时，链接整个静态库的链接器把它报告为重复定义。只链接需要的成员时
+// 结果更糟：同名的成员可能根本不被取出，程序静默地用上自己的定义
+// A demo of the export prefix: a C program with a staticlib_add of its own links together with
+// staticlib_gen, because the library exports rxc_staticlib_add; when a name really is the same,
+// the linker taking the whole archive reports a duplicate definition. Linking only the members
+// needed is worse: the member with that name may never be pulled out, and the program silently
+// uses its own definition
+#![cfg(target_os = "linux")]
+
+use std::env;
+use std::path::Path;
+use std::process::{Command, Output};
+
+const ARCHIVE: &str = concat!(env!("OUT_DIR"), "/libstaticlib_gen.a");
+const SOURCE: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/tests/symbol_prefix/generic_names.c"
+);
+const INCLUDE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../../include");
+
+fn link(name: &str, defines: &[&str]) -> (Output, String) {
+    let out = Path::new(env!("CARGO_TARGET_TMPDIR")).join(name);
+    let output = Command::new(env::var("CC").unwrap_or_else(|_| "cc".to_owned()))
+        .arg(SOURCE)
+        .arg(format!("-I{}", INCLUDE))
+        .args(defines)
+        .arg("-o")
+        .arg(&out)
+        .args(["-Wl,--whole-archive", ARCHIVE, "-Wl,--no-whole-archive"])
+        .args([
+            "-lgcc_s",
+            "-lutil",
+            "-lrt",
+            "-lpthread",
+            "-lm",
+            "-ldl",
+            "-lc",
+        ])
+        .output()
+        .expect("failed to run the C compiler");
+    let stderr = String::from_utf8_lossy(&output.stderr).into_owned();
+    (output, stderr)
+}
+
+#[test]
+fn generic_names_live_alongside_the_prefixed_exports() {
+    let (output, stderr) = link("generic_names", &[]);
+    assert!(output.status.success(), "linking failed:\n{}", stderr);
+    let run = Command::new(Path::new(env!("CARGO_TARGET_TMPDIR")).join("generic_names"))
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&run.stdout);
+    assert!(
+        run.status.success(),
+        "exited with {}:\n{}",
+        run.status,
+        stdout
+    );
+    assert!(
+        stdout.contains("[C] rxc_staticlib_add returned status 0, sum 3"),
+        "{}",
+        stdout
+    );
+    assert!(
+        stdout.contains("[C] The program's own staticlib_add(1, 2) = 3, called 1 time(s)"),
+        "{}",
+        stdout
+    );
+}
+
+#[test]
+fn a_shared_name_is_a_duplicate_definition() {
+    let (output, stderr) = link("generic_names_clash", &["-DCLASH"]);
+    assert!(!output.status.success(), "linking succeeded");
+    // GNU ld 说 multiple definition，lld 说 duplicate symbol
+    // GNU ld says multiple definition, lld says duplicate symbol
+    assert!(
+        stderr.contains("rxc_staticlib_init")
+            && (stderr.contains("multiple definition") || stderr.contains("duplicate symbol")),
+        "{}",
+        stderr
+    );
+}
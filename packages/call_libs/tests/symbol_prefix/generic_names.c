@@ -0,0 +1,41 @@
+// 一个自带通用名字的 C 程序：它自己的 staticlib_add 和 staticlib_gen 无关，和库导出的 rxc_staticlib_add
+// 可以共存；定义 CLASH 时它还定义了和库的导出符号同名的 rxc_staticlib_init，tests/symbol_prefix.rs
+// 检查链接器把它报告为重复定义
+// A C program with generic names of its own: its staticlib_add has nothing to do with
+// staticlib_gen and lives alongside the library's rxc_staticlib_add; with CLASH defined it also
+// defines rxc_staticlib_init, named like one of the library's exports, and tests/symbol_prefix.rs
+// checks that the linker reports it as a duplicate definition
+#include <stdio.h>
+#include "staticlib_gen.h"
+
+static int calls = 0;
+
+int staticlib_add(int a, int b)
+{
+    calls++;
+    return a + b;
+}
+
+#ifdef CLASH
+enum FfiStatus rxc_staticlib_init(const struct InitConfig *config)
+{
+    (void)config;
+    return FFI_STATUS_OK;
+}
+#endif
+
+int main(void)
+{
+    if (RUSTLIB_API(staticlib_init)(NULL) != FFI_STATUS_OK)
+    {
+        return 1;
+    }
+    char result[64] = "generic names";
+    int sum = 0;
+    enum FfiStatus status = RUSTLIB_API(staticlib_add)(1, 2, result, sizeof(result), &sum, NULL);
+    printf("[C] rxc_staticlib_add returned status %d, sum %d\n", (int)status, sum);
+    int own = staticlib_add(1, 2);
+    printf("[C] The program's own staticlib_add(1, 2) = %d, called %d time(s)\n", own, calls);
+    RUSTLIB_API(staticlib_shutdown)();
+    return 0;
+}
@@ -0,0 +1,258 @@
+// 展开语义的测试：Rust 回调中的 panic 经过 cdylib_gen 的 _unwind 导出函数和用 -fexceptions 编译的
+// C 栈帧展开回 Rust，C 的清理代码在途中运行；同样的回调交给普通的 extern "C" 版本时进程中止
+// Tests for unwinding semantics: a panic in a Rust callback unwinds back to Rust through
+// cdylib_gen's _unwind exports and C frames compiled with -fexceptions, running the C cleanups on
+// the way; given to the plain extern "C" versions the same callback aborts the process
+//
+// 中止的情况在子进程中运行：测试以 UNWIND_ABORT_CASE 重新启动自己，只运行 abort_case
+// The aborting cases run in a child process: the test starts itself again with
+// UNWIND_ABORT_CASE set, running only abort_case
+//
+// 需要动态库，静态构建（--features static）中不运行
+// Needs the dynamic library, so it does not run in the static build (--features static)
+
+#![cfg(not(feature = "static"))]
+
+use std::env;
+use std::ffi::{c_int, c_long, c_void};
+use std::panic::{self, AssertUnwindSafe};
+use std::process::{Command, Output};
+use std::ptr;
+
+use interop_common::InitConfig;
+
+mod common;
+use common::init;
+
+const STATUS_OK: c_int = 0;
+const ABORT_CASE: &str = "UNWIND_ABORT_CASE";
+
+/// What a C frame from tests/unwind/c_frames.c saw, laid out like `struct c_frame`.
+#[repr(C)]
+#[derive(Debug, Default, PartialEq, Eq)]
+struct CFrame {
+    returned: c_int,
+    cleaned_up: c_int,
+}
+
+type Transform = Option<extern "C" fn(value: c_int) -> c_int>;
+type TransformUnwind = Option<unsafe extern "C-unwind" fn(value: c_int) -> c_int>;
+type ProgressCallback = Option<extern "C" fn(percent: u8, user_data: *mut c_void) -> c_int>;
+type ProgressCallbackUnwind =
+    Option<unsafe extern "C-unwind" fn(percent: u8, user_data: *mut c_void) -> c_int>;
+
+// 会展开的 C 函数必须用 "C-unwind" 声明，否则 panic 到达这里时进程中止
+// C functions that may unwind must be declared "C-unwind", otherwise the process aborts when a
+// panic reaches them
+extern "C-unwind" {
+    fn c_frames_apply_unwind(value: c_int, transform: TransformUnwind, frame: *mut CFrame)
+        -> c_int;
+    fn c_frames_sum_with_progress_unwind(
+        values: *const c_int,
+        len: usize,
+        cb: ProgressCallbackUnwind,
+        user_data: *mut c_void,
+        total: *mut c_long,
+        frame: *mut CFrame,
+    ) -> c_int;
+}
+
+extern "C" {
+    fn c_frames_apply(value: c_int, transform: Transform, frame: *mut CFrame) -> c_int;
+    fn c_frames_sum_with_progress(
+        values: *const c_int,
+        len: usize,
+        cb: ProgressCallback,
+        user_data: *mut c_void,
+        total: *mut c_long,
+        frame: *mut CFrame,
+    ) -> c_int;
+}
+
+fn double_or_panic(value: c_int) -> c_int {
+    value
+        .checked_mul(2)
+        .unwrap_or_else(|| panic!("doubling {value} overflows"))
+}
+
+extern "C-unwind" fn double_unwind(value: c_int) -> c_int {
+    double_or_panic(value)
+}
+
+extern "C" fn double(value: c_int) -> c_int {
+    double_or_panic(value)
+}
+
+// user_data 指向回调看到的百分比，到 50% 时 panic
+// user_data points to the percentages the callback saw, it panics at 50%
+fn record_until_halfway(percent: u8, user_data: *mut c_void) -> c_int {
+    let seen = unsafe { &mut *(user_data as *mut Vec<u8>) };
+    seen.push(percent);
+    if percent == 50 {
+        panic!("stopped at {percent}%");
+    }
+    0
+}
+
+extern "C-unwind" fn halfway_unwind(percent: u8, user_data: *mut c_void) -> c_int {
+    record_until_halfway(percent, user_data)
+}
+
+extern "C" fn halfway(percent: u8, user_data: *mut c_void) -> c_int {
+    record_until_halfway(percent, user_data)
+}
+
+fn panic_message(payload: Box<dyn std::any::Any + Send>) -> String {
+    *payload
+        .downcast::<String>()
+        .expect("The panic payload is a String.")
+}
+
+#[test]
+fn panic_unwinds_through_c_frames() {
+    let mut frame = CFrame::default();
+    let doubled = unsafe { c_frames_apply_unwind(21, Some(double_unwind), &mut frame) };
+    assert_eq!(doubled, 42);
+    assert_eq!(
+        frame,
+        CFrame {
+            returned: 1,
+            cleaned_up: 1
+        }
+    );
+
+    // 展开跳过了 C 在调用之后的代码，但运行了它的清理代码
+    // Unwinding skipped the C code after the call but ran its cleanup
+    let mut frame = CFrame::default();
+    let payload = panic::catch_unwind(AssertUnwindSafe(|| unsafe {
+        c_frames_apply_unwind(c_int::MAX, Some(double_unwind), &mut frame)
+    }))
+    .unwrap_err();
+    assert_eq!(panic_message(payload), "doubling 2147483647 overflows");
+    assert_eq!(
+        frame,
+        CFrame {
+            returned: 0,
+            cleaned_up: 1
+        }
+    );
+}
+
+#[test]
+fn panic_unwinds_out_of_a_progress_callback() {
+    init(&InitConfig::default());
+    let values = vec![1; 100];
+    let mut seen = Vec::<u8>::new();
+    let mut total = -1;
+    let mut frame = CFrame::default();
+    let payload = panic::catch_unwind(AssertUnwindSafe(|| unsafe {
+        c_frames_sum_with_progress_unwind(
+            values.as_ptr(),
+            values.len(),
+            Some(halfway_unwind),
+            &mut seen as *mut _ as *mut c_void,
+            &mut total,
+            &mut frame,
+        )
+    }))
+    .unwrap_err();
+    assert_eq!(panic_message(payload), "stopped at 50%");
+    assert_eq!(seen, (0..=50).collect::<Vec<_>>());
+    assert_eq!(total, -1);
+    assert_eq!(
+        frame,
+        CFrame {
+            returned: 0,
+            cleaned_up: 1
+        }
+    );
+
+    // 展开之后库照常可用
+    // The library works as usual after the unwind
+    let status = unsafe {
+        c_frames_sum_with_progress_unwind(
+            values.as_ptr(),
+            values.len(),
+            None,
+            ptr::null_mut(),
+            &mut total,
+            &mut frame,
+        )
+    };
+    assert_eq!((status, total), (STATUS_OK, 100));
+}
+
+// 只在子进程中做事：让 panic 到达 extern "C" 回调的边界
+// Only does something in the child process: lets a panic reach the boundary of an extern "C"
+// callback
+#[test]
+fn abort_case() {
+    let Ok(case) = env::var(ABORT_CASE) else {
+        return;
+    };
+    init(&InitConfig::default());
+    let mut frame = CFrame::default();
+    match case.as_str() {
+        "apply" => unsafe {
+            c_frames_apply(c_int::MAX, Some(double), &mut frame);
+        },
+        "progress" => unsafe {
+            let mut seen = Vec::<u8>::new();
+            c_frames_sum_with_progress(
+                [1; 100].as_ptr(),
+                100,
+                Some(halfway),
+                &mut seen as *mut _ as *mut c_void,
+                &mut 0,
+                &mut frame,
+            );
+        },
+        _ => unreachable!("Unknown case {case}."),
+    }
+    println!("[Rust] The extern \"C\" call returned with {frame:?}");
+}
+
+fn run_abort_case(case: &str) -> Output {
+    let output = Command::new(env::current_exe().unwrap())
+        .args(["abort_case", "--exact", "--nocapture", "--test-threads=1"])
+        .env(ABORT_CASE, case)
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        !output.status.success(),
+        "{case} exited with {}:\n{stdout}\n{stderr}",
+        output.status
+    );
+    // Unix 上 abort 以 SIGABRT 结束进程
+    // On Unix abort ends the process with SIGABRT
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+        assert_eq!(output.status.signal(), Some(6), "{stderr}");
+    }
+    assert!(!stdout.contains("returned with"), "{stdout}");
+    assert!(
+        stderr.contains("panic in a function that cannot unwind"),
+        "{stderr}"
+    );
+    output
+}
+
+#[test]
+fn panic_in_an_extern_c_callback_aborts() {
+    let output = run_abort_case("apply");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("doubling 2147483647 overflows"), "{stderr}");
+}
+
+// ffi_guard 的 catch_unwind 也拦不住：进程在 panic 离开回调时就已经中止
+// ffi_guard's catch_unwind can't stop it either: the process aborts as the panic leaves the
+// callback
+#[test]
+fn panic_in_an_extern_c_progress_callback_aborts() {
+    let output = run_abort_case("progress");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("stopped at 50%"), "{stderr}");
+}
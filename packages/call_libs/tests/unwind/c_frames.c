@@ -0,0 +1,75 @@
+// tests/unwind.rs 用来在 Rust 和库之间插入 C 栈帧的函数，用 -fexceptions 编译，展开可以穿过它们；
+// frame 记录调用之后的代码是否运行，以及清理代码（GCC/Clang 的 cleanup 属性，MSVC 的 __finally）
+// 是否在展开时运行
+// Functions tests/unwind.rs uses to put C frames between Rust and the library, compiled with
+// -fexceptions so unwinding can pass through them; frame records whether the code after the call
+// ran, and whether the cleanup (the cleanup attribute of GCC/Clang, __finally on MSVC) ran while
+// unwinding
+#include "cdylib_gen.h"
+
+struct c_frame
+{
+    int returned;
+    int cleaned_up;
+};
+
+#if !defined(_MSC_VER)
+static void clean_up(struct c_frame **frame)
+{
+    (*frame)->cleaned_up += 1;
+}
+#endif
+
+// 调用之后还有代码要运行，编译器不会把调用优化成尾调用而去掉这一帧
+// Code still runs after the call, so the compiler can't turn it into a tail call and drop the frame
+#if defined(_MSC_VER)
+#define THROUGH_C_FRAME(frame, result, call) \
+    __try                                    \
+    {                                        \
+        result = call;                       \
+        (frame)->returned += 1;              \
+    }                                        \
+    __finally                                \
+    {                                        \
+        (frame)->cleaned_up += 1;            \
+    }
+#else
+#define THROUGH_C_FRAME(frame, result, call)                              \
+    {                                                                     \
+        __attribute__((cleanup(clean_up))) struct c_frame *guard = frame; \
+        result = call;                                                    \
+        guard->returned += 1;                                             \
+    }
+#endif
+
+int c_frames_apply(int value, Transform transform, struct c_frame *frame)
+{
+    int result;
+    THROUGH_C_FRAME(frame, result, rxc_cdylib_apply(value, transform));
+    return result;
+}
+
+int c_frames_apply_unwind(int value, TransformUnwind transform, struct c_frame *frame)
+{
+    int result;
+    THROUGH_C_FRAME(frame, result, rxc_cdylib_apply_unwind(value, transform));
+    return result;
+}
+
+enum FfiStatus c_frames_sum_with_progress(const int *values, size_t len, ProgressCallback cb,
+                                          void *user_data, long *total, struct c_frame *frame)
+{
+    enum FfiStatus status;
+    THROUGH_C_FRAME(frame, status, rxc_cdylib_sum_with_progress(values, len, cb, user_data, total));
+    return status;
+}
+
+enum FfiStatus c_frames_sum_with_progress_unwind(const int *values, size_t len,
+                                                 ProgressCallbackUnwind cb, void *user_data,
+                                                 long *total, struct c_frame *frame)
+{
+    enum FfiStatus status;
+    THROUGH_C_FRAME(frame, status,
+                    rxc_cdylib_sum_with_progress_unwind(values, len, cb, user_data, total));
+    return status;
+}
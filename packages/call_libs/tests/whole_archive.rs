@@ -0,0 +1,92 @@
+// staticlib_gen 操作表的测试：只被表引用的条目只有在链接整个静态库时才会留下。C 一侧运行 build.rs 用
+// 普通方式和 --whole-archive 链接出的 ops_consumer；Rust 一侧检查用 +whole-archive 链接的
+// tests/whole_archive/cube_op.c
+// Tests for the staticlib_gen operation table: entries referenced by nothing but the table only
+// survive when the whole archive is linked. On the C side it runs the ops_consumer build.rs links
+// the usual way and with --whole-archive; on the Rust side it checks
+// tests/whole_archive/cube_op.c, linked with +whole-archive
+//
+// Rust 的 staticlib 不能用 +whole-archive 链接进 Rust 程序：两边各带一份分配器垫片（__rust_alloc
+// 等），链接器会报重复定义
+// A Rust staticlib can't be linked into a Rust program with +whole-archive: both sides bring an
+// allocator shim (__rust_alloc and friends) and the linker reports duplicate definitions
+
+#![cfg(all(
+    any(target_os = "linux", target_os = "android"),
+    not(feature = "static")
+))]
+
+use std::ffi::{c_char, c_int, CStr};
+use std::path::Path;
+use std::process::Command;
+use std::ptr;
+
+const STATUS_OK: c_int = 0;
+
+#[repr(C)]
+struct StaticlibOp {
+    name: *const c_char,
+    apply: extern "C" fn(value: c_int) -> c_int,
+}
+
+extern "C" {
+    fn rxc_staticlib_ops(ops: *mut *const StaticlibOp, len: *mut usize) -> c_int;
+}
+
+// 运行 build.rs 编译的一个 ops_consumer，返回它输出的每一行
+// Runs one of the ops_consumer programs build.rs compiles, returning the lines it printed
+fn run(program: &str) -> Vec<String> {
+    let path = Path::new(env!("OUT_DIR")).join(program);
+    let output = Command::new(&path)
+        .output()
+        .unwrap_or_else(|err| panic!("cannot run {}: {}", path.display(), err));
+    assert!(output.status.success(), "exited with {}", output.status);
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(str::to_owned)
+        .collect()
+}
+
+fn linked_ops() -> Vec<(String, c_int)> {
+    let mut ops = ptr::null();
+    let mut len = 0;
+    assert_eq!(unsafe { rxc_staticlib_ops(&mut ops, &mut len) }, STATUS_OK);
+    let ops = unsafe { std::slice::from_raw_parts(ops, len) };
+    ops.iter()
+        .map(|op| {
+            let name = unsafe { CStr::from_ptr(op.name) };
+            (name.to_str().unwrap().to_owned(), (op.apply)(7))
+        })
+        .collect()
+}
+
+// rxc_staticlib_ops 直接引用 identity，无论怎样链接它都在
+// rxc_staticlib_ops refers to identity directly, so it is there however the library is linked
+#[test]
+fn plain_link_keeps_the_referenced_entry() {
+    let lines = run("ops_consumer");
+    assert!(
+        lines.contains(&"[C] identity(7) = 7".to_owned()),
+        "{lines:?}"
+    );
+}
+
+#[test]
+fn whole_archive_keeps_the_unreferenced_entry() {
+    let lines = run("ops_consumer_whole_archive");
+    assert!(
+        lines.contains(&"[C] identity(7) = 7".to_owned()),
+        "{lines:?}"
+    );
+    assert!(
+        lines.contains(&"[C] square(7) = 49".to_owned()),
+        "{lines:?}"
+    );
+}
+
+#[test]
+fn rust_link_with_whole_archive_keeps_the_c_entry() {
+    let ops = linked_ops();
+    assert!(ops.contains(&("identity".to_owned(), 7)), "{ops:?}");
+    assert!(ops.contains(&("cube".to_owned(), 343)), "{ops:?}");
+}
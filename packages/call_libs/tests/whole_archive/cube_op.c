@@ -0,0 +1,19 @@
+// 从 C 注册到 staticlib_gen 操作表中的操作：和 Rust 中的 register_op! 一样放进 staticlib_op_table 段，
+// 没有任何符号引用它。build.rs 把它编译成静态库，用 +whole-archive 链接进 call_libs，由
+// tests/whole_archive.rs 检查它还在
+// An operation registered in the staticlib_gen operation table from C: like register_op! in Rust
+// it is placed in the staticlib_op_table section and no symbol refers to it. build.rs compiles it
+// into a static archive linked into call_libs with +whole-archive, and tests/whole_archive.rs
+// checks that it is still there
+#include "staticlib_gen.h"
+
+static int cube(int value)
+{
+    return value * value * value;
+}
+
+// retain 让这个段在 --gc-sections 之后也留下：rustc 总是带着它链接，而 lld 默认不会因为 __start_/__stop_
+// 的引用保留一个段
+// retain keeps the section through --gc-sections: rustc always links with it, and by default lld
+// doesn't keep a section just because __start_/__stop_ refer to it
+__attribute__((used, retain, section("staticlib_op_table"))) static const StaticlibOp cube_op = {"cube", cube};
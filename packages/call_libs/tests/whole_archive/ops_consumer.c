@@ -0,0 +1,24 @@
+// 这个 C 程序列出链接进来的 staticlib_gen 操作表，build.rs 把它链接两次：ops_consumer 按普通方式链接
+// libstaticlib_gen.a，ops_consumer_whole_archive 用 --whole-archive 链接整个静态库
+// This C program lists the staticlib_gen operation table it was linked with, and build.rs links it
+// twice: ops_consumer links libstaticlib_gen.a the usual way, ops_consumer_whole_archive links the
+// whole archive with --whole-archive
+#include <stdio.h>
+#include "staticlib_gen.h"
+
+int main(void)
+{
+    const StaticlibOp *ops = NULL;
+    size_t len = 0;
+    enum FfiStatus status = rxc_staticlib_ops(&ops, &len);
+    if (status != FFI_STATUS_OK)
+    {
+        fprintf(stderr, "[C] rxc_staticlib_ops failed with status %d\n", (int)status);
+        return (int)status;
+    }
+    for (size_t i = 0; i < len; i++)
+    {
+        printf("[C] %s(7) = %d\n", ops[i].name, ops[i].apply(7));
+    }
+    return 0;
+}
@@ -0,0 +1,36 @@
+// MSVC 编译的 C 调用方用 L"..." 字面量调用 rxc_cdylib_add_w，检查宽字符串原样往返
+// An MSVC-compiled C caller calls rxc_cdylib_add_w with L"..." literals, checking wide strings
+// round trip unchanged
+
+#![cfg(all(windows, target_env = "msvc", not(feature = "static")))]
+
+use std::ffi::c_int;
+
+use interop_common::InitConfig;
+
+mod common;
+use common::init;
+
+// 和 include/cdylib_gen.h 中 FFI_STATUS_BUFFER_TOO_SMALL 的值相同
+// The value of FFI_STATUS_BUFFER_TOO_SMALL in include/cdylib_gen.h
+const STATUS_BUFFER_TOO_SMALL: c_int = 5;
+
+extern "C" {
+    fn c_wide_add(a: c_int, b: c_int, result: *mut u16, len: usize) -> c_int;
+    fn c_wide_add_matches() -> c_int;
+}
+
+#[test]
+fn c_literals_round_trip() {
+    init(&InitConfig::default());
+    assert_eq!(unsafe { c_wide_add_matches() }, 1);
+}
+
+#[test]
+fn c_buffer_is_truncated_in_code_units() {
+    init(&InitConfig::default());
+    let mut result = [0u16; 8];
+    let status = unsafe { c_wide_add(1, 2, result.as_mut_ptr(), result.len()) };
+    assert_eq!(status, STATUS_BUFFER_TOO_SMALL);
+    assert_eq!(String::from_utf16_lossy(&result[..7]), "[Rust c");
+}
@@ -0,0 +1,30 @@
+// tests/wide.rs 用到的 C 调用方，只用 MSVC 编译：Windows 的 wchar_t 是 UTF-16 代码单元，L"..." 字面量
+// 可以原样传给 rxc_cdylib_add_w，中文和代理对都不经过本地代码页
+// The C caller tests/wide.rs uses, only compiled with MSVC: Windows' wchar_t is a UTF-16 code unit,
+// so L"..." literals go to rxc_cdylib_add_w as they are, Chinese text and surrogate pairs never
+// passing through a code page
+#include <wchar.h>
+#include "cdylib_gen.h"
+
+_Static_assert(sizeof(wchar_t) == sizeof(uint16_t), "wchar_t is not a UTF-16 code unit");
+
+// 调用方的名字是“张三”和一个代理对（U+1F980）；用转义写出，不依赖源文件的编码
+// The caller's name is "Zhang San" in Chinese and a surrogate pair (U+1F980), written as escapes
+// so it doesn't depend on the source file's encoding
+int c_wide_add(int a, int b, wchar_t *result, size_t len)
+{
+    wcsncpy_s(result, len, L"\u5f20\u4e09 \U0001F980", _TRUNCATE);
+    return (int)rxc_cdylib_add_w(a, b, (uint16_t *)result, len);
+}
+
+// rxc_cdylib_add_w 写回的消息和 L"..." 字面量逐个代码单元相同时返回 1
+// Returns 1 when the message rxc_cdylib_add_w wrote back matches the L"..." literal unit by unit
+int c_wide_add_matches(void)
+{
+    wchar_t result[64];
+    if (c_wide_add(1, 2, result, sizeof(result) / sizeof(result[0])) != FFI_STATUS_OK)
+    {
+        return 0;
+    }
+    return wcscmp(result, L"[Rust cdylib] The result (1 + 2) is 3!") == 0;
+}
@@ -0,0 +1,104 @@
+// 这是我们的构建脚本，用 cbindgen 从 Rust 源码生成 C 头文件
+// This is our build script, it generates the C header from the Rust source with cbindgen
+
+use std::{env, fs, path::PathBuf};
+
+const HEADER: &str = "cdylib_gen.h";
+
+fn main() {
+    let crate_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap());
+    let out_dir = PathBuf::from(env::var("OUT_DIR").unwrap());
+    let config = cbindgen::Config::from_file(crate_dir.join("cbindgen.toml")).unwrap();
+
+    cbindgen::Builder::new()
+        .with_crate(&crate_dir)
+        .with_config(config)
+        .generate()
+        .expect("Unable to generate the C header.")
+        .write_to_file(out_dir.join(HEADER));
+
+    // 设置 UPDATE_HEADERS=1 时把生成的头文件同步到仓库的 include 目录
+    // With UPDATE_HEADERS=1 the generated header is copied into the repository's include directory
+    if env::var_os("UPDATE_HEADERS").is_some() {
+        let include_dir = crate_dir.join("../../include");
+        fs::create_dir_all(&include_dir).unwrap();
+        fs::copy(out_dir.join(HEADER), include_dir.join(HEADER)).unwrap();
+    }
+
+    inject_git_hash(&crate_dir);
+    write_def_file(&crate_dir, &out_dir);
+
+    // cargo xtask install 和 android 设置 CDYLIB_GEN_SONAME 时，在 Linux 和 Android 上把它写入库的 SONAME
+    // When cargo xtask install or android sets CDYLIB_GEN_SONAME, record it as the library's
+    // SONAME on Linux and Android
+    if let Ok(soname) = env::var("CDYLIB_GEN_SONAME") {
+        if matches!(env::var("CARGO_CFG_TARGET_OS").unwrap().as_str(), "linux" | "android") {
+            println!("cargo::rustc-cdylib-link-arg=-Wl,-soname,{}", soname);
+        }
+    }
+
+    println!("cargo::rerun-if-changed=src");
+    println!("cargo::rerun-if-changed=cbindgen.toml");
+    println!("cargo::rerun-if-changed=exports.txt");
+    println!("cargo::rerun-if-changed=exports_jni.txt");
+    println!("cargo::rerun-if-changed=exports_stdcall.txt");
+    println!("cargo::rerun-if-changed=../interop_common/src");
+    println!("cargo::rerun-if-env-changed=UPDATE_HEADERS");
+    println!("cargo::rerun-if-env-changed=CDYLIB_GEN_SONAME");
+}
+
+// 把当前提交的哈希注入为 CDYLIB_GIT_HASH，不在 git 仓库中构建时为空
+// Injects the current commit hash as CDYLIB_GIT_HASH, empty when building outside a git checkout
+fn inject_git_hash(crate_dir: &std::path::Path) {
+    let hash = std::process::Command::new("git")
+        .args(["rev-parse", "HEAD"])
+        .current_dir(crate_dir)
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .map(|hash| hash.trim().to_owned())
+        .unwrap_or_default();
+    println!("cargo::rustc-env=CDYLIB_GIT_HASH={}", hash);
+
+    // HEAD 指向的提交变化时重新运行
+    // Rerun when the commit HEAD points to changes
+    let git_dir = crate_dir.join("../../.git");
+    if git_dir.join("HEAD").exists() {
+        println!("cargo::rerun-if-changed={}", git_dir.join("HEAD").display());
+        println!("cargo::rerun-if-changed={}", git_dir.join("refs/heads").display());
+    }
+}
+
+// 由 exports.txt（启用 jni 特性时还有 exports_jni.txt，32 位 Windows 上还有 exports_stdcall.txt）生成
+// .def 文件，在 Windows 上让 DLL 只导出其中列出的符号
+// Generates a .def file from exports.txt (plus exports_jni.txt with the jni feature and
+// exports_stdcall.txt on 32-bit Windows) so that on Windows the DLL only exports the listed symbols
+fn write_def_file(crate_dir: &std::path::Path, out_dir: &std::path::Path) {
+    let mut exports = fs::read_to_string(crate_dir.join("exports.txt")).unwrap();
+    if env::var_os("CARGO_FEATURE_JNI").is_some() {
+        exports.push_str(&fs::read_to_string(crate_dir.join("exports_jni.txt")).unwrap());
+    }
+    let target_os = env::var("CARGO_CFG_TARGET_OS").unwrap();
+    if target_os == "windows" && env::var("CARGO_CFG_TARGET_ARCH").unwrap() == "x86" {
+        exports.push_str(&fs::read_to_string(crate_dir.join("exports_stdcall.txt")).unwrap());
+    }
+    let mut def = String::from("LIBRARY cdylib_gen\nEXPORTS\n");
+    for symbol in exports.lines().filter(|line| !line.is_empty() && !line.starts_with('#')) {
+        def.push_str("    ");
+        def.push_str(symbol);
+        def.push('\n');
+    }
+    let def_file = out_dir.join("cdylib_gen.def");
+    fs::write(&def_file, def).unwrap();
+
+    if target_os == "windows" {
+        // link.exe 用 /DEF: 接收 .def 文件，GNU ld 把它当作普通输入文件
+        // link.exe takes the .def file through /DEF:, GNU ld accepts it as a plain input file
+        if env::var("CARGO_CFG_TARGET_ENV").unwrap() == "msvc" {
+            println!("cargo::rustc-cdylib-link-arg=/DEF:{}", def_file.display());
+        } else {
+            println!("cargo::rustc-cdylib-link-arg={}", def_file.display());
+        }
+    }
+}
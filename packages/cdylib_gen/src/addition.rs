@@ -0,0 +1,28 @@
+// 不涉及指针的加法和消息格式化，cdylib_add 只负责在 C 指针和这里的值之间转换，因此可以在 Miri 下测试
+// Adding and message formatting without any pointers; cdylib_add only converts between C pointers
+// and these values, which keeps this part testable under Miri
+
+use std::ffi::c_int;
+
+use interop_common::FfiError;
+
+/// The sum of two operands and the message reporting it back to C.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Addition {
+    pub sum: c_int,
+    pub message: String,
+}
+
+/// Adds `a` and `b`, failing with [`FfiError::Overflow`] if the sum does not fit in a `c_int`.
+pub fn addition(a: c_int, b: c_int) -> Result<Addition, FfiError> {
+    let sum = a.checked_add(b).ok_or(FfiError::Overflow)?;
+    Ok(Addition {
+        sum,
+        message: format!("[Rust cdylib] The result ({a} + {b}) is {sum}!"),
+    })
+}
+
+/// The line printed for the caller's name.
+pub fn hello(name: &str) -> String {
+    format!("[Rust cdylib] Hello {name}")
+}
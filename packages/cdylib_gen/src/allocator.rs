@@ -0,0 +1,95 @@
+// 库的全局分配器带着计数器：C 端通过 rustlib_alloc_stats 查询分配情况；
+// InitConfig.leak_check 打开时，rustlib_shutdown 报告 rustlib_init 之后分配、还没有释放的内存，
+// 比如忘记调用 calc_free 的计算器
+// The library's global allocator carries counters: C queries them through rustlib_alloc_stats,
+// and with InitConfig.leak_check on rustlib_shutdown reports the memory allocated since
+// rustlib_init and not released yet, such as a calculator nobody passed to calc_free
+
+use std::sync::Mutex;
+
+use interop_common::{check_ptr, ffi_guard, write_out, AllocStats, CountingAllocator, FfiStatus};
+
+// 开启 malloc 特性后，库里所有的 Rust 分配都来自 C 运行时的堆
+// With the malloc feature on, every Rust allocation in the library comes from the C runtime's heap
+#[cfg(feature = "malloc")]
+#[global_allocator]
+static ALLOCATOR: CountingAllocator<interop_common::MallocAllocator> =
+    CountingAllocator::new(interop_common::MallocAllocator);
+
+#[cfg(not(feature = "malloc"))]
+#[global_allocator]
+static ALLOCATOR: CountingAllocator<std::alloc::System> =
+    CountingAllocator::new(std::alloc::System);
+
+#[derive(Default)]
+struct LeakCheck {
+    // leak_check 打开时 rustlib_init 开始时的快照
+    // The snapshot from the start of rustlib_init when leak_check is on
+    baseline: Option<AllocStats>,
+    leaked_blocks: u64,
+    leaked_bytes: u64,
+}
+
+static LEAK_CHECK: Mutex<LeakCheck> = Mutex::new(LeakCheck {
+    baseline: None,
+    leaked_blocks: 0,
+    leaked_bytes: 0,
+});
+
+fn leak_check() -> std::sync::MutexGuard<'static, LeakCheck> {
+    LEAK_CHECK.lock().unwrap_or_else(|err| err.into_inner())
+}
+
+// 在 rustlib_init 启动任何东西之前调用，之后的分配都算在这次检查里
+// Called before rustlib_init starts anything, so every allocation after it counts for the check
+pub(crate) fn begin_leak_check(enabled: bool) {
+    leak_check().baseline = enabled.then(|| ALLOCATOR.stats());
+}
+
+// 在 rustlib_shutdown 停掉工作线程和运行时之后调用，报告还没有释放的内存
+// Called once rustlib_shutdown stopped the worker threads and the runtime, reports the memory
+// still not released
+pub(crate) fn finish_leak_check() {
+    let mut check = leak_check();
+    let Some(baseline) = check.baseline.take() else {
+        return;
+    };
+    let now = ALLOCATOR.stats();
+    check.leaked_blocks = now.live_blocks.saturating_sub(baseline.live_blocks);
+    check.leaked_bytes = now.live_bytes.saturating_sub(baseline.live_bytes);
+    if check.leaked_blocks == 0 {
+        log::info!("[Rust cdylib] Leak check: nothing allocated since rxc_rustlib_init is left");
+    } else {
+        log::warn!(
+            "[Rust cdylib] Leak check: {} allocations ({} bytes) made since rxc_rustlib_init were never released",
+            check.leaked_blocks,
+            check.leaked_bytes
+        );
+    }
+}
+
+/// Writes the statistics of the library's allocator into `out`: the blocks and bytes allocated
+/// and still in use, and the result of the last leak check `rxc_rustlib_shutdown` ran for an
+/// `InitConfig` with `leak_check` set. Works at any time.
+///
+/// The counters cover every allocation in the library, including its own worker threads and
+/// caches, so compare two snapshots rather than reading one on its own. State kept for a calling
+/// thread until it exits, such as the runtime's after `rxc_async_block_on`, is counted by the leak
+/// check too.
+///
+/// # Safety
+///
+/// `out` must be valid for writes.
+#[export_name = "rxc_rustlib_alloc_stats"]
+pub unsafe extern "C" fn rustlib_alloc_stats(out: *mut AllocStats) -> FfiStatus {
+    ffi_guard(|| {
+        check_ptr(out)?;
+        let check = leak_check();
+        let stats = AllocStats {
+            leaked_blocks: check.leaked_blocks,
+            leaked_bytes: check.leaked_bytes,
+            ..ALLOCATOR.stats()
+        };
+        write_out(out, stats)
+    })
+}
@@ -0,0 +1,76 @@
+// 由 C 提供缓冲区，Rust 按容量写入数组元素
+// C provides the buffer and Rust writes array elements into it, up to its capacity
+
+use std::ffi;
+
+use interop_common::{
+    ensure_initialized, ffi_guard, slice_from_raw, slice_from_raw_mut, write_out, FfiError,
+    FfiSlice, FfiStatus,
+};
+
+/// Writes the values of `[start, end)` into `out`, stopping after `cap` values.
+///
+/// Returns the number of values in the range, so a return value greater than `cap` means `out`
+/// was too small; passing a NULL `out` with `cap == 0` only queries that count.
+///
+/// # Safety
+///
+/// `out` must be valid for writes of `cap` `int`s (it may be NULL when `cap` is 0).
+#[export_name = "rxc_cdylib_range"]
+pub unsafe extern "C" fn cdylib_range(
+    start: ffi::c_int,
+    end: ffi::c_int,
+    out: *mut ffi::c_int,
+    cap: usize,
+) -> usize {
+    let count = (end as i64 - start as i64).max(0) as usize;
+    if let Ok(out) = slice_from_raw_mut(out, cap) {
+        for (slot, value) in out.iter_mut().zip(start..end) {
+            *slot = value;
+        }
+    }
+    count
+}
+
+/// Sums the `len` values at `values` into `total`, like `rxc_staticlib_sum`.
+///
+/// `values` may be NULL when `len` is 0. Fails with `FFI_STATUS_OVERFLOW` if the sum does not fit
+/// in a `long`, which on Windows is only 32 bits wide.
+///
+/// # Safety
+///
+/// `values` must point to `len` readable `int`s and `total` must be valid for writes.
+#[export_name = "rxc_cdylib_sum"]
+pub unsafe extern "C" fn cdylib_sum(
+    values: *const ffi::c_int,
+    len: usize,
+    total: *mut ffi::c_long,
+) -> FfiStatus {
+    ffi_guard(|| {
+        ensure_initialized()?;
+        write_out(total, sum(slice_from_raw(values, len)?)?)
+    })
+}
+
+/// The same as `rxc_cdylib_sum`, taking the values as one `FfiSlice_c_int`.
+///
+/// # Safety
+///
+/// `values` must view `len` readable `int`s and `total` must be valid for writes.
+#[export_name = "rxc_cdylib_sum_slice"]
+pub unsafe extern "C" fn cdylib_sum_slice(
+    values: FfiSlice<ffi::c_int>,
+    total: *mut ffi::c_long,
+) -> FfiStatus {
+    ffi_guard(|| {
+        ensure_initialized()?;
+        write_out(total, sum(values.as_slice()?)?)
+    })
+}
+
+fn sum(values: &[ffi::c_int]) -> Result<ffi::c_long, FfiError> {
+    values
+        .iter()
+        .try_fold(0 as ffi::c_long, |acc, &v| acc.checked_add(v.into()))
+        .ok_or(FfiError::Overflow)
+}
@@ -0,0 +1,122 @@
+// 一个不透明句柄的例子：C 端只拿到 {index, generation} 句柄，对象留在 Rust 的注册表里，
+// 过期或重复释放的句柄返回 INVALID_HANDLE
+// An opaque handle example: C only ever sees an {index, generation} handle while the object
+// stays in a registry on the Rust side, and stale or double-freed handles fail with
+// INVALID_HANDLE
+
+use std::ffi;
+use std::sync::{Mutex, MutexGuard};
+
+use interop_common::{
+    ensure_initialized, ffi_guard, ffi_guard_or, write_cstr, write_out, FfiError, FfiHandle,
+    FfiStatus, HandleRegistry,
+};
+
+/// A calculator that remembers every addition it performed.
+///
+/// The layout is private to Rust; C code only handles the `CalculatorHandle` obtained from
+/// `rxc_calc_new`. One calculator may be used from several threads at once, the history behind a
+/// mutex keeps their calls apart; freeing it while another thread still uses it makes that
+/// thread's later calls fail with `FFI_STATUS_INVALID_HANDLE`.
+pub struct Calculator {
+    history: Mutex<Vec<String>>,
+}
+
+/// A calculator handed to C, the null handle when `rxc_calc_new` failed.
+pub type CalculatorHandle = FfiHandle;
+
+static CALCULATORS: HandleRegistry<Calculator> = HandleRegistry::new();
+
+/// Creates a new calculator. The handle must be released with `rxc_calc_free`.
+///
+/// Returns the null handle if the library is not initialized.
+#[export_name = "rxc_calc_new"]
+pub extern "C" fn calc_new() -> CalculatorHandle {
+    ffi_guard_or(FfiHandle::NULL, || {
+        ensure_initialized()?;
+        Ok(CALCULATORS.insert(Calculator {
+            history: Mutex::new(Vec::new()),
+        }))
+    })
+}
+
+/// Adds `a` and `b` with the calculator, storing the sum in `sum` and recording it in the history.
+///
+/// Fails with `FFI_STATUS_INVALID_HANDLE` when `handle` was freed or never came from
+/// `rxc_calc_new`.
+///
+/// # Safety
+///
+/// `sum` must be valid for writes.
+#[export_name = "rxc_calc_add"]
+pub unsafe extern "C" fn calc_add(
+    handle: CalculatorHandle,
+    a: ffi::c_int,
+    b: ffi::c_int,
+    sum: *mut ffi::c_int,
+) -> FfiStatus {
+    let _span = tracing::info_span!("rxc_calc_add", a, b).entered();
+    ffi_guard(|| {
+        ensure_initialized()?;
+        let calc = CALCULATORS.get(handle)?;
+        let mut history = calc.history();
+        let total = a.checked_add(b).ok_or_else(|| {
+            tracing::warn!("rxc_calc_add overflowed");
+            FfiError::Overflow
+        })?;
+        write_out(sum, total)?;
+        history.push(format!("{a} + {b} = {total}"));
+        Ok(())
+    })
+}
+
+/// Writes the calculator's history, one addition per line, into `buf` like `snprintf` does.
+///
+/// If `required_len` is not NULL it receives the length of the full history (excluding the NUL).
+/// Fails with `FFI_STATUS_INVALID_HANDLE` when `handle` was freed or never came from
+/// `rxc_calc_new`.
+///
+/// # Safety
+///
+/// `buf` must be valid for writes of `len` bytes (it may be NULL when `len` is 0) and
+/// `required_len` must be NULL or valid for writes.
+#[export_name = "rxc_calc_history"]
+pub unsafe extern "C" fn calc_history(
+    handle: CalculatorHandle,
+    buf: *mut ffi::c_char,
+    len: usize,
+    required_len: *mut usize,
+) -> FfiStatus {
+    ffi_guard(|| {
+        ensure_initialized()?;
+        let history = CALCULATORS.get(handle)?.history().join("\n");
+        if !required_len.is_null() {
+            write_out(required_len, history.len())?;
+        }
+        write_cstr(buf, len, &history).map(|_| ())
+    })
+}
+
+/// Destroys a calculator created by `rxc_calc_new`. Passing the null handle is a no-op.
+///
+/// Freeing a handle twice, or one that never came from `rxc_calc_new`, fails with
+/// `FFI_STATUS_INVALID_HANDLE` and leaves every other calculator alone. A call still running on
+/// another thread finishes with the calculator before it is destroyed.
+#[export_name = "rxc_calc_free"]
+pub extern "C" fn calc_free(handle: CalculatorHandle) -> FfiStatus {
+    ffi_guard(|| {
+        if !handle.is_null() {
+            drop(CALCULATORS.remove(handle)?);
+        }
+        Ok(())
+    })
+}
+
+impl Calculator {
+    // 多个线程可以同时持有同一个计算器；一次调用在 panic 中途留下的历史仍然可用
+    // Several threads may hold the same calculator at once; a history left behind by a call that
+    // panicked midway is still usable
+    fn history(&self) -> MutexGuard<'_, Vec<String>> {
+        self.history.lock().unwrap_or_else(|err| err.into_inner())
+    }
+}
@@ -0,0 +1,39 @@
+// C 回调的例子：结果通过函数指针和 void* 上下文交还给调用方
+// A C callback example: the result is handed back through a function pointer plus a void* context
+
+use std::ffi::{self, c_void};
+
+use interop_common::{ensure_initialized, ffi_guard, FfiError, FfiStatus};
+
+use crate::workers::{self, UserData};
+
+/// Completion callback for `rxc_cdylib_add_async`, receiving the sum and the caller's `user_data`.
+///
+/// `Option` makes a NULL function pointer representable, so it can be rejected instead of called.
+pub type AddCallback = Option<extern "C" fn(sum: ffi::c_int, user_data: *mut c_void)>;
+
+/// Adds `a` and `b` on one of the library's worker threads and reports the sum through `cb`.
+///
+/// On success `cb` is invoked exactly once, from another thread, with `user_data` passed through
+/// untouched; on failure (NULL `cb`, overflow or an uninitialized library) it is never invoked.
+/// `user_data` must stay valid until the callback has run, at the latest by `rxc_rustlib_shutdown`.
+#[export_name = "rxc_cdylib_add_async"]
+pub extern "C" fn cdylib_add_async(
+    a: ffi::c_int,
+    b: ffi::c_int,
+    cb: AddCallback,
+    user_data: *mut c_void,
+) -> FfiStatus {
+    ffi_guard(|| {
+        ensure_initialized()?;
+        let cb = cb.ok_or(FfiError::NullPointer)?;
+        let total = a.checked_add(b).ok_or(FfiError::Overflow)?;
+        let user_data = UserData(user_data);
+
+        workers::spawn(move || {
+            let user_data = user_data;
+            log::info!("[Rust cdylib] Calling back with the result ({a} + {b}) = {total}");
+            cb(total, user_data.0);
+        })
+    })
+}
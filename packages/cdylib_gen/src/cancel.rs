@@ -0,0 +1,118 @@
+// 协作式取消：长时间运行的调用在每一步之间检查令牌，另一个线程调用 cancel_token_cancel 后它尽快
+// 返回 CANCELLED
+// Cooperative cancellation: a long-running call checks its token between steps and returns
+// CANCELLED soon after another thread calls cancel_token_cancel
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use std::{ffi, thread};
+
+use interop_common::{
+    ensure_initialized, ffi_guard, ffi_guard_or, slice_from_raw, write_out, FfiError, FfiHandle,
+    FfiStatus, HandleRegistry,
+};
+
+/// A flag one thread sets to ask calls running on other threads to stop early.
+///
+/// The layout is private to Rust; C code only handles the `CancelTokenHandle` obtained from
+/// `rxc_cancel_token_new`. A token may be passed to any number of calls, and once cancelled it
+/// stays cancelled.
+pub struct CancelToken {
+    cancelled: AtomicBool,
+}
+
+/// A token handed to C, the null handle when `rxc_cancel_token_new` failed.
+pub type CancelTokenHandle = FfiHandle;
+
+static TOKENS: HandleRegistry<CancelToken> = HandleRegistry::new();
+
+impl CancelToken {
+    fn check(&self) -> Result<(), FfiError> {
+        if self.cancelled.load(Ordering::Acquire) {
+            Err(FfiError::Cancelled)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Creates a token that is not cancelled yet. The handle must be released with
+/// `rxc_cancel_token_free`.
+///
+/// Returns the null handle if the library is not initialized.
+#[export_name = "rxc_cancel_token_new"]
+pub extern "C" fn cancel_token_new() -> CancelTokenHandle {
+    ffi_guard_or(FfiHandle::NULL, || {
+        ensure_initialized()?;
+        Ok(TOKENS.insert(CancelToken {
+            cancelled: AtomicBool::new(false),
+        }))
+    })
+}
+
+/// Cancels `token`, making the calls using it return `FFI_STATUS_CANCELLED` at their next check.
+///
+/// Safe to call from any thread, including while the library shuts down; cancelling twice is
+/// harmless. Fails with `FFI_STATUS_INVALID_HANDLE` when `token` was freed or never came from
+/// `rxc_cancel_token_new`.
+#[export_name = "rxc_cancel_token_cancel"]
+pub extern "C" fn cancel_token_cancel(token: CancelTokenHandle) -> FfiStatus {
+    ffi_guard(|| {
+        TOKENS.get(token)?.cancelled.store(true, Ordering::Release);
+        Ok(())
+    })
+}
+
+/// Destroys a token created by `rxc_cancel_token_new`. Passing the null handle is a no-op.
+///
+/// Calls still using the token keep their own reference to it until they return, so it may be
+/// freed at any time. Freeing a handle twice, or one that never came from `rxc_cancel_token_new`,
+/// fails with `FFI_STATUS_INVALID_HANDLE`.
+#[export_name = "rxc_cancel_token_free"]
+pub extern "C" fn cancel_token_free(token: CancelTokenHandle) -> FfiStatus {
+    ffi_guard(|| {
+        if !token.is_null() {
+            drop(TOKENS.remove(token)?);
+        }
+        Ok(())
+    })
+}
+
+/// Sums the `len` values at `values` into `total` like `rxc_cdylib_sum`, waiting `delay_ms`
+/// milliseconds before each value to stand in for slow work.
+///
+/// Before each value it checks `token` (which may be the null handle to run to completion) and
+/// fails with `FFI_STATUS_CANCELLED` once the token was cancelled, leaving `total` untouched. A
+/// token that was freed or never came from `rxc_cancel_token_new` fails with
+/// `FFI_STATUS_INVALID_HANDLE` before any value is summed.
+///
+/// # Safety
+///
+/// `values` must point to `len` readable `int`s and `total` must be valid for writes.
+#[export_name = "rxc_cdylib_slow_sum"]
+pub unsafe extern "C" fn cdylib_slow_sum(
+    values: *const ffi::c_int,
+    len: usize,
+    delay_ms: u32,
+    token: CancelTokenHandle,
+    total: *mut ffi::c_long,
+) -> FfiStatus {
+    ffi_guard(|| {
+        ensure_initialized()?;
+        let values = slice_from_raw(values, len)?;
+        let token = (!token.is_null()).then(|| TOKENS.get(token)).transpose()?;
+        let mut sum: ffi::c_long = 0;
+        for (i, &value) in values.iter().enumerate() {
+            if let Some(token) = &token {
+                token.check().inspect_err(|_| {
+                    log::info!(
+                        "[Rust cdylib] rxc_cdylib_slow_sum cancelled after {i} of {len} values"
+                    );
+                })?;
+            }
+            thread::sleep(Duration::from_millis(delay_ms.into()));
+            sum = sum.checked_add(value.into()).ok_or(FfiError::Overflow)?;
+        }
+        write_out(total, sum)
+    })
+}
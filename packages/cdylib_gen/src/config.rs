@@ -0,0 +1,265 @@
+// 把 Rust 的建造者模式搬到 C ABI 上：C 不填一个越来越大的结构体，而是从 config_new 拿到一个不透明的
+// 建造者句柄，逐项调用 setter，最后用 config_build 换成不可变的 Config 句柄。每个 setter 立即检查自己的
+// 参数，build 检查必填项；新的选项只需要新的 setter，已有调用方的代码和 ABI 都不变
+// Rust's builder pattern carried over to a C ABI: instead of filling in an ever larger struct, C
+// gets an opaque builder handle from config_new, calls one setter per option and finally trades it
+// for an immutable Config handle with config_build. Every setter checks its own argument right
+// away and build checks the required ones; a new option only needs a new setter, leaving both the
+// code and the ABI of existing callers unchanged
+
+use std::ffi;
+use std::sync::Mutex;
+
+use interop_common::{
+    ensure_initialized, ffi_guard, ffi_guard_or, read_cstr, write_cstr, write_out, FfiError,
+    FfiHandle, FfiStatus, HandleRegistry,
+};
+
+/// The buffer size a [`Config`] gets unless `rxc_config_set_buffer_size` is called.
+pub const CONFIG_DEFAULT_BUFFER_SIZE: usize = 4096;
+
+/// The smallest buffer size `rxc_config_set_buffer_size` accepts.
+pub const CONFIG_MIN_BUFFER_SIZE: usize = 64;
+
+/// The largest buffer size `rxc_config_set_buffer_size` accepts.
+pub const CONFIG_MAX_BUFFER_SIZE: usize = 1 << 20;
+
+/// 构建完成、不再改变的配置
+/// A finished configuration, which never changes.
+///
+/// The layout is private to Rust; C code only handles the `ConfigHandle` obtained from
+/// `rxc_config_build`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Config {
+    name: String,
+    buffer_size: usize,
+}
+
+impl Config {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn buffer_size(&self) -> usize {
+        self.buffer_size
+    }
+}
+
+/// [`Config`] 的建造者，C 通过 ConfigBuilderHandle 使用它
+/// The builder of a [`Config`], used by C through a `ConfigBuilderHandle`.
+#[derive(Debug, Clone)]
+pub struct ConfigBuilder {
+    name: Option<String>,
+    buffer_size: usize,
+}
+
+impl Default for ConfigBuilder {
+    fn default() -> Self {
+        ConfigBuilder {
+            name: None,
+            buffer_size: CONFIG_DEFAULT_BUFFER_SIZE,
+        }
+    }
+}
+
+impl ConfigBuilder {
+    pub fn new() -> ConfigBuilder {
+        ConfigBuilder::default()
+    }
+
+    /// Sets the required name, failing with [`FfiError::InvalidArgument`] when it's empty.
+    pub fn name(&mut self, name: &str) -> Result<&mut ConfigBuilder, FfiError> {
+        if name.is_empty() {
+            return Err(FfiError::InvalidArgument);
+        }
+        self.name = Some(name.to_owned());
+        Ok(self)
+    }
+
+    /// Sets the buffer size, failing with [`FfiError::InvalidArgument`] unless it's a power of
+    /// two from [`CONFIG_MIN_BUFFER_SIZE`] to [`CONFIG_MAX_BUFFER_SIZE`].
+    pub fn buffer_size(&mut self, size: usize) -> Result<&mut ConfigBuilder, FfiError> {
+        if !size.is_power_of_two()
+            || !(CONFIG_MIN_BUFFER_SIZE..=CONFIG_MAX_BUFFER_SIZE).contains(&size)
+        {
+            return Err(FfiError::InvalidArgument);
+        }
+        self.buffer_size = size;
+        Ok(self)
+    }
+
+    /// The finished configuration, failing with [`FfiError::InvalidArgument`] when no name was
+    /// set.
+    pub fn build(&self) -> Result<Config, FfiError> {
+        Ok(Config {
+            name: self.name.clone().ok_or(FfiError::InvalidArgument)?,
+            buffer_size: self.buffer_size,
+        })
+    }
+}
+
+/// A builder handed to C, the null handle when `rxc_config_new` failed.
+pub type ConfigBuilderHandle = FfiHandle;
+
+/// A finished configuration handed to C.
+pub type ConfigHandle = FfiHandle;
+
+// 同一个建造者可能被多个线程同时修改，每次调用在锁内完成
+// Several threads may change the same builder at once, each call completes under the lock
+static BUILDERS: HandleRegistry<Mutex<ConfigBuilder>> = HandleRegistry::new();
+static CONFIGS: HandleRegistry<Config> = HandleRegistry::new();
+
+// 锁只保护建造者自己，失败的 setter 不会留下改了一半的状态
+// The lock only protects the builder itself, and a failing setter leaves no half-made change
+fn with_builder<R>(
+    handle: ConfigBuilderHandle,
+    f: impl FnOnce(&mut ConfigBuilder) -> Result<R, FfiError>,
+) -> Result<R, FfiError> {
+    let builder = BUILDERS.get(handle)?;
+    let mut builder = builder.lock().unwrap_or_else(|err| err.into_inner());
+    f(&mut builder)
+}
+
+/// Creates a builder with the default options and no name. The handle is used up by a successful
+/// `rxc_config_build`, or else must be released with `rxc_config_builder_free`.
+///
+/// Returns the null handle if the library is not initialized.
+#[export_name = "rxc_config_new"]
+pub extern "C" fn config_new() -> ConfigBuilderHandle {
+    ffi_guard_or(FfiHandle::NULL, || {
+        ensure_initialized()?;
+        Ok(BUILDERS.insert(Mutex::new(ConfigBuilder::new())))
+    })
+}
+
+/// Sets the configuration's name to a copy of the NUL-terminated UTF-8 `name`, which is required.
+///
+/// Fails with `FFI_STATUS_INVALID_ARGUMENT` when `name` is empty and with
+/// `FFI_STATUS_INVALID_HANDLE` when `builder` was built, freed or never came from
+/// `rxc_config_new`; the builder is unchanged when it fails.
+///
+/// # Safety
+///
+/// `name` must be NULL or point to a NUL-terminated string.
+#[export_name = "rxc_config_set_name"]
+pub unsafe extern "C" fn config_set_name(
+    builder: ConfigBuilderHandle,
+    name: *const ffi::c_char,
+) -> FfiStatus {
+    ffi_guard(|| {
+        ensure_initialized()?;
+        let name = read_cstr(name, usize::MAX)?;
+        with_builder(builder, |builder| builder.name(name).map(|_| ()))
+    })
+}
+
+/// Sets the size of the configuration's buffer in bytes, `CONFIG_DEFAULT_BUFFER_SIZE` unless
+/// called.
+///
+/// Fails with `FFI_STATUS_INVALID_ARGUMENT` unless `size` is a power of two from
+/// `CONFIG_MIN_BUFFER_SIZE` to `CONFIG_MAX_BUFFER_SIZE`, and like `rxc_config_set_name` for a bad
+/// handle; the builder is unchanged when it fails.
+#[export_name = "rxc_config_set_buffer_size"]
+pub extern "C" fn config_set_buffer_size(builder: ConfigBuilderHandle, size: usize) -> FfiStatus {
+    ffi_guard(|| {
+        ensure_initialized()?;
+        with_builder(builder, |builder| builder.buffer_size(size).map(|_| ()))
+    })
+}
+
+/// Finishes the configuration, storing its handle in `config`; it must be released with
+/// `rxc_config_free`.
+///
+/// On success the builder is used up and its handle becomes stale. Fails with
+/// `FFI_STATUS_INVALID_ARGUMENT` when no name was set, and then leaves the builder valid so it
+/// can be completed and built again, or freed.
+///
+/// # Safety
+///
+/// `config` must be valid for writes.
+#[export_name = "rxc_config_build"]
+pub unsafe extern "C" fn config_build(
+    builder: ConfigBuilderHandle,
+    config: *mut ConfigHandle,
+) -> FfiStatus {
+    ffi_guard(|| {
+        ensure_initialized()?;
+        if config.is_null() {
+            return Err(FfiError::NullPointer);
+        }
+        let built = with_builder(builder, |builder| builder.build())?;
+        // 另一个线程可能已经用同一个建造者构建过，只有从注册表中取走它的那次调用算数
+        // Another thread may have built the same builder already, only the call that takes it out
+        // of the registry counts
+        drop(BUILDERS.remove(builder)?);
+        write_out(config, CONFIGS.insert(built))
+    })
+}
+
+/// Destroys a builder that was never built. Passing the null handle is a no-op.
+///
+/// Fails with `FFI_STATUS_INVALID_HANDLE` for a builder already built or freed.
+#[export_name = "rxc_config_builder_free"]
+pub extern "C" fn config_builder_free(builder: ConfigBuilderHandle) -> FfiStatus {
+    ffi_guard(|| {
+        if !builder.is_null() {
+            drop(BUILDERS.remove(builder)?);
+        }
+        Ok(())
+    })
+}
+
+/// Writes the configuration's name into `buf` like `snprintf` does.
+///
+/// If `required_len` is not NULL it receives the length of the full name (excluding the NUL).
+/// Fails with `FFI_STATUS_INVALID_HANDLE` when `config` was freed or never came from
+/// `rxc_config_build`.
+///
+/// # Safety
+///
+/// `buf` must be valid for writes of `len` bytes (it may be NULL when `len` is 0) and
+/// `required_len` must be NULL or valid for writes.
+#[export_name = "rxc_config_name"]
+pub unsafe extern "C" fn config_name(
+    config: ConfigHandle,
+    buf: *mut ffi::c_char,
+    len: usize,
+    required_len: *mut usize,
+) -> FfiStatus {
+    ffi_guard(|| {
+        ensure_initialized()?;
+        let config = CONFIGS.get(config)?;
+        if !required_len.is_null() {
+            write_out(required_len, config.name().len())?;
+        }
+        write_cstr(buf, len, config.name()).map(|_| ())
+    })
+}
+
+/// Stores the configuration's buffer size in `size`, failing like `rxc_config_name` for a bad
+/// handle.
+///
+/// # Safety
+///
+/// `size` must be valid for writes.
+#[export_name = "rxc_config_buffer_size"]
+pub unsafe extern "C" fn config_buffer_size(config: ConfigHandle, size: *mut usize) -> FfiStatus {
+    ffi_guard(|| {
+        ensure_initialized()?;
+        write_out(size, CONFIGS.get(config)?.buffer_size())
+    })
+}
+
+/// Destroys a configuration built by `rxc_config_build`. Passing the null handle is a no-op.
+///
+/// Freeing a handle twice, or one that never came from `rxc_config_build`, fails with
+/// `FFI_STATUS_INVALID_HANDLE`.
+#[export_name = "rxc_config_free"]
+pub extern "C" fn config_free(config: ConfigHandle) -> FfiStatus {
+    ffi_guard(|| {
+        if !config.is_null() {
+            drop(CONFIGS.remove(config)?);
+        }
+        Ok(())
+    })
+}
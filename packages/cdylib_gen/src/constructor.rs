@@ -0,0 +1,60 @@
+// 加载时运行的构造函数：指向 on_load 的指针放在加载器执行的段中（ELF 的 .init_array、Mach-O 的
+// __mod_init_func、MSVC CRT 的 .CRT$XCU），库被加载时、dlopen 返回或 main 开始之前就会运行。
+// 它运行时还没有人能调用 rxc_rustlib_init，日志回调也没有设置，所以只记录一个原子变量
+// A constructor run at load time: a pointer to on_load sits in a section the loader executes
+// (.init_array for ELF, __mod_init_func for Mach-O, .CRT$XCU for the MSVC CRT), so it runs as the
+// library is loaded, before dlopen returns or main starts. Nobody can have called
+// rxc_rustlib_init by then and no log callback is set, so it only records an atomic
+//
+// 不同库的构造函数之间的顺序只由依赖关系决定：被依赖的库先运行，其他的顺序无法假定
+// The order between the constructors of different libraries only follows their dependencies:
+// a library's dependencies run first, nothing else about the order can be assumed
+
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+
+use interop_common::{ffi_guard, is_initialized, write_out, FfiStatus};
+
+static CONSTRUCTOR_RUNS: AtomicU32 = AtomicU32::new(0);
+static INITIALIZED_AT_LOAD: AtomicBool = AtomicBool::new(false);
+
+extern "C" fn on_load() {
+    INITIALIZED_AT_LOAD.store(is_initialized(), Ordering::Relaxed);
+    CONSTRUCTOR_RUNS.fetch_add(1, Ordering::Relaxed);
+}
+
+#[used]
+#[cfg_attr(
+    any(target_os = "linux", target_os = "android", target_os = "freebsd"),
+    link_section = ".init_array"
+)]
+#[cfg_attr(target_vendor = "apple", link_section = "__DATA,__mod_init_func")]
+#[cfg_attr(windows, link_section = ".CRT$XCU")]
+static ON_LOAD: extern "C" fn() = on_load;
+
+/// What the load-time constructor saw, filled in by [`cdylib_load_report`].
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct LoadReport {
+    /// How many times the constructor ran, 1 for every loaded copy of the library.
+    pub constructor_runs: u32,
+    /// Whether the library was initialized when the constructor ran, which it never is.
+    pub initialized_at_load: bool,
+}
+
+/// Writes what the load-time constructor saw into `out`; works at any time.
+///
+/// # Safety
+///
+/// `out` must be NULL or valid for writes.
+#[export_name = "rxc_cdylib_load_report"]
+pub unsafe extern "C" fn cdylib_load_report(out: *mut LoadReport) -> FfiStatus {
+    ffi_guard(|| {
+        write_out(
+            out,
+            LoadReport {
+                constructor_runs: CONSTRUCTOR_RUNS.load(Ordering::Relaxed),
+                initialized_at_load: INITIALIZED_AT_LOAD.load(Ordering::Relaxed),
+            },
+        )
+    })
+}
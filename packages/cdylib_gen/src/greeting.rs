@@ -0,0 +1,87 @@
+// 由 Rust 分配并返回字符串，调用方用 rust_free 归还
+// Strings allocated and returned by Rust, handed back by the caller through rust_free
+
+use std::ffi::{self, c_void};
+use std::ptr;
+
+use interop_common::{
+    ensure_initialized, ffi_guard, ffi_guard_or, read_cstr, write_cstr, write_out, FfiStatus,
+    FfiStr,
+};
+
+use crate::memory::{alloc_cstring, rust_free};
+
+/// Builds a greeting for the NUL-terminated UTF-8 `name`.
+///
+/// Ownership of the returned string passes to the caller, who must release it with `rxc_rust_free`
+/// (never with `free`, the memory belongs to Rust's allocator). Returns NULL
+/// on failure, with the reason available from `rxc_rustlib_last_error_message`.
+///
+/// # Safety
+///
+/// `name` must be NULL or point to a NUL-terminated string.
+#[export_name = "rxc_cdylib_make_greeting"]
+pub unsafe extern "C" fn cdylib_make_greeting(name: *const ffi::c_char) -> *mut ffi::c_char {
+    ffi_guard_or(ptr::null_mut(), || {
+        ensure_initialized()?;
+        let name = read_cstr(name, usize::MAX)?;
+        alloc_cstring(&greeting(name))
+    })
+}
+
+/// The same as `rxc_cdylib_make_greeting`, taking `name` as an `FfiStr` that doesn't need a NUL
+/// terminator, such as a slice of a longer string. Fails if `name` contains a NUL.
+///
+/// # Safety
+///
+/// `name` must view `len` readable bytes.
+#[export_name = "rxc_cdylib_make_greeting_str"]
+pub unsafe extern "C" fn cdylib_make_greeting_str(name: FfiStr) -> *mut ffi::c_char {
+    ffi_guard_or(ptr::null_mut(), || {
+        ensure_initialized()?;
+        alloc_cstring(&greeting(name.as_str()?))
+    })
+}
+
+/// Releases a string returned by `rxc_cdylib_make_greeting`. Passing NULL is a no-op.
+///
+/// The same as `rxc_rust_free`, kept for callers written before it existed.
+///
+/// # Safety
+///
+/// `s` must be NULL or a string returned by this library that has not been freed yet.
+#[export_name = "rxc_cdylib_string_free"]
+pub unsafe extern "C" fn cdylib_string_free(s: *mut ffi::c_char) {
+    rust_free(s.cast::<c_void>());
+}
+
+/// Writes the same greeting as `rxc_cdylib_make_greeting` into a caller-provided buffer.
+///
+/// This is the two-call pattern: call once with a NULL `buf` and `len == 0` to learn the length
+/// through `required_len` (the status is then `FFI_STATUS_BUFFER_TOO_SMALL`), allocate
+/// `required_len + 1` bytes and call again.
+///
+/// # Safety
+///
+/// `name` must be NULL or point to a NUL-terminated string, `buf` must be valid for writes of
+/// `len` bytes (it may be NULL when `len` is 0) and `required_len` must be NULL or valid for writes.
+#[export_name = "rxc_cdylib_greeting_message"]
+pub unsafe extern "C" fn cdylib_greeting_message(
+    name: *const ffi::c_char,
+    buf: *mut ffi::c_char,
+    len: usize,
+    required_len: *mut usize,
+) -> FfiStatus {
+    ffi_guard(|| {
+        ensure_initialized()?;
+        let greeting = greeting(read_cstr(name, usize::MAX)?);
+        if !required_len.is_null() {
+            write_out(required_len, greeting.len())?;
+        }
+        write_cstr(buf, len, &greeting).map(|_| ())
+    })
+}
+
+pub(crate) fn greeting(name: &str) -> String {
+    format!("[Rust cdylib] Hello {name}, nice to meet you!")
+}
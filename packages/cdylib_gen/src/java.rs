@@ -0,0 +1,125 @@
+// 供 Java/Kotlin 调用的 JNI 函数（jni 特性），和 C 接口共用加法与问候语的逻辑；
+// 对应的 Java 类是 android/app/src/main/java/com/example/Interop.java
+// JNI functions for Java/Kotlin (the jni feature), sharing the add and greeting logic with the C
+// interface; the matching Java class is android/app/src/main/java/com/example/Interop.java
+
+use std::ffi::c_void;
+use std::ptr;
+
+use interop_common::{ensure_initialized, ffi_guard_or, take_last_panic, FfiError};
+use jni::objects::{JClass, JString};
+use jni::sys::{jint, jstring, JavaVM, JNI_VERSION_1_6};
+use jni::JNIEnv;
+
+use crate::greeting::greeting;
+use crate::{rustlib_init, rustlib_shutdown};
+
+enum JavaError {
+    Ffi(FfiError),
+    Jni(jni::errors::Error),
+}
+
+impl From<FfiError> for JavaError {
+    fn from(err: FfiError) -> Self {
+        JavaError::Ffi(err)
+    }
+}
+
+impl From<jni::errors::Error> for JavaError {
+    fn from(err: jni::errors::Error) -> Self {
+        JavaError::Jni(err)
+    }
+}
+
+// FfiError 对应的 Java 异常类
+// The Java exception class for an FfiError
+fn exception_class(err: &FfiError) -> &'static str {
+    match err {
+        FfiError::Overflow => "java/lang/ArithmeticException",
+        FfiError::NullPointer => "java/lang/NullPointerException",
+        FfiError::NotInitialized => "java/lang/IllegalStateException",
+        _ => "java/lang/IllegalArgumentException",
+    }
+}
+
+// JNI 版本的 ffi_guard_or：错误和 panic 变成 Java 异常，调用方拿到的返回值被 JVM 忽略
+// The JNI counterpart of ffi_guard_or: errors and panics become Java exceptions, and the JVM
+// ignores the value returned alongside them
+fn java_guard<'local, T>(
+    env: &mut JNIEnv<'local>,
+    fallback: T,
+    body: impl FnOnce(&mut JNIEnv<'local>) -> Result<T, JavaError>,
+) -> T {
+    let result = ffi_guard_or(None, || {
+        Ok(Some(
+            ensure_initialized()
+                .map_err(JavaError::from)
+                .and_then(|()| body(env)),
+        ))
+    });
+    let (class, msg) = match result {
+        Some(Ok(value)) => return value,
+        // JNI 调用本身抛出的异常已经挂起，返回后由 JVM 继续抛出
+        // An exception thrown by the JNI call itself is already pending, the JVM rethrows it on return
+        Some(Err(JavaError::Jni(jni::errors::Error::JavaException))) => return fallback,
+        Some(Err(JavaError::Jni(err))) => ("java/lang/RuntimeException", err.to_string()),
+        Some(Err(JavaError::Ffi(err))) => (exception_class(&err), err.to_string()),
+        None => (
+            "java/lang/Error",
+            format!("panicked: {}", take_last_panic().unwrap_or_default()),
+        ),
+    };
+    // throw_new 只会在 JVM 已经有挂起异常时失败，这时保留那个异常即可
+    // throw_new only fails when the JVM already has a pending exception, which is kept then
+    let _ = env.throw_new(class, msg);
+    fallback
+}
+
+/// Initializes the library with the default configuration when the JVM loads it, so Java code
+/// has no `rxc_rustlib_init` to call. Returns the JNI version the library needs.
+///
+/// If native code in the same process already called `rxc_rustlib_init`, that configuration is
+/// kept.
+#[no_mangle]
+pub extern "system" fn JNI_OnLoad(_vm: *mut JavaVM, _reserved: *mut c_void) -> jint {
+    // SAFETY: NULL selects the default configuration
+    let _ = unsafe { rustlib_init(ptr::null()) };
+    JNI_VERSION_1_6
+}
+
+/// Shuts the library down when the JVM unloads it together with its class loader.
+#[no_mangle]
+pub extern "system" fn JNI_OnUnload(_vm: *mut JavaVM, _reserved: *mut c_void) {
+    rustlib_shutdown();
+}
+
+/// `static native int add(int a, int b)` of `com.example.Interop`, throws `ArithmeticException`
+/// on overflow.
+#[no_mangle]
+pub extern "system" fn Java_com_example_Interop_add<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    a: jint,
+    b: jint,
+) -> jint {
+    java_guard(&mut env, 0, |_| {
+        Ok(a.checked_add(b).ok_or(FfiError::Overflow)?)
+    })
+}
+
+/// `static native String greeting(String name)` of `com.example.Interop`, the Java version of
+/// `rxc_cdylib_make_greeting`.
+#[no_mangle]
+pub extern "system" fn Java_com_example_Interop_greeting<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    name: JString<'local>,
+) -> jstring {
+    java_guard(&mut env, ptr::null_mut(), |env| {
+        if name.is_null() {
+            return Err(FfiError::NullPointer.into());
+        }
+        let name: String = env.get_string(&name)?.into();
+        Ok(env.new_string(greeting(&name))?.into_raw())
+    })
+}
@@ -0,0 +1,44 @@
+// 最近一次错误的查询接口，状态码之外给 C 调用方提供可读的诊断信息
+// Last-error query API, giving C callers readable diagnostics beyond the status code
+
+use std::ffi;
+
+use interop_common::{copy_last_error, last_error_length, take_last_panic_into, FfiStatus};
+
+/// Returns the buffer size (including the NUL) needed for the calling thread's last error
+/// message, or 0 if no call on this thread has failed yet.
+#[export_name = "rxc_rustlib_last_error_length"]
+pub extern "C" fn rustlib_last_error_length() -> usize {
+    last_error_length()
+}
+
+/// Copies the calling thread's last error message into `buf` like `snprintf` does.
+///
+/// An empty string is written when there is no error. Reading does not clear the error.
+///
+/// # Safety
+///
+/// `buf` must be valid for writes of `len` bytes (it may be NULL when `len` is 0).
+#[export_name = "rxc_rustlib_last_error_message"]
+pub unsafe extern "C" fn rustlib_last_error_message(
+    buf: *mut ffi::c_char,
+    len: usize,
+) -> FfiStatus {
+    copy_last_error(buf, len).into()
+}
+
+/// Copies the message and location of the calling thread's last caught panic into `buf` like
+/// `snprintf` does, and returns the full message length (excluding the NUL).
+///
+/// Returns 0 when no panic is recorded. The message is only cleared once it was copied in full,
+/// so after a truncated read the call can be repeated with a buffer of the returned length plus
+/// one; a NULL `buf` with `len == 0` only queries the length. Returns -1 if `buf` is NULL while
+/// `len` is not 0.
+///
+/// # Safety
+///
+/// `buf` must be valid for writes of `len` bytes (it may be NULL when `len` is 0).
+#[export_name = "rxc_rustlib_take_last_panic"]
+pub unsafe extern "C" fn rustlib_take_last_panic(buf: *mut ffi::c_char, len: usize) -> ffi::c_int {
+    take_last_panic_into(buf, len)
+}
@@ -1,19 +1,149 @@
 use std::ffi;
-use std::ptr;
 
-#[no_mangle]
-pub extern "C" fn cdylib_add(a: ffi::c_int, b: ffi::c_int, result: *mut ffi::c_char) -> ffi::c_int {
-    let sum = a + b;
-    unsafe {
-        let name = ffi::CStr::from_ptr(result).to_str().unwrap();
+mod addition;
+mod allocator;
+mod array;
+mod calculator;
+mod callback;
+mod cancel;
+mod config;
+mod constructor;
+mod greeting;
+#[cfg(feature = "jni")]
+mod java;
+mod last_error;
+mod lifecycle;
+mod logging;
+mod memory;
+mod option;
+mod path;
+mod pool;
+mod progress;
+mod protocol;
+mod queue;
+mod runtime;
+mod scalar;
+#[cfg(all(windows, target_arch = "x86"))]
+mod stdcall;
+mod strings;
+mod trace;
+mod version;
+mod versioned;
+mod wide;
+mod workers;
 
-        println!("[Rust cdylib] Hello {name}");
+pub use addition::{addition, hello, Addition};
+pub use allocator::rustlib_alloc_stats;
+pub use array::{cdylib_range, cdylib_sum, cdylib_sum_slice};
+pub use calculator::{calc_free, calc_new, Calculator, CalculatorHandle};
+pub use callback::{cdylib_add_async, AddCallback};
+pub use cancel::{
+    cancel_token_cancel, cancel_token_free, cancel_token_new, cdylib_slow_sum, CancelToken,
+    CancelTokenHandle,
+};
+pub use config::{
+    config_buffer_size, config_build, config_builder_free, config_free, config_name, config_new,
+    config_set_buffer_size, config_set_name, Config, ConfigBuilder, ConfigBuilderHandle,
+    ConfigHandle, CONFIG_DEFAULT_BUFFER_SIZE, CONFIG_MAX_BUFFER_SIZE, CONFIG_MIN_BUFFER_SIZE,
+};
+pub use constructor::{cdylib_load_report, LoadReport};
+pub use greeting::{
+    cdylib_greeting_message, cdylib_make_greeting, cdylib_make_greeting_str, cdylib_string_free,
+};
+pub use lifecycle::{rustlib_init, rustlib_shutdown};
+pub use memory::{rust_alloc, rust_free, rust_realloc, rust_string_array_free};
+pub use option::{Transform, TransformUnwind};
+pub use path::{cdylib_read_file, PathChar};
+pub use pool::{
+    pool_create, pool_free, pool_join, pool_submit, PoolDone, PoolJob, ThreadPool, ThreadPoolHandle,
+};
+pub use progress::{
+    cdylib_sum_with_progress, cdylib_sum_with_progress_unwind, ProgressCallback,
+    ProgressCallbackUnwind,
+};
+pub use protocol::{
+    cdylib_header_init, cdylib_header_parse, MessageHeader, MESSAGE_HEADER_MAGIC,
+    MESSAGE_HEADER_SIZE, MESSAGE_HEADER_VERSION,
+};
+pub use queue::{queue_free, queue_new, queue_push, Queue, QueueCallback, QueueHandle};
+pub use runtime::{async_block_on, async_submit, AsyncCallback, AsyncRequest};
+pub use scalar::{cdylib_align_up, cdylib_is_even, cdylib_lerp, cdylib_not};
+#[cfg(all(windows, target_arch = "x86"))]
+pub use stdcall::{cdylib_add_stdcall, cdylib_apply_stdcall, TransformStdcall};
+pub use strings::{cdylib_join_strings, cdylib_split_words};
+pub use trace::{
+    cdylib_set_trace_callback, TraceCallback, TraceField, TraceKind, TraceRecord, TraceStr,
+};
+pub use version::{cdylib_abi_version, Version, CDYLIB_ABI_VERSION, CDYLIB_GIT_HASH_LEN};
+pub use versioned::{cdylib_add_v1, cdylib_add_v2};
+pub use wide::cdylib_add_w;
 
-        let msg = format!("[Rust cdylib] The result ({a} + {b}) is {sum}!");
+use interop_common::{ensure_initialized, ffi_guard, read_cstr, write_cstr, write_out, FfiError};
+pub use interop_common::{
+    AllocStats, FfiHandle, FfiSlice, FfiStatus, FfiStr, InitConfig, LogCallback, LogLevel,
+};
 
-        let msg = ffi::CString::new(msg).unwrap();
+/// Adds `a` and `b`, stores the sum in `sum` and writes a greeting message into `result`.
+///
+/// `result` is an in/out buffer of `result_len` bytes: on input it holds the caller's
+/// NUL-terminated name, on output the message. Like `snprintf`, the message is truncated to
+/// fit and always NUL terminated; if `required_len` is not NULL it receives the length the full
+/// message needs (excluding the NUL). Passing a NULL `result` with `result_len == 0` only
+/// queries that length.
+///
+/// Safe to call from several threads at once as long as each call has its own buffers: besides
+/// them it only touches the calling thread's last error.
+///
+/// # Safety
+///
+/// `result` must be valid for reads and writes of `result_len` bytes (it may be NULL when
+/// `result_len` is 0), `sum` must be valid for writes and `required_len` must be NULL or
+/// valid for writes.
+#[export_name = "rxc_cdylib_add"]
+pub unsafe extern "C" fn cdylib_add(
+    a: ffi::c_int,
+    b: ffi::c_int,
+    result: *mut ffi::c_char,
+    result_len: usize,
+    sum: *mut ffi::c_int,
+    required_len: *mut usize,
+) -> FfiStatus {
+    let _span = tracing::info_span!("rxc_cdylib_add", a, b).entered();
+    let status = ffi_guard(|| {
+        ensure_initialized()?;
+        add(a, b, result, result_len, sum, required_len)
+    });
+    tracing::debug!(?status, "rxc_cdylib_add returned");
+    status
+}
+
+unsafe fn add(
+    a: ffi::c_int,
+    b: ffi::c_int,
+    result: *mut ffi::c_char,
+    result_len: usize,
+    sum: *mut ffi::c_int,
+    required_len: *mut usize,
+) -> Result<(), FfiError> {
+    let addition = addition(a, b)?;
+    let name = if result.is_null() && result_len == 0 {
+        ""
+    } else {
+        read_cstr(result, result_len)?
+    };
+
+    // 名字在缓冲区里，写入消息之前先生成问候；只有消息写进去之后才打印，
+    // 调用方扩大缓冲区重试时问候不会出现两次
+    // The name lives in the buffer, so the greeting is built before the message overwrites it; it
+    // is only logged once the message fits, so a caller retrying with a larger buffer doesn't see
+    // it twice
+    let greeting = hello(name);
 
-        ptr::copy_nonoverlapping(msg.as_ptr(), result, msg.as_bytes().len() + 1);
-        return sum;
+    write_out(sum, addition.sum)?;
+    if !required_len.is_null() {
+        write_out(required_len, addition.message.len())?;
     }
+    write_cstr(result, result_len, &addition.message)?;
+    log::info!("{greeting}");
+    Ok(())
 }
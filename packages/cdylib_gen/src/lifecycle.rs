@@ -0,0 +1,80 @@
+// 库的初始化和关闭：其他返回状态码的导出函数要在 rustlib_init 之后才能使用
+// Initializing and shutting down the library: the other exports returning a status can only be
+// used after rustlib_init
+
+use std::sync::Mutex;
+
+use interop_common::{ffi_guard, init, is_initialized, shutdown, FfiError, FfiStatus, InitConfig};
+
+use crate::{allocator, runtime, workers};
+
+// 让工作线程的启动和回收与初始化状态一起切换
+// Makes starting and joining the worker threads switch together with the initialization state
+static LIFECYCLE: Mutex<()> = Mutex::new(());
+
+/// Initializes the library with `config`, or with the default configuration when `config` is
+/// NULL, and must be called before any other export returning a status.
+///
+/// Sets up logging, the worker threads for `rxc_cdylib_add_async`, the async runtime for
+/// `rxc_async_submit` and, if requested, the panic hook once; Rust's global allocator is fixed when
+/// the library is linked, so there is nothing to set up for it. With `leak_check` set, the
+/// allocations from here on are checked at shutdown. Until this succeeds the other exports fail
+/// with `FFI_STATUS_NOT_INITIALIZED` (or return NULL), while the ABI and version queries, the last
+/// error accessors and the log and trace callback setters work at any time. Calling it again before
+/// `rxc_rustlib_shutdown` fails with `FFI_STATUS_ALREADY_INITIALIZED` and keeps the first
+/// configuration.
+///
+/// Only the `size` bytes `config` declares are read, so callers compiled against the version 1
+/// `InitConfig` keep working and get the defaults for the newer fields; a size of no known
+/// version fails with `FFI_STATUS_UNSUPPORTED_VERSION`.
+///
+/// # Safety
+///
+/// `config` must be NULL or point to an `InitConfig` of the version its `size` declares.
+#[export_name = "rxc_rustlib_init"]
+pub unsafe extern "C" fn rustlib_init(config: *const InitConfig) -> FfiStatus {
+    ffi_guard(|| {
+        let config = InitConfig::read(config)?;
+        let _lifecycle = LIFECYCLE.lock().unwrap_or_else(|err| err.into_inner());
+        // 已经初始化时不能重新开始泄漏检查
+        // The leak check must not start over when the library already is initialized
+        if is_initialized() {
+            return Err(FfiError::AlreadyInitialized);
+        }
+        allocator::begin_leak_check(config.leak_check);
+        // 先启动工作线程，其他线程一看到已初始化就能提交任务
+        // The workers start first, so other threads can submit jobs as soon as they see the
+        // library initialized
+        workers::start(config.worker_threads);
+        runtime::start(config.worker_threads);
+        init(&config)
+    })
+}
+
+/// Shuts the library down again, after which the other exports fail with
+/// `FFI_STATUS_NOT_INITIALIZED` until `rxc_rustlib_init` is called again.
+///
+/// Waits for the pending `rxc_cdylib_add_async` and `rxc_async_submit` callbacks to run, so called
+/// from one of them it fails with `FFI_STATUS_WOULD_DEADLOCK` and leaves the library initialized,
+/// and restores writing log messages to stderr. Calls running on
+/// other threads at the same time finish normally, the log callback's `user_data` must stay valid
+/// until they return. Calculators and strings the library returned can still be freed afterwards.
+///
+/// With the `leak_check` of the `InitConfig` given to `rxc_rustlib_init` set, it finally logs how
+/// many allocations made since then are still outstanding, as a warning to stderr, and records
+/// them for `rxc_rustlib_alloc_stats`.
+#[export_name = "rxc_rustlib_shutdown"]
+pub extern "C" fn rustlib_shutdown() -> FfiStatus {
+    ffi_guard(|| {
+        // 在关闭任何东西之前检查，失败时库保持原样
+        // Checked before anything is shut down, so a failure leaves the library as it was
+        runtime::ensure_outside()?;
+        workers::ensure_outside()?;
+        let _lifecycle = LIFECYCLE.lock().unwrap_or_else(|err| err.into_inner());
+        shutdown()?;
+        workers::stop();
+        runtime::stop();
+        allocator::finish_leak_check();
+        Ok(())
+    })
+}
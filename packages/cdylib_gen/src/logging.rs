@@ -0,0 +1,17 @@
+// 日志回调的注册接口，让嵌入方决定库的输出去向，而不是直接写到 stdout
+// Log callback registration, letting embedders decide where the library's output goes instead
+// of it being written to stdout
+
+use std::ffi::c_void;
+
+use interop_common::{set_log_callback, LogCallback};
+
+/// Routes the library's log messages to `callback`, which receives each message with its level
+/// and `user_data`; passing NULL restores the default of writing them to stderr.
+///
+/// The callback may be invoked from any thread the library logs on. `user_data` must stay valid
+/// until another callback is registered.
+#[export_name = "rxc_rustlib_set_log_callback"]
+pub extern "C" fn rustlib_set_log_callback(callback: LogCallback, user_data: *mut c_void) {
+    set_log_callback(callback, user_data);
+}
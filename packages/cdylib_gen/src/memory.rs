@@ -0,0 +1,265 @@
+// 导出 Rust 的分配器：C 端通过 rust_alloc/rust_realloc/rust_free 分配和释放跨边界传递的缓冲区，
+// 库返回的字符串也用 rust_free 释放；每块内存前面记着大小和对齐，所以释放时不需要调用方提供
+// Rust's allocator, exported: C allocates and frees buffers crossing the boundary through
+// rust_alloc/rust_realloc/rust_free, and strings the library returns are released with rust_free
+// too; the size and alignment are recorded in front of every block, so freeing doesn't need them
+// from the caller
+//
+// Windows 上每个 DLL 可能链接自己的 C 运行时，用另一边的 free 释放内存会破坏堆；
+// 由分配方提供释放函数可以完全避开这个问题
+// On Windows every DLL may link its own C runtime, and freeing memory with the other side's free
+// corrupts the heap; letting the allocating side provide the free function sidesteps that entirely
+//
+// 开启 malloc 特性后这三个函数直接转发到 malloc/realloc/free，不再有头部，
+// 所以它们返回的内存和 malloc 的内存可以互换
+// With the malloc feature on the three functions forward straight to malloc/realloc/free without
+// a header, so their memory and malloc's are interchangeable
+
+use std::ffi::{c_char, c_void};
+use std::mem::{align_of, size_of};
+use std::ptr;
+
+use interop_common::{ffi_guard_or, FfiError};
+
+/// The alignment `rust_realloc(NULL, size)` uses, matching what `malloc` guarantees.
+const DEFAULT_ALIGN: usize = 2 * align_of::<usize>();
+
+fn check_align(align: usize) -> Result<(), FfiError> {
+    if align.is_power_of_two() {
+        Ok(())
+    } else {
+        Err(FfiError::InvalidLayout)
+    }
+}
+
+#[cfg(not(feature = "malloc"))]
+mod heap {
+    use std::alloc::{self, Layout};
+    use std::ffi::c_void;
+    use std::mem::{align_of, size_of};
+    use std::ptr::NonNull;
+
+    use interop_common::FfiError;
+
+    // 放在返回给调用方的指针前面
+    // Stored right in front of the pointer handed to the caller
+    #[derive(Clone, Copy)]
+    struct Header {
+        size: usize,
+        align: usize,
+    }
+
+    impl Header {
+        // 整块内存的布局，以及调用方的指针距离块开头的偏移；偏移是对齐的倍数，所以指针本身也是对齐的
+        // The layout of the whole block and the offset of the caller's pointer from its start; the
+        // offset is a multiple of the alignment, so the pointer is aligned as well
+        fn layout(self) -> Result<(Layout, usize), FfiError> {
+            let align = self.align.max(align_of::<Header>());
+            let offset = size_of::<Header>().next_multiple_of(align);
+            let size = offset
+                .checked_add(self.size)
+                .ok_or(FfiError::InvalidLayout)?;
+            let layout =
+                Layout::from_size_align(size, align).map_err(|_| FfiError::InvalidLayout)?;
+            Ok((layout, offset))
+        }
+    }
+
+    // 在块开头之后 offset 处写入头部，返回调用方的指针
+    // Writes the header at offset from the block's start and returns the caller's pointer
+    unsafe fn finish(
+        block: *mut u8,
+        offset: usize,
+        header: Header,
+    ) -> Result<*mut c_void, FfiError> {
+        let block = NonNull::new(block).ok_or(FfiError::OutOfMemory)?;
+        let user = block.as_ptr().add(offset);
+        user.cast::<Header>().sub(1).write(header);
+        Ok(user.cast())
+    }
+
+    unsafe fn header(ptr: *mut c_void) -> Header {
+        ptr.cast::<Header>().sub(1).read()
+    }
+
+    pub(super) fn allocate(size: usize, align: usize) -> Result<*mut c_void, FfiError> {
+        let header = Header { size, align };
+        let (layout, offset) = header.layout()?;
+        unsafe { finish(alloc::alloc(layout), offset, header) }
+    }
+
+    pub(super) unsafe fn reallocate(
+        ptr: *mut c_void,
+        new_size: usize,
+    ) -> Result<*mut c_void, FfiError> {
+        let old = header(ptr);
+        let new = Header {
+            size: new_size,
+            align: old.align,
+        };
+        let (old_layout, offset) = old.layout()?;
+        let (new_layout, _) = new.layout()?;
+        let block = ptr.cast::<u8>().sub(offset);
+        finish(
+            alloc::realloc(block, old_layout, new_layout.size()),
+            offset,
+            new,
+        )
+    }
+
+    pub(super) unsafe fn release(ptr: *mut c_void) {
+        let header = header(ptr);
+        // 头部是分配时写入的，布局在那时已经验证过
+        // The header was written at allocation time, when its layout was already validated
+        if let Ok((layout, offset)) = header.layout() {
+            alloc::dealloc(ptr.cast::<u8>().sub(offset), layout);
+        }
+    }
+}
+
+#[cfg(feature = "malloc")]
+mod heap {
+    use std::alloc::Layout;
+    use std::ffi::c_void;
+
+    use interop_common::{free_aligned, malloc_aligned, realloc_small, FfiError, MALLOC_ALIGN};
+
+    pub(super) fn allocate(size: usize, align: usize) -> Result<*mut c_void, FfiError> {
+        let layout = Layout::from_size_align(size, align).map_err(|_| FfiError::InvalidLayout)?;
+        // Windows 的对齐分配要用 _aligned_free 释放，free 认不出来
+        // Aligned allocations on Windows need _aligned_free, free doesn't recognize them
+        if cfg!(windows) && align > MALLOC_ALIGN {
+            return Err(FfiError::InvalidLayout);
+        }
+        let ptr = unsafe { malloc_aligned(layout) };
+        if ptr.is_null() {
+            Err(FfiError::OutOfMemory)
+        } else {
+            Ok(ptr.cast())
+        }
+    }
+
+    pub(super) unsafe fn reallocate(
+        ptr: *mut c_void,
+        new_size: usize,
+    ) -> Result<*mut c_void, FfiError> {
+        let new = realloc_small(ptr.cast(), new_size);
+        if new.is_null() {
+            Err(FfiError::OutOfMemory)
+        } else {
+            Ok(new.cast())
+        }
+    }
+
+    pub(super) unsafe fn release(ptr: *mut c_void) {
+        free_aligned(ptr.cast(), MALLOC_ALIGN);
+    }
+}
+
+/// Allocates `size` bytes aligned to `align` with Rust's allocator, to be released with
+/// `rxc_rust_free`. The contents are uninitialized.
+///
+/// A `size` of 0 still returns a unique pointer that must be freed. Returns NULL if `align` is
+/// not a power of two, the size is too large or the allocator is out of memory, with the reason
+/// available from `rxc_rustlib_last_error_message`. Works before `rxc_rustlib_init`.
+///
+/// With the `malloc` feature the block comes from `malloc` (or `posix_memalign`) and may be
+/// released with `free` as well; on Windows alignments above `malloc`'s are then rejected.
+#[export_name = "rxc_rust_alloc"]
+pub extern "C" fn rust_alloc(size: usize, align: usize) -> *mut c_void {
+    ffi_guard_or(ptr::null_mut(), || {
+        check_align(align)?;
+        heap::allocate(size, align)
+    })
+}
+
+/// Resizes a block from `rxc_rust_alloc` to `new_size` bytes, keeping its alignment and the
+/// contents up to the smaller of the two sizes, like `realloc`.
+///
+/// A NULL `ptr` allocates a new block aligned like `malloc` would. On failure NULL is returned
+/// and the original block is left untouched, so it must still be freed. With the `malloc`
+/// feature this is `realloc` itself, which only keeps alignments up to `malloc`'s.
+///
+/// # Safety
+///
+/// `ptr` must be NULL or come from `rxc_rust_alloc` or `rxc_rust_realloc` and not have been freed;
+/// on success it must not be used again.
+#[export_name = "rxc_rust_realloc"]
+pub unsafe extern "C" fn rust_realloc(ptr: *mut c_void, new_size: usize) -> *mut c_void {
+    if ptr.is_null() {
+        return rust_alloc(new_size, DEFAULT_ALIGN);
+    }
+    ffi_guard_or(ptr::null_mut(), || heap::reallocate(ptr, new_size))
+}
+
+/// Releases memory from `rxc_rust_alloc` or `rxc_rust_realloc`, and every string this library
+/// returns, such as the one from `rxc_cdylib_make_greeting`. Passing NULL is a no-op. Works at any
+/// time.
+///
+/// This is the only correct way to release them: `free` belongs to a different allocator and,
+/// on Windows, possibly to a different C runtime. The `malloc` feature lifts that restriction
+/// for C code sharing the library's C runtime, and lets `rxc_rust_free` release `malloc`'s memory.
+///
+/// # Safety
+///
+/// `ptr` must be NULL or come from one of those functions, and must not be used again afterwards.
+#[export_name = "rxc_rust_free"]
+pub unsafe extern "C" fn rust_free(ptr: *mut c_void) {
+    if !ptr.is_null() {
+        heap::release(ptr);
+    }
+}
+
+/// Copies `s` and a NUL terminator into a block that [`rust_free`] releases, for strings handed
+/// to C.
+pub(crate) fn alloc_cstring(s: &str) -> Result<*mut c_char, FfiError> {
+    if s.as_bytes().contains(&0) {
+        return Err(FfiError::InteriorNul);
+    }
+    let ptr = heap::allocate(s.len() + 1, 1)?.cast::<u8>();
+    unsafe {
+        ptr::copy_nonoverlapping(s.as_ptr(), ptr, s.len());
+        ptr.add(s.len()).write(0);
+    }
+    Ok(ptr.cast())
+}
+
+/// Releases an array of strings this library returns, such as the one from
+/// `rxc_cdylib_split_words`, together with the `len` strings in it. Passing NULL is a no-op.
+///
+/// # Safety
+///
+/// `array` must be NULL or an array of `len` strings returned by this library that has not been
+/// freed yet, and neither it nor its strings may be used again afterwards.
+#[export_name = "rxc_rust_string_array_free"]
+pub unsafe extern "C" fn rust_string_array_free(array: *mut *mut c_char, len: usize) {
+    if array.is_null() {
+        return;
+    }
+    for i in 0..len {
+        rust_free(array.add(i).read().cast());
+    }
+    rust_free(array.cast());
+}
+
+/// Copies `strings` into a NULL-terminated array of [`alloc_cstring`] strings, the way `argv` is
+/// laid out, that [`rust_string_array_free`] releases.
+pub(crate) fn alloc_string_array(strings: &[&str]) -> Result<*mut *mut c_char, FfiError> {
+    let size = (strings.len() + 1)
+        .checked_mul(size_of::<*mut c_char>())
+        .ok_or(FfiError::InvalidLayout)?;
+    let array = heap::allocate(size, align_of::<*mut c_char>())?.cast::<*mut c_char>();
+    for (i, s) in strings.iter().enumerate() {
+        match alloc_cstring(s) {
+            Ok(s) => unsafe { array.add(i).write(s) },
+            Err(err) => {
+                // 只释放已经复制好的 i 个字符串
+                // Only the i strings already copied are released
+                unsafe { rust_string_array_free(array, i) };
+                return Err(err);
+            }
+        }
+    }
+    unsafe { array.add(strings.len()).write(ptr::null_mut()) };
+    Ok(array)
+}
@@ -0,0 +1,80 @@
+// 可空指针作为 Option：Option<&T> 和 Option<extern "C" fn> 与 C 的指针布局相同，NULL 即 None
+// Nullable pointers as Option: Option<&T> and Option<extern "C" fn> share the layout of a C
+// pointer, with NULL as None
+
+use std::ffi;
+
+/// A transform applied by `rxc_cdylib_apply`; NULL means "leave the value unchanged".
+pub type Transform = Option<extern "C" fn(value: ffi::c_int) -> ffi::c_int>;
+
+/// Applies `transform` to `value`, or returns `value` unchanged when `transform` is NULL.
+#[export_name = "rxc_cdylib_apply"]
+pub extern "C" fn cdylib_apply(value: ffi::c_int, transform: Transform) -> ffi::c_int {
+    match transform {
+        Some(f) => f(value),
+        None => value,
+    }
+}
+
+/// A transform applied by `rxc_cdylib_apply_unwind`, which may unwind (for example a C++ function
+/// that throws); NULL means "leave the value unchanged".
+pub type TransformUnwind = Option<unsafe extern "C-unwind" fn(value: ffi::c_int) -> ffi::c_int>;
+
+// 展开时也会被丢弃，用来展示异常穿过 Rust 时析构函数照常运行
+// Also dropped while unwinding, showing that destructors run when an exception passes through Rust
+struct Cleanup;
+
+impl Drop for Cleanup {
+    fn drop(&mut self) {
+        log::info!("[Rust cdylib] rxc_cdylib_apply_unwind cleaned up");
+    }
+}
+
+/// Like `rxc_cdylib_apply`, but an exception thrown by `transform` unwinds through Rust back to the
+/// caller, running Rust's destructors on the way.
+///
+/// An exception must never escape a `Transform` passed to `rxc_cdylib_apply`: Rust assumes that
+/// `extern "C"` calls do not unwind, so that is undefined behavior.
+///
+/// # Safety
+///
+/// `transform` must be NULL or a function that either returns or unwinds with an exception the
+/// caller of `rxc_cdylib_apply_unwind` catches.
+#[export_name = "rxc_cdylib_apply_unwind"]
+pub unsafe extern "C-unwind" fn cdylib_apply_unwind(
+    value: ffi::c_int,
+    transform: TransformUnwind,
+) -> ffi::c_int {
+    let _cleanup = Cleanup;
+    match transform {
+        Some(f) => f(value),
+        None => value,
+    }
+}
+
+/// Adds the values behind `a` and `b`, using `fallback` for an operand that is NULL.
+///
+/// The sum wraps around on overflow.
+#[export_name = "rxc_cdylib_add_or_default"]
+pub extern "C" fn cdylib_add_or_default(
+    a: Option<&ffi::c_int>,
+    b: Option<&ffi::c_int>,
+    fallback: ffi::c_int,
+) -> ffi::c_int {
+    a.copied()
+        .unwrap_or(fallback)
+        .wrapping_add(b.copied().unwrap_or(fallback))
+}
+
+/// Increments the counter behind `counter` and returns its new value, or returns -1 without
+/// doing anything when `counter` is NULL.
+#[export_name = "rxc_cdylib_increment"]
+pub extern "C" fn cdylib_increment(counter: Option<&mut ffi::c_int>) -> ffi::c_int {
+    match counter {
+        Some(counter) => {
+            *counter = counter.wrapping_add(1);
+            *counter
+        }
+        None => -1,
+    }
+}
@@ -0,0 +1,88 @@
+// 路径以平台原生的编码跨越 FFI 边界：Unix 上的文件名是除 NUL 以外的任意字节，Windows 上是可能含有不成对
+// 代理项的 UTF-16，两者都不一定是合法的 UTF-8。CStr -> &str 会拒绝这些合法的文件名，to_string_lossy 则
+// 悄悄把它们换成另一个文件的名字；OsStrExt 在原生编码和 OsStr 之间原样转换，不会丢失任何东西
+// Paths cross the FFI boundary in the platform's native encoding: a file name on Unix is any bytes
+// but NUL and on Windows UTF-16 that may contain unpaired surrogates, and neither has to be valid
+// UTF-8. CStr -> &str rejects such valid file names and to_string_lossy quietly turns them into
+// the name of a different file; OsStrExt converts between the native encoding and OsStr as is,
+// losing nothing
+
+use std::ffi;
+use std::fs;
+use std::path::PathBuf;
+
+use interop_common::{
+    check_ptr, ensure_initialized, ffi_guard, slice_from_raw_mut, write_out, FfiError, FfiStatus,
+};
+
+/// A code unit of a native path: a `char` of bytes on Unix.
+#[cfg(not(windows))]
+pub type PathChar = ffi::c_char;
+
+/// A code unit of a native path: a UTF-16 `wchar_t` on Windows.
+#[cfg(windows)]
+pub type PathChar = u16;
+
+#[cfg(unix)]
+unsafe fn native_path(path: *const PathChar) -> Result<PathBuf, FfiError> {
+    use std::ffi::{CStr, OsStr};
+    use std::os::unix::ffi::OsStrExt;
+
+    check_ptr(path)?;
+    Ok(PathBuf::from(OsStr::from_bytes(
+        CStr::from_ptr(path).to_bytes(),
+    )))
+}
+
+#[cfg(windows)]
+unsafe fn native_path(path: *const PathChar) -> Result<PathBuf, FfiError> {
+    use std::ffi::OsString;
+    use std::os::windows::ffi::OsStringExt;
+    use std::slice;
+
+    check_ptr(path)?;
+    let mut len = 0;
+    while *path.add(len) != 0 {
+        len += 1;
+    }
+    Ok(PathBuf::from(OsString::from_wide(slice::from_raw_parts(
+        path, len,
+    ))))
+}
+
+/// Reads the file at `path` into `out`, writing its full size to `file_len`.
+///
+/// `path` is NUL terminated and in the platform's native encoding: bytes on Unix, UTF-16 on
+/// Windows, so the `wchar_t` paths `_wfopen` and the `W` functions take are passed as is. Neither
+/// has to be UTF-8. The contents are truncated to fit `out` with `FFI_STATUS_BUFFER_TOO_SMALL`, and a
+/// failure to read the file returns `FFI_STATUS_IO` with the OS error code in the last error.
+///
+/// # Safety
+///
+/// `path` must point to a NUL-terminated native path, `out` must be valid for writes of `len` bytes
+/// (it may be NULL when `len` is 0) and `file_len` must be NULL or valid for writes.
+#[export_name = "rxc_cdylib_read_file"]
+pub unsafe extern "C" fn cdylib_read_file(
+    path: *const PathChar,
+    out: *mut u8,
+    len: usize,
+    file_len: *mut usize,
+) -> FfiStatus {
+    ffi_guard(|| {
+        ensure_initialized()?;
+        let contents = fs::read(native_path(path)?)?;
+        if !file_len.is_null() {
+            write_out(file_len, contents.len())?;
+        }
+        let out = slice_from_raw_mut(out, len)?;
+        let n = contents.len().min(out.len());
+        out[..n].copy_from_slice(&contents[..n]);
+        if n < contents.len() {
+            Err(FfiError::BufferTooSmall {
+                required: contents.len(),
+            })
+        } else {
+            Ok(())
+        }
+    })
+}
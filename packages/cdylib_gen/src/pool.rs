@@ -0,0 +1,117 @@
+// 给 C 用的线程池：任务函数在 Rust 管理的线程上运行，结果通过完成回调交还给调用方
+// A thread pool for C: job functions run on threads managed by Rust and their results are handed
+// back through a completion callback
+
+use std::ffi::{c_int, c_void};
+use std::num::NonZeroUsize;
+use std::thread;
+
+use interop_common::{
+    ensure_initialized, ffi_guard, ffi_guard_or, FfiError, FfiHandle, FfiStatus, HandleRegistry,
+};
+
+use crate::workers::{UserData, WorkerPool};
+
+/// A job for `rxc_pool_submit`, receiving the caller's `user_data` and returning a result for the
+/// completion callback.
+pub type PoolJob = Option<extern "C" fn(user_data: *mut c_void) -> c_int>;
+
+/// Completion callback for `rxc_pool_submit`, receiving the job's result and the same `user_data`.
+pub type PoolDone = Option<extern "C" fn(result: c_int, user_data: *mut c_void)>;
+
+/// A pool of worker threads running C jobs.
+///
+/// The layout is private to Rust; C code only handles the `ThreadPoolHandle` obtained from
+/// `rxc_pool_create`. Jobs may be submitted from several threads at once.
+pub struct ThreadPool {
+    workers: WorkerPool,
+}
+
+/// A pool handed to C, the null handle when `rxc_pool_create` failed.
+pub type ThreadPoolHandle = FfiHandle;
+
+static POOLS: HandleRegistry<ThreadPool> = HandleRegistry::new();
+
+/// Creates a pool with `threads` worker threads, or one per CPU when `threads` is 0. The handle
+/// must be released with `rxc_pool_free`.
+///
+/// Returns the null handle if the library is not initialized.
+#[export_name = "rxc_pool_create"]
+pub extern "C" fn pool_create(threads: u32) -> ThreadPoolHandle {
+    ffi_guard_or(FfiHandle::NULL, || {
+        ensure_initialized()?;
+        let threads = match threads {
+            0 => thread::available_parallelism().map_or(1, NonZeroUsize::get) as u32,
+            n => n,
+        };
+        log::debug!("[Rust cdylib] Starting a pool of {threads} threads");
+        Ok(POOLS.insert(ThreadPool {
+            workers: WorkerPool::new("cdylib_gen pool", threads),
+        }))
+    })
+}
+
+/// Queues `job` to run with `user_data` on one of the pool's threads.
+///
+/// Once it returns, `done` (which may be NULL) is invoked on the same thread with the job's result
+/// and `user_data`. Jobs start in the order they were submitted but may finish in any order;
+/// `user_data` must stay valid until `done` has run, at the latest by `rxc_pool_join` or
+/// `rxc_pool_free`. Fails with `FFI_STATUS_INVALID_HANDLE` when `pool` was freed or never came from
+/// `rxc_pool_create`.
+#[export_name = "rxc_pool_submit"]
+pub extern "C" fn pool_submit(
+    pool: ThreadPoolHandle,
+    job: PoolJob,
+    done: PoolDone,
+    user_data: *mut c_void,
+) -> FfiStatus {
+    ffi_guard(|| {
+        ensure_initialized()?;
+        let pool = POOLS.get(pool)?;
+        let job = job.ok_or(FfiError::NullPointer)?;
+        let user_data = UserData(user_data);
+
+        pool.workers.submit(move || {
+            let user_data = user_data;
+            let result = job(user_data.0);
+            if let Some(done) = done {
+                done(result, user_data.0);
+            }
+        });
+        Ok(())
+    })
+}
+
+/// Blocks until every job submitted so far and its completion callback have run; the pool can be
+/// used again afterwards.
+///
+/// Called from one of the pool's own jobs or completion callbacks, which would wait for itself, it
+/// fails with `FFI_STATUS_WOULD_DEADLOCK`.
+#[export_name = "rxc_pool_join"]
+pub extern "C" fn pool_join(pool: ThreadPoolHandle) -> FfiStatus {
+    ffi_guard(|| {
+        let pool = POOLS.get(pool)?;
+        pool.workers.ensure_outside()?;
+        pool.workers.join();
+        Ok(())
+    })
+}
+
+/// Runs the jobs still queued, stops the pool's threads and destroys it. Passing the null handle
+/// is a no-op.
+///
+/// Like `rxc_pool_join` it fails with `FFI_STATUS_WOULD_DEADLOCK` from one of the pool's own jobs,
+/// leaving the pool as it was. Freeing a handle twice, or one that never came from
+/// `rxc_pool_create`, fails with `FFI_STATUS_INVALID_HANDLE`.
+#[export_name = "rxc_pool_free"]
+pub extern "C" fn pool_free(pool: ThreadPoolHandle) -> FfiStatus {
+    ffi_guard(|| {
+        if !pool.is_null() {
+            // 先检查再移除，被拒绝时句柄仍然有效
+            // Checked before removing, so the handle stays valid when refused
+            POOLS.get(pool)?.workers.ensure_outside()?;
+            drop(POOLS.remove(pool)?);
+        }
+        Ok(())
+    })
+}
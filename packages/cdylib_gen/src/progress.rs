@@ -0,0 +1,121 @@
+// 进度回调：计算过程中把完成的百分比报告给 C，回调返回非零值时提前结束并返回 CANCELLED；
+// _unwind 版本允许回调中的 panic 或异常展开回调用方
+// Progress callbacks: the computation reports the percentage done to C as it goes, and stops
+// early with CANCELLED when the callback returns non-zero; the _unwind version lets a panic or
+// exception in the callback unwind back to the caller
+
+use std::ffi::{self, c_void};
+
+use interop_common::{
+    ensure_initialized, ffi_guard, set_last_error, slice_from_raw, write_out, FfiError, FfiStatus,
+};
+
+/// Progress callback for `rxc_cdylib_sum_with_progress`, receiving the percentage done (0 to 100)
+/// and the caller's `user_data`; returning non-zero aborts the computation.
+pub type ProgressCallback =
+    Option<extern "C" fn(percent: u8, user_data: *mut c_void) -> ffi::c_int>;
+
+/// Progress callback for `rxc_cdylib_sum_with_progress_unwind`, which may unwind (for example a
+/// Rust callback that panics or a C++ function that throws).
+pub type ProgressCallbackUnwind =
+    Option<unsafe extern "C-unwind" fn(percent: u8, user_data: *mut c_void) -> ffi::c_int>;
+
+/// Sums the `len` values at `values` into `total` like `rxc_cdylib_sum`, one value per step, and
+/// reports the progress through `cb` (which may be NULL).
+///
+/// `cb` is called on the calling thread with 0 before the first step and then whenever the
+/// percentage grows, ending with 100, and gets `user_data` passed through untouched. It may call
+/// back into the library, since no lock is held while it runs. When it returns non-zero the sum
+/// stops there and fails with `FFI_STATUS_CANCELLED`, leaving `total` untouched.
+///
+/// `cb` must not unwind: a Rust callback that panics aborts the process at its own `extern "C"`
+/// boundary, and a C++ exception escaping it is undefined behavior. Use
+/// `rxc_cdylib_sum_with_progress_unwind` for callbacks that may unwind.
+///
+/// # Safety
+///
+/// `values` must point to `len` readable `int`s and `total` must be valid for writes.
+#[export_name = "rxc_cdylib_sum_with_progress"]
+pub unsafe extern "C" fn cdylib_sum_with_progress(
+    values: *const ffi::c_int,
+    len: usize,
+    cb: ProgressCallback,
+    user_data: *mut c_void,
+    total: *mut ffi::c_long,
+) -> FfiStatus {
+    ffi_guard(|| {
+        ensure_initialized()?;
+        sum_with_progress(values, len, total, |percent| {
+            cb.map_or(0, |cb| cb(percent, user_data))
+        })
+    })
+}
+
+/// Like `rxc_cdylib_sum_with_progress`, but a panic or exception raised by `cb` unwinds through the
+/// library back to the caller, which has to be compiled with unwinding enabled to catch it.
+/// `total` is left untouched then.
+///
+/// Nothing is caught on the way, so a panic in the library itself unwinds to the caller as well
+/// instead of returning `FFI_STATUS_PANIC`.
+///
+/// # Safety
+///
+/// Like `rxc_cdylib_sum_with_progress`; in addition `cb` must be NULL or a function that either
+/// returns or unwinds with a panic or exception the caller catches.
+#[export_name = "rxc_cdylib_sum_with_progress_unwind"]
+pub unsafe extern "C-unwind" fn cdylib_sum_with_progress_unwind(
+    values: *const ffi::c_int,
+    len: usize,
+    cb: ProgressCallbackUnwind,
+    user_data: *mut c_void,
+    total: *mut ffi::c_long,
+) -> FfiStatus {
+    // ffi_guard 的 catch_unwind 会拦下回调的 panic，来自另一个 Rust 运行时的 panic 还会让它中止，
+    // 所以这里不用它，只把错误转换成状态
+    // ffi_guard's catch_unwind would stop the callback's panic, and abort on one from another Rust
+    // runtime, so it is not used here and errors are only turned into their status
+    let result = ensure_initialized().and_then(|()| {
+        sum_with_progress(values, len, total, |percent| {
+            cb.map_or(0, |cb| cb(percent, user_data))
+        })
+    });
+    match result {
+        Ok(()) => FfiStatus::Ok,
+        Err(err) => {
+            set_last_error(err.to_string());
+            err.into()
+        }
+    }
+}
+
+unsafe fn sum_with_progress(
+    values: *const ffi::c_int,
+    len: usize,
+    total: *mut ffi::c_long,
+    mut cb: impl FnMut(u8) -> ffi::c_int,
+) -> Result<(), FfiError> {
+    let values = slice_from_raw(values, len)?;
+    let mut reported = None;
+    let mut report = |percent: u8| {
+        // 同一个百分比只报告一次，值很多时回调不会被调用上百万次
+        // Each percentage is reported once, so many values don't mean millions of callbacks
+        if reported == Some(percent) {
+            return Ok(());
+        }
+        reported = Some(percent);
+        if cb(percent) != 0 {
+            log::info!("[Rust cdylib] rxc_cdylib_sum_with_progress aborted at {percent}%");
+            return Err(FfiError::Cancelled);
+        }
+        Ok(())
+    };
+
+    report(0)?;
+    let mut sum: ffi::c_long = 0;
+    for (i, &value) in values.iter().enumerate() {
+        sum = sum.checked_add(value.into()).ok_or(FfiError::Overflow)?;
+        report(((i as u128 + 1) * 100 / len as u128) as u8)?;
+    }
+    report(100)?;
+    write_out(total, sum)
+}
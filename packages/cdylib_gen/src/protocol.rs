@@ -0,0 +1,184 @@
+// 二进制协议的消息头：#[repr(C, packed)] 去掉了字段之间的填充，布局和线上的字节完全一致，C 端是同一个
+// 用 #pragma pack 打包的结构体。代价是多字节的字段不再对齐，对它们取引用是未定义行为（编译器直接拒绝
+// &header.len），所以 Rust 端只通过 read_unaligned 和 write_unaligned 访问字段。消息头按主机字节序存放，
+// 只在同一台机器上的 C 和 Rust 之间交换
+// The header of a binary protocol: #[repr(C, packed)] drops the padding between fields so the
+// layout is exactly the bytes on the wire, and C sees the same struct packed with #pragma pack.
+// The price is that the multi-byte fields are no longer aligned and taking a reference to one is
+// undefined behaviour (the compiler refuses &header.len outright), so the Rust side only accesses
+// fields through read_unaligned and write_unaligned. The header is stored in host byte order and
+// only exchanged between C and Rust on the same machine
+
+use std::mem;
+use std::ptr;
+
+use interop_common::{ffi_guard, write_out, FfiError, FfiSlice, FfiStatus};
+
+/// The magic every header starts with, "RXC1" in little-endian byte order.
+pub const MESSAGE_HEADER_MAGIC: u32 = 0x3143_5852;
+
+/// The version of the header format.
+pub const MESSAGE_HEADER_VERSION: u8 = 1;
+
+/// The size of a header in bytes, without any padding.
+pub const MESSAGE_HEADER_SIZE: usize = 20;
+
+/// 打包的消息头，和线上的 20 个字节一一对应
+/// A packed message header, matching the 20 bytes on the wire one to one.
+///
+/// Its alignment is 1, so a header can sit at any offset of a byte buffer, but its fields can't
+/// be borrowed because they may be misaligned:
+///
+/// ```compile_fail,E0793
+/// # use cdylib_gen::MessageHeader;
+/// let header = MessageHeader::new(1, 2, 3);
+/// let len: &u32 = &header.payload_len;
+/// ```
+///
+/// The accessors read a copy instead.
+#[repr(C, packed)]
+#[derive(Debug, Clone, Copy)]
+pub struct MessageHeader {
+    pub magic: u32,
+    pub version: u8,
+    pub kind: u8,
+    pub payload_len: u32,
+    pub sequence: u64,
+    /// The wrapping sum of the bytes before it.
+    pub checksum: u16,
+}
+
+// 布局断言：没有任何填充，对齐为 1；C 端的定义在 cbindgen.toml 中
+// Layout assertions: no padding at all and an alignment of 1; the C definition is in
+// cbindgen.toml
+const _: () = assert!(u32::from_le_bytes(*b"RXC1") == MESSAGE_HEADER_MAGIC);
+const _: () = assert!(mem::size_of::<MessageHeader>() == MESSAGE_HEADER_SIZE);
+const _: () = assert!(mem::align_of::<MessageHeader>() == 1);
+const _: () = assert!(mem::offset_of!(MessageHeader, payload_len) == 6);
+const _: () = assert!(mem::offset_of!(MessageHeader, sequence) == 10);
+const _: () = assert!(mem::offset_of!(MessageHeader, checksum) == 18);
+
+impl MessageHeader {
+    /// A header of the current version for a payload of `payload_len` bytes, with its checksum.
+    pub fn new(kind: u8, sequence: u64, payload_len: u32) -> MessageHeader {
+        let mut header = MessageHeader {
+            magic: MESSAGE_HEADER_MAGIC,
+            version: MESSAGE_HEADER_VERSION,
+            kind,
+            payload_len,
+            sequence,
+            checksum: 0,
+        };
+        header.set_checksum(header.expected_checksum());
+        header
+    }
+
+    /// Copies a header out of the first [`MESSAGE_HEADER_SIZE`] bytes of `bytes`, wherever they
+    /// are, and checks its magic, version and checksum.
+    pub fn parse(bytes: &[u8]) -> Result<MessageHeader, FfiError> {
+        if bytes.len() < MESSAGE_HEADER_SIZE {
+            return Err(FfiError::BufferTooSmall {
+                required: MESSAGE_HEADER_SIZE,
+            });
+        }
+        // SAFETY: there are MESSAGE_HEADER_SIZE readable bytes, and every bit pattern is a valid header
+        let header = unsafe { bytes.as_ptr().cast::<MessageHeader>().read_unaligned() };
+        if header.magic() != MESSAGE_HEADER_MAGIC
+            || header.version() != MESSAGE_HEADER_VERSION
+            || header.checksum() != header.expected_checksum()
+        {
+            return Err(FfiError::Malformed);
+        }
+        Ok(header)
+    }
+
+    /// The header as the bytes on the wire.
+    pub fn as_bytes(&self) -> &[u8; MESSAGE_HEADER_SIZE] {
+        // SAFETY: the header is MESSAGE_HEADER_SIZE bytes without padding and aligned to 1
+        unsafe { &*ptr::from_ref(self).cast::<[u8; MESSAGE_HEADER_SIZE]>() }
+    }
+
+    fn expected_checksum(&self) -> u16 {
+        self.as_bytes()[..MESSAGE_HEADER_SIZE - 2]
+            .iter()
+            .fold(0u16, |sum, &b| sum.wrapping_add(b.into()))
+    }
+
+    /// The magic the header starts with, [`MESSAGE_HEADER_MAGIC`] once parsed.
+    pub fn magic(&self) -> u32 {
+        // SAFETY: addr_of! never creates a reference, and read_unaligned has no alignment
+        // requirement
+        unsafe { ptr::addr_of!(self.magic).read_unaligned() }
+    }
+
+    /// The version of the header format, [`MESSAGE_HEADER_VERSION`] once parsed.
+    pub fn version(&self) -> u8 {
+        self.version
+    }
+
+    /// The kind of message, left to the application.
+    pub fn kind(&self) -> u8 {
+        self.kind
+    }
+
+    /// The number of payload bytes following the header.
+    pub fn payload_len(&self) -> u32 {
+        // SAFETY: addr_of! never creates a reference, and read_unaligned has no alignment
+        // requirement
+        unsafe { ptr::addr_of!(self.payload_len).read_unaligned() }
+    }
+
+    /// The sequence number the sender gave the message.
+    pub fn sequence(&self) -> u64 {
+        // SAFETY: addr_of! never creates a reference, and read_unaligned has no alignment
+        // requirement
+        unsafe { ptr::addr_of!(self.sequence).read_unaligned() }
+    }
+
+    /// The checksum stored in the header, the wrapping sum of the bytes before it once parsed.
+    pub fn checksum(&self) -> u16 {
+        // SAFETY: addr_of! never creates a reference, and read_unaligned has no alignment
+        // requirement
+        unsafe { ptr::addr_of!(self.checksum).read_unaligned() }
+    }
+
+    fn set_checksum(&mut self, checksum: u16) {
+        // SAFETY: addr_of_mut! never creates a reference, and write_unaligned has no alignment
+        // requirement
+        unsafe { ptr::addr_of_mut!(self.checksum).write_unaligned(checksum) }
+    }
+}
+
+/// Fills in `header` for a message of `kind` with a payload of `payload_len` bytes, including its
+/// magic, version and checksum.
+///
+/// # Safety
+///
+/// `header` must be NULL or valid for writes of a `MessageHeader`; it may point anywhere into a
+/// byte buffer, as the header's alignment is 1.
+#[export_name = "rxc_cdylib_header_init"]
+pub unsafe extern "C" fn cdylib_header_init(
+    kind: u8,
+    sequence: u64,
+    payload_len: u32,
+    header: *mut MessageHeader,
+) -> FfiStatus {
+    ffi_guard(|| write_out(header, MessageHeader::new(kind, sequence, payload_len)))
+}
+
+/// Parses the header at the start of `bytes` into `header`.
+///
+/// Fails with `FFI_STATUS_BUFFER_TOO_SMALL` when `bytes` is shorter than a header and with
+/// `FFI_STATUS_MALFORMED` when its magic, version or checksum is wrong, leaving `header` untouched
+/// either way. `bytes` may start at any address.
+///
+/// # Safety
+///
+/// `bytes` must view `len` readable bytes and `header` must be NULL or valid for writes.
+#[export_name = "rxc_cdylib_header_parse"]
+pub unsafe extern "C" fn cdylib_header_parse(
+    bytes: FfiSlice<u8>,
+    header: *mut MessageHeader,
+) -> FfiStatus {
+    ffi_guard(|| write_out(header, MessageHeader::parse(bytes.as_slice()?)?))
+}
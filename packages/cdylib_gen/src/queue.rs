@@ -0,0 +1,107 @@
+// 跨 FFI 的生产者/消费者队列：任意数量的 C 线程通过不透明句柄推入元素，库里的一个消费者线程
+// 按顺序取出并通过回调报告结果；std::sync::mpsc 的两端都留在 Rust 一侧
+// A producer/consumer queue across the FFI: any number of C threads push items through an opaque
+// handle, one consumer thread in the library takes them in order and reports the results through
+// a callback; both ends of the std::sync::mpsc channel stay on the Rust side
+
+use std::ffi::{c_int, c_void};
+use std::sync::mpsc::{self, Sender};
+use std::thread::{self, JoinHandle};
+
+use interop_common::{
+    ensure_initialized, ffi_guard, ffi_guard_or, FfiError, FfiHandle, FfiStatus, HandleRegistry,
+};
+
+use crate::workers::UserData;
+
+/// Result callback for a queue, receiving each item, the result of processing it (its square)
+/// and the `user_data` given to `rxc_queue_new`.
+pub type QueueCallback = Option<extern "C" fn(item: c_int, result: i64, user_data: *mut c_void)>;
+
+/// A queue of `int` items drained by a consumer thread in the library.
+///
+/// The layout is private to Rust; C code only handles the `QueueHandle` obtained from
+/// `rxc_queue_new`. Any number of threads may push to one queue at once.
+pub struct Queue {
+    items: Option<Sender<c_int>>,
+    consumer: Option<JoinHandle<()>>,
+}
+
+/// A queue handed to C, the null handle when `rxc_queue_new` failed.
+pub type QueueHandle = FfiHandle;
+
+static QUEUES: HandleRegistry<Queue> = HandleRegistry::new();
+
+impl Drop for Queue {
+    fn drop(&mut self) {
+        // 关闭发送端后消费者取完剩下的元素就会退出
+        // With the sending end closed the consumer exits once it has taken the remaining items
+        drop(self.items.take());
+        if let Some(consumer) = self.consumer.take() {
+            let _ = consumer.join();
+        }
+    }
+}
+
+/// Creates a queue whose consumer thread calls `cb` with `user_data` for every item pushed, in the
+/// order the pushes happened. The handle must be released with `rxc_queue_free`.
+///
+/// `user_data` must stay valid until `rxc_queue_free` returns. Returns the null handle if `cb` is
+/// NULL or the library is not initialized.
+#[export_name = "rxc_queue_new"]
+pub extern "C" fn queue_new(cb: QueueCallback, user_data: *mut c_void) -> QueueHandle {
+    ffi_guard_or(FfiHandle::NULL, || {
+        ensure_initialized()?;
+        let cb = cb.ok_or(FfiError::NullPointer)?;
+        let user_data = UserData(user_data);
+        let (items, receiver) = mpsc::channel::<c_int>();
+        let consumer = thread::Builder::new()
+            .name("cdylib_gen queue".to_owned())
+            .spawn(move || {
+                let user_data = user_data;
+                for item in receiver {
+                    cb(item, i64::from(item) * i64::from(item), user_data.0);
+                }
+            })
+            .expect("Unable to spawn the queue's consumer thread.");
+        Ok(QUEUES.insert(Queue {
+            items: Some(items),
+            consumer: Some(consumer),
+        }))
+    })
+}
+
+/// Pushes `item` onto the queue without waiting for the consumer.
+///
+/// Fails with `FFI_STATUS_INVALID_HANDLE` when `queue` was freed or never came from
+/// `rxc_queue_new`.
+#[export_name = "rxc_queue_push"]
+pub extern "C" fn queue_push(queue: QueueHandle, item: c_int) -> FfiStatus {
+    ffi_guard(|| {
+        ensure_initialized()?;
+        QUEUES
+            .get(queue)?
+            .items
+            .as_ref()
+            .expect("The sender lives as long as the queue.")
+            .send(item)
+            .expect("The consumer lives as long as the queue.");
+        Ok(())
+    })
+}
+
+/// Lets the consumer finish the items already pushed, stops it and destroys the queue. Passing
+/// the null handle is a no-op.
+///
+/// Must not be called from the queue's callback, which would wait for itself. A push still running
+/// on another thread is finished first, by that thread, and later pushes fail with
+/// `FFI_STATUS_INVALID_HANDLE`, as does freeing the handle twice.
+#[export_name = "rxc_queue_free"]
+pub extern "C" fn queue_free(queue: QueueHandle) -> FfiStatus {
+    ffi_guard(|| {
+        if !queue.is_null() {
+            drop(QUEUES.remove(queue)?);
+        }
+        Ok(())
+    })
+}
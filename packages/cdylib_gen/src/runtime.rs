@@ -0,0 +1,181 @@
+// 嵌在库里的 Tokio 运行时：C 端提交请求后立即返回，请求在运行时的工作线程上作为 future 运行，
+// 完成后在同一个线程上调用 C 的回调；async_block_on 则在调用线程上等待结果
+// A Tokio runtime embedded in the library: C submits a request and returns immediately, the
+// request runs as a future on one of the runtime's worker threads, which then calls the C
+// callback; async_block_on waits for the result on the calling thread instead
+//
+// rustlib_shutdown 先拒绝新的请求，再等待已提交的请求运行完并调用回调，最后停止运行时的线程
+// rustlib_shutdown first rejects new requests, then waits for the submitted ones to run and call
+// back, and finally stops the runtime's threads
+
+use std::ffi::{c_int, c_void};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::thread;
+use std::time::Duration;
+
+use interop_common::{
+    ensure_initialized, ffi_guard, set_last_error, write_out, FfiError, FfiStatus,
+};
+use tokio::runtime::{Builder, Handle};
+
+use crate::workers::{Pending, UserData};
+
+/// 交给运行时的请求：等待 `delay_ms` 毫秒后计算 `a + b`，模拟一次 I/O
+/// A request for the runtime: computes `a + b` after waiting `delay_ms` milliseconds, standing in
+/// for a round of I/O.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct AsyncRequest {
+    pub a: c_int,
+    pub b: c_int,
+    pub delay_ms: u32,
+}
+
+/// Completion callback for `rxc_async_submit`, receiving the request's status, the sum (0 unless
+/// the status is `FFI_STATUS_OK`) and the caller's `user_data`.
+pub type AsyncCallback =
+    Option<extern "C" fn(status: FfiStatus, sum: c_int, user_data: *mut c_void)>;
+
+struct Runtime {
+    runtime: tokio::runtime::Runtime,
+    pending: Arc<Pending>,
+}
+
+static RUNTIME: Mutex<Option<Runtime>> = Mutex::new(None);
+
+fn runtime() -> MutexGuard<'static, Option<Runtime>> {
+    RUNTIME.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Starts the runtime with `worker_threads` threads, 0 giving one per CPU, or does nothing if it
+/// is already running.
+pub(crate) fn start(worker_threads: u32) {
+    let mut runtime = runtime();
+    if runtime.is_some() {
+        return;
+    }
+    let mut builder = Builder::new_multi_thread();
+    if worker_threads > 0 {
+        builder.worker_threads(worker_threads as usize);
+    }
+    *runtime = Some(Runtime {
+        runtime: builder
+            .thread_name("cdylib_gen runtime")
+            .enable_time()
+            .build()
+            .expect("Unable to start the async runtime."),
+        pending: Arc::default(),
+    });
+}
+
+/// Waits for the submitted requests to finish and stops the runtime.
+pub(crate) fn stop() {
+    // 先从全局取出运行时，之后提交的请求得到 NOT_INITIALIZED；在锁外等待，免得回调里的调用卡住
+    // The runtime leaves the global first so later requests get NOT_INITIALIZED; waiting happens
+    // outside the lock so calls made from the callbacks don't get stuck
+    let Some(runtime) = runtime().take() else {
+        return;
+    };
+    runtime.pending.wait();
+    // 销毁运行时时要在 tokio 为每个线程缓存的 parker 上等待工作线程，换一个短命的线程来做，
+    // 这个缓存随它一起释放，不会留在调用方的线程上被泄漏检查算进去
+    // Dropping the runtime waits for its workers on a parker tokio caches for each thread; doing
+    // it on a short-lived thread frees that cache with the thread instead of leaving it on the
+    // caller's, where the leak check would count it
+    thread::spawn(move || drop(runtime))
+        .join()
+        .expect("Dropping the async runtime panicked.");
+}
+
+/// Fails with [`FfiError::WouldDeadlock`] on the runtime's own threads, where blocking on the
+/// runtime would wait for itself.
+pub(crate) fn ensure_outside() -> Result<(), FfiError> {
+    match Handle::try_current() {
+        Ok(_) => Err(FfiError::WouldDeadlock),
+        Err(_) => Ok(()),
+    }
+}
+
+// 等待期间不占用线程，不像工作线程池上的任务
+// Waiting doesn't tie up a thread, unlike a job on the worker pool
+async fn run(request: AsyncRequest) -> Result<c_int, FfiError> {
+    tokio::time::sleep(Duration::from_millis(request.delay_ms.into())).await;
+    request.a.checked_add(request.b).ok_or(FfiError::Overflow)
+}
+
+/// Runs `request` on the library's async runtime and reports the outcome through `cb`.
+///
+/// Returns as soon as the request is queued. `cb` is then invoked exactly once, on one of the
+/// runtime's worker threads, with `user_data` passed through untouched; if the status it gets is
+/// not `FFI_STATUS_OK`, that thread's last error describes why. When this call fails (NULL `cb`
+/// or an uninitialized library) `cb` is never invoked. `user_data` must stay valid until the
+/// callback has run, at the latest by `rxc_rustlib_shutdown`, which waits for it.
+///
+/// The callback must not block for long, since it holds up the other requests sharing its thread,
+/// and must not call `rxc_async_block_on` or `rxc_rustlib_shutdown`.
+#[export_name = "rxc_async_submit"]
+pub extern "C" fn async_submit(
+    request: AsyncRequest,
+    cb: AsyncCallback,
+    user_data: *mut c_void,
+) -> FfiStatus {
+    ffi_guard(|| {
+        ensure_initialized()?;
+        let cb = cb.ok_or(FfiError::NullPointer)?;
+        let user_data = UserData(user_data);
+
+        let runtime = runtime();
+        let runtime = runtime.as_ref().ok_or(FfiError::NotInitialized)?;
+        // 在持有锁时登记，stop 取走运行时之后一定会等到这个请求
+        // Registered while holding the lock, so stop is sure to wait for this request once it
+        // has taken the runtime
+        let pending = Arc::clone(&runtime.pending);
+        pending.begin();
+        runtime.runtime.spawn(async move {
+            let user_data = user_data;
+            let (status, sum) = match run(request).await {
+                Ok(sum) => (FfiStatus::Ok, sum),
+                Err(err) => {
+                    set_last_error(err.to_string());
+                    (err.into(), 0)
+                }
+            };
+            log::info!("[Rust cdylib] Async request finished with {status:?}");
+            cb(status, sum, user_data.0);
+            pending.finish();
+        });
+        Ok(())
+    })
+}
+
+/// Runs `request` on the library's async runtime and blocks the calling thread until it is done,
+/// storing the sum in `sum`.
+///
+/// Fails with `FFI_STATUS_WOULD_DEADLOCK` when called on one of the runtime's threads, such as
+/// from an `rxc_async_submit` callback.
+///
+/// # Safety
+///
+/// `sum` must be valid for writes.
+#[export_name = "rxc_async_block_on"]
+pub unsafe extern "C" fn async_block_on(request: AsyncRequest, sum: *mut c_int) -> FfiStatus {
+    ffi_guard(|| {
+        ensure_initialized()?;
+        ensure_outside()?;
+        let (handle, pending) = {
+            let runtime = runtime();
+            let runtime = runtime.as_ref().ok_or(FfiError::NotInitialized)?;
+            runtime.pending.begin();
+            (
+                runtime.runtime.handle().clone(),
+                Arc::clone(&runtime.pending),
+            )
+        };
+        // 计时器由运行时的工作线程驱动，调用线程只是等待 future 完成
+        // The runtime's worker threads drive the timers, the calling thread only waits for the
+        // future to complete
+        let result = handle.block_on(run(request));
+        pending.finish();
+        write_out(sum, result?)
+    })
+}
@@ -0,0 +1,35 @@
+// int 以外的标量按值跨越 FFI 边界：bool 是 C 的 bool（_Bool），usize 是 size_t，i64 是 int64_t，
+// f64 是 double。C 的 bool 只能是 0 或 1，其他值传给 Rust 的 bool 是未定义行为，所以来自不可信 C 代码的
+// 标志应该声明为整数再比较
+// Scalars other than int crossing the FFI boundary by value: bool is C's bool (_Bool), usize is
+// size_t, i64 is int64_t and f64 is double. A C bool can only be 0 or 1 and any other value is
+// undefined behaviour in a Rust bool, so flags from untrusted C code should be declared as
+// integers and compared instead
+
+/// Whether `value` is even.
+#[export_name = "rxc_cdylib_is_even"]
+pub extern "C" fn cdylib_is_even(value: i64) -> bool {
+    value % 2 == 0
+}
+
+/// Returns `!value`.
+#[export_name = "rxc_cdylib_not"]
+pub extern "C" fn cdylib_not(value: bool) -> bool {
+    !value
+}
+
+/// Rounds `size` up to a multiple of `align`, returning 0 if `align` is not a power of two or
+/// the result does not fit in a `size_t`.
+#[export_name = "rxc_cdylib_align_up"]
+pub extern "C" fn cdylib_align_up(size: usize, align: usize) -> usize {
+    if !align.is_power_of_two() {
+        return 0;
+    }
+    size.checked_next_multiple_of(align).unwrap_or(0)
+}
+
+/// Linearly interpolates between `a` and `b`: `a` at `t == 0.0` and `b` at `t == 1.0`.
+#[export_name = "rxc_cdylib_lerp"]
+pub extern "C" fn cdylib_lerp(a: f64, b: f64, t: f64) -> f64 {
+    a + (b - a) * t
+}
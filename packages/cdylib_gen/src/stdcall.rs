@@ -0,0 +1,33 @@
+// 32 位 Windows 上默认的 cdecl 之外的调用约定：stdcall 由被调用者清理栈上的参数，Win32 API 都用它。
+// 调用双方对约定的理解不一致时，栈指针在每次调用后都会偏移参数的字节数
+// A calling convention beyond the default cdecl on 32-bit Windows: with stdcall the callee removes
+// the arguments from the stack, as the whole Win32 API does. When the two sides disagree on the
+// convention, the stack pointer is off by the size of the arguments after every call
+//
+// cbindgen 跳过非 extern "C" 的函数，头文件中的声明写在 cbindgen.toml 的 trailer 里
+// cbindgen skips functions that are not extern "C", their declarations in the header are written
+// in the trailer of cbindgen.toml
+
+use std::ffi;
+
+/// A transform applied by `rxc_cdylib_apply_stdcall`, called with the stdcall convention; NULL
+/// means "leave the value unchanged".
+pub type TransformStdcall = Option<extern "stdcall" fn(value: ffi::c_int) -> ffi::c_int>;
+
+/// Returns `a + b`, wrapping around on overflow, with the stdcall convention.
+#[export_name = "rxc_cdylib_add_stdcall"]
+pub extern "stdcall" fn cdylib_add_stdcall(a: ffi::c_int, b: ffi::c_int) -> ffi::c_int {
+    a.wrapping_add(b)
+}
+
+/// Like `rxc_cdylib_apply`, with the stdcall convention for both the function and `transform`.
+#[export_name = "rxc_cdylib_apply_stdcall"]
+pub extern "stdcall" fn cdylib_apply_stdcall(
+    value: ffi::c_int,
+    transform: TransformStdcall,
+) -> ffi::c_int {
+    match transform {
+        Some(f) => f(value),
+        None => value,
+    }
+}
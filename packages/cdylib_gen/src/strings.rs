@@ -0,0 +1,73 @@
+// 字符串数组在两个方向上跨越 FFI 边界：C 以 argv 的形式传入 char* 数组和个数，Rust 返回自己分配的
+// 以 NULL 结尾的数组，调用方用 rust_string_array_free 连同其中的字符串一起归还
+// String arrays crossing the FFI boundary in both directions: C passes an array of char* and its
+// count the way argv is passed, and Rust returns a NULL-terminated array it allocated, which the
+// caller hands back together with its strings through rust_string_array_free
+
+use std::ffi::c_char;
+use std::ptr;
+
+use interop_common::{
+    ensure_initialized, ffi_guard_or, read_cstr, slice_from_raw, write_out, FfiError,
+};
+
+use crate::memory::{alloc_cstring, alloc_string_array};
+
+// 数组中的每个指针都必须是 NUL 结尾的 UTF-8 字符串，NULL 元素视为错误
+// Every pointer in the array must be a NUL-terminated UTF-8 string, a NULL element is an error
+unsafe fn read_string_array<'a>(
+    strings: *const *const c_char,
+    len: usize,
+) -> Result<Vec<&'a str>, FfiError> {
+    slice_from_raw(strings, len)?
+        .iter()
+        .map(|&s| read_cstr(s, usize::MAX))
+        .collect()
+}
+
+/// Joins the `len` strings at `strings` with `separator` in between, such as `argc` and `argv`.
+///
+/// Ownership of the returned string passes to the caller, who must release it with
+/// `rxc_rust_free`. Returns NULL on failure, with the reason available from
+/// `rxc_rustlib_last_error_message`.
+///
+/// # Safety
+///
+/// `strings` must point to `len` NUL-terminated strings (it may be NULL when `len` is 0) and
+/// `separator` must be NULL or point to a NUL-terminated string.
+#[export_name = "rxc_cdylib_join_strings"]
+pub unsafe extern "C" fn cdylib_join_strings(
+    strings: *const *const c_char,
+    len: usize,
+    separator: *const c_char,
+) -> *mut c_char {
+    ffi_guard_or(ptr::null_mut(), || {
+        ensure_initialized()?;
+        let separator = read_cstr(separator, usize::MAX)?;
+        alloc_cstring(&read_string_array(strings, len)?.join(separator))
+    })
+}
+
+/// Splits `text` at whitespace, returning the words as an array of `*count` strings followed by
+/// a NULL.
+///
+/// Ownership of the array and its strings passes to the caller, who must release them with
+/// `rxc_rust_string_array_free(array, *count)`. Returns NULL on failure, with the reason
+/// available from `rxc_rustlib_last_error_message`; text without words still gets an array
+/// holding only the NULL.
+///
+/// # Safety
+///
+/// `text` must be NULL or point to a NUL-terminated string, and `count` must be valid for writes.
+#[export_name = "rxc_cdylib_split_words"]
+pub unsafe extern "C" fn cdylib_split_words(
+    text: *const c_char,
+    count: *mut usize,
+) -> *mut *mut c_char {
+    ffi_guard_or(ptr::null_mut(), || {
+        ensure_initialized()?;
+        let words: Vec<&str> = read_cstr(text, usize::MAX)?.split_whitespace().collect();
+        write_out(count, words.len())?;
+        alloc_string_array(&words)
+    })
+}
@@ -0,0 +1,186 @@
+// 把 tracing 的 span 和事件作为结构化记录转发给 C 注册的回调，让原生宿主把 Rust 的遥测接入自己的追踪系统
+// Forwards tracing spans and events as structured records to a callback registered from C, so
+// native hosts can stitch Rust telemetry into their own tracing systems
+
+use std::ffi::c_void;
+use std::fmt;
+use std::sync::{Once, RwLock};
+
+use interop_common::{FfiStr, LogLevel};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Level, Metadata, Subscriber};
+use tracing_subscriber::layer::{Context, Layer, SubscriberExt};
+use tracing_subscriber::registry::{LookupSpan, Registry};
+
+/// What a `TraceRecord` describes.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TraceKind {
+    /// The thread entered the span `span_id`.
+    SpanEnter = 0,
+    /// The thread left the span `span_id`.
+    SpanExit = 1,
+    /// An event inside the span `span_id`, or outside any span when it is 0.
+    Event = 2,
+}
+
+/// A UTF-8 string that is not NUL terminated, the same as `FfiStr`.
+pub type TraceStr = FfiStr;
+
+/// One `key = value` field of a span or event, the value formatted as with `{:?}`.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct TraceField {
+    pub key: TraceStr,
+    pub value: TraceStr,
+}
+
+/// A span enter/exit or an event, with every pointer only valid during the callback.
+///
+/// Span records carry the fields the span was created and recorded with; an event's message is
+/// its `message` field.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct TraceRecord {
+    pub kind: TraceKind,
+    pub level: LogLevel,
+    pub span_id: u64,
+    pub name: TraceStr,
+    pub target: TraceStr,
+    pub fields: *const TraceField,
+    pub field_count: usize,
+}
+
+/// Receives every `TraceRecord` with the caller's `user_data`, on whichever thread the record
+/// happened.
+pub type TraceCallback =
+    Option<unsafe extern "C" fn(record: *const TraceRecord, user_data: *mut c_void)>;
+
+#[derive(Clone, Copy)]
+struct Sink {
+    callback: unsafe extern "C" fn(*const TraceRecord, *mut c_void),
+    user_data: *mut c_void,
+}
+
+// The pointer is only handed back to the callback, synchronising access is the caller's job.
+unsafe impl Send for Sink {}
+unsafe impl Sync for Sink {}
+
+static SINK: RwLock<Option<Sink>> = RwLock::new(None);
+static INSTALL_SUBSCRIBER: Once = Once::new();
+
+/// Forwards this library's tracing spans and events to `callback` with `user_data`; passing NULL
+/// stops forwarding.
+///
+/// The subscriber is installed as the process's global tracing default on the first call, unless
+/// a Rust host linking the library as an rlib set its own default first. `user_data` must stay
+/// valid until another callback is registered.
+#[export_name = "rxc_cdylib_set_trace_callback"]
+pub extern "C" fn cdylib_set_trace_callback(callback: TraceCallback, user_data: *mut c_void) {
+    INSTALL_SUBSCRIBER.call_once(|| {
+        let _ = tracing::subscriber::set_global_default(Registry::default().with(CallbackLayer));
+    });
+    *SINK.write().unwrap_or_else(|err| err.into_inner()) = callback.map(|callback| Sink {
+        callback,
+        user_data,
+    });
+}
+
+// 字段以字符串对的形式收集，保存在 span 的扩展数据中
+// Fields are collected as pairs of strings and kept in the span's extensions
+#[derive(Default)]
+struct Fields(Vec<(&'static str, String)>);
+
+impl Visit for Fields {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.push((field.name(), value.to_owned()));
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.0.push((field.name(), format!("{value:?}")));
+    }
+}
+
+struct CallbackLayer;
+
+impl<S> Layer<S> for CallbackLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let mut fields = Fields::default();
+        attrs.record(&mut fields);
+        if let Some(span) = ctx.span(id) {
+            span.extensions_mut().insert(fields);
+        }
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id) {
+            if let Some(fields) = span.extensions_mut().get_mut::<Fields>() {
+                values.record(fields);
+            }
+        }
+    }
+
+    fn on_enter(&self, id: &Id, ctx: Context<'_, S>) {
+        span_record(TraceKind::SpanEnter, id, &ctx);
+    }
+
+    fn on_exit(&self, id: &Id, ctx: Context<'_, S>) {
+        span_record(TraceKind::SpanExit, id, &ctx);
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let mut fields = Fields::default();
+        event.record(&mut fields);
+        let span_id = ctx.current_span().id().map_or(0, Id::into_u64);
+        forward(TraceKind::Event, span_id, event.metadata(), &fields);
+    }
+}
+
+fn span_record<S>(kind: TraceKind, id: &Id, ctx: &Context<'_, S>)
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    if let Some(span) = ctx.span(id) {
+        let extensions = span.extensions();
+        let empty = Fields::default();
+        let fields = extensions.get::<Fields>().unwrap_or(&empty);
+        forward(kind, id.into_u64(), span.metadata(), fields);
+    }
+}
+
+fn forward(kind: TraceKind, span_id: u64, metadata: &Metadata<'_>, fields: &Fields) {
+    // 先复制出 sink 再调用，回调里再次注册回调也不会死锁
+    // Copy the sink out before calling it, so a callback that registers a callback does not
+    // deadlock
+    let Some(sink) = *SINK.read().unwrap_or_else(|err| err.into_inner()) else {
+        return;
+    };
+    let fields: Vec<TraceField> = fields
+        .0
+        .iter()
+        .map(|(key, value)| TraceField {
+            key: FfiStr::new(key),
+            value: FfiStr::new(value),
+        })
+        .collect();
+    let record = TraceRecord {
+        kind,
+        level: match *metadata.level() {
+            Level::ERROR => LogLevel::Error,
+            Level::WARN => LogLevel::Warn,
+            Level::INFO => LogLevel::Info,
+            Level::DEBUG => LogLevel::Debug,
+            Level::TRACE => LogLevel::Trace,
+        },
+        span_id,
+        name: FfiStr::new(metadata.name()),
+        target: FfiStr::new(metadata.target()),
+        fields: fields.as_ptr(),
+        field_count: fields.len(),
+    };
+    unsafe { (sink.callback)(&record, sink.user_data) };
+}
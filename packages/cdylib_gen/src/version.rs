@@ -0,0 +1,54 @@
+// ABI 版本握手：调用方在调用任何其他函数之前比较这个版本
+// ABI version handshake: callers compare this version before calling anything else
+
+use std::ffi;
+
+use interop_common::{ffi_guard, write_out, FfiStatus};
+
+/// Bumped whenever an exported signature or `#[repr(C)]` type changes incompatibly.
+pub const CDYLIB_ABI_VERSION: u32 = 1;
+
+/// Returns the ABI version this library was built with, compare it with `CDYLIB_ABI_VERSION`
+/// from the header the caller was compiled against.
+#[export_name = "rxc_cdylib_abi_version"]
+pub extern "C" fn cdylib_abi_version() -> u32 {
+    CDYLIB_ABI_VERSION
+}
+
+/// Room for a full 40-character git commit hash and its NUL terminator.
+pub const CDYLIB_GIT_HASH_LEN: usize = 41;
+
+/// The library version, filled in by [`cdylib_version`].
+#[repr(C)]
+pub struct Version {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+    /// NUL-terminated commit hash the library was built from, empty when it was built outside git.
+    pub git_hash: [ffi::c_char; CDYLIB_GIT_HASH_LEN],
+}
+
+/// Writes the package version and the git commit the library was built from into `out`.
+///
+/// # Safety
+///
+/// `out` must be NULL or valid for writes.
+#[export_name = "rxc_cdylib_version"]
+pub unsafe extern "C" fn cdylib_version(out: *mut Version) -> FfiStatus {
+    ffi_guard(|| write_out(out, version()))
+}
+
+// 版本号来自 Cargo.toml，提交哈希由 build.rs 通过 CDYLIB_GIT_HASH 注入
+// The version numbers come from Cargo.toml, build.rs injects the commit hash as CDYLIB_GIT_HASH
+fn version() -> Version {
+    let mut git_hash = [0; CDYLIB_GIT_HASH_LEN];
+    for (slot, byte) in git_hash.iter_mut().zip(env!("CDYLIB_GIT_HASH").bytes()) {
+        *slot = byte as ffi::c_char;
+    }
+    Version {
+        major: env!("CARGO_PKG_VERSION_MAJOR").parse().unwrap(),
+        minor: env!("CARGO_PKG_VERSION_MINOR").parse().unwrap(),
+        patch: env!("CARGO_PKG_VERSION_PATCH").parse().unwrap(),
+        git_hash,
+    }
+}
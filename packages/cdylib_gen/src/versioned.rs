@@ -0,0 +1,70 @@
+// 带版本号的 add 导出：签名发生不兼容的变化时新增一个 _vN 符号，旧的 _vN 符号继续导出，作为转调最新实现的
+// 薄垫片，按旧签名编译的 C 程序不用重新编译就能链接新版的库。不带后缀的 rxc_cdylib_add 是当前的签名，
+// 和 rxc_cdylib_add_v2 相同
+// Versioned add exports: an incompatible signature change adds a new _vN symbol while the old _vN
+// symbols stay exported as thin shims over the newest implementation, so C programs compiled
+// against an old signature keep linking against the new library without a rebuild. The unsuffixed
+// rxc_cdylib_add is the current signature, the same as rxc_cdylib_add_v2
+//
+// 只增加符号不会破坏已有的调用方，所以 CDYLIB_ABI_VERSION 不变
+// Only adding symbols breaks no existing caller, so CDYLIB_ABI_VERSION stays the same
+
+use std::ffi::{self, CStr};
+
+use interop_common::{ensure_initialized, ffi_guard_or, FfiError, FfiStatus};
+
+use crate::{addition, cdylib_add};
+
+/// The first version of `rxc_cdylib_add`, kept for callers compiled against it.
+///
+/// `result` holds the caller's NUL-terminated name on input and receives the message on output,
+/// it must be large enough for the whole message. Returns the sum, or 0 on failure with the reason
+/// available from `rxc_rustlib_last_error_message`; a real sum of 0 can't be told apart from a
+/// failure, one of the reasons `rxc_cdylib_add_v2` replaced it.
+///
+/// # Safety
+///
+/// `result` must be NULL or point to a NUL-terminated string in a buffer large enough for the
+/// message.
+#[export_name = "rxc_cdylib_add_v1"]
+pub unsafe extern "C" fn cdylib_add_v1(
+    a: ffi::c_int,
+    b: ffi::c_int,
+    result: *mut ffi::c_char,
+) -> ffi::c_int {
+    ffi_guard_or(0, || {
+        ensure_initialized()?;
+        if result.is_null() {
+            return Err(FfiError::NullPointer);
+        }
+        // 旧签名没有缓冲区长度，只能按调用方的约定假定它放得下名字和消息中较长的那个
+        // The old signature has no buffer length, so all the shim can go by is the caller's
+        // promise that the buffer fits the longer of the name and the message
+        let name_len = CStr::from_ptr(result).to_bytes().len();
+        let result_len = name_len.max(addition(a, b)?.message.len()) + 1;
+        let mut sum = 0;
+        match cdylib_add(a, b, result, result_len, &mut sum, std::ptr::null_mut()) {
+            FfiStatus::Ok => Ok(sum),
+            // 失败原因已经由 cdylib_add 记为本线程的最后错误
+            // cdylib_add already recorded the reason as the thread's last error
+            _ => Ok(0),
+        }
+    })
+}
+
+/// The length-checked `rxc_cdylib_add` under its versioned name, see it for the contract.
+///
+/// # Safety
+///
+/// The same as `rxc_cdylib_add`.
+#[export_name = "rxc_cdylib_add_v2"]
+pub unsafe extern "C" fn cdylib_add_v2(
+    a: ffi::c_int,
+    b: ffi::c_int,
+    result: *mut ffi::c_char,
+    result_len: usize,
+    sum: *mut ffi::c_int,
+    required_len: *mut usize,
+) -> FfiStatus {
+    cdylib_add(a, b, result, result_len, sum, required_len)
+}
@@ -0,0 +1,43 @@
+// cdylib_add 的 UTF-16 版本，供 Windows 上使用 wchar_t / LPWSTR 的调用方直接传入宽字符串，不必先转换成
+// 可能丢失字符的本地代码页或 UTF-8；转换由 interop_common 的 read_wstr / write_wstr 完成
+// The UTF-16 flavour of cdylib_add, so callers on Windows using wchar_t / LPWSTR can pass wide
+// strings straight in instead of converting them to a code page that may lose characters, or to
+// UTF-8 first; interop_common's read_wstr / write_wstr do the conversion
+
+use std::ffi;
+
+use interop_common::{ensure_initialized, ffi_guard, read_wstr, write_wstr, FfiStatus};
+
+use crate::{addition, hello};
+
+/// Like `rxc_cdylib_add`, with `result` a buffer of `len` UTF-16 code units, a `wchar_t` buffer
+/// on Windows.
+///
+/// `result` holds the caller's NUL-terminated name on input and receives the message on output,
+/// truncated to fit with `FFI_STATUS_BUFFER_TOO_SMALL` but never in the middle of a surrogate
+/// pair. A name with an unpaired surrogate fails with `FFI_STATUS_INVALID_UTF16`. `result` may be
+/// NULL when `len` is 0.
+///
+/// # Safety
+///
+/// `result` must be valid for reads and writes of `len` code units and hold a NUL-terminated
+/// string within them.
+#[export_name = "rxc_cdylib_add_w"]
+pub unsafe extern "C" fn cdylib_add_w(
+    a: ffi::c_int,
+    b: ffi::c_int,
+    result: *mut u16,
+    len: usize,
+) -> FfiStatus {
+    ffi_guard(|| {
+        ensure_initialized()?;
+        let addition = addition(a, b)?;
+        let name = if result.is_null() && len == 0 {
+            String::new()
+        } else {
+            read_wstr(result, len)?
+        };
+        log::info!("{}", hello(&name));
+        write_wstr(result, len, &addition.message).map(|_| ())
+    })
+}
@@ -0,0 +1,208 @@
+// 由 Rust 管理的工作线程池：rustlib_init 启动一个全局的池给 cdylib_add_async 等异步调用使用，
+// pool_create 创建的池也建立在同一个类型上
+// Worker thread pools managed by Rust: rustlib_init starts a global one for asynchronous calls
+// such as cdylib_add_async, and the pools pool_create returns are built on the same type
+
+use std::cell::Cell;
+use std::ffi::c_void;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError};
+use std::thread::{self, JoinHandle};
+
+use interop_common::FfiError;
+
+type Job = Box<dyn FnOnce() + Send>;
+
+/// The caller's context pointer, moved to a worker thread as-is.
+pub(crate) struct UserData(pub(crate) *mut c_void);
+
+// The pointer is only handed back to the callback, synchronising access is the caller's job.
+unsafe impl Send for UserData {}
+
+/// A fixed set of threads running the jobs submitted to it in order.
+///
+/// Dropping the pool runs the jobs still queued and then joins its threads.
+pub(crate) struct WorkerPool {
+    id: usize,
+    jobs: Option<Sender<Job>>,
+    threads: Vec<JoinHandle<()>>,
+    pending: Arc<Pending>,
+}
+
+// 每个池的编号从 1 开始，工作线程把所属池的编号记在 CURRENT_POOL 里，不属于任何池的线程是 0
+// Pools are numbered from 1 and a worker thread records its pool's number in CURRENT_POOL, which
+// is 0 on threads belonging to no pool
+static NEXT_POOL_ID: AtomicUsize = AtomicUsize::new(1);
+
+thread_local! {
+    static CURRENT_POOL: Cell<usize> = const { Cell::new(0) };
+}
+
+// 已提交但还没有运行完的任务数，降到零时唤醒等待的调用方
+// The number of jobs submitted but not yet finished, reaching zero wakes the waiting callers
+#[derive(Default)]
+pub(crate) struct Pending {
+    count: Mutex<usize>,
+    idle: Condvar,
+}
+
+impl Pending {
+    pub(crate) fn begin(&self) {
+        *self.count() += 1;
+    }
+
+    pub(crate) fn finish(&self) {
+        let mut count = self.count();
+        *count -= 1;
+        if *count == 0 {
+            self.idle.notify_all();
+        }
+    }
+
+    /// Blocks until every job begun so far has finished.
+    pub(crate) fn wait(&self) {
+        let count = self.count();
+        drop(
+            self.idle
+                .wait_while(count, |count| *count > 0)
+                .unwrap_or_else(PoisonError::into_inner),
+        );
+    }
+
+    fn count(&self) -> MutexGuard<'_, usize> {
+        self.count.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl WorkerPool {
+    /// Starts `count` threads (at least one) named after `name`.
+    pub(crate) fn new(name: &str, count: u32) -> Self {
+        let (jobs, receiver) = mpsc::channel();
+        let receiver = Arc::new(Mutex::new(receiver));
+        let pending = Arc::new(Pending::default());
+        let id = NEXT_POOL_ID.fetch_add(1, Ordering::Relaxed);
+        let threads = (0..count.max(1))
+            .map(|i| {
+                let receiver = Arc::clone(&receiver);
+                let pending = Arc::clone(&pending);
+                thread::Builder::new()
+                    .name(format!("{name} {i}"))
+                    .spawn(move || run(id, &receiver, &pending))
+                    .expect("Unable to spawn a worker thread.")
+            })
+            .collect();
+        WorkerPool {
+            id,
+            jobs: Some(jobs),
+            threads,
+            pending,
+        }
+    }
+
+    /// Queues `job` to run on one of the pool's threads.
+    pub(crate) fn submit(&self, job: impl FnOnce() + Send + 'static) {
+        self.pending.begin();
+        self.jobs
+            .as_ref()
+            .expect("The sender lives as long as the pool.")
+            .send(Box::new(job))
+            .expect("The worker threads live as long as the pool.");
+    }
+
+    /// Blocks until every job submitted so far has finished. Must not be called from a job.
+    pub(crate) fn join(&self) {
+        self.pending.wait();
+    }
+
+    /// Fails with [`FfiError::WouldDeadlock`] on the pool's own threads, where joining or dropping
+    /// the pool would wait for itself.
+    pub(crate) fn ensure_outside(&self) -> Result<(), FfiError> {
+        ensure_outside_pool(self.id)
+    }
+}
+
+impl Drop for WorkerPool {
+    fn drop(&mut self) {
+        // 关闭通道后工作线程处理完剩下的任务就会退出
+        // With the channel closed the workers exit once the remaining jobs are done
+        drop(self.jobs.take());
+        for thread in self.threads.drain(..) {
+            let _ = thread.join();
+        }
+    }
+}
+
+fn ensure_outside_pool(id: usize) -> Result<(), FfiError> {
+    if id != 0 && CURRENT_POOL.with(Cell::get) == id {
+        Err(FfiError::WouldDeadlock)
+    } else {
+        Ok(())
+    }
+}
+
+fn run(id: usize, receiver: &Mutex<Receiver<Job>>, pending: &Pending) {
+    CURRENT_POOL.with(|current| current.set(id));
+    loop {
+        // 只在取任务时持有锁，任务本身并行运行
+        // The lock is only held while taking a job, the jobs themselves run in parallel
+        let job = receiver
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .recv();
+        let Ok(job) = job else {
+            return;
+        };
+        // 任务中的 panic 已经由 panic hook 报告过，线程继续处理后面的任务
+        // A panic in a job has already been reported by the panic hook, the thread goes on with
+        // the next jobs
+        let _ = panic::catch_unwind(AssertUnwindSafe(job));
+        pending.finish();
+    }
+}
+
+static POOL: Mutex<Option<WorkerPool>> = Mutex::new(None);
+
+// 全局池的编号，没有全局池时是 0；stop 回收完线程之后才清零，回收期间它的工作线程仍然认得出自己
+// The global pool's number, 0 while there is none; stop only clears it once the threads are
+// joined, so its workers still recognize themselves while that happens
+static GLOBAL_POOL_ID: AtomicUsize = AtomicUsize::new(0);
+
+fn pool() -> MutexGuard<'static, Option<WorkerPool>> {
+    POOL.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Starts the global pool with `count` worker threads (at least one), or does nothing if it is
+/// already running.
+pub(crate) fn start(count: u32) {
+    let mut pool = pool();
+    if pool.is_none() {
+        let workers = WorkerPool::new("cdylib_gen worker", count);
+        GLOBAL_POOL_ID.store(workers.id, Ordering::Relaxed);
+        *pool = Some(workers);
+    }
+}
+
+/// Stops the global pool after the queued jobs have run. Must not be called from a worker thread.
+pub(crate) fn stop() {
+    // 在锁外回收线程，还在运行的任务调用 spawn 时得到 NOT_INITIALIZED 而不是死锁
+    // The threads are joined outside the lock, so a job still running that calls spawn gets
+    // NOT_INITIALIZED instead of a deadlock
+    let pool = pool().take();
+    drop(pool);
+    GLOBAL_POOL_ID.store(0, Ordering::Relaxed);
+}
+
+/// Fails with [`FfiError::WouldDeadlock`] on the global pool's threads, where [`stop`] would join
+/// the calling thread.
+pub(crate) fn ensure_outside() -> Result<(), FfiError> {
+    ensure_outside_pool(GLOBAL_POOL_ID.load(Ordering::Relaxed))
+}
+
+/// Queues `job` on the global pool, failing with [`FfiError::NotInitialized`] while none is
+/// running.
+pub(crate) fn spawn(job: impl FnOnce() + Send + 'static) -> Result<(), FfiError> {
+    pool().as_ref().ok_or(FfiError::NotInitialized)?.submit(job);
+    Ok(())
+}
@@ -0,0 +1,202 @@
+// cdylib_add 的边界条件测试：NULL 指针、缓冲区不足、非 UTF-8 名字、溢出和多线程调用，以及带版本号的
+// cdylib_add_v1/cdylib_add_v2
+// Edge case tests for cdylib_add: NULL pointers, short buffers, non-UTF-8 names, overflow and
+// calls from several threads, plus the versioned cdylib_add_v1/cdylib_add_v2
+
+use std::ffi::{c_char, c_int, CStr};
+use std::{ptr, thread};
+
+use cdylib_gen::{cdylib_add, cdylib_add_v1, cdylib_add_v2, FfiStatus};
+
+mod common;
+use common::init;
+
+// 把名字和结尾的 NUL 放进一个 capacity 字节的缓冲区
+// Puts the name and its NUL terminator into a buffer of capacity bytes
+fn name_buf(name: &[u8], capacity: usize) -> Vec<c_char> {
+    let mut buf = vec![0; capacity];
+    for (dst, &src) in buf.iter_mut().zip(name) {
+        *dst = src as c_char;
+    }
+    buf
+}
+
+fn message(buf: &[c_char]) -> &str {
+    unsafe { CStr::from_ptr(buf.as_ptr()) }.to_str().unwrap()
+}
+
+unsafe fn add(
+    a: c_int,
+    b: c_int,
+    buf: &mut [c_char],
+    sum: &mut c_int,
+    required: &mut usize,
+) -> FfiStatus {
+    cdylib_add(a, b, buf.as_mut_ptr(), buf.len(), sum, required)
+}
+
+#[test]
+fn writes_message_and_sum() {
+    init();
+    let mut buf = name_buf(b"Lee", 64);
+    let (mut sum, mut required) = (0, 0);
+    let status = unsafe { add(2, 3, &mut buf, &mut sum, &mut required) };
+    assert_eq!(status, FfiStatus::Ok);
+    assert_eq!(sum, 5);
+    assert_eq!(message(&buf), "[Rust cdylib] The result (2 + 3) is 5!");
+    assert_eq!(required, message(&buf).len());
+}
+
+#[test]
+fn null_result_with_zero_length_queries_the_length() {
+    init();
+    let (mut sum, mut required) = (0, 0);
+    let status = unsafe { cdylib_add(2, 3, ptr::null_mut(), 0, &mut sum, &mut required) };
+    assert_eq!(status, FfiStatus::BufferTooSmall);
+    assert_eq!(sum, 5);
+    assert_eq!(required, "[Rust cdylib] The result (2 + 3) is 5!".len());
+}
+
+#[test]
+fn rejects_null_result_with_length() {
+    init();
+    let (mut sum, mut required) = (0, 0);
+    let status = unsafe { cdylib_add(2, 3, ptr::null_mut(), 16, &mut sum, &mut required) };
+    assert_eq!(status, FfiStatus::NullPointer);
+}
+
+#[test]
+fn rejects_null_sum() {
+    init();
+    let mut buf = name_buf(b"Lee", 64);
+    let status = unsafe {
+        cdylib_add(
+            2,
+            3,
+            buf.as_mut_ptr(),
+            buf.len(),
+            ptr::null_mut(),
+            ptr::null_mut(),
+        )
+    };
+    assert_eq!(status, FfiStatus::NullPointer);
+}
+
+#[test]
+fn required_len_may_be_null() {
+    init();
+    let mut buf = name_buf(b"Lee", 64);
+    let mut sum = 0;
+    let status =
+        unsafe { cdylib_add(2, 3, buf.as_mut_ptr(), buf.len(), &mut sum, ptr::null_mut()) };
+    assert_eq!(status, FfiStatus::Ok);
+    assert_eq!(sum, 5);
+}
+
+#[test]
+fn truncates_into_a_short_buffer() {
+    init();
+    let mut buf = name_buf(b"Lee", 8);
+    let (mut sum, mut required) = (0, 0);
+    let status = unsafe { add(2, 3, &mut buf, &mut sum, &mut required) };
+    assert_eq!(status, FfiStatus::BufferTooSmall);
+    assert_eq!(sum, 5);
+    assert_eq!(message(&buf), "[Rust c");
+    assert_eq!(buf[7], 0);
+
+    // 按报告的长度重新分配后第二次调用成功
+    // A second call succeeds once the buffer is reallocated with the reported length
+    let mut buf = name_buf(b"Lee", required + 1);
+    let status = unsafe { add(2, 3, &mut buf, &mut sum, &mut required) };
+    assert_eq!(status, FfiStatus::Ok);
+    assert_eq!(message(&buf).len(), required);
+}
+
+#[test]
+fn rejects_non_utf8_name() {
+    init();
+    let mut buf = name_buf(&[b'L', 0xff, 0xfe], 64);
+    let (mut sum, mut required) = (0, 0);
+    let status = unsafe { add(2, 3, &mut buf, &mut sum, &mut required) };
+    assert_eq!(status, FfiStatus::InvalidUtf8);
+    assert_eq!(buf[1] as u8, 0xff);
+}
+
+#[test]
+fn rejects_unterminated_name() {
+    init();
+    let mut buf = name_buf(b"Lee", 3);
+    let (mut sum, mut required) = (0, 0);
+    let status = unsafe { add(2, 3, &mut buf, &mut sum, &mut required) };
+    assert_eq!(status, FfiStatus::Unterminated);
+}
+
+#[test]
+fn reports_overflow_without_writing_outputs() {
+    init();
+    let mut buf = name_buf(b"Lee", 64);
+    let (mut sum, mut required) = (-1, 0);
+    for (a, b) in [(c_int::MAX, 1), (c_int::MIN, -1)] {
+        let status = unsafe { add(a, b, &mut buf, &mut sum, &mut required) };
+        assert_eq!(status, FfiStatus::Overflow);
+    }
+    assert_eq!(sum, -1);
+    assert_eq!(message(&buf), "Lee");
+}
+
+#[test]
+fn concurrent_calls_do_not_interfere() {
+    init();
+    let threads: Vec<_> = (0..8)
+        .map(|t| {
+            thread::spawn(move || {
+                for i in 0..200 {
+                    let mut buf = name_buf(format!("thread {t}").as_bytes(), 64);
+                    let (mut sum, mut required) = (0, 0);
+                    let status = unsafe { add(t, i, &mut buf, &mut sum, &mut required) };
+                    assert_eq!(status, FfiStatus::Ok);
+                    assert_eq!(sum, t + i);
+                    assert_eq!(
+                        message(&buf),
+                        format!("[Rust cdylib] The result ({t} + {i}) is {}!", t + i)
+                    );
+                }
+            })
+        })
+        .collect();
+    for thread in threads {
+        thread.join().unwrap();
+    }
+}
+
+#[test]
+fn v2_is_the_current_signature() {
+    init();
+    let mut buf = name_buf(b"Lee", 8);
+    let (mut sum, mut required) = (0, 0);
+    let status =
+        unsafe { cdylib_add_v2(2, 3, buf.as_mut_ptr(), buf.len(), &mut sum, &mut required) };
+    assert_eq!(status, FfiStatus::BufferTooSmall);
+    assert_eq!(sum, 5);
+    assert_eq!(message(&buf), "[Rust c");
+}
+
+// v1 按旧约定返回和，消息写进调用方保证足够大的缓冲区
+// v1 returns the sum the old way, writing the message into a buffer the caller promises is large
+// enough
+#[test]
+fn v1_shim_keeps_the_old_contract() {
+    init();
+    let mut buf = name_buf(b"Lee", 64);
+    assert_eq!(unsafe { cdylib_add_v1(2, 3, buf.as_mut_ptr()) }, 5);
+    assert_eq!(message(&buf), "[Rust cdylib] The result (2 + 3) is 5!");
+}
+
+#[test]
+fn v1_shim_returns_zero_on_failure() {
+    init();
+    let mut buf = name_buf(b"Lee", 64);
+    assert_eq!(unsafe { cdylib_add_v1(c_int::MAX, 1, buf.as_mut_ptr()) }, 0);
+    assert_eq!(message(&buf), "Lee");
+    assert_eq!(unsafe { cdylib_add_v1(2, 3, ptr::null_mut()) }, 0);
+}
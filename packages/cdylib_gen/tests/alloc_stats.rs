@@ -0,0 +1,69 @@
+// rustlib_alloc_stats 的计数器，以及 rustlib_shutdown 的泄漏检查能发现忘记释放的计算器
+// The counters behind rustlib_alloc_stats, and that the leak check of rustlib_shutdown finds a
+// calculator nobody freed
+
+use std::mem::size_of;
+use std::ptr;
+
+use cdylib_gen::{
+    calc_free, calc_new, rustlib_alloc_stats, rustlib_init, rustlib_shutdown, AllocStats,
+    Calculator, FfiStatus, InitConfig,
+};
+
+fn stats() -> AllocStats {
+    let mut stats = AllocStats::default();
+    assert_eq!(unsafe { rustlib_alloc_stats(&mut stats) }, FfiStatus::Ok);
+    stats
+}
+
+fn cycle(leak: bool) -> AllocStats {
+    let config = InitConfig {
+        leak_check: true,
+        ..InitConfig::default()
+    };
+    assert_eq!(unsafe { rustlib_init(&config) }, FfiStatus::Ok);
+    let calc = calc_new();
+    assert!(!calc.is_null());
+    if !leak {
+        assert_eq!(calc_free(calc), FfiStatus::Ok);
+    }
+    assert_eq!(rustlib_shutdown(), FfiStatus::Ok);
+    let stats = stats();
+    if leak {
+        // 关闭之后仍然可以释放计算器
+        // The calculator can still be freed after shutdown
+        assert_eq!(calc_free(calc), FfiStatus::Ok);
+    }
+    stats
+}
+
+// 计数器是进程全局的，所以不用测试框架，全部在主线程上按顺序检查
+// The counters are process-wide, so this runs without the test harness and checks everything in
+// order on the main thread
+fn main() {
+    assert_eq!(
+        unsafe { rustlib_alloc_stats(ptr::null_mut()) },
+        FfiStatus::NullPointer
+    );
+
+    let before = stats();
+    let block = vec![0u8; 4096];
+    let during = stats();
+    drop(block);
+    let after = stats();
+    assert!(during.allocations > before.allocations);
+    assert!(during.live_bytes >= before.live_bytes + 4096);
+    assert!(during.peak_bytes >= during.live_bytes);
+    assert!(after.deallocations > during.deallocations);
+    assert_eq!(after.live_blocks, after.allocations - after.deallocations);
+    assert_eq!(after.leaked_blocks, 0);
+
+    let clean = cycle(false);
+    assert_eq!(clean.leaked_blocks, 0);
+    assert_eq!(clean.leaked_bytes, 0);
+
+    let leaky = cycle(true);
+    assert!(leaky.leaked_blocks >= 1);
+    assert!(leaky.leaked_bytes >= size_of::<Calculator>() as u64);
+    println!("alloc_stats: ok");
+}
@@ -0,0 +1,66 @@
+// cdylib_range 的容量边界测试，以及 cdylib_sum_slice 的切片参数
+// Capacity boundary tests for cdylib_range, and the slice argument of cdylib_sum_slice
+
+use std::ptr;
+
+use cdylib_gen::{cdylib_range, cdylib_sum_slice, rustlib_init, FfiSlice, FfiStatus};
+
+#[test]
+fn fills_buffer_with_range() {
+    let mut out = [0; 4];
+    let count = unsafe { cdylib_range(3, 7, out.as_mut_ptr(), out.len()) };
+    assert_eq!(count, 4);
+    assert_eq!(out, [3, 4, 5, 6]);
+}
+
+#[test]
+fn stops_at_capacity_and_reports_full_count() {
+    let mut out = [0; 5];
+    let count = unsafe { cdylib_range(0, 10, out.as_mut_ptr(), 3) };
+    assert_eq!(count, 10);
+    assert_eq!(out, [0, 1, 2, 0, 0]);
+}
+
+#[test]
+fn null_buffer_queries_count() {
+    assert_eq!(unsafe { cdylib_range(-5, 5, ptr::null_mut(), 0) }, 10);
+}
+
+#[test]
+fn empty_and_reversed_ranges_write_nothing() {
+    let mut out = [9; 2];
+    assert_eq!(
+        unsafe { cdylib_range(4, 4, out.as_mut_ptr(), out.len()) },
+        0
+    );
+    assert_eq!(
+        unsafe { cdylib_range(4, 1, out.as_mut_ptr(), out.len()) },
+        0
+    );
+    assert_eq!(out, [9, 9]);
+}
+
+#[test]
+fn handles_full_int_range_without_overflow() {
+    let mut out = [0; 2];
+    let count = unsafe { cdylib_range(i32::MIN, i32::MAX, out.as_mut_ptr(), out.len()) };
+    assert_eq!(count, u32::MAX as usize);
+    assert_eq!(out, [i32::MIN, i32::MIN + 1]);
+}
+
+#[test]
+fn sums_an_ffi_slice() {
+    let status = unsafe { rustlib_init(ptr::null()) };
+    assert!(matches!(
+        status,
+        FfiStatus::Ok | FfiStatus::AlreadyInitialized
+    ));
+    let values = [5, 6, 7];
+    let mut total = 0;
+    let status = unsafe { cdylib_sum_slice(FfiSlice::new(&values), &mut total) };
+    assert_eq!(status, FfiStatus::Ok);
+    assert_eq!(total, 18);
+    let status = unsafe { cdylib_sum_slice(FfiSlice::empty(), &mut total) };
+    assert_eq!(status, FfiStatus::Ok);
+    assert_eq!(total, 0);
+}
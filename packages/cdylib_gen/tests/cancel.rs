@@ -0,0 +1,109 @@
+// cancel_token_* 和 cdylib_slow_sum 的测试：另一个线程取消令牌后调用提前返回 CANCELLED，
+// 不传令牌时照常算完
+// Tests for cancel_token_* and cdylib_slow_sum: once another thread cancels the token the call
+// returns CANCELLED early, without a token it runs to completion
+
+use std::ffi::{c_int, c_long};
+use std::ptr;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use cdylib_gen::{
+    cancel_token_cancel, cancel_token_free, cancel_token_new, cdylib_slow_sum, CancelTokenHandle,
+    FfiHandle, FfiStatus,
+};
+
+mod common;
+use common::init;
+
+fn slow_sum(values: &[c_int], delay_ms: u32, token: CancelTokenHandle) -> (FfiStatus, c_long) {
+    let mut total = -1;
+    let status =
+        unsafe { cdylib_slow_sum(values.as_ptr(), values.len(), delay_ms, token, &mut total) };
+    (status, total)
+}
+
+#[test]
+fn runs_to_completion_without_cancel() {
+    init();
+    let values = [1, 2, 3, 4];
+    assert_eq!(slow_sum(&values, 1, FfiHandle::NULL), (FfiStatus::Ok, 10));
+
+    let token = cancel_token_new();
+    assert!(!token.is_null());
+    assert_eq!(slow_sum(&values, 1, token), (FfiStatus::Ok, 10));
+    assert_eq!(cancel_token_free(token), FfiStatus::Ok);
+}
+
+// 完整运行要 10 秒，取消后应当在下一步就返回
+// A full run takes 10 seconds, after the cancel it should return at the next step
+#[test]
+fn cancel_from_another_thread_stops_early() {
+    init();
+    let token = cancel_token_new();
+    let values = vec![1; 1000];
+    let start = Instant::now();
+    let canceller = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(50));
+        cancel_token_cancel(token)
+    });
+    let (status, total) = slow_sum(&values, 10, token);
+    assert_eq!(canceller.join().unwrap(), FfiStatus::Ok);
+    assert_eq!((status, total), (FfiStatus::Cancelled, -1));
+    assert!(start.elapsed() < Duration::from_secs(5));
+    assert_eq!(cancel_token_free(token), FfiStatus::Ok);
+}
+
+#[test]
+fn cancelled_token_stays_cancelled() {
+    init();
+    let token = cancel_token_new();
+    assert_eq!(cancel_token_cancel(token), FfiStatus::Ok);
+    assert_eq!(cancel_token_cancel(token), FfiStatus::Ok);
+    assert_eq!(slow_sum(&[1, 2], 0, token), (FfiStatus::Cancelled, -1));
+    assert_eq!(slow_sum(&[1, 2], 0, token), (FfiStatus::Cancelled, -1));
+    // 没有工作要做时不会检查令牌
+    // The token is not checked when there is no work to do
+    assert_eq!(slow_sum(&[], 0, token), (FfiStatus::Ok, 0));
+    assert_eq!(cancel_token_free(token), FfiStatus::Ok);
+}
+
+#[test]
+fn rejects_null_arguments() {
+    init();
+    assert_eq!(
+        cancel_token_cancel(FfiHandle::NULL),
+        FfiStatus::InvalidHandle
+    );
+    assert_eq!(cancel_token_free(FfiHandle::NULL), FfiStatus::Ok);
+    let status = unsafe { cdylib_slow_sum(ptr::null(), 2, 0, FfiHandle::NULL, &mut 0) };
+    assert_eq!(status, FfiStatus::NullPointer);
+}
+
+// 释放后的令牌过期了：取消、重复释放和用它求和都返回 INVALID_HANDLE
+// A freed token is stale: cancelling it, freeing it again and summing with it return
+// INVALID_HANDLE
+#[test]
+fn rejects_stale_handles() {
+    init();
+    let token = cancel_token_new();
+    assert_eq!(cancel_token_free(token), FfiStatus::Ok);
+    assert_eq!(cancel_token_cancel(token), FfiStatus::InvalidHandle);
+    assert_eq!(cancel_token_free(token), FfiStatus::InvalidHandle);
+    assert_eq!(slow_sum(&[1, 2], 0, token), (FfiStatus::InvalidHandle, -1));
+}
+
+// 调用持有自己的引用，运行期间释放令牌是安全的，取消也不会再送达
+// A call holds a reference of its own, so freeing the token while it runs is safe, and no
+// cancel can reach it any more
+#[test]
+fn free_while_in_use() {
+    init();
+    let token = cancel_token_new();
+    let freer = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(20));
+        cancel_token_free(token)
+    });
+    assert_eq!(slow_sum(&[1; 10], 10, token), (FfiStatus::Ok, 10));
+    assert_eq!(freer.join().unwrap(), FfiStatus::Ok);
+}
@@ -0,0 +1,155 @@
+// 建造者风格的配置 API：默认值、setter 的参数检查、缺少名字时 build 失败而建造者仍然可用、build 用掉建造者，
+// 以及 Rust 端的 ConfigBuilder 本身
+// The builder-style configuration API: the defaults, the setters checking their arguments, build
+// failing without a name while the builder stays usable, build using up the builder, and the
+// Rust-side ConfigBuilder itself
+
+use std::ffi::{c_char, CStr};
+use std::ptr;
+
+use cdylib_gen::{
+    config_buffer_size, config_build, config_builder_free, config_free, config_name, config_new,
+    config_set_buffer_size, config_set_name, ConfigBuilder, ConfigHandle, FfiHandle, FfiStatus,
+    CONFIG_DEFAULT_BUFFER_SIZE, CONFIG_MAX_BUFFER_SIZE, CONFIG_MIN_BUFFER_SIZE,
+};
+use interop_common::FfiError;
+
+mod common;
+use common::init;
+
+fn name_of(config: ConfigHandle) -> String {
+    let mut buf = [0 as c_char; 64];
+    let status = unsafe { config_name(config, buf.as_mut_ptr(), buf.len(), ptr::null_mut()) };
+    assert_eq!(status, FfiStatus::Ok);
+    unsafe { CStr::from_ptr(buf.as_ptr()) }
+        .to_str()
+        .unwrap()
+        .to_owned()
+}
+
+fn buffer_size_of(config: ConfigHandle) -> usize {
+    let mut size = 0;
+    assert_eq!(
+        unsafe { config_buffer_size(config, &mut size) },
+        FfiStatus::Ok
+    );
+    size
+}
+
+#[test]
+fn builds_with_defaults_and_options() {
+    init();
+    let builder = config_new();
+    assert!(!builder.is_null());
+    assert_eq!(
+        unsafe { config_set_name(builder, c"defaults".as_ptr()) },
+        FfiStatus::Ok
+    );
+    let mut config = FfiHandle::NULL;
+    assert_eq!(unsafe { config_build(builder, &mut config) }, FfiStatus::Ok);
+    assert_eq!(name_of(config), "defaults");
+    assert_eq!(buffer_size_of(config), CONFIG_DEFAULT_BUFFER_SIZE);
+    assert_eq!(config_free(config), FfiStatus::Ok);
+
+    let builder = config_new();
+    assert_eq!(
+        unsafe { config_set_name(builder, c"first".as_ptr()) },
+        FfiStatus::Ok
+    );
+    assert_eq!(
+        unsafe { config_set_name(builder, c"second".as_ptr()) },
+        FfiStatus::Ok
+    );
+    assert_eq!(config_set_buffer_size(builder, 256), FfiStatus::Ok);
+    assert_eq!(unsafe { config_build(builder, &mut config) }, FfiStatus::Ok);
+    assert_eq!(name_of(config), "second");
+    assert_eq!(buffer_size_of(config), 256);
+    assert_eq!(config_free(config), FfiStatus::Ok);
+    assert_eq!(config_free(config), FfiStatus::InvalidHandle);
+}
+
+#[test]
+fn setters_reject_bad_arguments() {
+    init();
+    let builder = config_new();
+    assert_eq!(
+        unsafe { config_set_name(builder, ptr::null()) },
+        FfiStatus::NullPointer
+    );
+    assert_eq!(
+        unsafe { config_set_name(builder, c"".as_ptr()) },
+        FfiStatus::InvalidArgument
+    );
+    for size in [
+        0,
+        1000,
+        CONFIG_MIN_BUFFER_SIZE / 2,
+        CONFIG_MAX_BUFFER_SIZE * 2,
+    ] {
+        assert_eq!(
+            config_set_buffer_size(builder, size),
+            FfiStatus::InvalidArgument,
+            "size {size}"
+        );
+    }
+    for size in [CONFIG_MIN_BUFFER_SIZE, CONFIG_MAX_BUFFER_SIZE] {
+        assert_eq!(config_set_buffer_size(builder, size), FfiStatus::Ok);
+    }
+    assert_eq!(config_builder_free(builder), FfiStatus::Ok);
+    assert_eq!(config_builder_free(FfiHandle::NULL), FfiStatus::Ok);
+}
+
+#[test]
+fn failed_build_keeps_the_builder_and_success_uses_it_up() {
+    init();
+    let builder = config_new();
+    let mut config = FfiHandle::NULL;
+    assert_eq!(
+        unsafe { config_build(builder, ptr::null_mut()) },
+        FfiStatus::NullPointer
+    );
+    assert_eq!(
+        unsafe { config_build(builder, &mut config) },
+        FfiStatus::InvalidArgument
+    );
+    assert!(config.is_null());
+
+    // 一个失败的 setter 不会改变之前设置的值
+    // A failing setter doesn't change the value set before
+    assert_eq!(config_set_buffer_size(builder, 128), FfiStatus::Ok);
+    assert_eq!(
+        config_set_buffer_size(builder, 3),
+        FfiStatus::InvalidArgument
+    );
+    assert_eq!(
+        unsafe { config_set_name(builder, c"fixed".as_ptr()) },
+        FfiStatus::Ok
+    );
+    assert_eq!(unsafe { config_build(builder, &mut config) }, FfiStatus::Ok);
+    assert_eq!(buffer_size_of(config), 128);
+
+    assert_eq!(
+        unsafe { config_set_name(builder, c"late".as_ptr()) },
+        FfiStatus::InvalidHandle
+    );
+    let mut again = FfiHandle::NULL;
+    assert_eq!(
+        unsafe { config_build(builder, &mut again) },
+        FfiStatus::InvalidHandle
+    );
+    assert_eq!(config_builder_free(builder), FfiStatus::InvalidHandle);
+    assert_eq!(name_of(config), "fixed");
+    assert_eq!(config_free(config), FfiStatus::Ok);
+}
+
+#[test]
+fn rust_builder_chains() {
+    let mut builder = ConfigBuilder::new();
+    assert_eq!(builder.build(), Err(FfiError::InvalidArgument));
+    let config = builder
+        .name("chained")
+        .and_then(|builder| builder.buffer_size(512))
+        .and_then(|builder| builder.build())
+        .unwrap();
+    assert_eq!((config.name(), config.buffer_size()), ("chained", 512));
+}
@@ -0,0 +1,48 @@
+// 加载时构造函数的测试：它在测试开始之前就运行过一次，那时库还没有初始化
+// Tests for the load-time constructor: it ran once before any test started, when the library
+// wasn't initialized yet
+
+#![cfg(any(
+    target_os = "linux",
+    target_os = "android",
+    target_os = "freebsd",
+    target_vendor = "apple",
+    windows
+))]
+
+use cdylib_gen::{cdylib_load_report, rustlib_init, FfiStatus, LoadReport};
+
+fn load_report() -> LoadReport {
+    let mut report = LoadReport {
+        constructor_runs: 0,
+        initialized_at_load: true,
+    };
+    assert_eq!(unsafe { cdylib_load_report(&mut report) }, FfiStatus::Ok);
+    report
+}
+
+#[test]
+fn constructor_runs_once_before_initialization() {
+    let report = load_report();
+    assert_eq!(report.constructor_runs, 1);
+    assert!(!report.initialized_at_load);
+
+    // 之后的初始化不会改变构造函数当时看到的状态
+    // Initializing afterwards doesn't change what the constructor saw back then
+    let status = unsafe { rustlib_init(std::ptr::null()) };
+    assert!(matches!(
+        status,
+        FfiStatus::Ok | FfiStatus::AlreadyInitialized
+    ));
+    let report = load_report();
+    assert_eq!(report.constructor_runs, 1);
+    assert!(!report.initialized_at_load);
+}
+
+#[test]
+fn null_report_is_rejected() {
+    assert_eq!(
+        unsafe { cdylib_load_report(std::ptr::null_mut()) },
+        FfiStatus::NullPointer
+    );
+}
@@ -0,0 +1,113 @@
+// 构建出的动态库的导出表必须和 exports.txt（启用 jni 特性时加上 exports_jni.txt，32 位 Windows 上加上
+// exports_stdcall.txt）完全一致，由 dylib_inspect 读取
+// The export table of the built dynamic library must match exports.txt (plus exports_jni.txt with
+// the jni feature and exports_stdcall.txt on 32-bit Windows) exactly, as read by dylib_inspect
+
+use std::collections::BTreeSet;
+use std::env::{self, consts};
+
+use dylib_inspect::{inspect_file, Inspection};
+
+const EXPORTS: &str = include_str!("../exports.txt");
+#[cfg(feature = "jni")]
+const EXPORTS_JNI: &str = include_str!("../exports_jni.txt");
+#[cfg(all(windows, target_arch = "x86"))]
+const EXPORTS_STDCALL: &str = include_str!("../exports_stdcall.txt");
+
+fn allowed() -> BTreeSet<String> {
+    let lists = [
+        EXPORTS,
+        #[cfg(feature = "jni")]
+        EXPORTS_JNI,
+        #[cfg(all(windows, target_arch = "x86"))]
+        EXPORTS_STDCALL,
+    ];
+    lists
+        .iter()
+        .flat_map(|list| list.lines())
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::to_owned)
+        .collect()
+}
+
+// 集成测试和 cdylib 一起放在 target/{profile}/deps 中
+// Integration tests live next to the cdylib in target/{profile}/deps
+fn inspect() -> Inspection {
+    let dir = env::current_exe().unwrap().parent().unwrap().to_owned();
+    let path = dir.join(format!(
+        "{}cdylib_gen{}",
+        consts::DLL_PREFIX,
+        consts::DLL_SUFFIX
+    ));
+    inspect_file(&path).unwrap_or_else(|err| panic!("{}: {}", path.display(), err))
+}
+
+#[test]
+fn exports_match_the_allow_list() {
+    let allowed = allowed();
+    let exported = inspect().exports;
+    let unexpected: Vec<_> = exported.difference(&allowed).collect();
+    let missing: Vec<_> = allowed.difference(&exported).collect();
+    assert!(
+        unexpected.is_empty() && missing.is_empty(),
+        "exported but not in exports.txt: {:?}, listed but not exported: {:?}",
+        unexpected,
+        missing
+    );
+}
+
+// 不带前缀的 C 名字（add、calc_new 之类）很容易和进程中其他库的符号重名；JNI 的名字由 JVM 决定，不在此列
+// Unprefixed C names (add, calc_new and the like) easily clash with the symbols of other libraries
+// in the process; the JNI names are decided by the JVM and are not part of this
+#[test]
+fn c_exports_carry_the_prefix() {
+    let unprefixed: Vec<_> = [
+        EXPORTS,
+        #[cfg(all(windows, target_arch = "x86"))]
+        EXPORTS_STDCALL,
+    ]
+    .iter()
+    .flat_map(|list| list.lines())
+    .filter(|line| !line.is_empty() && !line.starts_with('#'))
+    .filter(|name| !name.starts_with("rxc_"))
+    .collect();
+    assert!(
+        unprefixed.is_empty(),
+        "exports without the rxc_ prefix: {:?}",
+        unprefixed
+    );
+}
+
+// 只有 cargo xtask install 和 android 设置 CDYLIB_GEN_SONAME 时库才有 SONAME
+// The library only has a SONAME when cargo xtask install or android sets CDYLIB_GEN_SONAME
+#[test]
+#[cfg(target_os = "linux")]
+fn soname_follows_the_build_environment() {
+    assert_eq!(inspect().name.as_deref(), option_env!("CDYLIB_GEN_SONAME"));
+}
+
+// 库只依赖 C 运行时和它自带的库，不会把构建机上别的 .so 带给调用方
+// The library only depends on the C runtime and the libraries shipped with it, bringing no other
+// .so of the build machine along to its callers
+#[test]
+#[cfg(all(target_os = "linux", target_env = "gnu"))]
+fn depends_only_on_the_c_runtime() {
+    const RUNTIME: [&str; 6] = [
+        "libc.so",
+        "libm.so",
+        "libgcc_s.so",
+        "libdl.so",
+        "libpthread.so",
+        "ld-linux",
+    ];
+    let unexpected: Vec<_> = inspect()
+        .dependencies
+        .into_iter()
+        .filter(|lib| !RUNTIME.iter().any(|runtime| lib.starts_with(runtime)))
+        .collect();
+    assert!(
+        unexpected.is_empty(),
+        "unexpected dependencies: {:?}",
+        unexpected
+    );
+}
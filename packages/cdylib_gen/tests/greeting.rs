@@ -0,0 +1,37 @@
+// cdylib_make_greeting_str：名字以不带 NUL 的 FfiStr 传入
+// cdylib_make_greeting_str: the name is passed as an FfiStr without a NUL
+
+use std::ffi::CStr;
+
+use cdylib_gen::{cdylib_make_greeting_str, cdylib_string_free, FfiStr};
+
+mod common;
+use common::init;
+
+#[test]
+fn greets_part_of_a_longer_string() {
+    init();
+    let names = "Li,Wang";
+    let greeting = unsafe { cdylib_make_greeting_str(FfiStr::new(&names[..2])) };
+    assert!(!greeting.is_null());
+    unsafe {
+        assert_eq!(
+            CStr::from_ptr(greeting).to_str(),
+            Ok("[Rust cdylib] Hello Li, nice to meet you!")
+        );
+        cdylib_string_free(greeting);
+    }
+}
+
+#[test]
+fn rejects_interior_nul_and_invalid_utf8() {
+    init();
+    let greeting = unsafe { cdylib_make_greeting_str(FfiStr::new("Li\0Wang")) };
+    assert!(greeting.is_null());
+    let bytes = [0xffu8];
+    let invalid = FfiStr {
+        ptr: bytes.as_ptr().cast(),
+        len: bytes.len(),
+    };
+    assert!(unsafe { cdylib_make_greeting_str(invalid) }.is_null());
+}
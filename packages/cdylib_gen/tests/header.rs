@@ -0,0 +1,13 @@
+// 生成的头文件必须和仓库中提交的 include/cdylib_gen.h 一致
+// The generated header must match the committed include/cdylib_gen.h
+
+const GENERATED: &str = include_str!(concat!(env!("OUT_DIR"), "/cdylib_gen.h"));
+const COMMITTED: &str = include_str!("../../../include/cdylib_gen.h");
+
+#[test]
+fn header_matches_committed_copy() {
+    assert!(
+        GENERATED == COMMITTED,
+        "include/cdylib_gen.h is out of date, rebuild with UPDATE_HEADERS=1 to refresh it"
+    );
+}
@@ -0,0 +1,119 @@
+// 同一个库接受不同版本的 InitConfig：第 1 版只有 size 和日志回调，紧跟在它后面的字节即使看起来像打开了
+// panic hook 也不会被读取；第 2 版的 panic hook 生效；不属于任何版本的 size 被拒绝，库保持未初始化
+// The same library accepts different versions of InitConfig: version 1 only has size and the log
+// callback, and the bytes right after it aren't read even when they look like the panic hook is
+// switched on; version 2's panic hook takes effect; a size of no version is rejected and the
+// library stays uninitialized
+
+use std::ffi::{c_char, c_long, c_void};
+use std::sync::Mutex;
+use std::{mem, ptr, slice};
+
+use cdylib_gen::{
+    cdylib_sum, rustlib_init, rustlib_shutdown, FfiStatus, InitConfig, LogCallback, LogLevel,
+};
+use interop_common::{ffi_guard, take_last_panic, INIT_CONFIG_SIZE_V1, INIT_CONFIG_SIZE_V2};
+
+/// 为第 1 版头文件编译的调用方看到的 InitConfig
+/// InitConfig as seen by a caller compiled against the version 1 header.
+#[repr(C)]
+struct InitConfigV1 {
+    size: usize,
+    log_callback: LogCallback,
+    log_user_data: *mut c_void,
+}
+
+/// 第 1 版的结构体，后面跟着库不应读取的字节
+/// A version 1 struct followed by bytes the library must not read.
+#[repr(C)]
+struct V1WithTrailer {
+    config: InitConfigV1,
+    trailer: [u8; 16],
+}
+
+type Received = Mutex<Vec<String>>;
+
+unsafe extern "C" fn collect(
+    _level: LogLevel,
+    msg: *const c_char,
+    len: usize,
+    user_data: *mut c_void,
+) {
+    let received = &*(user_data as *const Received);
+    let msg = String::from_utf8_lossy(slice::from_raw_parts(msg.cast::<u8>(), len));
+    received.lock().unwrap().push(msg.into_owned());
+}
+
+fn sum() -> FfiStatus {
+    let mut total: c_long = 0;
+    unsafe { cdylib_sum([1, 2].as_ptr(), 2, &mut total) }
+}
+
+fn panic_message() -> String {
+    assert_eq!(ffi_guard(|| panic!("boom")), FfiStatus::Panic);
+    take_last_panic().unwrap()
+}
+
+#[test]
+fn version_sizes() {
+    assert_eq!(INIT_CONFIG_SIZE_V1, mem::size_of::<InitConfigV1>());
+    assert_eq!(INIT_CONFIG_SIZE_V2, mem::size_of::<InitConfig>());
+    assert_eq!(mem::offset_of!(V1WithTrailer, trailer), INIT_CONFIG_SIZE_V1);
+    assert_eq!(InitConfig::default().size, INIT_CONFIG_SIZE_V2);
+}
+
+// 初始化状态和 panic hook 是进程全局的，所以全部放在一个测试里按顺序检查；panic hook 装上之后不会卸载，
+// 第 1 版必须先测
+// The initialization state and the panic hook are process-wide, so everything is checked in order
+// within a single test; once installed the panic hook stays, so version 1 has to come first
+#[test]
+fn accepts_v1_and_v2_configs() {
+    for size in [0, INIT_CONFIG_SIZE_V1 - 8, INIT_CONFIG_SIZE_V2 + 8] {
+        let config = InitConfig {
+            size,
+            ..InitConfig::default()
+        };
+        assert_eq!(
+            unsafe { rustlib_init(&config) },
+            FfiStatus::UnsupportedVersion,
+            "size {size}"
+        );
+        assert_eq!(sum(), FfiStatus::NotInitialized);
+    }
+
+    let received = Received::default();
+    let user_data = &received as *const Received as *mut c_void;
+    let mut v1 = V1WithTrailer {
+        config: InitConfigV1 {
+            size: INIT_CONFIG_SIZE_V1,
+            log_callback: Some(collect),
+            log_user_data: user_data,
+        },
+        trailer: [0; 16],
+    };
+    // 在第 2 版中这个字节是 install_panic_hook
+    // In version 2 this byte is install_panic_hook
+    v1.trailer[0] = 1;
+    let config = ptr::addr_of!(v1).cast::<InitConfig>();
+    assert_eq!(unsafe { rustlib_init(config) }, FfiStatus::Ok);
+    assert_eq!(sum(), FfiStatus::Ok);
+    log::info!("[Rust test] v1");
+    assert_eq!(panic_message(), "boom");
+    assert_eq!(rustlib_shutdown(), FfiStatus::Ok);
+
+    let config = InitConfig {
+        log_callback: Some(collect),
+        log_user_data: user_data,
+        install_panic_hook: true,
+        worker_threads: 1,
+        ..InitConfig::default()
+    };
+    assert_eq!(unsafe { rustlib_init(&config) }, FfiStatus::Ok);
+    log::info!("[Rust test] v2");
+    assert!(panic_message().starts_with("boom at "));
+    assert_eq!(rustlib_shutdown(), FfiStatus::Ok);
+
+    let received = received.into_inner().unwrap();
+    assert!(received.iter().any(|msg| msg == "[Rust test] v1"));
+    assert!(received.iter().any(|msg| msg == "[Rust test] v2"));
+}
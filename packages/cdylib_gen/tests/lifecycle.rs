@@ -0,0 +1,120 @@
+// rustlib_init 之前和 rustlib_shutdown 之后导出函数返回 NOT_INITIALIZED，shutdown 会等待异步回调
+// Exports return NOT_INITIALIZED before rustlib_init and after rustlib_shutdown, and shutdown
+// waits for the asynchronous callbacks
+
+use std::ffi::{c_int, c_long, c_void};
+use std::ptr;
+use std::sync::Mutex;
+
+use cdylib_gen::{
+    async_submit, calc_free, calc_new, cancel_token_new, cdylib_abi_version, cdylib_add_async,
+    cdylib_sum, pool_create, queue_new, rustlib_init, rustlib_shutdown, AsyncRequest, FfiStatus,
+    InitConfig, CDYLIB_ABI_VERSION,
+};
+
+extern "C" fn record(sum: c_int, user_data: *mut c_void) {
+    let sums = unsafe { &*(user_data as *const Mutex<Vec<c_int>>) };
+    sums.lock().unwrap().push(sum);
+}
+
+extern "C" fn ignore_item(_: c_int, _: i64, _: *mut c_void) {}
+
+extern "C" fn record_async(_: FfiStatus, sum: c_int, user_data: *mut c_void) {
+    record(sum, user_data);
+}
+
+// 在工作线程上调用 shutdown，记下它的状态
+// Calls shutdown on a worker thread and records its status
+extern "C" fn shutdown_inside(_: c_int, user_data: *mut c_void) {
+    let status = unsafe { &*(user_data as *const Mutex<Option<FfiStatus>>) };
+    *status.lock().unwrap() = Some(rustlib_shutdown());
+}
+
+fn sum() -> FfiStatus {
+    let mut total: c_long = 0;
+    unsafe { cdylib_sum([1, 2].as_ptr(), 2, &mut total) }
+}
+
+// 初始化状态是进程全局的，所以全部放在一个测试里按顺序检查
+// The initialization state is process-wide, so everything is checked in order within a single test
+#[test]
+fn exports_follow_init_and_shutdown() {
+    assert_eq!(sum(), FfiStatus::NotInitialized);
+    assert!(calc_new().is_null());
+    assert!(pool_create(1).is_null());
+    assert!(cancel_token_new().is_null());
+    assert!(queue_new(Some(ignore_item), ptr::null_mut()).is_null());
+    assert_eq!(cdylib_abi_version(), CDYLIB_ABI_VERSION);
+    assert_eq!(rustlib_shutdown(), FfiStatus::NotInitialized);
+
+    let config = InitConfig {
+        worker_threads: 2,
+        ..InitConfig::default()
+    };
+    assert_eq!(unsafe { rustlib_init(&config) }, FfiStatus::Ok);
+    assert_eq!(
+        unsafe { rustlib_init(ptr::null()) },
+        FfiStatus::AlreadyInitialized
+    );
+    assert_eq!(sum(), FfiStatus::Ok);
+    let calc = calc_new();
+    assert!(!calc.is_null());
+
+    let sums = Mutex::new(Vec::<c_int>::new());
+    let user_data = &sums as *const _ as *mut c_void;
+    for i in 0..8 {
+        assert_eq!(
+            cdylib_add_async(i, 1, Some(record), user_data),
+            FfiStatus::Ok
+        );
+    }
+    // 回调里的 shutdown 会回收自己所在的线程，它被拒绝，库保持初始化
+    // A shutdown from a callback would join its own thread, so it is refused and the library stays
+    // initialized
+    let inside = Mutex::new(None);
+    assert_eq!(
+        cdylib_add_async(
+            0,
+            0,
+            Some(shutdown_inside),
+            &inside as *const _ as *mut c_void
+        ),
+        FfiStatus::Ok
+    );
+    // 运行时上的请求还在等待时 shutdown 也会等它
+    // Shutdown also waits for a request still sleeping on the runtime
+    let request = AsyncRequest {
+        a: 8,
+        b: 1,
+        delay_ms: 50,
+    };
+    assert_eq!(
+        async_submit(request, Some(record_async), user_data),
+        FfiStatus::Ok
+    );
+
+    // shutdown 返回时所有排队的回调都已经运行过
+    // By the time shutdown returns every queued callback has run
+    assert_eq!(rustlib_shutdown(), FfiStatus::Ok);
+    let mut sums = sums.into_inner().unwrap();
+    sums.sort();
+    assert_eq!(sums, (1..=9).collect::<Vec<_>>());
+    assert_eq!(inside.into_inner().unwrap(), Some(FfiStatus::WouldDeadlock));
+
+    assert_eq!(sum(), FfiStatus::NotInitialized);
+    assert_eq!(
+        cdylib_add_async(1, 2, Some(record), ptr::null_mut()),
+        FfiStatus::NotInitialized
+    );
+    assert_eq!(
+        async_submit(request, Some(record_async), ptr::null_mut()),
+        FfiStatus::NotInitialized
+    );
+    // shutdown 之前创建的句柄仍然可以释放
+    // Handles created before shutdown can still be freed
+    assert_eq!(calc_free(calc), FfiStatus::Ok);
+
+    assert_eq!(unsafe { rustlib_init(ptr::null()) }, FfiStatus::Ok);
+    assert_eq!(sum(), FfiStatus::Ok);
+    assert_eq!(rustlib_shutdown(), FfiStatus::Ok);
+}
@@ -0,0 +1,63 @@
+// malloc 特性的测试：库和 C 共用同一个堆，C 的 free 可以释放库返回的字符串，rust_free 也能释放 malloc 的内存
+// Tests for the malloc feature: the library and C share one heap, so C's free releases strings
+// the library returns and rust_free releases malloc's memory
+//
+// 运行 / Run: cargo test -p cdylib_gen --features malloc --test malloc
+
+#![cfg(feature = "malloc")]
+
+use std::ffi::{c_void, CStr};
+use std::ptr;
+
+use cdylib_gen::{cdylib_make_greeting, rust_alloc, rust_free, rustlib_init, FfiStatus};
+
+mod common;
+use common::init;
+
+extern "C" {
+    fn malloc(size: usize) -> *mut c_void;
+    fn free(ptr: *mut c_void);
+}
+
+#[test]
+fn c_frees_a_rust_allocated_string() {
+    init();
+    let greeting = unsafe { cdylib_make_greeting(c"Li".as_ptr()) };
+    assert!(!greeting.is_null());
+    unsafe {
+        assert!(CStr::from_ptr(greeting).to_str().unwrap().contains("Li"));
+        free(greeting.cast());
+    }
+}
+
+#[test]
+fn c_frees_rust_alloc_blocks() {
+    for align in [1, 8, 16, 64] {
+        let ptr = rust_alloc(100, align);
+        assert!(!ptr.is_null());
+        assert_eq!(ptr as usize % align, 0, "align {}", align);
+        unsafe { free(ptr) };
+    }
+}
+
+#[test]
+fn rust_free_releases_malloc_blocks() {
+    unsafe {
+        let ptr = malloc(32);
+        assert!(!ptr.is_null());
+        ptr::write_bytes(ptr.cast::<u8>(), 0xAB, 32);
+        rust_free(ptr);
+    }
+}
+
+#[test]
+fn rust_collections_use_the_c_heap() {
+    // 全局分配器换成了 malloc，Vec 和 String 照常工作
+    // With malloc as the global allocator Vec and String work as usual
+    let mut values: Vec<u64> = (0..10_000).collect();
+    values.truncate(100);
+    values.shrink_to_fit();
+    let text = values.iter().map(u64::to_string).collect::<String>();
+    assert_eq!(values.iter().sum::<u64>(), 4950);
+    assert!(text.starts_with("0123"));
+}
@@ -0,0 +1,85 @@
+// rust_alloc/rust_realloc/rust_free 的测试，以及库返回的字符串必须、也能够用 rust_free 释放；
+// 也可以在 Miri 下运行，检查每块内存都按分配时的布局释放
+// Tests for rust_alloc/rust_realloc/rust_free, and that strings the library returns must and can
+// be released with rust_free; they also run under Miri, which checks every block is freed with
+// the layout it was allocated with
+//
+// 运行 / Run: cargo +nightly miri test -p cdylib_gen --test memory
+
+use std::ffi::{c_void, CStr};
+use std::ptr;
+
+use cdylib_gen::{
+    cdylib_make_greeting, cdylib_string_free, rust_alloc, rust_free, rust_realloc, rustlib_init,
+    rustlib_shutdown, FfiStatus,
+};
+
+#[test]
+fn blocks_honour_the_alignment() {
+    for align in [1, 2, 8, 16, 64, 4096] {
+        for size in [0, 1, 7, 100] {
+            let ptr = rust_alloc(size, align);
+            assert!(!ptr.is_null());
+            assert_eq!(ptr as usize % align, 0, "align {}", align);
+            unsafe {
+                ptr::write_bytes(ptr.cast::<u8>(), 0xAB, size);
+                rust_free(ptr);
+            }
+        }
+    }
+}
+
+// malloc 特性下 rust_realloc 就是 realloc，不保留更大的对齐
+// With the malloc feature rust_realloc is realloc, which doesn't keep larger alignments
+#[cfg(not(feature = "malloc"))]
+#[test]
+fn realloc_keeps_contents_and_alignment() {
+    let ptr = rust_alloc(4, 64).cast::<u8>();
+    unsafe {
+        ptr.copy_from(b"abcd".as_ptr(), 4);
+        let grown = rust_realloc(ptr.cast(), 10_000).cast::<u8>();
+        assert!(!grown.is_null());
+        assert_eq!(grown as usize % 64, 0);
+        assert_eq!(std::slice::from_raw_parts(grown, 4), b"abcd");
+
+        let shrunk = rust_realloc(grown.cast(), 2).cast::<u8>();
+        assert_eq!(std::slice::from_raw_parts(shrunk, 2), b"ab");
+        rust_free(shrunk.cast());
+    }
+}
+
+#[test]
+fn realloc_of_null_allocates() {
+    let ptr = unsafe { rust_realloc(ptr::null_mut(), 32) };
+    assert!(!ptr.is_null());
+    assert_eq!(ptr as usize % (2 * std::mem::align_of::<usize>()), 0);
+    unsafe { rust_free(ptr) };
+}
+
+#[test]
+fn rejects_invalid_layouts() {
+    assert!(rust_alloc(8, 0).is_null());
+    assert!(rust_alloc(8, 3).is_null());
+    assert!(rust_alloc(usize::MAX, 8).is_null());
+    unsafe { rust_free(ptr::null_mut()) };
+}
+
+// cdylib_make_greeting 的结果用 rust_free 释放，旧的 cdylib_string_free 仍然等价；只有这个测试
+// 需要初始化，最后关闭库，Miri 才不会报告还在运行的工作线程
+// cdylib_make_greeting's result is released with rust_free, the older cdylib_string_free is
+// still equivalent; only this test needs the library initialized, and it shuts it down at the end
+// so Miri doesn't report the worker threads still running
+#[test]
+fn returned_strings_go_back_to_rust_free() {
+    assert_eq!(unsafe { rustlib_init(ptr::null()) }, FfiStatus::Ok);
+    let name = c"Li".as_ptr();
+    let greeting = unsafe { cdylib_make_greeting(name) };
+    assert!(!greeting.is_null());
+    let text = unsafe { CStr::from_ptr(greeting) }.to_str().unwrap();
+    assert_eq!(text, "[Rust cdylib] Hello Li, nice to meet you!");
+    unsafe { rust_free(greeting.cast::<c_void>()) };
+
+    let greeting = unsafe { cdylib_make_greeting(name) };
+    unsafe { cdylib_string_free(greeting) };
+    assert_eq!(rustlib_shutdown(), FfiStatus::Ok);
+}
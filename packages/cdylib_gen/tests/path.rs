@@ -0,0 +1,122 @@
+// cdylib_read_file 的路径以平台原生的编码传入：不是 UTF-8 的文件名也能打开，CStr -> &str 的转换却会拒绝它
+// cdylib_read_file takes paths in the platform's native encoding: a file name that isn't UTF-8
+// still opens, while converting it with CStr -> &str is rejected
+
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::{env, fs, process};
+
+use cdylib_gen::{cdylib_read_file, FfiStatus, PathChar};
+
+mod common;
+use common::init;
+
+// 路径的原生编码加上结尾的 NUL
+// The native encoding of a path plus its NUL terminator
+#[cfg(unix)]
+fn native(path: &Path) -> Vec<PathChar> {
+    use std::os::unix::ffi::OsStrExt;
+    let bytes = path.as_os_str().as_bytes().iter();
+    bytes.map(|&b| b as PathChar).chain([0]).collect()
+}
+
+#[cfg(windows)]
+fn native(path: &Path) -> Vec<PathChar> {
+    use std::os::windows::ffi::OsStrExt;
+    path.as_os_str().encode_wide().chain([0]).collect()
+}
+
+fn temp_file(name: OsString, contents: &[u8]) -> PathBuf {
+    let mut file_name = OsString::from(format!("rxc_path_{}_", process::id()));
+    file_name.push(name);
+    let path = env::temp_dir().join(file_name);
+    fs::write(&path, contents).unwrap();
+    path
+}
+
+unsafe fn read(path: &Path, out: &mut [u8], file_len: &mut usize) -> FfiStatus {
+    let path = native(path);
+    cdylib_read_file(path.as_ptr(), out.as_mut_ptr(), out.len(), file_len)
+}
+
+#[test]
+fn reads_a_file() {
+    init();
+    let path = temp_file("文件.txt".into(), b"contents");
+    let mut out = [0; 16];
+    let mut file_len = 0;
+    assert_eq!(
+        unsafe { read(&path, &mut out, &mut file_len) },
+        FfiStatus::Ok
+    );
+    assert_eq!(&out[..file_len], b"contents");
+    fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn truncates_to_the_buffer() {
+    init();
+    let path = temp_file("short.txt".into(), b"contents");
+    let mut out = [0; 4];
+    let mut file_len = 0;
+    let status = unsafe { read(&path, &mut out, &mut file_len) };
+    assert_eq!(status, FfiStatus::BufferTooSmall);
+    assert_eq!((&out, file_len), (b"cont", 8));
+    fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn missing_file_is_an_io_error() {
+    init();
+    let path = env::temp_dir().join(format!("rxc_path_{}_missing", process::id()));
+    let mut file_len = 0;
+    assert_eq!(
+        unsafe { read(&path, &mut [], &mut file_len) },
+        FfiStatus::Io
+    );
+}
+
+// Linux 的文件名可以是任意字节；macOS 的文件系统要求 UTF-8，所以不在那里运行
+// File names on Linux can be any bytes; macOS file systems require UTF-8, so this doesn't run there
+#[test]
+#[cfg(target_os = "linux")]
+fn opens_a_name_that_is_not_utf8() {
+    use std::ffi::{CStr, OsStr};
+    use std::os::unix::ffi::OsStrExt;
+
+    init();
+    let path = temp_file(OsStr::from_bytes(b"caf\xe9.txt").into(), b"latin-1");
+    let native = native(&path);
+    // 按 &str 处理的绑定在这里就已经失败了
+    // A binding going through &str already fails here
+    assert!(unsafe { CStr::from_ptr(native.as_ptr()) }.to_str().is_err());
+    let mut out = [0; 16];
+    let mut file_len = 0;
+    assert_eq!(
+        unsafe { read(&path, &mut out, &mut file_len) },
+        FfiStatus::Ok
+    );
+    assert_eq!(&out[..file_len], b"latin-1");
+    fs::remove_file(path).unwrap();
+}
+
+// Windows 的文件名可以含有不成对的代理项，它同样不能转换成 &str
+// File names on Windows can contain unpaired surrogates, which can't become a &str either
+#[test]
+#[cfg(windows)]
+fn opens_a_name_with_an_unpaired_surrogate() {
+    use std::os::windows::ffi::OsStringExt;
+
+    init();
+    let name = OsString::from_wide(&[0x61, 0xD800, 0x2E, 0x74]);
+    assert!(name.to_str().is_none());
+    let path = temp_file(name, b"utf-16");
+    let mut out = [0; 16];
+    let mut file_len = 0;
+    assert_eq!(
+        unsafe { read(&path, &mut out, &mut file_len) },
+        FfiStatus::Ok
+    );
+    assert_eq!(&out[..file_len], b"utf-16");
+    fs::remove_file(path).unwrap();
+}
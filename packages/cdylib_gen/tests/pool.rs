@@ -0,0 +1,184 @@
+// pool_create/pool_submit/pool_join/pool_free 的测试：任务和完成回调都在池的线程上运行，
+// join 之后池可以继续使用，free 会先运行排队的任务
+// Tests for pool_create/pool_submit/pool_join/pool_free: jobs and completion callbacks run on the
+// pool's threads, the pool stays usable after a join and free runs the queued jobs first
+
+use std::ffi::{c_int, c_void};
+use std::sync::atomic::{AtomicI32, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+
+use cdylib_gen::{pool_create, pool_free, pool_join, pool_submit, FfiHandle, FfiStatus};
+
+mod common;
+use common::init;
+
+// 计数器加一，返回加之前的值
+// Increments the counter and returns its previous value
+extern "C" fn count(user_data: *mut c_void) -> c_int {
+    let counter = unsafe { &*(user_data as *const AtomicI32) };
+    counter.fetch_add(1, Ordering::SeqCst)
+}
+
+extern "C" fn slow_count(user_data: *mut c_void) -> c_int {
+    thread::sleep(Duration::from_millis(20));
+    count(user_data)
+}
+
+// 记录结果和运行完成回调的线程名
+// Records the result and the name of the thread running the completion callback
+struct Results {
+    counter: AtomicI32,
+    done: Mutex<Vec<(c_int, String)>>,
+}
+
+extern "C" fn count_results(user_data: *mut c_void) -> c_int {
+    let results = unsafe { &*(user_data as *const Results) };
+    results.counter.fetch_add(1, Ordering::SeqCst)
+}
+
+extern "C" fn record(result: c_int, user_data: *mut c_void) {
+    let results = unsafe { &*(user_data as *const Results) };
+    let name = thread::current().name().unwrap_or_default().to_owned();
+    results.done.lock().unwrap().push((result, name));
+}
+
+// 在池自己的任务里 join 或 free 这个池，记下两个状态
+// Joins and frees the pool from one of its own jobs, recording both statuses
+struct Inside {
+    pool: FfiHandle,
+    statuses: Mutex<Vec<FfiStatus>>,
+}
+
+extern "C" fn join_and_free_inside(user_data: *mut c_void) -> c_int {
+    let inside = unsafe { &*(user_data as *const Inside) };
+    let mut statuses = inside.statuses.lock().unwrap();
+    statuses.push(pool_join(inside.pool));
+    statuses.push(pool_free(inside.pool));
+    0
+}
+
+#[test]
+fn runs_every_job_and_callback() {
+    init();
+    let pool = pool_create(4);
+    assert!(!pool.is_null());
+    let results = Results {
+        counter: AtomicI32::new(0),
+        done: Mutex::new(Vec::new()),
+    };
+    let user_data = &results as *const _ as *mut c_void;
+    for _ in 0..100 {
+        let status = pool_submit(pool, Some(count_results), Some(record), user_data);
+        assert_eq!(status, FfiStatus::Ok);
+    }
+    assert_eq!(pool_join(pool), FfiStatus::Ok);
+
+    let mut done = results.done.into_inner().unwrap();
+    assert!(done
+        .iter()
+        .all(|(_, name)| name.starts_with("cdylib_gen pool ")));
+    done.sort();
+    let results: Vec<c_int> = done.into_iter().map(|(result, _)| result).collect();
+    assert_eq!(results, (0..100).collect::<Vec<_>>());
+    assert_eq!(pool_free(pool), FfiStatus::Ok);
+}
+
+#[test]
+fn can_be_reused_after_join() {
+    init();
+    let pool = pool_create(0);
+    assert!(!pool.is_null());
+    let counter = AtomicI32::new(0);
+    let user_data = &counter as *const _ as *mut c_void;
+    for round in 1..=3 {
+        for _ in 0..10 {
+            let status = pool_submit(pool, Some(count), None, user_data);
+            assert_eq!(status, FfiStatus::Ok);
+        }
+        assert_eq!(pool_join(pool), FfiStatus::Ok);
+        assert_eq!(counter.load(Ordering::SeqCst), round * 10);
+    }
+    assert_eq!(pool_free(pool), FfiStatus::Ok);
+}
+
+#[test]
+fn free_runs_the_queued_jobs() {
+    init();
+    let pool = pool_create(1);
+    let counter = AtomicI32::new(0);
+    let user_data = &counter as *const _ as *mut c_void;
+    for _ in 0..5 {
+        let status = pool_submit(pool, Some(slow_count), None, user_data);
+        assert_eq!(status, FfiStatus::Ok);
+    }
+    assert_eq!(pool_free(pool), FfiStatus::Ok);
+    assert_eq!(counter.load(Ordering::SeqCst), 5);
+}
+
+#[test]
+fn rejects_null_arguments() {
+    init();
+    let counter = AtomicI32::new(0);
+    let user_data = &counter as *const _ as *mut c_void;
+    let status = pool_submit(FfiHandle::NULL, Some(count), None, user_data);
+    assert_eq!(status, FfiStatus::InvalidHandle);
+    assert_eq!(pool_join(FfiHandle::NULL), FfiStatus::InvalidHandle);
+
+    let pool = pool_create(1);
+    let status = pool_submit(pool, None, None, user_data);
+    assert_eq!(status, FfiStatus::NullPointer);
+    assert_eq!(pool_free(pool), FfiStatus::Ok);
+    assert_eq!(pool_free(FfiHandle::NULL), FfiStatus::Ok);
+    assert_eq!(counter.load(Ordering::SeqCst), 0);
+}
+
+// 释放后的句柄过期了：再次使用或释放都返回 INVALID_HANDLE，之后创建的池不受影响
+// A freed handle is stale: using or freeing it again returns INVALID_HANDLE, and pools created
+// later are not affected
+#[test]
+fn rejects_stale_handles() {
+    init();
+    let counter = AtomicI32::new(0);
+    let user_data = &counter as *const _ as *mut c_void;
+    let stale = pool_create(1);
+    assert_eq!(pool_free(stale), FfiStatus::Ok);
+    let pool = pool_create(1);
+    assert_ne!(pool, stale);
+
+    assert_eq!(
+        pool_submit(stale, Some(count), None, user_data),
+        FfiStatus::InvalidHandle
+    );
+    assert_eq!(pool_join(stale), FfiStatus::InvalidHandle);
+    assert_eq!(pool_free(stale), FfiStatus::InvalidHandle);
+
+    assert_eq!(
+        pool_submit(pool, Some(count), None, user_data),
+        FfiStatus::Ok
+    );
+    assert_eq!(pool_free(pool), FfiStatus::Ok);
+    assert_eq!(counter.load(Ordering::SeqCst), 1);
+}
+
+// 池的线程等待自己会死锁，所以 join 和 free 被拒绝，池照常可用
+// A pool thread waiting for its own pool would deadlock, so join and free are refused and the
+// pool stays usable
+#[test]
+fn refuses_to_wait_for_itself() {
+    init();
+    let inside = Inside {
+        pool: pool_create(1),
+        statuses: Mutex::new(Vec::new()),
+    };
+    let user_data = &inside as *const _ as *mut c_void;
+    let status = pool_submit(inside.pool, Some(join_and_free_inside), None, user_data);
+    assert_eq!(status, FfiStatus::Ok);
+    assert_eq!(pool_join(inside.pool), FfiStatus::Ok);
+    assert_eq!(
+        *inside.statuses.lock().unwrap(),
+        [FfiStatus::WouldDeadlock, FfiStatus::WouldDeadlock]
+    );
+    assert_eq!(pool_free(inside.pool), FfiStatus::Ok);
+}
@@ -0,0 +1,222 @@
+// cdylib_sum_with_progress 的测试：百分比单调递增并以 100 结束，回调可以重新调用库，
+// 返回非零值时提前结束；_unwind 版本行为相同，回调中的 panic 展开回调用方
+// Tests for cdylib_sum_with_progress: the percentages grow and end at 100, the callback may call
+// into the library again and returning non-zero stops the work early; the _unwind version
+// behaves the same, and a panic in its callback unwinds back to the caller
+
+use std::ffi::{c_int, c_long, c_void};
+use std::panic;
+use std::ptr;
+
+use cdylib_gen::{
+    cdylib_sum, cdylib_sum_with_progress, cdylib_sum_with_progress_unwind, FfiStatus,
+    ProgressCallback, ProgressCallbackUnwind,
+};
+
+mod common;
+use common::init;
+
+// 回调看到的进度，以及要在哪个百分比中止
+// The progress the callback saw, and the percentage at which to abort
+#[derive(Default)]
+struct Progress {
+    seen: Vec<u8>,
+    abort_at: Option<u8>,
+}
+
+extern "C" fn record(percent: u8, user_data: *mut c_void) -> c_int {
+    let progress = unsafe { &mut *(user_data as *mut Progress) };
+    progress.seen.push(percent);
+    c_int::from(
+        progress
+            .abort_at
+            .is_some_and(|abort_at| percent >= abort_at),
+    )
+}
+
+// 在回调中再次调用库，确认计算没有持有会让它卡住的锁
+// Calls into the library again from the callback, showing the computation holds no lock that would
+// make it hang
+extern "C" fn reenter(_: u8, user_data: *mut c_void) -> c_int {
+    let sums = unsafe { &mut *(user_data as *mut Vec<c_long>) };
+    let mut total = 0;
+    let status = unsafe { cdylib_sum([1, 2, 3].as_ptr(), 3, &mut total) };
+    assert_eq!(status, FfiStatus::Ok);
+    sums.push(total);
+    0
+}
+
+fn sum_with_progress(
+    values: &[c_int],
+    cb: ProgressCallback,
+    user_data: *mut c_void,
+) -> (FfiStatus, c_long) {
+    let mut total = -1;
+    let status = unsafe {
+        cdylib_sum_with_progress(values.as_ptr(), values.len(), cb, user_data, &mut total)
+    };
+    (status, total)
+}
+
+#[test]
+fn reports_every_percentage_once() {
+    init();
+    let values: Vec<c_int> = (1..=1000).collect();
+    let mut progress = Progress::default();
+    let user_data = &mut progress as *mut _ as *mut c_void;
+    assert_eq!(
+        sum_with_progress(&values, Some(record), user_data),
+        (FfiStatus::Ok, 500500)
+    );
+    assert_eq!(progress.seen, (0..=100).collect::<Vec<_>>());
+}
+
+#[test]
+fn few_values_still_start_at_0_and_end_at_100() {
+    init();
+    let mut progress = Progress::default();
+    let user_data = &mut progress as *mut _ as *mut c_void;
+    assert_eq!(
+        sum_with_progress(&[5, 6, 7], Some(record), user_data),
+        (FfiStatus::Ok, 18)
+    );
+    assert_eq!(progress.seen, [0, 33, 66, 100]);
+
+    let mut progress = Progress::default();
+    let user_data = &mut progress as *mut _ as *mut c_void;
+    assert_eq!(
+        sum_with_progress(&[], Some(record), user_data),
+        (FfiStatus::Ok, 0)
+    );
+    assert_eq!(progress.seen, [0, 100]);
+}
+
+#[test]
+fn non_zero_return_aborts() {
+    init();
+    let values = vec![1; 200];
+    let mut progress = Progress {
+        abort_at: Some(40),
+        ..Progress::default()
+    };
+    let user_data = &mut progress as *mut _ as *mut c_void;
+    assert_eq!(
+        sum_with_progress(&values, Some(record), user_data),
+        (FfiStatus::Cancelled, -1)
+    );
+    assert_eq!(progress.seen.last(), Some(&40));
+
+    // 在第一次回调就中止时一步都不会做
+    // Aborting at the very first callback means no step runs at all
+    let mut progress = Progress {
+        abort_at: Some(0),
+        ..Progress::default()
+    };
+    let user_data = &mut progress as *mut _ as *mut c_void;
+    assert_eq!(
+        sum_with_progress(&values, Some(record), user_data),
+        (FfiStatus::Cancelled, -1)
+    );
+    assert_eq!(progress.seen, [0]);
+}
+
+#[test]
+fn callback_may_reenter_the_library() {
+    init();
+    let mut sums = Vec::<c_long>::new();
+    let user_data = &mut sums as *mut _ as *mut c_void;
+    assert_eq!(
+        sum_with_progress(&[1, 2, 3, 4], Some(reenter), user_data),
+        (FfiStatus::Ok, 10)
+    );
+    assert_eq!(sums, [6; 5]);
+}
+
+#[test]
+fn runs_without_a_callback() {
+    init();
+    assert_eq!(
+        sum_with_progress(&[1, 2, 3], None, ptr::null_mut()),
+        (FfiStatus::Ok, 6)
+    );
+}
+
+extern "C-unwind" fn record_unwind(percent: u8, user_data: *mut c_void) -> c_int {
+    record(percent, user_data)
+}
+
+// 记录进度，到 50% 时 panic
+// Records the progress and panics at 50%
+extern "C-unwind" fn panic_halfway(percent: u8, user_data: *mut c_void) -> c_int {
+    let progress = unsafe { &mut *(user_data as *mut Progress) };
+    progress.seen.push(percent);
+    if percent == 50 {
+        panic!("stopped at {percent}%");
+    }
+    0
+}
+
+fn sum_with_progress_unwind(
+    values: &[c_int],
+    cb: ProgressCallbackUnwind,
+    user_data: *mut c_void,
+) -> (FfiStatus, c_long) {
+    let mut total = -1;
+    let status = unsafe {
+        cdylib_sum_with_progress_unwind(values.as_ptr(), values.len(), cb, user_data, &mut total)
+    };
+    (status, total)
+}
+
+#[test]
+fn unwind_variant_reports_and_aborts_alike() {
+    init();
+    let mut progress = Progress::default();
+    let user_data = &mut progress as *mut _ as *mut c_void;
+    assert_eq!(
+        sum_with_progress_unwind(&[5, 6, 7], Some(record_unwind), user_data),
+        (FfiStatus::Ok, 18)
+    );
+    assert_eq!(progress.seen, [0, 33, 66, 100]);
+
+    let mut progress = Progress {
+        abort_at: Some(40),
+        ..Progress::default()
+    };
+    let user_data = &mut progress as *mut _ as *mut c_void;
+    assert_eq!(
+        sum_with_progress_unwind(&[1; 200], Some(record_unwind), user_data),
+        (FfiStatus::Cancelled, -1)
+    );
+    assert_eq!(
+        sum_with_progress_unwind(&[1, 2], None, ptr::null_mut()),
+        (FfiStatus::Ok, 3)
+    );
+    let status =
+        unsafe { cdylib_sum_with_progress_unwind(ptr::null(), 2, None, ptr::null_mut(), &mut 0) };
+    assert_eq!(status, FfiStatus::NullPointer);
+}
+
+// 回调的 panic 不会变成 FFI_STATUS_PANIC，而是原样回到调用方，total 保持不变
+// The callback's panic doesn't become FFI_STATUS_PANIC but reaches the caller as it was, leaving
+// total untouched
+#[test]
+fn unwind_variant_lets_the_panic_through() {
+    init();
+    let values = vec![1; 100];
+    let mut progress = Progress::default();
+    let mut total = -1;
+    let payload = panic::catch_unwind(panic::AssertUnwindSafe(|| unsafe {
+        cdylib_sum_with_progress_unwind(
+            values.as_ptr(),
+            values.len(),
+            Some(panic_halfway),
+            &mut progress as *mut _ as *mut c_void,
+            &mut total,
+        )
+    }))
+    .unwrap_err();
+    assert_eq!(payload.downcast_ref::<String>().unwrap(), "stopped at 50%");
+    assert_eq!(progress.seen, (0..=50).collect::<Vec<_>>());
+    assert_eq!(total, -1);
+}
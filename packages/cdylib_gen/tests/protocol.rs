@@ -0,0 +1,137 @@
+// 打包的消息头的测试：字段在线上的位置和 C 的 #pragma pack 一致；消息头在字节缓冲区的任何偏移处都能写入和
+// 解析，即使多字节的字段因此没有对齐，这正是对它们取引用会是未定义行为的原因；长度不够、magic、版本或
+// 校验和错误的输入被拒绝，输出保持不变
+// Tests for the packed message header: the fields sit on the wire where C's #pragma pack puts
+// them; a header can be written and parsed at any offset of a byte buffer even though its
+// multi-byte fields end up misaligned, which is exactly why referencing them would be undefined
+// behaviour; input that is too short or has the wrong magic, version or checksum is refused,
+// leaving the output unchanged
+
+use std::mem;
+use std::ptr;
+
+use cdylib_gen::{
+    cdylib_header_init, cdylib_header_parse, FfiSlice, FfiStatus, MessageHeader,
+    MESSAGE_HEADER_MAGIC, MESSAGE_HEADER_SIZE, MESSAGE_HEADER_VERSION,
+};
+use interop_common::FfiError;
+
+// 按 8 字节对齐的缓冲区，某个偏移处的地址是否对齐因此是确定的
+// A buffer aligned to 8 bytes, so whether the address at some offset is aligned is known
+#[repr(C, align(8))]
+struct Wire([u8; 64]);
+
+fn assert_fields(header: &MessageHeader, kind: u8, sequence: u64, payload_len: u32) {
+    assert_eq!(header.magic(), MESSAGE_HEADER_MAGIC);
+    assert_eq!(header.version(), MESSAGE_HEADER_VERSION);
+    assert_eq!(header.kind(), kind);
+    assert_eq!(header.sequence(), sequence);
+    assert_eq!(header.payload_len(), payload_len);
+}
+
+#[test]
+fn lays_out_the_wire_format() {
+    let header = MessageHeader::new(3, 0x0102_0304_0506_0708, 0x1122_3344);
+    let bytes = header.as_bytes();
+    assert_eq!(bytes.len(), MESSAGE_HEADER_SIZE);
+    assert_eq!(bytes[..4], MESSAGE_HEADER_MAGIC.to_ne_bytes());
+    assert_eq!(bytes[4], MESSAGE_HEADER_VERSION);
+    assert_eq!(bytes[5], 3);
+    assert_eq!(bytes[6..10], 0x1122_3344u32.to_ne_bytes());
+    assert_eq!(bytes[10..18], 0x0102_0304_0506_0708u64.to_ne_bytes());
+    let sum = bytes[..18].iter().map(|&b| b as u16).sum::<u16>();
+    assert_eq!(bytes[18..], sum.to_ne_bytes());
+}
+
+#[test]
+fn round_trips_at_every_offset() {
+    for offset in 0..8 {
+        let mut wire = Wire([0; 64]);
+        let at = unsafe { wire.0.as_mut_ptr().add(offset) };
+        let status =
+            unsafe { cdylib_header_init(9, offset as u64, 1000, at.cast::<MessageHeader>()) };
+        assert_eq!(status, FfiStatus::Ok);
+
+        let mut header = mem::MaybeUninit::<MessageHeader>::uninit();
+        let bytes = FfiSlice::new(&wire.0[offset..offset + MESSAGE_HEADER_SIZE]);
+        let status = unsafe { cdylib_header_parse(bytes, header.as_mut_ptr()) };
+        assert_eq!(status, FfiStatus::Ok);
+        assert_fields(unsafe { &header.assume_init() }, 9, offset as u64, 1000);
+    }
+}
+
+// 放在 8 字节对齐的地址上时，payload_len 在偏移 6、sequence 在偏移 10，两个都没有对齐：对它们取的引用
+// 会违反 &u32 和 &u64 的对齐要求，所以编译器拒绝 &header.payload_len，访问函数只能逐字节读取
+// At an 8-byte aligned address payload_len is at offset 6 and sequence at offset 10, both
+// misaligned: references to them would break the alignment &u32 and &u64 require, which is why
+// the compiler refuses &header.payload_len and the accessors have to read them unaligned
+#[test]
+fn fields_are_misaligned() {
+    let mut wire = Wire([0; 64]);
+    let header = wire.0.as_mut_ptr().cast::<MessageHeader>();
+    unsafe {
+        assert_eq!(cdylib_header_init(1, 2, 3, header), FfiStatus::Ok);
+        let payload_len = ptr::addr_of!((*header).payload_len);
+        let sequence = ptr::addr_of!((*header).sequence);
+        assert!(!payload_len.is_aligned());
+        assert!(!sequence.is_aligned());
+        assert_eq!(payload_len.read_unaligned(), 3);
+        assert_eq!(sequence.read_unaligned(), 2);
+        assert_fields(&*header, 1, 2, 3);
+    }
+}
+
+#[test]
+fn refuses_short_input() {
+    let header = MessageHeader::new(1, 1, 1);
+    assert_eq!(
+        MessageHeader::parse(&header.as_bytes()[..MESSAGE_HEADER_SIZE - 1]).unwrap_err(),
+        FfiError::BufferTooSmall {
+            required: MESSAGE_HEADER_SIZE
+        }
+    );
+    let mut out = MessageHeader::new(0, 0, 0);
+    let status = unsafe { cdylib_header_parse(FfiSlice::empty(), &mut out) };
+    assert_eq!(status, FfiStatus::BufferTooSmall);
+    assert_fields(&out, 0, 0, 0);
+}
+
+#[test]
+fn refuses_corrupted_headers() {
+    let good = *MessageHeader::new(5, 77, 4096).as_bytes();
+    assert!(MessageHeader::parse(&good).is_ok());
+    // magic、版本、kind 和校验和各改一个字节
+    // One byte each of the magic, the version, the kind and the checksum changed
+    for index in [0, 4, 5, 18] {
+        let mut bytes = good;
+        bytes[index] ^= 0x40;
+        assert_eq!(
+            MessageHeader::parse(&bytes).unwrap_err(),
+            FfiError::Malformed,
+            "byte {}",
+            index
+        );
+        let mut out = MessageHeader::new(0, 0, 0);
+        let status = unsafe { cdylib_header_parse(FfiSlice::new(&bytes), &mut out) };
+        assert_eq!(status, FfiStatus::Malformed);
+        assert_fields(&out, 0, 0, 0);
+    }
+}
+
+#[test]
+fn accepts_trailing_payload() {
+    let mut message = MessageHeader::new(2, 3, 5).as_bytes().to_vec();
+    message.extend_from_slice(b"hello");
+    let header = MessageHeader::parse(&message).unwrap();
+    assert_fields(&header, 2, 3, 5);
+    assert_eq!(
+        &message[MESSAGE_HEADER_SIZE..],
+        &b"hello"[..header.payload_len() as usize]
+    );
+}
+
+#[test]
+fn refuses_null_output() {
+    let status = unsafe { cdylib_header_init(1, 2, 3, ptr::null_mut()) };
+    assert_eq!(status, FfiStatus::NullPointer);
+}
@@ -0,0 +1,108 @@
+// queue_new/queue_push/queue_free 的测试：多个线程同时推入，消费者线程报告每个元素一次，
+// 同一个生产者的元素保持顺序，queue_free 会先处理完剩下的元素
+// Tests for queue_new/queue_push/queue_free: several threads push at once, the consumer thread
+// reports every item once, items from one producer keep their order and queue_free drains the
+// remaining items first
+
+use std::ffi::{c_int, c_void};
+use std::sync::{Barrier, Mutex};
+use std::{ptr, thread};
+
+use cdylib_gen::{queue_free, queue_new, queue_push, FfiHandle, FfiStatus};
+
+mod common;
+use common::init;
+
+const PRODUCERS: c_int = 8;
+const ITEMS: c_int = 200;
+
+// 回调收到的元素、结果和所在线程名
+// The items, results and thread names the callback received
+type Received = Mutex<Vec<(c_int, i64, String)>>;
+
+extern "C" fn record(item: c_int, result: i64, user_data: *mut c_void) {
+    let received = unsafe { &*(user_data as *const Received) };
+    let name = thread::current().name().unwrap_or_default().to_owned();
+    received.lock().unwrap().push((item, result, name));
+}
+
+#[test]
+fn reports_items_from_many_producers() {
+    init();
+    let received = Received::default();
+    let queue = queue_new(Some(record), &received as *const _ as *mut c_void);
+    assert!(!queue.is_null());
+    let barrier = Barrier::new(PRODUCERS as usize);
+    thread::scope(|scope| {
+        for p in 0..PRODUCERS {
+            let barrier = &barrier;
+            scope.spawn(move || {
+                barrier.wait();
+                for i in 0..ITEMS {
+                    let status = queue_push(queue, p * ITEMS + i);
+                    assert_eq!(status, FfiStatus::Ok);
+                }
+            });
+        }
+    });
+    assert_eq!(queue_free(queue), FfiStatus::Ok);
+
+    let received = received.into_inner().unwrap();
+    assert_eq!(received.len(), (PRODUCERS * ITEMS) as usize);
+    for (item, result, name) in &received {
+        assert_eq!(*result, i64::from(*item) * i64::from(*item));
+        assert_eq!(name, "cdylib_gen queue");
+    }
+    // 每个生产者自己的元素按推入的顺序到达
+    // Each producer's own items arrive in the order it pushed them
+    for p in 0..PRODUCERS {
+        let items: Vec<c_int> = received
+            .iter()
+            .map(|&(item, _, _)| item)
+            .filter(|item| item / ITEMS == p)
+            .collect();
+        assert_eq!(items, (p * ITEMS..(p + 1) * ITEMS).collect::<Vec<_>>());
+    }
+}
+
+#[test]
+fn free_drains_the_remaining_items() {
+    init();
+    let received = Received::default();
+    let queue = queue_new(Some(record), &received as *const _ as *mut c_void);
+    for item in 0..1000 {
+        assert_eq!(queue_push(queue, item), FfiStatus::Ok);
+    }
+    assert_eq!(queue_free(queue), FfiStatus::Ok);
+    assert_eq!(received.into_inner().unwrap().len(), 1000);
+}
+
+#[test]
+fn rejects_null_arguments() {
+    init();
+    assert!(queue_new(None, ptr::null_mut()).is_null());
+    assert_eq!(queue_push(FfiHandle::NULL, 1), FfiStatus::InvalidHandle);
+    assert_eq!(queue_free(FfiHandle::NULL), FfiStatus::Ok);
+}
+
+// 释放后的句柄过期了：推入和重复释放都返回 INVALID_HANDLE，回调不会再被调用
+// A freed handle is stale: pushing to it and freeing it again return INVALID_HANDLE, and the
+// callback is not called again
+#[test]
+fn rejects_stale_handles() {
+    init();
+    let received = Received::default();
+    let queue = queue_new(Some(record), &received as *const _ as *mut c_void);
+    assert_eq!(queue_push(queue, 3), FfiStatus::Ok);
+    assert_eq!(queue_free(queue), FfiStatus::Ok);
+
+    assert_eq!(queue_push(queue, 4), FfiStatus::InvalidHandle);
+    assert_eq!(queue_free(queue), FfiStatus::InvalidHandle);
+    let items: Vec<c_int> = received
+        .into_inner()
+        .unwrap()
+        .into_iter()
+        .map(|(item, _, _)| item)
+        .collect();
+    assert_eq!(items, [3]);
+}
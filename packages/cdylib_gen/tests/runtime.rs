@@ -0,0 +1,103 @@
+// async_submit 和 async_block_on 的测试：回调在运行时的工作线程上运行，错误通过回调的状态报告，
+// 在运行时的线程上阻塞等待会被拒绝
+// Tests for async_submit and async_block_on: callbacks run on the runtime's worker threads,
+// errors are reported through the callback's status and blocking on a runtime thread is refused
+
+use std::ffi::{c_int, c_void};
+use std::ptr;
+use std::sync::mpsc::{self, Sender};
+use std::thread;
+use std::time::Duration;
+
+use cdylib_gen::{async_block_on, async_submit, AsyncRequest, FfiStatus};
+
+mod common;
+use common::init;
+
+fn request(a: c_int, b: c_int, delay_ms: u32) -> AsyncRequest {
+    AsyncRequest { a, b, delay_ms }
+}
+
+type Outcome = (FfiStatus, c_int, String);
+
+// 每次提交都把自己的一份 Sender 交给回调，由回调收回：测试返回时运行时的线程可能还在发送
+// Every submission hands its own Sender to the callback, which reclaims it: the runtime's thread
+// may still be sending when the test returns
+fn context<T>(tx: &Sender<T>) -> *mut c_void {
+    Box::into_raw(Box::new(tx.clone())) as *mut c_void
+}
+
+// 把结果和运行回调的线程名发回测试线程
+// Sends the outcome and the name of the thread running the callback back to the test thread
+extern "C" fn send(status: FfiStatus, sum: c_int, user_data: *mut c_void) {
+    let tx = unsafe { Box::from_raw(user_data as *mut Sender<Outcome>) };
+    let name = thread::current().name().unwrap_or_default().to_owned();
+    tx.send((status, sum, name)).unwrap();
+}
+
+// 在回调里调用 async_block_on，把它的状态发回去
+// Calls async_block_on from the callback and sends its status back
+extern "C" fn block_on_inside(_: FfiStatus, _: c_int, user_data: *mut c_void) {
+    let tx = unsafe { Box::from_raw(user_data as *mut Sender<FfiStatus>) };
+    let mut sum = 0;
+    tx.send(unsafe { async_block_on(request(1, 2, 0), &mut sum) })
+        .unwrap();
+}
+
+#[test]
+fn callbacks_run_on_runtime_threads() {
+    init();
+    let (tx, rx) = mpsc::channel::<Outcome>();
+    for i in 0..20 {
+        let status = async_submit(request(i, 1, 10), Some(send), context(&tx));
+        assert_eq!(status, FfiStatus::Ok);
+    }
+    let mut sums: Vec<c_int> = (0..20)
+        .map(|_| {
+            let (status, sum, name) = rx.recv_timeout(Duration::from_secs(5)).unwrap();
+            assert_eq!(status, FfiStatus::Ok);
+            assert_eq!(name, "cdylib_gen runtime");
+            sum
+        })
+        .collect();
+    sums.sort();
+    assert_eq!(sums, (1..=20).collect::<Vec<_>>());
+}
+
+#[test]
+fn failures_arrive_through_the_callback() {
+    init();
+    let (tx, rx) = mpsc::channel::<Outcome>();
+    let status = async_submit(request(c_int::MAX, 1, 0), Some(send), context(&tx));
+    assert_eq!(status, FfiStatus::Ok);
+    let (status, sum, _) = rx.recv_timeout(Duration::from_secs(5)).unwrap();
+    assert_eq!((status, sum), (FfiStatus::Overflow, 0));
+
+    assert_eq!(
+        async_submit(request(1, 2, 0), None, ptr::null_mut()),
+        FfiStatus::NullPointer
+    );
+}
+
+#[test]
+fn block_on_waits_for_the_result() {
+    init();
+    let mut sum = 0;
+    let status = unsafe { async_block_on(request(40, 2, 10), &mut sum) };
+    assert_eq!((status, sum), (FfiStatus::Ok, 42));
+
+    let status = unsafe { async_block_on(request(c_int::MAX, 1, 0), &mut sum) };
+    assert_eq!(status, FfiStatus::Overflow);
+    let status = unsafe { async_block_on(request(1, 2, 0), ptr::null_mut()) };
+    assert_eq!(status, FfiStatus::NullPointer);
+}
+
+#[test]
+fn block_on_from_a_callback_would_deadlock() {
+    init();
+    let (tx, rx) = mpsc::channel::<FfiStatus>();
+    let status = async_submit(request(1, 2, 0), Some(block_on_inside), context(&tx));
+    assert_eq!(status, FfiStatus::Ok);
+    let status = rx.recv_timeout(Duration::from_secs(5)).unwrap();
+    assert_eq!(status, FfiStatus::WouldDeadlock);
+}
@@ -0,0 +1,63 @@
+// 字符串数组的两个方向：argv 形式的参数和 Rust 分配、rust_string_array_free 归还的数组
+// String arrays in both directions: argv-style arguments, and arrays Rust allocates and
+// rust_string_array_free hands back
+
+use std::ffi::{c_char, CStr};
+use std::ptr;
+
+use cdylib_gen::{cdylib_join_strings, cdylib_split_words, rust_free, rust_string_array_free};
+
+mod common;
+use common::init;
+
+#[test]
+fn joins_argv_style_strings() {
+    init();
+    let argv = [c"rust".as_ptr(), c"and".as_ptr(), c"C".as_ptr()];
+    unsafe {
+        let joined = cdylib_join_strings(argv.as_ptr(), argv.len(), c", ".as_ptr());
+        assert_eq!(CStr::from_ptr(joined).to_str(), Ok("rust, and, C"));
+        rust_free(joined.cast());
+        let empty = cdylib_join_strings(ptr::null(), 0, c" ".as_ptr());
+        assert_eq!(CStr::from_ptr(empty).to_bytes(), b"");
+        rust_free(empty.cast());
+    }
+}
+
+#[test]
+fn rejects_null_elements() {
+    init();
+    let argv = [c"rust".as_ptr(), ptr::null()];
+    let joined = unsafe { cdylib_join_strings(argv.as_ptr(), argv.len(), c" ".as_ptr()) };
+    assert!(joined.is_null());
+}
+
+#[test]
+fn returns_words_followed_by_null() {
+    init();
+    let mut count = 0;
+    unsafe {
+        let words = cdylib_split_words(c"  one two\tthree ".as_ptr(), &mut count);
+        assert!(!words.is_null());
+        assert_eq!(count, 3);
+        let read: Vec<&str> = (0..count)
+            .map(|i| CStr::from_ptr(*words.add(i)).to_str().unwrap())
+            .collect();
+        assert_eq!(read, ["one", "two", "three"]);
+        assert!((*words.add(count)).is_null());
+        rust_string_array_free(words, count);
+    }
+}
+
+#[test]
+fn text_without_words_gets_an_empty_array() {
+    init();
+    let mut count = 1;
+    unsafe {
+        let words = cdylib_split_words(c" \n ".as_ptr(), &mut count);
+        assert_eq!(count, 0);
+        assert!((*words).is_null());
+        rust_string_array_free(words, count);
+        rust_string_array_free(ptr::null_mut::<*mut c_char>(), 0);
+    }
+}
@@ -0,0 +1,112 @@
+// cdylib_set_trace_callback 注册的回调收到 cdylib_add 的 span 和事件
+// A callback registered with cdylib_set_trace_callback receives cdylib_add's spans and events
+
+use std::ffi::{c_void, CString};
+use std::sync::Mutex;
+use std::{ptr, slice, str};
+
+use cdylib_gen::{cdylib_add, cdylib_set_trace_callback, TraceKind, TraceRecord, TraceStr};
+
+mod common;
+use common::init;
+
+#[derive(Debug, PartialEq)]
+struct Received {
+    kind: TraceKind,
+    span_id: u64,
+    name: String,
+    fields: Vec<(String, String)>,
+}
+
+unsafe fn string(s: TraceStr) -> String {
+    str::from_utf8(slice::from_raw_parts(s.ptr as *const u8, s.len))
+        .unwrap()
+        .to_owned()
+}
+
+unsafe extern "C" fn collect(record: *const TraceRecord, user_data: *mut c_void) {
+    let record = &*record;
+    let fields = slice::from_raw_parts(record.fields, record.field_count)
+        .iter()
+        .map(|field| (string(field.key), string(field.value)))
+        .collect();
+    let received = &*(user_data as *const Mutex<Vec<Received>>);
+    received.lock().unwrap().push(Received {
+        kind: record.kind,
+        span_id: record.span_id,
+        name: string(record.name),
+        fields,
+    });
+}
+
+fn pairs(fields: &[(&str, &str)]) -> Vec<(String, String)> {
+    fields
+        .iter()
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect()
+}
+
+// 订阅者是进程全局的，所以全部放在一个测试里按顺序检查
+// The subscriber is process-wide, so everything is checked in order within a single test
+#[test]
+fn forwards_spans_and_events() {
+    init();
+    let received = Mutex::new(Vec::<Received>::new());
+    cdylib_set_trace_callback(Some(collect), &received as *const _ as *mut c_void);
+
+    let mut buf = CString::new("Lee").unwrap().into_bytes_with_nul();
+    buf.resize(64, 0);
+    let mut sum = 0;
+    unsafe {
+        cdylib_add(
+            2,
+            3,
+            buf.as_mut_ptr().cast(),
+            buf.len(),
+            &mut sum,
+            ptr::null_mut(),
+        )
+    };
+
+    cdylib_set_trace_callback(None, ptr::null_mut());
+    unsafe {
+        cdylib_add(
+            2,
+            3,
+            buf.as_mut_ptr().cast(),
+            buf.len(),
+            &mut sum,
+            ptr::null_mut(),
+        )
+    };
+
+    let received = received.into_inner().unwrap();
+    assert_eq!(received.len(), 3, "{received:#?}");
+    let span_id = received[0].span_id;
+    assert_ne!(span_id, 0);
+    let span_fields = pairs(&[("a", "2"), ("b", "3")]);
+    assert_eq!(
+        received[0],
+        Received {
+            kind: TraceKind::SpanEnter,
+            span_id,
+            name: "rxc_cdylib_add".to_string(),
+            fields: span_fields.clone(),
+        }
+    );
+    assert_eq!(received[1].kind, TraceKind::Event);
+    assert_eq!(received[1].span_id, span_id);
+    assert_eq!(
+        received[1].fields,
+        pairs(&[("message", "rxc_cdylib_add returned"), ("status", "Ok")])
+    );
+    assert_eq!(
+        received[2],
+        Received {
+            kind: TraceKind::SpanExit,
+            span_id,
+            name: "rxc_cdylib_add".to_string(),
+            fields: span_fields,
+        }
+    );
+}
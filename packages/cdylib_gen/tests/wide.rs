@@ -0,0 +1,57 @@
+// cdylib_add_w 的 UTF-16 缓冲区：代理对原样往返，截断不拆开代理对，不成对的代理项被拒绝
+// The UTF-16 buffer of cdylib_add_w: surrogate pairs round trip unchanged, truncation doesn't split
+// them and unpaired surrogates are rejected
+
+use cdylib_gen::{cdylib_add_w, FfiStatus};
+
+mod common;
+use common::init;
+
+// 把名字和结尾的 NUL 放进一个 capacity 个代码单元的缓冲区
+// Puts the name and its NUL terminator into a buffer of capacity code units
+fn name_buf(name: &str, capacity: usize) -> Vec<u16> {
+    let mut buf = vec![0; capacity];
+    for (dst, src) in buf.iter_mut().zip(name.encode_utf16()) {
+        *dst = src;
+    }
+    buf
+}
+
+fn message(buf: &[u16]) -> String {
+    let end = buf.iter().position(|&unit| unit == 0).unwrap();
+    String::from_utf16(&buf[..end]).unwrap()
+}
+
+#[test]
+fn writes_the_message_as_utf16() {
+    init();
+    let mut buf = name_buf("张三 🦀", 64);
+    let status = unsafe { cdylib_add_w(1, 2, buf.as_mut_ptr(), buf.len()) };
+    assert_eq!(status, FfiStatus::Ok);
+    assert_eq!(message(&buf), "[Rust cdylib] The result (1 + 2) is 3!");
+}
+
+#[test]
+fn truncates_like_snprintf() {
+    init();
+    let mut buf = name_buf("W", 8);
+    let status = unsafe { cdylib_add_w(1, 2, buf.as_mut_ptr(), buf.len()) };
+    assert_eq!(status, FfiStatus::BufferTooSmall);
+    assert_eq!(message(&buf), "[Rust c");
+}
+
+#[test]
+fn rejects_an_unpaired_surrogate() {
+    init();
+    let mut buf = vec![0x57, 0xD800, 0x57, 0, 0, 0];
+    let status = unsafe { cdylib_add_w(1, 2, buf.as_mut_ptr(), buf.len()) };
+    assert_eq!(status, FfiStatus::InvalidUtf16);
+}
+
+#[test]
+fn rejects_an_unterminated_name() {
+    init();
+    let mut buf = vec![0x57; 4];
+    let status = unsafe { cdylib_add_w(1, 2, buf.as_mut_ptr(), buf.len()) };
+    assert_eq!(status, FfiStatus::Unterminated);
+}
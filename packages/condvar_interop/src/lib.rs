@@ -0,0 +1,138 @@
+// 这个库演示 C 线程通过条件变量唤醒等待中的 Rust 线程：WorkQueue 是 Mutex 加 Condvar 的工作队列，
+// 交给 C 的是一个 Arc 引用和两个 extern "C" 函数，C 线程每准备好一项工作就调用 work_queue_push，
+// 最后调用 work_queue_close 交还引用；Rust 线程在 pop 中等待，直到有工作或者队列关闭才醒来。
+// 条件变量总是和它保护的状态一起检查，虚假唤醒和先于等待到达的通知都不会丢失工作
+// This library demonstrates a C thread waking a waiting Rust thread through a condition variable:
+// WorkQueue is a work queue made of a Mutex and a Condvar, C gets an Arc reference and two
+// extern "C" functions, and the C thread calls work_queue_push for every piece of work it has
+// ready and finally work_queue_close, which hands the reference back; the Rust thread waits in
+// pop and only wakes up once there is work or the queue is closed. The condition variable is
+// always checked together with the state it protects, so neither spurious wakeups nor
+// notifications that arrive before the wait lose any work
+
+use std::collections::VecDeque;
+use std::ffi::c_void;
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError};
+use std::time::Duration;
+
+/// The C signature of [`work_queue_push`].
+pub type PushFn = unsafe extern "C" fn(ctx: *mut c_void, work: u64);
+
+/// The C signature of [`work_queue_close`].
+pub type CloseFn = unsafe extern "C" fn(ctx: *mut c_void);
+
+#[derive(Default)]
+struct State {
+    work: VecDeque<u64>,
+    producers: usize,
+}
+
+/// 生产者在 C、消费者在 Rust 的工作队列
+/// A work queue with its producers in C and its consumers in Rust.
+#[derive(Default)]
+pub struct WorkQueue {
+    state: Mutex<State>,
+    ready: Condvar,
+}
+
+impl WorkQueue {
+    pub fn new() -> Arc<WorkQueue> {
+        Arc::default()
+    }
+
+    // 锁只保护队列中的数据，持有它时发生的 panic 不会让数据失去一致
+    // The lock only protects the queued data, which a panic while holding it can't leave
+    // inconsistent
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Registers a producer and returns the context to pass to C along with [`work_queue_push`]
+    /// and [`work_queue_close`].
+    ///
+    /// The context holds a reference to the queue, so the queue stays alive until C calls
+    /// [`work_queue_close`] with it; if C never gets to use it, such as when its thread could not
+    /// be started, [`WorkQueue::reclaim`] it instead.
+    pub fn producer(self: &Arc<WorkQueue>) -> *mut c_void {
+        self.lock().producers += 1;
+        Arc::into_raw(Arc::clone(self)).cast_mut().cast()
+    }
+
+    /// Takes back a context from [`WorkQueue::producer`] that C never used, as if C had closed it.
+    ///
+    /// # Safety
+    ///
+    /// `ctx` must come from [`WorkQueue::producer`] and must not be used again.
+    pub unsafe fn reclaim(ctx: *mut c_void) {
+        work_queue_close(ctx)
+    }
+
+    /// Waits until work is ready and returns it, or returns `None` once the queue is empty and
+    /// every producer closed it.
+    pub fn pop(&self) -> Option<u64> {
+        let mut state = self.lock();
+        loop {
+            if let Some(work) = state.work.pop_front() {
+                return Some(work);
+            }
+            if state.producers == 0 {
+                return None;
+            }
+            state = self
+                .ready
+                .wait(state)
+                .unwrap_or_else(PoisonError::into_inner);
+        }
+    }
+
+    /// Like [`WorkQueue::pop`], but also returns `None` when nothing arrives within `timeout`.
+    pub fn pop_timeout(&self, timeout: Duration) -> Option<u64> {
+        let state = self.lock();
+        let (mut state, _) = self
+            .ready
+            .wait_timeout_while(state, timeout, |state| {
+                state.work.is_empty() && state.producers > 0
+            })
+            .unwrap_or_else(PoisonError::into_inner);
+        state.work.pop_front()
+    }
+
+    /// Queues work from Rust, waking one waiting consumer.
+    pub fn push(&self, work: u64) {
+        self.lock().work.push_back(work);
+        self.ready.notify_one();
+    }
+
+    /// How many producers have not closed the queue yet.
+    pub fn producers(&self) -> usize {
+        self.lock().producers
+    }
+}
+
+/// Queues `work` on behalf of a C producer, waking one consumer waiting in [`WorkQueue::pop`].
+///
+/// # Safety
+///
+/// `ctx` must come from [`WorkQueue::producer`] and not have been closed yet.
+pub unsafe extern "C" fn work_queue_push(ctx: *mut c_void, work: u64) {
+    let queue = &*ctx.cast_const().cast::<WorkQueue>();
+    queue.push(work);
+}
+
+/// Closes a C producer's context, waking every consumer once no producer is left, and releases
+/// the reference to the queue it held.
+///
+/// # Safety
+///
+/// `ctx` must come from [`WorkQueue::producer`] and must not be used again.
+pub unsafe extern "C" fn work_queue_close(ctx: *mut c_void) {
+    let queue = Arc::from_raw(ctx.cast_const().cast::<WorkQueue>());
+    let last = {
+        let mut state = queue.lock();
+        state.producers -= 1;
+        state.producers == 0
+    };
+    if last {
+        queue.ready.notify_all();
+    }
+}
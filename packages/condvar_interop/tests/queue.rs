@@ -0,0 +1,76 @@
+// WorkQueue 的测试：生产者线程像 C 一样只通过上下文指针和两个 extern "C" 函数交出工作，等待的线程按顺序
+// 收到每一项工作，所有生产者关闭之后醒来并返回 None
+// Tests for WorkQueue: producer threads hand over work like C would, only through the context
+// pointer and the two extern "C" functions, and the waiting thread receives every piece of work in
+// order and wakes up to return None once every producer closed
+
+use std::ffi::c_void;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use condvar_interop::{work_queue_close, work_queue_push, WorkQueue};
+
+// 裸指针不能直接转移到其他线程，C 拿到的 ctx 也只是一个地址
+// A raw pointer can't move to another thread as it is, and the ctx C gets is just an address too
+struct Ctx(*mut c_void);
+unsafe impl Send for Ctx {}
+
+fn spawn_producer(queue: &Arc<WorkQueue>, work: Vec<u64>) -> thread::JoinHandle<()> {
+    let ctx = Ctx(queue.producer());
+    thread::spawn(move || {
+        let ctx = ctx;
+        for work in work {
+            thread::sleep(Duration::from_millis(1));
+            unsafe { work_queue_push(ctx.0, work) };
+        }
+        unsafe { work_queue_close(ctx.0) };
+    })
+}
+
+#[test]
+fn waits_for_every_piece_of_work_then_closes() {
+    let queue = WorkQueue::new();
+    let producer = spawn_producer(&queue, (1..=20).collect());
+    let received: Vec<u64> = std::iter::from_fn(|| queue.pop()).collect();
+    producer.join().unwrap();
+    assert_eq!(received, (1..=20).collect::<Vec<_>>());
+    assert_eq!(queue.producers(), 0);
+    assert_eq!(Arc::strong_count(&queue), 1);
+}
+
+#[test]
+fn stays_open_until_the_last_producer_closes() {
+    let queue = WorkQueue::new();
+    let producers = [
+        spawn_producer(&queue, vec![1, 2, 3]),
+        spawn_producer(&queue, vec![10, 20, 30]),
+    ];
+    let mut received: Vec<u64> = std::iter::from_fn(|| queue.pop()).collect();
+    producers.into_iter().for_each(|p| p.join().unwrap());
+    received.sort_unstable();
+    assert_eq!(received, [1, 2, 3, 10, 20, 30]);
+}
+
+#[test]
+fn work_pushed_before_waiting_is_not_lost() {
+    let queue = WorkQueue::new();
+    let ctx = queue.producer();
+    unsafe {
+        work_queue_push(ctx, 7);
+        work_queue_close(ctx);
+    }
+    assert_eq!(queue.pop(), Some(7));
+    assert_eq!(queue.pop(), None);
+}
+
+#[test]
+fn times_out_while_a_producer_is_idle() {
+    let queue = WorkQueue::new();
+    let ctx = queue.producer();
+    assert_eq!(queue.pop_timeout(Duration::from_millis(20)), None);
+    assert_eq!(queue.producers(), 1);
+    unsafe { WorkQueue::reclaim(ctx) };
+    assert_eq!(queue.producers(), 0);
+    assert_eq!(Arc::strong_count(&queue), 1);
+}
@@ -0,0 +1,11 @@
+// 这是我们的构建脚本，由 cxx_build 生成桥接代码并和 cpp/tally.cc 一起编译
+// This is our build script, cxx_build generates the bridge code and compiles it with cpp/tally.cc
+
+fn main() {
+    cxx_build::bridge("src/lib.rs")
+        .file("cpp/tally.cc")
+        .std("c++14")
+        .compile("tally");
+    println!("cargo::rerun-if-changed=src/lib.rs");
+    println!("cargo::rerun-if-changed=cpp");
+}
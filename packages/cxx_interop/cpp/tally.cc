@@ -0,0 +1,50 @@
+#include "cxx_interop/cpp/tally.h"
+
+// cxx_build 从 src/lib.rs 生成的头文件，声明了 extern "Rust" 中的函数
+// The header cxx_build generates from src/lib.rs, declaring the functions in extern "Rust"
+#include "cxx_interop/src/lib.rs.h"
+
+namespace interop
+{
+    Tally::Tally(std::string label) : label_(std::move(label)) {}
+
+    void Tally::add(int value)
+    {
+        values_.push_back(value);
+    }
+
+    const std::string &Tally::label() const
+    {
+        return label_;
+    }
+
+    const std::vector<int> &Tally::values() const
+    {
+        return values_;
+    }
+
+    std::unique_ptr<std::string> Tally::summary() const
+    {
+        // rust::String 可以直接转换成 std::string
+        // A rust::String converts straight into a std::string
+        std::string greeting(rust_greeting(label_));
+        std::string total = std::to_string(static_cast<long long>(rust_sum(values_)));
+        return std::unique_ptr<std::string>(new std::string(
+            greeting + " " + std::to_string(values_.size()) + " values add up to " + total));
+    }
+
+    std::unique_ptr<Tally> new_tally(const std::string &label)
+    {
+        return std::unique_ptr<Tally>(new Tally(label));
+    }
+
+    std::unique_ptr<std::vector<int>> squares(int count)
+    {
+        std::unique_ptr<std::vector<int>> values(new std::vector<int>);
+        for (int i = 1; i <= count; i++)
+        {
+            values->push_back(i * i);
+        }
+        return values;
+    }
+}
@@ -0,0 +1,29 @@
+// cxx 桥接的 C++ 一侧：Tally 类以及直接返回 std::vector 的函数
+// The C++ side of the cxx bridge: the Tally class and a function returning a std::vector
+#pragma once
+#include <memory>
+#include <string>
+#include <vector>
+
+namespace interop
+{
+    class Tally
+    {
+    public:
+        explicit Tally(std::string label);
+
+        void add(int value);
+        const std::string &label() const;
+        const std::vector<int> &values() const;
+        // 反过来调用 Rust 实现的 rust_greeting 和 rust_sum
+        // Calls back into rust_greeting and rust_sum, which are implemented in Rust
+        std::unique_ptr<std::string> summary() const;
+
+    private:
+        std::string label_;
+        std::vector<int> values_;
+    };
+
+    std::unique_ptr<Tally> new_tally(const std::string &label);
+    std::unique_ptr<std::vector<int>> squares(int count);
+}
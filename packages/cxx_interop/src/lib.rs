@@ -0,0 +1,55 @@
+// 这个库用 cxx 在 Rust 和 C++ 之间直接传递 std::string、std::vector<int> 和 C++ 类，
+// 不需要手写 extern "C" 声明、#[repr(C)] 结构体和缓冲区长度
+// This library uses cxx to pass std::string, std::vector<int> and a C++ class between Rust and
+// C++ directly, without hand-written extern "C" declarations, #[repr(C)] structs or buffer lengths
+
+use cxx::{let_cxx_string, CxxString, CxxVector, UniquePtr};
+
+#[cxx::bridge(namespace = "interop")]
+pub mod ffi {
+    // 由 Rust 实现、供 C++ 调用
+    // Implemented in Rust, called from C++
+    extern "Rust" {
+        fn rust_greeting(name: &CxxString) -> String;
+        fn rust_sum(values: &CxxVector<i32>) -> i64;
+    }
+
+    // 由 C++ 实现、供 Rust 调用；cxx 在编译期检查这些签名和 cpp/tally.h 一致
+    // Implemented in C++, called from Rust; cxx checks at compile time that these signatures
+    // match cpp/tally.h
+    unsafe extern "C++" {
+        include!("cxx_interop/cpp/tally.h");
+
+        /// A C++ class collecting ints under a label.
+        type Tally;
+
+        fn new_tally(label: &CxxString) -> UniquePtr<Tally>;
+        fn add(self: Pin<&mut Tally>, value: i32);
+        fn label(self: &Tally) -> &CxxString;
+        fn values(self: &Tally) -> &CxxVector<i32>;
+        /// Built in C++ by calling back into `rust_greeting` and `rust_sum`.
+        fn summary(self: &Tally) -> UniquePtr<CxxString>;
+        /// The squares of 1 to `count`, returned as a `std::vector<int>`.
+        fn squares(count: i32) -> UniquePtr<CxxVector<i32>>;
+    }
+}
+
+pub use ffi::{squares, Tally};
+
+/// Creates a C++ `Tally` labelled `label`.
+pub fn new_tally(label: &str) -> UniquePtr<Tally> {
+    // 在栈上构造 std::string，传给 C++ 的是它的引用
+    // Constructs a std::string on the stack and passes C++ a reference to it
+    let_cxx_string!(label = label);
+    ffi::new_tally(&label)
+}
+
+fn rust_greeting(name: &CxxString) -> String {
+    format!("[Rust cxx] Hello {}!", name.to_string_lossy())
+}
+
+// 用 i64 累加，任意多的 int 相加都不会溢出
+// Accumulates in i64 so that adding up any number of ints cannot overflow
+fn rust_sum(values: &CxxVector<i32>) -> i64 {
+    values.iter().map(|&value| i64::from(value)).sum()
+}
@@ -0,0 +1,162 @@
+// 用 object 读取动态库的导出表、自身的名字（ELF 的 SONAME、Mach-O 的 install name、PE 导出目录中的 DLL 名）
+// 和它依赖的库，不需要加载它，所以也能检查为其他平台构建的库
+// Reads a dynamic library's export table, its own name (the SONAME of ELF, the install name of
+// Mach-O, the DLL name in the PE export directory) and the libraries it depends on with object,
+// without loading it, so libraries built for other platforms can be inspected as well
+
+use std::collections::BTreeSet;
+use std::{fmt, fs, io, path::Path};
+
+use object::elf::{DT_NEEDED, DT_SONAME};
+use object::read::elf::{Dyn, ElfFile, FileHeader};
+use object::read::macho::{LoadCommandVariant, MachHeader, MachOFile};
+use object::read::pe::{ImageNtHeaders, PeFile};
+use object::{BinaryFormat, Endianness, LittleEndian as LE, Object};
+
+/// What a dynamic library exports and links against.
+#[derive(Debug)]
+pub struct Inspection {
+    pub format: BinaryFormat,
+    /// The name the library records for itself: the SONAME, install name or DLL name, if any.
+    pub name: Option<String>,
+    /// Exported symbols, without the leading underscore Mach-O adds to C names.
+    pub exports: BTreeSet<String>,
+    /// The libraries loaded along with this one, in the order the file lists them.
+    pub dependencies: Vec<String>,
+}
+
+/// Why [`inspect_file`] failed.
+#[derive(Debug)]
+pub enum InspectError {
+    Read(io::Error),
+    Parse(object::Error),
+    /// The file is an object format without dynamic linking information, such as wasm.
+    Unsupported(BinaryFormat),
+}
+
+impl fmt::Display for InspectError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InspectError::Read(err) => write!(f, "cannot read the file: {}", err),
+            InspectError::Parse(err) => write!(f, "cannot parse the file: {}", err),
+            InspectError::Unsupported(format) => {
+                write!(f, "{:?} files are not dynamic libraries", format)
+            }
+        }
+    }
+}
+
+impl std::error::Error for InspectError {}
+
+impl From<object::Error> for InspectError {
+    fn from(err: object::Error) -> Self {
+        InspectError::Parse(err)
+    }
+}
+
+/// Reads and inspects the library at `path`.
+pub fn inspect_file(path: &Path) -> Result<Inspection, InspectError> {
+    let data = fs::read(path).map_err(InspectError::Read)?;
+    inspect(&data)
+}
+
+/// Inspects a library already read into memory.
+pub fn inspect(data: &[u8]) -> Result<Inspection, InspectError> {
+    let file = object::File::parse(data)?;
+    let format = file.format();
+    let exports = file
+        .exports()?
+        .iter()
+        .map(|export| String::from_utf8_lossy(export.name()).into_owned())
+        .map(|name| match format {
+            BinaryFormat::MachO => name.strip_prefix('_').map(str::to_owned).unwrap_or(name),
+            _ => name,
+        })
+        .collect();
+    let (name, dependencies) = match &file {
+        object::File::Elf32(elf) => elf_dynamic(elf)?,
+        object::File::Elf64(elf) => elf_dynamic(elf)?,
+        object::File::MachO32(macho) => macho_dylibs(macho)?,
+        object::File::MachO64(macho) => macho_dylibs(macho)?,
+        object::File::Pe32(pe) => pe_dlls(pe)?,
+        object::File::Pe64(pe) => pe_dlls(pe)?,
+        _ => return Err(InspectError::Unsupported(format)),
+    };
+    Ok(Inspection {
+        format,
+        name,
+        exports,
+        dependencies,
+    })
+}
+
+type Names = (Option<String>, Vec<String>);
+
+fn string(bytes: &[u8]) -> String {
+    String::from_utf8_lossy(bytes).into_owned()
+}
+
+// DT_SONAME 和 DT_NEEDED 是动态段中指向其字符串表的偏移
+// DT_SONAME and DT_NEEDED are offsets into the string table linked to the dynamic section
+fn elf_dynamic<Elf: FileHeader<Endian = Endianness>>(
+    elf: &ElfFile<'_, Elf>,
+) -> Result<Names, object::Error> {
+    let endian = elf.endian();
+    let sections = elf.elf_section_table();
+    let (mut name, mut dependencies) = (None, Vec::new());
+    let Some((entries, link)) = sections.dynamic(endian, elf.data())? else {
+        return Ok((name, dependencies));
+    };
+    let strings = sections.strings(endian, elf.data(), link)?;
+    for entry in entries {
+        match entry.tag32(endian) {
+            Some(DT_SONAME) => name = Some(string(entry.string(endian, strings)?)),
+            Some(DT_NEEDED) => dependencies.push(string(entry.string(endian, strings)?)),
+            _ => {}
+        }
+    }
+    Ok((name, dependencies))
+}
+
+// LC_ID_DYLIB 记录库自己的 install name，LC_LOAD_DYLIB 及其变体记录依赖
+// LC_ID_DYLIB records the library's own install name, LC_LOAD_DYLIB and its variants the
+// dependencies
+fn macho_dylibs<Mach: MachHeader<Endian = Endianness>>(
+    macho: &MachOFile<'_, Mach>,
+) -> Result<Names, object::Error> {
+    let endian = macho.endian();
+    let (mut name, mut dependencies) = (None, Vec::new());
+    let mut commands = macho.macho_load_commands()?;
+    while let Some(command) = commands.next()? {
+        match command.variant()? {
+            LoadCommandVariant::IdDylib(dylib) => {
+                name = Some(string(command.string(endian, dylib.dylib.name)?))
+            }
+            LoadCommandVariant::Dylib(dylib) => {
+                dependencies.push(string(command.string(endian, dylib.dylib.name)?))
+            }
+            _ => {}
+        }
+    }
+    Ok((name, dependencies))
+}
+
+// 导出目录中记录了链接时的 DLL 名，导入表的每个描述符对应一个依赖的 DLL
+// The export directory records the DLL name at link time, and every descriptor of the import
+// table stands for a DLL depended on
+fn pe_dlls<Pe: ImageNtHeaders>(pe: &PeFile<'_, Pe>) -> Result<Names, object::Error> {
+    let name = match pe.export_table()? {
+        Some(exports) => Some(string(
+            exports.name_from_pointer(exports.directory().name.get(LE))?,
+        )),
+        None => None,
+    };
+    let mut dependencies = Vec::new();
+    if let Some(imports) = pe.import_table()? {
+        let mut descriptors = imports.descriptors()?;
+        while let Some(descriptor) = descriptors.next()? {
+            dependencies.push(string(imports.name(descriptor.name.get(LE))?));
+        }
+    }
+    Ok((name, dependencies))
+}
@@ -0,0 +1,30 @@
+// 打印动态库的格式、名字、依赖和导出符号
+// Prints a dynamic library's format, name, dependencies and exported symbols
+//
+// 运行 / Run: cargo run -p dylib_inspect -- target/debug/libcdylib_gen.so
+
+use std::{env, path::PathBuf, process};
+
+fn main() {
+    let Some(path) = env::args_os().nth(1).map(PathBuf::from) else {
+        eprintln!("usage: dylib_inspect <library>");
+        process::exit(2);
+    };
+    let inspection = match dylib_inspect::inspect_file(&path) {
+        Ok(inspection) => inspection,
+        Err(err) => {
+            eprintln!("{}: {}", path.display(), err);
+            process::exit(1);
+        }
+    };
+    println!("{}: {:?}", path.display(), inspection.format);
+    println!("name: {}", inspection.name.as_deref().unwrap_or("(none)"));
+    println!("dependencies:");
+    for dependency in &inspection.dependencies {
+        println!("  {}", dependency);
+    }
+    println!("exports ({}):", inspection.exports.len());
+    for export in &inspection.exports {
+        println!("  {}", export);
+    }
+}
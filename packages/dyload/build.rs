@@ -0,0 +1,21 @@
+// 把 external_lib/dylib.c 编译成 OUT_DIR 中的动态库，供 tests/ownership.rs 加载；测试只在 Unix 上运行
+// Compiles external_lib/dylib.c into a dynamic library in OUT_DIR for tests/ownership.rs to load;
+// the tests only run on Unix
+
+fn main() {
+    let source = "../../external_lib/dylib.c";
+    println!("cargo::rerun-if-changed={}", source);
+    if std::env::var("CARGO_CFG_UNIX").is_err() {
+        return;
+    }
+    let out_dir = std::path::PathBuf::from(std::env::var("OUT_DIR").unwrap());
+    let out_file = out_dir.join("libexternal_dy.so");
+    let compiler = cc::Build::new().static_flag(false).get_compiler();
+    let status = compiler
+        .to_command()
+        .args(["-shared", "-fPIC", source, "-o"])
+        .arg(&out_file)
+        .status()
+        .expect("Failed to run the C compiler for external_lib.");
+    assert!(status.success(), "Compiling {} failed.", source);
+}
@@ -0,0 +1,134 @@
+// 共享所有权的动态库句柄：LibraryHandle 把 Library 放在 Arc 中，从它取出的每个 OwnedSymbol 都持有一份
+// 引用，所以只要还有符号、回调或指向库中数据的指针活着，库就不会被卸载；最后一份引用消失时才卸载
+// A dynamic library handle with shared ownership: LibraryHandle keeps the Library in an Arc and
+// every OwnedSymbol taken from it holds a reference, so the library can't be unloaded while a
+// symbol, a callback or a pointer into its data is still alive; it unloads when the last
+// reference goes away
+//
+// libloading 的 Symbol 借用 Library，生命周期检查只能在一个作用域里起作用，不能把符号存进结构体或交给
+// 其他线程；这里用引用计数代替借用
+// libloading's Symbol borrows the Library, so the lifetime check only works within one scope and
+// the symbol can't be stored in a struct or handed to another thread; reference counting replaces
+// the borrow here
+
+use std::ffi::OsStr;
+use std::fmt;
+use std::ops::Deref;
+use std::sync::{Arc, Weak};
+
+use libloading::{Error, Library};
+
+/// A loaded library, shared by every [`OwnedSymbol`] taken from it.
+///
+/// Clones refer to the same copy of the library. It is unloaded once the last handle and the
+/// last symbol are dropped.
+#[derive(Clone)]
+pub struct LibraryHandle {
+    lib: Arc<Library>,
+}
+
+impl LibraryHandle {
+    /// Loads the library at `path`, see [`Library::new`].
+    ///
+    /// # Safety
+    ///
+    /// The library's initialisers run, with the same requirements as [`Library::new`].
+    pub unsafe fn open(path: impl AsRef<OsStr>) -> Result<LibraryHandle, Error> {
+        Library::new(path).map(LibraryHandle::new)
+    }
+
+    /// Takes ownership of a library that is already loaded.
+    pub fn new(lib: Library) -> LibraryHandle {
+        LibraryHandle { lib: Arc::new(lib) }
+    }
+
+    /// Looks up `name`, returning a symbol that keeps the library loaded.
+    ///
+    /// # Safety
+    ///
+    /// `T` must match the type of the symbol, as for [`Library::get`].
+    pub unsafe fn get<T: Copy>(&self, name: &[u8]) -> Result<OwnedSymbol<T>, Error> {
+        let value = *self.lib.get::<T>(name)?;
+        Ok(OwnedSymbol {
+            value,
+            lib: self.clone(),
+        })
+    }
+
+    /// A reference that doesn't keep the library loaded, to check later whether it was unloaded.
+    pub fn downgrade(&self) -> WeakLibraryHandle {
+        WeakLibraryHandle {
+            lib: Arc::downgrade(&self.lib),
+        }
+    }
+
+    /// The underlying library, to call the platform-specific parts of libloading.
+    pub fn library(&self) -> &Library {
+        &self.lib
+    }
+}
+
+impl From<Library> for LibraryHandle {
+    fn from(lib: Library) -> LibraryHandle {
+        LibraryHandle::new(lib)
+    }
+}
+
+impl fmt::Debug for LibraryHandle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LibraryHandle")
+            .field("holders", &Arc::strong_count(&self.lib))
+            .finish()
+    }
+}
+
+/// A [`LibraryHandle`] that doesn't keep the library loaded.
+#[derive(Clone, Debug)]
+pub struct WeakLibraryHandle {
+    lib: Weak<Library>,
+}
+
+impl WeakLibraryHandle {
+    /// Whether some handle or symbol still keeps the library loaded.
+    pub fn is_loaded(&self) -> bool {
+        self.holders() > 0
+    }
+
+    /// How many handles and symbols keep the library loaded.
+    pub fn holders(&self) -> usize {
+        self.lib.strong_count()
+    }
+}
+
+/// A symbol that keeps its library loaded for as long as it lives.
+///
+/// It dereferences to the symbol's value. Copying the value out, such as a function pointer,
+/// leaves that copy unprotected: keep the `OwnedSymbol` and call through it instead.
+#[derive(Clone)]
+pub struct OwnedSymbol<T> {
+    value: T,
+    lib: LibraryHandle,
+}
+
+impl<T> OwnedSymbol<T> {
+    /// The library the symbol came from.
+    pub fn library(&self) -> &LibraryHandle {
+        &self.lib
+    }
+}
+
+impl<T> Deref for OwnedSymbol<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T> fmt::Debug for OwnedSymbol<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OwnedSymbol")
+            .field("lib", &self.lib)
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,73 @@
+// LibraryHandle 和 OwnedSymbol 的测试：只要还有符号活着库就保持加载，最后一个持有者消失后库被卸载，
+// 重新加载得到一份新的静态状态
+// Tests for LibraryHandle and OwnedSymbol: the library stays loaded while any symbol is alive, is
+// unloaded once the last holder goes away, and loading it again gives fresh static state
+
+#![cfg(unix)]
+
+use std::ffi::{c_char, c_int, CStr};
+use std::path::PathBuf;
+use std::thread;
+
+use dyload::{LibraryHandle, OwnedSymbol};
+
+type DyloadingAdd = unsafe extern "C" fn(c_int, c_int, *mut c_char) -> c_int;
+type DyloadingCallCount = unsafe extern "C" fn() -> c_int;
+
+fn open() -> LibraryHandle {
+    let path = PathBuf::from(env!("OUT_DIR")).join("libexternal_dy.so");
+    unsafe { LibraryHandle::open(path) }.unwrap()
+}
+
+fn add(add: &OwnedSymbol<DyloadingAdd>, a: c_int, b: c_int) -> String {
+    let mut buf = [0 as c_char; 128];
+    buf[..5].copy_from_slice(&b"Jack\0".map(|byte| byte as c_char));
+    unsafe {
+        assert_eq!((**add)(a, b, buf.as_mut_ptr()), a + b);
+        CStr::from_ptr(buf.as_ptr()).to_str().unwrap().to_owned()
+    }
+}
+
+fn call_count(lib: &LibraryHandle) -> c_int {
+    unsafe {
+        let call_count = lib
+            .get::<DyloadingCallCount>(b"dyloading_call_count\0")
+            .unwrap();
+        (*call_count)()
+    }
+}
+
+// 一个测试里按顺序进行：同一进程中另一个测试打开同一个库会让它一直保持加载
+// Done in order in a single test: another test in the same process opening the library would
+// keep it loaded throughout
+#[test]
+fn the_last_holder_unloads_the_library() {
+    let lib = open();
+    let weak = lib.downgrade();
+    let symbol = unsafe { lib.get::<DyloadingAdd>(b"dyloading_add\0") }.unwrap();
+    assert_eq!(weak.holders(), 2);
+
+    // 句柄先被丢掉，符号仍然可以调用
+    // The handle is dropped first, and the symbol can still be called
+    drop(lib);
+    assert!(weak.is_loaded());
+    assert_eq!(
+        add(&symbol, 1, 2),
+        "[External dyloading] The result (1 + 2) is 3!"
+    );
+
+    // 符号可以交给其他线程，库随它一起留下
+    // A symbol can be handed to another thread, and the library stays with it
+    let moved = symbol.clone();
+    let message = thread::spawn(move || add(&moved, 3, 4)).join().unwrap();
+    assert_eq!(message, "[External dyloading] The result (3 + 4) is 7!");
+    assert_eq!(call_count(symbol.library()), 2);
+
+    drop(symbol);
+    assert!(!weak.is_loaded());
+
+    // 库被真正卸载过，静态变量从头开始
+    // The library really was unloaded, so its statics start over
+    let reloaded = open();
+    assert_eq!(call_count(&reloaded), 0);
+}
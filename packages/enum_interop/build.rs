@@ -0,0 +1,7 @@
+// 这是我们的构建脚本，编译和 Rust 共享 Shape 标签联合体的 C 代码
+// This is our build script, it compiles the C code sharing the Shape tagged union with Rust
+
+fn main() {
+    cc::Build::new().file("c/shape.c").std("c11").compile("shape");
+    println!("cargo::rerun-if-changed=c");
+}
@@ -0,0 +1,65 @@
+// Shape 标签联合体在 C 端的定义，必须和 src/lib.rs 中的 #[repr(C)] 类型保持一致
+// The C definition of the Shape tagged union, it must match the #[repr(C)] types in src/lib.rs
+#ifndef SHAPE_H
+#define SHAPE_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+typedef enum ShapeKind
+{
+    SHAPE_KIND_CIRCLE = 0,
+    SHAPE_KIND_RECT = 1,
+    SHAPE_KIND_TRIANGLE = 2,
+} ShapeKind;
+
+typedef struct CircleData
+{
+    double radius;
+} CircleData;
+
+typedef struct RectData
+{
+    double width;
+    double height;
+} RectData;
+
+typedef struct TriangleData
+{
+    double a;
+    double b;
+    double c;
+} TriangleData;
+
+typedef union ShapeData
+{
+    CircleData circle;
+    RectData rect;
+    TriangleData triangle;
+} ShapeData;
+
+// tag 是普通的 int，C 端可能传入任何值，Rust 端必须先校验
+// tag is a plain int, C may pass any value, so Rust must validate it first
+typedef struct Shape
+{
+    int tag;
+    ShapeData data;
+} Shape;
+
+_Static_assert(sizeof(ShapeKind) == sizeof(int), "ShapeKind must be int-sized");
+_Static_assert(sizeof(ShapeData) == 3 * sizeof(double), "ShapeData must hold three doubles");
+_Static_assert(offsetof(Shape, data) == _Alignof(double), "Shape.data must follow tag at double alignment");
+_Static_assert(sizeof(Shape) == offsetof(Shape, data) + sizeof(ShapeData), "Shape must have no trailing padding");
+
+// 由 C 实现，Rust 调用
+// Implemented in C, called from Rust
+Shape c_shape_make(ShapeKind kind, double size);
+const char *c_shape_kind_name(int tag);
+int c_shape_harness(void);
+
+// 由 Rust 实现，C 调用
+// Implemented in Rust, called from C
+int rust_shape_area(const Shape *shape, double *area);
+Shape rust_shape_scale(Shape shape, double factor);
+
+#endif
@@ -0,0 +1,213 @@
+// 这个库演示 #[repr(C)] 枚举、#[repr(C)] 联合体以及与 C 的 `struct { int tag; union {...} }` 对应的标签联合体
+// This library demonstrates #[repr(C)] enums, #[repr(C)] unions and the tagged union matching
+// C's `struct { int tag; union {...} }`
+
+use std::ffi::{c_char, c_int, CStr};
+use std::mem;
+
+/// A field-less `#[repr(C)]` enum has the size of a C `int`, like `enum ShapeKind` in c/shape.h.
+///
+/// Never accept it from C by value or in a struct: any out-of-range integer would be undefined
+/// behaviour in Rust. The structs below carry the tag as a plain `c_int` instead.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShapeKind {
+    Circle = 0,
+    Rect = 1,
+    Triangle = 2,
+}
+
+impl TryFrom<c_int> for ShapeKind {
+    type Error = c_int;
+
+    fn try_from(tag: c_int) -> Result<Self, c_int> {
+        match tag {
+            0 => Ok(ShapeKind::Circle),
+            1 => Ok(ShapeKind::Rect),
+            2 => Ok(ShapeKind::Triangle),
+            _ => Err(tag),
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CircleData {
+    pub radius: f64,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RectData {
+    pub width: f64,
+    pub height: f64,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TriangleData {
+    pub a: f64,
+    pub b: f64,
+    pub c: f64,
+}
+
+/// The untagged payload; reading a field is `unsafe` because nothing says which one is active.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub union ShapeData {
+    pub circle: CircleData,
+    pub rect: RectData,
+    pub triangle: TriangleData,
+}
+
+/// The C-side tagged union, `struct Shape` in c/shape.h.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct Shape {
+    pub tag: c_int,
+    pub data: ShapeData,
+}
+
+/// 和 Shape 布局相同的 Rust 枚举：带数据的 #[repr(C)] 枚举就是 tag + union
+/// The idiomatic Rust counterpart of [`Shape`]: a data-carrying `#[repr(C)]` enum is laid out as
+/// exactly that tag + union pair, but unlike `Shape` it can only hold a valid tag.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ShapeValue {
+    Circle(CircleData),
+    Rect(RectData),
+    Triangle(TriangleData),
+}
+
+// 布局断言，C 端的 shape.h 中有对应的 _Static_assert；f64 的对齐随目标而变（i686 上是 4），
+// 所以偏移和大小都由它推出
+// Layout assertions, mirrored by the _Static_asserts in shape.h; the alignment of f64 varies by
+// target (4 on i686), so the offset and sizes are derived from it
+const _: () = assert!(mem::size_of::<ShapeKind>() == mem::size_of::<c_int>());
+const _: () = assert!(mem::size_of::<ShapeData>() == 3 * mem::size_of::<f64>());
+const _: () = assert!(mem::offset_of!(Shape, data) == mem::align_of::<f64>());
+const _: () =
+    assert!(mem::size_of::<Shape>() == mem::offset_of!(Shape, data) + mem::size_of::<ShapeData>());
+const _: () = assert!(mem::size_of::<ShapeValue>() == mem::size_of::<Shape>());
+const _: () = assert!(mem::align_of::<ShapeValue>() == mem::align_of::<Shape>());
+
+impl From<ShapeValue> for Shape {
+    fn from(value: ShapeValue) -> Self {
+        match value {
+            ShapeValue::Circle(circle) => Shape {
+                tag: ShapeKind::Circle as c_int,
+                data: ShapeData { circle },
+            },
+            ShapeValue::Rect(rect) => Shape {
+                tag: ShapeKind::Rect as c_int,
+                data: ShapeData { rect },
+            },
+            ShapeValue::Triangle(triangle) => Shape {
+                tag: ShapeKind::Triangle as c_int,
+                data: ShapeData { triangle },
+            },
+        }
+    }
+}
+
+impl TryFrom<Shape> for ShapeValue {
+    /// The invalid tag.
+    type Error = c_int;
+
+    fn try_from(shape: Shape) -> Result<Self, c_int> {
+        // 只有在校验过 tag 之后才读取对应的联合体字段
+        // The union field is only read once the tag has been validated
+        let value = unsafe {
+            match ShapeKind::try_from(shape.tag)? {
+                ShapeKind::Circle => ShapeValue::Circle(shape.data.circle),
+                ShapeKind::Rect => ShapeValue::Rect(shape.data.rect),
+                ShapeKind::Triangle => ShapeValue::Triangle(shape.data.triangle),
+            }
+        };
+        Ok(value)
+    }
+}
+
+impl ShapeValue {
+    pub fn area(&self) -> f64 {
+        match *self {
+            ShapeValue::Circle(CircleData { radius }) => std::f64::consts::PI * radius * radius,
+            ShapeValue::Rect(RectData { width, height }) => width * height,
+            ShapeValue::Triangle(TriangleData { a, b, c }) => {
+                let s = (a + b + c) / 2.0;
+                (s * (s - a) * (s - b) * (s - c)).sqrt()
+            }
+        }
+    }
+
+    pub fn scale(&self, factor: f64) -> ShapeValue {
+        match *self {
+            ShapeValue::Circle(CircleData { radius }) => ShapeValue::Circle(CircleData {
+                radius: radius * factor,
+            }),
+            ShapeValue::Rect(RectData { width, height }) => ShapeValue::Rect(RectData {
+                width: width * factor,
+                height: height * factor,
+            }),
+            ShapeValue::Triangle(TriangleData { a, b, c }) => ShapeValue::Triangle(TriangleData {
+                a: a * factor,
+                b: b * factor,
+                c: c * factor,
+            }),
+        }
+    }
+}
+
+extern "C" {
+    fn c_shape_make(kind: ShapeKind, size: f64) -> Shape;
+    fn c_shape_kind_name(tag: c_int) -> *const c_char;
+    fn c_shape_harness() -> c_int;
+}
+
+/// Stores the area of `shape` in `area`. Returns 0 on success, -1 for a NULL pointer and -2 for
+/// an invalid tag.
+///
+/// # Safety
+///
+/// `shape` must be NULL or point to a valid `Shape`, `area` must be NULL or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn rust_shape_area(shape: *const Shape, area: *mut f64) -> c_int {
+    let (Some(shape), false) = (shape.as_ref(), area.is_null()) else {
+        return -1;
+    };
+    match ShapeValue::try_from(*shape) {
+        Ok(value) => {
+            *area = value.area();
+            0
+        }
+        Err(_) => -2,
+    }
+}
+
+/// Scales `shape` by `factor`, passing and returning the tagged union by value. A shape with an
+/// invalid tag is returned unchanged.
+#[no_mangle]
+pub extern "C" fn rust_shape_scale(shape: Shape, factor: f64) -> Shape {
+    match ShapeValue::try_from(shape) {
+        Ok(value) => value.scale(factor).into(),
+        Err(_) => shape,
+    }
+}
+
+/// Builds a shape of the given kind in C and converts it back to a [`ShapeValue`].
+pub fn make(kind: ShapeKind, size: f64) -> ShapeValue {
+    let shape = unsafe { c_shape_make(kind, size) };
+    ShapeValue::try_from(shape).expect("C returned an invalid tag.")
+}
+
+/// Returns the name C uses for `tag`, including "unknown" for invalid tags.
+pub fn kind_name(tag: c_int) -> &'static str {
+    unsafe { CStr::from_ptr(c_shape_kind_name(tag)) }
+        .to_str()
+        .unwrap()
+}
+
+/// Runs the C harness that calls the Rust exports, returning its number of failed checks.
+pub fn run_c_harness() -> c_int {
+    unsafe { c_shape_harness() }
+}
@@ -0,0 +1,81 @@
+// Shape 标签联合体的测试：C 的测试程序没有失败，Rust 和 C 之间按值和按指针的往返都保持数据不变，
+// 越界的 tag 会被拒绝
+// Tests for the Shape tagged union: the C harness reports no failures, round trips between Rust
+// and C by value and by pointer keep the data intact, and an out-of-range tag is rejected
+
+use std::ffi::c_int;
+use std::ptr;
+
+use enum_interop::{
+    kind_name, make, run_c_harness, rust_shape_area, rust_shape_scale, CircleData, RectData, Shape,
+    ShapeData, ShapeKind, ShapeValue, TriangleData,
+};
+
+#[test]
+fn c_harness_passes() {
+    assert_eq!(run_c_harness(), 0);
+}
+
+#[test]
+fn shapes_made_in_c_round_trip() {
+    assert_eq!(
+        make(ShapeKind::Circle, 1.5),
+        ShapeValue::Circle(CircleData { radius: 1.5 })
+    );
+    assert_eq!(
+        make(ShapeKind::Rect, 1.5),
+        ShapeValue::Rect(RectData {
+            width: 1.5,
+            height: 3.0,
+        })
+    );
+    assert_eq!(
+        make(ShapeKind::Triangle, 2.0),
+        ShapeValue::Triangle(TriangleData {
+            a: 2.0,
+            b: 2.0,
+            c: 2.0,
+        })
+    );
+}
+
+#[test]
+fn values_round_trip_through_the_exports() {
+    let value = ShapeValue::Rect(RectData {
+        width: 3.0,
+        height: 6.0,
+    });
+    let shape = Shape::from(value);
+    assert_eq!(shape.tag, ShapeKind::Rect as c_int);
+    assert_eq!(ShapeValue::try_from(shape), Ok(value));
+
+    let scaled = rust_shape_scale(shape, 0.5);
+    assert_eq!(ShapeValue::try_from(scaled), Ok(value.scale(0.5)));
+
+    let mut area = 0.0;
+    assert_eq!(unsafe { rust_shape_area(&shape, &mut area) }, 0);
+    assert_eq!(area, 18.0);
+    assert_eq!(unsafe { rust_shape_area(ptr::null(), &mut area) }, -1);
+    assert_eq!(unsafe { rust_shape_area(&shape, ptr::null_mut()) }, -1);
+}
+
+#[test]
+fn out_of_range_tags_are_rejected() {
+    let bogus = Shape {
+        tag: 42,
+        data: ShapeData {
+            circle: CircleData { radius: 1.0 },
+        },
+    };
+    assert_eq!(ShapeKind::try_from(42), Err(42));
+    assert_eq!(ShapeKind::try_from(-1), Err(-1));
+    assert_eq!(ShapeValue::try_from(bogus), Err(42));
+
+    let mut area = 0.0;
+    assert_eq!(unsafe { rust_shape_area(&bogus, &mut area) }, -2);
+    // 越界的 tag 原样返回，不会被当成某个有效的形状
+    // An out-of-range tag comes back unchanged instead of being treated as some valid shape
+    assert_eq!(rust_shape_scale(bogus, 2.0).tag, 42);
+    assert_eq!(kind_name(42), "unknown");
+    assert_eq!(kind_name(ShapeKind::Triangle as c_int), "triangle");
+}
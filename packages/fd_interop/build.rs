@@ -0,0 +1,11 @@
+// 这是我们的构建脚本，编译打开文件并把文件描述符或句柄在 C 和 Rust 之间传递的 C 代码
+// This is our build script, it compiles the C code that opens files and passes their file
+// descriptors or handles between C and Rust
+
+fn main() {
+    cc::Build::new()
+        .file("c/files.c")
+        .std("c11")
+        .compile("files");
+    println!("cargo::rerun-if-changed=c");
+}
@@ -0,0 +1,141 @@
+// C 一侧的文件传递：打开文件，用 fdopen 借用或接管 Rust 交来的文件，以及把自己打开的文件先借给、再交给
+// Rust。Windows 上 fdopen 需要 C 运行时的描述符，先用 _open_osfhandle 把 HANDLE 包成一个，之后关闭描述符
+// 也就关闭了 HANDLE
+// File passing on the C side: opening files, borrowing or taking over files from Rust with
+// fdopen, and lending and then handing a file it opened itself to Rust. On Windows fdopen needs a
+// C runtime descriptor, so _open_osfhandle wraps the HANDLE into one first, and closing that
+// descriptor closes the HANDLE as well
+#ifndef _WIN32
+// -std=c11 只声明标准 C 的函数，fdopen 和 dup 需要明确要求 POSIX
+// -std=c11 only declares standard C functions, fdopen and dup need POSIX asked for
+#define _POSIX_C_SOURCE 200809L
+#endif
+#include <stdio.h>
+#include <string.h>
+#include "files.h"
+
+#ifdef _WIN32
+#include <fcntl.h>
+#include <io.h>
+#include <windows.h>
+#else
+#include <fcntl.h>
+#include <unistd.h>
+#endif
+
+raw_file c_file_open(const char *path)
+{
+#ifdef _WIN32
+    return CreateFileA(path, GENERIC_READ | GENERIC_WRITE, FILE_SHARE_READ | FILE_SHARE_WRITE, NULL,
+                       CREATE_ALWAYS, FILE_ATTRIBUTE_NORMAL, NULL);
+#else
+    return open(path, O_RDWR | O_CREAT | O_TRUNC, 0600);
+#endif
+}
+
+// 在 file 上打开一个写入的流，之后 fclose 会关闭 file；失败时 file 已经关闭
+// Opens a stream for writing on file, whose fclose then closes file; on failure file is closed
+// already
+static FILE *open_stream(raw_file file)
+{
+#ifdef _WIN32
+    int fd = _open_osfhandle((intptr_t)file, _O_WRONLY);
+    if (fd < 0)
+    {
+        CloseHandle(file);
+        return NULL;
+    }
+    FILE *stream = _fdopen(fd, "w");
+    if (stream == NULL)
+    {
+        _close(fd);
+    }
+#else
+    FILE *stream = fdopen(file, "w");
+    if (stream == NULL)
+    {
+        close(file);
+    }
+#endif
+    return stream;
+}
+
+static int32_t write_and_close(FILE *stream, const char *text)
+{
+    if (stream == NULL)
+    {
+        return -1;
+    }
+    int failed = fputs(text, stream) < 0;
+    failed |= fclose(stream) != 0;
+    return failed ? -1 : 0;
+}
+
+int32_t c_file_borrow_puts(raw_file file, const char *text)
+{
+#ifdef _WIN32
+    HANDLE copy;
+    if (!DuplicateHandle(GetCurrentProcess(), file, GetCurrentProcess(), &copy, 0, FALSE,
+                         DUPLICATE_SAME_ACCESS))
+    {
+        return -1;
+    }
+#else
+    int copy = dup(file);
+    if (copy < 0)
+    {
+        return -1;
+    }
+#endif
+    return write_and_close(open_stream(copy), text);
+}
+
+int32_t c_file_transfer_puts(raw_file file, const char *text)
+{
+    return write_and_close(open_stream(file), text);
+}
+
+int32_t c_file_is_open(raw_file file)
+{
+#ifdef _WIN32
+    DWORD flags;
+    return GetHandleInformation(file, &flags) != 0;
+#else
+    return fcntl(file, F_GETFD) != -1;
+#endif
+}
+
+// C 自己写入的那一行直接写到 file 上，不经过流，证明 Rust 借用之后 file 还是打开的
+// C's own line is written to file directly, not through a stream, showing file is still open
+// after Rust borrowed it
+static int write_line(raw_file file, const char *text)
+{
+#ifdef _WIN32
+    DWORD written;
+    return WriteFile(file, text, (DWORD)strlen(text), &written, NULL) ? 0 : -1;
+#else
+    return write(file, text, strlen(text)) == (ssize_t)strlen(text) ? 0 : -1;
+#endif
+}
+
+int64_t c_share_file_with_rust(const char *path, char *out, size_t out_len)
+{
+    raw_file file = c_file_open(path);
+    if (file == RAW_FILE_INVALID)
+    {
+        return -1;
+    }
+    if (rust_file_borrow_write(file, "[Rust] written through a borrowed file\n") != 0 ||
+        write_line(file, "[C source] written after Rust returned it\n") != 0)
+    {
+#ifdef _WIN32
+        CloseHandle(file);
+#else
+        close(file);
+#endif
+        return -1;
+    }
+    // 交给 Rust 之后就不再碰 file，关闭它是 Rust 的事
+    // file isn't touched once it was handed to Rust, closing it is Rust's job
+    return rust_file_transfer_read(file, out, out_len);
+}
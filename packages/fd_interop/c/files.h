@@ -0,0 +1,60 @@
+// 在 C 和 Rust 之间传递打开的文件：Unix 上是文件描述符，Windows 上是 HANDLE。每个函数都在名字里写明
+// 所有权：borrow 只在调用期间使用文件，调用方之后照常使用并负责关闭；transfer 把文件交给对方，对方负责
+// 关闭，调用方之后不能再碰它
+// Passing open files between C and Rust: a file descriptor on Unix and a HANDLE on Windows. Every
+// function states the ownership in its name: borrow only uses the file during the call, and the
+// caller goes on using it afterwards and remains responsible for closing it; transfer hands the
+// file over, the callee becomes responsible for closing it and the caller must not touch it again
+#ifndef FILES_H
+#define FILES_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef _WIN32
+typedef void *raw_file;
+#define RAW_FILE_INVALID ((raw_file)(intptr_t)-1)
+#else
+typedef int raw_file;
+#define RAW_FILE_INVALID (-1)
+#endif
+
+// 由 C 实现，Rust 调用
+// Implemented in C, called from Rust
+
+// 打开或创建 path 用于读写并清空它，所有权交给调用方；失败时返回 RAW_FILE_INVALID
+// Opens or creates path for reading and writing and truncates it, ownership goes to the caller;
+// returns RAW_FILE_INVALID on failure
+raw_file c_file_open(const char *path);
+// 通过复制出的描述符或句柄用 fdopen 写入 text，fclose 只关闭那份副本；成功时返回 0
+// Writes text with fdopen on a duplicated descriptor or handle, so fclose only closes the copy;
+// returns 0 on success
+int32_t c_file_borrow_puts(raw_file file, const char *text);
+// 直接对 file 用 fdopen 写入 text，fclose 连同 file 一起关闭，失败时也会关闭；成功时返回 0
+// Writes text with fdopen on file itself, and fclose closes file along with the stream, failures
+// close it too; returns 0 on success
+int32_t c_file_transfer_puts(raw_file file, const char *text);
+// C 打开一个文件，借给 Rust 写入一行，自己再写入一行，最后把它交给 Rust 读回全部内容并关闭。返回读到
+// 的字节数，出错时返回 -1
+// C opens a file, lends it to Rust to write a line, writes a line of its own and finally hands it
+// to Rust, which reads back everything and closes it. Returns the number of bytes read, or -1 on
+// failure
+// file 是否还是打开的描述符或句柄，测试用它检查文件只被关闭了一次，而且没有提前关闭
+// Whether file is still an open descriptor or handle, the tests use it to check a file was closed
+// exactly once and not too early
+int32_t c_file_is_open(raw_file file);
+int64_t c_share_file_with_rust(const char *path, char *out, size_t out_len);
+
+// 由 Rust 实现，C 调用
+// Implemented in Rust, called from C
+
+// 在文件当前的位置写入 text，不关闭它；成功时返回 0
+// Writes text at the file's current position without closing it; returns 0 on success
+int32_t rust_file_borrow_write(raw_file file, const char *text);
+// 取得文件的所有权，从头读取最多 out_len 个字节到 out 并关闭它，返回读到的字节数，出错时返回 -1，
+// 文件同样会被关闭
+// Takes ownership of the file, reads up to out_len bytes from its start into out and closes it,
+// returning the number of bytes read, or -1 on failure, in which case the file is closed as well
+int64_t rust_file_transfer_read(raw_file file, char *out, size_t out_len);
+
+#endif
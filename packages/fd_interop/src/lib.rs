@@ -0,0 +1,201 @@
+// 这个库演示打开的文件在 C 和 Rust 之间的传递，以及谁负责关闭它：Unix 上传递的是文件描述符，Windows 上
+// 是 HANDLE。C 打开的文件交给 Rust 后由 File::from_raw_fd 或 from_raw_handle 接管；只借给 Rust 时
+// 用 ManuallyDrop 包住，File 被丢弃时不会关闭它。反方向 Rust 把文件交给 C 的 fdopen，借出时 C 先复制一份
+// 描述符，fclose 只关闭副本。所有权写在类型里：借出用 AsFd / AsHandle，转移用 OwnedFd / OwnedHandle，
+// 转移之后 Rust 这边已经没有可以再关闭一次的值
+// This library demonstrates passing open files between C and Rust, and who is responsible for
+// closing them: a file descriptor is passed on Unix and a HANDLE on Windows. A file C opened and
+// hands to Rust is taken over by File::from_raw_fd or from_raw_handle; one only lent to Rust is
+// wrapped in ManuallyDrop, so dropping the File doesn't close it. In the other direction Rust
+// hands files to C's fdopen, and when it only lends one C duplicates the descriptor first so
+// fclose only closes the copy. The ownership is in the types: lending takes AsFd / AsHandle and
+// transferring OwnedFd / OwnedHandle, so after a transfer Rust has nothing left to close again
+
+use std::ffi::{c_char, CStr, CString};
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::mem::ManuallyDrop;
+use std::path::Path;
+use std::slice;
+
+#[cfg(unix)]
+use std::os::fd::{AsFd as AsFile, AsRawFd, FromRawFd, IntoRawFd, OwnedFd as OwnedFile};
+#[cfg(windows)]
+use std::os::windows::io::{
+    AsHandle as AsFile, AsRawHandle, FromRawHandle, IntoRawHandle, OwnedHandle as OwnedFile,
+};
+
+/// 在 C 和 Rust 之间传递的文件，和 c/files.h 中的 raw_file 相同
+/// A file as passed between C and Rust, the same as `raw_file` in c/files.h: a file descriptor
+/// on Unix.
+#[cfg(unix)]
+pub type RawFile = std::os::fd::RawFd;
+
+/// 在 C 和 Rust 之间传递的文件，和 c/files.h 中的 raw_file 相同
+/// A file as passed between C and Rust, the same as `raw_file` in c/files.h: a HANDLE on
+/// Windows.
+#[cfg(windows)]
+pub type RawFile = std::os::windows::io::RawHandle;
+
+extern "C" {
+    fn c_file_open(path: *const c_char) -> RawFile;
+    fn c_file_borrow_puts(file: RawFile, text: *const c_char) -> i32;
+    fn c_file_transfer_puts(file: RawFile, text: *const c_char) -> i32;
+    fn c_file_is_open(file: RawFile) -> i32;
+    fn c_share_file_with_rust(path: *const c_char, out: *mut c_char, out_len: usize) -> i64;
+}
+
+#[cfg(unix)]
+fn is_invalid(file: RawFile) -> bool {
+    file < 0
+}
+
+#[cfg(windows)]
+fn is_invalid(file: RawFile) -> bool {
+    file as isize == -1
+}
+
+#[cfg(unix)]
+fn raw(file: &impl AsFile) -> RawFile {
+    file.as_fd().as_raw_fd()
+}
+
+#[cfg(windows)]
+fn raw(file: &impl AsFile) -> RawFile {
+    file.as_handle().as_raw_handle()
+}
+
+#[cfg(unix)]
+unsafe fn file_from_raw(file: RawFile) -> File {
+    File::from_raw_fd(file)
+}
+
+#[cfg(windows)]
+unsafe fn file_from_raw(file: RawFile) -> File {
+    File::from_raw_handle(file)
+}
+
+#[cfg(unix)]
+fn into_raw(file: OwnedFile) -> RawFile {
+    file.into_raw_fd()
+}
+
+#[cfg(windows)]
+fn into_raw(file: OwnedFile) -> RawFile {
+    file.into_raw_handle()
+}
+
+fn c_string(text: &str) -> io::Result<CString> {
+    CString::new(text).map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))
+}
+
+fn status(code: i32) -> io::Result<()> {
+    match code {
+        0 => Ok(()),
+        _ => Err(io::Error::last_os_error()),
+    }
+}
+
+/// Has C open or create `path` for reading and writing, truncated, and takes ownership of the
+/// descriptor or handle it returns.
+pub fn c_open(path: &Path) -> io::Result<File> {
+    let path = c_string(&path.to_string_lossy())?;
+    let file = unsafe { c_file_open(path.as_ptr()) };
+    if is_invalid(file) {
+        return Err(io::Error::last_os_error());
+    }
+    // SAFETY: C opened the file and gave up ownership of it
+    Ok(unsafe { file_from_raw(file) })
+}
+
+/// Lends `file` to C, which writes `text` through `fdopen` on a duplicate and only closes that;
+/// `file` stays open and usable.
+pub fn c_puts_borrowed(file: &impl AsFile, text: &str) -> io::Result<()> {
+    let text = c_string(text)?;
+    status(unsafe { c_file_borrow_puts(raw(file), text.as_ptr()) })
+}
+
+/// Hands `file` over to C, which writes `text` through `fdopen` and closes it with `fclose`,
+/// whether writing succeeded or not.
+pub fn c_puts_transferred(file: impl Into<OwnedFile>, text: &str) -> io::Result<()> {
+    let text = c_string(text)?;
+    status(unsafe { c_file_transfer_puts(into_raw(file.into()), text.as_ptr()) })
+}
+
+/// Whether `file` is an open descriptor or handle, as seen by C.
+///
+/// A raw value of a file that was closed may have been reused by another file since, which then
+/// reads as open.
+pub fn c_is_open(file: RawFile) -> bool {
+    unsafe { c_file_is_open(file) != 0 }
+}
+
+/// Has C open `path`, lend it to [`rust_file_borrow_write`], write a line of its own and hand it
+/// to [`rust_file_transfer_read`], returning what that read back.
+pub fn c_share_file(path: &Path) -> io::Result<String> {
+    let path = c_string(&path.to_string_lossy())?;
+    let mut out = [0u8; 256];
+    let len = unsafe { c_share_file_with_rust(path.as_ptr(), out.as_mut_ptr().cast(), out.len()) };
+    if len < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(String::from_utf8_lossy(&out[..len as usize]).into_owned())
+}
+
+/// Writes `text` at the current position of a file C lends to Rust, leaving it open.
+///
+/// # Safety
+///
+/// `file` must be an open descriptor or handle, and `text` NULL or a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn rust_file_borrow_write(file: RawFile, text: *const c_char) -> i32 {
+    if is_invalid(file) || text.is_null() {
+        return -1;
+    }
+    // ManuallyDrop 让 File 在这里被丢弃时不关闭 C 的文件
+    // ManuallyDrop keeps the File from closing C's file when it's dropped here
+    let mut borrowed = ManuallyDrop::new(file_from_raw(file));
+    match borrowed.write_all(CStr::from_ptr(text).to_bytes()) {
+        Ok(()) => 0,
+        Err(_) => -1,
+    }
+}
+
+/// Takes ownership of a file C hands to Rust, reads up to `out_len` bytes from its start into
+/// `out` and closes it, returning the number of bytes read or -1 on failure.
+///
+/// # Safety
+///
+/// `file` must be an open descriptor or handle C no longer uses, and `out` NULL or valid for
+/// `out_len` bytes of writes.
+#[no_mangle]
+pub unsafe extern "C" fn rust_file_transfer_read(
+    file: RawFile,
+    out: *mut c_char,
+    out_len: usize,
+) -> i64 {
+    if is_invalid(file) {
+        return -1;
+    }
+    // 从这里开始文件归 Rust 所有，无论哪条路径返回都会在 owned 被丢弃时关闭
+    // From here on the file belongs to Rust and is closed when owned is dropped, whichever way
+    // this returns
+    let mut owned = file_from_raw(file);
+    if out.is_null() {
+        return -1;
+    }
+    let out = slice::from_raw_parts_mut(out.cast::<u8>(), out_len);
+    let mut read = 0;
+    if owned.seek(SeekFrom::Start(0)).is_err() {
+        return -1;
+    }
+    while read < out.len() {
+        match owned.read(&mut out[read..]) {
+            Ok(0) => break,
+            Ok(n) => read += n,
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+            Err(_) => return -1,
+        }
+    }
+    read as i64
+}
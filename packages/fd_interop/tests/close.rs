@@ -0,0 +1,82 @@
+// 每个文件正好关闭一次的测试：借出之后文件还开着，转移或者交给 Rust 接管之后文件已经关闭。描述符和
+// 句柄的值关闭后会被复用，所以这些检查放在只有一个测试的单独的测试程序里，不会有其他线程同时打开文件
+// Tests that every file is closed exactly once: a file is still open after being lent, and closed
+// after being transferred or taken over by Rust. Descriptor and handle values are reused once
+// closed, so these checks live in a test program of their own with a single test, where no other
+// thread opens files at the same time
+
+use std::fs;
+
+#[cfg(unix)]
+use std::os::fd::{AsRawFd, FromRawFd, IntoRawFd};
+#[cfg(windows)]
+use std::os::windows::io::{AsRawHandle, FromRawHandle, IntoRawHandle};
+
+use fd_interop::{
+    c_is_open, c_open, c_puts_borrowed, c_puts_transferred, rust_file_borrow_write, RawFile,
+};
+
+#[cfg(unix)]
+fn raw(file: &fs::File) -> RawFile {
+    file.as_raw_fd()
+}
+
+#[cfg(windows)]
+fn raw(file: &fs::File) -> RawFile {
+    file.as_raw_handle()
+}
+
+#[cfg(unix)]
+fn into_raw(file: fs::File) -> RawFile {
+    file.into_raw_fd()
+}
+
+#[cfg(windows)]
+fn into_raw(file: fs::File) -> RawFile {
+    file.into_raw_handle()
+}
+
+#[cfg(unix)]
+unsafe fn from_raw(file: RawFile) -> fs::File {
+    fs::File::from_raw_fd(file)
+}
+
+#[cfg(windows)]
+unsafe fn from_raw(file: RawFile) -> fs::File {
+    fs::File::from_raw_handle(file)
+}
+
+#[test]
+fn files_are_closed_exactly_once() {
+    let path = std::env::temp_dir().join(format!("fd_interop_{}_close", std::process::id()));
+
+    // C 借用之后还开着，转移给 C 之后由 C 关闭
+    // Still open after C borrowed it, closed by C after being transferred to it
+    let file = c_open(&path).unwrap();
+    let value = raw(&file);
+    c_puts_borrowed(&file, "borrowed\n").unwrap();
+    assert!(c_is_open(value));
+    c_puts_transferred(file, "transferred\n").unwrap();
+    assert!(!c_is_open(value));
+
+    // Rust 借用之后还开着，丢弃接管它的 File 时关闭
+    // Still open after Rust borrowed it, closed when the File that took it over is dropped
+    let file = c_open(&path).unwrap();
+    let value = raw(&file);
+    assert_eq!(
+        unsafe { rust_file_borrow_write(value, c"borrowed by Rust\n".as_ptr()) },
+        0
+    );
+    assert!(c_is_open(value));
+    drop(file);
+    assert!(!c_is_open(value));
+
+    // 取出原始值之后由调用方负责，这里交还给 File 关闭
+    // Once the raw value is taken out the caller is responsible, here a File gets it back to close
+    let value = into_raw(c_open(&path).unwrap());
+    assert!(c_is_open(value));
+    drop(unsafe { from_raw(value) });
+    assert!(!c_is_open(value));
+
+    fs::remove_file(&path).unwrap();
+}
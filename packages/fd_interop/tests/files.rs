@@ -0,0 +1,72 @@
+// 文件传递的测试：借给对方的文件在调用之后还能照常使用，转移给对方的文件由对方关闭；C 打开的文件交给
+// Rust 后由 File 管理，Rust 借出或转移的文件由 C 通过 fdopen 写入
+// Tests for passing files: a file lent to the other side is usable as usual after the call, and one
+// transferred is closed by the other side; a file C opened is managed by a File once handed to
+// Rust, and the files Rust lends or transfers are written by C through fdopen
+
+use std::fs;
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::PathBuf;
+
+use fd_interop::{c_open, c_puts_borrowed, c_puts_transferred, c_share_file};
+
+fn temp_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("fd_interop_{}_{}", std::process::id(), name))
+}
+
+#[test]
+fn file_opened_by_c_is_owned_by_rust() {
+    let path = temp_path("owned");
+    let mut file = c_open(&path).unwrap();
+    file.write_all(b"from Rust").unwrap();
+    drop(file);
+    assert_eq!(fs::read_to_string(&path).unwrap(), "from Rust");
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn borrowed_file_stays_usable_in_rust() {
+    let path = temp_path("borrowed");
+    let mut file = fs::File::options()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(&path)
+        .unwrap();
+    c_puts_borrowed(&file, "first from C\n").unwrap();
+    file.write_all(b"then Rust\n").unwrap();
+    c_puts_borrowed(&file, "again from C\n").unwrap();
+
+    let mut contents = String::new();
+    file.seek(SeekFrom::Start(0)).unwrap();
+    file.read_to_string(&mut contents).unwrap();
+    assert_eq!(contents, "first from C\nthen Rust\nagain from C\n");
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn transferred_file_is_written_and_closed_by_c() {
+    let path = temp_path("transferred");
+    let file = fs::File::create(&path).unwrap();
+    c_puts_transferred(file, "all from C\n").unwrap();
+    assert_eq!(fs::read_to_string(&path).unwrap(), "all from C\n");
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn c_lends_then_transfers_its_file_to_rust() {
+    let path = temp_path("shared");
+    let contents = c_share_file(&path).unwrap();
+    assert_eq!(
+        contents,
+        "[Rust] written through a borrowed file\n[C source] written after Rust returned it\n"
+    );
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn c_open_reports_errors() {
+    let missing = temp_path("no_such_dir").join("file");
+    assert!(c_open(&missing).is_err());
+}
@@ -0,0 +1,113 @@
+use std::ffi::c_char;
+use std::{slice, str};
+
+use crate::FfiError;
+
+/// 检查 C 端传入的指针非空且按 `T` 对齐
+/// Checks that a pointer received from C is non-NULL and aligned for `T`.
+pub fn check_ptr<T>(ptr: *const T) -> Result<(), FfiError> {
+    if ptr.is_null() {
+        Err(FfiError::NullPointer)
+    } else if !ptr.is_aligned() {
+        Err(FfiError::Misaligned)
+    } else {
+        Ok(())
+    }
+}
+
+/// Writes `value` through an out-parameter after validating it with [`check_ptr`].
+///
+/// # Safety
+///
+/// If non-NULL and aligned, `out` must be valid for writes.
+pub unsafe fn write_out<T>(out: *mut T, value: T) -> Result<(), FfiError> {
+    check_ptr(out)?;
+    out.write(value);
+    Ok(())
+}
+
+/// 把 C 的指针和长度转换为切片，长度为 0 时允许 NULL
+/// Turns a C pointer + length pair into a slice; NULL is accepted when `len` is 0.
+///
+/// # Safety
+///
+/// If `len > 0`, `ptr` must point to `len` initialised values that are not mutated while the
+/// slice is alive.
+pub unsafe fn slice_from_raw<'a, T>(ptr: *const T, len: usize) -> Result<&'a [T], FfiError> {
+    if len == 0 {
+        return Ok(&[]);
+    }
+    check_ptr(ptr)?;
+    Ok(slice::from_raw_parts(ptr, len))
+}
+
+/// Mutable counterpart of [`slice_from_raw`].
+///
+/// # Safety
+///
+/// If `len > 0`, `ptr` must point to `len` initialised values that nothing else accesses while
+/// the slice is alive.
+pub unsafe fn slice_from_raw_mut<'a, T>(ptr: *mut T, len: usize) -> Result<&'a mut [T], FfiError> {
+    if len == 0 {
+        return Ok(&mut []);
+    }
+    check_ptr(ptr)?;
+    Ok(slice::from_raw_parts_mut(ptr, len))
+}
+
+/// Reads a NUL-terminated UTF-8 string, looking at no more than `max_len` bytes.
+///
+/// # Safety
+///
+/// If non-NULL, `src` must be valid for reads of `max_len` bytes, or up to and including its
+/// NUL terminator if that comes first, and the memory must not be mutated while the returned
+/// string is alive.
+pub unsafe fn read_cstr<'a>(src: *const c_char, max_len: usize) -> Result<&'a str, FfiError> {
+    check_ptr(src)?;
+    let mut len = 0;
+    while len < max_len && *src.add(len) != 0 {
+        len += 1;
+    }
+    if len == max_len {
+        return Err(FfiError::Unterminated);
+    }
+    let bytes = slice::from_raw_parts(src as *const u8, len);
+    str::from_utf8(bytes).map_err(|_| FfiError::InvalidUtf8)
+}
+
+/// 以 `snprintf` 的方式把 `s` 复制进切片，是 [`write_cstr`] 不含指针的核心
+/// Copies `s` into `dst` the way `snprintf` does; the pointer-free core of [`write_cstr`].
+///
+/// The copy is truncated to fit and always NUL terminated when `dst` is not empty. Returns the
+/// number of bytes written (excluding the NUL), or [`FfiError::BufferTooSmall`] with the full
+/// length if `s` did not fit.
+pub fn copy_cstr(dst: &mut [u8], s: &str) -> Result<usize, FfiError> {
+    if s.as_bytes().contains(&0) {
+        return Err(FfiError::InteriorNul);
+    }
+    let Some(room) = dst.len().checked_sub(1) else {
+        return Err(FfiError::BufferTooSmall { required: s.len() });
+    };
+
+    let n = s.len().min(room);
+    dst[..n].copy_from_slice(&s.as_bytes()[..n]);
+    dst[n] = 0;
+
+    if n < s.len() {
+        Err(FfiError::BufferTooSmall { required: s.len() })
+    } else {
+        Ok(n)
+    }
+}
+
+/// 以 `snprintf` 的方式把 `s` 写入 C 的缓冲区
+/// Writes `s` into a C buffer of `dst_len` bytes the way `snprintf` does.
+///
+/// See [`copy_cstr`] for the results. A NULL `dst` with `dst_len == 0` only queries the length.
+///
+/// # Safety
+///
+/// If `dst_len > 0`, `dst` must be valid for writes of `dst_len` bytes.
+pub unsafe fn write_cstr(dst: *mut c_char, dst_len: usize, s: &str) -> Result<usize, FfiError> {
+    copy_cstr(slice_from_raw_mut(dst as *mut u8, dst_len)?, s)
+}
@@ -0,0 +1,72 @@
+// 可移植的 C 字符缓冲区：内部使用 Vec<u8>，不假设 c_char 是 i8
+// A portable C character buffer: backed by Vec<u8>, without assuming that c_char is i8
+
+use std::ffi::{c_char, CStr};
+
+use crate::FfiError;
+
+/// An owned, zero-initialised buffer handed to C functions as `char *`.
+///
+/// `c_char` is `i8` on x86 but `u8` on aarch64 and ARM Linux, so the bytes are stored as `u8` and
+/// only cast to `*mut c_char` at the boundary. The constructors always leave room for a NUL
+/// terminator, and reading back checks that one is still present within the capacity.
+#[derive(Debug, Clone)]
+pub struct CBuffer {
+    bytes: Vec<u8>,
+}
+
+impl CBuffer {
+    /// Creates an empty (all NUL) buffer of `capacity` bytes.
+    ///
+    /// Fails with [`FfiError::BufferTooSmall`] if `capacity` is 0, as there would be no room for
+    /// the terminator.
+    pub fn with_capacity(capacity: usize) -> Result<Self, FfiError> {
+        if capacity == 0 {
+            return Err(FfiError::BufferTooSmall { required: 0 });
+        }
+        Ok(CBuffer {
+            bytes: vec![0; capacity],
+        })
+    }
+
+    /// Creates a buffer of `capacity` bytes that starts out holding `s` as a C string.
+    ///
+    /// Fails if `s` contains a NUL byte or does not fit together with its terminator.
+    pub fn new(s: &str, capacity: usize) -> Result<Self, FfiError> {
+        if s.as_bytes().contains(&0) {
+            return Err(FfiError::InteriorNul);
+        }
+        if s.len() >= capacity {
+            return Err(FfiError::BufferTooSmall { required: s.len() });
+        }
+        let mut buffer = CBuffer::with_capacity(capacity)?;
+        buffer.bytes[..s.len()].copy_from_slice(s.as_bytes());
+        Ok(buffer)
+    }
+
+    /// The total size in bytes, including the room for the NUL terminator.
+    pub fn capacity(&self) -> usize {
+        self.bytes.len()
+    }
+
+    pub fn as_c_ptr(&self) -> *const c_char {
+        self.bytes.as_ptr() as *const c_char
+    }
+
+    pub fn as_mut_c_ptr(&mut self) -> *mut c_char {
+        self.bytes.as_mut_ptr() as *mut c_char
+    }
+
+    /// Reads the contents back as a C string, failing with [`FfiError::Unterminated`] if the C
+    /// side overwrote every byte including the last one.
+    pub fn as_c_str(&self) -> Result<&CStr, FfiError> {
+        CStr::from_bytes_until_nul(&self.bytes).map_err(|_| FfiError::Unterminated)
+    }
+
+    /// Like [`as_c_str`](Self::as_c_str), additionally requiring the contents to be UTF-8.
+    pub fn to_str(&self) -> Result<&str, FfiError> {
+        self.as_c_str()?
+            .to_str()
+            .map_err(|_| FfiError::InvalidUtf8)
+    }
+}
@@ -0,0 +1,124 @@
+// 带计数器的全局分配器包装：统计分配、释放和仍在使用的字节数，C 端可以查询这些数字，
+// 库也能在关闭时检查有没有忘记释放的内存
+// A global allocator wrapper with counters: it tracks allocations, frees and the bytes still in
+// use, which C can query and which lets a library check for memory nobody released at shutdown
+
+use std::alloc::{GlobalAlloc, Layout};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// 分配器的统计数字
+/// Statistics of a library's global allocator.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct AllocStats {
+    /// Blocks allocated so far.
+    pub allocations: u64,
+    /// Blocks released so far.
+    pub deallocations: u64,
+    /// Blocks resized in place or moved so far, not counted as allocations or frees.
+    pub reallocations: u64,
+    /// Blocks allocated and not released yet.
+    pub live_blocks: u64,
+    /// Bytes in the blocks allocated and not released yet.
+    pub live_bytes: u64,
+    /// The largest `live_bytes` has been.
+    pub peak_bytes: u64,
+    /// Blocks the last leak check at shutdown found still allocated, 0 if none ran.
+    pub leaked_blocks: u64,
+    /// Bytes in those blocks.
+    pub leaked_bytes: u64,
+}
+
+/// 在另一个分配器外面加上计数器的 [`GlobalAlloc`]
+/// A [`GlobalAlloc`] counting what the allocator it wraps does.
+///
+/// The counters are atomics updated with relaxed ordering, so a snapshot taken while other
+/// threads allocate is only approximately consistent.
+pub struct CountingAllocator<A> {
+    inner: A,
+    allocations: AtomicU64,
+    deallocations: AtomicU64,
+    reallocations: AtomicU64,
+    live_bytes: AtomicU64,
+    peak_bytes: AtomicU64,
+}
+
+impl<A> CountingAllocator<A> {
+    /// Wraps `inner`, for a `#[global_allocator]` static.
+    pub const fn new(inner: A) -> Self {
+        CountingAllocator {
+            inner,
+            allocations: AtomicU64::new(0),
+            deallocations: AtomicU64::new(0),
+            reallocations: AtomicU64::new(0),
+            live_bytes: AtomicU64::new(0),
+            peak_bytes: AtomicU64::new(0),
+        }
+    }
+
+    /// A snapshot of the counters; the leak fields are left at 0.
+    pub fn stats(&self) -> AllocStats {
+        let allocations = self.allocations.load(Ordering::Relaxed);
+        let deallocations = self.deallocations.load(Ordering::Relaxed);
+        AllocStats {
+            allocations,
+            deallocations,
+            reallocations: self.reallocations.load(Ordering::Relaxed),
+            live_blocks: allocations.saturating_sub(deallocations),
+            live_bytes: self.live_bytes.load(Ordering::Relaxed),
+            peak_bytes: self.peak_bytes.load(Ordering::Relaxed),
+            leaked_blocks: 0,
+            leaked_bytes: 0,
+        }
+    }
+
+    fn grow(&self, bytes: usize) {
+        let live = self.live_bytes.fetch_add(bytes as u64, Ordering::Relaxed) + bytes as u64;
+        self.peak_bytes.fetch_max(live, Ordering::Relaxed);
+    }
+
+    fn shrink(&self, bytes: usize) {
+        self.live_bytes.fetch_sub(bytes as u64, Ordering::Relaxed);
+    }
+}
+
+unsafe impl<A: GlobalAlloc> GlobalAlloc for CountingAllocator<A> {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = self.inner.alloc(layout);
+        if !ptr.is_null() {
+            self.allocations.fetch_add(1, Ordering::Relaxed);
+            self.grow(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = self.inner.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            self.allocations.fetch_add(1, Ordering::Relaxed);
+            self.grow(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        self.inner.dealloc(ptr, layout);
+        self.deallocations.fetch_add(1, Ordering::Relaxed);
+        self.shrink(layout.size());
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new = self.inner.realloc(ptr, layout, new_size);
+        // 失败时原来的块保持不变，计数也不变
+        // On failure the old block is left as it was, and so are the counters
+        if !new.is_null() {
+            self.reallocations.fetch_add(1, Ordering::Relaxed);
+            if new_size >= layout.size() {
+                self.grow(new_size - layout.size());
+            } else {
+                self.shrink(layout.size() - new_size);
+            }
+        }
+        new
+    }
+}
@@ -0,0 +1,79 @@
+use std::{fmt, io};
+
+/// Rust 端的 FFI 错误，在导出函数的边界上转换为 [`FfiStatus`](crate::FfiStatus)
+/// Rust-side FFI errors, converted to a [`FfiStatus`](crate::FfiStatus) at the boundary of an
+/// exported function.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FfiError {
+    NullPointer,
+    Misaligned,
+    InvalidUtf8,
+    /// A UTF-16 string has an unpaired surrogate.
+    InvalidUtf16,
+    /// No NUL terminator was found within the allowed length.
+    Unterminated,
+    InteriorNul,
+    Overflow,
+    /// The destination buffer was too small; `required` is the full length excluding the NUL.
+    BufferTooSmall { required: usize },
+    /// The library was called before its init function or after its shutdown function.
+    NotInitialized,
+    /// The library's init function was called again without shutting it down first.
+    AlreadyInitialized,
+    /// The call would block waiting for itself, such as one made from the library's own threads.
+    WouldDeadlock,
+    /// The caller cancelled the call through its cancellation token or progress callback.
+    Cancelled,
+    /// The requested size and alignment don't describe a valid allocation.
+    InvalidLayout,
+    /// The allocator could not provide the requested memory.
+    OutOfMemory,
+    /// The handle is null, stale, already freed or was never handed out.
+    InvalidHandle,
+    /// An I/O operation failed; `code` is the OS error code, or -1 when there is none.
+    Io { code: i32 },
+    /// The input bytes don't form a valid message, such as a header with the wrong magic.
+    Malformed,
+    /// A size-prefixed struct declares a size that isn't one of the versions the library knows.
+    UnsupportedVersion,
+    /// An argument is outside the range the function accepts, or a required one was never set.
+    InvalidArgument,
+}
+
+impl fmt::Display for FfiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FfiError::NullPointer => write!(f, "a required pointer argument was NULL"),
+            FfiError::Misaligned => write!(f, "a pointer argument is misaligned"),
+            FfiError::InvalidUtf8 => write!(f, "the string is not valid UTF-8"),
+            FfiError::InvalidUtf16 => write!(f, "the string is not valid UTF-16"),
+            FfiError::Unterminated => write!(f, "the string is not NUL terminated"),
+            FfiError::InteriorNul => write!(f, "the string contains an interior NUL byte"),
+            FfiError::Overflow => write!(f, "the arithmetic result overflowed"),
+            FfiError::BufferTooSmall { required } => {
+                write!(f, "the buffer is too small, {} bytes are required", required + 1)
+            }
+            FfiError::NotInitialized => write!(f, "the library has not been initialized"),
+            FfiError::AlreadyInitialized => write!(f, "the library is already initialized"),
+            FfiError::WouldDeadlock => write!(f, "the call would wait for itself and deadlock"),
+            FfiError::Cancelled => write!(f, "the call was cancelled"),
+            FfiError::InvalidLayout => write!(f, "the size and alignment are not a valid layout"),
+            FfiError::OutOfMemory => write!(f, "the allocator is out of memory"),
+            FfiError::InvalidHandle => write!(f, "the handle is stale, already freed or was never valid"),
+            FfiError::Io { code } => write!(f, "an I/O operation failed with OS error {}", code),
+            FfiError::Malformed => write!(f, "the input is not a valid message"),
+            FfiError::UnsupportedVersion => write!(f, "the struct size matches no known version"),
+            FfiError::InvalidArgument => write!(f, "an argument is missing or out of range"),
+        }
+    }
+}
+
+impl std::error::Error for FfiError {}
+
+impl From<io::Error> for FfiError {
+    fn from(err: io::Error) -> Self {
+        FfiError::Io {
+            code: err.raw_os_error().unwrap_or(-1),
+        }
+    }
+}
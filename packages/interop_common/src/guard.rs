@@ -0,0 +1,140 @@
+use std::any::Any;
+use std::cell::RefCell;
+use std::ffi::{c_char, c_int};
+use std::panic::{self, AssertUnwindSafe, PanicHookInfo};
+use std::sync::Once;
+
+use crate::last_error::set_last_error;
+use crate::{write_cstr, FfiError, FfiStatus};
+
+thread_local! {
+    static LAST_PANIC: RefCell<Option<String>> = const { RefCell::new(None) };
+    // panic hook 在展开之前于 panic 的线程上运行，在这里留下带位置的消息
+    // The panic hook runs on the panicking thread before unwinding and leaves the message with
+    // its location here
+    static HOOKED_PANIC: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+static INSTALL_HOOK: Once = Once::new();
+
+/// 安装记录 panic 消息和位置的 panic hook，之前的 hook 仍然会被调用
+/// Installs a panic hook that records the panic message and location for the panicking thread.
+///
+/// The previously installed hook (by default the one printing to stderr) still runs afterwards.
+/// [`init`](crate::init) calls this when [`InitConfig::install_panic_hook`](crate::InitConfig) is
+/// set; calling it again is a no-op.
+pub fn install_panic_hook() {
+    INSTALL_HOOK.call_once(|| {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            let msg = hooked_message(info);
+            HOOKED_PANIC.with(|hooked| *hooked.borrow_mut() = Some(msg));
+            previous(info);
+        }));
+    });
+}
+
+/// 在 `catch_unwind` 中执行导出函数的函数体，panic 不会越过 FFI 边界
+/// Runs the body of an exported function inside `catch_unwind` so a panic never crosses the
+/// FFI boundary.
+///
+/// An error returned by the body is recorded as the thread's last error (see
+/// [`last_error_length`](crate::last_error_length)) before being mapped to its status. A panic
+/// is turned into [`FfiStatus::Panic`] and its message is stored for the current thread, where
+/// [`take_last_panic`] can pick it up. The closure is wrapped in `AssertUnwindSafe`: the
+/// exported functions only touch caller-owned buffers, which are never observed after a panic.
+pub fn ffi_guard<F>(body: F) -> FfiStatus
+where
+    F: FnOnce() -> Result<(), FfiError>,
+{
+    ffi_guard_or(FfiStatus::Panic, || match body() {
+        Ok(()) => Ok(FfiStatus::Ok),
+        Err(err) => {
+            set_last_error(err.to_string());
+            Ok(err.into())
+        }
+    })
+}
+
+/// 用于返回值不是状态码的导出函数，例如返回指针的函数
+/// Like [`ffi_guard`], for exported functions that return something other than a status, such
+/// as a pointer.
+///
+/// Errors and panics are recorded as the thread's last error and `fallback` (typically NULL) is
+/// returned instead.
+pub fn ffi_guard_or<T, F>(fallback: T, body: F) -> T
+where
+    F: FnOnce() -> Result<T, FfiError>,
+{
+    match panic::catch_unwind(AssertUnwindSafe(body)) {
+        Ok(Ok(value)) => value,
+        Ok(Err(err)) => {
+            set_last_error(err.to_string());
+            fallback
+        }
+        Err(payload) => {
+            // 另一个 panic hook 替换了这里的 hook 时只能退回到 payload 本身
+            // Falls back to the payload alone when another panic hook replaced ours
+            let msg = HOOKED_PANIC
+                .with(|hooked| hooked.borrow_mut().take())
+                .unwrap_or_else(|| panic_message(payload.as_ref()));
+            set_last_error(format!("panicked: {msg}"));
+            LAST_PANIC.with(|last| *last.borrow_mut() = Some(msg));
+            fallback
+        }
+    }
+}
+
+/// Takes the message of the last panic caught by [`ffi_guard`] on this thread, if any.
+///
+/// The message includes the location of the panic, as in `boom at src/lib.rs:10:5`.
+pub fn take_last_panic() -> Option<String> {
+    LAST_PANIC.with(|last| last.borrow_mut().take())
+}
+
+/// 以 `snprintf` 的方式把最近一次 panic 的消息复制进 C 的缓冲区，完整复制后才取走
+/// Copies the last panic message into `buf` like [`write_cstr`] and returns its full length
+/// (excluding the NUL), or 0 if no panic is recorded on this thread.
+///
+/// The message is only taken once it was copied in full, so a truncated read can be repeated
+/// with a buffer of the returned length plus one; a NULL `buf` with `len == 0` only queries it.
+/// Returns -1 if `buf` is NULL while `len` is not 0.
+///
+/// # Safety
+///
+/// If `len > 0`, `buf` must be valid for writes of `len` bytes.
+pub unsafe fn take_last_panic_into(buf: *mut c_char, len: usize) -> c_int {
+    LAST_PANIC.with(|last| {
+        let mut last = last.borrow_mut();
+        let Some(msg) = last.as_deref() else {
+            return 0;
+        };
+        let full = c_int::try_from(msg.len()).unwrap_or(c_int::MAX);
+        match write_cstr(buf, len, msg) {
+            Ok(_) => {
+                *last = None;
+                full
+            }
+            Err(FfiError::BufferTooSmall { .. }) => full,
+            Err(_) => -1,
+        }
+    })
+}
+
+fn hooked_message(info: &PanicHookInfo<'_>) -> String {
+    let msg = panic_message(info.payload());
+    match info.location() {
+        Some(location) => format!("{msg} at {location}"),
+        None => msg,
+    }
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(s) = payload.downcast_ref::<&str>() {
+        s.to_string()
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s.clone()
+    } else {
+        "unknown panic payload".to_string()
+    }
+}
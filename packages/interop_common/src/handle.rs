@@ -0,0 +1,155 @@
+// 带代数标记的句柄注册表：交给 C 的不是指针，而是 {index, generation}；槽位复用时代数不同，
+// 过期或重复释放的句柄因此会被识别出来，返回 INVALID_HANDLE 而不是破坏内存
+// A generation-tagged handle registry: C gets {index, generation} instead of a pointer; a reused
+// slot has a different generation, so stale and double-freed handles are recognized and fail
+// with INVALID_HANDLE instead of corrupting memory
+
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+use crate::FfiError;
+
+/// 交给 C 的句柄，全零是空句柄
+/// A handle given to C; the all-zero value is the null handle.
+///
+/// C code treats it as an opaque value passed and returned by value, never made up.
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FfiHandle {
+    /// The slot in the registry.
+    pub index: u32,
+    /// Which occupant of the slot the handle refers to, never 0 for a valid handle.
+    pub generation: u32,
+}
+
+impl FfiHandle {
+    /// The handle constructors return on failure, it never refers to anything.
+    pub const NULL: FfiHandle = FfiHandle {
+        index: 0,
+        generation: 0,
+    };
+
+    pub fn is_null(self) -> bool {
+        self == FfiHandle::NULL
+    }
+}
+
+struct Slot<T> {
+    generation: u32,
+    value: Option<Arc<T>>,
+}
+
+struct Slab<T> {
+    slots: Vec<Slot<T>>,
+    free: Vec<u32>,
+    live: usize,
+    // 整个注册表共用一个代数计数器，槽位的存储释放后重新分配也不会重复之前的句柄
+    // One generation counter for the whole registry, so handles don't repeat even when the slots'
+    // storage was released and allocated again
+    last_generation: u32,
+}
+
+/// 按句柄保存对象的注册表
+/// A registry keeping objects by handle, for an opaque-handle API.
+///
+/// Objects are stored as `Arc`s so a call can keep using one after releasing the registry's lock,
+/// even if another thread removes it meanwhile; it is destroyed once the last of them is dropped.
+/// Handles stay unique until the registry has handed out 2³² − 1 of them. The storage is
+/// released whenever the registry becomes empty, so it doesn't outlive the objects.
+pub struct HandleRegistry<T> {
+    slab: Mutex<Slab<T>>,
+}
+
+impl<T> HandleRegistry<T> {
+    /// An empty registry, for a `static`.
+    pub const fn new() -> Self {
+        HandleRegistry {
+            slab: Mutex::new(Slab {
+                slots: Vec::new(),
+                free: Vec::new(),
+                live: 0,
+                last_generation: 0,
+            }),
+        }
+    }
+
+    fn slab(&self) -> MutexGuard<'_, Slab<T>> {
+        self.slab.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Stores `value` and returns its handle.
+    pub fn insert(&self, value: T) -> FfiHandle {
+        let value = Some(Arc::new(value));
+        let mut slab = self.slab();
+        let generation = match slab.last_generation.wrapping_add(1) {
+            0 => 1,
+            generation => generation,
+        };
+        slab.last_generation = generation;
+        slab.live += 1;
+        let index = match slab.free.pop() {
+            Some(index) => {
+                slab.slots[index as usize] = Slot { generation, value };
+                index
+            }
+            None => {
+                let index = u32::try_from(slab.slots.len()).expect("Too many live handles.");
+                slab.slots.push(Slot { generation, value });
+                index
+            }
+        };
+        FfiHandle { index, generation }
+    }
+
+    /// The object behind `handle`, failing with [`FfiError::InvalidHandle`] when it is null,
+    /// stale, already removed or was never handed out.
+    pub fn get(&self, handle: FfiHandle) -> Result<Arc<T>, FfiError> {
+        let slab = self.slab();
+        match slab.slots.get(handle.index as usize) {
+            Some(Slot {
+                generation,
+                value: Some(value),
+            }) if *generation == handle.generation => Ok(Arc::clone(value)),
+            _ => Err(FfiError::InvalidHandle),
+        }
+    }
+
+    /// Takes the object behind `handle` out of the registry, making the handle stale, and fails
+    /// like [`get`](Self::get) if it is not valid.
+    ///
+    /// The caller drops the returned `Arc` after the registry's lock is released, so a `Drop`
+    /// that waits for other threads can't block the registry.
+    pub fn remove(&self, handle: FfiHandle) -> Result<Arc<T>, FfiError> {
+        let mut slab = self.slab();
+        let slot = match slab.slots.get_mut(handle.index as usize) {
+            Some(slot) if slot.value.is_some() && slot.generation == handle.generation => slot,
+            _ => return Err(FfiError::InvalidHandle),
+        };
+        let value = slot
+            .value
+            .take()
+            .expect("The slot was checked to be occupied.");
+        slab.live -= 1;
+        if slab.live == 0 {
+            slab.slots = Vec::new();
+            slab.free = Vec::new();
+        } else {
+            slab.free.push(handle.index);
+        }
+        Ok(value)
+    }
+
+    /// How many objects the registry holds.
+    pub fn len(&self) -> usize {
+        self.slab().live
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<T> Default for HandleRegistry<T> {
+    fn default() -> Self {
+        HandleRegistry::new()
+    }
+}
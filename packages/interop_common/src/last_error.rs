@@ -0,0 +1,37 @@
+// 每个线程保存最近一次错误的描述，供 C 端在拿到状态码后查询
+// Each thread keeps a description of its most recent error for C callers to query after a status code
+
+use std::cell::RefCell;
+use std::ffi::c_char;
+
+use crate::{write_cstr, FfiError};
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// Records `msg` as the current thread's last error, replacing the previous one.
+///
+/// Like `errno`, the last error is only written on failure and never cleared by a successful call.
+pub fn set_last_error(msg: impl Into<String>) {
+    LAST_ERROR.with(|last| *last.borrow_mut() = Some(msg.into()));
+}
+
+/// Returns the buffer size needed for the last error message including its NUL, or 0 if the
+/// current thread has no error recorded.
+pub fn last_error_length() -> usize {
+    LAST_ERROR.with(|last| last.borrow().as_ref().map_or(0, |msg| msg.len() + 1))
+}
+
+/// Copies the last error message into `buf` like [`write_cstr`]; an empty string is written
+/// when there is no error. The error stays recorded, so it can be read again with a larger buffer.
+///
+/// # Safety
+///
+/// If `len > 0`, `buf` must be valid for writes of `len` bytes.
+pub unsafe fn copy_last_error(buf: *mut c_char, len: usize) -> Result<(), FfiError> {
+    LAST_ERROR.with(|last| {
+        let last = last.borrow();
+        write_cstr(buf, len, last.as_deref().unwrap_or("")).map(|_| ())
+    })
+}
@@ -0,0 +1,41 @@
+// 这是工作区中各个 crate 共享的 FFI 工具库
+// This is the FFI helper library shared by the crates in this workspace
+
+mod buffer;
+mod cbuffer;
+mod counting;
+mod error;
+mod guard;
+mod handle;
+mod last_error;
+mod lifecycle;
+mod logger;
+mod malloc;
+mod mutex;
+mod status;
+mod trampoline;
+mod view;
+mod wide;
+
+pub use buffer::{
+    check_ptr, copy_cstr, read_cstr, slice_from_raw, slice_from_raw_mut, write_cstr, write_out,
+};
+pub use cbuffer::CBuffer;
+pub use counting::{AllocStats, CountingAllocator};
+pub use error::FfiError;
+pub use guard::{
+    ffi_guard, ffi_guard_or, install_panic_hook, take_last_panic, take_last_panic_into,
+};
+pub use handle::{FfiHandle, HandleRegistry};
+pub use last_error::{copy_last_error, last_error_length, set_last_error};
+pub use lifecycle::{
+    ensure_initialized, init, is_initialized, shutdown, InitConfig, INIT_CONFIG_SIZE_V1,
+    INIT_CONFIG_SIZE_V2,
+};
+pub use logger::{install_logger, set_log_callback, LogCallback, LogLevel};
+pub use malloc::{free_aligned, malloc_aligned, realloc_small, MallocAllocator, MALLOC_ALIGN};
+pub use mutex::{CMutex, CMutexGuard, RawCMutex};
+pub use status::FfiStatus;
+pub use trampoline::{leak_callback, with_callback, CtxCallback};
+pub use view::{FfiSlice, FfiStr};
+pub use wide::{copy_wstr, read_wstr, write_wstr};
@@ -0,0 +1,160 @@
+// 库的显式生命周期：init 一次性完成日志和 panic hook 等全局设置，之前和 shutdown 之后导出函数
+// 返回 NOT_INITIALIZED
+// The libraries' explicit lifecycle: init does the global setup such as logging and the panic
+// hook once, exported functions return NOT_INITIALIZED before it and after shutdown
+//
+// InitConfig 像 Win32 的结构体一样以自己的大小开头：新版本只在末尾添加字段，库按 size 判断调用方用的是
+// 哪个版本，只读取这么多字节，调用方没有的字段取默认值。为旧版本头文件编译的程序因此可以继续使用新的库
+// InitConfig starts with its own size like the Win32 structs do: newer versions only add fields at
+// the end, and the library tells from size which version the caller uses and only reads that many
+// bytes, taking the defaults for the fields the caller doesn't have. Programs compiled against an
+// older header can therefore keep using a newer library
+
+use std::ffi::c_void;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::{mem, ptr};
+
+use crate::{install_logger, install_panic_hook, set_log_callback, FfiError, LogCallback};
+
+static INITIALIZED: AtomicBool = AtomicBool::new(false);
+// 串行化 init 和 shutdown，设置完成之后其他线程才会看到已初始化
+// Serializes init and shutdown, other threads only see the library as initialized once the setup
+// is complete
+static LIFECYCLE: Mutex<()> = Mutex::new(());
+
+/// 传给库的 init 函数的配置，size 之外的字段全部为零时得到默认配置
+/// The configuration passed to a library's init function; a zeroed struct with `size` set is the
+/// default configuration.
+///
+/// Version 1 ends before `install_panic_hook`, version 2 is the whole struct.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct InitConfig {
+    /// The size of the struct the caller passes, `sizeof(InitConfig)` when compiled against the
+    /// current header; anything but [`INIT_CONFIG_SIZE_V1`] or [`INIT_CONFIG_SIZE_V2`] is
+    /// rejected with `FFI_STATUS_UNSUPPORTED_VERSION`.
+    pub size: usize,
+    /// Receives the library's log messages as with `rxc_rustlib_set_log_callback`; NULL writes them
+    /// to stderr.
+    pub log_callback: LogCallback,
+    /// Passed through untouched to `log_callback`, it must stay valid until shutdown.
+    pub log_user_data: *mut c_void,
+    /// Installs a panic hook that records the location of a panic for
+    /// `rxc_rustlib_take_last_panic`; without it only the panic message is available.
+    pub install_panic_hook: bool,
+    /// The number of worker threads for asynchronous calls such as `rxc_cdylib_add_async` and
+    /// `rxc_async_submit`, 0 picks one. Libraries without asynchronous calls ignore it.
+    pub worker_threads: u32,
+    /// Reports the allocations made since init and still outstanding when the library shuts
+    /// down, as with `rxc_rustlib_alloc_stats`. Libraries without allocation statistics ignore it.
+    pub leak_check: bool,
+}
+
+/// The size of version 1 of [`InitConfig`], which only has `size`, `log_callback` and
+/// `log_user_data`.
+pub const INIT_CONFIG_SIZE_V1: usize = mem::offset_of!(InitConfig, install_panic_hook);
+
+/// The size of version 2 of [`InitConfig`], which added `install_panic_hook`, `worker_threads`
+/// and `leak_check`; the current version.
+pub const INIT_CONFIG_SIZE_V2: usize = mem::size_of::<InitConfig>();
+
+impl Default for InitConfig {
+    fn default() -> Self {
+        InitConfig {
+            size: INIT_CONFIG_SIZE_V2,
+            log_callback: None,
+            log_user_data: ptr::null_mut(),
+            install_panic_hook: false,
+            worker_threads: 0,
+            leak_check: false,
+        }
+    }
+}
+
+impl InitConfig {
+    /// Reads the configuration a C caller passes to an init function, or the default one when
+    /// `config` is NULL.
+    ///
+    /// Only the `size` bytes the caller declares are read and the fields past them keep their
+    /// defaults, so a version 1 struct works as well as the current one. Fails with
+    /// [`FfiError::UnsupportedVersion`] when `size` is not the size of a known version and with
+    /// [`FfiError::Misaligned`] when `config` is misaligned.
+    ///
+    /// # Safety
+    ///
+    /// `config` must be NULL or point to a readable `usize`, and then to as many bytes as it
+    /// says that form a valid prefix of an `InitConfig`.
+    pub unsafe fn read(config: *const InitConfig) -> Result<InitConfig, FfiError> {
+        if config.is_null() {
+            return Ok(InitConfig::default());
+        }
+        if !config.is_aligned() {
+            return Err(FfiError::Misaligned);
+        }
+        let size = config.cast::<usize>().read();
+        if size != INIT_CONFIG_SIZE_V1 && size != INIT_CONFIG_SIZE_V2 {
+            return Err(FfiError::UnsupportedVersion);
+        }
+        // 只复制调用方声明的前缀，后面的字段保留默认值；两个版本都在字段的边界上结束
+        // Only the prefix the caller declares is copied and the fields after it keep their
+        // defaults; both versions end on a field boundary
+        let mut read = InitConfig::default();
+        ptr::copy_nonoverlapping(
+            config.cast::<u8>(),
+            ptr::addr_of_mut!(read).cast::<u8>(),
+            size,
+        );
+        Ok(read)
+    }
+}
+
+/// 完成全局设置并把库标记为已初始化
+/// Does the global setup described by `config` and marks the library as initialized.
+///
+/// Fails with [`FfiError::AlreadyInitialized`] if it was already initialized and not shut down
+/// since. The panic hook and the logger stay installed after [`shutdown`], a later `init` only
+/// replaces the log callback. Concurrent calls to `init` and `shutdown` take turns.
+pub fn init(config: &InitConfig) -> Result<(), FfiError> {
+    let _lifecycle = LIFECYCLE.lock().unwrap_or_else(|err| err.into_inner());
+    if is_initialized() {
+        return Err(FfiError::AlreadyInitialized);
+    }
+    install_logger();
+    set_log_callback(config.log_callback, config.log_user_data);
+    if config.install_panic_hook {
+        install_panic_hook();
+    }
+    INITIALIZED.store(true, Ordering::Release);
+    Ok(())
+}
+
+/// 把库标记为未初始化，并恢复默认的日志输出
+/// Marks the library as uninitialized again and restores writing log messages to stderr.
+///
+/// Fails with [`FfiError::NotInitialized`] if it was not initialized. Calls that already passed
+/// [`ensure_initialized`] on other threads run to completion.
+pub fn shutdown() -> Result<(), FfiError> {
+    let _lifecycle = LIFECYCLE.lock().unwrap_or_else(|err| err.into_inner());
+    if !is_initialized() {
+        return Err(FfiError::NotInitialized);
+    }
+    INITIALIZED.store(false, Ordering::Release);
+    set_log_callback(None, ptr::null_mut());
+    Ok(())
+}
+
+/// Whether [`init`] succeeded and [`shutdown`] has not been called since.
+pub fn is_initialized() -> bool {
+    INITIALIZED.load(Ordering::Acquire)
+}
+
+/// Fails with [`FfiError::NotInitialized`] unless the library is initialized, for the start of
+/// an exported function's body.
+pub fn ensure_initialized() -> Result<(), FfiError> {
+    if is_initialized() {
+        Ok(())
+    } else {
+        Err(FfiError::NotInitialized)
+    }
+}
@@ -0,0 +1,111 @@
+// 库通过 log crate 输出的日志转发给 C 注册的回调，没有注册回调时写到 stderr
+// Log records the libraries emit through the log crate are forwarded to a callback registered
+// from C, or written to stderr while none is registered
+
+use std::ffi::{c_char, c_void};
+use std::sync::{Once, RwLock};
+
+use log::{Level, LevelFilter, Log, Metadata, Record};
+
+/// 日志级别，数值与 `log::Level` 相同
+/// The level of a log message, with the same values as `log::Level`.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogLevel {
+    Error = 1,
+    Warn = 2,
+    Info = 3,
+    Debug = 4,
+    Trace = 5,
+}
+
+impl From<Level> for LogLevel {
+    fn from(level: Level) -> Self {
+        match level {
+            Level::Error => LogLevel::Error,
+            Level::Warn => LogLevel::Warn,
+            Level::Info => LogLevel::Info,
+            Level::Debug => LogLevel::Debug,
+            Level::Trace => LogLevel::Trace,
+        }
+    }
+}
+
+/// Receives one log message: `msg` points to `msg_len` bytes of UTF-8 that are not NUL
+/// terminated and only valid during the call; `user_data` is passed through untouched.
+///
+/// The callback may be invoked from any thread the library logs on, including its own worker
+/// threads. `Option` makes NULL representable, which restores the stderr fallback.
+pub type LogCallback = Option<
+    unsafe extern "C" fn(
+        level: LogLevel,
+        msg: *const c_char,
+        msg_len: usize,
+        user_data: *mut c_void,
+    ),
+>;
+
+#[derive(Clone, Copy)]
+struct Sink {
+    callback: unsafe extern "C" fn(LogLevel, *const c_char, usize, *mut c_void),
+    user_data: *mut c_void,
+}
+
+// The pointer is only handed back to the callback, synchronising access is the caller's job.
+unsafe impl Send for Sink {}
+unsafe impl Sync for Sink {}
+
+static SINK: RwLock<Option<Sink>> = RwLock::new(None);
+static INSTALL_LOGGER: Once = Once::new();
+
+struct CallbackLogger;
+
+impl Log for CallbackLogger {
+    fn enabled(&self, _: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn log(&self, record: &Record<'_>) {
+        let msg = record.args().to_string();
+        // 先复制出 sink 再调用，回调里再次注册回调也不会死锁
+        // Copy the sink out before calling it, so a callback that registers a callback does not
+        // deadlock
+        let sink = *SINK.read().unwrap_or_else(|err| err.into_inner());
+        match sink {
+            Some(sink) => unsafe {
+                (sink.callback)(
+                    record.level().into(),
+                    msg.as_ptr() as *const c_char,
+                    msg.len(),
+                    sink.user_data,
+                )
+            },
+            None => eprintln!("{msg}"),
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+/// 把转发到回调的 logger 安装为 log crate 的全局 logger
+/// Installs the callback-forwarding logger as the log crate's global logger.
+///
+/// [`init`](crate::init) calls this itself, so calling it again is a no-op. If a Rust
+/// host linking the library as an rlib already installed a logger of its own, that one is kept.
+pub fn install_logger() {
+    INSTALL_LOGGER.call_once(|| {
+        if log::set_logger(&CallbackLogger).is_ok() {
+            log::set_max_level(LevelFilter::Trace);
+        }
+    });
+}
+
+/// Routes this library's log messages to `callback` with `user_data`, or back to stderr when
+/// `callback` is NULL.
+pub fn set_log_callback(callback: LogCallback, user_data: *mut c_void) {
+    install_logger();
+    *SINK.write().unwrap_or_else(|err| err.into_inner()) = callback.map(|callback| Sink {
+        callback,
+        user_data,
+    });
+}
@@ -0,0 +1,122 @@
+// 转发到 C 运行时 malloc/free 的全局分配器：库用 malloc 特性把它装成 #[global_allocator] 后，
+// Rust 和 C 的分配都来自同一个堆，C 可以直接 free 库返回的内存
+// A global allocator forwarding to the C runtime's malloc/free: once a library installs it as its
+// #[global_allocator] through the malloc feature, Rust and C allocations come from the same heap
+// and C may free the memory the library returns directly
+
+use std::alloc::{GlobalAlloc, Layout};
+use std::ffi::c_void;
+use std::mem::align_of;
+use std::ptr;
+
+/// The alignment every `malloc` block has at least.
+pub const MALLOC_ALIGN: usize = 2 * align_of::<usize>();
+
+extern "C" {
+    fn malloc(size: usize) -> *mut c_void;
+    fn calloc(count: usize, size: usize) -> *mut c_void;
+    fn realloc(ptr: *mut c_void, size: usize) -> *mut c_void;
+    fn free(ptr: *mut c_void);
+    #[cfg(unix)]
+    fn posix_memalign(out: *mut *mut c_void, align: usize, size: usize) -> std::ffi::c_int;
+    #[cfg(windows)]
+    fn _aligned_malloc(size: usize, align: usize) -> *mut c_void;
+    #[cfg(windows)]
+    fn _aligned_free(ptr: *mut c_void);
+}
+
+/// 用 C 运行时的 malloc 系列函数实现的 [`GlobalAlloc`]
+/// A [`GlobalAlloc`] built on the C runtime's malloc family.
+///
+/// Alignments up to [`MALLOC_ALIGN`] come straight from `malloc`, so those blocks can be released
+/// with `free`. Larger ones use `posix_memalign` on Unix, which `free` accepts too, and
+/// `_aligned_malloc` on Windows, which needs `_aligned_free` instead.
+pub struct MallocAllocator;
+
+/// Allocates `layout.size()` bytes (at least one) aligned to `layout.align()` from the C heap,
+/// returning NULL when out of memory.
+///
+/// # Safety
+///
+/// The block must be released with [`free_aligned`] and the same alignment.
+pub unsafe fn malloc_aligned(layout: Layout) -> *mut u8 {
+    let size = layout.size().max(1);
+    if layout.align() <= MALLOC_ALIGN {
+        return malloc(size).cast();
+    }
+    #[cfg(unix)]
+    {
+        let mut out = ptr::null_mut();
+        let align = layout.align().max(align_of::<*mut c_void>());
+        if posix_memalign(&mut out, align, size) == 0 {
+            out.cast()
+        } else {
+            ptr::null_mut()
+        }
+    }
+    #[cfg(windows)]
+    {
+        _aligned_malloc(size, layout.align()).cast()
+    }
+}
+
+/// Releases a block from [`malloc_aligned`]; NULL is a no-op.
+///
+/// # Safety
+///
+/// `ptr` must be NULL or come from [`malloc_aligned`] with an alignment of `align`.
+pub unsafe fn free_aligned(ptr: *mut u8, align: usize) {
+    #[cfg(windows)]
+    if align > MALLOC_ALIGN {
+        return _aligned_free(ptr.cast());
+    }
+    let _ = align;
+    free(ptr.cast());
+}
+
+/// Resizes a block from [`malloc_aligned`] with an alignment of at most [`MALLOC_ALIGN`] through
+/// `realloc`, returning NULL and keeping the old block when out of memory.
+///
+/// # Safety
+///
+/// `ptr` must be NULL or come from [`malloc_aligned`] with such an alignment.
+pub unsafe fn realloc_small(ptr: *mut u8, new_size: usize) -> *mut u8 {
+    realloc(ptr.cast(), new_size.max(1)).cast()
+}
+
+unsafe impl GlobalAlloc for MallocAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        malloc_aligned(layout)
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        if layout.align() <= MALLOC_ALIGN {
+            calloc(1, layout.size().max(1)).cast()
+        } else {
+            let ptr = malloc_aligned(layout);
+            if !ptr.is_null() {
+                ptr.write_bytes(0, layout.size());
+            }
+            ptr
+        }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        free_aligned(ptr, layout.align());
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        if layout.align() <= MALLOC_ALIGN {
+            return realloc_small(ptr, new_size);
+        }
+        // realloc 不保留更大的对齐，只能分配新块再复制
+        // realloc doesn't keep larger alignments, so this allocates a new block and copies
+        let new_layout = Layout::from_size_align_unchecked(new_size, layout.align());
+        let new = malloc_aligned(new_layout);
+        if !new.is_null() {
+            ptr::copy_nonoverlapping(ptr, new, layout.size().min(new_size));
+            free_aligned(ptr, layout.align());
+        }
+        new
+    }
+}
@@ -0,0 +1,138 @@
+// 由 C 创建的锁的 RAII 包装：Unix 上是 pthread_mutex_t，Windows 上是 SRWLOCK。锁和它保护的数据都归 C
+// 所有，Rust 只借用它们；lock 返回的守卫离开作用域时在同一个线程上解锁，守卫不能转移到其他线程，
+// 数据也只能通过守卫访问
+// An RAII wrapper for a lock created by C: a pthread_mutex_t on Unix and an SRWLOCK on Windows.
+// Both the lock and the data it protects belong to C and Rust only borrows them; the guard lock
+// returns unlocks on the same thread when it goes out of scope, it can't be moved to another
+// thread, and the data can only be reached through it
+
+use std::io;
+use std::marker::{PhantomData, PhantomPinned};
+use std::ops::{Deref, DerefMut};
+use std::ptr::NonNull;
+
+use crate::FfiError;
+
+/// C 的锁，只通过指针使用
+/// A C lock, only ever used through a pointer: a `pthread_mutex_t` on Unix and an `SRWLOCK` on
+/// Windows.
+#[repr(C)]
+pub struct RawCMutex {
+    _opaque: [u8; 0],
+    _marker: PhantomData<(*mut u8, PhantomPinned)>,
+}
+
+#[cfg(unix)]
+extern "C" {
+    fn pthread_mutex_lock(mutex: *mut RawCMutex) -> std::ffi::c_int;
+    fn pthread_mutex_unlock(mutex: *mut RawCMutex) -> std::ffi::c_int;
+}
+
+#[cfg(windows)]
+#[link(name = "kernel32")]
+extern "system" {
+    fn AcquireSRWLockExclusive(lock: *mut RawCMutex);
+    fn ReleaseSRWLockExclusive(lock: *mut RawCMutex);
+}
+
+/// C 创建的锁和它保护的数据
+/// A lock created by C together with the `T` it protects.
+///
+/// Rust and C code may lock it at the same time from any threads; C keeps locking it with its own
+/// `pthread_mutex_lock` or `AcquireSRWLockExclusive` calls. Dropping a `CMutex` neither destroys
+/// the lock nor frees the data.
+pub struct CMutex<T> {
+    raw: NonNull<RawCMutex>,
+    data: NonNull<T>,
+}
+
+// 和 std 的 Mutex 一样，只要数据可以转移到其他线程，锁就可以共享
+// Like std's Mutex, the lock can be shared as long as the data may move to other threads
+unsafe impl<T: Send> Send for CMutex<T> {}
+unsafe impl<T: Send> Sync for CMutex<T> {}
+
+impl<T> CMutex<T> {
+    /// Wraps C's lock `raw` and the `data` it protects, failing with
+    /// [`FfiError::NullPointer`] when either is NULL.
+    ///
+    /// # Safety
+    ///
+    /// `raw` must point to an initialized, non-recursive lock and `data` to a valid `T` that C
+    /// only accesses while holding it. Both must stay in place, and the lock undestroyed, for as
+    /// long as the returned `CMutex` is in use.
+    pub unsafe fn from_raw(raw: *mut RawCMutex, data: *mut T) -> Result<CMutex<T>, FfiError> {
+        match (NonNull::new(raw), NonNull::new(data)) {
+            (Some(raw), Some(data)) => Ok(CMutex { raw, data }),
+            _ => Err(FfiError::NullPointer),
+        }
+    }
+
+    /// Blocks until the calling thread holds the lock, the same as C calling
+    /// `pthread_mutex_lock` or `AcquireSRWLockExclusive`.
+    ///
+    /// Fails with the error code `pthread_mutex_lock` returned; SRW locks can't fail. Locking it
+    /// again while the thread holds the guard deadlocks, as it would in C.
+    pub fn lock(&self) -> io::Result<CMutexGuard<'_, T>> {
+        #[cfg(unix)]
+        match unsafe { pthread_mutex_lock(self.raw.as_ptr()) } {
+            0 => {}
+            code => return Err(io::Error::from_raw_os_error(code)),
+        }
+        #[cfg(windows)]
+        unsafe {
+            AcquireSRWLockExclusive(self.raw.as_ptr())
+        };
+        Ok(CMutexGuard {
+            mutex: self,
+            _not_send: PhantomData,
+        })
+    }
+
+    /// C's lock, for passing it back to C.
+    pub fn as_raw(&self) -> *mut RawCMutex {
+        self.raw.as_ptr()
+    }
+}
+
+/// 持有 [`CMutex`] 期间访问数据的守卫，离开作用域时解锁
+/// Access to the data of a [`CMutex`] while holding its lock, unlocking when dropped.
+///
+/// It isn't `Send`: both kinds of lock must be released by the thread that acquired them.
+pub struct CMutexGuard<'a, T> {
+    mutex: &'a CMutex<T>,
+    _not_send: PhantomData<*const ()>,
+}
+
+// 守卫只借出数据，共享守卫的引用和共享 &T 一样
+// The guard only lends the data out, so sharing a reference to it is the same as sharing a &T
+unsafe impl<T: Sync> Sync for CMutexGuard<'_, T> {}
+
+impl<T> Deref for CMutexGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        // SAFETY: the lock is held, so neither C nor another guard touches the data
+        unsafe { self.mutex.data.as_ref() }
+    }
+}
+
+impl<T> DerefMut for CMutexGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        // SAFETY: as above, and this guard is borrowed mutably
+        unsafe { &mut *self.mutex.data.as_ptr() }
+    }
+}
+
+impl<T> Drop for CMutexGuard<'_, T> {
+    fn drop(&mut self) {
+        // SAFETY: this thread acquired the lock in CMutex::lock and hasn't released it since
+        #[cfg(unix)]
+        unsafe {
+            pthread_mutex_unlock(self.mutex.raw.as_ptr());
+        }
+        #[cfg(windows)]
+        unsafe {
+            ReleaseSRWLockExclusive(self.mutex.raw.as_ptr())
+        };
+    }
+}
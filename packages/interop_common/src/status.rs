@@ -0,0 +1,92 @@
+use crate::FfiError;
+
+/// 导出函数的状态码，C 端看到的是一个 `int` 枚举
+/// Status codes returned by the exported functions, seen as an `int` enum on the C side.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FfiStatus {
+    /// The call succeeded and every output was written in full.
+    Ok = 0,
+    /// A required pointer argument was NULL.
+    NullPointer = 1,
+    /// An input string is not valid UTF-8.
+    InvalidUtf8 = 2,
+    /// An input string has no NUL terminator within its buffer length.
+    Unterminated = 3,
+    /// An arithmetic result does not fit in its C type.
+    Overflow = 4,
+    /// An output string was truncated to fit its buffer.
+    BufferTooSmall = 5,
+    /// The Rust code panicked; the message is available as the last error and from
+    /// `rxc_rustlib_take_last_panic`.
+    Panic = 6,
+    /// A pointer argument is not properly aligned for its type.
+    Misaligned = 7,
+    /// A string to be written contains an interior NUL byte.
+    InteriorNul = 8,
+    /// The library has not been initialized, or it was shut down.
+    NotInitialized = 9,
+    /// The library was initialized a second time without being shut down in between.
+    AlreadyInitialized = 10,
+    /// The call would block waiting for itself, for example when made from one of the library's
+    /// own threads.
+    WouldDeadlock = 11,
+    /// The call stopped early because the caller cancelled it, through a cancellation token or by
+    /// returning non-zero from a progress callback.
+    Cancelled = 12,
+    /// An allocation's alignment is not a power of two or its size is too large.
+    InvalidLayout = 13,
+    /// The allocator could not provide the requested memory.
+    OutOfMemory = 14,
+    /// The handle does not refer to a live object: it is null, was already freed, or is a stale
+    /// copy of one whose slot now holds another object.
+    InvalidHandle = 15,
+    /// An input wide string is not valid UTF-16: it has an unpaired surrogate.
+    InvalidUtf16 = 16,
+    /// A file or other I/O operation failed; the last error has the OS error code.
+    Io = 17,
+    /// The input bytes don't form a valid message, such as a header with the wrong magic or
+    /// checksum.
+    Malformed = 18,
+    /// The `size` a struct such as `InitConfig` starts with is not the size of any version of it
+    /// the library knows, such as one from a newer header.
+    UnsupportedVersion = 19,
+    /// An argument is outside the range the function accepts, such as a buffer size of 0, or a
+    /// value the call requires was never set.
+    InvalidArgument = 20,
+}
+
+impl From<FfiError> for FfiStatus {
+    fn from(err: FfiError) -> Self {
+        match err {
+            FfiError::NullPointer => FfiStatus::NullPointer,
+            FfiError::Misaligned => FfiStatus::Misaligned,
+            FfiError::InvalidUtf8 => FfiStatus::InvalidUtf8,
+            FfiError::InvalidUtf16 => FfiStatus::InvalidUtf16,
+            FfiError::Unterminated => FfiStatus::Unterminated,
+            FfiError::InteriorNul => FfiStatus::InteriorNul,
+            FfiError::Overflow => FfiStatus::Overflow,
+            FfiError::BufferTooSmall { .. } => FfiStatus::BufferTooSmall,
+            FfiError::NotInitialized => FfiStatus::NotInitialized,
+            FfiError::AlreadyInitialized => FfiStatus::AlreadyInitialized,
+            FfiError::WouldDeadlock => FfiStatus::WouldDeadlock,
+            FfiError::Cancelled => FfiStatus::Cancelled,
+            FfiError::InvalidLayout => FfiStatus::InvalidLayout,
+            FfiError::OutOfMemory => FfiStatus::OutOfMemory,
+            FfiError::InvalidHandle => FfiStatus::InvalidHandle,
+            FfiError::Io { .. } => FfiStatus::Io,
+            FfiError::Malformed => FfiStatus::Malformed,
+            FfiError::UnsupportedVersion => FfiStatus::UnsupportedVersion,
+            FfiError::InvalidArgument => FfiStatus::InvalidArgument,
+        }
+    }
+}
+
+impl From<Result<(), FfiError>> for FfiStatus {
+    fn from(result: Result<(), FfiError>) -> Self {
+        match result {
+            Ok(()) => FfiStatus::Ok,
+            Err(err) => err.into(),
+        }
+    }
+}
@@ -0,0 +1,91 @@
+// 把 Rust 闭包当作 C 的 `fn(ctx, arg)` 回调传出去的蹦床函数
+// Trampolines that pass a Rust closure where C expects a `fn(ctx, arg)` callback
+
+use std::any::Any;
+use std::ffi::c_void;
+use std::panic::{self, AssertUnwindSafe};
+
+/// A C callback taking the context pointer first, as in `int (*cb)(void *ctx, int value)`.
+pub type CtxCallback<A, R> = extern "C" fn(ctx: *mut c_void, arg: A) -> R;
+
+struct Scoped<F> {
+    closure: F,
+    panic: Option<Box<dyn Any + Send>>,
+}
+
+/// 在 `call` 执行期间把 `closure` 借给 C 代码
+/// Lends `closure` to C code for the duration of `call`.
+///
+/// `call` receives a C function pointer and a context pointer to pass on to the C function; both
+/// are only valid until `call` returns, so the C side must not keep them. If the closure panics,
+/// the panic is caught inside the trampoline (it must not unwind through C frames), later
+/// invocations return `R::default()`, and the panic is resumed once `call` has returned.
+pub fn with_callback<F, A, R, T>(
+    closure: F,
+    call: impl FnOnce(CtxCallback<A, R>, *mut c_void) -> T,
+) -> T
+where
+    F: FnMut(A) -> R,
+    R: Default,
+{
+    let mut scoped = Scoped {
+        closure,
+        panic: None,
+    };
+    let result = call(
+        scoped_trampoline::<F, A, R>,
+        &mut scoped as *mut Scoped<F> as *mut c_void,
+    );
+    if let Some(payload) = scoped.panic {
+        panic::resume_unwind(payload);
+    }
+    result
+}
+
+extern "C" fn scoped_trampoline<F, A, R>(ctx: *mut c_void, arg: A) -> R
+where
+    F: FnMut(A) -> R,
+    R: Default,
+{
+    // ctx 由 with_callback 传入，指向仍然存活的 Scoped<F>
+    // ctx comes from with_callback and points to a Scoped<F> that is still alive
+    let scoped = unsafe { &mut *(ctx as *mut Scoped<F>) };
+    if scoped.panic.is_some() {
+        return R::default();
+    }
+    match panic::catch_unwind(AssertUnwindSafe(|| (scoped.closure)(arg))) {
+        Ok(value) => value,
+        Err(payload) => {
+            scoped.panic = Some(payload);
+            R::default()
+        }
+    }
+}
+
+/// 把只调用一次的闭包装箱并泄漏给 C 代码，回调执行时再收回并释放
+/// Boxes a call-once closure and leaks it to C code; it is reclaimed and dropped when the
+/// callback fires.
+///
+/// Meant for callbacks that outlive the registering call, e.g. completion callbacks invoked from
+/// another thread. The C side must invoke the callback exactly once, otherwise the closure leaks
+/// (never) or is freed twice (more than once). A panic in the closure is caught and the callback
+/// returns `R::default()`, as there is no Rust caller left to resume it in.
+pub fn leak_callback<F, A, R>(closure: F) -> (CtxCallback<A, R>, *mut c_void)
+where
+    F: FnOnce(A) -> R + Send + 'static,
+    R: Default,
+{
+    let ctx = Box::into_raw(Box::new(closure)) as *mut c_void;
+    (once_trampoline::<F, A, R>, ctx)
+}
+
+extern "C" fn once_trampoline<F, A, R>(ctx: *mut c_void, arg: A) -> R
+where
+    F: FnOnce(A) -> R,
+    R: Default,
+{
+    // ctx 由 leak_callback 泄漏，这里是它唯一一次被收回
+    // ctx was leaked by leak_callback and this is the only place it is reclaimed
+    let closure = unsafe { Box::from_raw(ctx as *mut F) };
+    panic::catch_unwind(AssertUnwindSafe(|| closure(arg))).unwrap_or_default()
+}
@@ -0,0 +1,140 @@
+// 跨越 FFI 边界的借用视图：FfiSlice 是数组的指针加长度，FfiStr 是不以 NUL 结尾的 UTF-8 字符串的指针加
+// 字节数。两者都是 #[repr(C)] 的值类型，cbindgen 为它们生成 C 的 typedef，新的数组和字符串 API 用它们代替
+// 两个分开的参数。视图不拥有数据，Rust 端变回切片或 &str 时检查空指针、对齐和 UTF-8
+// Borrowed views crossing the FFI boundary: FfiSlice is an array's pointer and length, FfiStr the
+// pointer and byte count of a UTF-8 string that isn't NUL terminated. Both are #[repr(C)] value
+// types cbindgen generates C typedefs for, and the newer array and string APIs take them instead
+// of two separate parameters. A view doesn't own its data, turning it back into a slice or a &str
+// on the Rust side checks for NULL, alignment and UTF-8
+
+use std::ffi::c_char;
+use std::{ptr, str};
+
+use crate::{slice_from_raw, FfiError};
+
+/// 借用的数组：指针加元素个数
+/// A borrowed array: a pointer and a count of elements.
+///
+/// `ptr` may be NULL when `len` is 0. In C, `FfiSlice<int>` is `FfiSlice_c_int`.
+#[repr(C)]
+#[derive(Debug)]
+pub struct FfiSlice<T> {
+    pub ptr: *const T,
+    pub len: usize,
+}
+
+// 视图本身总是可以复制，不要求 T: Copy
+// The view itself can always be copied, without requiring T: Copy
+impl<T> Clone for FfiSlice<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for FfiSlice<T> {}
+
+impl<T> FfiSlice<T> {
+    /// An empty view with a NULL pointer.
+    pub const fn empty() -> FfiSlice<T> {
+        FfiSlice {
+            ptr: ptr::null(),
+            len: 0,
+        }
+    }
+
+    /// A view of `values`, valid for as long as they are borrowed.
+    pub fn new(values: &[T]) -> FfiSlice<T> {
+        FfiSlice {
+            ptr: values.as_ptr(),
+            len: values.len(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Turns the view back into a slice with [`slice_from_raw`], failing on a NULL or
+    /// misaligned pointer unless the view is empty.
+    ///
+    /// # Safety
+    ///
+    /// If `len > 0`, `ptr` must point to `len` initialised values that are not mutated for `'a`.
+    pub unsafe fn as_slice<'a>(self) -> Result<&'a [T], FfiError> {
+        slice_from_raw(self.ptr, self.len)
+    }
+}
+
+impl<T> From<&[T]> for FfiSlice<T> {
+    fn from(values: &[T]) -> FfiSlice<T> {
+        FfiSlice::new(values)
+    }
+}
+
+/// 借用的 UTF-8 字符串，不以 NUL 结尾，可以包含 NUL
+/// A borrowed UTF-8 string: a pointer and a length in bytes, not NUL terminated and possibly
+/// containing NULs.
+///
+/// `ptr` may be NULL when `len` is 0.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct FfiStr {
+    pub ptr: *const c_char,
+    pub len: usize,
+}
+
+impl FfiStr {
+    /// An empty string with a NULL pointer.
+    pub const fn empty() -> FfiStr {
+        FfiStr {
+            ptr: ptr::null(),
+            len: 0,
+        }
+    }
+
+    /// A view of `s`, valid for as long as it is borrowed.
+    pub fn new(s: &str) -> FfiStr {
+        FfiStr {
+            ptr: s.as_ptr().cast(),
+            len: s.len(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The bytes of the string, failing with [`FfiError::NullPointer`] on a NULL pointer unless
+    /// the string is empty; they are not checked to be UTF-8.
+    ///
+    /// # Safety
+    ///
+    /// If `len > 0`, `ptr` must point to `len` readable bytes that are not mutated for `'a`.
+    pub unsafe fn as_bytes<'a>(self) -> Result<&'a [u8], FfiError> {
+        slice_from_raw(self.ptr.cast::<u8>(), self.len)
+    }
+
+    /// Like [`FfiStr::as_bytes`], additionally failing with [`FfiError::InvalidUtf8`] unless the
+    /// bytes are UTF-8.
+    ///
+    /// # Safety
+    ///
+    /// As for [`FfiStr::as_bytes`].
+    pub unsafe fn as_str<'a>(self) -> Result<&'a str, FfiError> {
+        str::from_utf8(self.as_bytes()?).map_err(|_| FfiError::InvalidUtf8)
+    }
+}
+
+impl From<&str> for FfiStr {
+    fn from(s: &str) -> FfiStr {
+        FfiStr::new(s)
+    }
+}
@@ -0,0 +1,80 @@
+// UTF-16 宽字符串和 Rust 字符串之间的转换，供 Windows 上使用 wchar_t / LPWSTR 的调用方使用：Windows 的
+// wchar_t 是 16 位的 UTF-16 代码单元，和 u16 相同。读取时找到 NUL 后用 widestring 解码，不成对的代理项是
+// 错误而不会被替换成 U+FFFD；写入时和 write_cstr 一样按 snprintf 的方式截断，但不会把代理对拆开，所以
+// 两个方向都不丢失信息
+// Conversions between UTF-16 wide strings and Rust strings, for callers on Windows using wchar_t /
+// LPWSTR: Windows' wchar_t is a 16-bit UTF-16 code unit, the same as u16. Reading finds the NUL and
+// decodes up to it with widestring, an unpaired surrogate being an error rather than replaced
+// with U+FFFD; writing truncates the way snprintf does like write_cstr, but never splits a
+// surrogate pair, so neither direction loses anything
+
+use widestring::{U16CString, U16Str};
+
+use crate::{check_ptr, slice_from_raw_mut, FfiError};
+
+/// Reads a NUL-terminated UTF-16 string, looking at no more than `max_len` code units.
+///
+/// # Safety
+///
+/// If non-NULL, `src` must be valid for reads of `max_len` code units, or up to and including
+/// its NUL terminator if that comes first.
+pub unsafe fn read_wstr(src: *const u16, max_len: usize) -> Result<String, FfiError> {
+    check_ptr(src)?;
+    // 和 read_cstr 一样逐个查找 NUL，不读取它后面的内容
+    // Looks for the NUL one unit at a time like read_cstr, reading nothing past it
+    let mut len = 0;
+    while len < max_len && *src.add(len) != 0 {
+        len += 1;
+    }
+    if len == max_len {
+        return Err(FfiError::Unterminated);
+    }
+    U16Str::from_ptr(src, len)
+        .to_string()
+        .map_err(|_| FfiError::InvalidUtf16)
+}
+
+/// Copies `s` into `dst` as UTF-16 the way `snprintf` does; the pointer-free core of
+/// [`write_wstr`].
+///
+/// The copy is truncated to fit, before a surrogate pair rather than in the middle of one, and
+/// always NUL terminated when `dst` is not empty. Returns the number of code units written
+/// (excluding the NUL), or [`FfiError::BufferTooSmall`] with the full length in code units if `s`
+/// did not fit.
+pub fn copy_wstr(dst: &mut [u16], s: &str) -> Result<usize, FfiError> {
+    let wide = U16CString::from_str(s).map_err(|_| FfiError::InteriorNul)?;
+    let units = wide.as_slice();
+    let Some(room) = dst.len().checked_sub(1) else {
+        return Err(FfiError::BufferTooSmall {
+            required: units.len(),
+        });
+    };
+
+    let mut n = units.len().min(room);
+    // 截断点前面是高代理项时，把它也留在外面
+    // When the unit before the cut is a high surrogate, leave it out as well
+    if n < units.len() && n > 0 && (0xD800..0xDC00).contains(&units[n - 1]) {
+        n -= 1;
+    }
+    dst[..n].copy_from_slice(&units[..n]);
+    dst[n] = 0;
+
+    if n < units.len() {
+        Err(FfiError::BufferTooSmall {
+            required: units.len(),
+        })
+    } else {
+        Ok(n)
+    }
+}
+
+/// Writes `s` as UTF-16 into a C buffer of `dst_len` code units the way `snprintf` does.
+///
+/// See [`copy_wstr`] for the results. A NULL `dst` with `dst_len == 0` only queries the length.
+///
+/// # Safety
+///
+/// If `dst_len > 0`, `dst` must be valid for writes of `dst_len` code units.
+pub unsafe fn write_wstr(dst: *mut u16, dst_len: usize, s: &str) -> Result<usize, FfiError> {
+    copy_wstr(slice_from_raw_mut(dst, dst_len)?, s)
+}
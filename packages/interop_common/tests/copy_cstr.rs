@@ -0,0 +1,82 @@
+// write_cstr 不含指针的核心 copy_cstr 的测试，也可以在 Miri 下运行
+// Tests for copy_cstr, the pointer-free core of write_cstr, which also run under Miri
+//
+// 运行 / Run: cargo +nightly miri test -p interop_common --test copy_cstr
+
+use std::ffi::c_char;
+use std::ptr;
+
+use interop_common::{copy_cstr, write_cstr, FfiError};
+
+#[test]
+fn copies_and_terminates() {
+    let mut buf = [0xff; 8];
+    assert_eq!(copy_cstr(&mut buf, "Lee"), Ok(3));
+    assert_eq!(&buf[..4], b"Lee\0");
+    assert_eq!(buf[4..], [0xff; 4]);
+}
+
+#[test]
+fn exact_fit_leaves_room_for_the_nul() {
+    let mut buf = [0xff; 4];
+    assert_eq!(copy_cstr(&mut buf, "Lee"), Ok(3));
+    assert_eq!(&buf, b"Lee\0");
+}
+
+#[test]
+fn truncates_one_byte_short() {
+    let mut buf = [0xff; 3];
+    assert_eq!(
+        copy_cstr(&mut buf, "Lee"),
+        Err(FfiError::BufferTooSmall { required: 3 })
+    );
+    assert_eq!(&buf, b"Le\0");
+}
+
+#[test]
+fn truncation_may_split_a_multibyte_character() {
+    let mut buf = [0xff; 3];
+    assert_eq!(
+        copy_cstr(&mut buf, "李"),
+        Err(FfiError::BufferTooSmall { required: 3 })
+    );
+    assert_eq!(buf, [0xe6, 0x9d, 0]);
+}
+
+#[test]
+fn empty_buffer_only_reports_the_length() {
+    assert_eq!(
+        copy_cstr(&mut [], "Lee"),
+        Err(FfiError::BufferTooSmall { required: 3 })
+    );
+    let mut buf = [0xff; 1];
+    assert_eq!(
+        copy_cstr(&mut buf, "Lee"),
+        Err(FfiError::BufferTooSmall { required: 3 })
+    );
+    assert_eq!(buf, [0]);
+}
+
+#[test]
+fn rejects_interior_nul_without_writing() {
+    let mut buf = [0xff; 8];
+    assert_eq!(copy_cstr(&mut buf, "L\0e"), Err(FfiError::InteriorNul));
+    assert_eq!(buf, [0xff; 8]);
+}
+
+#[test]
+fn write_cstr_shim_matches_and_accepts_a_null_query() {
+    let mut buf = [0 as c_char; 8];
+    assert_eq!(
+        unsafe { write_cstr(buf.as_mut_ptr(), buf.len(), "Lee") },
+        Ok(3)
+    );
+    assert_eq!(
+        unsafe { write_cstr(ptr::null_mut(), 0, "Lee") },
+        Err(FfiError::BufferTooSmall { required: 3 })
+    );
+    assert_eq!(
+        unsafe { write_cstr(ptr::null_mut(), 4, "Lee") },
+        Err(FfiError::NullPointer)
+    );
+}
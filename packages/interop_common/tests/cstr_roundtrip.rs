@@ -0,0 +1,86 @@
+// 用 proptest 生成任意不含 NUL 的字符串和缓冲区大小，检查 write_cstr、read_cstr 和 CBuffer 的往返结果
+// Generates arbitrary NUL-free strings and buffer sizes with proptest and checks the round trip
+// through write_cstr, read_cstr and CBuffer
+
+use std::ffi::{c_char, CStr};
+
+use interop_common::{read_cstr, write_cstr, CBuffer, FfiError};
+use proptest::prelude::*;
+
+// 不含 NUL 的任意 Unicode 字符串，包括多字节字符
+// Arbitrary Unicode strings without NUL, multi-byte characters included
+fn name() -> impl Strategy<Value = String> {
+    "[^\\x00]{0,48}"
+}
+
+// 容量落在字符串长度加 NUL 附近，覆盖刚好放下、少一个字节和多一个字节的情况
+// A capacity around the string length plus the NUL, covering exact fits and one byte short or over
+fn around(len: usize) -> impl Strategy<Value = usize> {
+    len.saturating_sub(2)..=len + 2
+}
+
+fn name_and_capacity() -> impl Strategy<Value = (String, usize)> {
+    name().prop_flat_map(|s| {
+        let len = s.len();
+        (Just(s), prop_oneof![around(len), 0..len + 64])
+    })
+}
+
+proptest! {
+    #[test]
+    fn write_then_read_matches((s, cap) in name_and_capacity()) {
+        // 多出的一个字节是哨兵，用来发现越界写入
+        // The one extra byte is a sentinel that catches writes past the capacity
+        let mut buf = vec![0x7f as c_char; cap + 1];
+        let result = unsafe { write_cstr(buf.as_mut_ptr(), cap, &s) };
+        prop_assert_eq!(buf[cap], 0x7f as c_char);
+
+        if cap == 0 {
+            prop_assert_eq!(result, Err(FfiError::BufferTooSmall { required: s.len() }));
+        } else if s.len() < cap {
+            prop_assert_eq!(result, Ok(s.len()));
+            prop_assert_eq!(unsafe { read_cstr(buf.as_ptr(), cap) }, Ok(s.as_str()));
+        } else {
+            // 截断按字节进行，可能切开多字节字符，所以按字节比较
+            // Truncation works on bytes and may split a multi-byte character, so compare bytes
+            prop_assert_eq!(result, Err(FfiError::BufferTooSmall { required: s.len() }));
+            let copied = unsafe { CStr::from_ptr(buf.as_ptr()) }.to_bytes();
+            prop_assert_eq!(copied, &s.as_bytes()[..cap - 1]);
+        }
+    }
+
+    #[test]
+    fn read_stops_at_max_len((s, cap) in name_and_capacity()) {
+        let mut bytes = s.clone().into_bytes();
+        bytes.push(0);
+        let result = unsafe { read_cstr(bytes.as_ptr() as *const c_char, cap) };
+        if s.len() < cap {
+            prop_assert_eq!(result, Ok(s.as_str()));
+        } else {
+            prop_assert_eq!(result, Err(FfiError::Unterminated));
+        }
+    }
+
+    #[test]
+    fn cbuffer_round_trips((s, cap) in name_and_capacity()) {
+        match CBuffer::new(&s, cap) {
+            Ok(buffer) => {
+                prop_assert!(s.len() < cap);
+                prop_assert_eq!(buffer.capacity(), cap);
+                prop_assert_eq!(buffer.to_str(), Ok(s.as_str()));
+            }
+            Err(err) => {
+                prop_assert!(s.len() >= cap);
+                prop_assert_eq!(err, FfiError::BufferTooSmall { required: s.len() });
+            }
+        }
+    }
+
+    #[test]
+    fn rejects_interior_nul(before in name(), after in name(), cap in 0usize..128) {
+        let s = format!("{before}\0{after}");
+        let mut buf = vec![0 as c_char; cap.max(1)];
+        prop_assert_eq!(unsafe { write_cstr(buf.as_mut_ptr(), cap, &s) }, Err(FfiError::InteriorNul));
+        prop_assert_eq!(CBuffer::new(&s, cap).unwrap_err(), FfiError::InteriorNul);
+    }
+}
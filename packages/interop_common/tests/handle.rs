@@ -0,0 +1,139 @@
+// HandleRegistry 的测试：槽位复用时代数不同，过期、重复释放和伪造的句柄都返回 InvalidHandle，
+// 也可以在 Miri 下运行
+// Tests for HandleRegistry: a reused slot gets a new generation, and stale, double-freed and
+// made-up handles fail with InvalidHandle; they also run under Miri
+//
+// 运行 / Run: cargo +nightly miri test -p interop_common --test handle
+
+use std::sync::Arc;
+use std::thread;
+
+use interop_common::{FfiError, FfiHandle, HandleRegistry};
+
+#[test]
+fn insert_get_remove() {
+    let registry = HandleRegistry::new();
+    let first = registry.insert("first");
+    let second = registry.insert("second");
+    assert!(!first.is_null());
+    assert_ne!(first, second);
+    assert_eq!(registry.len(), 2);
+
+    assert_eq!(*registry.get(first).unwrap(), "first");
+    assert_eq!(*registry.remove(second).unwrap(), "second");
+    assert_eq!(registry.len(), 1);
+    assert_eq!(*registry.get(first).unwrap(), "first");
+}
+
+#[test]
+fn double_remove_is_detected() {
+    let registry = HandleRegistry::new();
+    let handle = registry.insert(1);
+    assert_eq!(registry.remove(handle).as_deref(), Ok(&1));
+    assert_eq!(registry.remove(handle), Err(FfiError::InvalidHandle));
+    assert_eq!(registry.get(handle), Err(FfiError::InvalidHandle));
+    assert!(registry.is_empty());
+}
+
+// 过期句柄指向的槽位已经换了主人，旧句柄既不能读到也不能释放新对象
+// The slot behind a stale handle has a new occupant, which the old handle can neither reach nor
+// free
+#[test]
+fn stale_handle_does_not_reach_the_new_occupant() {
+    let registry = HandleRegistry::new();
+    let keep = registry.insert(0);
+    let stale = registry.insert(1);
+    registry.remove(stale).unwrap();
+    let fresh = registry.insert(2);
+    assert_eq!(fresh.index, stale.index);
+    assert_ne!(fresh.generation, stale.generation);
+
+    assert_eq!(registry.get(stale), Err(FfiError::InvalidHandle));
+    assert_eq!(registry.remove(stale), Err(FfiError::InvalidHandle));
+    assert_eq!(*registry.get(fresh).unwrap(), 2);
+    assert_eq!(*registry.get(keep).unwrap(), 0);
+}
+
+// 注册表清空后存储被释放，之后的句柄仍然不会和以前的重复
+// The storage is released once the registry is empty, and later handles still don't repeat
+// earlier ones
+#[test]
+fn handles_stay_unique_after_emptying() {
+    let registry = HandleRegistry::new();
+    let old = registry.insert('a');
+    registry.remove(old).unwrap();
+    let new = registry.insert('b');
+    assert_ne!(new, old);
+    assert_eq!(registry.get(old), Err(FfiError::InvalidHandle));
+}
+
+#[test]
+fn null_and_made_up_handles_are_invalid() {
+    let registry = HandleRegistry::new();
+    let handle = registry.insert(());
+    assert!(FfiHandle::NULL.is_null());
+    assert_eq!(FfiHandle::default(), FfiHandle::NULL);
+    for made_up in [
+        FfiHandle::NULL,
+        FfiHandle {
+            index: handle.index,
+            generation: handle.generation + 1,
+        },
+        FfiHandle {
+            index: handle.index + 1,
+            generation: handle.generation,
+        },
+        FfiHandle {
+            index: u32::MAX,
+            generation: u32::MAX,
+        },
+    ] {
+        assert_eq!(registry.get(made_up), Err(FfiError::InvalidHandle));
+        assert_eq!(registry.remove(made_up), Err(FfiError::InvalidHandle));
+    }
+    assert_eq!(registry.len(), 1);
+}
+
+// 移除后，仍在使用对象的线程手里的 Arc 让它活到用完为止
+// After a remove, the Arc held by a thread still using the object keeps it alive until it is done
+#[test]
+fn removed_object_lives_while_in_use() {
+    let registry = HandleRegistry::new();
+    let handle = registry.insert(vec![1, 2, 3]);
+    let in_use = registry.get(handle).unwrap();
+    let removed = registry.remove(handle).unwrap();
+    drop(removed);
+    assert_eq!(Arc::strong_count(&in_use), 1);
+    assert_eq!(in_use.iter().sum::<i32>(), 6);
+}
+
+#[test]
+fn shared_between_threads() {
+    static REGISTRY: HandleRegistry<u32> = HandleRegistry::new();
+    let handles: Vec<FfiHandle> = thread::scope(|scope| {
+        let threads: Vec<_> = (0..4)
+            .map(|t| {
+                scope.spawn(move || {
+                    (0..50)
+                        .map(|i| REGISTRY.insert(t * 100 + i))
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+        threads
+            .into_iter()
+            .flat_map(|thread| thread.join().unwrap())
+            .collect()
+    });
+    assert_eq!(REGISTRY.len(), 200);
+    let mut values: Vec<u32> = handles
+        .iter()
+        .map(|&handle| *REGISTRY.remove(handle).unwrap())
+        .collect();
+    values.sort_unstable();
+    let expected: Vec<u32> = (0..4)
+        .flat_map(|t| (0..50).map(move |i| t * 100 + i))
+        .collect();
+    assert_eq!(values, expected);
+    assert!(REGISTRY.is_empty());
+}
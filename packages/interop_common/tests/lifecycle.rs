@@ -0,0 +1,61 @@
+// init 和 shutdown 的顺序约束，以及 InitConfig 中的日志回调
+// The ordering rules of init and shutdown, and the log callback taken from InitConfig
+
+use std::ffi::{c_char, c_void};
+use std::slice;
+use std::sync::Mutex;
+
+use interop_common::{
+    ensure_initialized, ffi_guard, init, is_initialized, shutdown, take_last_panic, FfiError,
+    FfiStatus, InitConfig, LogLevel,
+};
+
+type Received = Mutex<Vec<String>>;
+
+unsafe extern "C" fn collect(
+    _level: LogLevel,
+    msg: *const c_char,
+    len: usize,
+    user_data: *mut c_void,
+) {
+    let received = &*(user_data as *const Received);
+    let msg = String::from_utf8(slice::from_raw_parts(msg as *const u8, len).to_vec()).unwrap();
+    received.lock().unwrap().push(msg);
+}
+
+// 初始化状态是进程全局的，所以全部放在一个测试里按顺序检查
+// The initialization state is process-wide, so everything is checked in order within a single test
+#[test]
+fn init_and_shutdown_in_order() {
+    assert!(!is_initialized());
+    assert_eq!(ensure_initialized(), Err(FfiError::NotInitialized));
+    assert_eq!(shutdown(), Err(FfiError::NotInitialized));
+
+    let received = Received::default();
+    let config = InitConfig {
+        log_callback: Some(collect),
+        log_user_data: &received as *const Received as *mut c_void,
+        install_panic_hook: true,
+        ..InitConfig::default()
+    };
+    assert_eq!(init(&config), Ok(()));
+    assert_eq!(ensure_initialized(), Ok(()));
+    assert_eq!(
+        init(&InitConfig::default()),
+        Err(FfiError::AlreadyInitialized)
+    );
+
+    log::info!("[Rust test] initialized");
+    assert_eq!(ffi_guard(|| panic!("boom")), FfiStatus::Panic);
+    assert!(take_last_panic().unwrap().starts_with("boom at "));
+
+    assert_eq!(shutdown(), Ok(()));
+    assert!(!is_initialized());
+    log::info!("this one goes to stderr");
+    assert_eq!(*received.lock().unwrap(), ["[Rust test] initialized"]);
+
+    // shutdown 之后可以重新初始化
+    // The library can be initialized again after shutdown
+    assert_eq!(init(&InitConfig::default()), Ok(()));
+    assert_eq!(shutdown(), Ok(()));
+}
@@ -0,0 +1,48 @@
+// 通过 set_log_callback 注册的回调收到 log crate 的记录，注销后回到 stderr
+// A callback registered with set_log_callback receives the log crate's records, and
+// unregistering it goes back to stderr
+
+use std::ffi::{c_char, c_void};
+use std::sync::Mutex;
+use std::{ptr, slice};
+
+use interop_common::{set_log_callback, LogLevel};
+
+type Received = Mutex<Vec<(LogLevel, String)>>;
+
+unsafe extern "C" fn collect(
+    level: LogLevel,
+    msg: *const c_char,
+    len: usize,
+    user_data: *mut c_void,
+) {
+    let received = &*(user_data as *const Received);
+    let msg = String::from_utf8(slice::from_raw_parts(msg as *const u8, len).to_vec()).unwrap();
+    received.lock().unwrap().push((level, msg));
+}
+
+// sink 是进程全局的，所以全部放在一个测试里按顺序检查
+// The sink is process-wide, so everything is checked in order within a single test
+#[test]
+fn forwards_records_until_unregistered() {
+    let received = Received::default();
+    set_log_callback(Some(collect), &received as *const Received as *mut c_void);
+
+    log::info!("[Rust test] Hello {}", "Lee");
+    log::warn!("careful");
+    std::thread::scope(|scope| {
+        scope.spawn(|| log::trace!("from a worker thread"));
+    });
+
+    set_log_callback(None, ptr::null_mut());
+    log::error!("this one goes to stderr");
+
+    assert_eq!(
+        *received.lock().unwrap(),
+        [
+            (LogLevel::Info, "[Rust test] Hello Lee".to_string()),
+            (LogLevel::Warn, "careful".to_string()),
+            (LogLevel::Trace, "from a worker thread".to_string()),
+        ]
+    );
+}
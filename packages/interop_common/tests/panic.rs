@@ -0,0 +1,69 @@
+// ffi_guard 捕获的 panic 消息和位置，以及 take_last_panic_into 的截断和取走行为
+// The panic message and location ffi_guard catches, and how take_last_panic_into truncates and
+// takes it
+
+use std::ffi::{c_char, CStr};
+use std::ptr;
+
+use interop_common::{
+    ffi_guard, install_panic_hook, take_last_panic, take_last_panic_into, FfiStatus,
+};
+
+fn panicking_call() -> FfiStatus {
+    install_panic_hook();
+    ffi_guard(|| panic!("boom"))
+}
+
+#[test]
+fn records_message_and_location() {
+    assert_eq!(panicking_call(), FfiStatus::Panic);
+    let msg = take_last_panic().unwrap();
+    assert!(msg.starts_with("boom at "), "{msg}");
+    assert!(msg.contains("panic.rs:"), "{msg}");
+    assert_eq!(take_last_panic(), None);
+}
+
+#[test]
+fn copies_and_takes_the_message() {
+    assert_eq!(unsafe { take_last_panic_into(ptr::null_mut(), 0) }, 0);
+    panicking_call();
+
+    let len = unsafe { take_last_panic_into(ptr::null_mut(), 0) };
+    assert!(len > 0);
+    let mut buf = vec![0 as c_char; len as usize + 1];
+    assert_eq!(
+        unsafe { take_last_panic_into(buf.as_mut_ptr(), buf.len()) },
+        len
+    );
+    let msg = unsafe { CStr::from_ptr(buf.as_ptr()) }.to_str().unwrap();
+    assert!(msg.starts_with("boom at "), "{msg}");
+    assert_eq!(
+        unsafe { take_last_panic_into(buf.as_mut_ptr(), buf.len()) },
+        0
+    );
+}
+
+#[test]
+fn keeps_the_message_after_truncating() {
+    panicking_call();
+    let mut buf = [0 as c_char; 4];
+    let len = unsafe { take_last_panic_into(buf.as_mut_ptr(), buf.len()) };
+    assert!(len > 3);
+    assert_eq!(unsafe { CStr::from_ptr(buf.as_ptr()) }.to_bytes(), b"boo");
+    assert!(take_last_panic().is_some());
+}
+
+#[test]
+fn rejects_null_with_length() {
+    panicking_call();
+    assert_eq!(unsafe { take_last_panic_into(ptr::null_mut(), 8) }, -1);
+}
+
+#[test]
+fn messages_are_per_thread() {
+    panicking_call();
+    std::thread::spawn(|| assert_eq!(take_last_panic(), None))
+        .join()
+        .unwrap();
+    assert!(take_last_panic().is_some());
+}
@@ -0,0 +1,54 @@
+// FfiSlice 和 FfiStr 在视图和切片、&str 之间的往返
+// Round trips of FfiSlice and FfiStr between views and slices or &strs
+
+use std::ptr;
+
+use interop_common::{FfiError, FfiSlice, FfiStr};
+
+#[test]
+fn slice_round_trips() {
+    let values = [1, 2, 3];
+    let view = FfiSlice::new(&values);
+    assert_eq!(view.len(), 3);
+    assert_eq!(unsafe { view.as_slice() }, Ok(&values[..]));
+}
+
+#[test]
+fn empty_slice_may_be_null() {
+    let view = FfiSlice::<u32>::empty();
+    assert!(view.is_empty());
+    assert_eq!(unsafe { view.as_slice() }, Ok(&[][..]));
+}
+
+#[test]
+fn null_slice_with_a_length_fails() {
+    let view = FfiSlice::<u32> {
+        ptr: ptr::null(),
+        len: 2,
+    };
+    assert_eq!(unsafe { view.as_slice() }, Err(FfiError::NullPointer));
+}
+
+#[test]
+fn str_round_trips_without_a_nul() {
+    let text = "hello, world";
+    let view = FfiStr::new(&text[..5]);
+    assert_eq!(view.len(), 5);
+    assert_eq!(unsafe { view.as_str() }, Ok("hello"));
+}
+
+#[test]
+fn str_rejects_invalid_utf8() {
+    let bytes = [b'a', 0xff];
+    let view = FfiStr {
+        ptr: bytes.as_ptr().cast(),
+        len: bytes.len(),
+    };
+    assert_eq!(unsafe { view.as_bytes() }, Ok(&bytes[..]));
+    assert_eq!(unsafe { view.as_str() }, Err(FfiError::InvalidUtf8));
+}
+
+#[test]
+fn empty_str_may_be_null() {
+    assert_eq!(unsafe { FfiStr::empty().as_str() }, Ok(""));
+}
@@ -0,0 +1,65 @@
+// UTF-16 宽字符串的读写：代理对、截断和不成对的代理项
+// Reading and writing UTF-16 wide strings: surrogate pairs, truncation and unpaired surrogates
+
+use std::ptr;
+
+use interop_common::{copy_wstr, read_wstr, FfiError};
+
+fn wide(s: &str) -> Vec<u16> {
+    s.encode_utf16().chain([0]).collect()
+}
+
+#[test]
+fn reads_surrogate_pairs_losslessly() {
+    let name = wide("Zoë 🦀");
+    assert_eq!(
+        unsafe { read_wstr(name.as_ptr(), name.len()) },
+        Ok("Zoë 🦀".to_owned())
+    );
+}
+
+#[test]
+fn read_stops_at_max_len() {
+    let name = wide("abc");
+    assert_eq!(
+        unsafe { read_wstr(name.as_ptr(), 3) },
+        Err(FfiError::Unterminated)
+    );
+    assert_eq!(
+        unsafe { read_wstr(ptr::null(), 3) },
+        Err(FfiError::NullPointer)
+    );
+}
+
+#[test]
+fn rejects_unpaired_surrogates() {
+    let lone = [0xDC00, 0x61, 0];
+    assert_eq!(
+        unsafe { read_wstr(lone.as_ptr(), lone.len()) },
+        Err(FfiError::InvalidUtf16)
+    );
+}
+
+#[test]
+fn copies_and_terminates() {
+    let mut dst = [0xFFFF; 8];
+    assert_eq!(copy_wstr(&mut dst, "🦀!"), Ok(3));
+    assert_eq!(dst[..4], wide("🦀!")[..]);
+}
+
+#[test]
+fn truncation_keeps_surrogate_pairs_whole() {
+    // "a🦀" 是三个代码单元，两个单元的空间放不下代理对的后一半
+    // "a🦀" is three code units, and room for two can't hold the second half of the pair
+    let mut dst = [0xFFFF; 3];
+    assert_eq!(
+        copy_wstr(&mut dst, "a🦀"),
+        Err(FfiError::BufferTooSmall { required: 3 })
+    );
+    assert_eq!(dst, [0x61, 0, 0xFFFF]);
+    assert_eq!(
+        copy_wstr(&mut [], "a"),
+        Err(FfiError::BufferTooSmall { required: 1 })
+    );
+    assert_eq!(copy_wstr(&mut [0; 4], "a\0b"), Err(FfiError::InteriorNul));
+}
@@ -0,0 +1,329 @@
+// 这是我们的构建脚本：根据下面 SHARED 中列出的每个和 C 共享的结构体，生成一个打印 sizeof、对齐和每个
+// 字段 offsetof 的 C 程序，用 cc 找到的编译器把它编译成 OUT_DIR 中的可执行文件，路径通过 LAYOUT_PROBE
+// 交给测试；同时生成在 Rust 端用 size_of、align_of 和 offset_of! 计算同样数字的代码。两边由同一张表生成，
+// 新增的共享结构体只需要在这里加一行
+// This is our build script: from every struct shared with C listed in SHARED below, it generates a
+// C program printing its sizeof, alignment and the offsetof of every field, builds it into an
+// executable in OUT_DIR with the compiler cc finds and hands its path to the tests through
+// LAYOUT_PROBE; it also generates the code computing the same numbers on the Rust side with
+// size_of, align_of and offset_of!. Both sides come from the same table, so a new shared struct
+// only needs one more line here
+
+use std::env;
+use std::fmt::Write;
+use std::fs;
+use std::path::PathBuf;
+
+// 生成的 C 程序包含的头文件，相对于仓库根目录；cdylib_gen.h 和 staticlib_gen.h 都定义了 FfiStatus，
+// 只能包含其中一个，interop_common 的类型两者都有
+// The headers the generated C program includes, relative to the repository root; cdylib_gen.h and
+// staticlib_gen.h both define FfiStatus so only one of them can be included, and both have the
+// interop_common types
+const HEADERS: &[&str] = &[
+    "include/cdylib_gen.h",
+    "include/plugin_api.h",
+    "packages/atomic_interop/c/ping_pong.h",
+    "packages/enum_interop/c/shape.h",
+    "packages/ownership_interop/c/stash.h",
+    "packages/shm_ipc/c/shm_ipc.h",
+    "packages/struct_interop/c/aligned.h",
+    "packages/struct_interop/c/by_value.h",
+    "packages/struct_interop/c/point.h",
+];
+
+/// A struct shared with C: its C type, its Rust type and the fields to compare the offsets of;
+/// fields Rust keeps private can't be named by offset_of! outside their crate and are left out.
+struct Shared {
+    c: &'static str,
+    rust: &'static str,
+    fields: &'static [&'static str],
+}
+
+const SHARED: &[Shared] = &[
+    // interop_common
+    Shared {
+        c: "AllocStats",
+        rust: "interop_common::AllocStats",
+        fields: &[
+            "allocations",
+            "deallocations",
+            "reallocations",
+            "live_blocks",
+            "live_bytes",
+            "peak_bytes",
+            "leaked_blocks",
+            "leaked_bytes",
+        ],
+    },
+    Shared {
+        c: "FfiHandle",
+        rust: "interop_common::FfiHandle",
+        fields: &["index", "generation"],
+    },
+    Shared {
+        c: "FfiSlice_c_int",
+        rust: "interop_common::FfiSlice<std::ffi::c_int>",
+        fields: &["ptr", "len"],
+    },
+    Shared {
+        c: "FfiSlice_u8",
+        rust: "interop_common::FfiSlice<u8>",
+        fields: &["ptr", "len"],
+    },
+    Shared {
+        c: "FfiStr",
+        rust: "interop_common::FfiStr",
+        fields: &["ptr", "len"],
+    },
+    Shared {
+        c: "InitConfig",
+        rust: "interop_common::InitConfig",
+        fields: &[
+            "size",
+            "log_callback",
+            "log_user_data",
+            "install_panic_hook",
+            "worker_threads",
+        ],
+    },
+    // cdylib_gen
+    Shared {
+        c: "AsyncRequest",
+        rust: "cdylib_gen::AsyncRequest",
+        fields: &["a", "b", "delay_ms"],
+    },
+    Shared {
+        c: "LoadReport",
+        rust: "cdylib_gen::LoadReport",
+        fields: &["constructor_runs", "initialized_at_load"],
+    },
+    Shared {
+        c: "MessageHeader",
+        rust: "cdylib_gen::MessageHeader",
+        fields: &[
+            "magic",
+            "version",
+            "kind",
+            "payload_len",
+            "sequence",
+            "checksum",
+        ],
+    },
+    Shared {
+        c: "TraceField",
+        rust: "cdylib_gen::TraceField",
+        fields: &["key", "value"],
+    },
+    Shared {
+        c: "TraceRecord",
+        rust: "cdylib_gen::TraceRecord",
+        fields: &[
+            "kind",
+            "level",
+            "span_id",
+            "name",
+            "target",
+            "fields",
+            "field_count",
+        ],
+    },
+    Shared {
+        c: "Version",
+        rust: "cdylib_gen::Version",
+        fields: &["major", "minor", "patch", "git_hash"],
+    },
+    // plugin_api
+    Shared {
+        c: "PluginHost",
+        rust: "plugin_api::PluginHost",
+        fields: &["api_version", "ctx", "log", "register_vtable"],
+    },
+    Shared {
+        c: "PluginVTable",
+        rust: "plugin_api::PluginVTable",
+        fields: &["name", "user_data", "compute", "destroy"],
+    },
+    // atomic_interop
+    Shared {
+        c: "PingPong",
+        rust: "atomic_interop::PingPong",
+        fields: &[],
+    },
+    // enum_interop
+    Shared {
+        c: "CircleData",
+        rust: "enum_interop::CircleData",
+        fields: &["radius"],
+    },
+    Shared {
+        c: "RectData",
+        rust: "enum_interop::RectData",
+        fields: &["width", "height"],
+    },
+    Shared {
+        c: "TriangleData",
+        rust: "enum_interop::TriangleData",
+        fields: &["a", "b", "c"],
+    },
+    Shared {
+        c: "ShapeData",
+        rust: "enum_interop::ShapeData",
+        fields: &["circle", "rect", "triangle"],
+    },
+    Shared {
+        c: "Shape",
+        rust: "enum_interop::Shape",
+        fields: &["tag", "data"],
+    },
+    // ownership_interop
+    Shared {
+        c: "Stash",
+        rust: "ownership_interop::Stash",
+        fields: &[],
+    },
+    // shm_ipc
+    Shared {
+        c: "ShmHeader",
+        rust: "shm_ipc::ShmHeader",
+        fields: &[],
+    },
+    Shared {
+        c: "ShmSnapshot",
+        rust: "shm_ipc::ShmSnapshot",
+        fields: &["message_id", "len", "checksum", "text"],
+    },
+    // struct_interop
+    Shared {
+        c: "Point",
+        rust: "struct_interop::Point",
+        fields: &["x", "y"],
+    },
+    Shared {
+        c: "Vec4",
+        rust: "struct_interop::Vec4",
+        fields: &["lanes"],
+    },
+    Shared {
+        c: "Particle",
+        rust: "struct_interop::Particle",
+        fields: &["position", "velocity", "id"],
+    },
+    Shared {
+        c: "Pair32",
+        rust: "struct_interop::Pair32",
+        fields: &["a", "b"],
+    },
+    Shared {
+        c: "Pair64",
+        rust: "struct_interop::Pair64",
+        fields: &["a", "b"],
+    },
+    Shared {
+        c: "Mixed16",
+        rust: "struct_interop::Mixed16",
+        fields: &["x", "n"],
+    },
+    Shared {
+        c: "Triple64",
+        rust: "struct_interop::Triple64",
+        fields: &["a", "b", "c"],
+    },
+];
+
+// C 程序每行打印一个 "名字 值"：Type.size、Type.align 和 Type.field
+// The C program prints one "name value" per line: Type.size, Type.align and Type.field
+fn probe_source() -> String {
+    let mut c = String::from("// Generated by packages/layout_check/build.rs, do not edit.\n");
+    c.push_str("#include <stddef.h>\n#include <stdio.h>\n");
+    for header in HEADERS {
+        writeln!(c, "#include \"{}\"", header).unwrap();
+    }
+    // 结构体成员的对齐，对 i386 上的 double 这样的类型，它和 _Alignof 给出的首选对齐不同
+    // A type's alignment as a struct member, which differs from the preferred alignment _Alignof
+    // gives for types such as double on i386
+    c.push_str("\n#define ABI_ALIGN(T) offsetof(struct { char c; T value; }, value)\n");
+    c.push_str("#define PRINT(name, value) printf(\"%s %zu\\n\", name, (size_t)(value))\n\n");
+    c.push_str("int main(void)\n{\n");
+    for shared in SHARED {
+        let name = shared.c;
+        writeln!(c, "    PRINT(\"{name}.size\", sizeof({name}));").unwrap();
+        writeln!(c, "    PRINT(\"{name}.align\", ABI_ALIGN({name}));").unwrap();
+        for field in shared.fields {
+            writeln!(
+                c,
+                "    PRINT(\"{name}.{field}\", offsetof({name}, {field}));"
+            )
+            .unwrap();
+        }
+    }
+    c.push_str("    return 0;\n}\n");
+    c
+}
+
+fn rust_source() -> String {
+    let mut rust = String::from("// Generated by packages/layout_check/build.rs, do not edit.\n\n");
+    rust.push_str("const RUST_LAYOUTS: &[(&str, usize)] = &[\n");
+    for shared in SHARED {
+        let (name, ty) = (shared.c, shared.rust);
+        writeln!(rust, "    (\"{name}.size\", std::mem::size_of::<{ty}>()),").unwrap();
+        writeln!(
+            rust,
+            "    (\"{name}.align\", std::mem::align_of::<{ty}>()),"
+        )
+        .unwrap();
+        for field in shared.fields {
+            writeln!(
+                rust,
+                "    (\"{name}.{field}\", std::mem::offset_of!({ty}, {field})),"
+            )
+            .unwrap();
+        }
+    }
+    rust.push_str("];\n");
+    rust
+}
+
+fn main() {
+    let out_dir = PathBuf::from(env::var("OUT_DIR").unwrap());
+    let root = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap()).join("../..");
+    let source = out_dir.join("layout_probe.c");
+    fs::write(&source, probe_source()).unwrap();
+    fs::write(out_dir.join("rust_layouts.rs"), rust_source()).unwrap();
+
+    let probe = out_dir.join(if env::var("CARGO_CFG_TARGET_OS").unwrap() == "windows" {
+        "layout_probe.exe"
+    } else {
+        "layout_probe"
+    });
+    let compiler = cc::Build::new().get_compiler();
+    let mut command = compiler.to_command();
+    command.arg(&source);
+    // 头文件中的 _Atomic 在 MSVC 上还需要 /experimental:c11atomics
+    // The _Atomic in the headers also needs /experimental:c11atomics on MSVC
+    if compiler.is_like_msvc() {
+        command
+            .args(["/nologo", "/std:c11", "/experimental:c11atomics"])
+            .arg(format!("/I{}", root.display()))
+            .arg(format!("/I{}", root.join("include").display()))
+            .arg(format!("/Fo{}\\", out_dir.display()))
+            .arg(format!("/Fe{}", probe.display()));
+    } else {
+        command
+            .arg("-std=c11")
+            .arg(format!("-I{}", root.display()))
+            .arg(format!("-I{}", root.join("include").display()))
+            .arg("-o")
+            .arg(&probe);
+    }
+    let status = command
+        .status()
+        .unwrap_or_else(|err| panic!("Failed to run the C compiler: {}", err));
+    assert!(status.success(), "Building the layout probe failed.");
+    println!("cargo::rustc-env=LAYOUT_PROBE={}", probe.display());
+
+    println!("cargo::rerun-if-changed=build.rs");
+    println!("cargo::rerun-if-changed=../../include");
+    for header in HEADERS {
+        println!("cargo::rerun-if-changed=../../{}", header);
+    }
+}
@@ -0,0 +1,50 @@
+// 这个库比较 C 和 Rust 看到的共享结构体的布局：构建脚本生成的 C 程序打印每个结构体的 sizeof、对齐和字段的
+// offsetof，生成的 Rust 代码用 size_of、align_of 和 offset_of! 算出同样的数字。两边的结构体定义任何一处
+// 不一致，tests/layouts.rs 就会失败并指出是哪个字段
+// This library compares the layouts C and Rust see for the shared structs: a C program the build
+// script generates prints every struct's sizeof, alignment and the offsetof of its fields, and
+// the generated Rust code computes the same numbers with size_of, align_of and offset_of!. Any
+// drift between the two definitions of a struct fails tests/layouts.rs, naming the field
+
+use std::collections::BTreeMap;
+use std::io;
+use std::process::Command;
+
+include!(concat!(env!("OUT_DIR"), "/rust_layouts.rs"));
+
+/// C 的布局探针程序的路径，由构建脚本编译
+/// The path of the C layout probe, built by the build script.
+pub const PROBE: &str = env!("LAYOUT_PROBE");
+
+/// Every size, alignment and field offset as Rust sees them, keyed by `Type.size`, `Type.align`
+/// and `Type.field` with the C name of the type.
+pub fn rust_layouts() -> BTreeMap<String, usize> {
+    RUST_LAYOUTS
+        .iter()
+        .map(|&(name, value)| (name.to_owned(), value))
+        .collect()
+}
+
+/// The same numbers as C sees them, read from the output of the [`PROBE`].
+pub fn c_layouts() -> io::Result<BTreeMap<String, usize>> {
+    let output = Command::new(PROBE).output()?;
+    if !output.status.success() {
+        return Err(io::Error::other(format!(
+            "the layout probe exited with {}",
+            output.status
+        )));
+    }
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(|line| {
+            line.split_once(' ')
+                .and_then(|(name, value)| Some((name.to_owned(), value.parse().ok()?)))
+                .ok_or_else(|| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("unexpected line from the layout probe: {:?}", line),
+                    )
+                })
+        })
+        .collect()
+}
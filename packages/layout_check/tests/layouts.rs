@@ -0,0 +1,38 @@
+// 共享结构体布局的测试：C 和 Rust 对每个结构体的大小、对齐和每个字段的偏移给出相同的数字，两边列出的
+// 条目也完全相同；另外用几个已知的数字确认 C 的探针测的是对的结构体
+// Tests for the layouts of the shared structs: C and Rust give the same size, alignment and offset
+// of every field for every struct, and both list exactly the same entries; a few known numbers
+// also confirm C's probe measures the right structs
+
+use layout_check::{c_layouts, rust_layouts};
+
+#[test]
+fn c_and_rust_agree_on_every_layout() {
+    let c = c_layouts().unwrap();
+    let rust = rust_layouts();
+    let mismatches: Vec<String> = rust
+        .iter()
+        .filter(|&(name, value)| c.get(name) != Some(value))
+        .map(|(name, value)| format!("{}: Rust {}, C {:?}", name, value, c.get(name)))
+        .collect();
+    assert!(mismatches.is_empty(), "{}", mismatches.join("\n"));
+    assert_eq!(
+        c.keys().collect::<Vec<_>>(),
+        rust.keys().collect::<Vec<_>>()
+    );
+}
+
+// 几个已知的数字，确认探针确实测的是这些结构体：打包的 MessageHeader 没有填充，Particle 按 16 字节对齐
+// A few known numbers confirming the probe really measures these structs: the packed
+// MessageHeader has no padding and Particle is aligned to 16 bytes
+#[test]
+fn c_reports_known_layouts() {
+    let c = c_layouts().unwrap();
+    assert_eq!(c["MessageHeader.size"], 20);
+    assert_eq!(c["MessageHeader.align"], 1);
+    assert_eq!(c["MessageHeader.payload_len"], 6);
+    assert_eq!(c["Particle.align"], 16);
+    assert_eq!(c["Particle.id"], 32);
+    assert_eq!(c["Point.y"], 4);
+    assert_eq!(c["ShapeData.rect"], 0);
+}
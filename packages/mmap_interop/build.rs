@@ -0,0 +1,11 @@
+// 这是我们的构建脚本，编译映射文件并读取 Rust 映射的内存的 C 代码
+// This is our build script, it compiles the C code that maps files and reads the memory Rust
+// mapped
+
+fn main() {
+    cc::Build::new()
+        .file("c/mapping.c")
+        .std("c11")
+        .compile("mapping");
+    println!("cargo::rerun-if-changed=c");
+}
@@ -0,0 +1,156 @@
+// C 一侧的内存映射：POSIX 上用 mmap，Windows 上用文件映射；以及读取 Rust 借来的映射
+// Memory mapping on the C side: mmap on POSIX and a file mapping on Windows; and reading the
+// mapping Rust lends
+#ifndef _WIN32
+// -std=c11 只声明标准 C 的函数，mmap 和 fstat 需要明确要求 POSIX
+// -std=c11 only declares standard C functions, mmap and fstat need POSIX asked for
+#define _POSIX_C_SOURCE 200809L
+#endif
+#include <stdlib.h>
+#include "mapping.h"
+
+#ifdef _WIN32
+#include <windows.h>
+#else
+#include <fcntl.h>
+#include <sys/mman.h>
+#include <sys/stat.h>
+#include <unistd.h>
+#endif
+
+struct CMapping
+{
+    const uint8_t *data;
+    size_t len;
+};
+
+CMapping *c_mmap_open(const char *path)
+{
+    CMapping *mapping = calloc(1, sizeof(CMapping));
+    if (mapping == NULL)
+    {
+        return NULL;
+    }
+#ifdef _WIN32
+    HANDLE file = CreateFileA(path, GENERIC_READ, FILE_SHARE_READ, NULL, OPEN_EXISTING,
+                              FILE_ATTRIBUTE_NORMAL, NULL);
+    LARGE_INTEGER size;
+    if (file == INVALID_HANDLE_VALUE || !GetFileSizeEx(file, &size))
+    {
+        if (file != INVALID_HANDLE_VALUE)
+        {
+            CloseHandle(file);
+        }
+        free(mapping);
+        return NULL;
+    }
+    mapping->len = (size_t)size.QuadPart;
+    if (mapping->len > 0)
+    {
+        // 视图会让文件映射对象和文件一直打开，两个句柄都可以马上关掉
+        // The view keeps the file mapping object and the file open, so both handles can be
+        // closed right away
+        HANDLE section = CreateFileMappingA(file, NULL, PAGE_READONLY, 0, 0, NULL);
+        if (section != NULL)
+        {
+            mapping->data = MapViewOfFile(section, FILE_MAP_READ, 0, 0, 0);
+            CloseHandle(section);
+        }
+    }
+    CloseHandle(file);
+#else
+    int fd = open(path, O_RDONLY);
+    struct stat info;
+    if (fd < 0 || fstat(fd, &info) != 0)
+    {
+        if (fd >= 0)
+        {
+            close(fd);
+        }
+        free(mapping);
+        return NULL;
+    }
+    mapping->len = (size_t)info.st_size;
+    if (mapping->len > 0)
+    {
+        // 映射建立之后就不再需要文件描述符
+        // The file descriptor is no longer needed once the mapping exists
+        void *data = mmap(NULL, mapping->len, PROT_READ, MAP_PRIVATE, fd, 0);
+        mapping->data = data == MAP_FAILED ? NULL : data;
+    }
+    close(fd);
+#endif
+    if (mapping->len > 0 && mapping->data == NULL)
+    {
+        free(mapping);
+        return NULL;
+    }
+    return mapping;
+}
+
+const uint8_t *c_mmap_data(const CMapping *mapping)
+{
+    return mapping->data;
+}
+
+size_t c_mmap_len(const CMapping *mapping)
+{
+    return mapping->len;
+}
+
+void c_mmap_close(CMapping *mapping)
+{
+    if (mapping == NULL)
+    {
+        return;
+    }
+    if (mapping->data != NULL)
+    {
+#ifdef _WIN32
+        UnmapViewOfFile(mapping->data);
+#else
+        munmap((void *)mapping->data, mapping->len);
+#endif
+    }
+    free(mapping);
+}
+
+uint64_t c_checksum(const uint8_t *data, size_t len)
+{
+    uint64_t hash = 14695981039346656037ull;
+    for (size_t i = 0; i < len; i++)
+    {
+        hash = (hash ^ data[i]) * 1099511628211ull;
+    }
+    return hash;
+}
+
+int32_t c_read_rust_mapping(const char *path, uint64_t *checksum)
+{
+    RustMapping *mapping = rust_mmap_open(path);
+    if (mapping == NULL)
+    {
+        return MAPPING_ERROR;
+    }
+    const uint8_t *data;
+    size_t len;
+    int32_t status = rust_mmap_borrow(mapping, &data, &len);
+    if (status != MAPPING_OK)
+    {
+        rust_mmap_close(mapping);
+        return status;
+    }
+    *checksum = c_checksum(data, len);
+    // 还借着的时候关闭会被拒绝，data 依然有效
+    // Closing while it's still borrowed is refused and data stays valid
+    if (rust_mmap_close(mapping) != MAPPING_BORROWED || c_checksum(data, len) != *checksum)
+    {
+        status = MAPPING_ERROR;
+    }
+    rust_mmap_release(mapping);
+    if (rust_mmap_close(mapping) != MAPPING_OK)
+    {
+        status = MAPPING_ERROR;
+    }
+    return status;
+}
@@ -0,0 +1,56 @@
+// 在 C 和 Rust 之间共享内存映射的文件，不复制数据：Rust 映射的文件以指针加长度借给 C，借出期间关闭会被
+// 拒绝；C 映射的文件由 Rust 包装成带边界检查的切片
+// Sharing memory-mapped files between C and Rust without copying the data: a file Rust mapped is
+// lent to C as a pointer and a length, and closing it is refused while it's lent out; a file C
+// mapped is wrapped by Rust into a bounds-checked slice
+#ifndef MAPPING_H
+#define MAPPING_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#define MAPPING_OK 0
+#define MAPPING_ERROR -1
+// 映射还借给别人，没有关闭
+// The mapping is still lent out and was not closed
+#define MAPPING_BORROWED -2
+
+typedef struct CMapping CMapping;
+typedef struct RustMapping RustMapping;
+
+// 由 C 实现，Rust 调用
+// Implemented in C, called from Rust
+
+// 以只读方式映射整个文件，出错时返回 NULL；空文件没有映射，数据指针是 NULL，长度是 0
+// Maps a whole file read-only, returning NULL on failure; an empty file has no mapping, with a
+// NULL data pointer and a length of 0
+CMapping *c_mmap_open(const char *path);
+const uint8_t *c_mmap_data(const CMapping *mapping);
+size_t c_mmap_len(const CMapping *mapping);
+void c_mmap_close(CMapping *mapping);
+
+// 数据的 FNV-1a 散列，C 直接读取映射的内存
+// The FNV-1a hash of the data, C reads the mapped memory directly
+uint64_t c_checksum(const uint8_t *data, size_t len);
+// 让 Rust 映射 path，借来读取它的散列，借出期间试着关闭一次（应当被拒绝），归还之后再关闭。成功时返回
+// MAPPING_OK 并写入散列
+// Has Rust map path, borrows it to read its hash, tries closing it once while it's lent out
+// (which must be refused) and closes it after giving it back. Returns MAPPING_OK and writes the
+// hash on success
+int32_t c_read_rust_mapping(const char *path, uint64_t *checksum);
+
+// 由 Rust 实现，C 调用
+// Implemented in Rust, called from C
+
+RustMapping *rust_mmap_open(const char *path);
+// 把映射借给调用方：data 和 len 在 rust_mmap_release 之前一直有效，每次借用都要归还一次
+// Lends the mapping to the caller: data and len stay valid until rust_mmap_release, and every
+// borrow must be given back once
+int32_t rust_mmap_borrow(RustMapping *mapping, const uint8_t **data, size_t *len);
+int32_t rust_mmap_release(RustMapping *mapping);
+// 没有借用时取消映射并释放，返回 MAPPING_OK；还有借用时什么都不做，返回 MAPPING_BORROWED
+// Unmaps and frees the mapping when nothing borrows it, returning MAPPING_OK; does nothing and
+// returns MAPPING_BORROWED while it's still borrowed
+int32_t rust_mmap_close(RustMapping *mapping);
+
+#endif
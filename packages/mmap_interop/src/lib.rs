@@ -0,0 +1,235 @@
+// 这个库演示内存映射的文件在 C 和 Rust 之间不经复制地共享：Rust 用 memmap2 映射的文件以指针加长度借给 C，
+// 借用计数不为 0 时 rust_mmap_close 拒绝取消映射，C 手里的指针不会悬空；C 用 mmap 或文件映射映射的文件
+// 由 CMapping 包装，Rust 拿到的切片借用 CMapping，编译器保证切片活着时映射不会被关闭，越界的访问返回 None
+// This library demonstrates sharing memory-mapped files between C and Rust without copying: a file
+// Rust mapped with memmap2 is lent to C as a pointer and a length, and rust_mmap_close refuses to
+// unmap it while the borrow count isn't 0, so the pointers C holds never dangle; a file C mapped
+// with mmap or a file mapping is wrapped by CMapping, the slices Rust gets borrow the CMapping,
+// so the compiler ensures the mapping can't be closed while a slice is alive, and out-of-bounds
+// accesses return None
+//
+// 映射的内存可能被别的进程通过文件修改，两边都假设示例中的文件在映射期间保持不变
+// Mapped memory may be changed by other processes through the file, both sides assume the files
+// in this example stay unchanged while mapped
+
+use std::ffi::{c_char, CStr, CString};
+use std::fs::File;
+use std::io;
+use std::ops::Range;
+use std::path::Path;
+use std::ptr::{self, NonNull};
+use std::slice;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use memmap2::Mmap;
+
+pub const MAPPING_OK: i32 = 0;
+pub const MAPPING_ERROR: i32 = -1;
+/// The mapping is still lent out and was not closed.
+pub const MAPPING_BORROWED: i32 = -2;
+
+#[repr(C)]
+struct RawCMapping {
+    _opaque: [u8; 0],
+}
+
+extern "C" {
+    fn c_mmap_open(path: *const c_char) -> *mut RawCMapping;
+    fn c_mmap_data(mapping: *const RawCMapping) -> *const u8;
+    fn c_mmap_len(mapping: *const RawCMapping) -> usize;
+    fn c_mmap_close(mapping: *mut RawCMapping);
+    fn c_checksum(data: *const u8, len: usize) -> u64;
+    fn c_read_rust_mapping(path: *const c_char, checksum: *mut u64) -> i32;
+}
+
+fn c_path(path: &Path) -> io::Result<CString> {
+    CString::new(path.to_string_lossy().into_owned())
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))
+}
+
+/// 借给 C 的、由 Rust 映射的文件
+/// A file mapped by Rust to lend to C.
+///
+/// An empty file has no mapping; it lends a dangling, non-NULL pointer with a length of 0.
+pub struct RustMapping {
+    map: Option<Mmap>,
+    borrows: AtomicUsize,
+}
+
+impl RustMapping {
+    pub fn open(path: &Path) -> io::Result<RustMapping> {
+        let file = File::open(path)?;
+        let map = if file.metadata()?.len() == 0 {
+            None
+        } else {
+            // SAFETY: the file is assumed to stay unchanged while mapped, see the top of the file
+            Some(unsafe { Mmap::map(&file)? })
+        };
+        Ok(RustMapping {
+            map,
+            borrows: AtomicUsize::new(0),
+        })
+    }
+
+    pub fn as_slice(&self) -> &[u8] {
+        self.map.as_deref().unwrap_or_default()
+    }
+
+    /// How many borrows C has not given back yet.
+    pub fn borrows(&self) -> usize {
+        self.borrows.load(Ordering::Acquire)
+    }
+}
+
+/// Maps `path` for C, returning NULL on failure; free it with [`rust_mmap_close`].
+///
+/// # Safety
+///
+/// `path` must be NULL or a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn rust_mmap_open(path: *const c_char) -> *mut RustMapping {
+    if path.is_null() {
+        return ptr::null_mut();
+    }
+    let Ok(path) = CStr::from_ptr(path).to_str() else {
+        return ptr::null_mut();
+    };
+    match RustMapping::open(Path::new(path)) {
+        Ok(mapping) => Box::into_raw(Box::new(mapping)),
+        Err(_) => ptr::null_mut(),
+    }
+}
+
+/// Lends the mapped bytes to C until the matching [`rust_mmap_release`].
+///
+/// # Safety
+///
+/// `mapping` must be NULL or come from [`rust_mmap_open`] and not be closed yet, and `data` and
+/// `len` must be NULL or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn rust_mmap_borrow(
+    mapping: *mut RustMapping,
+    data: *mut *const u8,
+    len: *mut usize,
+) -> i32 {
+    let (Some(mapping), false, false) = (mapping.as_ref(), data.is_null(), len.is_null()) else {
+        return MAPPING_ERROR;
+    };
+    mapping.borrows.fetch_add(1, Ordering::AcqRel);
+    let bytes = mapping.as_slice();
+    *data = bytes.as_ptr();
+    *len = bytes.len();
+    MAPPING_OK
+}
+
+/// Gives back one borrow from [`rust_mmap_borrow`]; fails when nothing is borrowed.
+///
+/// # Safety
+///
+/// `mapping` must be NULL or come from [`rust_mmap_open`] and not be closed yet.
+#[no_mangle]
+pub unsafe extern "C" fn rust_mmap_release(mapping: *mut RustMapping) -> i32 {
+    let Some(mapping) = mapping.as_ref() else {
+        return MAPPING_ERROR;
+    };
+    match mapping
+        .borrows
+        .fetch_update(Ordering::AcqRel, Ordering::Acquire, |n| n.checked_sub(1))
+    {
+        Ok(_) => MAPPING_OK,
+        Err(_) => MAPPING_ERROR,
+    }
+}
+
+/// Unmaps and frees a mapping nothing borrows, or returns [`MAPPING_BORROWED`] and leaves it
+/// untouched while C still has borrows out.
+///
+/// # Safety
+///
+/// `mapping` must be NULL or come from [`rust_mmap_open`] and not be closed yet; once this
+/// returns [`MAPPING_OK`] it must not be used again.
+#[no_mangle]
+pub unsafe extern "C" fn rust_mmap_close(mapping: *mut RustMapping) -> i32 {
+    let Some(borrowed) = mapping.as_ref() else {
+        return MAPPING_ERROR;
+    };
+    if borrowed.borrows() != 0 {
+        return MAPPING_BORROWED;
+    }
+    drop(Box::from_raw(mapping));
+    MAPPING_OK
+}
+
+/// The FNV-1a hash C computes over `data`, reading it in place.
+pub fn checksum(data: &[u8]) -> u64 {
+    unsafe { c_checksum(data.as_ptr(), data.len()) }
+}
+
+/// Has C map `path` through Rust and hash it without copying, see `c_read_rust_mapping`.
+pub fn c_hash_rust_mapping(path: &Path) -> io::Result<u64> {
+    let path = c_path(path)?;
+    let mut hash = 0;
+    match unsafe { c_read_rust_mapping(path.as_ptr(), &mut hash) } {
+        MAPPING_OK => Ok(hash),
+        code => Err(io::Error::other(format!("C failed with {}", code))),
+    }
+}
+
+/// 由 C 映射的文件
+/// A file mapped read-only by C, unmapped by C when dropped.
+///
+/// Every slice borrows the mapping, so it can't be dropped while one is still in use:
+///
+/// ```compile_fail
+/// # use mmap_interop::CMapping;
+/// let mapping = CMapping::open("Cargo.toml".as_ref()).unwrap();
+/// let bytes = mapping.as_slice();
+/// drop(mapping);
+/// println!("{}", bytes[0]);
+/// ```
+pub struct CMapping {
+    raw: NonNull<RawCMapping>,
+}
+
+// C 的映射只读，创建之后不会再改变
+// C's mapping is read-only and never changes after creation
+unsafe impl Send for CMapping {}
+unsafe impl Sync for CMapping {}
+
+impl CMapping {
+    pub fn open(path: &Path) -> io::Result<CMapping> {
+        let path = c_path(path)?;
+        NonNull::new(unsafe { c_mmap_open(path.as_ptr()) })
+            .map(|raw| CMapping { raw })
+            .ok_or_else(io::Error::last_os_error)
+    }
+
+    pub fn len(&self) -> usize {
+        unsafe { c_mmap_len(self.raw.as_ptr()) }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The whole mapping, valid for as long as the `CMapping` is borrowed.
+    pub fn as_slice(&self) -> &[u8] {
+        let data = unsafe { c_mmap_data(self.raw.as_ptr()) };
+        if data.is_null() {
+            return &[];
+        }
+        // SAFETY: C mapped len bytes at data read-only, and they stay mapped until drop
+        unsafe { slice::from_raw_parts(data, self.len()) }
+    }
+
+    /// The bytes in `range`, or `None` when it's out of bounds.
+    pub fn get(&self, range: Range<usize>) -> Option<&[u8]> {
+        self.as_slice().get(range)
+    }
+}
+
+impl Drop for CMapping {
+    fn drop(&mut self) {
+        unsafe { c_mmap_close(self.raw.as_ptr()) }
+    }
+}
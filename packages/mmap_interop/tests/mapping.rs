@@ -0,0 +1,89 @@
+// 内存映射共享的测试：C 读到的 Rust 映射和 Rust 读到的 C 映射都和文件内容一致；C 还借着 Rust 的映射时
+// rust_mmap_close 被拒绝，映射依然可读，全部归还之后才能关闭；C 映射的切片访问越界时返回 None
+// Tests for sharing memory mappings: the Rust mapping C reads and the C mapping Rust reads both
+// match the file; rust_mmap_close is refused while C still borrows the Rust mapping, which stays
+// readable, and only succeeds once everything was given back; out-of-bounds slices of the C
+// mapping return None
+
+use std::fs;
+use std::path::PathBuf;
+use std::ptr;
+
+use mmap_interop::{
+    c_hash_rust_mapping, checksum, rust_mmap_borrow, rust_mmap_close, rust_mmap_open,
+    rust_mmap_release, CMapping, RustMapping, MAPPING_BORROWED, MAPPING_ERROR, MAPPING_OK,
+};
+
+fn temp_file(name: &str, contents: &[u8]) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("mmap_interop_{}_{}", std::process::id(), name));
+    fs::write(&path, contents).unwrap();
+    path
+}
+
+fn contents() -> Vec<u8> {
+    (0..100_000u32).map(|i| (i % 251) as u8).collect()
+}
+
+#[test]
+fn c_reads_the_rust_mapping_in_place() {
+    let contents = contents();
+    let path = temp_file("rust", &contents);
+    assert_eq!(c_hash_rust_mapping(&path).unwrap(), checksum(&contents));
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn rust_reads_the_c_mapping_in_place() {
+    let contents = contents();
+    let path = temp_file("c", &contents);
+    let mapping = CMapping::open(&path).unwrap();
+    assert_eq!(mapping.len(), contents.len());
+    assert_eq!(mapping.as_slice(), &contents[..]);
+    assert_eq!(mapping.get(10..20), Some(&contents[10..20]));
+    assert_eq!(mapping.get(contents.len() - 1..contents.len() + 1), None);
+    drop(mapping);
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn closing_while_borrowed_is_refused() {
+    let contents = contents();
+    let path = temp_file("borrowed", &contents);
+    let c_path = std::ffi::CString::new(path.to_str().unwrap()).unwrap();
+    unsafe {
+        let mapping = rust_mmap_open(c_path.as_ptr());
+        assert!(!mapping.is_null());
+        let (mut data, mut len) = (ptr::null(), 0);
+        assert_eq!(rust_mmap_borrow(mapping, &mut data, &mut len), MAPPING_OK);
+        assert_eq!(rust_mmap_borrow(mapping, &mut data, &mut len), MAPPING_OK);
+        assert_eq!((*mapping).borrows(), 2);
+
+        assert_eq!(rust_mmap_close(mapping), MAPPING_BORROWED);
+        assert_eq!(std::slice::from_raw_parts(data, len), &contents[..]);
+        assert_eq!(rust_mmap_release(mapping), MAPPING_OK);
+        assert_eq!(rust_mmap_close(mapping), MAPPING_BORROWED);
+        assert_eq!(rust_mmap_release(mapping), MAPPING_OK);
+        assert_eq!(rust_mmap_release(mapping), MAPPING_ERROR);
+        assert_eq!(rust_mmap_close(mapping), MAPPING_OK);
+    }
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn empty_files_map_to_empty_slices() {
+    let path = temp_file("empty", b"");
+    assert!(CMapping::open(&path).unwrap().is_empty());
+    assert!(RustMapping::open(&path).unwrap().as_slice().is_empty());
+    assert_eq!(c_hash_rust_mapping(&path).unwrap(), checksum(b""));
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn missing_files_fail_to_map() {
+    let path = std::env::temp_dir().join("mmap_interop_missing_file");
+    assert!(CMapping::open(&path).is_err());
+    assert!(RustMapping::open(&path).is_err());
+    assert!(c_hash_rust_mapping(&path).is_err());
+    assert!(unsafe { rust_mmap_open(ptr::null()) }.is_null());
+    assert_eq!(unsafe { rust_mmap_close(ptr::null_mut()) }, MAPPING_ERROR);
+}
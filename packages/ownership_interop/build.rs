@@ -0,0 +1,12 @@
+// 这是我们的构建脚本，编译替 Rust 保管 Widget 和在线程间共享 Shared 的 C 代码
+// This is our build script, it compiles the C code holding on to Widgets for Rust and sharing
+// Shared between threads
+
+fn main() {
+    cc::Build::new()
+        .file("c/stash.c")
+        .file("c/shared.c")
+        .std("c11")
+        .compile("ownership");
+    println!("cargo::rerun-if-changed=c");
+}
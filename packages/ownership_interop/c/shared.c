@@ -0,0 +1,92 @@
+// 这个文件把 Shared 交给几个 C 线程：每个线程拿到自己克隆的引用，计数后再释放
+// This file hands Shared to a few C threads: each gets a reference cloned for it, counts and then
+// releases it
+#include <stdlib.h>
+#include "shared.h"
+
+#ifdef _WIN32
+#include <windows.h>
+#else
+#include <pthread.h>
+#endif
+
+typedef struct Job
+{
+    const Shared *shared;
+    int increments;
+} Job;
+
+// 线程拥有 job 中的引用，返回前释放它
+// The thread owns the reference in its job and releases it before returning
+#ifdef _WIN32
+static DWORD WINAPI count(LPVOID arg)
+#else
+static void *count(void *arg)
+#endif
+{
+    Job *job = arg;
+    for (int i = 0; i < job->increments; i++)
+    {
+        shared_add(job->shared, 1);
+    }
+    shared_release(job->shared);
+    job->shared = NULL;
+    return 0;
+}
+
+// 启动 threads 个线程，每个给计数加 increments 次；全部结束后返回 0，出错时返回 -1
+// Starts threads threads that each add to the count increments times, returning 0 once all are
+// done or -1 on failure
+int c_share_across_threads(const Shared *shared, int threads, int increments)
+{
+    if (threads <= 0)
+    {
+        return 0;
+    }
+    Job *jobs = calloc((size_t)threads, sizeof(Job));
+#ifdef _WIN32
+    HANDLE *ids = calloc((size_t)threads, sizeof(HANDLE));
+#else
+    pthread_t *ids = calloc((size_t)threads, sizeof(pthread_t));
+#endif
+    if (jobs == NULL || ids == NULL)
+    {
+        free(jobs);
+        free(ids);
+        return -1;
+    }
+
+    int started = 0;
+    int status = 0;
+    for (; started < threads; started++)
+    {
+        // 克隆发生在启动线程之前，线程运行时引用一定有效
+        // The clone happens before the thread starts, so its reference is valid while it runs
+        jobs[started].shared = shared_clone(shared);
+        jobs[started].increments = increments;
+#ifdef _WIN32
+        ids[started] = CreateThread(NULL, 0, count, &jobs[started], 0, NULL);
+        int failed = ids[started] == NULL;
+#else
+        int failed = pthread_create(&ids[started], NULL, count, &jobs[started]) != 0;
+#endif
+        if (failed)
+        {
+            shared_release(jobs[started].shared);
+            status = -1;
+            break;
+        }
+    }
+    for (int i = 0; i < started; i++)
+    {
+#ifdef _WIN32
+        WaitForSingleObject(ids[i], INFINITE);
+        CloseHandle(ids[i]);
+#else
+        pthread_join(ids[i], NULL);
+#endif
+    }
+    free(jobs);
+    free(ids);
+    return status;
+}
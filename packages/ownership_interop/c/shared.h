@@ -0,0 +1,23 @@
+// C 端的 Shared：引用计数的对象，每个持有者都有自己的一份引用，用完之后释放
+// Shared on the C side: a reference-counted object, every holder owns a reference of its own and
+// releases it when done
+#ifndef SHARED_H
+#define SHARED_H
+
+#include <stdint.h>
+
+typedef struct Shared Shared;
+
+// 由 C 实现，Rust 调用
+// Implemented in C, called from Rust
+int c_share_across_threads(const Shared *shared, int threads, int increments);
+
+// 由 Rust 实现，C 调用
+// Implemented in Rust, called from C
+const Shared *shared_new(void);
+const Shared *shared_clone(const Shared *shared);
+void shared_release(const Shared *shared);
+int64_t shared_add(const Shared *shared, int64_t delta);
+int64_t shared_value(const Shared *shared);
+
+#endif
@@ -0,0 +1,49 @@
+// 这个文件替 Rust 保管 Widget，之后再交还；后两个函数故意犯下裸指针上会是未定义行为的错误
+// This file holds on to Widgets for Rust and hands them back later; the last two functions make
+// on purpose the mistakes that would be undefined behaviour with raw pointers
+#include <stddef.h>
+#include "stash.h"
+
+void c_stash_keep(Stash *stash, Widget *widget)
+{
+    stash->widget = widget;
+}
+
+// 交还之后 C 不再拥有这个指针，清空它免得再用
+// Once handed back C no longer owns the pointer, clearing it keeps it from being used again
+Widget *c_stash_give_back(Stash *stash)
+{
+    Widget *widget = stash->widget;
+    stash->widget = NULL;
+    return widget;
+}
+
+void c_stash_keep_handle(Stash *stash, WidgetHandle handle)
+{
+    stash->handle = handle;
+}
+
+WidgetHandle c_stash_give_back_handle(Stash *stash)
+{
+    WidgetHandle handle = stash->handle;
+    stash->handle = 0;
+    return handle;
+}
+
+// 同一个句柄释放两次，返回成功的次数；只有第一次会成功
+// Frees the same handle twice and returns how many times that succeeded, only the first does
+int c_free_handle_twice(WidgetHandle handle)
+{
+    int freed = 0;
+    freed += widget_handle_free(handle);
+    freed += widget_handle_free(handle);
+    return freed;
+}
+
+// 释放之后继续使用句柄，Rust 拒绝而不是读到已经释放的内存
+// Keeps using a handle after freeing it, which Rust rejects instead of reading freed memory
+bool c_touch_after_free(WidgetHandle handle, uint32_t *touches)
+{
+    widget_handle_free(handle);
+    return widget_handle_touch(handle, touches);
+}
@@ -0,0 +1,42 @@
+// C 端的 Widget：裸指针的版本只有前向声明，C 看不到内部；句柄的版本只是一个整数
+// Widgets on the C side: the raw pointer version is only forward declared, so C can't see inside,
+// and the handle version is just an integer
+#ifndef STASH_H
+#define STASH_H
+
+#include <stdbool.h>
+#include <stdint.h>
+
+typedef struct Widget Widget;
+
+// 0 从来不是有效的句柄
+// 0 is never a valid handle
+typedef uint64_t WidgetHandle;
+
+// C 替 Rust 保管的东西，交还给 Rust 之后就清空
+// What C holds on to for Rust, cleared once it is handed back
+typedef struct Stash
+{
+    Widget *widget;
+    WidgetHandle handle;
+} Stash;
+
+// 由 C 实现，Rust 调用
+// Implemented in C, called from Rust
+void c_stash_keep(Stash *stash, Widget *widget);
+Widget *c_stash_give_back(Stash *stash);
+void c_stash_keep_handle(Stash *stash, WidgetHandle handle);
+WidgetHandle c_stash_give_back_handle(Stash *stash);
+int c_free_handle_twice(WidgetHandle handle);
+bool c_touch_after_free(WidgetHandle handle, uint32_t *touches);
+
+// 由 Rust 实现，C 调用
+// Implemented in Rust, called from C
+Widget *widget_new(uint32_t id);
+uint32_t widget_touch(Widget *widget);
+void widget_free(Widget *widget);
+WidgetHandle widget_handle_new(uint32_t id);
+bool widget_handle_touch(WidgetHandle handle, uint32_t *touches);
+bool widget_handle_free(WidgetHandle handle);
+
+#endif
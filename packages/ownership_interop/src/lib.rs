@@ -0,0 +1,215 @@
+// 这个库演示把 Box<T> 的所有权交给 C：Box::into_raw 得到 *mut T，C 保管它，之后交还给 Rust，
+// 由 Box::from_raw 重建并释放；裸指针上的重复释放和释放后使用是未定义行为，句柄注册表把它们变成
+// 可以检测的错误；shared 模块用 Arc 在多个持有者之间共享所有权
+// This library demonstrates handing ownership of a Box<T> to C: Box::into_raw gives a *mut T,
+// C holds on to it and later hands it back to Rust, where Box::from_raw rebuilds and drops it;
+// double frees and uses after free are undefined behaviour with raw pointers, a handle registry
+// turns them into errors that can be detected; the shared module shares ownership among several
+// holders with Arc
+
+mod shared;
+
+pub use shared::{
+    share_across_c_threads, shared_add, shared_clone, shared_new, shared_release, shared_value,
+    strong_count, Shared,
+};
+
+use std::collections::BTreeMap;
+use std::ptr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+/// An object owned by Rust and handed to C, either as a raw pointer or as a handle.
+///
+/// The layout is private to Rust; C only sees the forward declaration in c/stash.h.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Widget {
+    id: u32,
+    touches: u32,
+}
+
+impl Widget {
+    pub fn new(id: u32) -> Self {
+        Widget { id, touches: 0 }
+    }
+
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    /// How often the widget was touched.
+    pub fn touches(&self) -> u32 {
+        self.touches
+    }
+
+    fn touch(&mut self) -> u32 {
+        self.touches += 1;
+        self.touches
+    }
+}
+
+/// A widget handed to C by handle, 0 is never a valid one.
+pub type WidgetHandle = u64;
+
+/// What C holds on to for Rust, laid out like `struct Stash` in c/stash.h.
+///
+/// Dropping it reclaims a widget C still holds, so nothing leaks if Rust never asks for it back.
+#[repr(C)]
+#[derive(Debug)]
+pub struct Stash {
+    widget: *mut Widget,
+    handle: WidgetHandle,
+}
+
+// C 只存放指针，从不访问 Widget 的内部，所以不需要 #[repr(C)]
+// C only stores the pointers and never looks inside a Widget, so it needs no #[repr(C)]
+#[allow(improper_ctypes)]
+extern "C" {
+    fn c_stash_keep(stash: *mut Stash, widget: *mut Widget);
+    fn c_stash_give_back(stash: *mut Stash) -> *mut Widget;
+    fn c_stash_keep_handle(stash: *mut Stash, handle: WidgetHandle);
+    fn c_stash_give_back_handle(stash: *mut Stash) -> WidgetHandle;
+    fn c_free_handle_twice(handle: WidgetHandle) -> i32;
+    fn c_touch_after_free(handle: WidgetHandle, touches: *mut u32) -> bool;
+}
+
+// ==== 裸指针：所有权随指针转移，由调用方保证只释放一次 ====
+// ==== Raw pointers: ownership moves with the pointer, the caller must free it exactly once ====
+
+/// Creates a widget and hands its ownership to the caller, to be released with `widget_free`.
+#[no_mangle]
+pub extern "C" fn widget_new(id: u32) -> *mut Widget {
+    Box::into_raw(Box::new(Widget::new(id)))
+}
+
+/// Touches the widget and returns how often it has been touched; NULL returns 0.
+///
+/// # Safety
+///
+/// `widget` must be NULL or come from `widget_new` and not have been freed. Touching a freed
+/// widget reads memory that may already belong to something else.
+#[no_mangle]
+pub unsafe extern "C" fn widget_touch(widget: *mut Widget) -> u32 {
+    match widget.as_mut() {
+        Some(widget) => widget.touch(),
+        None => 0,
+    }
+}
+
+/// Takes the ownership of the widget back and destroys it. Passing NULL is a no-op.
+///
+/// # Safety
+///
+/// `widget` must be NULL or come from `widget_new`, and must not be used again afterwards:
+/// freeing it a second time corrupts the heap.
+#[no_mangle]
+pub unsafe extern "C" fn widget_free(widget: *mut Widget) {
+    if !widget.is_null() {
+        drop(Box::from_raw(widget));
+    }
+}
+
+// ==== 句柄注册表：Rust 一直持有对象，C 只拿到一个不会重复使用的编号 ====
+// ==== A handle registry: Rust keeps the objects, C only gets a number that is never reused ====
+
+static WIDGETS: Mutex<BTreeMap<WidgetHandle, Widget>> = Mutex::new(BTreeMap::new());
+static NEXT_HANDLE: AtomicU64 = AtomicU64::new(1);
+
+fn widgets() -> MutexGuard<'static, BTreeMap<WidgetHandle, Widget>> {
+    WIDGETS.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Creates a widget kept by the library and returns its handle, to be released with
+/// `widget_handle_free`.
+#[no_mangle]
+pub extern "C" fn widget_handle_new(id: u32) -> WidgetHandle {
+    let handle = NEXT_HANDLE.fetch_add(1, Ordering::Relaxed);
+    widgets().insert(handle, Widget::new(id));
+    handle
+}
+
+/// Touches the widget and stores how often it has been touched in `touches`.
+///
+/// Returns false, leaving `touches` untouched, when `handle` is unknown or already freed, or
+/// `touches` is NULL.
+///
+/// # Safety
+///
+/// `touches` must be NULL or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn widget_handle_touch(handle: WidgetHandle, touches: *mut u32) -> bool {
+    if touches.is_null() {
+        return false;
+    }
+    match widgets().get_mut(&handle) {
+        Some(widget) => {
+            touches.write(widget.touch());
+            true
+        }
+        None => false,
+    }
+}
+
+/// Destroys the widget, returning false when `handle` is unknown or already freed.
+///
+/// Any handle value is safe to pass: since handles are never reused, freeing one twice can't
+/// destroy a different widget.
+#[no_mangle]
+pub extern "C" fn widget_handle_free(handle: WidgetHandle) -> bool {
+    widgets().remove(&handle).is_some()
+}
+
+impl Stash {
+    pub fn new() -> Self {
+        Stash {
+            widget: ptr::null_mut(),
+            handle: 0,
+        }
+    }
+
+    /// Hands the widget to C, which keeps the raw pointer.
+    pub fn keep(&mut self, widget: Box<Widget>) {
+        unsafe { c_stash_keep(self, Box::into_raw(widget)) }
+    }
+
+    /// Asks C for the widget back and takes its ownership again; None if C holds none.
+    pub fn give_back(&mut self) -> Option<Box<Widget>> {
+        let widget = unsafe { c_stash_give_back(self) };
+        // C 清空了自己的副本，这个指针只会在这里重建一次
+        // C cleared its copy, so the pointer is only rebuilt into a Box once, here
+        (!widget.is_null()).then(|| unsafe { Box::from_raw(widget) })
+    }
+
+    /// Hands the handle to C.
+    pub fn keep_handle(&mut self, handle: WidgetHandle) {
+        unsafe { c_stash_keep_handle(self, handle) }
+    }
+
+    /// Asks C for the handle back, 0 if C holds none.
+    pub fn give_back_handle(&mut self) -> WidgetHandle {
+        unsafe { c_stash_give_back_handle(self) }
+    }
+}
+
+impl Default for Stash {
+    fn default() -> Self {
+        Stash::new()
+    }
+}
+
+impl Drop for Stash {
+    fn drop(&mut self) {
+        drop(self.give_back());
+    }
+}
+
+/// Lets C free `handle` twice, returning how many of the frees succeeded.
+pub fn free_handle_twice(handle: WidgetHandle) -> i32 {
+    unsafe { c_free_handle_twice(handle) }
+}
+
+/// Lets C free `handle` and then touch it, returning the touch count if that succeeded.
+pub fn touch_after_free(handle: WidgetHandle) -> Option<u32> {
+    let mut touches = 0;
+    unsafe { c_touch_after_free(handle, &mut touches) }.then_some(touches)
+}
@@ -0,0 +1,122 @@
+// 跨 FFI 的共享所有权：Arc::into_raw 得到的指针就是句柄，shared_clone 增加强引用计数，
+// shared_release 减少它，最后一份引用释放时对象被销毁；C 线程和 Rust 线程可以各自持有引用
+// Shared ownership across the FFI: the pointer from Arc::into_raw is the handle, shared_clone
+// increments the strong count and shared_release decrements it, and the object is destroyed when
+// the last reference goes; C threads and Rust threads may each hold references
+
+use std::mem::ManuallyDrop;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::Arc;
+
+/// A counter shared between any number of owners, in C and in Rust.
+///
+/// The layout is private to Rust; C only sees the forward declaration in c/shared.h. Each
+/// `Shared *` a caller holds is one strong reference, released with `shared_release`.
+#[derive(Debug, Default)]
+pub struct Shared {
+    value: AtomicI64,
+}
+
+// C 只传递指针，从不访问 Shared 的内部
+// C only passes the pointer around and never looks inside a Shared
+#[allow(improper_ctypes)]
+extern "C" {
+    fn c_share_across_threads(shared: *const Shared, threads: i32, increments: i32) -> i32;
+}
+
+// 借用句柄背后的 Arc 而不改变计数
+// Borrows the Arc behind a handle without changing the count
+unsafe fn borrow(shared: *const Shared) -> ManuallyDrop<Arc<Shared>> {
+    ManuallyDrop::new(Arc::from_raw(shared))
+}
+
+/// Creates a counter at 0 and returns the caller's reference to it.
+#[no_mangle]
+pub extern "C" fn shared_new() -> *const Shared {
+    Arc::into_raw(Arc::new(Shared::default()))
+}
+
+/// Takes another reference to the counter for a new owner, such as another thread, and returns
+/// it. The pointer is the same, but each reference must be released on its own. NULL returns
+/// NULL.
+///
+/// # Safety
+///
+/// `shared` must be NULL or a reference from `shared_new` or `shared_clone` that the caller
+/// still holds.
+#[no_mangle]
+pub unsafe extern "C" fn shared_clone(shared: *const Shared) -> *const Shared {
+    if !shared.is_null() {
+        Arc::increment_strong_count(shared);
+    }
+    shared
+}
+
+/// Releases one reference, destroying the counter when it was the last. Passing NULL is a
+/// no-op.
+///
+/// # Safety
+///
+/// `shared` must be NULL or a reference the caller holds, which must not be used again
+/// afterwards, although other references to the same counter remain valid.
+#[no_mangle]
+pub unsafe extern "C" fn shared_release(shared: *const Shared) {
+    if !shared.is_null() {
+        drop(Arc::from_raw(shared));
+    }
+}
+
+/// Adds `delta` to the counter and returns the new value, wrapping on overflow; NULL returns 0.
+///
+/// Safe to call from several threads at once.
+///
+/// # Safety
+///
+/// `shared` must be NULL or a reference the caller holds.
+#[no_mangle]
+pub unsafe extern "C" fn shared_add(shared: *const Shared, delta: i64) -> i64 {
+    match shared.as_ref() {
+        Some(shared) => shared
+            .value
+            .fetch_add(delta, Ordering::Relaxed)
+            .wrapping_add(delta),
+        None => 0,
+    }
+}
+
+/// Returns the counter's value; NULL returns 0.
+///
+/// # Safety
+///
+/// `shared` must be NULL or a reference the caller holds.
+#[no_mangle]
+pub unsafe extern "C" fn shared_value(shared: *const Shared) -> i64 {
+    shared
+        .as_ref()
+        .map_or(0, |shared| shared.value.load(Ordering::Relaxed))
+}
+
+/// How many references to the counter exist, for tests; NULL returns 0.
+///
+/// # Safety
+///
+/// `shared` must be NULL or a reference the caller holds.
+pub unsafe fn strong_count(shared: *const Shared) -> usize {
+    if shared.is_null() {
+        return 0;
+    }
+    Arc::strong_count(&borrow(shared))
+}
+
+/// Lets `threads` C threads each take a reference to the counter, add to it `increments` times
+/// and release their reference again, returning once all of them are done. Returns false if a
+/// thread could not be started.
+///
+/// # Safety
+///
+/// `shared` must be a reference the caller holds.
+pub unsafe fn share_across_c_threads(shared: *const Shared, threads: i32, increments: i32) -> bool {
+    // 线程启动失败时 C 已经释放了为它克隆的引用
+    // When a thread fails to start C has already released the reference cloned for it
+    c_share_across_threads(shared, threads, increments) == 0
+}
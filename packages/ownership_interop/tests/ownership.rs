@@ -0,0 +1,93 @@
+// Box 的所有权在 Rust 和 C 之间往返，以及句柄注册表如何拦下重复释放和释放后使用
+// A Box's ownership travelling between Rust and C and back, and how the handle registry stops
+// double frees and uses after free
+
+use std::ptr;
+
+use ownership_interop::{
+    free_handle_twice, touch_after_free, widget_free, widget_handle_free, widget_handle_new,
+    widget_handle_touch, widget_new, widget_touch, Stash, Widget,
+};
+
+fn touch(handle: u64) -> Option<u32> {
+    let mut touches = 0;
+    unsafe { widget_handle_touch(handle, &mut touches) }.then_some(touches)
+}
+
+#[test]
+fn a_box_round_trips_through_c() {
+    let mut stash = Stash::new();
+    stash.keep(Box::new(Widget::new(7)));
+    let widget = stash.give_back().expect("C handed the widget back");
+    assert_eq!(widget.id(), 7);
+    // 交还之后 C 已经清空了自己的副本
+    // After handing it back C has cleared its copy
+    assert!(stash.give_back().is_none());
+}
+
+#[test]
+fn dropping_the_stash_reclaims_the_widget() {
+    let mut stash = Stash::new();
+    stash.keep(Box::new(Widget::new(1)));
+    drop(stash);
+}
+
+#[test]
+fn raw_pointers_keep_the_state_between_calls() {
+    let widget = widget_new(3);
+    unsafe {
+        assert_eq!(widget_touch(widget), 1);
+        assert_eq!(widget_touch(widget), 2);
+        assert_eq!(widget_touch(ptr::null_mut()), 0);
+        widget_free(widget);
+        widget_free(ptr::null_mut());
+    }
+}
+
+#[test]
+fn handles_round_trip_through_c() {
+    let mut stash = Stash::new();
+    let handle = widget_handle_new(5);
+    stash.keep_handle(handle);
+    assert_eq!(touch(handle), Some(1));
+    assert_eq!(stash.give_back_handle(), handle);
+    assert_eq!(stash.give_back_handle(), 0);
+    assert!(widget_handle_free(handle));
+}
+
+#[test]
+fn double_frees_are_detected() {
+    let handle = widget_handle_new(9);
+    assert_eq!(free_handle_twice(handle), 1);
+    assert!(!widget_handle_free(handle));
+}
+
+#[test]
+fn uses_after_free_are_detected() {
+    let handle = widget_handle_new(11);
+    assert_eq!(touch(handle), Some(1));
+    assert_eq!(touch_after_free(handle), None);
+    assert_eq!(touch(handle), None);
+}
+
+#[test]
+fn freed_handles_are_never_reused() {
+    let stale = widget_handle_new(1);
+    assert!(widget_handle_free(stale));
+    let fresh = widget_handle_new(2);
+    assert_ne!(fresh, stale);
+    // 旧句柄不会误伤新的 Widget
+    // The stale handle can't hit the new widget
+    assert!(!widget_handle_free(stale));
+    assert_eq!(touch(fresh), Some(1));
+    assert!(widget_handle_free(fresh));
+}
+
+#[test]
+fn invalid_arguments_are_rejected() {
+    assert!(!widget_handle_free(0));
+    assert_eq!(touch(0), None);
+    let handle = widget_handle_new(4);
+    assert!(!unsafe { widget_handle_touch(handle, ptr::null_mut()) });
+    assert!(widget_handle_free(handle));
+}
@@ -0,0 +1,102 @@
+// 跨 FFI 的 Arc：克隆和释放改变强引用计数，C 线程和 Rust 线程各自持有引用
+// An Arc across the FFI: clones and releases change the strong count, and C threads and Rust
+// threads each hold references of their own
+
+use std::ptr;
+use std::thread;
+
+use ownership_interop::{
+    share_across_c_threads, shared_add, shared_clone, shared_new, shared_release, shared_value,
+    strong_count, Shared,
+};
+
+// 裸指针不是 Send，把引用包起来交给 Rust 线程
+// Raw pointers are not Send, so references travel to Rust threads wrapped in this
+struct Reference(*const Shared);
+
+unsafe impl Send for Reference {}
+
+#[test]
+fn clones_and_releases_change_the_count() {
+    let shared = shared_new();
+    unsafe {
+        assert_eq!(strong_count(shared), 1);
+        let clone = shared_clone(shared);
+        assert_eq!(clone, shared);
+        assert_eq!(strong_count(shared), 2);
+
+        assert_eq!(shared_add(clone, 5), 5);
+        shared_release(clone);
+        assert_eq!(strong_count(shared), 1);
+        // 另一份引用释放之后，对象和它的值都还在
+        // With the other reference released the object and its value are still there
+        assert_eq!(shared_value(shared), 5);
+        shared_release(shared);
+    }
+}
+
+#[test]
+fn null_is_ignored() {
+    unsafe {
+        assert!(shared_clone(ptr::null()).is_null());
+        assert_eq!(shared_add(ptr::null(), 1), 0);
+        assert_eq!(shared_value(ptr::null()), 0);
+        assert_eq!(strong_count(ptr::null()), 0);
+        shared_release(ptr::null());
+    }
+}
+
+#[test]
+fn rust_threads_hold_references_of_their_own() {
+    let shared = shared_new();
+    let workers: Vec<_> = (0..4)
+        .map(|_| {
+            let reference = Reference(unsafe { shared_clone(shared) });
+            thread::spawn(move || {
+                let reference = reference;
+                for _ in 0..1000 {
+                    unsafe { shared_add(reference.0, 1) };
+                }
+                unsafe { shared_release(reference.0) };
+            })
+        })
+        .collect();
+    for worker in workers {
+        worker.join().unwrap();
+    }
+    unsafe {
+        assert_eq!(shared_value(shared), 4000);
+        assert_eq!(strong_count(shared), 1);
+        shared_release(shared);
+    }
+}
+
+#[test]
+fn c_threads_hold_references_of_their_own() {
+    let shared = shared_new();
+    unsafe {
+        assert!(share_across_c_threads(shared, 4, 1000));
+        assert_eq!(shared_value(shared), 4000);
+        assert_eq!(strong_count(shared), 1);
+        shared_release(shared);
+    }
+}
+
+#[test]
+fn the_last_owner_may_be_another_thread() {
+    let shared = shared_new();
+    let reference = Reference(unsafe { shared_clone(shared) });
+    // 主线程先释放自己的引用，对象由工作线程最后销毁
+    // The main thread releases its reference first, so the worker thread destroys the object
+    unsafe { shared_release(shared) };
+    thread::spawn(move || {
+        let reference = reference;
+        unsafe {
+            assert_eq!(strong_count(reference.0), 1);
+            assert_eq!(shared_add(reference.0, 2), 2);
+            shared_release(reference.0);
+        }
+    })
+    .join()
+    .unwrap();
+}
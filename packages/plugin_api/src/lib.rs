@@ -0,0 +1,57 @@
+// 插件 ABI：宿主和插件之间只通过这里的 #[repr(C)] 类型通信，C 端的定义在 include/plugin_api.h
+// The plugin ABI: the host and its plugins only talk through the #[repr(C)] types here, the C
+// definitions are in include/plugin_api.h
+
+use std::ffi::{c_char, c_int, c_void};
+use std::mem;
+
+/// Bumped whenever a type in this crate changes layout or meaning.
+pub const PLUGIN_API_VERSION: u32 = 1;
+
+/// The symbol every plugin exports, with the [`PluginRegisterFn`] signature.
+pub const PLUGIN_REGISTER_SYMBOL: &str = "plugin_register";
+
+/// `int plugin_register(const PluginHost *host)`, returns 0 once the plugin has registered.
+pub type PluginRegisterFn = unsafe extern "C" fn(host: *const PluginHost) -> c_int;
+
+/// Writes a NUL-terminated message to the host's log.
+pub type PluginLogFn = extern "C" fn(host_ctx: *mut c_void, message: *const c_char);
+
+/// Hands a vtable to the host, which copies it; returns 0 on success.
+pub type PluginRegisterVTableFn =
+    extern "C" fn(host_ctx: *mut c_void, vtable: *const PluginVTable) -> c_int;
+
+/// Computes a result from `a` and `b` into `out`, returns 0 on success.
+pub type PluginComputeFn =
+    extern "C" fn(user_data: *mut c_void, a: c_int, b: c_int, out: *mut c_int) -> c_int;
+
+/// Releases `user_data` before the host unloads the plugin.
+pub type PluginDestroyFn = Option<extern "C" fn(user_data: *mut c_void)>;
+
+/// What the host offers a plugin during `plugin_register`; only valid for that call.
+#[repr(C)]
+pub struct PluginHost {
+    pub api_version: u32,
+    pub ctx: *mut c_void,
+    pub log: PluginLogFn,
+    pub register_vtable: PluginRegisterVTableFn,
+}
+
+/// The operations a plugin implements. `name` must stay valid while the plugin is loaded.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct PluginVTable {
+    pub name: *const c_char,
+    pub user_data: *mut c_void,
+    pub compute: PluginComputeFn,
+    pub destroy: PluginDestroyFn,
+}
+
+// 布局断言，plugin_api.h 中有对应的 _Static_assert
+// Layout assertions, mirrored by the _Static_asserts in plugin_api.h
+const PTR: usize = mem::size_of::<*const c_void>();
+const _: () = assert!(mem::size_of::<PluginHost>() == 4 * PTR);
+const _: () = assert!(mem::offset_of!(PluginHost, ctx) == PTR);
+const _: () = assert!(mem::offset_of!(PluginHost, register_vtable) == 3 * PTR);
+const _: () = assert!(mem::size_of::<PluginVTable>() == 4 * PTR);
+const _: () = assert!(mem::offset_of!(PluginVTable, destroy) == 3 * PTR);
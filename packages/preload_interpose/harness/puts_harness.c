@@ -0,0 +1,10 @@
+// cargo xtask preload 运行的 C 程序：直接调用 puts，不知道它会被替换
+// The C program cargo xtask preload runs: calls puts directly, unaware that it may be replaced
+#include <stdio.h>
+
+int main(void)
+{
+    puts("[C] Hello from the harness");
+    puts("[C] Goodbye from the harness");
+    return 0;
+}
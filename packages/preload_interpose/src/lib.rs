@@ -0,0 +1,63 @@
+// 用 LD_PRELOAD 替换 libc 的 puts：动态链接器先在预加载的库中查找符号，程序对 puts 的调用就落到这里。
+// 这里给每一行加上前缀和序号，再通过 dlsym(RTLD_NEXT, "puts") 找到查找顺序中的下一个 puts，也就是
+// libc 的那一个，把结果交给它输出
+// Replaces libc's puts through LD_PRELOAD: the dynamic linker looks symbols up in preloaded
+// libraries first, so the program's calls to puts land here. Each line gets a prefix and a
+// number, and dlsym(RTLD_NEXT, "puts") finds the next puts in the lookup order, libc's, to print
+// the result
+//
+// 运行 / Run: cargo xtask preload
+//
+// 只替换通过动态符号表的调用：libc 内部对 puts 的调用和静态链接的程序都看不到这个库
+// Only calls made through the dynamic symbol table are replaced: libc's internal calls to puts
+// and statically linked programs never see this library
+
+#![cfg(target_os = "linux")]
+
+use std::ffi::{c_char, c_int, c_void, CStr, CString};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::OnceLock;
+
+type Puts = unsafe extern "C" fn(s: *const c_char) -> c_int;
+
+// glibc 和 musl 中 RTLD_NEXT 都是 (void *)-1
+// RTLD_NEXT is (void *)-1 in both glibc and musl
+const RTLD_NEXT: *mut c_void = -1isize as *mut c_void;
+
+const EOF: c_int = -1;
+
+extern "C" {
+    fn dlsym(handle: *mut c_void, symbol: *const c_char) -> *mut c_void;
+}
+
+static CALLS: AtomicUsize = AtomicUsize::new(0);
+
+// 第一次调用时解析，之后复用
+// Resolved on the first call and reused afterwards
+fn real_puts() -> Option<Puts> {
+    static REAL: OnceLock<Option<Puts>> = OnceLock::new();
+    *REAL.get_or_init(|| {
+        // SAFETY: the symbol named puts has the type of puts
+        let symbol = unsafe { dlsym(RTLD_NEXT, c"puts".as_ptr()) };
+        (!symbol.is_null()).then(|| unsafe { std::mem::transmute::<*mut c_void, Puts>(symbol) })
+    })
+}
+
+/// Prints `s` through libc's `puts`, prefixed with `[preload #n]` where `n` counts the calls.
+///
+/// # Safety
+///
+/// `s` must point to a NUL-terminated string, as for `puts`.
+#[no_mangle]
+pub unsafe extern "C" fn puts(s: *const c_char) -> c_int {
+    let Some(real) = real_puts() else {
+        return EOF;
+    };
+    let n = CALLS.fetch_add(1, Ordering::Relaxed) + 1;
+    let mut line = format!("[preload #{}] ", n).into_bytes();
+    line.extend_from_slice(CStr::from_ptr(s).to_bytes());
+    match CString::new(line) {
+        Ok(line) => real(line.as_ptr()),
+        Err(_) => EOF,
+    }
+}
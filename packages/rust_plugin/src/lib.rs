@@ -0,0 +1,56 @@
+// 用 Rust 编写的示例插件，实现乘法并在 user_data 中记录调用次数
+// A sample plugin written in Rust, it multiplies and counts its calls in user_data
+
+use std::ffi::{c_int, c_void};
+
+use plugin_api::{PluginHost, PluginVTable, PLUGIN_API_VERSION};
+
+struct Stats {
+    calls: u32,
+}
+
+extern "C" fn multiply(user_data: *mut c_void, a: c_int, b: c_int, out: *mut c_int) -> c_int {
+    // SAFETY: user_data is the Stats leaked in plugin_register, the host passes it back unchanged
+    let stats = unsafe { &mut *user_data.cast::<Stats>() };
+    stats.calls += 1;
+    match (a.checked_mul(b), out.is_null()) {
+        (Some(product), false) => {
+            unsafe { out.write(product) };
+            0
+        }
+        _ => -1,
+    }
+}
+
+extern "C" fn destroy(user_data: *mut c_void) {
+    let stats = unsafe { Box::from_raw(user_data.cast::<Stats>()) };
+    println!("[Rust plugin] Unloading after {} calls", stats.calls);
+}
+
+/// Registers the multiply plugin with the host.
+///
+/// # Safety
+///
+/// `host` must be NULL or point to a valid `PluginHost`.
+#[no_mangle]
+pub unsafe extern "C" fn plugin_register(host: *const PluginHost) -> c_int {
+    let Some(host) = host.as_ref() else {
+        return -1;
+    };
+    if host.api_version != PLUGIN_API_VERSION {
+        return -1;
+    }
+    (host.log)(host.ctx, c"[Rust plugin] Registering multiply".as_ptr());
+
+    let vtable = PluginVTable {
+        name: c"multiply".as_ptr(),
+        user_data: Box::into_raw(Box::new(Stats { calls: 0 })).cast(),
+        compute: multiply,
+        destroy: Some(destroy),
+    };
+    let status = (host.register_vtable)(host.ctx, &vtable);
+    if status != 0 {
+        destroy(vtable.user_data);
+    }
+    status
+}
@@ -0,0 +1,15 @@
+// 这是我们的构建脚本，只在目标是 Windows 时编译故意访问坏指针的 C 代码
+// This is our build script, it only compiles the C code that dereferences bad pointers on
+// purpose when the target is Windows
+
+use std::env;
+
+fn main() {
+    println!("cargo::rerun-if-changed=c");
+    // 构建脚本在主机上运行，目标平台要从 CARGO_CFG_TARGET_OS 得知
+    // The build script runs on the host, the target comes from CARGO_CFG_TARGET_OS
+    if env::var("CARGO_CFG_TARGET_OS").as_deref() != Ok("windows") {
+        return;
+    }
+    cc::Build::new().file("c/clib.c").compile("seh_clib");
+}
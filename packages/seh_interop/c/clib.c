@@ -0,0 +1,18 @@
+#include "clib.h"
+
+int clib_read(const volatile int *value)
+{
+    // volatile 让读取一定发生，编译器不能把它优化掉
+    // volatile makes sure the read happens, the compiler can't optimize it away
+    return *value;
+}
+
+static int read_ctx(void *ctx)
+{
+    return clib_read(ctx);
+}
+
+clib_callback clib_reading_callback(void)
+{
+    return read_ctx;
+}
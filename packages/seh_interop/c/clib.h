@@ -0,0 +1,21 @@
+// 在 Rust 调用的函数里和 Rust 调用的 C 回调里访问坏指针，用来观察 Windows 上的访问冲突（SEH 异常）
+// 怎样越过边界
+// Dereferences a bad pointer in a function called from Rust and in a C callback invoked by Rust,
+// to observe how an access violation (an SEH exception) on Windows crosses the boundary
+#ifndef SEH_CLIB_H
+#define SEH_CLIB_H
+
+// 读取 value 指向的 int；value 是坏指针时在这里触发 EXCEPTION_ACCESS_VIOLATION
+// Reads the int value points to; raises EXCEPTION_ACCESS_VIOLATION right here when it is a bad
+// pointer
+int clib_read(const volatile int *value);
+
+// 交给 Rust 调用的回调，ctx 是它要读取的 int 指针
+// A callback for Rust to invoke, ctx being the int pointer it reads
+typedef int (*clib_callback)(void *ctx);
+
+// 返回读取 ctx 的回调
+// Returns the callback that reads ctx
+clib_callback clib_reading_callback(void);
+
+#endif
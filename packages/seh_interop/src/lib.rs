@@ -0,0 +1,242 @@
+// Windows 上坏指针的试验台：c/clib.c 在 Rust 调用的函数里、以及在 Rust 调用的 C 回调里访问一个只保留
+// 未提交的页，触发访问冲突；访问冲突是 SEH 异常，不是 Rust 的 panic，越过边界的只有下面这些：
+//
+// - 没有处理函数时：catch_unwind 拦不住它，中间的 Rust 栈帧不会展开，析构函数不会运行，
+//   进程以 STATUS_ACCESS_VIOLATION（0xC0000005）结束
+// - 安装了只观察的向量化异常处理函数时：它在出错的线程上、C 栈帧还在栈顶时最先看到异常，
+//   返回 EXCEPTION_CONTINUE_SEARCH 之后结果和没有处理函数时一样
+// - 处理函数提交这一页并返回 EXCEPTION_CONTINUE_EXECUTION 时：出错的指令重新执行并成功，
+//   C 和 Rust 的栈帧都察觉不到发生过什么
+//
+// A testbed for bad pointers on Windows: c/clib.c reads a page that is reserved but not committed,
+// in a function called from Rust and in a C callback invoked by Rust, raising an access
+// violation; access violations are SEH exceptions, not Rust panics, and this is all that crosses
+// the boundary:
+//
+// - Without a handler: catch_unwind can't catch it, the Rust frames in between are not unwound
+//   and no destructors run; the process ends with STATUS_ACCESS_VIOLATION (0xC0000005)
+// - With a vectored exception handler that only observes: it sees the exception first, on the
+//   faulting thread with the C frame still on top, and after EXCEPTION_CONTINUE_SEARCH the outcome
+//   is the same as without a handler
+// - With a handler that commits the page and returns EXCEPTION_CONTINUE_EXECUTION: the faulting
+//   instruction runs again and succeeds, and neither the C nor the Rust frames notice anything
+//
+// 其他平台上这个 crate 是空的
+// On other platforms this crate is empty
+
+#![cfg(windows)]
+
+use std::ffi::c_void;
+use std::io::{self, Write};
+use std::marker::PhantomData;
+use std::ptr::NonNull;
+use std::sync::atomic::{AtomicBool, AtomicPtr, AtomicUsize, Ordering};
+
+/// The exception code of an access violation, `STATUS_ACCESS_VIOLATION`.
+pub const EXCEPTION_ACCESS_VIOLATION: u32 = 0xC000_0005;
+
+/// The exit code of a process ended by an unhandled access violation, as
+/// `ExitStatus::code` reports it.
+pub const ACCESS_VIOLATION_EXIT_CODE: i32 = EXCEPTION_ACCESS_VIOLATION as i32;
+
+/// The value a committing handler stores in the page before the faulting read runs again.
+pub const COMMITTED_VALUE: i32 = 42;
+
+const PAGE_SIZE: usize = 4096;
+const MEM_COMMIT: u32 = 0x1000;
+const MEM_RESERVE: u32 = 0x2000;
+const MEM_RELEASE: u32 = 0x8000;
+const PAGE_NOACCESS: u32 = 0x01;
+const PAGE_READWRITE: u32 = 0x04;
+const EXCEPTION_CONTINUE_EXECUTION: i32 = -1;
+const EXCEPTION_CONTINUE_SEARCH: i32 = 0;
+const SEM_FAILCRITICALERRORS: u32 = 0x0001;
+const SEM_NOGPFAULTERRORBOX: u32 = 0x0002;
+
+// winnt.h 中 EXCEPTION_RECORD 和 EXCEPTION_POINTERS 用到的部分
+// The parts of EXCEPTION_RECORD and EXCEPTION_POINTERS from winnt.h that are used here
+#[repr(C)]
+struct ExceptionRecord {
+    code: u32,
+    flags: u32,
+    record: *mut ExceptionRecord,
+    address: *mut c_void,
+    parameters: u32,
+    information: [usize; 15],
+}
+
+#[repr(C)]
+struct ExceptionPointers {
+    record: *mut ExceptionRecord,
+    context: *mut c_void,
+}
+
+type VectoredHandlerFn = unsafe extern "system" fn(info: *mut ExceptionPointers) -> i32;
+
+#[link(name = "kernel32")]
+extern "system" {
+    fn VirtualAlloc(address: *mut c_void, size: usize, kind: u32, protect: u32) -> *mut c_void;
+    fn VirtualFree(address: *mut c_void, size: usize, kind: u32) -> i32;
+    fn AddVectoredExceptionHandler(first: u32, handler: VectoredHandlerFn) -> *mut c_void;
+    fn RemoveVectoredExceptionHandler(handle: *mut c_void) -> u32;
+    fn SetErrorMode(mode: u32) -> u32;
+}
+
+extern "C" {
+    fn clib_read(value: *const i32) -> i32;
+    fn clib_reading_callback() -> Option<unsafe extern "C" fn(ctx: *mut c_void) -> i32>;
+}
+
+/// A page of address space that is reserved but not committed, so any access to it faults.
+///
+/// It stays reserved until dropped, so its address never belongs to anything else meanwhile.
+#[derive(Debug)]
+pub struct ReservedPage {
+    base: NonNull<i32>,
+}
+
+impl ReservedPage {
+    pub fn new() -> Self {
+        let base =
+            unsafe { VirtualAlloc(std::ptr::null_mut(), PAGE_SIZE, MEM_RESERVE, PAGE_NOACCESS) };
+        ReservedPage {
+            base: NonNull::new(base.cast()).expect("Unable to reserve a page."),
+        }
+    }
+
+    /// The start of the page, a bad pointer until a handler commits the page.
+    pub fn as_ptr(&self) -> *const i32 {
+        self.base.as_ptr()
+    }
+}
+
+impl Default for ReservedPage {
+    fn default() -> Self {
+        ReservedPage::new()
+    }
+}
+
+impl Drop for ReservedPage {
+    fn drop(&mut self) {
+        unsafe { VirtualFree(self.base.as_ptr().cast(), 0, MEM_RELEASE) };
+    }
+}
+
+/// What a [`VectoredHandler`] does with an access violation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HandlerMode {
+    /// Records and reports it on stderr, then lets the search for a handler go on.
+    Observe,
+    /// Like `Observe`, but a fault inside the handler's page commits the page, stores
+    /// [`COMMITTED_VALUE`] in it and resumes the faulting instruction.
+    CommitPage,
+}
+
+static COMMIT: AtomicBool = AtomicBool::new(false);
+static PAGE: AtomicPtr<c_void> = AtomicPtr::new(std::ptr::null_mut());
+static FAULTS: AtomicUsize = AtomicUsize::new(0);
+static LAST_FAULT: AtomicUsize = AtomicUsize::new(0);
+
+/// A vectored exception handler, installed first in line until it is dropped.
+///
+/// The handler's state is process-wide, so only one may be installed at a time.
+#[derive(Debug)]
+pub struct VectoredHandler<'a> {
+    handle: NonNull<c_void>,
+    page: PhantomData<&'a ReservedPage>,
+}
+
+impl<'a> VectoredHandler<'a> {
+    pub fn install(mode: HandlerMode, page: &'a ReservedPage) -> Self {
+        COMMIT.store(mode == HandlerMode::CommitPage, Ordering::SeqCst);
+        PAGE.store(page.base.as_ptr().cast(), Ordering::SeqCst);
+        FAULTS.store(0, Ordering::SeqCst);
+        LAST_FAULT.store(0, Ordering::SeqCst);
+        let handle = unsafe { AddVectoredExceptionHandler(1, on_exception) };
+        VectoredHandler {
+            handle: NonNull::new(handle).expect("Unable to add the vectored exception handler."),
+            page: PhantomData,
+        }
+    }
+
+    /// How many access violations the handler saw.
+    pub fn faults(&self) -> usize {
+        FAULTS.load(Ordering::SeqCst)
+    }
+
+    /// The address the last access violation tried to access, 0 if there was none.
+    pub fn last_fault(&self) -> usize {
+        LAST_FAULT.load(Ordering::SeqCst)
+    }
+}
+
+impl Drop for VectoredHandler<'_> {
+    fn drop(&mut self) {
+        unsafe { RemoveVectoredExceptionHandler(self.handle.as_ptr()) };
+        PAGE.store(std::ptr::null_mut(), Ordering::SeqCst);
+    }
+}
+
+// 在出错的线程上运行，C 的栈帧还在栈顶；处理函数不能 panic，所以写 stderr 的错误被忽略
+// Runs on the faulting thread with the C frame still on top; the handler must not panic, so
+// errors writing to stderr are ignored
+unsafe extern "system" fn on_exception(info: *mut ExceptionPointers) -> i32 {
+    let record = &*(*info).record;
+    if record.code != EXCEPTION_ACCESS_VIOLATION {
+        return EXCEPTION_CONTINUE_SEARCH;
+    }
+    // information[0] 是访问的种类（0 读，1 写，8 执行），information[1] 是访问的地址
+    // information[0] is the kind of access (0 read, 1 write, 8 execute), information[1] the
+    // address accessed
+    let address = record.information[1];
+    FAULTS.fetch_add(1, Ordering::SeqCst);
+    LAST_FAULT.store(address, Ordering::SeqCst);
+    let _ = writeln!(
+        io::stderr(),
+        "[Rust] The vectored handler saw access violation {:#x} accessing {:#x}",
+        record.code,
+        address
+    );
+
+    let page = PAGE.load(Ordering::SeqCst);
+    if COMMIT.load(Ordering::SeqCst)
+        && (page as usize..page as usize + PAGE_SIZE).contains(&address)
+    {
+        let committed = VirtualAlloc(page, PAGE_SIZE, MEM_COMMIT, PAGE_READWRITE);
+        if !committed.is_null() {
+            page.cast::<i32>().write(COMMITTED_VALUE);
+            return EXCEPTION_CONTINUE_EXECUTION;
+        }
+    }
+    EXCEPTION_CONTINUE_SEARCH
+}
+
+/// Keeps Windows Error Reporting from showing a dialog when this process crashes, so a test
+/// that crashes on purpose ends right away.
+pub fn disable_error_dialog() {
+    unsafe { SetErrorMode(SEM_FAILCRITICALERRORS | SEM_NOGPFAULTERRORBOX) };
+}
+
+/// Reads `value` in C, in `clib_read`.
+///
+/// # Safety
+///
+/// `value` must be valid for reads, unless the fault is what the caller is after.
+pub unsafe fn read_in_c(value: *const i32) -> i32 {
+    clib_read(value)
+}
+
+/// Invokes C's reading callback from a Rust frame that owns `held`, with `value` as the
+/// callback's context.
+///
+/// `held` is dropped when the frame is left, which shows whether a fault in the callback unwinds
+/// the Rust frame.
+///
+/// # Safety
+///
+/// Like [`read_in_c`].
+pub unsafe fn read_in_c_callback<T>(value: *const i32, held: T) -> i32 {
+    let _held = held;
+    let callback = clib_reading_callback().expect("C returned a NULL callback.");
+    callback(value as *mut c_void)
+}
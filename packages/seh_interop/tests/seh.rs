@@ -0,0 +1,158 @@
+// C 在 Rust 调用的函数里和 Rust 调用的 C 回调里访问坏指针时越过边界的是什么：没有处理函数或只有观察的
+// 向量化异常处理函数时进程以 STATUS_ACCESS_VIOLATION 结束，catch_unwind 和析构函数都不起作用；
+// 提交这一页的处理函数让 C 代码照常返回
+// What crosses the boundary when C dereferences a bad pointer in a function called from Rust and
+// in a C callback invoked by Rust: without a handler, or with a vectored exception handler that
+// only observes, the process ends with STATUS_ACCESS_VIOLATION and neither catch_unwind nor
+// destructors take effect; a handler that commits the page lets the C code return as usual
+//
+// 会让进程结束的情况在子进程中运行：测试以 SEH_CASE 重新启动自己，只运行 crashing_case
+// The cases that end the process run in a child process: the test starts itself again with
+// SEH_CASE set, running only crashing_case
+
+#![cfg(windows)]
+
+use std::cell::Cell;
+use std::env;
+use std::panic;
+use std::process::Command;
+
+use seh_interop::{
+    disable_error_dialog, read_in_c, read_in_c_callback, HandlerMode, ReservedPage,
+    VectoredHandler, ACCESS_VIOLATION_EXIT_CODE, COMMITTED_VALUE,
+};
+
+const CASE: &str = "SEH_CASE";
+
+// 在 Rust 栈帧中持有，被丢弃时输出一行
+// Held by a Rust frame, prints a line when dropped
+struct Held;
+
+impl Drop for Held {
+    fn drop(&mut self) {
+        println!("[Rust] The value held by the Rust frame was dropped");
+    }
+}
+
+// 只在子进程中做事；出错之后的输出都不应该出现
+// Only does something in the child process; none of the output after the fault should appear
+#[test]
+fn crashing_case() {
+    let Ok(case) = env::var(CASE) else {
+        return;
+    };
+    disable_error_dialog();
+    let page = ReservedPage::new();
+    println!(
+        "[Rust] Reading the reserved page at {:#x}",
+        page.as_ptr() as usize
+    );
+    let _handler = case
+        .ends_with("_observed")
+        .then(|| VectoredHandler::install(HandlerMode::Observe, &page));
+    let result = panic::catch_unwind(|| unsafe {
+        match case.trim_end_matches("_observed") {
+            "direct" => read_in_c(page.as_ptr()),
+            "callback" => read_in_c_callback(page.as_ptr(), Held),
+            _ => unreachable!("Unknown case {case}."),
+        }
+    });
+    println!("[Rust] catch_unwind returned {result:?}");
+}
+
+// 运行会让进程结束的情况，返回子进程的 stdout 和 stderr
+// Runs a case that ends the process, returning the child's stdout and stderr
+fn run_crashing_case(case: &str) -> (String, String) {
+    let output = Command::new(env::current_exe().unwrap())
+        .args([
+            "crashing_case",
+            "--exact",
+            "--nocapture",
+            "--test-threads=1",
+        ])
+        .env(CASE, case)
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout).into_owned();
+    let stderr = String::from_utf8_lossy(&output.stderr).into_owned();
+    assert_eq!(
+        output.status.code(),
+        Some(ACCESS_VIOLATION_EXIT_CODE),
+        "{case}:\n{stdout}\n{stderr}"
+    );
+    assert!(
+        stdout.contains("[Rust] Reading the reserved page at "),
+        "{stdout}"
+    );
+    // 不是 panic：catch_unwind 没有返回，也没有 panic 消息
+    // Not a panic: catch_unwind never returned and there is no panic message
+    assert!(!stdout.contains("catch_unwind returned"), "{stdout}");
+    assert!(!stderr.contains("panicked"), "{stderr}");
+    (stdout, stderr)
+}
+
+fn page_address(stdout: &str) -> &str {
+    stdout
+        .lines()
+        .find_map(|line| line.strip_prefix("[Rust] Reading the reserved page at "))
+        .expect("The child printed the page's address.")
+}
+
+#[test]
+fn fault_in_c_ends_the_process() {
+    run_crashing_case("direct");
+}
+
+// 没有展开：Rust 栈帧持有的值没有被丢弃
+// Nothing is unwound: the value the Rust frame holds is never dropped
+#[test]
+fn fault_in_a_c_callback_skips_the_rust_frames() {
+    let (stdout, _) = run_crashing_case("callback");
+    assert!(!stdout.contains("was dropped"), "{stdout}");
+}
+
+// 处理函数最先看到访问冲突和出错的地址，但继续搜索之后进程照样结束
+// The handler is the first to see the access violation and the faulting address, but once the
+// search goes on the process ends all the same
+#[test]
+fn observing_handler_sees_the_fault_first() {
+    for case in ["direct_observed", "callback_observed"] {
+        let (stdout, stderr) = run_crashing_case(case);
+        let seen = format!(
+            "[Rust] The vectored handler saw access violation 0xc0000005 accessing {}",
+            page_address(&stdout)
+        );
+        assert_eq!(stderr.matches(&seen).count(), 1, "{case}:\n{stderr}");
+        assert!(!stdout.contains("was dropped"), "{stdout}");
+    }
+}
+
+// 提交这一页之后出错的读取重新执行，C 返回处理函数写入的值，Rust 栈帧照常离开
+// Once the page is committed the faulting read runs again, C returns the value the handler stored
+// and the Rust frame is left as usual
+#[test]
+fn committing_handler_resumes_the_c_code() {
+    let page = ReservedPage::new();
+    let handler = VectoredHandler::install(HandlerMode::CommitPage, &page);
+    assert_eq!(unsafe { read_in_c(page.as_ptr()) }, COMMITTED_VALUE);
+    assert_eq!(handler.faults(), 1);
+    assert_eq!(handler.last_fault(), page.as_ptr() as usize);
+    // 这一页已经提交，不会再出错
+    // The page is committed now and doesn't fault again
+    assert_eq!(unsafe { read_in_c(page.as_ptr()) }, COMMITTED_VALUE);
+    assert_eq!(handler.faults(), 1);
+    drop(handler);
+
+    struct Flag<'a>(&'a Cell<bool>);
+    impl Drop for Flag<'_> {
+        fn drop(&mut self) {
+            self.0.set(true);
+        }
+    }
+    let page = ReservedPage::new();
+    let handler = VectoredHandler::install(HandlerMode::CommitPage, &page);
+    let dropped = Cell::new(false);
+    let value = unsafe { read_in_c_callback(page.as_ptr(), Flag(&dropped)) };
+    assert_eq!((value, handler.faults()), (COMMITTED_VALUE, 1));
+    assert!(dropped.get());
+}
@@ -0,0 +1,49 @@
+// 这是我们的构建脚本：把共享内存段的 C 代码编译成 Rust 写入者链接的静态库，再用同一个编译器把 C 的读取者
+// 编译成 OUT_DIR 中的可执行文件，路径通过 SHM_READER 交给写入者
+// This is our build script: it compiles the C code for the shared memory segment into a static
+// library the Rust writer links, then builds the C reader into an executable in OUT_DIR with the
+// same compiler, handing its path to the writer through SHM_READER
+
+use std::env;
+use std::path::PathBuf;
+
+fn main() {
+    let mut build = cc::Build::new();
+    build.file("c/shm_ipc.c").std("c11");
+    // MSVC 的 <stdatomic.h> 还需要打开这个开关
+    // MSVC's <stdatomic.h> also needs this switch
+    let compiler = build.get_compiler();
+    if compiler.is_like_msvc() {
+        build.flag("/experimental:c11atomics");
+    }
+    build.compile("shm_ipc");
+
+    let out_dir = PathBuf::from(env::var("OUT_DIR").unwrap());
+    let target_os = env::var("CARGO_CFG_TARGET_OS").unwrap();
+    let reader = out_dir.join(if target_os == "windows" {
+        "shm_reader.exe"
+    } else {
+        "shm_reader"
+    });
+    let mut command = compiler.to_command();
+    command.args(["c/shm_reader.c", "c/shm_ipc.c"]);
+    if compiler.is_like_msvc() {
+        command
+            .args(["/std:c11", "/experimental:c11atomics"])
+            .arg(format!("/Fo{}\\", out_dir.display()))
+            .arg(format!("/Fe{}", reader.display()));
+    } else {
+        command.arg("-std=c11").arg("-o").arg(&reader);
+        // glibc 2.34 之前 shm_open 在 librt 中
+        // Before glibc 2.34 shm_open lives in librt
+        if target_os == "linux" {
+            command.arg("-lrt");
+        }
+    }
+    let status = command
+        .status()
+        .unwrap_or_else(|err| panic!("Failed to run the C compiler: {}", err));
+    assert!(status.success(), "Building the C reader failed.");
+    println!("cargo::rustc-env=SHM_READER={}", reader.display());
+    println!("cargo::rerun-if-changed=c");
+}
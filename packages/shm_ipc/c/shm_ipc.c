@@ -0,0 +1,183 @@
+// 共享内存段的创建和映射，以及顺序锁的读取端。POSIX 上用 shm_open 和 mmap，Windows 上用命名的文件映射；
+// Rust 的写入者和 C 的读取者都链接这份代码，两个进程用同样的方式打开同一段内存
+// Creating and mapping the shared memory segment, and the reading side of the seqlock. POSIX uses
+// shm_open and mmap, Windows a named file mapping; both the Rust writer and the C reader link this
+// code, so the two processes open the same memory the same way
+// -std=c11 只声明标准 C 的函数，shm_open 和 ftruncate 需要明确要求 POSIX
+// -std=c11 only declares standard C functions, shm_open and ftruncate need POSIX asked for
+#ifndef _WIN32
+#define _POSIX_C_SOURCE 200809L
+#endif
+#include "shm_ipc.h"
+
+#ifdef _WIN32
+#include <windows.h>
+#else
+#include <fcntl.h>
+#include <sched.h>
+#include <sys/mman.h>
+#include <unistd.h>
+#endif
+
+#ifdef _WIN32
+static ShmHeader *map(const char *name, int create)
+{
+    HANDLE mapping = create
+                         ? CreateFileMappingA(INVALID_HANDLE_VALUE, NULL, PAGE_READWRITE, 0,
+                                              sizeof(ShmHeader), name)
+                         : OpenFileMappingA(FILE_MAP_ALL_ACCESS, FALSE, name);
+    if (mapping == NULL)
+    {
+        return NULL;
+    }
+    if (create && GetLastError() == ERROR_ALREADY_EXISTS)
+    {
+        CloseHandle(mapping);
+        return NULL;
+    }
+    // 映射视图会让文件映射对象一直存在，句柄可以马上关掉
+    // The mapped view keeps the file mapping object alive, so the handle can be closed right away
+    void *view = MapViewOfFile(mapping, FILE_MAP_ALL_ACCESS, 0, 0, sizeof(ShmHeader));
+    DWORD error = GetLastError();
+    CloseHandle(mapping);
+    SetLastError(error);
+    return view;
+}
+#else
+static ShmHeader *map(const char *name, int create)
+{
+    int fd = create ? shm_open(name, O_RDWR | O_CREAT | O_EXCL, 0600) : shm_open(name, O_RDWR, 0);
+    if (fd < 0)
+    {
+        return NULL;
+    }
+    if (create && ftruncate(fd, sizeof(ShmHeader)) != 0)
+    {
+        close(fd);
+        shm_unlink(name);
+        return NULL;
+    }
+    // 映射建立之后就不再需要文件描述符
+    // The file descriptor is no longer needed once the mapping exists
+    void *view = mmap(NULL, sizeof(ShmHeader), PROT_READ | PROT_WRITE, MAP_SHARED, fd, 0);
+    close(fd);
+    if (view == MAP_FAILED)
+    {
+        if (create)
+        {
+            shm_unlink(name);
+        }
+        return NULL;
+    }
+    return view;
+}
+#endif
+
+// 新建的共享内存全是 0，这里写入 magic 和 version，读取者据此确认打开的是同一种布局
+// New shared memory is all zeros, this writes magic and version, by which readers confirm they
+// opened the same layout
+ShmHeader *shm_ipc_create(const char *name)
+{
+    ShmHeader *header = map(name, 1);
+    if (header != NULL)
+    {
+        header->magic = SHM_IPC_MAGIC;
+        header->version = SHM_IPC_VERSION;
+    }
+    return header;
+}
+
+ShmHeader *shm_ipc_open(const char *name)
+{
+    ShmHeader *header = map(name, 0);
+    if (header != NULL && (header->magic != SHM_IPC_MAGIC || header->version != SHM_IPC_VERSION))
+    {
+        shm_ipc_close(header);
+        return NULL;
+    }
+    return header;
+}
+
+void shm_ipc_close(ShmHeader *header)
+{
+#ifdef _WIN32
+    UnmapViewOfFile(header);
+#else
+    munmap(header, sizeof(ShmHeader));
+#endif
+}
+
+// Windows 的文件映射在最后一个视图关闭时自动消失，没有名字要删除
+// A Windows file mapping goes away once its last view is closed, there is no name to remove
+void shm_ipc_unlink(const char *name)
+{
+#ifdef _WIN32
+    (void)name;
+#else
+    shm_unlink(name);
+#endif
+}
+
+// 消息编号和文本的 FNV-1a 散列，用来发现读到一半被改掉的消息
+// The FNV-1a hash of the message id and text, catching messages changed halfway through reading
+uint32_t shm_ipc_checksum(uint32_t message_id, const uint8_t *text, size_t len)
+{
+    uint32_t hash = 2166136261u;
+    for (int i = 0; i < 4; i++)
+    {
+        hash = (hash ^ ((message_id >> (8 * i)) & 0xff)) * 16777619u;
+    }
+    for (size_t i = 0; i < len; i++)
+    {
+        hash = (hash ^ text[i]) * 16777619u;
+    }
+    return hash;
+}
+
+static void yield_now(void)
+{
+#ifdef _WIN32
+    SwitchToThread();
+#else
+    sched_yield();
+#endif
+}
+
+// 先用 acquire 读 seq，为奇数说明写入者正在写；复制消息之后的 acquire 栅栏保证第二次读 seq 不会排到
+// 复制之前，两次相同就说明复制期间没有写入
+// seq is read with acquire first, odd meaning the writer is writing; the acquire fence after copying
+// the message keeps the second read of seq from moving before the copy, and the two being equal
+// means nothing was written meanwhile
+uint32_t shm_ipc_read(ShmHeader *header, ShmSnapshot *out)
+{
+    uint32_t retries = 0;
+    for (;;)
+    {
+        uint32_t before = atomic_load_explicit(&header->seq, memory_order_acquire);
+        if (before % 2 == 0)
+        {
+            out->message_id = atomic_load_explicit(&header->message_id, memory_order_relaxed);
+            out->len = atomic_load_explicit(&header->len, memory_order_relaxed);
+            out->checksum = atomic_load_explicit(&header->checksum, memory_order_relaxed);
+            for (int i = 0; i < SHM_IPC_WORDS; i++)
+            {
+                uint32_t word = atomic_load_explicit(&header->words[i], memory_order_relaxed);
+                for (int b = 0; b < 4; b++)
+                {
+                    out->text[4 * i + b] = (uint8_t)(word >> (8 * b));
+                }
+            }
+            atomic_thread_fence(memory_order_acquire);
+            if (atomic_load_explicit(&header->seq, memory_order_relaxed) == before)
+            {
+                if (out->len > SHM_IPC_MAX_TEXT)
+                {
+                    out->len = SHM_IPC_MAX_TEXT;
+                }
+                return retries;
+            }
+        }
+        retries++;
+        yield_now();
+    }
+}
@@ -0,0 +1,73 @@
+// 进程间共享内存的布局：magic 和 version 由创建者写入一次，其余字段都是原子的。seq 是顺序锁，写入者在
+// 修改消息之前把它加到奇数、修改之后加到偶数；读取者在 seq 为偶数且读前读后不变时才接受读到的消息。
+// 必须和 src/lib.rs 中的 #[repr(C)] struct ShmHeader 保持一致
+// The layout of the memory shared between processes: magic and version are written once by the
+// creator, every other field is atomic. seq is a seqlock the writer bumps to odd before changing
+// the message and to even after; a reader only accepts the message it read when seq was even and
+// unchanged before and after. It must match the #[repr(C)] struct ShmHeader in src/lib.rs
+#ifndef SHM_IPC_H
+#define SHM_IPC_H
+
+#include <stdatomic.h>
+#include <stddef.h>
+#include <stdint.h>
+
+#define SHM_IPC_MAGIC 0x31584352u
+#define SHM_IPC_VERSION 1u
+#define SHM_IPC_WORDS 64
+#define SHM_IPC_MAX_TEXT (SHM_IPC_WORDS * 4)
+
+typedef struct ShmHeader
+{
+    uint32_t magic;
+    uint32_t version;
+    // 已经连上的读取者数，写入者在第一个读取者到来之前不写消息
+    // How many readers attached, the writer doesn't write messages before the first one arrives
+    _Atomic uint32_t readers;
+    // 写入者写完最后一条消息后置 1
+    // Set to 1 by the writer once it wrote its last message
+    _Atomic uint32_t done;
+    _Atomic uint32_t seq;
+    // 以下字段由 seq 保护
+    // The fields below are protected by seq
+    _Atomic uint32_t message_id;
+    _Atomic uint32_t len;
+    _Atomic uint32_t checksum;
+    // 消息文本，按小端序每 4 个字节装进一个字
+    // The message text, packed 4 bytes to a word in little-endian order
+    _Atomic uint32_t words[SHM_IPC_WORDS];
+} ShmHeader;
+
+_Static_assert(sizeof(_Atomic uint32_t) == 4, "_Atomic uint32_t must be 4 bytes");
+_Static_assert(sizeof(ShmHeader) == 32 + SHM_IPC_WORDS * 4, "ShmHeader must have no padding");
+_Static_assert(offsetof(ShmHeader, words) == 32, "ShmHeader.words must be at offset 32");
+
+// 读取者得到的一致的消息副本
+// The consistent copy of the message a reader gets
+typedef struct ShmSnapshot
+{
+    uint32_t message_id;
+    uint32_t len;
+    uint32_t checksum;
+    uint8_t text[SHM_IPC_MAX_TEXT];
+} ShmSnapshot;
+
+// 由 C 实现，Rust 和 C 的程序都调用
+// Implemented in C, called from both the Rust and the C program
+
+// 创建名为 name 的共享内存并映射整个头部，出错时返回 NULL，原因在 errno 或 GetLastError 中
+// Creates the shared memory named name and maps the whole header, returning NULL on failure with
+// the reason in errno or GetLastError
+ShmHeader *shm_ipc_create(const char *name);
+ShmHeader *shm_ipc_open(const char *name);
+void shm_ipc_close(ShmHeader *header);
+void shm_ipc_unlink(const char *name);
+
+uint32_t shm_ipc_checksum(uint32_t message_id, const uint8_t *text, size_t len);
+
+// 按顺序锁读取一条一致的消息，返回因为写入者同时在写而重读的次数
+// Reads a consistent message under the seqlock, returning how many times it had to read again
+// because the writer was writing at the same time
+uint32_t shm_ipc_read(ShmHeader *header, ShmSnapshot *out);
+
+#endif
@@ -0,0 +1,65 @@
+// C 的读取者：打开 Rust 写入者创建的共享内存，登记自己，然后不断按顺序锁读取消息，直到写入者写完。
+// 检查每条读到的消息校验和都正确、编号只增不减，并且最后读到的是最后一条；有问题时以 1 退出
+// The C reader: opens the shared memory the Rust writer created, registers itself, then keeps
+// reading messages under the seqlock until the writer is done. It checks that every message it
+// read has the right checksum, that ids never go backwards and that the last one it read is the
+// final message; it exits with 1 on any problem
+//
+// 用法 / Usage: shm_reader <name> <messages>
+#include <stdio.h>
+#include <stdlib.h>
+#include "shm_ipc.h"
+
+int main(int argc, char **argv)
+{
+    if (argc != 3)
+    {
+        fprintf(stderr, "usage: %s <name> <messages>\n", argv[0]);
+        return 2;
+    }
+    uint32_t expected = (uint32_t)strtoul(argv[2], NULL, 10);
+    ShmHeader *header = shm_ipc_open(argv[1]);
+    if (header == NULL)
+    {
+        fprintf(stderr, "[C reader] Cannot open shared memory %s\n", argv[1]);
+        return 1;
+    }
+    atomic_fetch_add(&header->readers, 1);
+    printf("[C reader] Attached to %s\n", argv[1]);
+
+    ShmSnapshot snapshot;
+    uint32_t last_id = 0, reads = 0, distinct = 0, retries = 0, corrupt = 0, backwards = 0;
+    int done;
+    do
+    {
+        // 先读 done 再读消息：done 为 1 时最后一条消息已经写完，这次读到的就是它
+        // done is read before the message: once it is 1 the final message is complete, so this
+        // read gets it
+        done = atomic_load_explicit(&header->done, memory_order_acquire);
+        retries += shm_ipc_read(header, &snapshot);
+        reads++;
+        // 编号 0 表示写入者还没有写第一条消息
+        // Id 0 means the writer has yet to write its first message
+        if (snapshot.message_id != 0 &&
+            shm_ipc_checksum(snapshot.message_id, snapshot.text, snapshot.len) != snapshot.checksum)
+        {
+            corrupt++;
+        }
+        if (snapshot.message_id < last_id)
+        {
+            backwards++;
+        }
+        if (snapshot.message_id != last_id)
+        {
+            distinct++;
+        }
+        last_id = snapshot.message_id;
+    } while (!done);
+    printf("[C reader] Last message #%u: %.*s\n", snapshot.message_id, (int)snapshot.len,
+           (const char *)snapshot.text);
+    printf("[C reader] %u reads saw %u of %u messages, %u reads retried, ", reads, distinct,
+           expected, retries);
+    printf("%u corrupt, %u out of order\n", corrupt, backwards);
+    shm_ipc_close(header);
+    return corrupt == 0 && backwards == 0 && last_id == expected ? 0 : 1;
+}
@@ -0,0 +1,71 @@
+// Rust 的写入者：创建共享内存，启动 C 的读取者并等它连上，然后尽快写入一条条消息，让读取者在读的同时
+// 不断遇到正在写的消息；写完后等读取者检查完毕，以它的结果退出
+// The Rust writer: creates the shared memory, starts the C reader and waits for it to attach, then
+// writes message after message as fast as it can so the reader keeps running into messages
+// being written while it reads; once done it waits for the reader's checks and exits with its
+// result
+//
+// 用法 / Usage: shm_writer [messages] [reader]
+
+use std::process::{exit, Command};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use shm_ipc::{segment_name, Segment, READER};
+
+const ATTACH_TIMEOUT: Duration = Duration::from_secs(10);
+
+fn main() {
+    let mut args = std::env::args().skip(1);
+    let messages: u32 = args
+        .next()
+        .map(|arg| arg.parse().expect("messages must be a number"))
+        .unwrap_or(100_000);
+    let reader = args.next().unwrap_or_else(|| READER.to_owned());
+
+    let name = segment_name("writer");
+    let segment = Segment::create(&name)
+        .unwrap_or_else(|err| panic!("Failed to create shared memory {}: {}", name, err));
+    let header = segment.header();
+    println!("[Rust writer] Created {}, starting {}", name, reader);
+    let mut child = Command::new(&reader)
+        .args([&name, &messages.to_string()])
+        .spawn()
+        .unwrap_or_else(|err| panic!("Failed to start {}: {}", reader, err));
+
+    let started = Instant::now();
+    while header.readers() == 0 {
+        if let Some(status) = child.try_wait().unwrap() {
+            eprintln!(
+                "[Rust writer] The reader exited with {} before attaching",
+                status
+            );
+            exit(1);
+        }
+        if started.elapsed() > ATTACH_TIMEOUT {
+            let _ = child.kill();
+            eprintln!(
+                "[Rust writer] The reader did not attach within {:?}",
+                ATTACH_TIMEOUT
+            );
+            exit(1);
+        }
+        thread::sleep(Duration::from_millis(1));
+    }
+
+    for id in 1..=messages {
+        header.write(
+            id,
+            format!("message #{} from the Rust writer", id).as_bytes(),
+        );
+    }
+    header.finish();
+    println!("[Rust writer] Wrote {} messages", messages);
+
+    let status = child.wait().unwrap();
+    drop(segment);
+    if !status.success() {
+        eprintln!("[Rust writer] The reader failed with {}", status);
+        exit(1);
+    }
+}
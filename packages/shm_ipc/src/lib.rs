@@ -0,0 +1,221 @@
+// 这个库演示两个进程之间通过共享内存交换数据：Rust 的写入者创建一段共享内存，C 的读取者在另一个进程中
+// 打开同一段；两边都把它看作同一个 #[repr(C)] 的 ShmHeader。写入者只有一个，读取者可以有多个，消息由
+// 顺序锁保护：写入者在修改前后各把 seq 加 1，读取者在 seq 为偶数且读前读后不变时才接受读到的消息，
+// 两边都不会阻塞对方。段的映射和读取端的代码在 c/shm_ipc.c 中，两个程序链接的是同一份
+// This library demonstrates two processes exchanging data through shared memory: the Rust writer
+// creates a segment and the C reader opens the same one in another process, both seeing it as the
+// same #[repr(C)] ShmHeader. There is one writer and any number of readers, and the message is
+// protected by a seqlock: the writer bumps seq before and after changing it, and a reader only
+// accepts the message it read when seq was even and unchanged before and after, so neither side
+// ever blocks the other. Mapping the segment and the reading side live in c/shm_ipc.c, and both
+// programs link the same copy
+
+use std::ffi::{c_char, CString};
+use std::io;
+use std::mem;
+use std::ptr::NonNull;
+use std::sync::atomic::{fence, AtomicU32, Ordering};
+
+/// The number of 32-bit words the message text is packed into.
+pub const SHM_IPC_WORDS: usize = 64;
+
+/// The longest message text in bytes.
+pub const MAX_TEXT: usize = SHM_IPC_WORDS * 4;
+
+/// 共享内存的布局，必须和 c/shm_ipc.h 中的 struct ShmHeader 保持一致
+/// The layout of the shared memory, which must match `struct ShmHeader` in c/shm_ipc.h.
+#[repr(C)]
+pub struct ShmHeader {
+    magic: u32,
+    version: u32,
+    readers: AtomicU32,
+    done: AtomicU32,
+    seq: AtomicU32,
+    message_id: AtomicU32,
+    len: AtomicU32,
+    checksum: AtomicU32,
+    words: [AtomicU32; SHM_IPC_WORDS],
+}
+
+// 布局断言，C 端的 shm_ipc.h 中有对应的 _Static_assert
+// Layout assertions, mirrored by the _Static_asserts in shm_ipc.h
+const _: () = assert!(mem::size_of::<ShmHeader>() == 32 + SHM_IPC_WORDS * 4);
+const _: () = assert!(mem::offset_of!(ShmHeader, words) == 32);
+
+/// C 的读取端读到的一致的消息副本
+/// The consistent copy of the message C's reading side returns.
+#[repr(C)]
+pub struct ShmSnapshot {
+    pub message_id: u32,
+    pub len: u32,
+    pub checksum: u32,
+    pub text: [u8; MAX_TEXT],
+}
+
+impl Default for ShmSnapshot {
+    fn default() -> ShmSnapshot {
+        ShmSnapshot {
+            message_id: 0,
+            len: 0,
+            checksum: 0,
+            text: [0; MAX_TEXT],
+        }
+    }
+}
+
+impl ShmSnapshot {
+    pub fn text(&self) -> &[u8] {
+        &self.text[..self.len as usize]
+    }
+
+    /// Whether the checksum matches the id and text, which a torn read would break.
+    pub fn is_intact(&self) -> bool {
+        checksum(self.message_id, self.text()) == self.checksum
+    }
+}
+
+extern "C" {
+    fn shm_ipc_create(name: *const c_char) -> *mut ShmHeader;
+    fn shm_ipc_open(name: *const c_char) -> *mut ShmHeader;
+    fn shm_ipc_close(header: *mut ShmHeader);
+    fn shm_ipc_unlink(name: *const c_char);
+    fn shm_ipc_checksum(message_id: u32, text: *const u8, len: usize) -> u32;
+    fn shm_ipc_read(header: *const ShmHeader, out: *mut ShmSnapshot) -> u32;
+}
+
+/// The checksum C's reader expects for a message, computed by C's `shm_ipc_checksum`.
+pub fn checksum(message_id: u32, text: &[u8]) -> u32 {
+    unsafe { shm_ipc_checksum(message_id, text.as_ptr(), text.len()) }
+}
+
+impl ShmHeader {
+    /// How many readers attached so far.
+    pub fn readers(&self) -> u32 {
+        self.readers.load(Ordering::Acquire)
+    }
+
+    /// Registers a reader, as C's reader does once it opened the segment.
+    pub fn attach(&self) {
+        self.readers.fetch_add(1, Ordering::AcqRel);
+    }
+
+    /// Replaces the message with `text`, truncated to [`MAX_TEXT`] bytes.
+    ///
+    /// Only one thread in one process may write at a time: the seqlock keeps readers from
+    /// accepting a half-written message, but nothing keeps two writers apart.
+    pub fn write(&self, message_id: u32, text: &[u8]) {
+        let text = &text[..text.len().min(MAX_TEXT)];
+        let seq = self.seq.load(Ordering::Relaxed);
+        self.seq.store(seq.wrapping_add(1), Ordering::Relaxed);
+        // 让奇数的 seq 先于下面对消息的修改被看到
+        // Makes the odd seq visible before the changes to the message below
+        fence(Ordering::Release);
+        self.message_id.store(message_id, Ordering::Relaxed);
+        self.len.store(text.len() as u32, Ordering::Relaxed);
+        self.checksum
+            .store(checksum(message_id, text), Ordering::Relaxed);
+        for (i, word) in self.words.iter().enumerate() {
+            let mut bytes = [0; 4];
+            let start = (4 * i).min(text.len());
+            let chunk = &text[start..(start + 4).min(text.len())];
+            bytes[..chunk.len()].copy_from_slice(chunk);
+            word.store(u32::from_le_bytes(bytes), Ordering::Relaxed);
+        }
+        self.seq.store(seq.wrapping_add(2), Ordering::Release);
+    }
+
+    /// Reads a consistent copy of the message with C's reading side, returning it together with
+    /// how many times the read had to be repeated.
+    pub fn read(&self) -> (ShmSnapshot, u32) {
+        let mut snapshot = ShmSnapshot::default();
+        let retries = unsafe { shm_ipc_read(self, &mut snapshot) };
+        (snapshot, retries)
+    }
+
+    /// Tells readers the last message was written.
+    pub fn finish(&self) {
+        self.done.store(1, Ordering::Release);
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.done.load(Ordering::Acquire) != 0
+    }
+}
+
+/// 映射到当前进程的共享内存段
+/// A shared memory segment mapped into this process, unmapped when dropped.
+///
+/// When the process that created the segment drops it, the name is removed too; processes that
+/// still have it mapped keep using the memory.
+pub struct Segment {
+    header: NonNull<ShmHeader>,
+    name: CString,
+    owner: bool,
+}
+
+// 头部只包含原子操作访问的字段和创建后不再修改的字段
+// The header only has fields accessed atomically and fields that never change after creation
+unsafe impl Send for Segment {}
+unsafe impl Sync for Segment {}
+
+impl Segment {
+    /// Creates the segment `name`, failing if it already exists. The name starts with a `/` on
+    /// POSIX, and lives in `Local\` or `Global\` on Windows.
+    pub fn create(name: &str) -> io::Result<Segment> {
+        Segment::map(name, true)
+    }
+
+    /// Opens the segment `name` another process created.
+    pub fn open(name: &str) -> io::Result<Segment> {
+        Segment::map(name, false)
+    }
+
+    fn map(name: &str, create: bool) -> io::Result<Segment> {
+        let name =
+            CString::new(name).map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))?;
+        let header = unsafe {
+            if create {
+                shm_ipc_create(name.as_ptr())
+            } else {
+                shm_ipc_open(name.as_ptr())
+            }
+        };
+        match NonNull::new(header) {
+            Some(header) => Ok(Segment {
+                header,
+                name,
+                owner: create,
+            }),
+            None => Err(io::Error::last_os_error()),
+        }
+    }
+
+    pub fn header(&self) -> &ShmHeader {
+        // SAFETY: the mapping lives until drop
+        unsafe { self.header.as_ref() }
+    }
+}
+
+impl Drop for Segment {
+    fn drop(&mut self) {
+        unsafe {
+            shm_ipc_close(self.header.as_ptr());
+            if self.owner {
+                shm_ipc_unlink(self.name.as_ptr());
+            }
+        }
+    }
+}
+
+/// A name for a new segment that is unique to this process and `tag`.
+pub fn segment_name(tag: &str) -> String {
+    if cfg!(windows) {
+        format!("Local\\rxc_shm_{}_{}", std::process::id(), tag)
+    } else {
+        format!("/rxc_shm_{}_{}", std::process::id(), tag)
+    }
+}
+
+/// C 的读取者程序的路径，由构建脚本编译
+/// The path of C's reader program, built by the build script.
+pub const READER: &str = env!("SHM_READER");
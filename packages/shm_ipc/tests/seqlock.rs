@@ -0,0 +1,98 @@
+// 共享内存和顺序锁的测试：同一段内存映射两次，一个线程通过一份映射用 Rust 写入，另一个线程通过另一份用 C
+// 读取，读到的每条消息都完整且编号只增不减；最后端到端运行写入者和 C 的读取者两个进程
+// Tests for the shared memory and the seqlock: the same segment is mapped twice, one thread
+// writes through one mapping in Rust while another reads through the other in C, and every
+// message read is intact with ids never going backwards; finally the writer and the C reader run
+// end to end as two processes
+
+use std::process::Command;
+use std::thread;
+
+use shm_ipc::{segment_name, Segment};
+
+const MESSAGES: u32 = 20_000;
+
+#[test]
+fn readers_only_accept_intact_messages() {
+    let name = segment_name("seqlock");
+    let writer = Segment::create(&name).unwrap();
+    let reader = Segment::open(&name).unwrap();
+    assert_ne!(
+        writer.header() as *const _,
+        reader.header() as *const _,
+        "the two mappings should be at different addresses"
+    );
+
+    thread::scope(|scope| {
+        scope.spawn(|| {
+            let header = reader.header();
+            header.attach();
+            let mut last_id = 0;
+            loop {
+                let done = header.is_done();
+                let (snapshot, _) = header.read();
+                if snapshot.message_id != 0 {
+                    assert!(
+                        snapshot.is_intact(),
+                        "torn read of #{}",
+                        snapshot.message_id
+                    );
+                    assert_eq!(
+                        snapshot.text(),
+                        format!("message {}", snapshot.message_id).as_bytes()
+                    );
+                }
+                assert!(snapshot.message_id >= last_id);
+                last_id = snapshot.message_id;
+                if done {
+                    break;
+                }
+            }
+            assert_eq!(last_id, MESSAGES);
+        });
+
+        let header = writer.header();
+        while header.readers() == 0 {
+            thread::yield_now();
+        }
+        for id in 1..=MESSAGES {
+            header.write(id, format!("message {}", id).as_bytes());
+        }
+        header.finish();
+    });
+}
+
+#[test]
+fn text_is_truncated_to_the_segment() {
+    let segment = Segment::create(&segment_name("truncate")).unwrap();
+    let long = vec![b'x'; shm_ipc::MAX_TEXT + 10];
+    segment.header().write(1, &long);
+    let (snapshot, retries) = segment.header().read();
+    assert_eq!(retries, 0);
+    assert_eq!(snapshot.text(), &long[..shm_ipc::MAX_TEXT]);
+    assert!(snapshot.is_intact());
+}
+
+#[test]
+fn creating_an_existing_segment_fails() {
+    let name = segment_name("exists");
+    let _first = Segment::create(&name).unwrap();
+    assert!(Segment::create(&name).is_err());
+}
+
+#[test]
+fn opening_a_missing_segment_fails() {
+    assert!(Segment::open(&segment_name("missing")).is_err());
+}
+
+#[test]
+fn writer_and_c_reader_processes() {
+    let output = Command::new(env!("CARGO_BIN_EXE_shm_writer"))
+        .arg("5000")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{}", stdout);
+    assert!(stdout.contains("[C reader] Last message #5000: message #5000 from the Rust writer"));
+    assert!(stdout.contains("0 corrupt, 0 out of order"));
+}
@@ -0,0 +1,32 @@
+// 这是我们的构建脚本，用 cbindgen 从 Rust 源码生成 C 头文件
+// This is our build script, it generates the C header from the Rust source with cbindgen
+
+use std::{env, fs, path::PathBuf};
+
+const HEADER: &str = "staticlib_gen.h";
+
+fn main() {
+    let crate_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap());
+    let out_dir = PathBuf::from(env::var("OUT_DIR").unwrap());
+    let config = cbindgen::Config::from_file(crate_dir.join("cbindgen.toml")).unwrap();
+
+    cbindgen::Builder::new()
+        .with_crate(&crate_dir)
+        .with_config(config)
+        .generate()
+        .expect("Unable to generate the C header.")
+        .write_to_file(out_dir.join(HEADER));
+
+    // 设置 UPDATE_HEADERS=1 时把生成的头文件同步到仓库的 include 目录
+    // With UPDATE_HEADERS=1 the generated header is copied into the repository's include directory
+    if env::var_os("UPDATE_HEADERS").is_some() {
+        let include_dir = crate_dir.join("../../include");
+        fs::create_dir_all(&include_dir).unwrap();
+        fs::copy(out_dir.join(HEADER), include_dir.join(HEADER)).unwrap();
+    }
+
+    println!("cargo::rerun-if-changed=src");
+    println!("cargo::rerun-if-changed=cbindgen.toml");
+    println!("cargo::rerun-if-changed=../interop_common/src");
+    println!("cargo::rerun-if-env-changed=UPDATE_HEADERS");
+}
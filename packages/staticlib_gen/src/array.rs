@@ -0,0 +1,51 @@
+// 数组以指针加长度的形式跨越 FFI 边界，在 Rust 端还原为切片
+// Arrays cross the FFI boundary as pointer + length and become slices again on the Rust side
+
+use std::ffi;
+
+use interop_common::{
+    ensure_initialized, ffi_guard, slice_from_raw, write_out, FfiError, FfiSlice, FfiStatus,
+};
+
+/// Sums the `len` values at `values` into `total`.
+///
+/// `values` may be NULL when `len` is 0. Fails with `FFI_STATUS_OVERFLOW` if the sum does not fit
+/// in a `long`, which on Windows is only 32 bits wide.
+///
+/// # Safety
+///
+/// `values` must point to `len` readable `int`s and `total` must be valid for writes.
+#[export_name = "rxc_staticlib_sum"]
+pub unsafe extern "C" fn staticlib_sum(
+    values: *const ffi::c_int,
+    len: usize,
+    total: *mut ffi::c_long,
+) -> FfiStatus {
+    ffi_guard(|| {
+        ensure_initialized()?;
+        write_out(total, sum(slice_from_raw(values, len)?)?)
+    })
+}
+
+/// The same as `rxc_staticlib_sum`, taking the values as one `FfiSlice_c_int`.
+///
+/// # Safety
+///
+/// `values` must view `len` readable `int`s and `total` must be valid for writes.
+#[export_name = "rxc_staticlib_sum_slice"]
+pub unsafe extern "C" fn staticlib_sum_slice(
+    values: FfiSlice<ffi::c_int>,
+    total: *mut ffi::c_long,
+) -> FfiStatus {
+    ffi_guard(|| {
+        ensure_initialized()?;
+        write_out(total, sum(values.as_slice()?)?)
+    })
+}
+
+fn sum(values: &[ffi::c_int]) -> Result<ffi::c_long, FfiError> {
+    values
+        .iter()
+        .try_fold(0 as ffi::c_long, |acc, &v| acc.checked_add(v.into()))
+        .ok_or(FfiError::Overflow)
+}
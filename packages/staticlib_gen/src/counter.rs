@@ -0,0 +1,20 @@
+// 和 C 共享的静态变量：C 按导出的名字把它声明为 extern uint32_t，用原子操作读写。AtomicU32 和 u32 的大小
+// 与对齐相同，C 的 __atomic_* 内建函数和 Interlocked* 函数与 Rust 的原子操作可以同时作用于它
+// A static shared with C: C declares it by its exported name as an extern uint32_t and reads and
+// writes it with atomic operations. AtomicU32 has the size and alignment of u32, so C's
+// __atomic_* builtins and Interlocked* functions can work on it alongside Rust's atomics
+
+use std::sync::atomic::{AtomicU32, Ordering};
+
+/// A counter C and Rust update together. Only touch it with atomic operations: plain reads and
+/// writes from C race with the other side.
+#[export_name = "rxc_staticlib_counter"]
+pub static STATICLIB_COUNTER: AtomicU32 = AtomicU32::new(0);
+
+/// Adds `n` to `rxc_staticlib_counter`, wrapping around on overflow, and returns the new value.
+#[export_name = "rxc_staticlib_counter_add"]
+pub extern "C" fn staticlib_counter_add(n: u32) -> u32 {
+    STATICLIB_COUNTER
+        .fetch_add(n, Ordering::SeqCst)
+        .wrapping_add(n)
+}
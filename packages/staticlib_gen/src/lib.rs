@@ -1,19 +1,96 @@
 use std::ffi;
-use std::ptr;
 
-#[no_mangle]
-pub extern "C" fn staticlib_add(a: ffi::c_int, b: ffi::c_int, result: *mut ffi::c_char) -> ffi::c_int {
-    let sum = a + b;
-    unsafe {
-        let name = ffi::CStr::from_ptr(result).to_str().unwrap();
+mod addition;
+mod array;
+mod counter;
+mod last_error;
+mod lifecycle;
+mod logging;
+#[cfg(any(target_os = "linux", target_os = "android"))]
+mod ops;
+mod scalar;
+#[cfg(any(target_os = "linux", target_os = "android"))]
+mod square_op;
 
-        println!("[Rust staticlib] Hello {name}");
+pub use addition::{addition, hello, Addition};
+pub use array::{staticlib_sum, staticlib_sum_slice};
+pub use counter::{staticlib_counter_add, STATICLIB_COUNTER};
+pub use lifecycle::{staticlib_init, staticlib_shutdown};
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub use ops::{staticlib_ops, StaticlibOp};
+pub use scalar::{
+    staticlib_add_i64, staticlib_mul_f32, staticlib_mul_f64, staticlib_mul_high_u64,
+};
 
-        let msg = format!("[Rust staticlib] The result ({a} + {b}) is {sum}!");
+use interop_common::{
+    ensure_initialized, ffi_guard, read_cstr, write_cstr, write_out, FfiError,
+};
+pub use interop_common::{FfiSlice, FfiStatus, InitConfig, LogCallback, LogLevel};
 
-        let msg = ffi::CString::new(msg).unwrap();
+// 开启 malloc 特性后，库里所有的 Rust 分配都来自 C 运行时的堆
+// With the malloc feature on, every Rust allocation in the library comes from the C runtime's heap
+#[cfg(feature = "malloc")]
+#[global_allocator]
+static ALLOCATOR: interop_common::MallocAllocator = interop_common::MallocAllocator;
 
-        ptr::copy_nonoverlapping(msg.as_ptr(), result, msg.as_bytes().len() + 1);
-        return sum;
+/// Adds `a` and `b`, stores the sum in `sum` and writes a greeting message into `result`.
+///
+/// `result` is an in/out buffer of `result_len` bytes: on input it holds the caller's
+/// NUL-terminated name, on output the message. Like `snprintf`, the message is truncated to
+/// fit and always NUL terminated; if `required_len` is not NULL it receives the length the full
+/// message needs (excluding the NUL). Passing a NULL `result` with `result_len == 0` only
+/// queries that length.
+///
+/// Safe to call from several threads at once as long as each call has its own buffers: besides
+/// them it only touches the calling thread's last error.
+///
+/// # Safety
+///
+/// `result` must be valid for reads and writes of `result_len` bytes (it may be NULL when
+/// `result_len` is 0), `sum` must be valid for writes and `required_len` must be NULL or
+/// valid for writes.
+#[export_name = "rxc_staticlib_add"]
+pub unsafe extern "C" fn staticlib_add(
+    a: ffi::c_int,
+    b: ffi::c_int,
+    result: *mut ffi::c_char,
+    result_len: usize,
+    sum: *mut ffi::c_int,
+    required_len: *mut usize,
+) -> FfiStatus {
+    ffi_guard(|| {
+        ensure_initialized()?;
+        add(a, b, result, result_len, sum, required_len)
+    })
+}
+
+unsafe fn add(
+    a: ffi::c_int,
+    b: ffi::c_int,
+    result: *mut ffi::c_char,
+    result_len: usize,
+    sum: *mut ffi::c_int,
+    required_len: *mut usize,
+) -> Result<(), FfiError> {
+    let addition = addition(a, b)?;
+    let name = if result.is_null() && result_len == 0 {
+        ""
+    } else {
+        read_cstr(result, result_len)?
+    };
+
+    // 名字在缓冲区里，写入消息之前先生成问候；只有消息写进去之后才打印，
+    // 调用方扩大缓冲区重试时问候不会出现两次
+    // The name lives in the buffer, so the greeting is built before the message overwrites it; it
+    // is only logged once the message fits, so a caller retrying with a larger buffer doesn't see
+    // it twice
+    let greeting = hello(name);
+
+    write_out(sum, addition.sum)?;
+    if !required_len.is_null() {
+        write_out(required_len, addition.message.len())?;
     }
+    write_cstr(result, result_len, &addition.message)?;
+    log::info!("{greeting}");
+    Ok(())
 }
@@ -0,0 +1,40 @@
+// 库的初始化和关闭：其他返回状态码的导出函数要在 staticlib_init 之后才能使用
+// Initializing and shutting down the library: the other exports returning a status can only be
+// used after staticlib_init
+//
+// cdylib_gen 的对应函数叫 rustlib_init。这个库的所有导出都带 staticlib 前缀：可执行文件从 .a 中取用的
+// 成员定义的符号会抢在动态库之前，同名的导出会让链接两个库的程序调用到错误的那一份
+// cdylib_gen's counterpart is called rustlib_init. All of this library's exports carry the
+// staticlib prefix: the archive members an executable pulls in define their symbols ahead of any
+// shared library, so exports of the same names would have programs linking both libraries call
+// the wrong copy
+
+use interop_common::{ffi_guard, init, shutdown, FfiStatus, InitConfig};
+
+/// Initializes the library with `config`, or with the default configuration when `config` is
+/// NULL, and must be called before any other export returning a status.
+///
+/// Sets up logging and, if requested, the panic hook once; Rust's global allocator is fixed when
+/// the library is linked, so there is nothing to set up for it. Until this succeeds the other
+/// exports fail with `FFI_STATUS_NOT_INITIALIZED`. Calling it again before `rxc_staticlib_shutdown`
+/// fails with `FFI_STATUS_ALREADY_INITIALIZED` and keeps the first configuration.
+///
+/// Only the `size` bytes `config` declares are read, like `rxc_rustlib_init` does.
+///
+/// # Safety
+///
+/// `config` must be NULL or point to an `InitConfig` of the version its `size` declares.
+#[export_name = "rxc_staticlib_init"]
+pub unsafe extern "C" fn staticlib_init(config: *const InitConfig) -> FfiStatus {
+    ffi_guard(|| init(&InitConfig::read(config)?))
+}
+
+/// Shuts the library down again, after which the other exports fail with
+/// `FFI_STATUS_NOT_INITIALIZED` until `rxc_staticlib_init` is called again.
+///
+/// Restores writing log messages to stderr. Calls running on other threads at the same time
+/// finish normally, the log callback's `user_data` must stay valid until they return.
+#[export_name = "rxc_staticlib_shutdown"]
+pub extern "C" fn staticlib_shutdown() -> FfiStatus {
+    ffi_guard(shutdown)
+}
@@ -0,0 +1,83 @@
+// 通过链接段收集的操作表：每个操作是放在 staticlib_op_table 段中的一个 StaticlibOp，链接器把所有
+// 目标文件中的这个段拼在一起，staticlib_ops 通过链接器生成的 __start_/__stop_ 符号找到整张表。没有代码
+// 按名字引用这些条目，所以只有被链接进来的目标文件中的条目才会出现：从静态库链接时，链接器只取出解析了
+// 未定义符号的成员，只被这张表引用的条目（例如 square_op.rs 中的 square）会被丢掉，除非用
+// --whole-archive（Rust 中是 +whole-archive）链接整个静态库
+// A table of operations collected through a link section: each operation is a StaticlibOp
+// placed in the staticlib_op_table section, the linker concatenates that section from every
+// object file, and staticlib_ops finds the whole table through the __start_/__stop_ symbols the
+// linker generates. No code refers to the entries by name, so only the entries of object files
+// that get linked in show up: linking from a static archive, the linker only pulls out the
+// members that resolve an undefined symbol, and an entry referenced by nothing but this table
+// (such as square in square_op.rs) is dropped unless the whole archive is linked with
+// --whole-archive (+whole-archive in Rust)
+//
+// __start_/__stop_ 是 ELF 链接器的约定，其他平台上没有这张表
+// __start_/__stop_ are a convention of ELF linkers, there is no table on other platforms
+
+use std::ffi;
+
+use interop_common::{ffi_guard, write_out, FfiStatus};
+
+/// An operation in the table `rxc_staticlib_ops` returns.
+#[repr(C)]
+#[derive(Debug)]
+pub struct StaticlibOp {
+    /// The name of the operation, NUL terminated.
+    pub name: *const ffi::c_char,
+    /// Applies the operation to `value`.
+    pub apply: extern "C" fn(value: ffi::c_int) -> ffi::c_int,
+}
+
+// 条目只包含指向静态数据和函数的指针，在线程间共享是安全的
+// The entries only hold pointers to static data and functions, sharing them between threads is
+// safe
+unsafe impl Sync for StaticlibOp {}
+
+/// Declares a [`StaticlibOp`] in the `staticlib_op_table` link section.
+macro_rules! register_op {
+    ($entry:ident, $name:literal, $apply:path) => {
+        #[used]
+        #[link_section = "staticlib_op_table"]
+        static $entry: $crate::ops::StaticlibOp = $crate::ops::StaticlibOp {
+            name: concat!($name, "\0").as_ptr().cast(),
+            apply: $apply,
+        };
+    };
+}
+pub(crate) use register_op;
+
+extern "C" {
+    static __start_staticlib_op_table: StaticlibOp;
+    static __stop_staticlib_op_table: StaticlibOp;
+}
+
+// staticlib_ops 引用的这一项保证表不为空，链接器总会生成 __start_/__stop_ 符号
+// Referenced by staticlib_ops, this entry keeps the table from being empty, so the linker always
+// generates the __start_/__stop_ symbols
+extern "C" fn identity(value: ffi::c_int) -> ffi::c_int {
+    value
+}
+
+register_op!(IDENTITY, "identity", identity);
+
+/// Stores the table of operations linked into the program in `ops` and its length in `len`.
+///
+/// Which entries the table has depends on how the library was linked: ones referenced by nothing
+/// else, such as `square`, only survive when the whole archive is linked.
+///
+/// # Safety
+///
+/// `ops` and `len` must be valid for writes.
+#[export_name = "rxc_staticlib_ops"]
+pub unsafe extern "C" fn staticlib_ops(ops: *mut *const StaticlibOp, len: *mut usize) -> FfiStatus {
+    ffi_guard(|| {
+        // 直接引用 IDENTITY，它所在的目标文件总和这个函数一起被链接进来
+        // Refers to IDENTITY directly, so its object file is always linked in with this function
+        std::hint::black_box(&IDENTITY);
+        let start: *const StaticlibOp = &__start_staticlib_op_table;
+        let stop: *const StaticlibOp = &__stop_staticlib_op_table;
+        write_out(ops, start)?;
+        write_out(len, stop.offset_from(start) as usize)
+    })
+}
@@ -0,0 +1,29 @@
+// int 以外的标量按值跨越 FFI 边界：Rust 的 f32 / f64 / i64 / u64 / usize / bool 分别是 C 的 float、double、
+// int64_t、uint64_t、size_t 和 bool，大小、对齐和调用约定中的传递方式都相同，不需要任何转换
+// Scalars other than int crossing the FFI boundary by value: Rust's f32 / f64 / i64 / u64 / usize /
+// bool are C's float, double, int64_t, uint64_t, size_t and bool, with the same size, alignment
+// and way of being passed in the calling convention, so no conversion is needed
+
+/// Returns `a * b`, following IEEE 754 for infinities, NaN and signed zeros.
+#[export_name = "rxc_staticlib_mul_f64"]
+pub extern "C" fn staticlib_mul_f64(a: f64, b: f64) -> f64 {
+    a * b
+}
+
+/// Returns `a * b` in single precision.
+#[export_name = "rxc_staticlib_mul_f32"]
+pub extern "C" fn staticlib_mul_f32(a: f32, b: f32) -> f32 {
+    a * b
+}
+
+/// Returns `a + b`, wrapping around on overflow.
+#[export_name = "rxc_staticlib_add_i64"]
+pub extern "C" fn staticlib_add_i64(a: i64, b: i64) -> i64 {
+    a.wrapping_add(b)
+}
+
+/// Returns the high 64 bits of the 128-bit product `a * b`.
+#[export_name = "rxc_staticlib_mul_high_u64"]
+pub extern "C" fn staticlib_mul_high_u64(a: u64, b: u64) -> u64 {
+    ((a as u128 * b as u128) >> 64) as u64
+}
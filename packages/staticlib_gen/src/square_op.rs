@@ -0,0 +1,15 @@
+// 只通过 staticlib_ops 返回的表引用的操作：没有任何符号指向这个文件，从静态库链接时它所在的成员不会被
+// 取出，除非链接整个静态库
+// An operation referenced only through the table staticlib_ops returns: no symbol points into this
+// file, so linking from the static archive never pulls out its member unless the whole archive is
+// linked
+
+use std::ffi;
+
+use crate::ops::register_op;
+
+extern "C" fn square(value: ffi::c_int) -> ffi::c_int {
+    value.wrapping_mul(value)
+}
+
+register_op!(SQUARE, "square", square);
@@ -0,0 +1,52 @@
+// 用 proptest 生成任意名字、操作数和缓冲区大小，检查 staticlib_add 写回的消息
+// Generates arbitrary names, operands and buffer sizes with proptest and checks the message
+// staticlib_add writes back
+
+use std::ffi::{c_char, c_int, CStr};
+
+use proptest::prelude::*;
+use staticlib_gen::{staticlib_add, FfiStatus};
+
+mod common;
+use common::init;
+
+// 不会溢出的操作数、不含 NUL 的名字，以及能放下名字、落在消息长度附近的容量
+// Operands that do not overflow, a NUL-free name and a capacity that holds the name and falls
+// around the message length
+fn call() -> impl Strategy<Value = (c_int, c_int, String, usize)> {
+    (any::<c_int>(), any::<c_int>(), "[^\\x00]{0,48}")
+        .prop_filter("a + b overflows", |(a, b, _)| a.checked_add(*b).is_some())
+        .prop_flat_map(|(a, b, name)| {
+            let len = format!("[Rust staticlib] The result ({a} + {b}) is {}!", a + b).len();
+            let min = name.len() + 1;
+            let cap = prop_oneof![min.max(len - 1)..=min.max(len + 2), min..min + 64];
+            (Just(a), Just(b), Just(name), cap)
+        })
+}
+
+proptest! {
+    #[test]
+    fn message_round_trips((a, b, name, cap) in call()) {
+        init();
+        let expected = format!("[Rust staticlib] The result ({a} + {b}) is {}!", a + b);
+        let mut buf = vec![0 as c_char; cap];
+        for (dst, &src) in buf.iter_mut().zip(name.as_bytes()) {
+            *dst = src as c_char;
+        }
+        let (mut sum, mut required) = (0, 0);
+        let status = unsafe { staticlib_add(a, b, buf.as_mut_ptr(), cap, &mut sum, &mut required) };
+
+        prop_assert_eq!(sum, a + b);
+        prop_assert_eq!(required, expected.len());
+        let message = unsafe { CStr::from_ptr(buf.as_ptr()) }.to_str().unwrap();
+        if expected.len() < cap {
+            prop_assert_eq!(status, FfiStatus::Ok);
+            prop_assert_eq!(message, expected);
+        } else {
+            // 消息只含 ASCII，截断后仍是合法的 UTF-8
+            // The message is ASCII only, so it stays valid UTF-8 after truncation
+            prop_assert_eq!(status, FfiStatus::BufferTooSmall);
+            prop_assert_eq!(message, &expected[..cap - 1]);
+        }
+    }
+}
@@ -0,0 +1,49 @@
+// staticlib_add 背后不含指针的核心逻辑的测试，也可以在 Miri 下运行
+// Tests for the pointer-free core behind staticlib_add, which also run under Miri
+//
+// 运行 / Run: cargo +nightly miri test -p staticlib_gen --test addition
+
+use std::ffi::c_int;
+
+use interop_common::FfiError;
+use staticlib_gen::{addition, hello, Addition};
+
+#[test]
+fn adds_and_formats_the_message() {
+    assert_eq!(
+        addition(2, 3),
+        Ok(Addition {
+            sum: 5,
+            message: "[Rust staticlib] The result (2 + 3) is 5!".to_string(),
+        })
+    );
+}
+
+#[test]
+fn formats_negative_operands() {
+    let addition = addition(-7, 2).unwrap();
+    assert_eq!(addition.sum, -5);
+    assert_eq!(
+        addition.message,
+        "[Rust staticlib] The result (-7 + 2) is -5!"
+    );
+}
+
+#[test]
+fn accepts_the_extremes_of_int() {
+    assert_eq!(addition(c_int::MAX, 0).unwrap().sum, c_int::MAX);
+    assert_eq!(addition(c_int::MIN, 0).unwrap().sum, c_int::MIN);
+    assert_eq!(addition(c_int::MAX, c_int::MIN).unwrap().sum, -1);
+}
+
+#[test]
+fn reports_overflow_in_both_directions() {
+    assert_eq!(addition(c_int::MAX, 1), Err(FfiError::Overflow));
+    assert_eq!(addition(c_int::MIN, -1), Err(FfiError::Overflow));
+}
+
+#[test]
+fn greets_by_name() {
+    assert_eq!(hello("Lee"), "[Rust staticlib] Hello Lee");
+    assert_eq!(hello(""), "[Rust staticlib] Hello ");
+}
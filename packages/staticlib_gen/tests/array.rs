@@ -0,0 +1,82 @@
+// staticlib_sum 的切片边界测试
+// Slice boundary tests for staticlib_sum
+
+use std::ffi::{c_int, c_long};
+use std::ptr;
+
+use staticlib_gen::{staticlib_sum, staticlib_sum_slice, FfiSlice, FfiStatus};
+
+mod common;
+use common::init;
+
+#[test]
+fn sums_all_values() {
+    init();
+    let values = [1, 2, 3, 4];
+    let mut total = 0;
+    let status = unsafe { staticlib_sum(values.as_ptr(), values.len(), &mut total) };
+    assert_eq!(status, FfiStatus::Ok);
+    assert_eq!(total, 10);
+}
+
+#[test]
+fn only_reads_len_values() {
+    init();
+    let values = [1, 2, 3, 4];
+    let mut total = 0;
+    unsafe { staticlib_sum(values.as_ptr(), 2, &mut total) };
+    assert_eq!(total, 3);
+}
+
+#[test]
+fn empty_slice_may_be_null() {
+    init();
+    let mut total = -1;
+    let status = unsafe { staticlib_sum(ptr::null(), 0, &mut total) };
+    assert_eq!(status, FfiStatus::Ok);
+    assert_eq!(total, 0);
+}
+
+#[test]
+fn rejects_null_with_length() {
+    init();
+    let mut total: c_long = 0;
+    let status = unsafe { staticlib_sum(ptr::null(), 3, &mut total) };
+    assert_eq!(status, FfiStatus::NullPointer);
+}
+
+#[test]
+fn reports_overflow_of_long() {
+    init();
+    let values = [c_int::MAX; 3];
+    let mut total = 0;
+    let status = unsafe { staticlib_sum(values.as_ptr(), values.len(), &mut total) };
+    if std::mem::size_of::<c_long>() == std::mem::size_of::<c_int>() {
+        assert_eq!(status, FfiStatus::Overflow);
+    } else {
+        assert_eq!(status, FfiStatus::Ok);
+        assert_eq!(total, 3 * c_int::MAX as c_long);
+    }
+}
+
+#[test]
+fn sums_an_ffi_slice() {
+    init();
+    let values = [1, 2, 3, 4];
+    let mut total = 0;
+    let status = unsafe { staticlib_sum_slice(FfiSlice::new(&values[1..]), &mut total) };
+    assert_eq!(status, FfiStatus::Ok);
+    assert_eq!(total, 9);
+}
+
+#[test]
+fn rejects_null_ffi_slice_with_length() {
+    init();
+    let values = FfiSlice {
+        ptr: ptr::null(),
+        len: 3,
+    };
+    let mut total: c_long = 0;
+    let status = unsafe { staticlib_sum_slice(values, &mut total) };
+    assert_eq!(status, FfiStatus::NullPointer);
+}
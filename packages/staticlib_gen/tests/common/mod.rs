@@ -0,0 +1,17 @@
+// 集成测试共用的代码，测试文件用 mod common; 引入
+// Code shared by the integration tests, which pull it in with mod common;
+
+use std::ptr;
+use std::sync::Once;
+
+use staticlib_gen::{staticlib_init, FfiStatus};
+
+/// Initializes the library once for the whole test binary. The tests run in any order and on
+/// several threads, so each one calls this first.
+pub fn init() {
+    static INIT: Once = Once::new();
+    INIT.call_once(|| {
+        let status = unsafe { staticlib_init(ptr::null()) };
+        assert_eq!(status, FfiStatus::Ok);
+    });
+}
@@ -0,0 +1,30 @@
+// staticlib_init 之前和 staticlib_shutdown 之后导出函数返回 NOT_INITIALIZED
+// Exports return NOT_INITIALIZED before staticlib_init and after staticlib_shutdown
+
+use std::ffi::c_long;
+use std::ptr;
+
+use staticlib_gen::{staticlib_init, staticlib_shutdown, staticlib_sum, FfiStatus};
+
+fn sum() -> FfiStatus {
+    let mut total: c_long = 0;
+    unsafe { staticlib_sum([1, 2].as_ptr(), 2, &mut total) }
+}
+
+// 初始化状态是进程全局的，所以全部放在一个测试里按顺序检查
+// The initialization state is process-wide, so everything is checked in order within a single test
+#[test]
+fn exports_follow_init_and_shutdown() {
+    assert_eq!(sum(), FfiStatus::NotInitialized);
+    assert_eq!(staticlib_shutdown(), FfiStatus::NotInitialized);
+
+    assert_eq!(unsafe { staticlib_init(ptr::null()) }, FfiStatus::Ok);
+    assert_eq!(
+        unsafe { staticlib_init(ptr::null()) },
+        FfiStatus::AlreadyInitialized
+    );
+    assert_eq!(sum(), FfiStatus::Ok);
+
+    assert_eq!(staticlib_shutdown(), FfiStatus::Ok);
+    assert_eq!(sum(), FfiStatus::NotInitialized);
+}
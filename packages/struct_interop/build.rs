@@ -0,0 +1,13 @@
+// 这是我们的构建脚本，编译和 Rust 共享 Point、对齐的结构体和按值返回的结构体的 C 代码
+// This is our build script, it compiles the C code sharing the Point, the aligned structs and the
+// structs returned by value with Rust
+
+fn main() {
+    cc::Build::new()
+        .file("c/point.c")
+        .file("c/aligned.c")
+        .file("c/by_value.c")
+        .std("c11")
+        .compile("point");
+    println!("cargo::rerun-if-changed=c");
+}
@@ -0,0 +1,102 @@
+// 这个文件通过指针使用与 Rust 共享的 16 字节对齐的结构体；有 SSE 时用 _mm_load_ps 和 _mm_store_ps，
+// 它们要求地址按 16 字节对齐，对齐错误的指针在调用之前就被拒绝
+// This file uses the 16-byte aligned structs shared with Rust through pointers; with SSE it uses
+// _mm_load_ps and _mm_store_ps, which require 16-byte aligned addresses, so misaligned pointers are
+// refused before they get there
+#include <stdlib.h>
+#include "aligned.h"
+
+#if defined(__SSE__) || defined(_M_X64) || (defined(_M_IX86_FP) && _M_IX86_FP >= 1)
+#include <xmmintrin.h>
+#define HAVE_SSE 1
+#endif
+
+static int is_aligned(const void *p)
+{
+    return p != NULL && (uintptr_t)p % _Alignof(Vec4) == 0;
+}
+
+size_t c_particle_size(void)
+{
+    return sizeof(Particle);
+}
+
+size_t c_particle_align(void)
+{
+    return _Alignof(Particle);
+}
+
+static void add_scaled(const Vec4 *a, const Vec4 *b, float factor, Vec4 *out)
+{
+#ifdef HAVE_SSE
+    __m128 scaled = _mm_mul_ps(_mm_load_ps(b->lanes), _mm_set1_ps(factor));
+    _mm_store_ps(out->lanes, _mm_add_ps(_mm_load_ps(a->lanes), scaled));
+#else
+    for (int i = 0; i < 4; i++)
+    {
+        out->lanes[i] = a->lanes[i] + b->lanes[i] * factor;
+    }
+#endif
+}
+
+int c_vec4_add(const Vec4 *a, const Vec4 *b, Vec4 *out)
+{
+    if (!is_aligned(a) || !is_aligned(b) || !is_aligned(out))
+    {
+        return -1;
+    }
+    add_scaled(a, b, 1.0f, out);
+    return 0;
+}
+
+int c_particle_step(Particle *p, float dt)
+{
+    if (!is_aligned(p))
+    {
+        return -1;
+    }
+    add_scaled(&p->position, &p->velocity, dt, &p->position);
+    return 0;
+}
+
+// 反方向：C 把自己的对齐的结构体借给 Rust
+// The other direction: C lends its aligned structs to Rust
+int c_particle_energy_with_rust(const Particle *p, float *energy)
+{
+    if (!is_aligned(p) || energy == NULL)
+    {
+        return -1;
+    }
+    float speed_squared;
+    if (rust_vec4_dot(&p->velocity, &p->velocity, &speed_squared) != 0)
+    {
+        return -1;
+    }
+    *energy = speed_squared / 2.0f;
+    return 0;
+}
+
+// malloc 只保证 max_align_t 的对齐，超过它的类型需要专门的分配函数
+// malloc only guarantees the alignment of max_align_t, types beyond it need a dedicated allocator
+Particle *c_particle_new(uint32_t id)
+{
+#ifdef _MSC_VER
+    Particle *p = _aligned_malloc(sizeof(Particle), _Alignof(Particle));
+#else
+    Particle *p = aligned_alloc(_Alignof(Particle), sizeof(Particle));
+#endif
+    if (p != NULL)
+    {
+        *p = (Particle){.id = id};
+    }
+    return p;
+}
+
+void c_particle_free(Particle *p)
+{
+#ifdef _MSC_VER
+    _aligned_free(p);
+#else
+    free(p);
+#endif
+}
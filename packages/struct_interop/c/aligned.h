@@ -0,0 +1,53 @@
+// 超过默认对齐的结构体在 C 端的定义，必须和 src/aligned.rs 中的 #[repr(C, align(16))] 类型保持一致。
+// Vec4 的四个 float 正好是一个 SSE 寄存器（__m128），_Alignas(16) 让 _mm_load_ps 这样要求 16 字节对齐的
+// 指令可以直接读写它。MSVC 在 x86 上不能按值传递超过对齐的参数，所以这些结构体在两个方向上都通过指针传递
+// The C definitions of the over-aligned structs, they must match the #[repr(C, align(16))] types
+// in src/aligned.rs. The four floats of a Vec4 fill exactly one SSE register (__m128), and
+// _Alignas(16) lets instructions requiring 16-byte alignment such as _mm_load_ps read and write
+// it directly. MSVC can't pass over-aligned arguments by value on x86, so the structs go through
+// pointers in both directions
+#ifndef ALIGNED_H
+#define ALIGNED_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+typedef struct Vec4
+{
+    _Alignas(16) float lanes[4];
+} Vec4;
+
+typedef struct Particle
+{
+    Vec4 position;
+    Vec4 velocity;
+    uint32_t id;
+} Particle;
+
+// 布局断言，Rust 端有对应的编译期断言；Particle 的末尾补齐到 16 的倍数
+// Layout assertions, mirrored by compile-time assertions on the Rust side; the end of Particle is
+// padded to a multiple of 16
+_Static_assert(sizeof(Vec4) == 16, "Vec4 must be 16 bytes");
+_Static_assert(_Alignof(Vec4) == 16, "Vec4 must be 16-byte aligned");
+_Static_assert(sizeof(Particle) == 48, "Particle must be 48 bytes");
+_Static_assert(_Alignof(Particle) == 16, "Particle must be 16-byte aligned");
+_Static_assert(offsetof(Particle, position) == 0, "Particle.position must be at offset 0");
+_Static_assert(offsetof(Particle, velocity) == 16, "Particle.velocity must be at offset 16");
+_Static_assert(offsetof(Particle, id) == 32, "Particle.id must be at offset 32");
+
+// 由 C 实现，Rust 调用；返回 int 的函数成功时返回 0，指针为 NULL 或没有按 16 字节对齐时返回 -1
+// Implemented in C, called from Rust; the functions returning int return 0 on success and -1
+// when a pointer is NULL or not 16-byte aligned
+size_t c_particle_size(void);
+size_t c_particle_align(void);
+int c_vec4_add(const Vec4 *a, const Vec4 *b, Vec4 *out);
+int c_particle_step(Particle *p, float dt);
+int c_particle_energy_with_rust(const Particle *p, float *energy);
+Particle *c_particle_new(uint32_t id);
+void c_particle_free(Particle *p);
+
+// 由 Rust 实现，C 调用
+// Implemented in Rust, called from C
+int rust_vec4_dot(const Vec4 *a, const Vec4 *b, float *out);
+
+#endif
@@ -0,0 +1,54 @@
+// 这个文件按值返回与 Rust 共享的小结构体，并检查 Rust 按值返回的同样的结构体
+// This file returns the small structs shared with Rust by value and checks the same structs
+// returned by value from Rust
+#include "by_value.h"
+
+// 每个字段都由 seed 推出，和 Rust 端的算法相同，错位或截断的返回值不会碰巧相等
+// Every field is derived from seed the same way as on the Rust side, so a shifted or truncated
+// return value can't compare equal by accident
+Pair32 c_make_pair32(int32_t seed)
+{
+    return (Pair32){seed, -seed - 1};
+}
+
+Pair64 c_make_pair64(int64_t seed)
+{
+    return (Pair64){seed, seed * 3 + 1};
+}
+
+Mixed16 c_make_mixed16(int64_t seed)
+{
+    return (Mixed16){(double)seed + 0.5, -seed};
+}
+
+Triple64 c_make_triple64(int64_t seed)
+{
+    return (Triple64){seed, seed + 1000, seed * -7};
+}
+
+Triple64 c_triple64_rotate(Triple64 t)
+{
+    return (Triple64){t.b, t.c, t.a};
+}
+
+int32_t c_check_rust_returns(int64_t seed)
+{
+    int32_t mismatches = 0;
+
+    Pair32 p32 = rust_make_pair32((int32_t)seed), q32 = c_make_pair32((int32_t)seed);
+    mismatches += p32.a != q32.a || p32.b != q32.b;
+
+    Pair64 p64 = rust_make_pair64(seed), q64 = c_make_pair64(seed);
+    mismatches += p64.a != q64.a || p64.b != q64.b;
+
+    Mixed16 m = rust_make_mixed16(seed), n = c_make_mixed16(seed);
+    mismatches += m.x != n.x || m.n != n.n;
+
+    Triple64 t = rust_make_triple64(seed), u = c_make_triple64(seed);
+    mismatches += t.a != u.a || t.b != u.b || t.c != u.c;
+
+    Triple64 r = rust_triple64_rotate(u), s = c_triple64_rotate(u);
+    mismatches += r.a != s.a || r.b != s.b || r.c != s.c;
+
+    return mismatches;
+}
@@ -0,0 +1,73 @@
+// 按值返回的小结构体，必须和 src/by_value.rs 中的 #[repr(C)] 类型保持一致。返回方式由调用约定决定：
+// x86_64 的 System V 把不超过 16 字节的结构体放在 RAX/RDX 或 XMM 寄存器中返回，Windows x64 只有 1、2、4、8
+// 字节的结构体放在 RAX 中，AArch64 不超过 16 字节的放在 x0/x1 中；更大的结构体由调用方分配内存，通过隐藏的
+// 指针参数（sret）传入。两个编译器对同一个结构体选择不同的方式时，返回的值会是垃圾或者破坏栈
+// Small structs returned by value, they must match the #[repr(C)] types in src/by_value.rs. How
+// they come back is up to the calling convention: System V on x86_64 returns structs of up to 16
+// bytes in RAX/RDX or XMM registers, Windows x64 only returns structs of 1, 2, 4 or 8 bytes in
+// RAX, AArch64 returns up to 16 bytes in x0/x1; bigger structs go into memory the caller allocates
+// and passes as a hidden pointer argument (sret). When two compilers pick different ways for the
+// same struct the returned value is garbage or the stack gets corrupted
+#ifndef BY_VALUE_H
+#define BY_VALUE_H
+
+#include <stdint.h>
+
+// 8 字节，各平台都在一个寄存器中返回
+// 8 bytes, returned in one register everywhere
+typedef struct Pair32
+{
+    int32_t a;
+    int32_t b;
+} Pair32;
+
+// 16 字节，System V 和 AArch64 用两个寄存器返回，Windows x64 用 sret
+// 16 bytes, returned in two registers on System V and AArch64 and through sret on Windows x64
+typedef struct Pair64
+{
+    int64_t a;
+    int64_t b;
+} Pair64;
+
+// 16 字节，一个 double 一个整数：System V 分别放在 XMM0 和 RAX 中
+// 16 bytes of a double and an integer: System V splits them between XMM0 and RAX
+typedef struct Mixed16
+{
+    double x;
+    int64_t n;
+} Mixed16;
+
+// 24 字节，所有这些平台都用 sret
+// 24 bytes, returned through sret on all of these platforms
+typedef struct Triple64
+{
+    int64_t a;
+    int64_t b;
+    int64_t c;
+} Triple64;
+
+_Static_assert(sizeof(Pair32) == 8, "Pair32 must be 8 bytes");
+_Static_assert(sizeof(Pair64) == 16, "Pair64 must be 16 bytes");
+_Static_assert(sizeof(Mixed16) == 16, "Mixed16 must be 16 bytes");
+_Static_assert(sizeof(Triple64) == 24, "Triple64 must be 24 bytes");
+
+// 由 C 实现，Rust 调用
+// Implemented in C, called from Rust
+Pair32 c_make_pair32(int32_t seed);
+Pair64 c_make_pair64(int64_t seed);
+Mixed16 c_make_mixed16(int64_t seed);
+Triple64 c_make_triple64(int64_t seed);
+Triple64 c_triple64_rotate(Triple64 t);
+// 调用下面每个 Rust 函数，和 C 自己的结果比较，返回不一致的个数
+// Calls every Rust function below and compares with C's own results, returning how many disagree
+int32_t c_check_rust_returns(int64_t seed);
+
+// 由 Rust 实现，C 调用；和上面对应的 C 函数返回相同的值
+// Implemented in Rust, called from C; they return the same values as the C functions above
+Pair32 rust_make_pair32(int32_t seed);
+Pair64 rust_make_pair64(int64_t seed);
+Mixed16 rust_make_mixed16(int64_t seed);
+Triple64 rust_make_triple64(int64_t seed);
+Triple64 rust_triple64_rotate(Triple64 t);
+
+#endif
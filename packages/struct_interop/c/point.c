@@ -0,0 +1,26 @@
+// 这个文件按值和按指针使用与 Rust 共享的 Point 结构体
+// This file uses the Point struct shared with Rust, both by value and by pointer
+#include <stdio.h>
+#include "point.h"
+
+Point c_point_translate(Point p, int32_t dx, int32_t dy)
+{
+    Point moved = {p.x + dx, p.y + dy};
+    printf("[C struct] Translated (%d, %d) to (%d, %d)\n", p.x, p.y, moved.x, moved.y);
+    return moved;
+}
+
+void c_point_scale(Point *p, int32_t factor)
+{
+    p->x *= factor;
+    p->y *= factor;
+}
+
+// 反方向：C 按值把结构体传给 Rust 并接收按值返回的结果
+// The other direction: C passes structs to Rust by value and receives one back by value
+Point c_point_sum_with_rust(const Point *a, const Point *b)
+{
+    Point sum = rust_point_add(*a, *b);
+    printf("[C struct] Rust added the points to (%d, %d)\n", sum.x, sum.y);
+    return sum;
+}
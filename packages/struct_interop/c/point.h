@@ -0,0 +1,34 @@
+// Point 结构体在 C 端的定义，必须和 src/lib.rs 中的 #[repr(C)] struct Point 保持一致
+// The C definition of Point, it must match the #[repr(C)] struct Point in src/lib.rs
+#ifndef POINT_H
+#define POINT_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+typedef struct Point
+{
+    int32_t x;
+    int32_t y;
+} Point;
+
+// 布局断言，Rust 端有对应的编译期断言
+// Layout assertions, mirrored by compile-time assertions on the Rust side
+_Static_assert(sizeof(Point) == 8, "Point must be 8 bytes");
+_Static_assert(_Alignof(Point) == 4, "Point must be 4-byte aligned");
+_Static_assert(offsetof(Point, x) == 0, "Point.x must be at offset 0");
+_Static_assert(offsetof(Point, y) == 4, "Point.y must be at offset 4");
+
+// 由 C 实现，Rust 调用
+// Implemented in C, called from Rust
+Point c_point_translate(Point p, int32_t dx, int32_t dy);
+void c_point_scale(Point *p, int32_t factor);
+Point c_point_sum_with_rust(const Point *a, const Point *b);
+
+// 由 Rust 实现，C 调用
+// Implemented in Rust, called from C
+Point rust_point_add(Point a, Point b);
+int64_t rust_point_dot(const Point *a, const Point *b);
+void rust_point_swap(Point *p);
+
+#endif
@@ -0,0 +1,170 @@
+// 超过默认对齐的结构体：Vec4 是 16 字节对齐的四个 f32，和 C 的 _Alignas(16) 以及 SSE 的 __m128 一致；
+// Particle 包含两个 Vec4，因此也按 16 字节对齐。这些结构体只通过指针跨越边界，两边在使用指针之前都在运行时
+// 检查它的对齐，C 分配的 Particle 也要经过同样的检查才交给 Rust
+// Over-aligned structs: Vec4 is four f32s aligned to 16 bytes, matching C's _Alignas(16) and SSE's
+// __m128; Particle holds two Vec4s and is 16-byte aligned as well. The structs only cross the
+// boundary through pointers, both sides check a pointer's alignment at runtime before using it,
+// and a Particle C allocated goes through the same check before Rust gets it
+
+use std::mem;
+use std::ops::{Deref, DerefMut};
+use std::ptr::NonNull;
+
+/// 四个 f32 组成的向量，和 c/aligned.h 中的 Vec4 相同
+/// A vector of four `f32`s laid out exactly like `Vec4` in c/aligned.h.
+#[repr(C, align(16))]
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Vec4 {
+    pub lanes: [f32; 4],
+}
+
+/// A particle laid out exactly like `struct Particle` in c/aligned.h.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Particle {
+    pub position: Vec4,
+    pub velocity: Vec4,
+    pub id: u32,
+}
+
+// 布局断言，C 端的 aligned.h 中有对应的 _Static_assert
+// Layout assertions, mirrored by the _Static_asserts in aligned.h
+const _: () = assert!(mem::size_of::<Vec4>() == 16);
+const _: () = assert!(mem::align_of::<Vec4>() == 16);
+const _: () = assert!(mem::size_of::<Particle>() == 48);
+const _: () = assert!(mem::align_of::<Particle>() == 16);
+const _: () = assert!(mem::offset_of!(Particle, position) == 0);
+const _: () = assert!(mem::offset_of!(Particle, velocity) == 16);
+const _: () = assert!(mem::offset_of!(Particle, id) == 32);
+
+extern "C" {
+    fn c_particle_size() -> usize;
+    fn c_particle_align() -> usize;
+    fn c_vec4_add(a: *const Vec4, b: *const Vec4, out: *mut Vec4) -> i32;
+    fn c_particle_step(p: *mut Particle, dt: f32) -> i32;
+    fn c_particle_energy_with_rust(p: *const Particle, energy: *mut f32) -> i32;
+    fn c_particle_new(id: u32) -> *mut Particle;
+    fn c_particle_free(p: *mut Particle);
+}
+
+/// Whether `p` is non-NULL and aligned for `T`.
+pub fn is_aligned<T>(p: *const T) -> bool {
+    !p.is_null() && p.is_aligned()
+}
+
+// 在 x86_64 上 SSE 总是可用的，Vec4 和 __m128 可以用对齐的读写指令互相转换
+// SSE is always available on x86_64, so Vec4 and __m128 convert into each other with aligned
+// loads and stores
+#[cfg(target_arch = "x86_64")]
+mod simd {
+    use std::arch::x86_64::{__m128, _mm_load_ps, _mm_store_ps};
+
+    use super::Vec4;
+
+    impl From<Vec4> for __m128 {
+        fn from(v: Vec4) -> __m128 {
+            // SAFETY: Vec4 is 16-byte aligned, as _mm_load_ps requires
+            unsafe { _mm_load_ps(v.lanes.as_ptr()) }
+        }
+    }
+
+    impl From<__m128> for Vec4 {
+        fn from(v: __m128) -> Vec4 {
+            let mut out = Vec4::default();
+            // SAFETY: as above, for _mm_store_ps
+            unsafe { _mm_store_ps(out.lanes.as_mut_ptr(), v) };
+            out
+        }
+    }
+}
+
+/// The size and alignment of `Particle` as C sees them.
+pub fn c_particle_layout() -> (usize, usize) {
+    unsafe { (c_particle_size(), c_particle_align()) }
+}
+
+/// Adds two vectors in C, with SSE where the C compiler has it.
+pub fn vec4_add(a: &Vec4, b: &Vec4) -> Vec4 {
+    let mut sum = Vec4::default();
+    let status = unsafe { c_vec4_add(a, b, &mut sum) };
+    // 引用总是对齐的，C 不会拒绝它们
+    // References are always aligned, C never refuses them
+    debug_assert_eq!(status, 0);
+    sum
+}
+
+/// Moves `p` along its velocity for `dt` in C.
+pub fn step(p: &mut Particle, dt: f32) {
+    let status = unsafe { c_particle_step(p, dt) };
+    debug_assert_eq!(status, 0);
+}
+
+/// Asks C for the kinetic energy of `p` with a mass of 1, which C computes by calling back into
+/// [`rust_vec4_dot`].
+pub fn energy_with_rust(p: &Particle) -> f32 {
+    let mut energy = 0.0;
+    let status = unsafe { c_particle_energy_with_rust(p, &mut energy) };
+    debug_assert_eq!(status, 0);
+    energy
+}
+
+/// Computes the dot product of two vectors C passes by pointer into `out`, returning 0, or -1
+/// when a pointer is NULL or not 16-byte aligned.
+///
+/// # Safety
+///
+/// `a` and `b` must each be NULL or point to a valid `Vec4`, and `out` must be NULL or valid for
+/// writes.
+#[no_mangle]
+pub unsafe extern "C" fn rust_vec4_dot(a: *const Vec4, b: *const Vec4, out: *mut f32) -> i32 {
+    if !is_aligned(a) || !is_aligned(b) || !is_aligned(out) {
+        return -1;
+    }
+    let (a, b) = (&*a, &*b);
+    *out = a.lanes.iter().zip(&b.lanes).map(|(x, y)| x * y).sum();
+    0
+}
+
+/// 由 C 分配和释放的 Particle
+/// A `Particle` allocated by C's aligned allocator, freed by C when dropped.
+pub struct CParticle {
+    raw: NonNull<Particle>,
+}
+
+impl CParticle {
+    /// Has C allocate a zeroed particle, or returns `None` when it can't.
+    ///
+    /// # Panics
+    ///
+    /// If C returns memory that isn't aligned for `Particle`.
+    pub fn new(id: u32) -> Option<CParticle> {
+        let raw = NonNull::new(unsafe { c_particle_new(id) })?;
+        assert!(
+            raw.as_ptr().is_aligned(),
+            "C allocated a misaligned Particle at {:p}",
+            raw
+        );
+        Some(CParticle { raw })
+    }
+}
+
+impl Deref for CParticle {
+    type Target = Particle;
+
+    fn deref(&self) -> &Particle {
+        // SAFETY: the allocation was checked to be aligned and lives until drop
+        unsafe { self.raw.as_ref() }
+    }
+}
+
+impl DerefMut for CParticle {
+    fn deref_mut(&mut self) -> &mut Particle {
+        unsafe { self.raw.as_mut() }
+    }
+}
+
+impl Drop for CParticle {
+    fn drop(&mut self) {
+        unsafe { c_particle_free(self.raw.as_ptr()) }
+    }
+}
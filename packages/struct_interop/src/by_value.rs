@@ -0,0 +1,136 @@
+// 按值返回 8、16 和 24 字节的 #[repr(C)] 结构体，覆盖寄存器返回和 sret 两种约定：具体的规则在
+// c/by_value.h 的开头。rustc 和 C 编译器只要遵守同一个平台 ABI 就会选择同一种方式，这里的每个函数都有
+// 一个算法相同的 C 对应版本，测试两个方向交叉比较
+// Returning 8-, 16- and 24-byte #[repr(C)] structs by value, covering both the register and the
+// sret conventions: the rules are at the top of c/by_value.h. As long as rustc and the C compiler
+// follow the same platform ABI they pick the same way, and every function here has a C
+// counterpart with the same algorithm so the tests can cross-check both directions
+
+use std::mem;
+
+/// Two `i32`s, 8 bytes, laid out exactly like `Pair32` in c/by_value.h.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Pair32 {
+    pub a: i32,
+    pub b: i32,
+}
+
+/// Two `i64`s, 16 bytes, laid out exactly like `Pair64` in c/by_value.h.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Pair64 {
+    pub a: i64,
+    pub b: i64,
+}
+
+/// A `f64` and an `i64`, 16 bytes, laid out exactly like `Mixed16` in c/by_value.h.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Mixed16 {
+    pub x: f64,
+    pub n: i64,
+}
+
+/// Three `i64`s, 24 bytes, laid out exactly like `Triple64` in c/by_value.h.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Triple64 {
+    pub a: i64,
+    pub b: i64,
+    pub c: i64,
+}
+
+// 布局断言，C 端的 by_value.h 中有对应的 _Static_assert
+// Layout assertions, mirrored by the _Static_asserts in by_value.h
+const _: () = assert!(mem::size_of::<Pair32>() == 8);
+const _: () = assert!(mem::size_of::<Pair64>() == 16);
+const _: () = assert!(mem::size_of::<Mixed16>() == 16);
+const _: () = assert!(mem::size_of::<Triple64>() == 24);
+
+extern "C" {
+    fn c_make_pair32(seed: i32) -> Pair32;
+    fn c_make_pair64(seed: i64) -> Pair64;
+    fn c_make_mixed16(seed: i64) -> Mixed16;
+    fn c_make_triple64(seed: i64) -> Triple64;
+    fn c_triple64_rotate(t: Triple64) -> Triple64;
+    fn c_check_rust_returns(seed: i64) -> i32;
+}
+
+/// Returns `{seed, -seed - 1}`, wrapping around on overflow.
+#[no_mangle]
+pub extern "C" fn rust_make_pair32(seed: i32) -> Pair32 {
+    Pair32 {
+        a: seed,
+        b: seed.wrapping_neg().wrapping_sub(1),
+    }
+}
+
+/// Returns `{seed, seed * 3 + 1}`, wrapping around on overflow.
+#[no_mangle]
+pub extern "C" fn rust_make_pair64(seed: i64) -> Pair64 {
+    Pair64 {
+        a: seed,
+        b: seed.wrapping_mul(3).wrapping_add(1),
+    }
+}
+
+/// Returns `{seed + 0.5, -seed}`, wrapping around on overflow.
+#[no_mangle]
+pub extern "C" fn rust_make_mixed16(seed: i64) -> Mixed16 {
+    Mixed16 {
+        x: seed as f64 + 0.5,
+        n: seed.wrapping_neg(),
+    }
+}
+
+/// Returns `{seed, seed + 1000, seed * -7}`, wrapping around on overflow.
+#[no_mangle]
+pub extern "C" fn rust_make_triple64(seed: i64) -> Triple64 {
+    Triple64 {
+        a: seed,
+        b: seed.wrapping_add(1000),
+        c: seed.wrapping_mul(-7),
+    }
+}
+
+/// Rotates the fields of `t` by one place, passing and returning it by value.
+#[no_mangle]
+pub extern "C" fn rust_triple64_rotate(t: Triple64) -> Triple64 {
+    Triple64 {
+        a: t.b,
+        b: t.c,
+        c: t.a,
+    }
+}
+
+/// [`rust_make_pair32`] computed by C.
+pub fn c_pair32(seed: i32) -> Pair32 {
+    unsafe { c_make_pair32(seed) }
+}
+
+/// [`rust_make_pair64`] computed by C.
+pub fn c_pair64(seed: i64) -> Pair64 {
+    unsafe { c_make_pair64(seed) }
+}
+
+/// [`rust_make_mixed16`] computed by C.
+pub fn c_mixed16(seed: i64) -> Mixed16 {
+    unsafe { c_make_mixed16(seed) }
+}
+
+/// [`rust_make_triple64`] computed by C.
+pub fn c_triple64(seed: i64) -> Triple64 {
+    unsafe { c_make_triple64(seed) }
+}
+
+/// [`rust_triple64_rotate`] computed by C.
+pub fn c_rotate(t: Triple64) -> Triple64 {
+    unsafe { c_triple64_rotate(t) }
+}
+
+/// Has C call every `rust_make_*` function and compare with its own results, returning how many
+/// disagree.
+pub fn c_check_rust(seed: i64) -> i32 {
+    unsafe { c_check_rust_returns(seed) }
+}
@@ -0,0 +1,92 @@
+// 这个库演示 #[repr(C)] 结构体在 Rust 和 C 之间按值和按指针双向传递
+// This library demonstrates passing a #[repr(C)] struct between Rust and C in both directions,
+// by value and by pointer
+//
+// aligned 模块中是超过默认对齐的结构体，它们只通过指针传递；by_value 模块按值返回不同大小的结构体
+// The aligned module has the over-aligned structs, which are only passed through pointers; the
+// by_value module returns structs of different sizes by value
+
+use std::mem;
+
+mod aligned;
+mod by_value;
+
+pub use aligned::{
+    c_particle_layout, energy_with_rust, is_aligned, rust_vec4_dot, step, vec4_add, CParticle,
+    Particle, Vec4,
+};
+pub use by_value::{
+    c_check_rust, c_mixed16, c_pair32, c_pair64, c_rotate, c_triple64, rust_make_mixed16,
+    rust_make_pair32, rust_make_pair64, rust_make_triple64, rust_triple64_rotate, Mixed16, Pair32,
+    Pair64, Triple64,
+};
+
+/// A 2D point laid out exactly like `struct Point` in c/point.h.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Point {
+    pub x: i32,
+    pub y: i32,
+}
+
+// 布局断言，C 端的 point.h 中有对应的 _Static_assert
+// Layout assertions, mirrored by the _Static_asserts in point.h
+const _: () = assert!(mem::size_of::<Point>() == 8);
+const _: () = assert!(mem::align_of::<Point>() == 4);
+const _: () = assert!(mem::offset_of!(Point, x) == 0);
+const _: () = assert!(mem::offset_of!(Point, y) == 4);
+
+extern "C" {
+    fn c_point_translate(p: Point, dx: i32, dy: i32) -> Point;
+    fn c_point_scale(p: *mut Point, factor: i32);
+    fn c_point_sum_with_rust(a: *const Point, b: *const Point) -> Point;
+}
+
+/// Adds two points passed by value and returns the result by value.
+#[no_mangle]
+pub extern "C" fn rust_point_add(a: Point, b: Point) -> Point {
+    Point {
+        x: a.x.wrapping_add(b.x),
+        y: a.y.wrapping_add(b.y),
+    }
+}
+
+/// Returns the dot product of two points passed by pointer, or 0 if either is NULL.
+///
+/// # Safety
+///
+/// `a` and `b` must each be NULL or point to a valid `Point`.
+#[no_mangle]
+pub unsafe extern "C" fn rust_point_dot(a: *const Point, b: *const Point) -> i64 {
+    match (a.as_ref(), b.as_ref()) {
+        (Some(a), Some(b)) => a.x as i64 * b.x as i64 + a.y as i64 * b.y as i64,
+        _ => 0,
+    }
+}
+
+/// Swaps the coordinates of the point in place; NULL is ignored.
+///
+/// # Safety
+///
+/// `p` must be NULL or point to a valid, writable `Point`.
+#[no_mangle]
+pub unsafe extern "C" fn rust_point_swap(p: *mut Point) {
+    if let Some(p) = p.as_mut() {
+        mem::swap(&mut p.x, &mut p.y);
+    }
+}
+
+/// Moves `p` by `(dx, dy)` in C, passing and returning the struct by value.
+pub fn translate(p: Point, dx: i32, dy: i32) -> Point {
+    unsafe { c_point_translate(p, dx, dy) }
+}
+
+/// Scales `p` in place in C through a pointer.
+pub fn scale(p: &mut Point, factor: i32) {
+    unsafe { c_point_scale(p, factor) }
+}
+
+/// Asks C to add the points, which in turn calls back into [`rust_point_add`].
+pub fn sum_with_rust(a: &Point, b: &Point) -> Point {
+    unsafe { c_point_sum_with_rust(a, b) }
+}
@@ -0,0 +1,130 @@
+// 对齐的结构体的测试：C 和 Rust 看到的布局相同；栈上、Box 和 Vec 中的值，以及 C 分配的值都按 16 字节对齐，
+// 可以交给 C 的 SSE 代码；从字节缓冲区中错开 4 字节得到的指针被两边拒绝，而不是在对齐的读写指令中出错。
+// 这些测试不区分编译器，在 MSVC 和 GCC / Clang 的目标上都会运行
+// Tests for the aligned structs: C and Rust see the same layout; values on the stack, in a Box and
+// in a Vec, as well as ones C allocated, are 16-byte aligned and can be handed to C's SSE code;
+// pointers 4 bytes off into a byte buffer are refused by both sides instead of faulting in an
+// aligned load or store. The tests don't depend on the compiler and run on MSVC as well as GCC /
+// Clang targets
+
+use std::mem;
+use std::ptr;
+
+use struct_interop::{
+    c_particle_layout, energy_with_rust, is_aligned, rust_vec4_dot, step, vec4_add, CParticle,
+    Particle, Vec4,
+};
+
+fn vec4(lanes: [f32; 4]) -> Vec4 {
+    Vec4 { lanes }
+}
+
+#[test]
+fn c_sees_the_same_layout() {
+    assert_eq!(
+        c_particle_layout(),
+        (mem::size_of::<Particle>(), mem::align_of::<Particle>())
+    );
+}
+
+#[test]
+fn adds_vectors_wherever_they_live() {
+    let a = vec4([1.0, 2.0, 3.0, 4.0]);
+    let b = vec4([0.5, 0.5, 0.5, 0.5]);
+    let expected = vec4([1.5, 2.5, 3.5, 4.5]);
+    assert_eq!(vec4_add(&a, &b), expected);
+
+    let boxed = Box::new(b);
+    assert!(is_aligned(&*boxed));
+    assert_eq!(vec4_add(&a, &boxed), expected);
+
+    let many = vec![b; 7];
+    for v in &many {
+        assert!(is_aligned(v));
+        assert_eq!(vec4_add(&a, v), expected);
+    }
+}
+
+#[test]
+fn steps_particles_in_c() {
+    let mut p = Particle {
+        position: vec4([0.0, 1.0, 2.0, 0.0]),
+        velocity: vec4([2.0, -2.0, 4.0, 0.0]),
+        id: 7,
+    };
+    step(&mut p, 0.5);
+    assert_eq!(p.position, vec4([1.0, 0.0, 4.0, 0.0]));
+    assert_eq!(p.id, 7);
+    assert_eq!(energy_with_rust(&p), 12.0);
+}
+
+#[test]
+fn c_allocates_aligned_particles() {
+    let mut particles: Vec<CParticle> = (0..8).map(|id| CParticle::new(id).unwrap()).collect();
+    for (id, p) in particles.iter_mut().enumerate() {
+        assert!(is_aligned::<Particle>(&**p));
+        assert_eq!(p.id, id as u32);
+        assert_eq!(p.position, Vec4::default());
+        p.velocity = vec4([1.0, 1.0, 1.0, 1.0]);
+        step(p, 3.0);
+        assert_eq!(p.position, vec4([3.0; 4]));
+    }
+}
+
+// 对齐的缓冲区中错开 4 字节的位置：对 f32 对齐，对 Vec4 不对齐
+// A place 4 bytes into an aligned buffer: aligned for an f32, misaligned for a Vec4
+fn misaligned(buffer: &mut [Vec4; 4]) -> *mut Vec4 {
+    buffer.as_mut_ptr().cast::<u8>().wrapping_add(4).cast()
+}
+
+#[test]
+fn rust_refuses_misaligned_pointers() {
+    let mut buffer = [vec4([1.0; 4]); 4];
+    let good = vec4([1.0, 2.0, 3.0, 4.0]);
+    let mut out = 0.0;
+    unsafe {
+        assert_eq!(rust_vec4_dot(&good, &good, &mut out), 0);
+        assert_eq!(out, 30.0);
+        let bad = misaligned(&mut buffer);
+        assert!(!is_aligned(bad));
+        assert_eq!(rust_vec4_dot(bad, &good, &mut out), -1);
+        assert_eq!(rust_vec4_dot(&good, bad, &mut out), -1);
+        assert_eq!(rust_vec4_dot(ptr::null(), &good, &mut out), -1);
+        assert_eq!(rust_vec4_dot(&good, &good, ptr::null_mut()), -1);
+    }
+    assert_eq!(out, 30.0);
+}
+
+// step 和 vec4_add 背后的 C 函数，在这里再声明一次，以便传给它们错误的指针
+// The C functions behind step and vec4_add, declared again here to pass them bad pointers
+extern "C" {
+    fn c_vec4_add(a: *const Vec4, b: *const Vec4, out: *mut Vec4) -> i32;
+    fn c_particle_step(p: *mut Particle, dt: f32) -> i32;
+}
+
+#[test]
+fn c_refuses_misaligned_pointers() {
+    let mut buffer = [Vec4::default(); 4];
+    let good = vec4([1.0; 4]);
+    let mut out = Vec4::default();
+    unsafe {
+        let bad = misaligned(&mut buffer);
+        assert_eq!(c_vec4_add(bad, &good, &mut out), -1);
+        assert_eq!(c_vec4_add(&good, &good, bad), -1);
+        assert_eq!(c_particle_step(bad.cast(), 1.0), -1);
+        assert_eq!(c_particle_step(ptr::null_mut(), 1.0), -1);
+        assert_eq!(c_vec4_add(&good, &good, &mut out), 0);
+    }
+    assert_eq!(out, vec4([2.0; 4]));
+    assert_eq!(buffer, [Vec4::default(); 4]);
+}
+
+#[cfg(target_arch = "x86_64")]
+#[test]
+fn converts_to_and_from_m128() {
+    use std::arch::x86_64::{__m128, _mm_add_ps};
+
+    let a = vec4([1.0, 2.0, 3.0, 4.0]);
+    let sum: __m128 = unsafe { _mm_add_ps(a.into(), a.into()) };
+    assert_eq!(Vec4::from(sum), vec4_add(&a, &a));
+}
@@ -0,0 +1,60 @@
+// 按值返回的结构体的测试：同一个 seed 下 C 和 Rust 返回的 8、16 和 24 字节结构体逐字段相等，两个方向都检查。
+// 寄存器返回和 sret 返回的算法相同，约定不一致时字段会错位或变成垃圾，用多个 seed（包括负数和溢出的边界）
+// 避免碰巧相等
+// Tests for the structs returned by value: for the same seed the 8-, 16- and 24-byte structs C and
+// Rust return are equal field by field, checked in both directions. Register and sret returns use
+// the same algorithm, so mismatched conventions shift fields or turn them into garbage, and
+// several seeds, including negative ones and the overflow edges, keep them from matching by
+// accident
+
+use struct_interop::{
+    c_check_rust, c_mixed16, c_pair32, c_pair64, c_rotate, c_triple64, rust_make_mixed16,
+    rust_make_pair32, rust_make_pair64, rust_make_triple64, rust_triple64_rotate, Pair32, Triple64,
+};
+
+const SEEDS: [i64; 6] = [0, 1, -1, 123_456_789, i64::MAX / 8, i64::MIN / 8];
+
+#[test]
+fn rust_reads_c_returns() {
+    for seed in SEEDS {
+        assert_eq!(
+            c_pair32(seed as i32),
+            rust_make_pair32(seed as i32),
+            "seed {seed}"
+        );
+        assert_eq!(c_pair64(seed), rust_make_pair64(seed), "seed {seed}");
+        assert_eq!(c_mixed16(seed), rust_make_mixed16(seed), "seed {seed}");
+        assert_eq!(c_triple64(seed), rust_make_triple64(seed), "seed {seed}");
+    }
+}
+
+#[test]
+fn c_reads_rust_returns() {
+    for seed in SEEDS {
+        assert_eq!(c_check_rust(seed), 0, "seed {seed}");
+    }
+}
+
+#[test]
+fn known_values() {
+    assert_eq!(c_pair32(5), Pair32 { a: 5, b: -6 });
+    assert_eq!(
+        c_triple64(2),
+        Triple64 {
+            a: 2,
+            b: 1002,
+            c: -14
+        }
+    );
+    let mixed = c_mixed16(-3);
+    assert_eq!((mixed.x, mixed.n), (-2.5, 3));
+}
+
+#[test]
+fn triple_passes_and_returns_by_value() {
+    let t = Triple64 { a: 1, b: 2, c: 3 };
+    let rotated = Triple64 { a: 2, b: 3, c: 1 };
+    assert_eq!(c_rotate(t), rotated);
+    assert_eq!(rust_triple64_rotate(t), rotated);
+    assert_eq!(c_rotate(c_rotate(c_rotate(t))), t);
+}
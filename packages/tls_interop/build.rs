@@ -0,0 +1,8 @@
+// 这是我们的构建脚本，编译带线程局部变量并在自己的线程中调用 Rust 的 C 代码
+// This is our build script, it compiles the C code with a thread-local variable that calls into
+// Rust from threads of its own
+
+fn main() {
+    cc::Build::new().file("c/tls.c").std("c11").compile("tls");
+    println!("cargo::rerun-if-changed=c");
+}
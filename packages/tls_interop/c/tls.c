@@ -0,0 +1,133 @@
+// C 端的线程局部变量，以及在几个 C 线程中同时读写两边的线程局部变量、检查每个线程只看到自己的值
+// The thread-local variable on the C side, and a few C threads reading and writing the
+// thread-local variables of both sides at once, checking each thread only sees its own values
+#include <stdlib.h>
+#include "tls.h"
+
+#ifdef _WIN32
+#include <windows.h>
+#else
+#include <pthread.h>
+#include <sched.h>
+#endif
+
+#if defined(_MSC_VER)
+#define THREAD_LOCAL __declspec(thread)
+#else
+#define THREAD_LOCAL _Thread_local
+#endif
+
+// 每个线程一份，新线程中从 0 开始。static 让它只能通过下面的函数访问：在 Rust 中把它声明成 extern
+// static 会当作普通的全局变量读取，得到的不是当前线程的那一份
+// One per thread, starting at 0 in a new thread. Being static it can only be reached through the
+// functions below: declared as an extern static in Rust it would be read like an ordinary global,
+// which is not the current thread's copy
+static THREAD_LOCAL int32_t c_tls_value = 0;
+
+int32_t c_tls_get(void)
+{
+    return c_tls_value;
+}
+
+void c_tls_set(int32_t value)
+{
+    c_tls_value = value;
+}
+
+typedef struct Job
+{
+    int32_t value;
+    int rounds;
+    int isolated;
+} Job;
+
+static void yield_now(void)
+{
+#ifdef _WIN32
+    SwitchToThread();
+#else
+    sched_yield();
+#endif
+}
+
+// 线程把自己的值写进两边的线程局部变量，让出 CPU 若干次，每次都检查值没有被其他线程改掉
+// The thread writes its own value into the thread-local variables of both sides, yields a number
+// of times and checks every time that no other thread changed them
+#ifdef _WIN32
+static DWORD WINAPI check(LPVOID arg)
+#else
+static void *check(void *arg)
+#endif
+{
+    Job *job = arg;
+    c_tls_value = -job->value;
+    rust_tls_set(job->value);
+    job->isolated = 1;
+    for (int i = 0; i < job->rounds; i++)
+    {
+        yield_now();
+        if (c_tls_value != -job->value || rust_tls_get() != job->value)
+        {
+            job->isolated = 0;
+        }
+    }
+    return 0;
+}
+
+// 启动 threads 个线程，返回只看到自己的值的线程数，出错时返回 -1。线程退出时 Rust 的线程局部变量随之
+// 销毁，哪怕线程是 C 创建的
+// Starts threads threads and returns how many of them only saw their own values, or -1 on
+// failure. Rust's thread-local variable is destroyed as each thread exits, even though C created
+// the thread
+int c_tls_check_threads(int threads, int rounds)
+{
+    if (threads <= 0)
+    {
+        return 0;
+    }
+    Job *jobs = calloc((size_t)threads, sizeof(Job));
+#ifdef _WIN32
+    HANDLE *ids = calloc((size_t)threads, sizeof(HANDLE));
+#else
+    pthread_t *ids = calloc((size_t)threads, sizeof(pthread_t));
+#endif
+    if (jobs == NULL || ids == NULL)
+    {
+        free(jobs);
+        free(ids);
+        return -1;
+    }
+
+    int started = 0;
+    int status = 0;
+    for (; started < threads; started++)
+    {
+        jobs[started].value = started + 1;
+        jobs[started].rounds = rounds;
+#ifdef _WIN32
+        ids[started] = CreateThread(NULL, 0, check, &jobs[started], 0, NULL);
+        int failed = ids[started] == NULL;
+#else
+        int failed = pthread_create(&ids[started], NULL, check, &jobs[started]) != 0;
+#endif
+        if (failed)
+        {
+            status = -1;
+            break;
+        }
+    }
+    int isolated = 0;
+    for (int i = 0; i < started; i++)
+    {
+#ifdef _WIN32
+        WaitForSingleObject(ids[i], INFINITE);
+        CloseHandle(ids[i]);
+#else
+        pthread_join(ids[i], NULL);
+#endif
+        isolated += jobs[i].isolated;
+    }
+    free(jobs);
+    free(ids);
+    return status < 0 ? status : isolated;
+}
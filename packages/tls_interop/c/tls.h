@@ -0,0 +1,20 @@
+// C 和 Rust 两边的线程局部变量的访问函数：线程局部变量不能跨语言按名字访问，都通过函数读写
+// Accessors for the thread-local variables on both sides: a thread-local variable can't be
+// reached by name from the other language, so both are read and written through functions
+#ifndef TLS_H
+#define TLS_H
+
+#include <stdint.h>
+
+// 由 C 实现，Rust 调用
+// Implemented in C, called from Rust
+int32_t c_tls_get(void);
+void c_tls_set(int32_t value);
+int c_tls_check_threads(int threads, int rounds);
+
+// 由 Rust 实现，C 调用
+// Implemented in Rust, called from C
+int32_t rust_tls_get(void);
+void rust_tls_set(int32_t value);
+
+#endif
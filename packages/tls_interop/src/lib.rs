@@ -0,0 +1,86 @@
+// 这个库演示线程局部存储跨越 FFI 时的行为：Rust 的 thread_local! 通过访问函数交给 C 线程使用，C 的
+// _Thread_local 变量通过 C 的访问函数在 Rust 中读写。两边都不能按名字访问对方的线程局部变量：稳定版
+// Rust 没有 extern 的线程局部 static，C 也看不到 thread_local! 背后的符号。每个线程，包括 C 创建的线程，
+// 都有自己的一份，第一次访问时初始化，线程退出时销毁；在一个线程中设置的值不会跟着工作转到另一个线程，
+// 例如在 C 的工作线程上运行的回调看到的是那个线程的值
+// This library demonstrates how thread-local storage behaves across the FFI: a Rust thread_local!
+// is handed to C threads through accessor functions, and a C _Thread_local variable is read and
+// written from Rust through C's accessors. Neither side can reach the other's thread-local
+// variables by name: stable Rust has no extern thread-local statics, and C can't see the symbols
+// behind thread_local!. Every thread, C-created ones included, has a copy of its own, initialized
+// on first access and destroyed when the thread exits; a value set in one thread doesn't follow
+// the work to another, so a callback run on a C worker thread sees that thread's values
+
+use std::cell::Cell;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+extern "C" {
+    fn c_tls_get() -> i32;
+    fn c_tls_set(value: i32);
+    fn c_tls_check_threads(threads: i32, rounds: i32) -> i32;
+}
+
+static SLOTS_DROPPED: AtomicUsize = AtomicUsize::new(0);
+
+// 线程局部的值，放在一个可以数出销毁次数的类型中
+// The thread-local value, in a type whose destructions can be counted
+struct Slot {
+    value: Cell<i32>,
+}
+
+impl Drop for Slot {
+    fn drop(&mut self) {
+        SLOTS_DROPPED.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+thread_local! {
+    static SLOT: Slot = const {
+        Slot {
+            value: Cell::new(0),
+        }
+    };
+}
+
+/// Returns the calling thread's Rust thread-local value, 0 in a thread that never set it.
+///
+/// Also returns 0 once the value was destroyed, such as from another thread-local destructor
+/// that runs later as the thread exits.
+#[no_mangle]
+pub extern "C" fn rust_tls_get() -> i32 {
+    SLOT.try_with(|slot| slot.value.get()).unwrap_or(0)
+}
+
+/// Sets the calling thread's Rust thread-local value; other threads keep their own.
+///
+/// Ignored once the value was destroyed as the thread exits.
+#[no_mangle]
+pub extern "C" fn rust_tls_set(value: i32) {
+    let _ = SLOT.try_with(|slot| slot.value.set(value));
+}
+
+/// How many threads' Rust thread-local values were destroyed so far, C-created threads
+/// included.
+pub fn slots_dropped() -> usize {
+    SLOTS_DROPPED.load(Ordering::SeqCst)
+}
+
+/// Returns the calling thread's C thread-local value through C's accessor.
+pub fn c_value() -> i32 {
+    unsafe { c_tls_get() }
+}
+
+/// Sets the calling thread's C thread-local value through C's accessor.
+pub fn set_c_value(value: i32) {
+    unsafe { c_tls_set(value) }
+}
+
+/// Has C start `threads` threads that write both thread-local values and check them `rounds`
+/// times, returning how many of them only ever saw their own values, or `None` if C could not
+/// start them.
+pub fn check_c_threads(threads: i32, rounds: i32) -> Option<i32> {
+    match unsafe { c_tls_check_threads(threads, rounds) } {
+        -1 => None,
+        isolated => Some(isolated),
+    }
+}
@@ -0,0 +1,14 @@
+// C 创建的线程退出时 Rust 的线程局部变量也被销毁。销毁计数是进程全局的，Rust 线程退出时也会增加，
+// 所以这个测试单独成为一个测试程序，调用它的线程自己不碰线程局部变量
+// Rust's thread-local variable is destroyed as C-created threads exit too. The destruction count
+// is process-wide and Rust threads exiting add to it as well, so this test is a test program of
+// its own, and the thread calling it doesn't touch the thread-local variable itself
+
+use tls_interop::{check_c_threads, slots_dropped};
+
+#[test]
+fn c_threads_destroy_their_rust_values() {
+    let dropped = slots_dropped();
+    assert_eq!(check_c_threads(8, 10), Some(8));
+    assert_eq!(slots_dropped() - dropped, 8);
+}
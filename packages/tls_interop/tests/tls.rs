@@ -0,0 +1,42 @@
+// 线程局部存储的测试：Rust 线程和 C 线程各自只看到自己写入两边的线程局部变量的值
+// Tests for thread-local storage: Rust threads and C threads each only see the values they wrote
+// into the thread-local variables of both sides
+
+use std::sync::Barrier;
+use std::thread;
+
+use tls_interop::{c_value, check_c_threads, rust_tls_get, rust_tls_set, set_c_value};
+
+const THREADS: i32 = 8;
+
+#[test]
+fn rust_threads_see_their_own_values() {
+    // 所有线程都写完之后才开始读，没有隔离时一定会读到别人的值
+    // Reading only starts once every thread wrote, so without isolation some would read another's
+    let barrier = Barrier::new(THREADS as usize);
+    thread::scope(|scope| {
+        for value in 1..=THREADS {
+            let barrier = &barrier;
+            scope.spawn(move || {
+                assert_eq!((rust_tls_get(), c_value()), (0, 0));
+                rust_tls_set(value);
+                set_c_value(-value);
+                barrier.wait();
+                assert_eq!((rust_tls_get(), c_value()), (value, -value));
+            });
+        }
+    });
+}
+
+#[test]
+fn c_threads_see_their_own_values() {
+    assert_eq!(check_c_threads(THREADS, 100), Some(THREADS));
+}
+
+#[test]
+fn the_calling_thread_is_untouched() {
+    rust_tls_set(42);
+    set_c_value(-42);
+    assert_eq!(check_c_threads(THREADS, 10), Some(THREADS));
+    assert_eq!((rust_tls_get(), c_value()), (42, -42));
+}
@@ -0,0 +1,114 @@
+// 编译成 WebAssembly 的加法和问候语，接口和 cdylib_gen 相同；
+// 指针都是模块线性内存中的偏移，宿主先用 wasm_alloc 在其中分配缓冲区
+// The add and greeting logic compiled to WebAssembly, with the same interface as cdylib_gen;
+// every pointer is an offset into the module's linear memory, where the host first allocates
+// its buffers with wasm_alloc
+
+use std::alloc::{self, Layout};
+use std::ffi;
+
+use interop_common::{ffi_guard, read_cstr, write_cstr, write_out, FfiError};
+pub use interop_common::FfiStatus;
+
+// 宿主分配的缓冲区要能容纳 c_int 和 usize 的输出参数
+// Buffers the host allocates must fit c_int and usize out parameters
+const ALIGN: usize = 8;
+
+/// Allocates `len` bytes in the module's memory for the host, NULL when `len` is 0 or the
+/// allocation fails.
+#[no_mangle]
+pub extern "C" fn wasm_alloc(len: usize) -> *mut u8 {
+    match Layout::from_size_align(len, ALIGN) {
+        Ok(layout) if len > 0 => unsafe { alloc::alloc(layout) },
+        _ => std::ptr::null_mut(),
+    }
+}
+
+/// Releases memory returned by `wasm_alloc`. Passing NULL is a no-op.
+///
+/// # Safety
+///
+/// `ptr` must be NULL or returned by `wasm_alloc(len)` and not freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn wasm_free(ptr: *mut u8, len: usize) {
+    if !ptr.is_null() {
+        alloc::dealloc(ptr, Layout::from_size_align_unchecked(len, ALIGN));
+    }
+}
+
+/// Adds `a` and `b`, stores the sum in `sum` and writes a greeting message into `result`.
+///
+/// Behaves exactly like `rxc_cdylib_add`: `result` holds the caller's NUL-terminated name on input
+/// and the (possibly truncated) message on output, and `required_len`, when not NULL, receives
+/// the length the full message needs.
+///
+/// # Safety
+///
+/// `result` must be valid for reads and writes of `result_len` bytes (it may be NULL when
+/// `result_len` is 0), `sum` must be valid for writes and `required_len` must be NULL or
+/// valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn wasm_add(
+    a: ffi::c_int,
+    b: ffi::c_int,
+    result: *mut ffi::c_char,
+    result_len: usize,
+    sum: *mut ffi::c_int,
+    required_len: *mut usize,
+) -> FfiStatus {
+    ffi_guard(|| add(a, b, result, result_len, sum, required_len))
+}
+
+unsafe fn add(
+    a: ffi::c_int,
+    b: ffi::c_int,
+    result: *mut ffi::c_char,
+    result_len: usize,
+    sum: *mut ffi::c_int,
+    required_len: *mut usize,
+) -> Result<(), FfiError> {
+    let total = a.checked_add(b).ok_or(FfiError::Overflow)?;
+    let name = if result.is_null() && result_len == 0 {
+        ""
+    } else {
+        read_cstr(result, result_len)?
+    };
+
+    // 在 WASI 下由宿主决定标准输出去向
+    // Under WASI the host decides where standard output goes
+    println!("[Rust wasm] Hello {name}");
+
+    let msg = format!("[Rust wasm] The result ({a} + {b}) is {total}!");
+
+    write_out(sum, total)?;
+    if !required_len.is_null() {
+        write_out(required_len, msg.len())?;
+    }
+    write_cstr(result, result_len, &msg).map(|_| ())
+}
+
+/// Writes the same greeting as `rxc_cdylib_greeting_message` for the NUL-terminated `name` into
+/// `buf`, using the same two-call pattern.
+///
+/// # Safety
+///
+/// `name` must be NULL or point to a NUL-terminated string, `buf` must be valid for writes of
+/// `len` bytes (it may be NULL when `len` is 0) and `required_len` must be NULL or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn wasm_greeting_message(
+    name: *const ffi::c_char,
+    buf: *mut ffi::c_char,
+    len: usize,
+    required_len: *mut usize,
+) -> FfiStatus {
+    ffi_guard(|| {
+        let greeting = format!(
+            "[Rust wasm] Hello {}, nice to meet you!",
+            read_cstr(name, usize::MAX)?
+        );
+        if !required_len.is_null() {
+            write_out(required_len, greeting.len())?;
+        }
+        write_cstr(buf, len, &greeting).map(|_| ())
+    })
+}
@@ -0,0 +1,165 @@
+// cargo xtask check-abi：把 cbindgen 生成的 include/cdylib_gen.h 中声明的每个函数和构建出的 cdylib 实际导出的
+// 符号对比，名字缺失或多出都算失败，在调用方之前发现拼错的 #[export_name]
+// cargo xtask check-abi: cross-checks every function declared in the cbindgen-generated
+// include/cdylib_gen.h against the symbols the built cdylib actually exports, failing on a name
+// missing from either side, which catches a misspelt #[export_name] before a consumer does
+
+use std::{collections::BTreeSet, fs, path::Path, process::Command};
+
+use crate::{run, workspace_root, Platform, Result};
+
+/// The functions a header declares.
+#[derive(Default)]
+struct Declarations {
+    /// Declared outside any `#if`, every build must export them.
+    always: BTreeSet<String>,
+    /// Declared inside an `#if`, such as the stdcall functions of 32-bit Windows; only exported by
+    /// some builds.
+    conditional: BTreeSet<String>,
+}
+
+// 去掉 /* */ 和 // 注释，保留换行，预处理指令仍然各占一行
+// Strips /* */ and // comments while keeping the newlines, so preprocessor directives still have
+// a line each
+fn strip_comments(header: &str) -> String {
+    let mut out = String::with_capacity(header.len());
+    let mut rest = header;
+    while !rest.is_empty() {
+        if let Some(after) = rest.strip_prefix("/*") {
+            let end = after.find("*/").map_or(after.len(), |end| end + 2);
+            out.extend(after[..end].chars().filter(|&c| c == '\n'));
+            rest = &after[end..];
+        } else if let Some(after) = rest.strip_prefix("//") {
+            rest = &after[after.find('\n').unwrap_or(after.len())..];
+        } else {
+            let c = rest.chars().next().unwrap();
+            out.push(c);
+            rest = &rest[c.len_utf8()..];
+        }
+    }
+    out
+}
+
+// 顶层的声明以分号结束；带花括号的是类型定义，typedef 声明的是函数指针类型，其余带括号的是函数，
+// 函数名是左括号前的最后一个标识符（跳过 __stdcall 之类的修饰）
+// A top-level declaration ends with a semicolon; one with braces defines a type, a typedef
+// declares a function pointer type, and the rest with a parenthesis are functions, named by the
+// last identifier before the opening parenthesis (past modifiers such as __stdcall)
+fn parse_declarations(header: &str) -> Declarations {
+    let mut declarations = Declarations::default();
+    // 每一层 #if 是否是 include guard（#ifndef X_H），guard 里的声明仍然是无条件的
+    // For every level of #if whether it is an include guard (#ifndef X_H), the declarations inside
+    // a guard are still unconditional
+    let mut conditions: Vec<bool> = Vec::new();
+    let mut statement = String::new();
+    let mut depth = 0usize;
+    for line in strip_comments(header).lines() {
+        let trimmed = line.trim_start();
+        if let Some(directive) = trimmed.strip_prefix('#') {
+            let directive = directive.trim_start();
+            if directive.starts_with("if") {
+                let guard = directive
+                    .strip_prefix("ifndef")
+                    .is_some_and(|name| name.trim().ends_with("_H"));
+                conditions.push(!guard);
+            } else if directive.starts_with("endif") {
+                conditions.pop();
+            }
+            continue;
+        }
+        for c in line.chars().chain(['\n']) {
+            match c {
+                '{' => depth += 1,
+                '}' => depth = depth.saturating_sub(1),
+                ';' if depth == 0 => {
+                    if let Some(name) = function_name(&statement) {
+                        if conditions.iter().any(|&conditional| conditional) {
+                            declarations.conditional.insert(name);
+                        } else {
+                            declarations.always.insert(name);
+                        }
+                    }
+                    statement.clear();
+                    continue;
+                }
+                _ => {}
+            }
+            statement.push(c);
+        }
+    }
+    declarations
+}
+
+fn function_name(statement: &str) -> Option<String> {
+    let statement = statement.trim();
+    if statement.contains('{') || statement.starts_with("typedef") {
+        return None;
+    }
+    let before_paren = &statement[..statement.find('(')?];
+    let name = before_paren
+        .rsplit(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+        .next()?;
+    (!name.is_empty()).then(|| name.to_owned())
+}
+
+/// Builds cdylib_gen and checks its exports against include/cdylib_gen.h.
+pub fn check_abi(release: bool, target: Option<&str>) -> Result {
+    let root = workspace_root();
+    let mut cargo = Command::new(env!("CARGO"));
+    cargo.args(["build", "-p", "cdylib_gen"]).current_dir(&root);
+    if release {
+        cargo.arg("--release");
+    }
+    let mut lib_dir = root.join("target");
+    if let Some(target) = target {
+        cargo.args(["--target", target]);
+        lib_dir.push(target);
+    }
+    run(&mut cargo)?;
+    lib_dir.push(if release { "release" } else { "debug" });
+
+    let platform = target.map_or_else(Platform::host, Platform::from_triple);
+    let lib_path = lib_dir.join(platform.dylib_file("cdylib_gen"));
+    let header_path = root.join("include/cdylib_gen.h");
+    check(&header_path, &lib_path)
+}
+
+fn check(header_path: &Path, lib_path: &Path) -> Result {
+    let header = fs::read_to_string(header_path)
+        .map_err(|err| format!("cannot read {}: {}", header_path.display(), err))?;
+    let declarations = parse_declarations(&header);
+    let exports = dylib_inspect::inspect_file(lib_path)
+        .map_err(|err| format!("{}: {}", lib_path.display(), err))?
+        .exports;
+
+    let missing: Vec<_> = declarations.always.difference(&exports).collect();
+    let undeclared: Vec<_> = exports
+        .iter()
+        .filter(|name| {
+            !declarations.always.contains(*name) && !declarations.conditional.contains(*name)
+        })
+        .collect();
+    println!(
+        "[xtask] {} declares {} functions ({} conditional), {} exports {}",
+        header_path.display(),
+        declarations.always.len() + declarations.conditional.len(),
+        declarations.conditional.len(),
+        lib_path.display(),
+        exports.len()
+    );
+    if missing.is_empty() && undeclared.is_empty() {
+        println!("[xtask] The header and the library agree");
+        return Ok(());
+    }
+    for name in &missing {
+        eprintln!("[xtask] declared in the header but not exported: {}", name);
+    }
+    for name in &undeclared {
+        eprintln!("[xtask] exported but not declared in the header: {}", name);
+    }
+    Err(format!(
+        "the header and the library disagree on {} symbol(s)",
+        missing.len() + undeclared.len()
+    )
+    .into())
+}
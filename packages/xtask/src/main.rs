@@ -0,0 +1,834 @@
+// 整个示例的构建编排：cargo xtask build-all / stage-libs / run-demo / install / android / xcframework /
+// sanitize / static / check-abi / preload / shm
+// Build orchestration for the whole demo: cargo xtask build-all / stage-libs / run-demo / install /
+// android / xcframework / sanitize / static / check-abi / preload / shm
+
+use std::{
+    error::Error,
+    fs,
+    path::{Path, PathBuf},
+    process::{Command, ExitCode},
+};
+
+use clap::{Args, Parser, Subcommand};
+
+mod abi_check;
+mod preload;
+mod shm;
+
+type Result<T = ()> = std::result::Result<T, Box<dyn Error>>;
+
+#[derive(Parser)]
+#[command(about = "Builds, stages and runs the Rust and C interop demo")]
+struct Cli {
+    #[command(subcommand)]
+    command: Task,
+}
+
+#[derive(Subcommand)]
+enum Task {
+    /// Build every workspace package and the external C library.
+    BuildAll(BuildOptions),
+    /// Copy the runtime libraries next to the call_libs binary.
+    StageLibs(BuildOptions),
+    /// Build and stage everything, then run call_libs with the given arguments.
+    RunDemo {
+        #[command(flatten)]
+        options: BuildOptions,
+        /// Arguments passed on to call_libs.
+        #[arg(last = true)]
+        args: Vec<String>,
+    },
+    /// Install both libraries with their headers, CMake and pkg-config files into a prefix.
+    Install {
+        /// Use the release profile.
+        #[arg(long)]
+        release: bool,
+        /// Install prefix, laid out as bin/, include/ and lib/.
+        #[arg(long, default_value = "target/install")]
+        prefix: PathBuf,
+    },
+    /// Build cdylib_gen with the jni feature for Android into android/app/src/main/jniLibs.
+    Android {
+        /// Use the release profile.
+        #[arg(long)]
+        release: bool,
+        /// Android ABIs to build for.
+        #[arg(
+            long,
+            value_delimiter = ',',
+            default_value = "arm64-v8a,armeabi-v7a,x86_64"
+        )]
+        abi: Vec<String>,
+        /// Minimum Android API level the library supports.
+        #[arg(long, default_value_t = 24)]
+        api: u32,
+        /// Root directory of the Android NDK.
+        #[arg(long, env = "ANDROID_NDK_HOME")]
+        ndk: PathBuf,
+    },
+    /// Package staticlib_gen for iOS, the iOS simulator and macOS as an XCFramework (macOS only).
+    Xcframework {
+        /// Use the release profile.
+        #[arg(long)]
+        release: bool,
+        /// Where to write the XCFramework.
+        #[arg(long, default_value = "target/xcframework/staticlib_gen.xcframework")]
+        output: PathBuf,
+    },
+    /// Run the demo with AddressSanitizer and UndefinedBehaviorSanitizer (nightly, Linux only).
+    Sanitize {
+        /// Use the release profile.
+        #[arg(long)]
+        release: bool,
+        /// Arguments passed on to call_libs.
+        #[arg(last = true)]
+        args: Vec<String>,
+    },
+    /// Build and run a fully static call_libs (+crt-static: musl on Linux, static CRT on Windows).
+    Static {
+        /// Use the release profile.
+        #[arg(long)]
+        release: bool,
+        /// Target triple, by default <arch>-unknown-linux-musl on Linux and the host on Windows.
+        #[arg(long)]
+        target: Option<String>,
+        /// Arguments passed on to call_libs.
+        #[arg(last = true)]
+        args: Vec<String>,
+    },
+    /// Build cdylib_gen and check every function in include/cdylib_gen.h is exported and vice versa.
+    CheckAbi {
+        /// Use the release profile.
+        #[arg(long)]
+        release: bool,
+        /// Cross compile for this target triple.
+        #[arg(long)]
+        target: Option<String>,
+    },
+    /// Run a C program under LD_PRELOAD with preload_interpose replacing its puts (Linux only).
+    Preload {
+        /// Use the release profile.
+        #[arg(long)]
+        release: bool,
+    },
+    /// Run the Rust writer and the C reader processes exchanging messages through shared memory.
+    Shm {
+        /// Use the release profile.
+        #[arg(long)]
+        release: bool,
+        /// How many messages the writer writes.
+        #[arg(long, default_value_t = 100_000)]
+        messages: u32,
+    },
+}
+
+#[derive(Args)]
+struct BuildOptions {
+    /// Use the release profile.
+    #[arg(long)]
+    release: bool,
+    /// Skip CMake and use the external library already in external_lib/lib_build.
+    #[arg(long)]
+    skip_external: bool,
+    /// Cross compile for this target triple, e.g. aarch64-unknown-linux-gnu.
+    #[arg(long)]
+    target: Option<String>,
+}
+
+impl BuildOptions {
+    fn profile(&self) -> &'static str {
+        if self.release {
+            "release"
+        } else {
+            "debug"
+        }
+    }
+
+    // Cargo 把交叉编译的产物放在 target/<triple>/<profile> 中
+    // Cargo puts cross-compiled artifacts into target/<triple>/<profile>
+    fn bin_dir(&self) -> PathBuf {
+        let mut dir = workspace_root().join("target");
+        if let Some(target) = &self.target {
+            dir.push(target);
+        }
+        dir.join(self.profile())
+    }
+
+    fn platform(&self) -> Platform {
+        match &self.target {
+            Some(target) => Platform::from_triple(target),
+            None => Platform::host(),
+        }
+    }
+}
+
+// 库和可执行文件的命名约定，由目标三元组决定而不是 xtask 运行的主机
+// Naming conventions for libraries and executables, decided by the target triple rather than
+// the host xtask runs on
+struct Platform {
+    dll_prefix: &'static str,
+    dll_suffix: &'static str,
+    exe_suffix: &'static str,
+}
+
+impl Platform {
+    fn host() -> Self {
+        Platform {
+            dll_prefix: std::env::consts::DLL_PREFIX,
+            dll_suffix: std::env::consts::DLL_SUFFIX,
+            exe_suffix: std::env::consts::EXE_SUFFIX,
+        }
+    }
+
+    fn from_triple(triple: &str) -> Self {
+        if triple.contains("-windows") {
+            Platform {
+                dll_prefix: "",
+                dll_suffix: ".dll",
+                exe_suffix: ".exe",
+            }
+        } else if triple.contains("-apple-") {
+            Platform {
+                dll_prefix: "lib",
+                dll_suffix: ".dylib",
+                exe_suffix: "",
+            }
+        } else {
+            Platform {
+                dll_prefix: "lib",
+                dll_suffix: ".so",
+                exe_suffix: "",
+            }
+        }
+    }
+
+    fn dylib_file(&self, name: &str) -> String {
+        format!("{}{}{}", self.dll_prefix, name, self.dll_suffix)
+    }
+}
+
+fn workspace_root() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .ancestors()
+        .nth(2)
+        .unwrap()
+        .to_owned()
+}
+
+fn run(command: &mut Command) -> Result {
+    println!("[xtask] Running {:?}", command);
+    let status = command
+        .status()
+        .map_err(|err| format!("cannot run {:?}: {}", command.get_program(), err))?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("{:?} failed with {}", command.get_program(), status).into())
+    }
+}
+
+// 主机平台上的动态库文件名
+// Dynamic library file names on the host platform
+fn dylib_file(name: &str) -> String {
+    Platform::host().dylib_file(name)
+}
+
+fn build_all(options: &BuildOptions) -> Result {
+    let root = workspace_root();
+    let mut cargo = Command::new(env!("CARGO"));
+    // 排除 xtask 自己，Windows 上无法替换正在运行的可执行文件
+    // Exclude xtask itself, Windows cannot replace a running executable
+    cargo
+        .current_dir(&root)
+        .args(["build", "--workspace", "--exclude", "xtask"]);
+    if options.release {
+        cargo.arg("--release");
+    }
+    if let Some(target) = &options.target {
+        cargo.args(["--target", target]);
+    }
+    run(&mut cargo)?;
+
+    // 没有工具链文件时 CMake 只能构建主机上的库，交叉编译时改用 call_libs 的
+    // 构建脚本用交叉编译器编译出的 external_dy
+    // Without a toolchain file CMake only builds for the host, so cross builds use the
+    // external_dy call_libs' build script compiled with the cross compiler instead
+    if options.skip_external || options.target.is_some() {
+        return Ok(());
+    }
+    // 和 external_lib/build.sh、build.ps1 做同样的事，但不依赖具体的生成器
+    // Does what external_lib/build.sh and build.ps1 do, without depending on the generator
+    let external = root.join("external_lib");
+    run(Command::new("cmake")
+        .arg("-S")
+        .arg(&external)
+        .arg("-B")
+        .arg(external.join("build")))?;
+    run(Command::new("cmake")
+        .arg("--build")
+        .arg(external.join("build"))
+        .args(["--config", "Release"]))?;
+
+    // 多配置生成器（Visual Studio）把产物放在 lib_build/Release 中
+    // Multi-config generators (Visual Studio) put the artifacts into lib_build/Release
+    let release_dir = external.join("lib_build/Release");
+    if release_dir.is_dir() {
+        for entry in fs::read_dir(&release_dir)? {
+            let path = entry?.path();
+            fs::rename(
+                &path,
+                external.join("lib_build").join(path.file_name().unwrap()),
+            )?;
+        }
+    }
+    Ok(())
+}
+
+// call_libs 的构建脚本输出目录中最近一次构建出的文件
+// The most recently built copy of a file in call_libs' build script output directories
+fn call_libs_out_file(bin_dir: &Path, file: &str) -> Result<PathBuf> {
+    let mut newest = None;
+    for entry in fs::read_dir(bin_dir.join("build"))? {
+        let dir = entry?.path();
+        let is_call_libs = dir
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| name.starts_with("call_libs-"));
+        let path = dir.join("out").join(file);
+        if let (true, Ok(metadata)) = (is_call_libs, fs::metadata(&path)) {
+            let modified = metadata.modified()?;
+            if newest.as_ref().is_none_or(|(time, _)| modified > *time) {
+                newest = Some((modified, path));
+            }
+        }
+    }
+    newest
+        .map(|(_, path)| path)
+        .ok_or_else(|| format!("no call_libs build has produced {}", file).into())
+}
+
+fn stage_libs(options: &BuildOptions) -> Result {
+    let root = workspace_root();
+    let bin_dir = options.bin_dir();
+    let platform = options.platform();
+    let external_file = platform.dylib_file("external_dy");
+    let external = if options.target.is_some() {
+        call_libs_out_file(&bin_dir, &external_file)?
+    } else {
+        root.join("external_lib/lib_build").join(&external_file)
+    };
+    let libs = [
+        (
+            bin_dir.join(platform.dylib_file("cdylib_gen")),
+            platform.dylib_file("cdylib_gen"),
+        ),
+        (external, external_file),
+    ];
+    for (from, file) in libs {
+        let to = bin_dir.join(&file);
+        if from == to {
+            continue;
+        }
+        fs::copy(&from, &to).map_err(|err| format!("cannot stage {}: {}", from.display(), err))?;
+        println!("[xtask] Staged {}", to.display());
+    }
+    Ok(())
+}
+
+fn run_demo(options: &BuildOptions, args: &[String]) -> Result {
+    build_all(options)?;
+    stage_libs(options)?;
+    // 交叉编译出的程序需要目标机器、模拟器或 binfmt_misc 才能在这里运行
+    // A cross-compiled program needs the target machine, an emulator or binfmt_misc to run here
+    let bin = options
+        .bin_dir()
+        .join(format!("call_libs{}", options.platform().exe_suffix));
+    run(Command::new(bin).args(args).current_dir(workspace_root()))
+}
+
+// 从 Cargo.toml 读取包的版本，xtask 不能依赖这些库，否则会把它们的导出函数链接进来
+// Reads a package's version from its Cargo.toml; xtask cannot depend on the libraries without
+// linking their exports in
+fn package_version(root: &Path, package: &str) -> Result<String> {
+    let manifest = fs::read_to_string(root.join("packages").join(package).join("Cargo.toml"))?;
+    manifest
+        .lines()
+        .find_map(|line| line.strip_prefix("version = "))
+        .map(|version| version.trim_matches('"').to_owned())
+        .ok_or_else(|| format!("{}/Cargo.toml has no version", package).into())
+}
+
+// 静态链接 Rust 库时还需要的系统库，即 rustc --print native-static-libs 的输出
+// System libraries a static link of a Rust library also needs, as printed by
+// rustc --print native-static-libs
+const NATIVE_STATIC_LIBS: &str = if cfg!(windows) {
+    "-lkernel32 -ladvapi32 -lntdll -luserenv -lws2_32 -ldbghelp"
+} else if cfg!(target_os = "macos") {
+    "-lSystem -lc -lm"
+} else {
+    "-lgcc_s -lutil -lrt -lpthread -lm -ldl -lc"
+};
+
+fn render(template: &str, vars: &[(&str, &str)]) -> String {
+    vars.iter()
+        .fold(template.to_owned(), |text, (name, value)| {
+            text.replace(name, value)
+        })
+}
+
+fn install_file(contents: &[u8], to: &Path) -> Result {
+    fs::create_dir_all(to.parent().unwrap())?;
+    fs::write(to, contents)?;
+    println!("[xtask] Installed {}", to.display());
+    Ok(())
+}
+
+// 按 Cargo 的语义化版本规则得到 SONAME：1.2.0 为 libcdylib_gen.so.1，0.x 版本带上次版本号
+// The SONAME under Cargo's semver rules: 1.2.0 gives libcdylib_gen.so.1, 0.x versions keep the minor
+fn soname(lib_file: &str, version: &str) -> String {
+    let mut numbers = version.split('.');
+    match (numbers.next(), numbers.next()) {
+        (Some("0"), Some(minor)) => format!("{}.0.{}", lib_file, minor),
+        (Some(major), _) => format!("{}.{}", lib_file, major),
+        _ => lib_file.to_owned(),
+    }
+}
+
+// Linux 上安装 libcdylib_gen.so.<版本>，以及 SONAME 链接和开发用的 libcdylib_gen.so 链接；
+// 为了不影响日常构建出的库，带 SONAME 的库在单独的 target 目录中构建
+// On Linux, installs libcdylib_gen.so.<version> with the SONAME link and the libcdylib_gen.so
+// development link; the library with a SONAME is built in its own target directory so the
+// everyday build stays untouched
+#[cfg(target_os = "linux")]
+fn install_versioned_cdylib(root: &Path, release: bool, lib_dir: &Path, version: &str) -> Result {
+    let lib_file = dylib_file("cdylib_gen");
+    let soname = soname(&lib_file, version);
+    let target_dir = root.join("target/soname");
+    let mut cargo = Command::new(env!("CARGO"));
+    cargo
+        .current_dir(root)
+        .env("CDYLIB_GEN_SONAME", &soname)
+        .args(["build", "-p", "cdylib_gen", "--target-dir"])
+        .arg(&target_dir);
+    if release {
+        cargo.arg("--release");
+    }
+    run(&mut cargo)?;
+
+    let built = target_dir
+        .join(if release { "release" } else { "debug" })
+        .join(&lib_file);
+    let real_file = format!("{}.{}", lib_file, version);
+    install_file(&fs::read(&built)?, &lib_dir.join(&real_file))?;
+    for (link, points_to) in [(&soname, &real_file), (&lib_file, &soname)] {
+        let link = lib_dir.join(link);
+        let _ = fs::remove_file(&link);
+        std::os::unix::fs::symlink(points_to, &link)?;
+        println!("[xtask] Linked {} -> {}", link.display(), points_to);
+    }
+    Ok(())
+}
+
+fn install(release: bool, prefix: &Path) -> Result {
+    let root = workspace_root();
+    let prefix = root.join(prefix);
+    let version = package_version(&root, "cdylib_gen")?;
+    let mut cargo = Command::new(env!("CARGO"));
+    cargo
+        .current_dir(&root)
+        .args(["build", "-p", "cdylib_gen", "-p", "staticlib_gen"]);
+    if release {
+        cargo.arg("--release");
+    }
+    run(&mut cargo)?;
+
+    let built_dir = root
+        .join("target")
+        .join(if release { "release" } else { "debug" });
+    // Windows 上 DLL 放在 bin/，导入库放在 lib/
+    // On Windows the DLL goes into bin/ and its import library into lib/
+    let lib_file = dylib_file("cdylib_gen");
+    let runtime_dir = if cfg!(windows) { "bin" } else { "lib" };
+    let static_file = if cfg!(target_env = "msvc") {
+        "staticlib_gen.lib"
+    } else {
+        "libstaticlib_gen.a"
+    };
+    let mut files = vec![
+        (
+            root.join("include/cdylib_gen.h"),
+            "include/cdylib_gen.h".to_owned(),
+        ),
+        (
+            root.join("include/staticlib_gen.h"),
+            "include/staticlib_gen.h".to_owned(),
+        ),
+        (built_dir.join(static_file), format!("lib/{}", static_file)),
+        (
+            root.join("packages/staticlib_gen/staticlib_gen.map"),
+            "lib/staticlib_gen.map".to_owned(),
+        ),
+    ];
+    if !cfg!(target_os = "linux") {
+        files.push((
+            built_dir.join(&lib_file),
+            format!("{}/{}", runtime_dir, lib_file),
+        ));
+    }
+    if cfg!(windows) {
+        let implib = format!("{}.lib", lib_file);
+        files.push((built_dir.join(&implib), format!("lib/{}", implib)));
+    }
+    for (from, to) in &files {
+        let contents =
+            fs::read(from).map_err(|err| format!("cannot install {}: {}", from.display(), err))?;
+        install_file(&contents, &prefix.join(to))?;
+    }
+    #[cfg(target_os = "linux")]
+    install_versioned_cdylib(&root, release, &prefix.join("lib"), &version)?;
+
+    let mut numbers = version.split('.');
+    let major = numbers.next().unwrap_or("0");
+    let minor = numbers.next().unwrap_or("0");
+    let (runtime_file, soname) = if cfg!(target_os = "linux") {
+        (
+            format!("lib/{}.{}", lib_file, version),
+            soname(&lib_file, &version),
+        )
+    } else {
+        (format!("{}/{}", runtime_dir, lib_file), String::new())
+    };
+    let cmake_vars = [
+        ("@RUNTIME_FILE@", runtime_file.as_str()),
+        ("@SONAME@", soname.as_str()),
+        ("@VERSION@", version.as_str()),
+        ("@MAJOR@", major),
+        ("@MINOR@", minor),
+    ];
+    let cmake_templates = [
+        (
+            "cdylib_genConfig.cmake",
+            include_str!("../templates/cdylib_genConfig.cmake.in"),
+        ),
+        (
+            "cdylib_genTargets.cmake",
+            include_str!("../templates/cdylib_genTargets.cmake.in"),
+        ),
+        (
+            "cdylib_genConfigVersion.cmake",
+            include_str!("../templates/cdylib_genConfigVersion.cmake.in"),
+        ),
+    ];
+    for (file, template) in cmake_templates {
+        let to = prefix.join("lib/cmake/cdylib_gen").join(file);
+        install_file(render(template, &cmake_vars).as_bytes(), &to)?;
+    }
+
+    let pc_templates = [
+        ("cdylib_gen", include_str!("../templates/cdylib_gen.pc.in")),
+        (
+            "staticlib_gen",
+            include_str!("../templates/staticlib_gen.pc.in"),
+        ),
+    ];
+    for (package, template) in pc_templates {
+        let version = package_version(&root, package)?;
+        let vars = [
+            ("@VERSION@", version.as_str()),
+            ("@NATIVE_STATIC_LIBS@", NATIVE_STATIC_LIBS),
+        ];
+        let to = prefix.join("lib/pkgconfig").join(format!("{}.pc", package));
+        install_file(render(template, &vars).as_bytes(), &to)?;
+    }
+    Ok(())
+}
+
+// Android ABI、对应的 Rust 目标和 NDK 中 clang 包装脚本的前缀
+// Android ABIs, their Rust targets and the prefixes of the clang wrapper scripts in the NDK
+const ANDROID_ABIS: &[(&str, &str, &str)] = &[
+    (
+        "arm64-v8a",
+        "aarch64-linux-android",
+        "aarch64-linux-android",
+    ),
+    (
+        "armeabi-v7a",
+        "armv7-linux-androideabi",
+        "armv7a-linux-androideabi",
+    ),
+    ("x86", "i686-linux-android", "i686-linux-android"),
+    ("x86_64", "x86_64-linux-android", "x86_64-linux-android"),
+];
+
+// 用 NDK 的 clang 作为链接器交叉编译 cdylib_gen，并按 ABI 放进 Gradle 默认的 jniLibs 目录
+// Cross compiles cdylib_gen with the NDK's clang as the linker and puts it into Gradle's default
+// jniLibs directory by ABI
+fn android(release: bool, abis: &[String], api: u32, ndk: &Path) -> Result {
+    let root = workspace_root();
+    // NDK 在 Apple Silicon 上也只提供 darwin-x86_64 目录
+    // The NDK only ships a darwin-x86_64 directory, also on Apple Silicon
+    let host = if cfg!(windows) {
+        "windows-x86_64"
+    } else if cfg!(target_os = "macos") {
+        "darwin-x86_64"
+    } else {
+        "linux-x86_64"
+    };
+    let ndk_bin = ndk.join("toolchains/llvm/prebuilt").join(host).join("bin");
+    for abi in abis {
+        let (_, target, clang) = ANDROID_ABIS
+            .iter()
+            .find(|(name, ..)| name == abi)
+            .ok_or_else(|| format!("unknown Android ABI {}", abi))?;
+        let linker = ndk_bin.join(format!(
+            "{}{}-clang{}",
+            clang,
+            api,
+            if cfg!(windows) { ".cmd" } else { "" }
+        ));
+        let linker_var = format!(
+            "CARGO_TARGET_{}_LINKER",
+            target.replace('-', "_").to_uppercase()
+        );
+        let mut cargo = Command::new(env!("CARGO"));
+        // Android 的动态链接器按 SONAME 识别库，Rust 的 cdylib 默认没有 SONAME
+        // Android's dynamic linker identifies libraries by SONAME, which Rust cdylibs lack by default
+        cargo
+            .current_dir(&root)
+            .env(linker_var, &linker)
+            .env("CDYLIB_GEN_SONAME", "libcdylib_gen.so")
+            .args([
+                "build",
+                "-p",
+                "cdylib_gen",
+                "--features",
+                "jni",
+                "--target",
+                target,
+            ]);
+        if release {
+            cargo.arg("--release");
+        }
+        run(&mut cargo)?;
+
+        let built = root
+            .join("target")
+            .join(target)
+            .join(if release { "release" } else { "debug" })
+            .join("libcdylib_gen.so");
+        let contents = fs::read(&built)
+            .map_err(|err| format!("cannot install {}: {}", built.display(), err))?;
+        install_file(
+            &contents,
+            &root
+                .join("android/app/src/main/jniLibs")
+                .join(abi)
+                .join("libcdylib_gen.so"),
+        )?;
+    }
+    Ok(())
+}
+
+// XCFramework 的每个平台切片和组成它的 Rust 目标，同一切片内的多个架构用 lipo 合并
+// Each platform slice of the XCFramework and the Rust targets it is made of; several
+// architectures in one slice are merged with lipo
+const APPLE_SLICES: &[(&str, &[&str])] = &[
+    ("ios", &["aarch64-apple-ios"]),
+    (
+        "ios-simulator",
+        &["aarch64-apple-ios-sim", "x86_64-apple-ios"],
+    ),
+    ("macos", &["aarch64-apple-darwin", "x86_64-apple-darwin"]),
+];
+
+// 构建各个 Apple 目标的 staticlib_gen，连同头文件和 module map 交给 xcodebuild 组装成 XCFramework，
+// Swift 中用 import staticlib_gen 导入
+// Builds staticlib_gen for every Apple target and has xcodebuild assemble the XCFramework with the
+// header and a module map, which Swift imports with import staticlib_gen
+fn xcframework(release: bool, output: &Path) -> Result {
+    if !cfg!(target_os = "macos") {
+        return Err("building an XCFramework needs Xcode, run this on macOS".into());
+    }
+    let root = workspace_root();
+    let output = root.join(output);
+    let profile = if release { "release" } else { "debug" };
+    let staging = root.join("target/xcframework");
+
+    let headers = staging.join("Headers");
+    fs::create_dir_all(&headers)?;
+    fs::copy(
+        root.join("include/staticlib_gen.h"),
+        headers.join("staticlib_gen.h"),
+    )?;
+    fs::write(
+        headers.join("module.modulemap"),
+        "module staticlib_gen {\n    header \"staticlib_gen.h\"\n    export *\n}\n",
+    )?;
+
+    let mut create = Command::new("xcodebuild");
+    create.arg("-create-xcframework");
+    for (slice, targets) in APPLE_SLICES {
+        let mut cargo = Command::new(env!("CARGO"));
+        cargo
+            .current_dir(&root)
+            .args(["build", "-p", "staticlib_gen"]);
+        for target in *targets {
+            cargo.args(["--target", target]);
+        }
+        if release {
+            cargo.arg("--release");
+        }
+        run(&mut cargo)?;
+
+        let merged = staging.join(slice).join("libstaticlib_gen.a");
+        fs::create_dir_all(merged.parent().unwrap())?;
+        let mut lipo = Command::new("lipo");
+        lipo.arg("-create").arg("-output").arg(&merged);
+        for target in *targets {
+            lipo.arg(
+                root.join("target")
+                    .join(target)
+                    .join(profile)
+                    .join("libstaticlib_gen.a"),
+            );
+        }
+        run(&mut lipo)?;
+        create
+            .arg("-library")
+            .arg(&merged)
+            .arg("-headers")
+            .arg(&headers);
+    }
+
+    // xcodebuild 不会覆盖已有的 XCFramework
+    // xcodebuild does not overwrite an existing XCFramework
+    if output.exists() {
+        fs::remove_dir_all(&output)?;
+    }
+    run(create.arg("-output").arg(&output))?;
+    println!("[xtask] Created {}", output.display());
+    Ok(())
+}
+
+// 用 nightly 的 rustc 查询主机的目标三元组
+// Asks nightly rustc for the host target triple
+fn nightly_host() -> Result<String> {
+    let output = Command::new("rustup")
+        .args(["run", "nightly", "rustc", "-vV"])
+        .output()
+        .map_err(|err| format!("cannot run rustup: {}", err))?;
+    if !output.status.success() {
+        return Err(
+            "no nightly toolchain, install it with rustup toolchain install nightly".into(),
+        );
+    }
+    String::from_utf8(output.stdout)?
+        .lines()
+        .find_map(|line| line.strip_prefix("host: "))
+        .map(str::to_owned)
+        .ok_or_else(|| "rustc -vV printed no host".into())
+}
+
+// Rust 代码用 -Zsanitizer=address 构建，C 代码由 call_libs 的 sanitize feature 用 ASan 和 UBSan 编译。
+// 必须显式指定 --target，否则 RUSTFLAGS 也会作用于构建脚本和过程宏
+// The Rust code is built with -Zsanitizer=address and the C code with ASan and UBSan through
+// call_libs' sanitize feature. --target must be explicit, otherwise RUSTFLAGS also apply to build
+// scripts and proc macros
+fn sanitize(release: bool, args: &[String]) -> Result {
+    if !cfg!(target_os = "linux") {
+        return Err("cargo xtask sanitize is only supported on Linux".into());
+    }
+    let root = workspace_root();
+    let host = nightly_host()?;
+    let mut cargo = Command::new("rustup");
+    cargo
+        .args(["run", "nightly", "cargo", "run", "-p", "call_libs"])
+        .args(["--features", "sanitize", "--target", &host, "--target-dir"])
+        .arg(root.join("target/sanitize"))
+        .current_dir(&root)
+        .env(
+            "RUSTFLAGS",
+            "-Zsanitizer=address -Cforce-frame-pointers=yes",
+        )
+        .env("ASAN_OPTIONS", "detect_leaks=1")
+        .env("UBSAN_OPTIONS", "print_stacktrace=1:halt_on_error=1");
+    if release {
+        cargo.arg("--release");
+    }
+    run(cargo.arg("--").args(args))
+}
+
+// 用 rustc 查询主机的目标三元组
+// Asks rustc for the host target triple
+fn host_triple() -> Result<String> {
+    let output = Command::new("rustc")
+        .arg("-vV")
+        .output()
+        .map_err(|err| format!("cannot run rustc: {}", err))?;
+    String::from_utf8(output.stdout)?
+        .lines()
+        .find_map(|line| line.strip_prefix("host: "))
+        .map(str::to_owned)
+        .ok_or_else(|| "rustc -vV printed no host".into())
+}
+
+// 整个 call_libs 连同 cdylib_gen、staticlib_gen 和 C 代码链接成一个不依赖动态库的可执行文件。
+// Linux 上默认用 musl：glibc 的静态链接不支持 dlopen 和 NSS，链接器也会对此发出警告
+// Links the whole call_libs, with cdylib_gen, staticlib_gen and the C code, into one executable
+// that depends on no dynamic library. Linux defaults to musl: glibc's static linking supports
+// neither dlopen nor NSS, and the linker warns about it
+fn build_static(release: bool, target: Option<&str>, args: &[String]) -> Result {
+    let target = match target {
+        Some(target) => target.to_owned(),
+        None if cfg!(target_os = "linux") => {
+            format!("{}-unknown-linux-musl", std::env::consts::ARCH)
+        }
+        None if cfg!(windows) => host_triple()?,
+        None => return Err("cargo xtask static needs --target on this platform".into()),
+    };
+    let root = workspace_root();
+    let mut cargo = Command::new("cargo");
+    cargo
+        .args(["run", "-p", "call_libs", "--features", "static"])
+        .args(["--target", &target, "--target-dir"])
+        .arg(root.join("target/static"))
+        .current_dir(&root)
+        .env("RUSTFLAGS", "-C target-feature=+crt-static");
+    if release {
+        cargo.arg("--release");
+    }
+    run(cargo.arg("--").args(args))
+}
+
+fn main() -> ExitCode {
+    let result = match Cli::parse().command {
+        Task::BuildAll(options) => build_all(&options),
+        Task::StageLibs(options) => stage_libs(&options),
+        Task::RunDemo { options, args } => run_demo(&options, &args),
+        Task::Install { release, prefix } => install(release, &prefix),
+        Task::Android {
+            release,
+            abi,
+            api,
+            ndk,
+        } => android(release, &abi, api, &ndk),
+        Task::Xcframework { release, output } => xcframework(release, &output),
+        Task::Sanitize { release, args } => sanitize(release, &args),
+        Task::Static {
+            release,
+            target,
+            args,
+        } => build_static(release, target.as_deref(), &args),
+        Task::CheckAbi { release, target } => abi_check::check_abi(release, target.as_deref()),
+        Task::Preload { release } => preload::preload(release),
+        Task::Shm { release, messages } => shm::shm(release, messages),
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("[xtask] {}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,90 @@
+// cargo xtask preload：构建 preload_interpose，编译 C 程序 harness/puts_harness.c，先直接运行它，再在
+// LD_PRELOAD 下运行，检查第二次的每一行都经过了替换的 puts，而第一次没有
+// cargo xtask preload: builds preload_interpose, compiles the C program harness/puts_harness.c,
+// runs it once directly and once under LD_PRELOAD, and checks every line of the second run went
+// through the replacement puts while none of the first did
+
+use std::{path::Path, process::Command};
+
+use crate::{dylib_file, run, workspace_root, Result};
+
+// 运行 harness 并返回它输出的行
+// Runs the harness and returns the lines it printed
+fn run_harness(harness: &Path, preload: Option<&Path>) -> Result<Vec<String>> {
+    let mut command = Command::new(harness);
+    if let Some(preload) = preload {
+        command.env("LD_PRELOAD", preload);
+    }
+    match preload {
+        Some(preload) => println!(
+            "[xtask] Running {} with LD_PRELOAD={}",
+            harness.display(),
+            preload.display()
+        ),
+        None => println!("[xtask] Running {}", harness.display()),
+    }
+    let output = command
+        .output()
+        .map_err(|err| format!("cannot run {}: {}", harness.display(), err))?;
+    if !output.status.success() {
+        return Err(format!("{} failed with {}", harness.display(), output.status).into());
+    }
+    let stdout = String::from_utf8(output.stdout)?;
+    for line in stdout.lines() {
+        println!("    {}", line);
+    }
+    Ok(stdout.lines().map(str::to_owned).collect())
+}
+
+pub fn preload(release: bool) -> Result {
+    if !cfg!(target_os = "linux") {
+        return Err("cargo xtask preload is only supported on Linux".into());
+    }
+    let root = workspace_root();
+    let mut cargo = Command::new(env!("CARGO"));
+    cargo
+        .args(["build", "-p", "preload_interpose"])
+        .current_dir(&root);
+    if release {
+        cargo.arg("--release");
+    }
+    run(&mut cargo)?;
+    let profile_dir = root
+        .join("target")
+        .join(if release { "release" } else { "debug" });
+    let lib = profile_dir.join(dylib_file("preload_interpose"));
+
+    // -fno-builtin 让编译器保留对 puts 的调用，不改写或内联它
+    // -fno-builtin keeps the compiler from rewriting or inlining the calls to puts
+    let harness = profile_dir.join("puts_harness");
+    let compiler = std::env::var("CC").unwrap_or_else(|_| "cc".to_owned());
+    run(Command::new(compiler)
+        .arg("-fno-builtin")
+        .arg(root.join("packages/preload_interpose/harness/puts_harness.c"))
+        .arg("-o")
+        .arg(&harness))?;
+
+    let plain = run_harness(&harness, None)?;
+    if plain.iter().any(|line| line.starts_with("[preload")) {
+        return Err("the harness was interposed without LD_PRELOAD".into());
+    }
+    let preloaded = run_harness(&harness, Some(&lib))?;
+    let expected: Vec<String> = plain
+        .iter()
+        .enumerate()
+        .map(|(i, line)| format!("[preload #{}] {}", i + 1, line))
+        .collect();
+    if preloaded != expected {
+        return Err(format!(
+            "expected the preloaded puts to print {:?}, got {:?}",
+            expected, preloaded
+        )
+        .into());
+    }
+    println!(
+        "[xtask] All {} calls to puts went through {}",
+        plain.len(),
+        lib.display()
+    );
+    Ok(())
+}
@@ -0,0 +1,29 @@
+// cargo xtask shm：构建 shm_ipc，运行 Rust 的写入者，由它创建共享内存并启动 C 的读取者进程；两个进程
+// 通过顺序锁交换消息，读取者检查读到的每条消息都完整，写入者以读取者的结果退出
+// cargo xtask shm: builds shm_ipc and runs the Rust writer, which creates the shared memory and
+// starts the C reader process; the two exchange messages through the seqlock, the reader checks
+// every message it read is intact and the writer exits with the reader's result
+
+use std::process::Command;
+
+use crate::{run, workspace_root, Result};
+
+pub fn shm(release: bool, messages: u32) -> Result {
+    let root = workspace_root();
+    let mut cargo = Command::new(env!("CARGO"));
+    cargo.args(["build", "-p", "shm_ipc"]).current_dir(&root);
+    if release {
+        cargo.arg("--release");
+    }
+    run(&mut cargo)?;
+    let writer = root
+        .join("target")
+        .join(if release { "release" } else { "debug" })
+        .join(format!("shm_writer{}", std::env::consts::EXE_SUFFIX));
+    run(Command::new(writer).arg(messages.to_string()))?;
+    println!(
+        "[xtask] The C reader followed {} messages from the Rust writer without a torn read",
+        messages
+    );
+    Ok(())
+}